@@ -0,0 +1,23 @@
+//! A program which verifies the proofs of other programs, and commits to their verification keys
+//! and public values.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use sha2::{Digest, Sha256};
+
+pub fn main() {
+    // Read the verification keys and the public values of the proofs.
+    let vkeys = sp1_zkvm::io::read::<Vec<[u32; 8]>>();
+    let public_values = sp1_zkvm::io::read::<Vec<Vec<u8>>>();
+    assert_eq!(vkeys.len(), public_values.len());
+
+    // Verify the proofs, which the prover reads from the proofs written to the stdin.
+    for (vkey, public_values) in vkeys.iter().zip(public_values.iter()) {
+        let public_values_digest = Sha256::digest(public_values);
+        sp1_zkvm::precompiles::verify::verify_sp1_proof(vkey, &public_values_digest.into());
+    }
+
+    sp1_zkvm::io::commit(&vkeys);
+    sp1_zkvm::io::commit(&public_values);
+}
@@ -0,0 +1,4 @@
+fn main() {
+    sp1_helper::build_program(&format!("{}/../program", env!("CARGO_MANIFEST_DIR")));
+    sp1_helper::build_program(&format!("{}/../aggregator", env!("CARGO_MANIFEST_DIR")));
+}
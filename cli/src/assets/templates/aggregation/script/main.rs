@@ -0,0 +1,55 @@
+//! Proves the program on several inputs, and aggregates the proofs into a single proof with the
+//! aggregator program.
+
+use sp1_sdk::{utils, HashableKey, ProverClient, SP1Stdin};
+
+/// The ELF of the program whose proofs are aggregated.
+const PROGRAM_ELF: &[u8] = include_bytes!("../../program/elf/riscv32im-succinct-zkvm-elf");
+
+/// The ELF of the program which aggregates the proofs.
+const AGGREGATOR_ELF: &[u8] = include_bytes!("../../aggregator/elf/riscv32im-succinct-zkvm-elf");
+
+fn main() {
+    // Setup logging.
+    utils::setup_logger();
+
+    let client = ProverClient::new();
+    let (program_pk, program_vk) = client.setup(PROGRAM_ELF);
+    let (aggregator_pk, aggregator_vk) = client.setup(AGGREGATOR_ELF);
+
+    // Generate the compressed proofs of the program, which can be verified by other programs.
+    let proofs = [10u32, 20, 30]
+        .iter()
+        .map(|n| {
+            let mut stdin = SP1Stdin::new();
+            stdin.write(n);
+            client
+                .prove_compressed(&program_pk, stdin)
+                .expect("proving failed")
+        })
+        .collect::<Vec<_>>();
+
+    // Write the verification keys and public values the aggregator reads, and the proofs it
+    // verifies.
+    let mut stdin = SP1Stdin::new();
+    let vkeys = vec![program_vk.hash_u32(); proofs.len()];
+    stdin.write(&vkeys);
+    let public_values = proofs
+        .iter()
+        .map(|proof| proof.public_values.to_vec())
+        .collect::<Vec<_>>();
+    stdin.write(&public_values);
+    for proof in proofs {
+        stdin.write_proof(proof.proof, program_vk.vk.clone());
+    }
+
+    // Aggregate the proofs.
+    let proof = client
+        .prove_compressed(&aggregator_pk, stdin)
+        .expect("proving failed");
+    client
+        .verify_compressed(&proof, &aggregator_vk)
+        .expect("verification failed");
+
+    println!("successfully aggregated the proofs of the program!")
+}
@@ -0,0 +1,26 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.25;
+
+import {SP1ProgramVerifier} from "./SP1ProgramVerifier.sol";
+
+/// @notice An application which trusts the public values of proofs of the program.
+contract Fibonacci is SP1ProgramVerifier {
+    /// @notice The public values committed by the program with `commit_abi`.
+    struct PublicValues {
+        uint32 n;
+        uint32 a;
+        uint32 b;
+    }
+
+    /// @notice Verifies a proof of the program, and returns its public values.
+    /// @param publicValues The public values encoded as bytes.
+    /// @param proofBytes The proof of the program encoded as bytes.
+    function verifyFibonacciProof(
+        bytes calldata publicValues,
+        bytes calldata proofBytes
+    ) external view returns (uint32, uint32, uint32) {
+        verifyProof(PROGRAM_VKEY, publicValues, proofBytes);
+        PublicValues memory values = abi.decode(publicValues, (PublicValues));
+        return (values.n, values.a, values.b);
+    }
+}
@@ -0,0 +1,28 @@
+//! A program whose public values are read by a Solidity contract.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use sp1_zkvm::abi::SolidityAbi;
+
+/// The public values of the program, which the contract decodes with
+/// `abi.decode(publicValues, (PublicValues))`.
+#[derive(SolidityAbi)]
+struct PublicValues {
+    n: u32,
+    a: u32,
+    b: u32,
+}
+
+pub fn main() {
+    let n = sp1_zkvm::io::read::<u32>();
+    let mut a: u32 = 0;
+    let mut b: u32 = 1;
+    for _ in 0..n {
+        let sum = a.wrapping_add(b);
+        a = b;
+        b = sum;
+    }
+
+    sp1_zkvm::io::commit_abi(&PublicValues { n, a, b });
+}
@@ -0,0 +1,45 @@
+//! Generates a Groth16 proof of the program which the contracts verify, along with the verifier of
+//! the program and a fixture of the proof for the tests of the contracts.
+
+use std::fs;
+
+use sp1_sdk::{utils, HashableKey, ProverClient, SP1Stdin, VerifierCalldata};
+
+/// The ELF we want to execute inside the zkVM.
+const ELF: &[u8] = include_bytes!("../../program/elf/riscv32im-succinct-zkvm-elf");
+
+/// The directory of the Foundry project of the contracts.
+const CONTRACTS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../contracts");
+
+fn main() {
+    // Setup logging.
+    utils::setup_logger();
+
+    // Generate the proof.
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&20u32);
+    let client = ProverClient::new();
+    let (pk, vk) = client.setup(ELF);
+    let proof = client.prove_groth16(&pk, stdin).expect("proving failed");
+    client
+        .verify_groth16(&proof, &vk)
+        .expect("verification failed");
+
+    // Write the verifier of the program, which the contracts inherit.
+    client
+        .export_groth16_verifier(&vk, VerifierCalldata::Standard, CONTRACTS_DIR)
+        .expect("exporting the verifier failed");
+
+    // Write the fixture of the proof for the tests of the contracts.
+    let fixture = format!(
+        "{{\n  \"vkey\": \"{}\",\n  \"publicValues\": \"0x{}\",\n  \"proof\": \"{}\"\n}}\n",
+        vk.bytes32(),
+        hex::encode(proof.public_values.as_slice()),
+        proof.bytes()
+    );
+    fs::create_dir_all(format!("{}/fixtures", CONTRACTS_DIR)).expect("creating fixtures failed");
+    fs::write(format!("{}/fixtures/proof.json", CONTRACTS_DIR), fixture)
+        .expect("writing the fixture failed");
+
+    println!("successfully generated the proof and the verifier of the program!")
+}
@@ -0,0 +1,30 @@
+//! The types shared by the program and the script, which the script writes to the stdin of the
+//! program and reads from its public values.
+
+use serde::{Deserialize, Serialize};
+
+/// The input of the program.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Input {
+    pub name: String,
+    pub values: Vec<u64>,
+}
+
+/// The output the program commits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Output {
+    pub name: String,
+    pub count: u64,
+    pub sum: u64,
+    pub max: Option<u64>,
+}
+
+/// Computes the output of `input`.
+pub fn summarize(input: &Input) -> Output {
+    Output {
+        name: input.name.clone(),
+        count: input.values.len() as u64,
+        sum: input.values.iter().sum(),
+        max: input.values.iter().copied().max(),
+    }
+}
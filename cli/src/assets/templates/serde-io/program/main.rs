@@ -0,0 +1,12 @@
+//! A program which reads and commits structured values.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use unnamed_lib::{summarize, Input, Output};
+
+pub fn main() {
+    let input = sp1_zkvm::io::read::<Input>();
+    let output: Output = summarize(&input);
+    sp1_zkvm::io::commit(&output);
+}
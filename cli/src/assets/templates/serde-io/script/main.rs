@@ -0,0 +1,32 @@
+//! Writes a structured input to the program, and reads its structured output from the proof.
+
+use sp1_sdk::{utils, ProverClient, SP1Stdin};
+use unnamed_lib::{summarize, Input, Output};
+
+/// The ELF we want to execute inside the zkVM.
+const ELF: &[u8] = include_bytes!("../../program/elf/riscv32im-succinct-zkvm-elf");
+
+fn main() {
+    // Setup logging.
+    utils::setup_logger();
+
+    let input = Input {
+        name: "example".to_string(),
+        values: vec![3, 1, 4, 1, 5, 9, 2, 6],
+    };
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&input);
+
+    // Generate and verify the proof.
+    let client = ProverClient::new();
+    let (pk, vk) = client.setup(ELF);
+    let mut proof = client.prove_compressed(&pk, stdin).expect("proving failed");
+    client
+        .verify_compressed(&proof, &vk)
+        .expect("verification failed");
+
+    // Read the output, which matches the output computed outside of the zkVM.
+    let output = proof.public_values.read::<Output>();
+    assert_eq!(output, summarize(&input));
+    println!("output: {:?}", output);
+}
@@ -2,8 +2,9 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 use sp1_cli::{
     commands::{
-        build::BuildCmd, build_toolchain::BuildToolchainCmd,
-        install_toolchain::InstallToolchainCmd, new::NewCmd, prove::ProveCmd,
+        bench::BenchCmd, build::BuildCmd, build_toolchain::BuildToolchainCmd, inspect::InspectCmd,
+        install_toolchain::InstallToolchainCmd, new::NewCmd, prove::ProveCmd, size::SizeCmd,
+        verify::VerifyCmd,
     },
     SP1_VERSION_MESSAGE,
 };
@@ -29,6 +30,10 @@ pub enum ProveCliCommands {
     New(NewCmd),
     Build(BuildCmd),
     Prove(ProveCmd),
+    Size(SizeCmd),
+    Bench(BenchCmd),
+    Inspect(InspectCmd),
+    Verify(VerifyCmd),
     BuildToolchain(BuildToolchainCmd),
     InstallToolchain(InstallToolchainCmd),
 }
@@ -40,6 +45,10 @@ fn main() -> Result<()> {
         ProveCliCommands::New(cmd) => cmd.run(),
         ProveCliCommands::Build(cmd) => cmd.run(),
         ProveCliCommands::Prove(cmd) => cmd.run(),
+        ProveCliCommands::Size(cmd) => cmd.run(),
+        ProveCliCommands::Bench(cmd) => cmd.run(),
+        ProveCliCommands::Inspect(cmd) => cmd.run(),
+        ProveCliCommands::Verify(cmd) => cmd.run(),
         ProveCliCommands::BuildToolchain(cmd) => cmd.run(),
         ProveCliCommands::InstallToolchain(cmd) => cmd.run(),
     }
@@ -1,6 +1,8 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use cargo_metadata::camino::Utf8PathBuf;
 use clap::Parser;
+use sha2::{Digest, Sha256};
+use sp1_core::runtime::Program;
 use std::{
     fs,
     io::{BufRead, BufReader},
@@ -8,18 +10,100 @@ use std::{
     thread,
 };
 
-fn get_docker_image() -> String {
-    // Get the docker image name from the environment variable
-    std::env::var("SP1_DOCKER_IMAGE")
-        .unwrap_or_else(|_| "ghcr.io/succinctlabs/sp1:latest".to_string())
+/// The repository of the docker images of the toolchain.
+const DOCKER_REPOSITORY: &str = "ghcr.io/succinctlabs/sp1";
+
+/// The platform of the docker image, so that the toolchain is the same on every machine.
+const DOCKER_PLATFORM: &str = "linux/amd64";
+
+fn get_docker_image(tag: Option<&str>) -> String {
+    // Get the docker image name from the environment variable, or pin it to the version of the CLI.
+    std::env::var("SP1_DOCKER_IMAGE").unwrap_or_else(|_| {
+        let tag = tag.map_or_else(|| format!("v{}", env!("CARGO_PKG_VERSION")), String::from);
+        format!("{}:{}", DOCKER_REPOSITORY, tag)
+    })
 }
 
 #[derive(Parser)]
 pub(crate) struct BuildArgs {
-    #[clap(long, action, help = "Ignore Rust version check.")]
+    #[clap(
+        long,
+        action,
+        help = "Build the program in the docker image of the toolchain, so that the ELF is the \
+                same on every machine."
+    )]
     pub(crate) docker: bool,
+    #[clap(
+        long,
+        help = "The tag of the docker image to build in. Defaults to the version of the CLI."
+    )]
+    pub(crate) tag: Option<String>,
+    #[clap(
+        long,
+        help = "The expected SHA-256 of the ELF, as hex. The build fails if the ELF differs."
+    )]
+    pub(crate) elf_sha256: Option<String>,
     #[clap(long, action, help = "Ignore Rust version check.")]
     pub(crate) ignore_rust_version: bool,
+    #[clap(
+        long,
+        value_parser = ["0", "1", "2", "3", "s", "z"],
+        help = "The optimization level of the program. `s` and `z` optimize for size."
+    )]
+    pub(crate) opt_level: Option<String>,
+    #[clap(
+        long,
+        value_parser = ["off", "thin", "fat"],
+        help = "The link-time optimization of the program."
+    )]
+    pub(crate) lto: Option<String>,
+    #[clap(
+        long,
+        action,
+        help = "Keep the unused sections of the program, which the linker removes by default."
+    )]
+    pub(crate) no_gc_sections: bool,
+    #[clap(
+        long,
+        action,
+        help = "Unwind on panic instead of aborting, which adds the unwinding code to the program."
+    )]
+    pub(crate) panic_unwind: bool,
+}
+
+impl BuildArgs {
+    /// The options which control the size of the program, as arguments of `cargo prove build`.
+    fn size_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(opt_level) = &self.opt_level {
+            args.extend(["--opt-level".to_string(), opt_level.clone()]);
+        }
+        if let Some(lto) = &self.lto {
+            args.extend(["--lto".to_string(), lto.clone()]);
+        }
+        if self.no_gc_sections {
+            args.push("--no-gc-sections".to_string());
+        }
+        if self.panic_unwind {
+            args.push("--panic-unwind".to_string());
+        }
+        args
+    }
+}
+
+/// Prints the sizes of the segments of the ELF at `elf_path`. The program table of every proof has
+/// a row per instruction, so the text segment is part of the cost of proving the program.
+fn print_elf_size(elf_path: &Utf8PathBuf) -> Result<()> {
+    let program = Program::decode(&fs::read(elf_path)?)?;
+    let text = program.instructions.len() * 4;
+    let data = program.memory_image.len() * 4 - text;
+    println!(
+        "ELF size: text {} bytes ({} instructions), data {} bytes",
+        text,
+        program.instructions.len(),
+        data
+    );
+    Ok(())
 }
 
 pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
@@ -30,7 +114,7 @@ pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
 
     let build_target = "riscv32im-succinct-zkvm-elf";
     if args.docker {
-        let image = get_docker_image();
+        let image = get_docker_image(args.tag.as_deref());
 
         let docker_check = Command::new("docker")
             .args(["info"])
@@ -48,6 +132,8 @@ pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
         let mut child_args = vec![
             "run",
             "--rm",
+            "--platform",
+            DOCKER_PLATFORM,
             "-v",
             workspace_root_path.as_str(),
             image.as_str(),
@@ -57,6 +143,8 @@ pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
         if args.ignore_rust_version {
             child_args.push("--ignore-rust-version");
         }
+        let size_args = args.size_args();
+        child_args.extend(size_args.iter().map(String::as_str));
 
         let mut child = Command::new("docker")
             .args(&child_args)
@@ -86,26 +174,35 @@ pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
             exit(result.code().unwrap_or(1))
         }
     } else {
-        let rust_flags = [
+        let mut rust_flags = vec![
             "-C",
-            "passes=loweratomic",
+            "target-feature=+a",
             "-C",
             "link-arg=-Ttext=0x00200800",
-            "-C",
-            "panic=abort",
         ];
+        if !args.panic_unwind {
+            rust_flags.extend(["-C", "panic=abort"]);
+        }
+        if args.no_gc_sections {
+            rust_flags.extend(["-C", "link-dead-code"]);
+        }
 
         let mut cargo_args = vec!["build", "--release", "--target", build_target, "--locked"];
         if args.ignore_rust_version {
             cargo_args.push("--ignore-rust-version");
         }
 
-        let result = Command::new("cargo")
-            .env("RUSTUP_TOOLCHAIN", "succinct")
+        let mut cmd = Command::new("cargo");
+        cmd.env("RUSTUP_TOOLCHAIN", "succinct")
             .env("CARGO_ENCODED_RUSTFLAGS", rust_flags.join("\x1f"))
-            .args(&cargo_args)
-            .status()
-            .context("Failed to run cargo command.")?;
+            .args(&cargo_args);
+        if let Some(opt_level) = &args.opt_level {
+            cmd.env("CARGO_PROFILE_RELEASE_OPT_LEVEL", opt_level);
+        }
+        if let Some(lto) = &args.lto {
+            cmd.env("CARGO_PROFILE_RELEASE_LTO", lto);
+        }
+        let result = cmd.status().context("Failed to run cargo command.")?;
 
         if !result.success() {
             // Error message is already printed by cargo
@@ -118,10 +215,32 @@ pub fn build_program(args: &BuildArgs) -> Result<Utf8PathBuf> {
         .join(build_target)
         .join("release")
         .join(root_package_name.unwrap());
-    let elf_dir = metadata.target_directory.parent().unwrap().join("elf");
+    // The ELF is written next to the manifest of the program, so that the programs of a workspace
+    // do not overwrite each other's ELF.
+    let elf_dir = root_package
+        .unwrap()
+        .manifest_path
+        .parent()
+        .unwrap()
+        .join("elf");
     fs::create_dir_all(&elf_dir)?;
     let result_elf_path = elf_dir.join("riscv32im-succinct-zkvm-elf");
     fs::copy(elf_path, &result_elf_path)?;
 
+    // Check the ELF against the expected hash, so that a build which is not reproducible fails.
+    let elf_sha256 = hex::encode(Sha256::digest(fs::read(&result_elf_path)?));
+    println!("ELF SHA-256: {}", elf_sha256);
+    print_elf_size(&result_elf_path)?;
+    if let Some(expected) = args.elf_sha256.as_ref() {
+        let expected = expected.trim_start_matches("0x").to_ascii_lowercase();
+        if expected != elf_sha256 {
+            bail!(
+                "the SHA-256 of the ELF is {}, but {} was expected",
+                elf_sha256,
+                expected
+            );
+        }
+    }
+
     Ok(result_elf_path)
 }
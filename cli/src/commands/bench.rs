@@ -0,0 +1,222 @@
+use anstyle::*;
+use anyhow::Result;
+use clap::Parser;
+use sp1_core::{
+    runtime::ExecutionReport,
+    utils::{estimate_bytes_per_cycle, setup_logger},
+};
+use sp1_prover::SP1Stdin;
+use sp1_sdk::ProverClient;
+use std::{
+    env, fs,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    build::{build_program, BuildArgs},
+    commands::prove::Input,
+    util::{elapsed, write_status},
+};
+
+/// The program which calibrates the prover. The compressed and wrapped proofs of every program
+/// have the same size, so they are measured on it.
+const CALIBRATION_ELF: &[u8] =
+    include_bytes!("../../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+
+/// The number of iterations of the calibration program.
+const CALIBRATION_ITERATIONS: u32 = 50_000;
+
+#[derive(Parser)]
+#[command(
+    name = "bench",
+    about = "Execute a program and estimate the time and size of its proofs on this machine"
+)]
+pub struct BenchCmd {
+    #[clap(long, value_parser)]
+    input: Option<Input>,
+
+    /// Also measure compressed proofs on the calibration program.
+    #[clap(long, action)]
+    compressed: bool,
+
+    /// Also measure PLONK proofs on the calibration program, which needs the circuit artifacts.
+    #[clap(long, action)]
+    plonk: bool,
+
+    /// Also measure Groth16 proofs on the calibration program, which needs the circuit artifacts.
+    #[clap(long, action)]
+    groth16: bool,
+
+    #[clap(flatten)]
+    build_args: BuildArgs,
+}
+
+/// The time of a proof of the calibration program, and the size of the saved proof.
+#[derive(Debug, Clone, Copy)]
+struct Measurement {
+    time: Duration,
+    bytes: usize,
+}
+
+/// The estimated time and size of a proof of the program.
+struct Estimate {
+    mode: &'static str,
+    time: Duration,
+    bytes: usize,
+}
+
+impl BenchCmd {
+    pub fn run(&self) -> Result<()> {
+        let elf_path = build_program(&self.build_args)?;
+        if env::var("RUST_LOG").is_err() {
+            env::set_var("RUST_LOG", "info");
+        }
+        setup_logger();
+
+        let elf = fs::read(elf_path.as_std_path())?;
+        let mut stdin = SP1Stdin::new();
+        if let Some(ref input) = self.input {
+            input.write_to(&mut stdin)?;
+        }
+
+        let client = ProverClient::new();
+        let (_, report) = client.execute(&elf, stdin)?;
+        let cycles = report.total_cycle_count();
+        let shards = report.shard_cycles.len();
+
+        // Measure the prover on the calibration program.
+        let mut calibration_stdin = SP1Stdin::new();
+        calibration_stdin.write(&CALIBRATION_ITERATIONS);
+        let (_, calibration_report) = client.execute(CALIBRATION_ELF, calibration_stdin.clone())?;
+        let (pk, _) = client.setup(CALIBRATION_ELF);
+        let measure = |bytes: &dyn Fn() -> Result<usize>| -> Result<Measurement> {
+            let start = Instant::now();
+            let bytes = bytes()?;
+            Ok(Measurement {
+                time: start.elapsed(),
+                bytes,
+            })
+        };
+        let core = measure(&|| {
+            Ok(client
+                .prove(&pk, calibration_stdin.clone())?
+                .to_bytes()?
+                .len())
+        })?;
+        let wrapped = self.plonk || self.groth16;
+        let compressed = if self.compressed || wrapped {
+            Some(measure(&|| {
+                Ok(client
+                    .prove_compressed(&pk, calibration_stdin.clone())?
+                    .to_bytes()?
+                    .len())
+            })?)
+        } else {
+            None
+        };
+        let plonk = if self.plonk {
+            Some(measure(&|| {
+                Ok(client
+                    .prove_plonk(&pk, calibration_stdin.clone())?
+                    .to_bytes()?
+                    .len())
+            })?)
+        } else {
+            None
+        };
+        let groth16 = if self.groth16 {
+            Some(measure(&|| {
+                Ok(client
+                    .prove_groth16(&pk, calibration_stdin.clone())?
+                    .to_bytes()?
+                    .len())
+            })?)
+        } else {
+            None
+        };
+
+        // The time of the core proof grows with the weighted cycles of the program, and the size
+        // of a shard proof with the logarithm of the height of its traces.
+        let calibration_shards = calibration_report.shard_cycles.len().max(1);
+        let core_time = core
+            .time
+            .mul_f64(weighted_cycles(&report) / weighted_cycles(&calibration_report));
+        let calibration_log_height =
+            log_height(calibration_report.total_cycle_count() / calibration_shards as u64);
+        let core_bytes = report
+            .shard_cycles
+            .iter()
+            .map(|&cycles| {
+                core.bytes / calibration_shards * log_height(cycles) / calibration_log_height
+            })
+            .sum::<usize>();
+        let mut estimates = vec![Estimate {
+            mode: "core",
+            time: core_time,
+            bytes: core_bytes,
+        }];
+
+        // The recursion of the compressed proof grows with the number of shards, and the wrapping
+        // of the compressed proof takes the same time for every program.
+        if let Some(compressed) = compressed {
+            let recursion = compressed.time.saturating_sub(core.time);
+            let compressed_time =
+                core_time + recursion.mul_f64(shards as f64 / calibration_shards as f64);
+            estimates.push(Estimate {
+                mode: "compressed",
+                time: compressed_time,
+                bytes: compressed.bytes,
+            });
+            for (mode, measurement) in [("plonk", plonk), ("groth16", groth16)] {
+                if let Some(measurement) = measurement {
+                    estimates.push(Estimate {
+                        mode,
+                        time: compressed_time + measurement.time.saturating_sub(compressed.time),
+                        bytes: measurement.bytes,
+                    });
+                }
+            }
+        }
+
+        println!("cycles: {}", cycles);
+        println!("shards: {}", shards);
+        println!("exit code: {}", report.exit_code);
+        println!("{:<12} {:>16} {:>16}", "mode", "est. time", "est. size");
+        for estimate in estimates.iter() {
+            println!(
+                "{:<12} {:>16} {:>16}",
+                estimate.mode,
+                elapsed(estimate.time),
+                format!("{} bytes", estimate.bytes)
+            );
+        }
+
+        let green = AnsiColor::Green.on_default().effects(Effects::BOLD);
+        write_status(
+            &green,
+            "Finished",
+            format!(
+                "calibration in {}",
+                elapsed(
+                    core.time
+                        + compressed.map_or(Duration::ZERO, |m| m.time)
+                        + plonk.map_or(Duration::ZERO, |m| m.time)
+                        + groth16.map_or(Duration::ZERO, |m| m.time)
+                )
+            )
+            .as_str(),
+        );
+        Ok(())
+    }
+}
+
+/// The cycles of an execution, weighted by the memory the prover needs per cycle for its mix of
+/// instructions and precompiles.
+fn weighted_cycles(report: &ExecutionReport) -> f64 {
+    report.total_instruction_count() as f64 * estimate_bytes_per_cycle(report) as f64
+}
+
+/// The logarithm of the height of the traces of a shard of `cycles` cycles.
+fn log_height(cycles: u64) -> usize {
+    cycles.max(2).next_power_of_two().trailing_zeros() as usize
+}
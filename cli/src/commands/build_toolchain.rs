@@ -79,7 +79,7 @@ impl BuildToolchainCmd {
         Command::new("python3")
             .env(
                 "CARGO_TARGET_RISCV32IM_SUCCINCT_ZKVM_ELF_RUSTFLAGS",
-                "-Cpasses=loweratomic",
+                "-Ctarget-feature=+a",
             )
             .args(["x.py", "build"])
             .current_dir(&rust_dir)
@@ -89,7 +89,7 @@ impl BuildToolchainCmd {
         Command::new("python3")
             .env(
                 "CARGO_TARGET_RISCV32IM_SUCCINCT_ZKVM_ELF_RUSTFLAGS",
-                "-Cpasses=loweratomic",
+                "-Ctarget-feature=+a",
             )
             .args(["x.py", "build", "--stage", "2"])
             .current_dir(&rust_dir)
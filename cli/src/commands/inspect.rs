@@ -0,0 +1,71 @@
+use anyhow::Result;
+use clap::Parser;
+use sp1_sdk::{SP1CompressedProof, SP1Proof, SP1VerifyingKey};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(
+    name = "inspect",
+    about = "Print the shards, chips, public values and FRI parameters of a saved proof"
+)]
+pub struct InspectCmd {
+    /// The path of the proof, as saved by `SP1ProofWithPublicValues::save`.
+    proof: PathBuf,
+
+    /// Whether the proof is a compressed proof instead of a core proof.
+    #[clap(long, action)]
+    compressed: bool,
+
+    /// The path of the verifying key of the program, which records the FRI parameters of its core
+    /// proofs.
+    #[clap(long, conflicts_with = "compressed")]
+    vkey: Option<PathBuf>,
+
+    /// Print the inspection as JSON.
+    #[clap(long, action)]
+    json: bool,
+}
+
+impl InspectCmd {
+    pub fn run(&self) -> Result<()> {
+        let inspection = if self.compressed {
+            SP1CompressedProof::load(&self.proof)?.inspect()
+        } else {
+            SP1Proof::load(&self.proof)?.inspect()
+        };
+        let fri_parameters = match &self.vkey {
+            Some(path) => Some(SP1VerifyingKey::load(path)?.vk.fri_parameters),
+            None => None,
+        };
+
+        if self.json {
+            let json = serde_json::json!({
+                "shards": inspection.shards,
+                "fri_parameters": fri_parameters,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+            return Ok(());
+        }
+        print!("{}", inspection);
+        if let Some(parameters) = fri_parameters {
+            println!(
+                "fri parameters: log blowup {}, {} queries, {} proof of work bits ({} bits of conjectured security)",
+                parameters.log_blowup,
+                parameters.num_queries,
+                parameters.proof_of_work_bits,
+                parameters.conjectured_security_bits()
+            );
+            let mismatched = inspection
+                .shards
+                .iter()
+                .position(|shard| shard.summary.fri.num_queries != parameters.num_queries);
+            if let Some(shard) = mismatched {
+                println!(
+                    "shard {} has {} queries, which the verifying key does not accept",
+                    shard, inspection.shards[shard].summary.fri.num_queries
+                );
+            }
+        }
+        Ok(())
+    }
+}
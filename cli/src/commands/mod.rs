@@ -1,5 +1,9 @@
+pub mod bench;
 pub mod build;
 pub mod build_toolchain;
+pub mod inspect;
 pub mod install_toolchain;
 pub mod new;
 pub mod prove;
+pub mod size;
+pub mod verify;
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use std::{fs, path::Path};
 use yansi::Paint;
 
@@ -12,10 +12,77 @@ const SCRIPT_BUILD_RS: &str = include_str!("../assets/script/build.rs");
 const GIT_IGNORE: &str = include_str!("../assets/.gitignore");
 const VS_CODE_SETTINGS_JSON: &str = include_str!("../assets/.vscode/settings.json");
 
+/// The files of a template, by their path in the project and their asset in `assets/templates`.
+macro_rules! template {
+    ($($path:literal => $asset:literal),* $(,)?) => {
+        &[$(($path, include_str!(concat!("../assets/templates/", $asset)))),*]
+    };
+}
+
+const EVM_FILES: &[(&str, &str)] = template! {
+    "README.md" => "evm/README.md",
+    "program/Cargo.toml" => "evm/program/Cargo.toml",
+    "program/src/main.rs" => "evm/program/main.rs",
+    "script/Cargo.toml" => "evm/script/Cargo.toml",
+    "script/src/main.rs" => "evm/script/main.rs",
+    "contracts/src/Fibonacci.sol" => "evm/contracts/Fibonacci.sol",
+};
+
+const AGGREGATION_FILES: &[(&str, &str)] = template! {
+    "README.md" => "aggregation/README.md",
+    "aggregator/Cargo.toml" => "aggregation/aggregator/Cargo.toml",
+    "aggregator/src/main.rs" => "aggregation/aggregator/main.rs",
+    "script/build.rs" => "aggregation/script/build.rs",
+    "script/src/main.rs" => "aggregation/script/main.rs",
+};
+
+const SERDE_IO_FILES: &[(&str, &str)] = template! {
+    "README.md" => "serde-io/README.md",
+    "lib/Cargo.toml" => "serde-io/lib/Cargo.toml",
+    "lib/src/lib.rs" => "serde-io/lib/lib.rs",
+    "program/Cargo.toml" => "serde-io/program/Cargo.toml",
+    "program/src/main.rs" => "serde-io/program/main.rs",
+    "script/Cargo.toml" => "serde-io/script/Cargo.toml",
+    "script/src/main.rs" => "serde-io/script/main.rs",
+};
+
+/// The project scaffolded by `cargo prove new`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Template {
+    /// A program and a script which proves it.
+    #[default]
+    Basic,
+    /// A program whose public values are ABI encoded, a script which proves it with Groth16 and
+    /// exports its Solidity verifier, and a Foundry project of the contract which verifies it.
+    Evm,
+    /// A program, an aggregator program which verifies its proofs, and a script which aggregates
+    /// proofs of the program.
+    Aggregation,
+    /// A program which reads and commits structured values, with their types in a library shared
+    /// with the script.
+    SerdeIo,
+}
+
+impl Template {
+    /// The files of the template, which replace the files of the basic project at the same paths.
+    fn files(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Template::Basic => &[],
+            Template::Evm => EVM_FILES,
+            Template::Aggregation => AGGREGATION_FILES,
+            Template::SerdeIo => SERDE_IO_FILES,
+        }
+    }
+}
+
 #[derive(Parser)]
 #[command(name = "new", about = "Setup a new project that runs inside the SP1.")]
 pub struct NewCmd {
     name: String,
+
+    /// The template of the project.
+    #[clap(long, value_enum, default_value_t = Template::Basic)]
+    template: Template,
 }
 
 impl NewCmd {
@@ -48,15 +115,31 @@ impl NewCmd {
         fs::write(script_root.join("rust-toolchain"), SCRIPT_RUST_TOOLCHAIN)?;
         fs::write(script_root.join("build.rs"), SCRIPT_BUILD_RS)?;
 
+        // Add the files of the template, with the crates named after the project.
+        let crate_name = self.name.replace('-', "_");
+        for (path, contents) in self.template.files() {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap())?;
+            let contents = contents
+                .replace("unnamed_", &format!("{}_", crate_name))
+                .replace("unnamed", &self.name);
+            fs::write(path, contents)?;
+        }
+        let mut vs_code_settings = VS_CODE_SETTINGS_JSON.to_string();
+        if self.template == Template::Aggregation {
+            fs::create_dir_all(root.join("aggregator").join("elf"))?;
+            vs_code_settings = vs_code_settings.replace(
+                "\"script/Cargo.toml\",",
+                "\"script/Cargo.toml\",\n        \"aggregator/Cargo.toml\",",
+            );
+        }
+
         // Add .gitignore file to root.
         fs::write(root.join(".gitignore"), GIT_IGNORE)?;
 
         // Add .vscode/settings.json to root.
         fs::create_dir(root.join(".vscode"))?;
-        fs::write(
-            root.join(".vscode").join("settings.json"),
-            VS_CODE_SETTINGS_JSON,
-        )?;
+        fs::write(root.join(".vscode").join("settings.json"), vs_code_settings)?;
 
         println!(
             "    \x1b[1m{}\x1b[0m {} ({})",
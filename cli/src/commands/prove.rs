@@ -13,7 +13,7 @@ use crate::{
 };
 
 #[derive(Debug, Clone)]
-enum Input {
+pub(crate) enum Input {
     FilePath(PathBuf),
     HexBytes(Vec<u8>),
 }
@@ -54,6 +54,24 @@ impl FromStr for Input {
     }
 }
 
+impl Input {
+    /// Writes the input to `stdin`, as the bytes of a file or the decoded hex bytes.
+    pub(crate) fn write_to(&self, stdin: &mut SP1Stdin) -> Result<()> {
+        match self {
+            Input::FilePath(ref path) => {
+                let mut file = File::open(path).expect("failed to open input file");
+                let mut bytes = Vec::new();
+                file.read_to_end(&mut bytes)?;
+                stdin.write_slice(&bytes);
+            }
+            Input::HexBytes(ref bytes) => {
+                stdin.write_slice(bytes);
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Parser)]
 #[command(name = "prove", about = "(default) Build and prove a program")]
 pub struct ProveCmd {
@@ -99,17 +117,7 @@ impl ProveCmd {
 
         let mut stdin = SP1Stdin::new();
         if let Some(ref input) = self.input {
-            match input {
-                Input::FilePath(ref path) => {
-                    let mut file = File::open(path).expect("failed to open input file");
-                    let mut bytes = Vec::new();
-                    file.read_to_end(&mut bytes)?;
-                    stdin.write_slice(&bytes);
-                }
-                Input::HexBytes(ref bytes) => {
-                    stdin.write_slice(bytes);
-                }
-            }
+            input.write_to(&mut stdin)?;
         }
 
         let start_time = Instant::now();
@@ -0,0 +1,46 @@
+use anyhow::Result;
+use clap::Parser;
+use sp1_sdk::{SP1CompressedProof, SP1Proof};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(
+    name = "size",
+    about = "Break down the size of a saved proof into its components"
+)]
+pub struct SizeCmd {
+    /// The path of the proof, as saved by `SP1ProofWithPublicValues::save`.
+    proof: PathBuf,
+
+    /// Whether the proof is a compressed proof instead of a core proof.
+    #[clap(long, action)]
+    compressed: bool,
+
+    /// Print the breakdown as JSON.
+    #[clap(long, action)]
+    json: bool,
+}
+
+impl SizeCmd {
+    pub fn run(&self) -> Result<()> {
+        let shards = if self.compressed {
+            vec![SP1CompressedProof::load(&self.proof)?.size_breakdown()]
+        } else {
+            SP1Proof::load(&self.proof)?.size_breakdown()
+        };
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&shards)?);
+            return Ok(());
+        }
+        for (i, shard) in shards.iter().enumerate() {
+            println!("shard {}", i);
+            print!("{}", shard);
+        }
+        if shards.len() > 1 {
+            let total = shards.iter().map(|shard| shard.total).sum::<usize>();
+            println!("{} shards: {} bytes", shards.len(), total);
+        }
+        Ok(())
+    }
+}
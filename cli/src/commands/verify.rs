@@ -0,0 +1,159 @@
+use anstyle::*;
+use anyhow::{bail, Context, Result};
+use clap::{Parser, ValueEnum};
+use sha2::{Digest, Sha256};
+use sp1_core::air::{PublicValues, Word};
+use sp1_sdk::{
+    HashableKey, ProverClient, SP1CompressedProof, SP1Groth16Bn254Proof, SP1PlonkBn254Proof,
+    SP1Proof, SP1PublicValues, SP1VerifyingKey,
+};
+use std::{fs, path::PathBuf};
+
+use crate::util::write_status;
+
+/// The kind of a saved proof.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+enum ProofKind {
+    /// A proof generated with `ProverClient::prove`.
+    #[default]
+    Core,
+    /// A proof generated with `ProverClient::prove_compressed`.
+    Compressed,
+    /// A proof generated with `ProverClient::prove_plonk`.
+    Plonk,
+    /// A proof generated with `ProverClient::prove_groth16`.
+    Groth16,
+}
+
+#[derive(Parser)]
+#[command(
+    name = "verify",
+    about = "Verify a saved proof and print its public values"
+)]
+pub struct VerifyCmd {
+    /// The path of the proof, as saved by `SP1ProofWithPublicValues::save`.
+    proof: PathBuf,
+
+    /// The kind of the proof.
+    #[clap(long, value_enum, default_value_t = ProofKind::Core)]
+    kind: ProofKind,
+
+    /// The path of the verifying key of the program, as saved by `SP1VerifyingKey::save`.
+    #[clap(long, conflicts_with = "elf", required_unless_present = "elf")]
+    vkey: Option<PathBuf>,
+
+    /// The path of the ELF of the program, whose verifying key is computed.
+    #[clap(long)]
+    elf: Option<PathBuf>,
+
+    /// The expected public values of the proof, as hex. Verification fails if they differ.
+    #[clap(long)]
+    public_values: Option<String>,
+
+    /// Accept proofs of programs which halted with a nonzero exit code.
+    #[clap(long, action)]
+    allow_nonzero_exit_code: bool,
+}
+
+impl VerifyCmd {
+    pub fn run(&self) -> Result<()> {
+        let mut client = ProverClient::new();
+        client.allow_nonzero_exit_code = self.allow_nonzero_exit_code;
+        let vk = match (&self.vkey, &self.elf) {
+            (Some(path), _) => SP1VerifyingKey::load(path)
+                .with_context(|| format!("failed to load the vkey {}", path.display()))?,
+            (None, Some(path)) => {
+                let elf = fs::read(path)
+                    .with_context(|| format!("failed to read the ELF {}", path.display()))?;
+                client.setup(&elf).1
+            }
+            (None, None) => unreachable!(),
+        };
+
+        let public_values = match self.kind {
+            ProofKind::Core => {
+                let proof = SP1Proof::load(&self.proof)?;
+                client.verify(&proof, &vk)?;
+                if let Some(shard) = proof.proof.last() {
+                    let public_values =
+                        PublicValues::<Word<_>, _>::from_vec(shard.public_values.clone());
+                    print_shard_public_values(&public_values.to_u32s(), proof.proof.len());
+                }
+                proof.public_values
+            }
+            ProofKind::Compressed => {
+                let proof = SP1CompressedProof::load(&self.proof)?;
+                client.verify_compressed(&proof, &vk)?;
+                proof.public_values
+            }
+            ProofKind::Plonk => {
+                let proof = SP1PlonkBn254Proof::load(&self.proof)?;
+                client.verify_plonk(&proof, &vk)?;
+                proof.public_values
+            }
+            ProofKind::Groth16 => {
+                let proof = SP1Groth16Bn254Proof::load(&self.proof)?;
+                client.verify_groth16(&proof, &vk)?;
+                proof.public_values
+            }
+        };
+        print_public_values(&public_values);
+
+        if let Some(expected) = &self.public_values {
+            let expected = hex::decode(expected.trim_start_matches("0x"))
+                .context("the expected public values are not hex")?;
+            if expected != public_values.as_slice() {
+                bail!("the public values of the proof differ from the expected public values");
+            }
+        }
+
+        let green = AnsiColor::Green.on_default().effects(Effects::BOLD);
+        write_status(
+            &green,
+            "Verified",
+            format!("proof of the program {}", vk.bytes32()).as_str(),
+        );
+        Ok(())
+    }
+}
+
+/// Prints the public values committed by the program, with their digest and their 32 byte words,
+/// which are the fields of public values committed with `commit_abi`.
+fn print_public_values(public_values: &SP1PublicValues) {
+    let bytes = public_values.as_slice();
+    println!("public values: {} bytes", bytes.len());
+    println!("  digest: 0x{}", hex::encode(Sha256::digest(bytes)));
+    println!("  bytes: 0x{}", hex::encode(bytes));
+    for (i, word) in bytes.chunks(32).enumerate() {
+        println!("  word {}: 0x{}", i, hex::encode(word));
+    }
+}
+
+/// Prints the public values of the last shard of a core proof, which hold the outcome of the
+/// execution.
+fn print_shard_public_values(public_values: &PublicValues<u32, u32>, num_shards: usize) {
+    let words = |words: &[u32]| {
+        words
+            .iter()
+            .map(|word| format!("{:08x}", word))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    println!("shards: {}", num_shards);
+    println!("  exit code: {}", public_values.exit_code);
+    println!("  last shard: {}", public_values.shard);
+    println!(
+        "  pc: 0x{:08x} -> 0x{:08x}",
+        public_values.start_pc, public_values.next_pc
+    );
+    println!(
+        "  committed value digest: {}",
+        words(&public_values.committed_value_digest)
+    );
+    println!(
+        "  deferred proofs digest: {}",
+        words(&public_values.deferred_proofs_digest)
+    );
+    println!("  vk digest: {}", words(&public_values.vk_digest));
+    println!("  user values: {}", words(&public_values.user_values));
+}
@@ -0,0 +1,13 @@
+fn main() {
+    // Compile the CUDA kernels of the prover.
+    #[cfg(feature = "cuda")]
+    {
+        println!("cargo:rerun-if-changed=cuda");
+        cc::Build::new()
+            .cuda(true)
+            .cudart("static")
+            .flag("-O3")
+            .file("cuda/babybear_dft.cu")
+            .compile("sp1_cuda");
+    }
+}
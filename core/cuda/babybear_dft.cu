@@ -0,0 +1,148 @@
+// Coset low-degree extensions of matrices of BabyBear elements.
+//
+// The matrices are row major with one polynomial per column, and the elements are canonical. The
+// output is in bit-reversed row order, as the output of `Radix2DitParallel` on the host.
+
+#include <cstdint>
+#include <cuda_runtime.h>
+
+static constexpr uint32_t P = 0x78000001;
+static constexpr uint32_t THREADS = 256;
+
+__device__ __forceinline__ uint32_t add(uint32_t a, uint32_t b) {
+    uint32_t c = a + b;
+    return c >= P ? c - P : c;
+}
+
+__device__ __forceinline__ uint32_t sub(uint32_t a, uint32_t b) {
+    return a >= b ? a - b : a + P - b;
+}
+
+__device__ __forceinline__ uint32_t mul(uint32_t a, uint32_t b) {
+    return (uint32_t)(((uint64_t)a * b) % P);
+}
+
+// One stage of a decimation-in-frequency NTT over blocks of `2 * half` rows. The twiddle of index
+// `j` of the stage is `twiddles[j * stride]`.
+__global__ void dif_stage(uint32_t* values, uint32_t width, uint32_t height, uint32_t half,
+                          const uint32_t* twiddles, uint32_t stride) {
+    uint64_t t = (uint64_t)blockIdx.x * blockDim.x + threadIdx.x;
+    if (t >= (uint64_t)(height / 2) * width) {
+        return;
+    }
+    uint32_t col = t % width;
+    uint32_t butterfly = t / width;
+    uint32_t block = butterfly / half;
+    uint32_t j = butterfly % half;
+    uint64_t i0 = ((uint64_t)block * 2 * half + j) * width + col;
+    uint64_t i1 = i0 + (uint64_t)half * width;
+    uint32_t a = values[i0];
+    uint32_t b = values[i1];
+    values[i0] = add(a, b);
+    values[i1] = mul(sub(a, b), twiddles[(uint64_t)j * stride]);
+}
+
+// Writes the bit-reversed coefficients of `src`, scaled by `scale`, to the first rows of `dst`,
+// whose other rows are zero.
+__global__ void reverse_scale_pad(const uint32_t* src, uint32_t* dst, uint32_t width,
+                                  uint32_t height, uint32_t log_height, const uint32_t* scale) {
+    uint64_t t = (uint64_t)blockIdx.x * blockDim.x + threadIdx.x;
+    if (t >= (uint64_t)height * width) {
+        return;
+    }
+    uint32_t col = t % width;
+    uint32_t row = t / width;
+    uint32_t rev = log_height == 0 ? 0 : __brev(row) >> (32 - log_height);
+    dst[(uint64_t)row * width + col] = mul(src[(uint64_t)rev * width + col], scale[row]);
+}
+
+static uint32_t num_blocks(uint64_t n) {
+    return (uint32_t)((n + THREADS - 1) / THREADS);
+}
+
+static cudaError_t dif(uint32_t* values, uint32_t width, uint32_t height, const uint32_t* twiddles,
+                       uint32_t twiddles_len) {
+    for (uint32_t half = height / 2; half >= 1; half /= 2) {
+        uint32_t stride = twiddles_len / half;
+        dif_stage<<<num_blocks((uint64_t)(height / 2) * width), THREADS>>>(
+            values, width, height, half, twiddles, stride);
+        cudaError_t err = cudaGetLastError();
+        if (err != cudaSuccess) {
+            return err;
+        }
+    }
+    return cudaSuccess;
+}
+
+extern "C" int32_t sp1_cuda_device_count() {
+    int count = 0;
+    if (cudaGetDeviceCount(&count) != cudaSuccess) {
+        return 0;
+    }
+    return count;
+}
+
+// Computes the coset LDE of the `height x width` matrix `values` into the
+// `(height << log_blowup) x width` matrix `out`.
+//
+// `inv_twiddles` are the `height / 2` first powers of the inverse of the generator of the subgroup
+// of order `height`, `twiddles` the `(height << log_blowup) / 2` first powers of the generator of
+// the subgroup of the extended order, and `scale[j]` is `shift^j / height`.
+//
+// Returns 0 on success, and a CUDA error code otherwise.
+extern "C" int32_t sp1_cuda_babybear_coset_lde(const uint32_t* values, uint32_t height,
+                                               uint32_t width, uint32_t log_blowup,
+                                               const uint32_t* inv_twiddles,
+                                               const uint32_t* twiddles, const uint32_t* scale,
+                                               uint32_t* out) {
+    uint32_t log_height = 0;
+    while ((1u << log_height) < height) {
+        log_height++;
+    }
+    uint64_t lde_height = (uint64_t)height << log_blowup;
+    size_t len = (size_t)height * width;
+    size_t lde_len = (size_t)lde_height * width;
+
+    uint32_t *d_values = nullptr, *d_lde = nullptr, *d_inv_twiddles = nullptr,
+             *d_twiddles = nullptr, *d_scale = nullptr;
+    cudaError_t err;
+#define CHECK(expr)              \
+    err = (expr);                \
+    if (err != cudaSuccess) {    \
+        goto done;               \
+    }
+
+    CHECK(cudaMalloc(&d_values, len * sizeof(uint32_t)));
+    CHECK(cudaMalloc(&d_lde, lde_len * sizeof(uint32_t)));
+    CHECK(cudaMalloc(&d_inv_twiddles, (height / 2 + 1) * sizeof(uint32_t)));
+    CHECK(cudaMalloc(&d_twiddles, (lde_height / 2 + 1) * sizeof(uint32_t)));
+    CHECK(cudaMalloc(&d_scale, height * sizeof(uint32_t)));
+    CHECK(cudaMemcpy(d_values, values, len * sizeof(uint32_t), cudaMemcpyHostToDevice));
+    CHECK(cudaMemcpy(d_inv_twiddles, inv_twiddles, (height / 2) * sizeof(uint32_t),
+                     cudaMemcpyHostToDevice));
+    CHECK(cudaMemcpy(d_twiddles, twiddles, (lde_height / 2) * sizeof(uint32_t),
+                     cudaMemcpyHostToDevice));
+    CHECK(cudaMemcpy(d_scale, scale, height * sizeof(uint32_t), cudaMemcpyHostToDevice));
+    CHECK(cudaMemset(d_lde, 0, lde_len * sizeof(uint32_t)));
+
+    // Interpolate, which gives the coefficients in bit-reversed order.
+    CHECK(dif(d_values, width, height, d_inv_twiddles, height / 2));
+
+    // Put the coefficients in order, shift them to the coset and pad them.
+    reverse_scale_pad<<<num_blocks(len), THREADS>>>(d_values, d_lde, width, height, log_height,
+                                                    d_scale);
+    CHECK(cudaGetLastError());
+
+    // Evaluate over the extended coset.
+    CHECK(dif(d_lde, width, (uint32_t)lde_height, d_twiddles, (uint32_t)(lde_height / 2)));
+    CHECK(cudaMemcpy(out, d_lde, lde_len * sizeof(uint32_t), cudaMemcpyDeviceToHost));
+
+done:
+#undef CHECK
+    cudaFree(d_values);
+    cudaFree(d_lde);
+    cudaFree(d_inv_twiddles);
+    cudaFree(d_twiddles);
+    cudaFree(d_scale);
+    return (int32_t)err;
+}
@@ -0,0 +1,65 @@
+// Coset low-degree extensions of matrices of BabyBear elements, as in `cuda/babybear_dft.cu`.
+//
+// The matrices are row major with one polynomial per column, and the elements are canonical. The
+// host runs `dif_stage` for each stage of the interpolation, then `reverse_scale_pad`, then
+// `dif_stage` for each stage of the evaluation, so the output is in bit-reversed row order.
+
+#include <metal_stdlib>
+using namespace metal;
+
+constant uint P = 0x78000001;
+
+inline uint add(uint a, uint b) {
+    uint c = a + b;
+    return c >= P ? c - P : c;
+}
+
+inline uint sub(uint a, uint b) {
+    return a >= b ? a - b : a + P - b;
+}
+
+inline uint mul(uint a, uint b) {
+    return uint((ulong(a) * ulong(b)) % ulong(P));
+}
+
+struct StageParams {
+    uint width;
+    uint half_len;
+    uint stride;
+};
+
+// One stage of a decimation-in-frequency NTT over blocks of `2 * half_len` rows, with a thread for
+// each butterfly and column. The twiddle of index `j` of the stage is `twiddles[j * stride]`.
+kernel void dif_stage(device uint* values [[buffer(0)]],
+                      device const uint* twiddles [[buffer(1)]],
+                      constant StageParams& params [[buffer(2)]],
+                      uint2 gid [[thread_position_in_grid]]) {
+    uint butterfly = gid.x;
+    uint col = gid.y;
+    uint block = butterfly / params.half_len;
+    uint j = butterfly % params.half_len;
+    ulong i0 = (ulong(block) * 2 * params.half_len + j) * params.width + col;
+    ulong i1 = i0 + ulong(params.half_len) * params.width;
+    uint a = values[i0];
+    uint b = values[i1];
+    values[i0] = add(a, b);
+    values[i1] = mul(sub(a, b), twiddles[j * params.stride]);
+}
+
+struct PadParams {
+    uint width;
+    uint log_height;
+};
+
+// Writes the bit-reversed coefficients of `src`, scaled by `scale`, to the first rows of `dst`,
+// whose other rows are already zero.
+kernel void reverse_scale_pad(device const uint* src [[buffer(0)]],
+                              device uint* dst [[buffer(1)]],
+                              device const uint* scale [[buffer(2)]],
+                              constant PadParams& params [[buffer(3)]],
+                              uint2 gid [[thread_position_in_grid]]) {
+    uint row = gid.x;
+    uint col = gid.y;
+    uint rev = params.log_height == 0 ? 0 : reverse_bits(row) >> (32 - params.log_height);
+    dst[ulong(row) * params.width + col] = mul(src[ulong(rev) * params.width + col], scale[row]);
+}
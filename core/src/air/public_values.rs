@@ -1,14 +1,16 @@
 use core::fmt::Debug;
 use core::mem::size_of;
-use std::array;
 use std::iter::once;
 
 use itertools::Itertools;
 use p3_field::{AbstractField, PrimeField32};
 use serde::{Deserialize, Serialize};
+use sp1_precompiles::abi::{Bytes32, SolidityAbi, TupleDecoder, TupleEncoder, WORD_LEN};
+use thiserror::Error;
 
-use super::Word;
+use super::{Word, WORD_SIZE};
 use crate::stark::PROOF_MAX_NUM_PVS;
+use crate::utils::DIGEST_SIZE;
 
 /// The number of non padded elements in the SP1 proofs public values vec.
 pub const SP1_PROOF_NUM_PV_ELTS: usize = size_of::<PublicValues<Word<u8>, u8>>();
@@ -18,9 +20,22 @@ pub const PV_DIGEST_NUM_WORDS: usize = 8;
 
 pub const POSEIDON_NUM_WORDS: usize = 8;
 
+/// The number of 32 bit words that a guest program can set as public values of its own.
+pub const USER_PV_NUM_WORDS: usize = 8;
+
+/// The version of the layout of [PublicValues].
+///
+/// The version is the first element of the public values, so that it can be read under any
+/// layout. Changing the layout in any way requires a new version.
+pub const PV_VERSION: u32 = 3;
+
 /// The PublicValues struct is used to store all of a shard proof's public values.
 #[derive(Serialize, Deserialize, Clone, Copy, Default, Debug)]
 pub struct PublicValues<W, T> {
+    /// The version of the layout of the public values, which is [PV_VERSION] for the current
+    /// layout.
+    pub version: T,
+
     /// The hash of all the bytes that the guest program has written to public values.
     pub committed_value_digest: [W; PV_DIGEST_NUM_WORDS],
 
@@ -40,16 +55,24 @@ pub struct PublicValues<W, T> {
 
     /// The shard number.
     pub shard: T,
+
+    /// The digest of the verifying key of the program, which binds the shard to its program.
+    pub vk_digest: [T; DIGEST_SIZE],
+
+    /// The words that the guest program has set as public values of its own, zero by default.
+    pub user_values: [W; USER_PV_NUM_WORDS],
 }
 
 impl PublicValues<u32, u32> {
     /// Convert the public values into a vector of field elements.  This function will pad the vector
     /// to the maximum number of public values.
     pub fn to_vec<F: AbstractField>(&self) -> Vec<F> {
-        let mut ret = self
-            .committed_value_digest
-            .iter()
-            .flat_map(|w| Word::<F>::from(*w).into_iter())
+        let mut ret = once(F::from_canonical_u32(self.version))
+            .chain(
+                self.committed_value_digest
+                    .iter()
+                    .flat_map(|w| Word::<F>::from(*w).into_iter()),
+            )
             .chain(
                 self.deferred_proofs_digest
                     .iter()
@@ -60,6 +83,12 @@ impl PublicValues<u32, u32> {
             .chain(once(F::from_canonical_u32(self.next_pc)))
             .chain(once(F::from_canonical_u32(self.exit_code)))
             .chain(once(F::from_canonical_u32(self.shard)))
+            .chain(self.vk_digest.iter().cloned().map(F::from_canonical_u32))
+            .chain(
+                self.user_values
+                    .iter()
+                    .flat_map(|w| Word::<F>::from(*w).into_iter()),
+            )
             .collect_vec();
 
         assert!(
@@ -74,44 +103,150 @@ impl PublicValues<u32, u32> {
     }
 }
 
+/// Packs words into the bytes of a `bytes32`, each word in little-endian order, as the bytes of the
+/// committed value digest are packed into words.
+fn words_to_bytes32(words: &[u32; 8]) -> Bytes32 {
+    let mut bytes = [0u8; 32];
+    for (chunk, word) in bytes.chunks_exact_mut(WORD_SIZE).zip(words.iter()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    Bytes32(bytes)
+}
+
+/// Unpacks the words packed by [words_to_bytes32].
+fn bytes32_to_words(bytes: &Bytes32) -> [u32; 8] {
+    core::array::from_fn(|i| {
+        u32::from_le_bytes(
+            bytes.0[i * WORD_SIZE..(i + 1) * WORD_SIZE]
+                .try_into()
+                .unwrap(),
+        )
+    })
+}
+
+/// The public values are encoded in the Solidity ABI as the tuple
+/// `(uint32 version, bytes32 committedValueDigest, uint32[8] deferredProofsDigest, uint32 startPc,
+/// uint32 nextPc, uint32 exitCode, uint32 shard, uint32[8] vkDigest, bytes32 userValues)`.
+impl SolidityAbi for PublicValues<u32, u32> {
+    const DYNAMIC: bool = false;
+    const HEAD_LEN: usize = (1 + 1 + POSEIDON_NUM_WORDS + 4 + DIGEST_SIZE + 1) * WORD_LEN;
+
+    fn sol_type() -> String {
+        "(uint32,bytes32,uint32[8],uint32,uint32,uint32,uint32,uint32[8],bytes32)".to_string()
+    }
+
+    fn encode_body(&self, out: &mut Vec<u8>) {
+        let mut encoder = TupleEncoder::new(Self::HEAD_LEN);
+        encoder.push(&self.version);
+        encoder.push(&words_to_bytes32(&self.committed_value_digest));
+        encoder.push(&self.deferred_proofs_digest);
+        encoder.push(&self.start_pc);
+        encoder.push(&self.next_pc);
+        encoder.push(&self.exit_code);
+        encoder.push(&self.shard);
+        encoder.push(&self.vk_digest);
+        encoder.push(&words_to_bytes32(&self.user_values));
+        encoder.finish(out);
+    }
+
+    fn decode_body(data: &[u8], offset: usize) -> anyhow::Result<Self> {
+        let mut decoder = TupleDecoder::new(data, offset);
+        Ok(Self {
+            version: decoder.read()?,
+            committed_value_digest: bytes32_to_words(&decoder.read()?),
+            deferred_proofs_digest: decoder.read()?,
+            start_pc: decoder.read()?,
+            next_pc: decoder.read()?,
+            exit_code: decoder.read()?,
+            shard: decoder.read()?,
+            vk_digest: decoder.read()?,
+            user_values: bytes32_to_words(&decoder.read()?),
+        })
+    }
+}
+
+/// An error when decoding [PublicValues] from a vector of elements.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PublicValuesError {
+    #[error("expected at least {expected} public values to decode `{field}`, got {actual}")]
+    TooShort {
+        field: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+}
+
 impl<T: Clone + Debug> PublicValues<Word<T>, T> {
     /// Convert a vector of field elements into a PublicValues struct.
+    ///
+    /// Panics if the vector is too short. Use [TryFrom] for public values which are not known to
+    /// have the right length, such as those of a proof being verified.
     pub fn from_vec(data: Vec<T>) -> Self {
-        let mut iter = data.iter().cloned();
-
-        let committed_value_digest = array::from_fn(|_| Word::from_iter(&mut iter));
+        Self::try_from(data).unwrap_or_else(|e| panic!("{}", e))
+    }
+}
 
-        let deferred_proofs_digest = iter
-            .by_ref()
-            .take(POSEIDON_NUM_WORDS)
-            .collect_vec()
-            .try_into()
-            .unwrap();
-
-        // Collecting the remaining items into a tuple.  Note that it is only getting the first
-        // four items, as the rest would be padded values.
-        let remaining_items = iter.collect_vec();
-        if remaining_items.len() < 4 {
-            panic!("Invalid number of items in the serialized vector.");
-        }
+impl<T: Clone + Debug> TryFrom<Vec<T>> for PublicValues<Word<T>, T> {
+    type Error = PublicValuesError;
 
-        let [start_pc, next_pc, exit_code, shard] = match &remaining_items.as_slice()[0..4] {
-            [start_pc, next_pc, exit_code, shard] => [start_pc, next_pc, exit_code, shard],
-            _ => unreachable!(),
+    /// Convert a vector of field elements into a PublicValues struct. The elements after the
+    /// public values are padding and are ignored.
+    fn try_from(data: Vec<T>) -> Result<Self, Self::Error> {
+        let mut offset = 0;
+        let mut take = |field: &'static str, len: usize| -> Result<Vec<T>, PublicValuesError> {
+            let end = offset + len;
+            let values = data.get(offset..end).ok_or(PublicValuesError::TooShort {
+                field,
+                expected: end,
+                actual: data.len(),
+            })?;
+            offset = end;
+            Ok(values.to_vec())
         };
 
-        Self {
-            committed_value_digest,
+        let [version] = take_array(take("version", 1)?);
+        let committed_value_digest =
+            take("committed_value_digest", PV_DIGEST_NUM_WORDS * WORD_SIZE)?
+                .chunks_exact(WORD_SIZE)
+                .map(|chunk| Word::from_iter(chunk.iter().cloned()))
+                .collect_vec();
+        let deferred_proofs_digest =
+            take_array(take("deferred_proofs_digest", POSEIDON_NUM_WORDS)?);
+        let [start_pc] = take_array(take("start_pc", 1)?);
+        let [next_pc] = take_array(take("next_pc", 1)?);
+        let [exit_code] = take_array(take("exit_code", 1)?);
+        let [shard] = take_array(take("shard", 1)?);
+        let vk_digest = take_array(take("vk_digest", DIGEST_SIZE)?);
+        let user_values = take("user_values", USER_PV_NUM_WORDS * WORD_SIZE)?
+            .chunks_exact(WORD_SIZE)
+            .map(|chunk| Word::from_iter(chunk.iter().cloned()))
+            .collect_vec();
+
+        Ok(Self {
+            version,
+            committed_value_digest: take_array(committed_value_digest),
             deferred_proofs_digest,
-            start_pc: start_pc.to_owned(),
-            next_pc: next_pc.to_owned(),
-            exit_code: exit_code.to_owned(),
-            shard: shard.to_owned(),
-        }
+            start_pc,
+            next_pc,
+            exit_code,
+            shard,
+            vk_digest,
+            user_values: take_array(user_values),
+        })
     }
 }
 
+/// Converts a vector whose length is already checked into an array.
+fn take_array<T: Debug, const N: usize>(values: Vec<T>) -> [T; N] {
+    values.try_into().unwrap()
+}
+
 impl<F: PrimeField32> PublicValues<Word<F>, F> {
+    /// Whether the public values have the layout of this version.
+    pub fn is_current_version(&self) -> bool {
+        self.version == F::from_canonical_u32(PV_VERSION)
+    }
+
     /// Returns the commit digest as a vector of little-endian bytes.
     pub fn commit_digest_bytes(&self) -> Vec<u8> {
         self.committed_value_digest
@@ -119,11 +254,37 @@ impl<F: PrimeField32> PublicValues<Word<F>, F> {
             .flat_map(|w| w.into_iter().map(|f| f.as_canonical_u32() as u8))
             .collect_vec()
     }
+
+    /// Returns the words that the guest program has set as public values of its own.
+    pub fn user_values_u32(&self) -> [u32; USER_PV_NUM_WORDS] {
+        self.user_values
+            .map(|w| u32::from_le_bytes(w.0.map(|f| f.as_canonical_u32() as u8)))
+    }
+
+    /// Returns the public values as canonical integers, which can be encoded in the Solidity ABI.
+    pub fn to_u32s(&self) -> PublicValues<u32, u32> {
+        PublicValues {
+            version: self.version.as_canonical_u32(),
+            committed_value_digest: self.committed_value_digest.map(|w| w.to_u32()),
+            deferred_proofs_digest: self.deferred_proofs_digest.map(|f| f.as_canonical_u32()),
+            start_pc: self.start_pc.as_canonical_u32(),
+            next_pc: self.next_pc.as_canonical_u32(),
+            exit_code: self.exit_code.as_canonical_u32(),
+            shard: self.shard.as_canonical_u32(),
+            vk_digest: self.vk_digest.map(|f| f.as_canonical_u32()),
+            user_values: self.user_values_u32(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::air::public_values;
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+
+    use crate::air::public_values::{self, PublicValues, PublicValuesError, PV_VERSION};
+    use crate::air::Word;
+    use sp1_precompiles::abi::{abi_decode, abi_encode};
 
     /// Check that the PI_DIGEST_NUM_WORDS number match the zkVM crate's.
     #[test]
@@ -133,4 +294,87 @@ mod tests {
             sp1_zkvm::PV_DIGEST_NUM_WORDS
         );
     }
+
+    /// Check that the USER_PV_NUM_WORDS number match the zkVM crate's.
+    #[test]
+    fn test_user_public_values_num_words_consistency_zkvm() {
+        assert_eq!(
+            public_values::USER_PV_NUM_WORDS,
+            sp1_zkvm::USER_PV_NUM_WORDS
+        );
+    }
+
+    /// Check that the version is the first element and survives a round trip.
+    #[test]
+    fn test_public_values_version_round_trip() {
+        let public_values = PublicValues::<u32, u32> {
+            version: PV_VERSION,
+            shard: 3,
+            exit_code: 1,
+            vk_digest: [7; 8],
+            ..Default::default()
+        };
+        let elements = public_values.to_vec::<BabyBear>();
+        assert_eq!(elements[0], BabyBear::from_canonical_u32(PV_VERSION));
+
+        let decoded = PublicValues::<Word<BabyBear>, BabyBear>::from_vec(elements);
+        assert!(decoded.is_current_version());
+        assert_eq!(decoded.shard, BabyBear::from_canonical_u32(3));
+        assert_eq!(decoded.exit_code, BabyBear::one());
+        assert_eq!(decoded.vk_digest, [BabyBear::from_canonical_u32(7); 8]);
+
+        let stale = PublicValues::<u32, u32>::default().to_vec::<BabyBear>();
+        assert!(!PublicValues::<Word<BabyBear>, BabyBear>::from_vec(stale).is_current_version());
+    }
+
+    /// Check that decoding a truncated vector reports the first missing field.
+    #[test]
+    fn test_public_values_try_from_too_short() {
+        let mut elements = PublicValues::<u32, u32>::default().to_vec::<BabyBear>();
+        elements.truncate(40);
+        let result = PublicValues::<Word<BabyBear>, BabyBear>::try_from(elements);
+        assert_eq!(
+            result.unwrap_err(),
+            PublicValuesError::TooShort {
+                field: "deferred_proofs_digest",
+                expected: 41,
+                actual: 40,
+            }
+        );
+
+        let result = PublicValues::<Word<BabyBear>, BabyBear>::try_from(vec![]);
+        assert_eq!(
+            result.unwrap_err(),
+            PublicValuesError::TooShort {
+                field: "version",
+                expected: 1,
+                actual: 0,
+            }
+        );
+    }
+
+    /// Check that the public values survive a round trip through the Solidity ABI.
+    #[test]
+    fn test_public_values_abi_round_trip() {
+        let public_values = PublicValues::<u32, u32> {
+            version: PV_VERSION,
+            committed_value_digest: [0x04030201; 8],
+            exit_code: 1,
+            vk_digest: [7; 8],
+            user_values: [9; 8],
+            ..Default::default()
+        };
+        let encoded = abi_encode(&public_values);
+        assert_eq!(encoded.len(), 23 * 32);
+        assert_eq!(encoded[32..36], [1, 2, 3, 4]);
+        let decoded: PublicValues<u32, u32> = abi_decode(&encoded).unwrap();
+        assert_eq!(
+            decoded.to_vec::<BabyBear>(),
+            public_values.to_vec::<BabyBear>()
+        );
+
+        let elements = public_values.to_vec::<BabyBear>();
+        let field_values = PublicValues::<Word<BabyBear>, BabyBear>::from_vec(elements);
+        assert_eq!(abi_encode(&field_values.to_u32s()), encoded);
+    }
 }
@@ -86,6 +86,24 @@ impl<AB: SP1AirBuilder + PairBuilder> Air<AB> for ByteChip<AB::F> {
                         channel_f,
                         mult,
                     ),
+                    ByteOpcode::AesSbox => builder.receive_byte(
+                        field_op,
+                        local.aes_sbox,
+                        local.b,
+                        AB::F::zero(),
+                        shard,
+                        channel_f,
+                        mult,
+                    ),
+                    ByteOpcode::Gf256Mul => builder.receive_byte(
+                        field_op,
+                        local.gf256_mul,
+                        local.b,
+                        local.c,
+                        shard,
+                        channel_f,
+                        mult,
+                    ),
                 }
             }
         }
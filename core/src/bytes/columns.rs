@@ -42,6 +42,12 @@ pub struct BytePreprocessedCols<T> {
 
     /// A u16 value used for `U16Range`.
     pub value_u16: T,
+
+    /// The AES substitution box applied to `b`.
+    pub aes_sbox: T,
+
+    /// The product of `b` and `c` in GF(2^8).
+    pub gf256_mul: T,
 }
 
 /// For each byte operation in the preprocessed table, a corresponding ByteMultCols row tracks the
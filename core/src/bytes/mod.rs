@@ -17,11 +17,11 @@ use p3_field::Field;
 use p3_matrix::dense::RowMajorMatrix;
 
 use self::columns::{BytePreprocessedCols, NUM_BYTE_PREPROCESSED_COLS};
-use self::utils::shr_carry;
+use self::utils::{gf256_mul, shr_carry, AES_SBOX};
 use crate::bytes::trace::NUM_ROWS;
 
 /// The number of different byte operations.
-pub const NUM_BYTE_OPS: usize = 9;
+pub const NUM_BYTE_OPS: usize = 11;
 
 /// The number of different byte lookup channels.
 pub const NUM_BYTE_LOOKUP_CHANNELS: u32 = 4;
@@ -134,6 +134,32 @@ impl<F: Field> ByteChip<F> {
                             col.value_u16 = F::from_canonical_u32(v);
                             ByteLookupEvent::new(shard, channel, *opcode, v, 0, 0, 0)
                         }
+                        ByteOpcode::AesSbox => {
+                            let sbox = AES_SBOX[b as usize];
+                            col.aes_sbox = F::from_canonical_u8(sbox);
+                            ByteLookupEvent::new(
+                                shard,
+                                channel,
+                                *opcode,
+                                sbox as u32,
+                                0,
+                                b as u32,
+                                0,
+                            )
+                        }
+                        ByteOpcode::Gf256Mul => {
+                            let product = gf256_mul(b, c);
+                            col.gf256_mul = F::from_canonical_u8(product);
+                            ByteLookupEvent::new(
+                                shard,
+                                channel,
+                                *opcode,
+                                product as u32,
+                                0,
+                                b as u32,
+                                c as u32,
+                            )
+                        }
                     };
                     event_map.insert(event, (row_index, i));
                 }
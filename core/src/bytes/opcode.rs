@@ -32,6 +32,12 @@ pub enum ByteOpcode {
 
     /// U16 Range check.
     U16Range = 8,
+
+    /// The AES substitution box.
+    AesSbox = 9,
+
+    /// Multiplication in GF(2^8) modulo `x^8 + x^4 + x^3 + x^2 + 1`.
+    Gf256Mul = 10,
 }
 
 impl ByteOpcode {
@@ -47,6 +53,8 @@ impl ByteOpcode {
             ByteOpcode::LTU,
             ByteOpcode::MSB,
             ByteOpcode::U16Range,
+            ByteOpcode::AesSbox,
+            ByteOpcode::Gf256Mul,
         ];
         assert_eq!(opcodes.len(), NUM_BYTE_OPS);
         opcodes
@@ -2,7 +2,7 @@ use p3_air::AirBuilder;
 use p3_field::AbstractField;
 
 use crate::air::{BaseAirBuilder, PublicValues, WordAirBuilder};
-use crate::cpu::air::{Word, POSEIDON_NUM_WORDS, PV_DIGEST_NUM_WORDS};
+use crate::cpu::air::{Word, POSEIDON_NUM_WORDS, PV_DIGEST_NUM_WORDS, USER_PV_NUM_WORDS};
 use crate::cpu::columns::{CpuCols, OpcodeSelectorCols};
 use crate::memory::MemoryCols;
 use crate::operations::IsZeroOperation;
@@ -72,29 +72,45 @@ impl CpuChip {
             ecall_cols.is_hint_len.result
         };
 
+        // Compute whether this ecall is CYCLE_COUNT.
+        let is_cycle_count = {
+            IsZeroOperation::<AB::F>::eval(
+                builder,
+                syscall_id - AB::Expr::from_canonical_u32(SyscallCode::CYCLE_COUNT.syscall_id()),
+                ecall_cols.is_cycle_count,
+                is_ecall_instruction.clone(),
+            );
+            ecall_cols.is_cycle_count.result
+        };
+
         // When syscall_id is ENTER_UNCONSTRAINED, the new value of op_a should be 0.
         let zero_word = Word::<AB::F>::from(0);
         builder
             .when(is_ecall_instruction.clone() * is_enter_unconstrained)
             .assert_word_eq(local.op_a_val(), zero_word);
 
-        // When the syscall is not one of ENTER_UNCONSTRAINED or HINT_LEN, op_a shouldn't change.
+        // When the syscall is not one of ENTER_UNCONSTRAINED, HINT_LEN or CYCLE_COUNT, op_a
+        // shouldn't change.
         builder
             .when(is_ecall_instruction.clone())
-            .when_not(is_enter_unconstrained + is_hint_len)
+            .when_not(is_enter_unconstrained + is_hint_len + is_cycle_count)
             .assert_word_eq(local.op_a_val(), local.op_a_access.prev_value);
     }
 
-    /// Constraints related to the COMMIT and COMMIT_DEFERRED_PROOFS instructions.
+    /// Constraints related to the COMMIT, COMMIT_DEFERRED_PROOFS and COMMIT_USER_VALUE
+    /// instructions.
     pub(crate) fn eval_commit<AB: SP1AirBuilder>(
         &self,
         builder: &mut AB,
         local: &CpuCols<AB::Var>,
         commit_digest: [Word<AB::Expr>; PV_DIGEST_NUM_WORDS],
         deferred_proofs_digest: [AB::Expr; POSEIDON_NUM_WORDS],
+        user_values: [Word<AB::Expr>; USER_PV_NUM_WORDS],
     ) {
-        let (is_commit, is_commit_deferred_proofs) =
+        let (is_commit, is_commit_deferred_proofs, is_commit_user_value) =
             self.get_is_commit_related_syscall(builder, local);
+        let is_commit_related =
+            is_commit.clone() + is_commit_deferred_proofs.clone() + is_commit_user_value.clone();
 
         // Get the ecall specific columns.
         let ecall_columns = local.opcode_specific_columns.ecall();
@@ -106,18 +122,14 @@ impl CpuChip {
             builder.when(local.selectors.is_ecall).assert_bool(*bit);
             bitmap_sum += (*bit).into();
         }
-        // When the syscall is COMMIT or COMMIT_DEFERRED_PROOFS, there should be one set bit.
+        // When the syscall is COMMIT, COMMIT_DEFERRED_PROOFS or COMMIT_USER_VALUE, there should be
+        // one set bit.
         builder
-            .when(
-                local.selectors.is_ecall * (is_commit.clone() + is_commit_deferred_proofs.clone()),
-            )
+            .when(local.selectors.is_ecall * is_commit_related.clone())
             .assert_one(bitmap_sum.clone());
         // When it's some other syscall, there should be no set bits.
         builder
-            .when(
-                local.selectors.is_ecall
-                    * (AB::Expr::one() - (is_commit.clone() + is_commit_deferred_proofs.clone())),
-            )
+            .when(local.selectors.is_ecall * (AB::Expr::one() - is_commit_related.clone()))
             .assert_zero(bitmap_sum);
 
         // Verify that word_idx corresponds to the set bit in index bitmap.
@@ -130,10 +142,7 @@ impl CpuChip {
         // Verify that the 3 upper bytes of the word_idx are 0.
         for i in 0..3 {
             builder
-                .when(
-                    local.selectors.is_ecall
-                        * (is_commit.clone() + is_commit_deferred_proofs.clone()),
-                )
+                .when(local.selectors.is_ecall * is_commit_related.clone())
                 .assert_eq(
                     local.op_b_access.prev_value()[i + 1],
                     AB::Expr::from_canonical_u32(0),
@@ -164,6 +173,13 @@ impl CpuChip {
                 expected_deferred_proofs_digest_word,
                 digest_word.reduce::<AB>(),
             );
+
+        let expected_user_value_word =
+            builder.index_word_array(&user_values, &ecall_columns.index_bitmap);
+
+        builder
+            .when(local.selectors.is_ecall * is_commit_user_value)
+            .assert_word_eq(expected_user_value_word, *digest_word);
     }
 
     /// Constraint related to the halt and unimpl instruction.
@@ -218,12 +234,13 @@ impl CpuChip {
         is_halt * is_ecall_instruction
     }
 
-    /// Returns two boolean expression indicating whether the instruction is a COMMIT or COMMIT_DEFERRED_PROOFS instruction.
+    /// Returns three boolean expression indicating whether the instruction is a COMMIT,
+    /// COMMIT_DEFERRED_PROOFS or COMMIT_USER_VALUE instruction.
     pub(crate) fn get_is_commit_related_syscall<AB: SP1AirBuilder>(
         &self,
         builder: &mut AB,
         local: &CpuCols<AB::Var>,
-    ) -> (AB::Expr, AB::Expr) {
+    ) -> (AB::Expr, AB::Expr, AB::Expr) {
         let ecall_cols = local.opcode_specific_columns.ecall();
 
         let is_ecall_instruction = self.is_ecall_instruction::<AB>(&local.selectors);
@@ -258,7 +275,23 @@ impl CpuChip {
             ecall_cols.is_commit_deferred_proofs.result
         };
 
-        (is_commit.into(), is_commit_deferred_proofs.into())
+        // Compute whether this ecall is COMMIT_USER_VALUE.
+        let is_commit_user_value = {
+            IsZeroOperation::<AB::F>::eval(
+                builder,
+                syscall_id
+                    - AB::Expr::from_canonical_u32(SyscallCode::COMMIT_USER_VALUE.syscall_id()),
+                ecall_cols.is_commit_user_value,
+                is_ecall_instruction.clone(),
+            );
+            ecall_cols.is_commit_user_value.result
+        };
+
+        (
+            is_commit.into(),
+            is_commit_deferred_proofs.into(),
+            is_commit_user_value.into(),
+        )
     }
 
     /// Returns the number of extra cycles from an ECALL instruction.
@@ -21,6 +21,7 @@ impl CpuChip {
             + opcode_selectors.is_sb
             + opcode_selectors.is_sh
             + opcode_selectors.is_sw
+            + self.is_atomic_instruction::<AB>(opcode_selectors)
     }
 
     /// Computes whether the opcode is a load instruction.
@@ -43,10 +44,28 @@ impl CpuChip {
         opcode_selectors.is_sb + opcode_selectors.is_sh + opcode_selectors.is_sw
     }
 
+    /// Computes whether the opcode is an atomic memory instruction.
+    pub(crate) fn is_atomic_instruction<AB: SP1AirBuilder>(
+        &self,
+        opcode_selectors: &OpcodeSelectorCols<AB::Var>,
+    ) -> AB::Expr {
+        opcode_selectors.is_sc
+            + opcode_selectors.is_amoswap
+            + opcode_selectors.is_amoadd
+            + opcode_selectors.is_amoxor
+            + opcode_selectors.is_amoand
+            + opcode_selectors.is_amoor
+            + opcode_selectors.is_amomin
+            + opcode_selectors.is_amomax
+            + opcode_selectors.is_amominu
+            + opcode_selectors.is_amomaxu
+    }
+
     /// Constrains the addr_aligned, addr_offset, and addr_word memory columns.
     ///
     /// This method will do the following:
-    /// 1. Calculate that the unaligned address is correctly computed to be op_b.value + op_c.value.
+    /// 1. Calculate that the unaligned address is correctly computed to be op_b.value + op_c.value,
+    ///    or op_b.value for the atomic instructions, whose addresses are aligned.
     /// 2. Calculate that the address offset is address % 4.
     /// 3. Assert the validity of the aligned address given the address offset and the unaligned address.
     pub(crate) fn eval_memory_address_and_access<AB: SP1AirBuilder>(
@@ -57,6 +76,7 @@ impl CpuChip {
     ) {
         // Get the memory specific columns.
         let memory_columns = local.opcode_specific_columns.memory();
+        let is_atomic = self.is_atomic_instruction::<AB>(&local.selectors);
 
         // Send to the ALU table to verify correct calculation of addr_word.
         builder.send_alu(
@@ -66,7 +86,17 @@ impl CpuChip {
             local.op_c_val(),
             local.shard,
             local.channel,
-            is_memory_instruction.clone(),
+            is_memory_instruction.clone() - is_atomic.clone(),
+        );
+
+        // The address of an atomic instruction is op_b, and must be aligned.
+        builder
+            .when(is_atomic.clone())
+            .assert_word_eq(memory_columns.addr_word, local.op_b_val());
+        builder.when(is_atomic).assert_zero(
+            memory_columns.offset_is_one
+                + memory_columns.offset_is_two
+                + memory_columns.offset_is_three,
         );
 
         // Check that each addr_word element is a byte.
@@ -215,6 +245,79 @@ impl CpuChip {
             .assert_word_eq(mem_val.map(|x| x.into()), a_val.map(|x| x.into()));
     }
 
+    /// Evaluates constraints related to the atomic memory instructions.
+    ///
+    /// The previous value in memory is written to op_a, except for SC, which always succeeds and
+    /// writes zero, and the new value in memory is computed from it and op_c.
+    pub(crate) fn eval_memory_atomic<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &CpuCols<AB::Var>,
+    ) {
+        let memory_columns = local.opcode_specific_columns.memory();
+        let selectors = &local.selectors;
+        let one = AB::Expr::one();
+        let a_val = local.op_a_val();
+        let c_val = local.op_c_val();
+        let mem_val = *memory_columns.memory_access.value();
+        let prev_mem_val = *memory_columns.memory_access.prev_value();
+
+        // Write the previous value in memory to op_a, unless op_a is register 0.
+        builder
+            .when(self.is_atomic_instruction::<AB>(selectors) - selectors.is_sc)
+            .when_not(local.instruction.op_a_0)
+            .assert_word_eq(a_val, prev_mem_val);
+        builder.when(selectors.is_sc).assert_word_zero(a_val);
+
+        // SC and AMOSWAP store op_c.
+        builder
+            .when(selectors.is_sc + selectors.is_amoswap)
+            .assert_word_eq(mem_val, c_val);
+
+        // Send to the ALU table to verify the new value of AMOADD, AMOXOR, AMOAND and AMOOR.
+        builder.send_alu(
+            selectors.is_amoadd * Opcode::ADD.as_field::<AB::F>()
+                + selectors.is_amoxor * Opcode::XOR.as_field::<AB::F>()
+                + selectors.is_amoand * Opcode::AND.as_field::<AB::F>()
+                + selectors.is_amoor * Opcode::OR.as_field::<AB::F>(),
+            mem_val,
+            prev_mem_val,
+            c_val,
+            local.shard,
+            local.channel,
+            selectors.is_amoadd + selectors.is_amoxor + selectors.is_amoand + selectors.is_amoor,
+        );
+
+        // Send to the ALU table to verify the comparison of AMOMIN, AMOMAX, AMOMINU and AMOMAXU.
+        let is_signed = selectors.is_amomin + selectors.is_amomax;
+        let is_unsigned = selectors.is_amominu + selectors.is_amomaxu;
+        builder.send_alu(
+            is_signed.clone() * Opcode::SLT.as_field::<AB::F>()
+                + is_unsigned.clone() * Opcode::SLTU.as_field::<AB::F>(),
+            Word::extend_var::<AB>(memory_columns.amo_lt),
+            prev_mem_val,
+            c_val,
+            local.shard,
+            local.channel,
+            is_signed + is_unsigned,
+        );
+
+        // Select the smaller or the larger of the previous value and op_c.
+        let lt = memory_columns.amo_lt;
+        let min_value = Word(std::array::from_fn(|i| {
+            prev_mem_val[i] * lt + c_val[i] * (one.clone() - lt)
+        }));
+        let max_value = Word(std::array::from_fn(|i| {
+            c_val[i] * lt + prev_mem_val[i] * (one.clone() - lt)
+        }));
+        builder
+            .when(selectors.is_amomin + selectors.is_amominu)
+            .assert_word_eq(mem_val.map(|x| x.into()), min_value);
+        builder
+            .when(selectors.is_amomax + selectors.is_amomaxu)
+            .assert_word_eq(mem_val.map(|x| x.into()), max_value);
+    }
+
     /// This function is used to evaluate the unsigned memory value for the load memory instructions.
     pub(crate) fn eval_unsigned_mem_value<AB: SP1AirBuilder>(
         &self,
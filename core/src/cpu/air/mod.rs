@@ -13,11 +13,12 @@ use p3_field::AbstractField;
 use p3_matrix::Matrix;
 
 use crate::air::BaseAirBuilder;
-use crate::air::PublicValues;
 use crate::air::SP1AirBuilder;
 use crate::air::Word;
 use crate::air::POSEIDON_NUM_WORDS;
 use crate::air::PV_DIGEST_NUM_WORDS;
+use crate::air::USER_PV_NUM_WORDS;
+use crate::air::{PublicValues, PV_VERSION};
 use crate::bytes::ByteOpcode;
 use crate::cpu::columns::OpcodeSelectorCols;
 use crate::cpu::columns::{CpuCols, NUM_CPU_COLS};
@@ -65,6 +66,7 @@ where
         self.eval_memory_address_and_access::<AB>(builder, local, is_memory_instruction.clone());
         self.eval_memory_load::<AB>(builder, local);
         self.eval_memory_store::<AB>(builder, local);
+        self.eval_memory_atomic::<AB>(builder, local);
 
         // Channel constraints.
         eval_channel_selectors(
@@ -99,12 +101,13 @@ where
         // ECALL instruction.
         self.eval_ecall(builder, local);
 
-        // COMMIT/COMMIT_DEFERRED_PROOFS ecall instruction.
+        // COMMIT/COMMIT_DEFERRED_PROOFS/COMMIT_USER_VALUE ecall instruction.
         self.eval_commit(
             builder,
             local,
             public_values.committed_value_digest.clone(),
             public_values.deferred_proofs_digest.clone(),
+            public_values.user_values.clone(),
         );
 
         // HALT ecall and UNIMPL instruction.
@@ -324,6 +327,12 @@ impl CpuChip {
         next: &CpuCols<AB::Var>,
         public_values: &PublicValues<Word<AB::Expr>, AB::Expr>,
     ) {
+        // Verify the version of the public values layout.
+        builder.when_first_row().assert_eq(
+            public_values.version.clone(),
+            AB::Expr::from_canonical_u32(PV_VERSION),
+        );
+
         // Verify the public value's shard.
         builder
             .when(local.is_real)
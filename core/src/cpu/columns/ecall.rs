@@ -14,6 +14,9 @@ pub struct EcallCols<T> {
     /// Whether the current ecall is HINT_LEN.
     pub is_hint_len: IsZeroOperation<T>,
 
+    /// Whether the current ecall is CYCLE_COUNT.
+    pub is_cycle_count: IsZeroOperation<T>,
+
     /// Whether the current ecall is HALT.
     pub is_halt: IsZeroOperation<T>,
 
@@ -23,7 +26,11 @@ pub struct EcallCols<T> {
     /// Whether the current ecall is a COMMIT_DEFERRED_PROOFS.
     pub is_commit_deferred_proofs: IsZeroOperation<T>,
 
+    /// Whether the current ecall is a COMMIT_USER_VALUE.
+    pub is_commit_user_value: IsZeroOperation<T>,
+
     /// Field to store the word index passed into the COMMIT ecall.  index_bitmap[word index] should
-    /// be set to 1 and everything else set to 0.
+    /// be set to 1 and everything else set to 0.  The user public values have as many words as the
+    /// digest, so that COMMIT_USER_VALUE uses the same bitmap.
     pub index_bitmap: [T; PV_DIGEST_NUM_WORDS],
 }
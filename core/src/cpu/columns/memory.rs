@@ -28,4 +28,8 @@ pub struct MemoryColumns<T> {
     // LE bit decomposition for the most significant byte of memory value.  This is used to determine
     // the sign for that value (used for LB and LH).
     pub most_sig_byte_decomp: [T; 8],
+
+    // Whether the previous value in memory is less than op_c, for AMOMIN, AMOMAX, AMOMINU and
+    // AMOMAXU.
+    pub amo_lt: T,
 }
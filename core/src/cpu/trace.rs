@@ -244,41 +244,38 @@ impl CpuChip {
         new_alu_events: &mut HashMap<Opcode, Vec<alu::AluEvent>>,
         new_blu_events: &mut Vec<ByteLookupEvent>,
     ) {
-        if !matches!(
-            event.instruction.opcode,
-            Opcode::LB
-                | Opcode::LH
-                | Opcode::LW
-                | Opcode::LBU
-                | Opcode::LHU
-                | Opcode::SB
-                | Opcode::SH
-                | Opcode::SW
-        ) {
+        if !event.instruction.is_memory_instruction() {
             return;
         }
 
-        // Populate addr_word and addr_aligned columns.
+        // Populate addr_word and addr_aligned columns. The address of an atomic instruction is b.
+        let is_atomic = event.instruction.is_atomic_instruction();
         let memory_columns = cols.opcode_specific_columns.memory_mut();
-        let memory_addr = event.b.wrapping_add(event.c);
+        let memory_addr = if is_atomic {
+            event.b
+        } else {
+            event.b.wrapping_add(event.c)
+        };
         memory_columns.addr_word = memory_addr.into();
         memory_columns.addr_aligned =
             F::from_canonical_u32(memory_addr - memory_addr % WORD_SIZE as u32);
 
         // Add event to ALU check to check that addr == b + c
-        let add_event = AluEvent {
-            shard: event.shard,
-            channel: event.channel,
-            clk: event.clk,
-            opcode: Opcode::ADD,
-            a: memory_addr,
-            b: event.b,
-            c: event.c,
-        };
-        new_alu_events
-            .entry(Opcode::ADD)
-            .and_modify(|op_new_events| op_new_events.push(add_event))
-            .or_insert(vec![add_event]);
+        if !is_atomic {
+            let add_event = AluEvent {
+                shard: event.shard,
+                channel: event.channel,
+                clk: event.clk,
+                opcode: Opcode::ADD,
+                a: memory_addr,
+                b: event.b,
+                c: event.c,
+            };
+            new_alu_events
+                .entry(Opcode::ADD)
+                .and_modify(|op_new_events| op_new_events.push(add_event))
+                .or_insert(vec![add_event]);
+        }
 
         // Populate memory offsets.
         let addr_offset = (memory_addr % WORD_SIZE as u32) as u8;
@@ -349,6 +346,59 @@ impl CpuChip {
             }
         }
 
+        // For the atomic instructions, add the event to the ALU check of the new value in memory,
+        // or of the comparison of the previous value in memory with c.
+        if is_atomic {
+            let prev_mem_value = match event.memory_record {
+                Some(MemoryRecordEnum::Write(record)) => record.prev_value,
+                _ => unreachable!(),
+            };
+            let alu_event = match event.instruction.opcode {
+                Opcode::AMOADD | Opcode::AMOXOR | Opcode::AMOAND | Opcode::AMOOR => {
+                    Some(AluEvent {
+                        shard: event.shard,
+                        channel: event.channel,
+                        clk: event.clk,
+                        opcode: match event.instruction.opcode {
+                            Opcode::AMOADD => Opcode::ADD,
+                            Opcode::AMOXOR => Opcode::XOR,
+                            Opcode::AMOAND => Opcode::AND,
+                            _ => Opcode::OR,
+                        },
+                        a: mem_value,
+                        b: prev_mem_value,
+                        c: event.c,
+                    })
+                }
+                Opcode::AMOMIN | Opcode::AMOMAX | Opcode::AMOMINU | Opcode::AMOMAXU => {
+                    let is_signed =
+                        matches!(event.instruction.opcode, Opcode::AMOMIN | Opcode::AMOMAX);
+                    let lt = if is_signed {
+                        (prev_mem_value as i32) < (event.c as i32)
+                    } else {
+                        prev_mem_value < event.c
+                    };
+                    memory_columns.amo_lt = F::from_bool(lt);
+                    Some(AluEvent {
+                        shard: event.shard,
+                        channel: event.channel,
+                        clk: event.clk,
+                        opcode: if is_signed { Opcode::SLT } else { Opcode::SLTU },
+                        a: lt as u32,
+                        b: prev_mem_value,
+                        c: event.c,
+                    })
+                }
+                _ => None,
+            };
+            if let Some(alu_event) = alu_event {
+                new_alu_events
+                    .entry(alu_event.opcode)
+                    .and_modify(|op_new_events| op_new_events.push(alu_event))
+                    .or_insert(vec![alu_event]);
+            }
+        }
+
         // Add event to byte lookup for byte range checking each byte in the memory addr
         let addr_bytes = memory_addr.to_le_bytes();
         for byte_pair in addr_bytes.chunks_exact(2) {
@@ -577,6 +627,11 @@ impl CpuChip {
                 syscall_id - F::from_canonical_u32(SyscallCode::HINT_LEN.syscall_id()),
             );
 
+            // Populate `is_cycle_count`.
+            ecall_cols.is_cycle_count.populate_from_field_element(
+                syscall_id - F::from_canonical_u32(SyscallCode::CYCLE_COUNT.syscall_id()),
+            );
+
             // Populate `is_halt`.
             ecall_cols.is_halt.populate_from_field_element(
                 syscall_id - F::from_canonical_u32(SyscallCode::HALT.syscall_id()),
@@ -595,10 +650,17 @@ impl CpuChip {
                         - F::from_canonical_u32(SyscallCode::COMMIT_DEFERRED_PROOFS.syscall_id()),
                 );
 
-            // If the syscall is `COMMIT` or `COMMIT_DEFERRED_PROOFS`, set the index bitmap and digest word.
+            // Populate `is_commit_user_value`.
+            ecall_cols.is_commit_user_value.populate_from_field_element(
+                syscall_id - F::from_canonical_u32(SyscallCode::COMMIT_USER_VALUE.syscall_id()),
+            );
+
+            // If the syscall is `COMMIT`, `COMMIT_DEFERRED_PROOFS` or `COMMIT_USER_VALUE`, set the
+            // index bitmap and digest word.
             if syscall_id == F::from_canonical_u32(SyscallCode::COMMIT.syscall_id())
                 || syscall_id
                     == F::from_canonical_u32(SyscallCode::COMMIT_DEFERRED_PROOFS.syscall_id())
+                || syscall_id == F::from_canonical_u32(SyscallCode::COMMIT_USER_VALUE.syscall_id())
             {
                 let digest_idx = cols.op_b_access.value().to_u32() as usize;
                 ecall_cols.index_bitmap[digest_idx] = F::one();
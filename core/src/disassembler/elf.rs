@@ -1,10 +1,15 @@
 use std::cmp::min;
 use std::collections::BTreeMap;
 
-use elf::abi::{EM_RISCV, ET_EXEC, PF_X, PT_LOAD};
+use elf::abi::{
+    EM_RISCV, ET_EXEC, PF_X, PT_DYNAMIC, PT_INTERP, PT_LOAD, SHF_ALLOC, SHT_REL, SHT_RELA,
+};
 use elf::endian::LittleEndian;
 use elf::file::Class;
 use elf::ElfBytes;
+use thiserror::Error;
+
+use crate::runtime::{HEAP_END, STACK_TOP};
 
 /// The maximum size of the memory in bytes.
 pub const MAXIMUM_MEMORY_SIZE: u32 = u32::MAX;
@@ -12,6 +17,82 @@ pub const MAXIMUM_MEMORY_SIZE: u32 = u32::MAX;
 /// The size of a word in bytes.
 pub const WORD_SIZE: usize = 4;
 
+/// The ELF header flag set by toolchains that emit compressed (RVC) instructions.
+const EF_RISCV_RVC: u32 = 0x1;
+
+/// The ELF header flags of the floating point ABI, which are set by toolchains that pass floating
+/// point values in the registers of the F and D extensions.
+const EF_RISCV_FLOAT_ABI: u32 = 0x6;
+
+/// The ELF header flag of the RV32E ABI, which only uses 16 registers.
+const EF_RISCV_RVE: u32 = 0x8;
+
+/// The maximum number of program headers of an ELF.
+const MAX_SEGMENTS: usize = 256;
+
+/// An error of an ELF which the zkVM cannot run, with the problem and how to fix it.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ElfError {
+    #[error("the file is not a valid ELF: {0}")]
+    Malformed(String),
+    #[error("64-bit (rv64) ELFs are not supported, build the program for the riscv32im-succinct-zkvm-elf target")]
+    Elf64,
+    #[error("the ELF is for machine {0}, not RISC-V, build the program for the riscv32im-succinct-zkvm-elf target")]
+    UnsupportedMachine(u16),
+    #[error("the ELF has type {0} instead of an executable, link the program statically into an executable")]
+    NotExecutable(u16),
+    #[error("compressed (rvc) instructions are not supported, build the program without the c extension")]
+    CompressedInstructions,
+    #[error("the ELF uses the floating point ABI of the F and D extensions, which are not supported, build the program for the riscv32im-succinct-zkvm-elf target")]
+    FloatAbi,
+    #[error("the ELF uses the RV32E ABI, which is not supported, build the program for the riscv32im-succinct-zkvm-elf target")]
+    EmbeddedAbi,
+    #[error("the ELF is dynamically linked, link the program statically")]
+    DynamicallyLinked,
+    #[error("the ELF has relocations to apply when it is loaded in section {0}, which are not supported, link the program statically without PIE")]
+    UnsupportedRelocations(String),
+    #[error("the entrypoint 0x{0:08x} is not aligned to a word")]
+    UnalignedEntrypoint(u32),
+    #[error("the entrypoint 0x{0:08x} is not in an executable segment")]
+    EntrypointOutsideText(u32),
+    #[error("the ELF has {0} program headers, more than the maximum of {MAX_SEGMENTS}")]
+    TooManySegments(usize),
+    #[error("the {field} of segment {index} does not fit in 32 bits")]
+    SegmentTooLarge { index: usize, field: &'static str },
+    #[error("segment {index} at 0x{vaddr:08x} is not aligned to a word")]
+    UnalignedSegment { index: usize, vaddr: u32 },
+    #[error("segment {index} reads past the end of the file")]
+    SegmentOutOfFile { index: usize },
+    #[error("segment {index} has {file_size} bytes in the file, more than its {mem_size} bytes in memory")]
+    SegmentFileSizeExceedsMemSize {
+        index: usize,
+        file_size: u32,
+        mem_size: u32,
+    },
+    #[error("segment {index} at 0x{vaddr:08x}..0x{end:08x} overlaps the top of the stack at 0x{STACK_TOP:08x}, link the program with the text at 0x00200800")]
+    SegmentOverlapsStack { index: usize, vaddr: u32, end: u32 },
+    #[error("segment {index} at 0x{vaddr:08x}..0x{end:08x} overlaps the memory reserved for the system from 0x{HEAP_END:08x}")]
+    SegmentOverlapsSystem { index: usize, vaddr: u32, end: u32 },
+    #[error("segments {first} and {second} overlap at 0x{addr:08x}")]
+    OverlappingSegments {
+        first: usize,
+        second: usize,
+        addr: u32,
+    },
+    #[error("the executable segments are not contiguous, segment {index} starts at 0x{vaddr:08x} instead of 0x{expected:08x}")]
+    NonContiguousText {
+        index: usize,
+        vaddr: u32,
+        expected: u32,
+    },
+    #[error("the ELF has no executable segment")]
+    NoText,
+    #[error("the memory image ends at 0x{0:08x}, below the top of the stack at 0x{STACK_TOP:08x}, so the heap would overlap the stack")]
+    HeapOverlapsStack(u32),
+    #[error("unsupported instruction 0x{word:08x} at pc 0x{pc:08x}, the program may use an extension other than RV32IMA")]
+    UnsupportedInstruction { pc: u32, word: u32 },
+}
+
 /// A RV32IM ELF file.
 #[derive(Debug, Clone)]
 pub struct Elf {
@@ -46,89 +127,143 @@ impl Elf {
 
     /// Parse the ELF file into a vector of 32-bit encoded instructions and the first memory address.
     ///
+    /// The ELF is validated before it is loaded: it must be a statically linked RV32IMA executable
+    /// whose segments are between the top of the stack and the memory reserved for the system.
+    ///
     /// Reference: https://en.wikipedia.org/wiki/Executable_and_Linkable_Format
-    pub fn decode(input: &[u8]) -> Self {
+    pub fn decode(input: &[u8]) -> Result<Self, ElfError> {
         let mut image: BTreeMap<u32, u32> = BTreeMap::new();
         // Parse the ELF file assuming that it is little-endian..
-        let elf = ElfBytes::<LittleEndian>::minimal_parse(input).expect("failed to parse elf");
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(input)
+            .map_err(|err| ElfError::Malformed(err.to_string()))?;
 
         // Some sanity checks to make sure that the ELF file is valid.
-        if elf.ehdr.class != Class::ELF32 {
-            panic!("must be a 32-bit elf");
+        if elf.ehdr.class == Class::ELF64 {
+            return Err(ElfError::Elf64);
         } else if elf.ehdr.e_machine != EM_RISCV {
-            panic!("must be a riscv machine");
+            return Err(ElfError::UnsupportedMachine(elf.ehdr.e_machine));
         } else if elf.ehdr.e_type != ET_EXEC {
-            panic!("must be executable");
+            return Err(ElfError::NotExecutable(elf.ehdr.e_type));
+        } else if elf.ehdr.e_flags & EF_RISCV_RVC != 0 {
+            // The CPU chip constrains `next_pc = pc + 4` for sequential instructions, so programs
+            // containing 16-bit compressed instructions cannot be proven.
+            return Err(ElfError::CompressedInstructions);
+        } else if elf.ehdr.e_flags & EF_RISCV_FLOAT_ABI != 0 {
+            return Err(ElfError::FloatAbi);
+        } else if elf.ehdr.e_flags & EF_RISCV_RVE != 0 {
+            return Err(ElfError::EmbeddedAbi);
         }
 
-        // Get the entrypoint of the ELF file as an u32.
-        let entry: u32 = elf
-            .ehdr
-            .e_entry
-            .try_into()
-            .expect("e_entry was larger than 32 bits");
-
-        // Make sure the entrypoint is valid.
-        if entry == MAXIMUM_MEMORY_SIZE || entry % WORD_SIZE as u32 != 0 {
-            panic!("invalid entrypoint");
+        // Get the entrypoint of the ELF file as an u32, and make sure it is valid.
+        let entry = u32::try_from(elf.ehdr.e_entry).map_err(|_| ElfError::Elf64)?;
+        if entry % WORD_SIZE as u32 != 0 {
+            return Err(ElfError::UnalignedEntrypoint(entry));
         }
 
         // Get the segments of the ELF file.
-        let segments = elf.segments().expect("failed to get segments");
-        if segments.len() > 256 {
-            panic!("too many program headers");
+        let segments = elf
+            .segments()
+            .ok_or_else(|| ElfError::Malformed("the ELF has no program headers".to_string()))?;
+        if segments.len() > MAX_SEGMENTS {
+            return Err(ElfError::TooManySegments(segments.len()));
+        }
+        if segments
+            .iter()
+            .any(|segment| segment.p_type == PT_DYNAMIC || segment.p_type == PT_INTERP)
+        {
+            return Err(ElfError::DynamicallyLinked);
+        }
+
+        // Relocations in allocated sections are applied by a dynamic loader, which the zkVM
+        // does not have.
+        if let Ok((Some(sections), Some(strtab))) = elf.section_headers_with_strtab() {
+            if let Some(section) = sections.iter().find(|section| {
+                (section.sh_type == SHT_REL || section.sh_type == SHT_RELA)
+                    && section.sh_flags & SHF_ALLOC as u64 != 0
+                    && section.sh_size > 0
+            }) {
+                let name = strtab.get(section.sh_name as usize).unwrap_or("<unnamed>");
+                return Err(ElfError::UnsupportedRelocations(name.to_string()));
+            }
         }
 
         let mut instructions: Vec<u32> = Vec::new();
         let mut base_address = u32::MAX;
+        let mut text_end = None;
+        let mut ranges: Vec<(usize, u32, u32)> = Vec::new();
 
         // Only read segments that are executable instructions that are also PT_LOAD.
-        for segment in segments.iter().filter(|x| x.p_type == PT_LOAD) {
-            // Get the file size of the segment as an u32.
-            let file_size: u32 = segment
-                .p_filesz
-                .try_into()
-                .expect("filesize was larger than 32 bits");
-            if file_size == MAXIMUM_MEMORY_SIZE {
-                panic!("invalid segment file_size");
+        for (index, segment) in segments.iter().enumerate() {
+            if segment.p_type != PT_LOAD {
+                continue;
             }
+            let too_large = |field| ElfError::SegmentTooLarge { index, field };
 
-            // Get the memory size of the segment as an u32.
-            let mem_size: u32 = segment
-                .p_memsz
-                .try_into()
-                .expect("mem_size was larger than 32 bits");
-            if mem_size == MAXIMUM_MEMORY_SIZE {
-                panic!("Invalid segment mem_size");
-            }
-
-            // Get the virtual address of the segment as an u32.
-            let vaddr: u32 = segment
-                .p_vaddr
-                .try_into()
-                .expect("vaddr was larger than 32 bits");
+            // Get the sizes, the virtual address and the offset of the segment as u32s.
+            let file_size = u32::try_from(segment.p_filesz).map_err(|_| too_large("file size"))?;
+            let mem_size = u32::try_from(segment.p_memsz).map_err(|_| too_large("memory size"))?;
+            let vaddr = u32::try_from(segment.p_vaddr).map_err(|_| too_large("address"))?;
+            let offset = u32::try_from(segment.p_offset).map_err(|_| too_large("offset"))?;
             if vaddr % WORD_SIZE as u32 != 0 {
-                panic!("vaddr {vaddr:08x} is unaligned");
+                return Err(ElfError::UnalignedSegment { index, vaddr });
+            }
+            if file_size > mem_size {
+                return Err(ElfError::SegmentFileSizeExceedsMemSize {
+                    index,
+                    file_size,
+                    mem_size,
+                });
+            }
+            if offset as usize + file_size as usize > input.len() {
+                return Err(ElfError::SegmentOutOfFile { index });
+            }
+            if mem_size == 0 {
+                continue;
             }
 
-            // If the virtual address is less than the first memory address, then update the first
-            // memory address.
-            if (segment.p_flags & PF_X) != 0 && base_address > vaddr {
-                base_address = vaddr;
+            // The segment must fit between the top of the stack and the memory of the system.
+            let end = vaddr
+                .checked_add(mem_size)
+                .filter(|&end| end <= HEAP_END)
+                .ok_or(ElfError::SegmentOverlapsSystem {
+                    index,
+                    vaddr,
+                    end: vaddr.saturating_add(mem_size),
+                })?;
+            if vaddr < STACK_TOP && end > STACK_TOP - WORD_SIZE as u32 {
+                return Err(ElfError::SegmentOverlapsStack { index, vaddr, end });
+            }
+            if let Some(&(first, _, _)) = ranges
+                .iter()
+                .find(|&&(_, start, stop)| vaddr < stop && start < end)
+            {
+                return Err(ElfError::OverlappingSegments {
+                    first,
+                    second: index,
+                    addr: vaddr,
+                });
             }
+            ranges.push((index, vaddr, end));
 
-            // Get the offset to the segment.
-            let offset: u32 = segment
-                .p_offset
-                .try_into()
-                .expect("offset was larger than 32 bits");
+            // The instructions of the executable segments are fetched from a single vector, so
+            // they must follow each other in memory.
+            if (segment.p_flags & PF_X) != 0 {
+                if let Some(expected) = text_end {
+                    if vaddr != expected {
+                        return Err(ElfError::NonContiguousText {
+                            index,
+                            vaddr,
+                            expected,
+                        });
+                    }
+                }
+                base_address = base_address.min(vaddr);
+                text_end = Some(end);
+            }
 
             // Read the segment and decode each word as an instruction.
             for i in (0..mem_size).step_by(WORD_SIZE) {
-                let addr = vaddr.checked_add(i).expect("invalid segment vaddr");
-                if addr == MAXIMUM_MEMORY_SIZE {
-                    panic!("address [0x{addr:08x}] exceeds maximum address for guest programs [0x{MAXIMUM_MEMORY_SIZE:08x}]");
-                }
+                let addr = vaddr + i;
 
                 // If we are reading past the end of the file, then break.
                 if i >= file_size {
@@ -141,8 +276,7 @@ impl Elf {
                 let len = min(file_size - i, WORD_SIZE as u32);
                 for j in 0..len {
                     let offset = (offset + i + j) as usize;
-                    let byte = input.get(offset).expect("invalid segment offset");
-                    word |= (*byte as u32) << (j * 8);
+                    word |= (input[offset] as u32) << (j * 8);
                 }
                 image.insert(addr, word);
                 if (segment.p_flags & PF_X) != 0 {
@@ -151,6 +285,112 @@ impl Elf {
             }
         }
 
-        Elf::new(instructions, entry, base_address, image)
+        let Some(text_end) = text_end else {
+            return Err(ElfError::NoText);
+        };
+        if entry < base_address || entry >= text_end {
+            return Err(ElfError::EntrypointOutsideText(entry));
+        }
+
+        // The heap starts after the memory image and grows up, so it must start above the stack,
+        // which grows down from its top.
+        let image_end = ranges.iter().map(|&(_, _, end)| end).max().unwrap_or(0);
+        if image_end < STACK_TOP {
+            return Err(ElfError::HeapOverlapsStack(image_end));
+        }
+
+        Ok(Elf::new(instructions, entry, base_address, image))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disassembler::transpile;
+    use crate::runtime::Program;
+    use crate::utils::tests::FIBONACCI_ELF;
+
+    fn read_u32(elf: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(elf[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn write_u32(elf: &mut [u8], offset: usize, value: u32) {
+        elf[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    /// Returns the decoding of the ELF of the fibonacci program with `f` applied to its bytes.
+    fn decode_patched(f: impl FnOnce(&mut [u8])) -> Result<Elf, ElfError> {
+        let mut elf = FIBONACCI_ELF.to_vec();
+        f(&mut elf);
+        Elf::decode(&elf)
+    }
+
+    #[test]
+    fn test_decode_elf64() {
+        // The header of an rv64 executable without program or section headers.
+        let mut elf = vec![0u8; 64];
+        elf[..8].copy_from_slice(&[0x7f, b'E', b'L', b'F', 2, 1, 1, 0]);
+        elf[0x10..0x14].copy_from_slice(&[2, 0, 0xf3, 0]);
+        write_u32(&mut elf, 0x14, 1);
+        elf[0x34..0x3a].copy_from_slice(&[64, 0, 56, 0, 0, 0]);
+        elf[0x3a] = 64;
+        assert_eq!(Elf::decode(&elf).unwrap_err(), ElfError::Elf64);
+        assert_eq!(Program::decode(&elf).unwrap_err(), ElfError::Elf64);
+    }
+
+    #[test]
+    fn test_transpile_compressed() {
+        // `c.li a0, 1` followed by `c.nop`, and `addi a0, a0, 1`.
+        assert_eq!(
+            transpile(&[0x0001_4505], 0).unwrap_err(),
+            ElfError::CompressedInstructions
+        );
+        assert!(transpile(&[0x0015_0513], 0).is_ok());
+    }
+
+    #[test]
+    fn test_decode_elf_errors() {
+        let decoded = Elf::decode(FIBONACCI_ELF).unwrap();
+        assert!(decoded.pc_start >= decoded.pc_base);
+        let entry = decoded.pc_start;
+
+        assert!(matches!(
+            Elf::decode(b"not an elf"),
+            Err(ElfError::Malformed(_))
+        ));
+        assert_eq!(
+            decode_patched(|elf| elf[0x12] = 62).unwrap_err(),
+            ElfError::UnsupportedMachine(62)
+        );
+        assert_eq!(
+            decode_patched(|elf| elf[0x10] = 3).unwrap_err(),
+            ElfError::NotExecutable(3)
+        );
+        assert_eq!(
+            decode_patched(|elf| write_u32(elf, 0x24, EF_RISCV_RVC)).unwrap_err(),
+            ElfError::CompressedInstructions
+        );
+        assert_eq!(
+            decode_patched(|elf| write_u32(elf, 0x24, 0x4)).unwrap_err(),
+            ElfError::FloatAbi
+        );
+        assert_eq!(
+            decode_patched(|elf| write_u32(elf, 0x18, entry + 2)).unwrap_err(),
+            ElfError::UnalignedEntrypoint(entry + 2)
+        );
+
+        // Move the text of the program below the top of the stack.
+        let result = decode_patched(|elf| {
+            let phoff = read_u32(elf, 0x1c) as usize;
+            let phnum = u16::from_le_bytes([elf[0x2c], elf[0x2d]]) as usize;
+            let text = (0..phnum)
+                .map(|i| phoff + 32 * i)
+                .find(|&header| {
+                    read_u32(elf, header) == PT_LOAD && read_u32(elf, header + 24) & PF_X != 0
+                })
+                .unwrap();
+            write_u32(elf, text + 8, STACK_TOP - 0x400);
+        });
+        assert!(matches!(result, Err(ElfError::SegmentOverlapsStack { .. })));
     }
 }
@@ -3,6 +3,7 @@ use rrs_lib::instruction_formats::{
 };
 use rrs_lib::{process_instruction, InstructionProcessor};
 
+use super::ElfError;
 use crate::runtime::{Instruction, Opcode, Register};
 
 impl Instruction {
@@ -405,13 +406,64 @@ impl InstructionProcessor for InstructionTranspiler {
     }
 }
 
-/// Transpile the instructions from the 32-bit encoded instructions.
-pub fn transpile(instructions_u32: &[u32]) -> Vec<Instruction> {
+/// The major opcode of the instructions of the A extension.
+const OPCODE_AMO: u32 = 0b0101111;
+
+/// Transpile an instruction of the A extension, which `rrs_lib` does not decode.
+///
+/// The zkVM has a single hart and no interrupts, so the ordering bits `aq` and `rl` have no effect
+/// and a reservation is never invalidated by another hart: LR.W is a LW, and SC.W always succeeds.
+/// The other instructions read op_b as the address and op_c as the register of the operand.
+fn transpile_atomic(instruction_u32: u32) -> Option<Instruction> {
+    let rd = (instruction_u32 >> 7) & 0x1f;
+    let funct3 = (instruction_u32 >> 12) & 0x7;
+    let rs1 = (instruction_u32 >> 15) & 0x1f;
+    let rs2 = (instruction_u32 >> 20) & 0x1f;
+    let funct5 = instruction_u32 >> 27;
+
+    // Only the word width exists on RV32.
+    if funct3 != 0b010 {
+        return None;
+    }
+    if funct5 == 0b00010 {
+        return (rs2 == 0).then_some(Instruction::new(Opcode::LW, rd, rs1, 0, false, true));
+    }
+    let opcode = match funct5 {
+        0b00011 => Opcode::SC,
+        0b00001 => Opcode::AMOSWAP,
+        0b00000 => Opcode::AMOADD,
+        0b00100 => Opcode::AMOXOR,
+        0b01100 => Opcode::AMOAND,
+        0b01000 => Opcode::AMOOR,
+        0b10000 => Opcode::AMOMIN,
+        0b10100 => Opcode::AMOMAX,
+        0b11000 => Opcode::AMOMINU,
+        0b11100 => Opcode::AMOMAXU,
+        _ => return None,
+    };
+    Some(Instruction::new(opcode, rd, rs1, rs2, false, false))
+}
+
+/// Transpile the instructions from the 32-bit encoded instructions, which start at `pc_base`.
+pub fn transpile(instructions_u32: &[u32], pc_base: u32) -> Result<Vec<Instruction>, ElfError> {
     let mut instructions = Vec::new();
     let mut transpiler = InstructionTranspiler;
-    for instruction_u32 in instructions_u32 {
-        let instruction = process_instruction(&mut transpiler, *instruction_u32).unwrap();
+    for (i, instruction_u32) in instructions_u32.iter().enumerate() {
+        // The two lowest bits of 32-bit instructions are set, so a word without them starts with
+        // a 16-bit compressed instruction, even if the ELF flags do not declare the C extension.
+        if *instruction_u32 != 0 && *instruction_u32 & 0b11 != 0b11 {
+            return Err(ElfError::CompressedInstructions);
+        }
+        let instruction = if *instruction_u32 & 0x7f == OPCODE_AMO {
+            transpile_atomic(*instruction_u32)
+        } else {
+            process_instruction(&mut transpiler, *instruction_u32)
+        }
+        .ok_or(ElfError::UnsupportedInstruction {
+            pc: pc_base + 4 * i as u32,
+            word: *instruction_u32,
+        })?;
         instructions.push(instruction);
     }
-    instructions
+    Ok(instructions)
 }
@@ -20,20 +20,28 @@ impl Program {
     }
 
     /// Disassemble a RV32IM ELF to a program that be executed by the VM.
+    ///
+    /// Panics with the problem of the ELF if it is not valid, see [Program::decode].
     pub fn from(input: &[u8]) -> Self {
+        Self::decode(input).unwrap_or_else(|err| panic!("invalid ELF: {}", err))
+    }
+
+    /// Disassemble a RV32IM ELF to a program that be executed by the VM, or return the problem
+    /// which prevents the ELF from running.
+    pub fn decode(input: &[u8]) -> Result<Self, ElfError> {
         // Decode the bytes as an ELF.
-        let elf = Elf::decode(input);
+        let elf = Elf::decode(input)?;
 
         // Transpile the RV32IM instructions.
-        let instructions = transpile(&elf.instructions);
+        let instructions = transpile(&elf.instructions, elf.pc_base)?;
 
         // Return the program.
-        Program {
+        Ok(Program {
             instructions,
             pc_start: elf.pc_start,
             pc_base: elf.pc_base,
             memory_image: elf.memory_image,
-        }
+        })
     }
 
     /// Disassemble a RV32IM ELF to a program that be executed by the VM from a file path.
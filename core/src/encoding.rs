@@ -0,0 +1,127 @@
+//! The versioned binary encoding of proofs and verifying keys.
+//!
+//! An encoded value starts with a header of four magic bytes, which tell proofs from verifying
+//! keys, the version of the encoding and the version of the layout of the public values, as
+//! little-endian `u32`s. The value follows, encoded with bincode with fixed options, so that the
+//! encoding does not depend on the defaults of the bincode crate. Values with another header are
+//! rejected, instead of being decoded into garbage or failing with an obscure error.
+//!
+//! Proofs and verifying keys also record the [SP1_CIRCUIT_VERSION] of the prover which generated
+//! them, which the verifiers check before the proof itself.
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use bincode::Options;
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+use crate::air::PV_VERSION;
+
+/// The magic bytes of proofs.
+pub const PROOF_MAGIC: [u8; 4] = *b"SP1P";
+
+/// The magic bytes of verifying keys.
+pub const VKEY_MAGIC: [u8; 4] = *b"SP1K";
+
+/// The magic bytes of the verifying key of the compress program, which verifiers of compressed
+/// proofs outside of the prover check the proofs against.
+pub const COMPRESS_VKEY_MAGIC: [u8; 4] = *b"SP1C";
+
+/// The version of the encoding, which is bumped whenever the encoding of a proof or a verifying
+/// key changes.
+pub const ENCODING_VERSION: u32 = 2;
+
+/// The version of the circuits of SP1, which is bumped whenever the constraints of the core,
+/// recursion or wrap machines change.
+///
+/// A proof only verifies against a verifying key and a verifier of the same circuits, so a
+/// mismatch is reported with both versions instead of as a failed constraint.
+pub const SP1_CIRCUIT_VERSION: &str = "v0.2.0";
+
+/// The length of the header of an encoded value.
+pub const HEADER_LEN: usize = 12;
+
+#[derive(Error, Debug)]
+pub enum EncodingError {
+    #[error("io error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("serialization error: {0}")]
+    SerializationError(#[from] bincode::Error),
+    #[error("invalid magic bytes {found:?}, expected {expected:?}: the file is not of the expected kind, or was saved by a version of SP1 without versioned encodings")]
+    InvalidMagic { found: [u8; 4], expected: [u8; 4] },
+    #[error("unsupported encoding version {found}, expected {expected}")]
+    UnsupportedVersion { found: u32, expected: u32 },
+    #[error("unsupported public values version {found}, expected {expected}")]
+    UnsupportedPublicValuesVersion { found: u32, expected: u32 },
+}
+
+/// The options of the bincode encoding of values.
+fn options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_little_endian()
+        .reject_trailing_bytes()
+}
+
+/// Encodes a value with a header of `magic`.
+pub fn encode<T: Serialize>(magic: [u8; 4], value: &T) -> Result<Vec<u8>, EncodingError> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN);
+    bytes.extend_from_slice(&magic);
+    bytes.extend_from_slice(&ENCODING_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&PV_VERSION.to_le_bytes());
+    options().serialize_into(&mut bytes, value)?;
+    Ok(bytes)
+}
+
+/// Decodes a value encoded by [encode] with a header of `magic`, checking the versions of the
+/// header.
+pub fn decode<T: DeserializeOwned>(magic: [u8; 4], bytes: &[u8]) -> Result<T, EncodingError> {
+    if bytes.len() < HEADER_LEN || bytes[0..4] != magic {
+        let mut found = [0u8; 4];
+        let len = bytes.len().min(4);
+        found[..len].copy_from_slice(&bytes[..len]);
+        return Err(EncodingError::InvalidMagic {
+            found,
+            expected: magic,
+        });
+    }
+    let version = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+    if version != ENCODING_VERSION {
+        return Err(EncodingError::UnsupportedVersion {
+            found: version,
+            expected: ENCODING_VERSION,
+        });
+    }
+    let pv_version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+    if pv_version != PV_VERSION {
+        return Err(EncodingError::UnsupportedPublicValuesVersion {
+            found: pv_version,
+            expected: PV_VERSION,
+        });
+    }
+    Ok(options().deserialize(&bytes[HEADER_LEN..])?)
+}
+
+/// Encodes a value with a header of `magic` to a file.
+pub fn save<T: Serialize>(
+    magic: [u8; 4],
+    value: &T,
+    path: impl AsRef<Path>,
+) -> Result<(), EncodingError> {
+    let bytes = encode(magic, value)?;
+    let mut file = File::create(path)?;
+    file.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Decodes a value with a header of `magic` from a file written by [save].
+pub fn load<T: DeserializeOwned>(
+    magic: [u8; 4],
+    path: impl AsRef<Path>,
+) -> Result<T, EncodingError> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    decode(magic, &bytes)
+}
@@ -1,10 +1,19 @@
 use crate::{
+    air::USER_PV_NUM_WORDS,
+    runtime::ExecutionError,
     stark::{ShardProof, StarkVerifyingKey},
     utils::{BabyBearPoseidon2, Buffer},
 };
 use k256::sha2::{Digest, Sha256};
 use num_bigint::BigUint;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp1_precompiles::abi::{abi_decode, abi_encode, SolidityAbi};
+pub use sp1_precompiles::codec::{self, Bincode, IoCodec};
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::io::{self, Read};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
 
 /// Standard input for the prover.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,12 +26,101 @@ pub struct SP1Stdin {
         ShardProof<BabyBearPoseidon2>,
         StarkVerifyingKey<BabyBearPoseidon2>,
     )>,
+    /// Input that is pulled on demand by the executor once `buffer` has been consumed. Streams are
+    /// not serialized, so they cannot be sent to a remote prover.
+    #[serde(skip)]
+    pub stream: Option<InputStream>,
+    /// The seed of the randomness the program draws with `sp1_zkvm::io::random_bytes`, which the
+    /// program commits to its public values.
+    #[serde(default)]
+    pub random_seed: [u8; 32],
+}
+
+/// A source of input chunks that the executor pulls from lazily, one chunk per read.
+///
+/// Clones share the underlying source, so each chunk is only ever yielded once. An execution
+/// claims the stream with [InputStream::claim] before reading it, so that executing again with a
+/// clone of the same `SP1Stdin` fails instead of running the program on the rest of the stream.
+#[derive(Clone)]
+pub struct InputStream(Arc<Mutex<StreamSource>>);
+
+/// The shared source of an [InputStream].
+struct StreamSource {
+    chunks: Box<dyn Iterator<Item = io::Result<Vec<u8>>> + Send>,
+    claimed: bool,
+}
+
+/// The error of an execution with an [InputStream] which a previous execution already read.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("the input stream was already read by a previous execution, call `SP1Stdin::buffer_stream` before the first execution to reuse the input")]
+pub struct InputStreamConsumed;
+
+impl InputStream {
+    /// Create a stream from an iterator of chunks.
+    pub fn new<I: Iterator<Item = Vec<u8>> + Send + 'static>(chunks: I) -> Self {
+        Self(Arc::new(Mutex::new(StreamSource {
+            chunks: Box::new(chunks.map(Ok)),
+            claimed: false,
+        })))
+    }
+
+    /// Claim the stream for the execution which reads it. Fails if the stream, or a clone of it,
+    /// was already claimed, since the chunks it yielded would be missing.
+    pub fn claim(&self) -> Result<Self, InputStreamConsumed> {
+        let mut source = self.0.lock().unwrap();
+        if source.claimed {
+            return Err(InputStreamConsumed);
+        }
+        source.claimed = true;
+        Ok(self.clone())
+    }
+
+    /// Create a stream which reads `reader` in chunks of `chunk_size` bytes. The final chunk is
+    /// always empty, so the program can detect the end of the stream. A read error ends the stream
+    /// and fails the execution which reads it.
+    pub fn from_reader<R: Read + Send + 'static>(mut reader: R, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        let mut finished = false;
+        let chunks = std::iter::from_fn(move || {
+            if finished {
+                return None;
+            }
+            let mut chunk = Vec::with_capacity(chunk_size);
+            let read = (&mut reader)
+                .take(chunk_size as u64)
+                .read_to_end(&mut chunk);
+            finished = read.is_err() || chunk.is_empty();
+            Some(read.map(|_| chunk))
+        });
+        Self(Arc::new(Mutex::new(StreamSource {
+            chunks: Box::new(chunks),
+            claimed: false,
+        })))
+    }
+}
+
+impl Iterator for InputStream {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<io::Result<Vec<u8>>> {
+        self.0.lock().unwrap().chunks.next()
+    }
+}
+
+impl Debug for InputStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("InputStream")
+    }
 }
 
 /// Public values for the prover.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SP1PublicValues {
     buffer: Buffer,
+    /// The leaves of the Merkle tree of public outputs, whose root the program committed. They are
+    /// not part of the committed bytes.
+    #[serde(default)]
+    leaves: Vec<Vec<u8>>,
 }
 
 impl SP1Stdin {
@@ -32,6 +130,8 @@ impl SP1Stdin {
             buffer: Vec::new(),
             ptr: 0,
             proofs: Vec::new(),
+            stream: None,
+            random_seed: [0; 32],
         }
     }
 
@@ -41,7 +141,40 @@ impl SP1Stdin {
             buffer: vec![data.to_vec()],
             ptr: 0,
             proofs: Vec::new(),
+            stream: None,
+            random_seed: [0; 32],
+        }
+    }
+
+    /// Create a `SP1Stdin` which streams `reader` to the program in chunks of `chunk_size` bytes,
+    /// without buffering the whole input in memory during execution.
+    ///
+    /// Each chunk is consumed by one `sp1_zkvm::io::read_vec` call in the program, and the end of
+    /// the input is signalled by an empty chunk. When proving, the stream is drained up front since
+    /// shards are re-executed from checkpoints.
+    ///
+    /// The stream is read once, so only the first execution or proof with this stdin, or a clone
+    /// of it, succeeds. Call [SP1Stdin::buffer_stream] first to run several of them.
+    pub fn from_reader<R: Read + Send + 'static>(reader: R, chunk_size: usize) -> Self {
+        let mut stdin = Self::new();
+        stdin.stream = Some(InputStream::from_reader(reader, chunk_size));
+        stdin
+    }
+
+    /// Drain any streamed input into the buffer, so that the stdin can be used by several
+    /// executions. Fails if an execution already read the stream, or if reading it fails.
+    pub fn buffer_stream(&mut self) -> Result<(), ExecutionError> {
+        if let Some(stream) = self.stream.as_ref() {
+            let stream = stream
+                .claim()
+                .map_err(ExecutionError::InputStreamConsumed)?;
+            for chunk in stream {
+                self.buffer
+                    .push(chunk.map_err(ExecutionError::InputStreamRead)?);
+            }
+            self.stream = None;
         }
+        Ok(())
     }
 
     /// Read a value from the buffer.
@@ -52,6 +185,13 @@ impl SP1Stdin {
         result
     }
 
+    /// Read a value encoded with `C` from the buffer.
+    pub fn read_with<C: IoCodec, T: DeserializeOwned>(&mut self) -> T {
+        let result = C::decode(&self.buffer[self.ptr]);
+        self.ptr += 1;
+        result
+    }
+
     /// Read a slice of bytes from the buffer.
     pub fn read_slice(&mut self, slice: &mut [u8]) {
         slice.copy_from_slice(&self.buffer[self.ptr]);
@@ -65,6 +205,12 @@ impl SP1Stdin {
         self.buffer.push(tmp);
     }
 
+    /// Write a value encoded with `C` to the buffer, which the program reads with
+    /// `sp1_zkvm::io::read_with::<C, _>`.
+    pub fn write_with<C: IoCodec, T: Serialize>(&mut self, data: &T) {
+        self.buffer.push(C::encode(data));
+    }
+
     /// Write a slice of bytes to the buffer.
     pub fn write_slice(&mut self, slice: &[u8]) {
         self.buffer.push(slice.to_vec());
@@ -81,6 +227,12 @@ impl SP1Stdin {
     ) {
         self.proofs.push((proof, vk));
     }
+
+    /// Set the seed of the randomness drawn by the program. Executions with the same seed draw the
+    /// same randomness, which defaults to the zero seed.
+    pub fn set_random_seed(&mut self, seed: [u8; 32]) {
+        self.random_seed = seed;
+    }
 }
 
 impl SP1PublicValues {
@@ -88,6 +240,7 @@ impl SP1PublicValues {
     pub const fn new() -> Self {
         Self {
             buffer: Buffer::new(),
+            leaves: Vec::new(),
         }
     }
 
@@ -99,9 +252,30 @@ impl SP1PublicValues {
     pub fn from(data: &[u8]) -> Self {
         Self {
             buffer: Buffer::from(data),
+            leaves: Vec::new(),
+        }
+    }
+
+    /// Create a `SP1PublicValues` from a slice of bytes and the leaves of the Merkle tree of
+    /// public outputs.
+    pub fn with_leaves(data: &[u8], leaves: Vec<Vec<u8>>) -> Self {
+        Self {
+            buffer: Buffer::from(data),
+            leaves,
         }
     }
 
+    /// The leaves that the program appended with `sp1_zkvm::io::commit_leaf`.
+    pub fn leaves(&self) -> &[Vec<u8>] {
+        &self.leaves
+    }
+
+    /// The Merkle tree of the public outputs, whose root is what the program committed with
+    /// `sp1_zkvm::io::commit_merkle_root`.
+    pub fn outputs_tree(&self) -> PublicOutputsTree {
+        PublicOutputsTree::new(&self.leaves)
+    }
+
     pub fn as_slice(&self) -> &[u8] {
         self.buffer.data.as_slice()
     }
@@ -115,6 +289,11 @@ impl SP1PublicValues {
         self.buffer.read()
     }
 
+    /// Read a value which the program committed with `sp1_zkvm::io::commit_with::<C, _>`.
+    pub fn read_with<C: IoCodec, T: DeserializeOwned>(&mut self) -> T {
+        self.buffer.read_with::<C, T>()
+    }
+
     /// Read a slice of bytes from the buffer.
     pub fn read_slice(&mut self, slice: &mut [u8]) {
         self.buffer.read_slice(slice);
@@ -125,11 +304,27 @@ impl SP1PublicValues {
         self.buffer.write(data);
     }
 
+    /// Write a value encoded with `C` to the buffer.
+    pub fn write_with<C: IoCodec, T: Serialize>(&mut self, data: &T) {
+        self.buffer.write_with::<C, T>(data);
+    }
+
     /// Write a slice of bytes to the buffer.
     pub fn write_slice(&mut self, slice: &[u8]) {
         self.buffer.write_slice(slice);
     }
 
+    /// Decode the public values as a value which the program committed with
+    /// `sp1_zkvm::io::commit_abi`, as `abi.decode(publicValues, (T))` does in Solidity.
+    pub fn abi_decode<T: SolidityAbi>(&self) -> anyhow::Result<T> {
+        abi_decode(self.buffer.data.as_slice())
+    }
+
+    /// Write a value encoded as with Solidity's `abi.encode` to the buffer.
+    pub fn write_abi<T: SolidityAbi>(&mut self, value: &T) {
+        self.buffer.write_slice(&abi_encode(value));
+    }
+
     /// Hash the public values, mask the top 3 bits and return a BigUint. Matches the implementation
     /// of `hashPublicValues` in the Solidity verifier.
     ///
@@ -157,6 +352,206 @@ impl AsRef<[u8]> for SP1PublicValues {
     }
 }
 
+/// Hash a leaf of the Merkle tree of public outputs, as `sha256(0x00 || leaf)`.
+pub fn hash_public_leaf(leaf: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0u8]);
+    hasher.update(leaf);
+    hasher.finalize().into()
+}
+
+/// Hash two nodes of the Merkle tree of public outputs, as `sha256(0x01 || left || right)`.
+pub fn hash_public_nodes(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([1u8]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// The Merkle tree of the public outputs of a program, which lets a verifier open one output with
+/// a proof of logarithmic size instead of the whole public values.
+///
+/// The leaves are padded with zero hashes to a power of two, and the root of an empty tree is zero.
+/// This matches the root computed by `sp1_zkvm::io::commit_merkle_root`.
+#[derive(Debug, Clone)]
+pub struct PublicOutputsTree {
+    /// The layers of the tree, from the hashed leaves to the root.
+    layers: Vec<Vec<[u8; 32]>>,
+    num_leaves: usize,
+}
+
+impl PublicOutputsTree {
+    /// Build the tree of a list of leaves.
+    pub fn new<L: AsRef<[u8]>>(leaves: &[L]) -> Self {
+        let mut layer = leaves
+            .iter()
+            .map(|leaf| hash_public_leaf(leaf.as_ref()))
+            .collect::<Vec<_>>();
+        layer.resize(leaves.len().next_power_of_two(), [0u8; 32]);
+
+        let mut layers = vec![layer];
+        while layers.last().unwrap().len() > 1 {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks_exact(2)
+                .map(|pair| hash_public_nodes(&pair[0], &pair[1]))
+                .collect();
+            layers.push(next);
+        }
+        Self {
+            layers,
+            num_leaves: leaves.len(),
+        }
+    }
+
+    /// The root of the tree.
+    pub fn root(&self) -> [u8; 32] {
+        if self.num_leaves == 0 {
+            return [0u8; 32];
+        }
+        self.layers.last().unwrap()[0]
+    }
+
+    /// The number of leaves, before padding.
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// The proof of the leaf at `index`, or `None` if there is no such leaf.
+    pub fn proof(&self, index: usize) -> Option<PublicOutputProof> {
+        if index >= self.num_leaves {
+            return None;
+        }
+        let siblings = self.layers[..self.layers.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(height, layer)| layer[(index >> height) ^ 1])
+            .collect();
+        Some(PublicOutputProof { index, siblings })
+    }
+}
+
+/// A proof that a leaf is in the Merkle tree of public outputs, as the siblings of the path from
+/// the leaf to the root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicOutputProof {
+    pub index: usize,
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl PublicOutputProof {
+    /// Check that `leaf` is at the index of the proof in the tree with the given root.
+    pub fn verify(&self, root: &[u8; 32], leaf: &[u8]) -> bool {
+        if self.index >> self.siblings.len() != 0 {
+            return false;
+        }
+        let node = self.siblings.iter().enumerate().fold(
+            hash_public_leaf(leaf),
+            |node, (height, sibling)| {
+                if (self.index >> height) & 1 == 0 {
+                    hash_public_nodes(&node, sibling)
+                } else {
+                    hash_public_nodes(sibling, &node)
+                }
+            },
+        );
+        &node == root
+    }
+}
+
+/// The type of a user public value, which determines how its word is decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UserPublicValueKind {
+    U32,
+    I32,
+    U16,
+    U8,
+    Bool,
+}
+
+/// A decoded user public value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UserPublicValue {
+    U32(u32),
+    I32(i32),
+    U16(u16),
+    U8(u8),
+    Bool(bool),
+}
+
+/// An error when decoding the user public values.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum UserPublicValuesError {
+    #[error("the word {word:#x} of `{name}` is out of range for {kind:?}")]
+    OutOfRange {
+        name: String,
+        kind: UserPublicValueKind,
+        word: u32,
+    },
+}
+
+/// The names and types of the user public values that a program sets with
+/// `sp1_zkvm::io::set_user_public_value`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserPublicValuesSchema {
+    fields: Vec<(String, usize, UserPublicValueKind)>,
+}
+
+impl UserPublicValuesSchema {
+    /// Create an empty schema.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a named field at a word index of the user public values.
+    pub fn field(
+        mut self,
+        name: impl Into<String>,
+        index: usize,
+        kind: UserPublicValueKind,
+    ) -> Self {
+        assert!(
+            index < USER_PV_NUM_WORDS,
+            "user public value index {} is out of bounds",
+            index
+        );
+        self.fields.push((name.into(), index, kind));
+        self
+    }
+
+    /// Decode the words of the user public values into the named fields.
+    pub fn decode(
+        &self,
+        words: &[u32; USER_PV_NUM_WORDS],
+    ) -> Result<BTreeMap<String, UserPublicValue>, UserPublicValuesError> {
+        self.fields
+            .iter()
+            .map(|(name, index, kind)| {
+                let word = words[*index];
+                let value = match kind {
+                    UserPublicValueKind::U32 => Some(UserPublicValue::U32(word)),
+                    UserPublicValueKind::I32 => Some(UserPublicValue::I32(word as i32)),
+                    UserPublicValueKind::U16 => u16::try_from(word).ok().map(UserPublicValue::U16),
+                    UserPublicValueKind::U8 => u8::try_from(word).ok().map(UserPublicValue::U8),
+                    UserPublicValueKind::Bool => match word {
+                        0 => Some(UserPublicValue::Bool(false)),
+                        1 => Some(UserPublicValue::Bool(true)),
+                        _ => None,
+                    },
+                };
+                let value = value.ok_or_else(|| UserPublicValuesError::OutOfRange {
+                    name: name.clone(),
+                    kind: *kind,
+                    word,
+                })?;
+                Ok((name.clone(), value))
+            })
+            .collect()
+    }
+}
+
 pub mod proof_serde {
     use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 
@@ -213,4 +608,162 @@ mod tests {
 
         assert_eq!(hash, expected_hash_biguint);
     }
+
+    #[test]
+    fn test_stdin_from_reader() {
+        let data = (0..10u8).collect::<Vec<_>>();
+        let mut stdin = SP1Stdin::from_reader(std::io::Cursor::new(data), 4);
+        let mut copy = stdin.clone();
+        stdin.buffer_stream().unwrap();
+        assert_eq!(
+            stdin.buffer,
+            vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9], vec![]]
+        );
+        assert!(stdin.stream.is_none());
+
+        // The copy shares the stream, which was already read.
+        assert!(matches!(
+            copy.buffer_stream(),
+            Err(ExecutionError::InputStreamConsumed(InputStreamConsumed))
+        ));
+    }
+
+    #[test]
+    fn test_stdin_from_failing_reader() {
+        struct FailingReader;
+
+        impl Read for FailingReader {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+            }
+        }
+
+        let mut stdin = SP1Stdin::from_reader(FailingReader, 4);
+        assert!(matches!(
+            stdin.buffer_stream(),
+            Err(ExecutionError::InputStreamRead(_))
+        ));
+    }
+
+    fn check_codec<C: IoCodec>() {
+        let values = (7u64, "codec".to_string(), vec![1u32, 2, 3]);
+
+        let mut stdin = SP1Stdin::new();
+        stdin.write_with::<C, _>(&values);
+        stdin.write(&5u8);
+        assert_eq!(stdin.read_with::<C, (u64, String, Vec<u32>)>(), values);
+        assert_eq!(stdin.read::<u8>(), 5);
+
+        // Values committed one after the other are read back in order.
+        let mut public_values = SP1PublicValues::new();
+        public_values.write_with::<C, _>(&values);
+        public_values.write_with::<C, _>(&-3i32);
+        assert_eq!(
+            public_values.read_with::<C, (u64, String, Vec<u32>)>(),
+            values
+        );
+        assert_eq!(public_values.read_with::<C, i32>(), -3);
+    }
+
+    #[test]
+    fn test_io_codecs() {
+        check_codec::<Bincode>();
+        #[cfg(feature = "postcard")]
+        check_codec::<codec::Postcard>();
+        #[cfg(feature = "json")]
+        check_codec::<codec::Json>();
+    }
+
+    #[test]
+    fn test_user_public_values_schema() {
+        let schema = UserPublicValuesSchema::new()
+            .field("balance", 0, UserPublicValueKind::U32)
+            .field("delta", 1, UserPublicValueKind::I32)
+            .field("valid", 2, UserPublicValueKind::Bool);
+        let mut words = [0u32; USER_PV_NUM_WORDS];
+        words[0] = 1_000;
+        words[1] = -5i32 as u32;
+        words[2] = 1;
+
+        let fields = schema.decode(&words).unwrap();
+        assert_eq!(fields["balance"], UserPublicValue::U32(1_000));
+        assert_eq!(fields["delta"], UserPublicValue::I32(-5));
+        assert_eq!(fields["valid"], UserPublicValue::Bool(true));
+
+        words[2] = 2;
+        assert!(schema.decode(&words).is_err());
+    }
+
+    #[test]
+    fn test_public_outputs_tree() {
+        let leaves = (0..5u8).map(|i| vec![i; i as usize]).collect::<Vec<_>>();
+        let tree = PublicOutputsTree::new(&leaves);
+        assert_eq!(tree.num_leaves(), 5);
+
+        let root = tree.root();
+        for (i, leaf) in leaves.iter().enumerate() {
+            let proof = tree.proof(i).unwrap();
+            assert_eq!(proof.siblings.len(), 3);
+            assert!(proof.verify(&root, leaf));
+            assert!(!proof.verify(&root, b"not a leaf"));
+        }
+        assert!(tree.proof(5).is_none());
+
+        let single = PublicOutputsTree::new(&[b"leaf"]);
+        assert_eq!(single.root(), hash_public_leaf(b"leaf"));
+        assert!(single.proof(0).unwrap().verify(&single.root(), b"leaf"));
+        assert_eq!(PublicOutputsTree::new::<Vec<u8>>(&[]).root(), [0u8; 32]);
+    }
+
+    #[derive(sp1_derive::SolidityAbi, Debug, PartialEq)]
+    #[sol_abi_path = "sp1_precompiles::abi"]
+    struct AbiOutput {
+        count: u64,
+        data: sp1_precompiles::abi::Bytes,
+        values: Vec<u32>,
+    }
+
+    #[test]
+    fn test_public_values_abi() {
+        let output = AbiOutput {
+            count: 1,
+            data: vec![0x12, 0x34].into(),
+            values: vec![5, 6],
+        };
+        assert_eq!(
+            <AbiOutput as SolidityAbi>::sol_type(),
+            "(uint64,bytes,uint32[])"
+        );
+
+        // The words of `abi.encode(output)` in Solidity.
+        let word = |value: u8| {
+            let mut word = [0u8; 32];
+            word[31] = value;
+            word
+        };
+        let mut data = word(0);
+        data[..2].copy_from_slice(&[0x12, 0x34]);
+        let expected = [
+            word(0x20),
+            word(1),
+            word(0x60),
+            word(0xa0),
+            word(2),
+            data,
+            word(2),
+            word(5),
+            word(6),
+        ]
+        .concat();
+
+        let mut public_values = SP1PublicValues::new();
+        public_values.write_abi(&output);
+        assert_eq!(public_values.as_slice(), expected.as_slice());
+        assert_eq!(public_values.abi_decode::<AbiOutput>().unwrap(), output);
+
+        let truncated = SP1PublicValues::from(&expected[..expected.len() - 32]);
+        assert!(truncated.abi_decode::<AbiOutput>().is_err());
+        assert!(abi_decode::<bool>(&word(2)).is_err());
+        assert_eq!(abi_decode::<i32>(&[0xff; 32]).unwrap(), -1);
+    }
 }
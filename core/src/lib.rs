@@ -20,6 +20,7 @@ pub mod alu;
 pub mod bytes;
 pub mod cpu;
 pub mod disassembler;
+pub mod encoding;
 pub mod io;
 pub mod lookup;
 pub mod memory;
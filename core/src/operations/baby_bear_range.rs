@@ -0,0 +1,84 @@
+use std::array;
+
+use p3_air::AirBuilder;
+use p3_field::{AbstractField, Field};
+use sp1_derive::AlignedBorrow;
+
+use crate::air::{SP1AirBuilder, Word};
+
+/// A set of columns needed to check that a word is the canonical encoding of a BabyBear element.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct BabyBearWordRangeChecker<T> {
+    /// The bits of the most significant byte of the word.
+    pub most_sig_byte_decomp: [T; 8],
+
+    /// The products of the bits 3 to 5, 3 to 6 and 3 to 7 of the most significant byte.
+    pub and_most_sig_byte_decomp_3_to_5: T,
+    pub and_most_sig_byte_decomp_3_to_6: T,
+    pub and_most_sig_byte_decomp_3_to_7: T,
+}
+
+impl<F: Field> BabyBearWordRangeChecker<F> {
+    pub fn populate(&mut self, value: u32) {
+        self.most_sig_byte_decomp = array::from_fn(|i| F::from_bool(value & (1 << (24 + i)) != 0));
+        self.and_most_sig_byte_decomp_3_to_5 =
+            self.most_sig_byte_decomp[3] * self.most_sig_byte_decomp[4];
+        self.and_most_sig_byte_decomp_3_to_6 =
+            self.and_most_sig_byte_decomp_3_to_5 * self.most_sig_byte_decomp[5];
+        self.and_most_sig_byte_decomp_3_to_7 =
+            self.and_most_sig_byte_decomp_3_to_6 * self.most_sig_byte_decomp[6];
+    }
+
+    pub fn range_check<AB: SP1AirBuilder>(
+        builder: &mut AB,
+        value: Word<AB::Var>,
+        cols: BabyBearWordRangeChecker<AB::Var>,
+        is_real: AB::Var,
+    ) {
+        let mut recomposed_byte = AB::Expr::zero();
+        for (i, bit) in cols.most_sig_byte_decomp.iter().enumerate() {
+            builder.when(is_real).assert_bool(*bit);
+            recomposed_byte += AB::Expr::from_canonical_usize(1 << i) * *bit;
+        }
+        builder.when(is_real).assert_eq(recomposed_byte, value[3]);
+
+        // The BabyBear modulus is 01111000_00000000_00000000_00000001 in big-endian binary, so it
+        // is enough to compare the most significant byte to 01111000:
+        // 1) if it is larger, the value is not canonical.
+        // 2) if it is equal, the lower bytes must all be zero.
+        // 3) if it is smaller, the value is canonical.
+        builder
+            .when(is_real)
+            .assert_zero(cols.most_sig_byte_decomp[7]);
+
+        // Compute the product of the top bits.
+        builder.when(is_real).assert_eq(
+            cols.and_most_sig_byte_decomp_3_to_5,
+            cols.most_sig_byte_decomp[3] * cols.most_sig_byte_decomp[4],
+        );
+        builder.when(is_real).assert_eq(
+            cols.and_most_sig_byte_decomp_3_to_6,
+            cols.and_most_sig_byte_decomp_3_to_5 * cols.most_sig_byte_decomp[5],
+        );
+        builder.when(is_real).assert_eq(
+            cols.and_most_sig_byte_decomp_3_to_7,
+            cols.and_most_sig_byte_decomp_3_to_6 * cols.most_sig_byte_decomp[6],
+        );
+
+        // If the top bits are all set, the lower bits of the byte and the lower bytes must be zero.
+        let lower_bits_sum: AB::Expr = cols.most_sig_byte_decomp[..3]
+            .iter()
+            .map(|bit| (*bit).into())
+            .sum();
+        builder
+            .when(is_real)
+            .when(cols.and_most_sig_byte_decomp_3_to_7)
+            .assert_zero(lower_bits_sum);
+        let lower_bytes_sum: AB::Expr = value[0] + value[1] + value[2];
+        builder
+            .when(is_real)
+            .when(cols.and_most_sig_byte_decomp_3_to_7)
+            .assert_zero(lower_bytes_sum);
+    }
+}
@@ -11,10 +11,11 @@ use super::util_air::eval_field_operation;
 use crate::air::Polynomial;
 use crate::air::SP1AirBuilder;
 use crate::bytes::event::ByteRecord;
+use serde::{Deserialize, Serialize};
 use typenum::Unsigned;
 
 /// Airthmetic operation for emulating modular arithmetic.
-#[derive(PartialEq, Copy, Clone, Debug)]
+#[derive(PartialEq, Copy, Clone, Debug, Serialize, Deserialize)]
 pub enum FieldOperation {
     Add,
     Mul,
@@ -218,7 +219,62 @@ impl<V: Copy, P: FieldParameters> FieldOpCols<V, P> {
         let p_witness_high = self.witness_high.0.iter().into();
         eval_field_operation::<AB, P>(builder, &p_vanishing, &p_witness_low, &p_witness_high);
 
-        // Range checks for the result, carry, and witness columns.
+        self.eval_range_checks(builder, shard, channel, is_real);
+    }
+
+    /// Evaluate the operation selected by the flags `is_add`, `is_sub` and `is_mul` with a
+    /// specified modulus. At most one of the flags may be set, and the caller is responsible for
+    /// constraining them to be boolean. This allows a single set of columns to be shared by the
+    /// operations of a chip, e.g. the `fp` precompiles.
+    #[allow(clippy::too_many_arguments)]
+    pub fn eval_variable<AB: SP1AirBuilder<Var = V>>(
+        &self,
+        builder: &mut AB,
+        a: &(impl Into<Polynomial<AB::Expr>> + Clone),
+        b: &(impl Into<Polynomial<AB::Expr>> + Clone),
+        modulus: &(impl Into<Polynomial<AB::Expr>> + Clone),
+        is_add: impl Into<AB::Expr> + Clone,
+        is_sub: impl Into<AB::Expr> + Clone,
+        is_mul: impl Into<AB::Expr> + Clone,
+        shard: impl Into<AB::Expr> + Clone,
+        channel: impl Into<AB::Expr> + Clone,
+        is_real: impl Into<AB::Expr> + Clone,
+    ) where
+        V: Into<AB::Expr>,
+        Limbs<V, P::Limbs>: Copy,
+    {
+        let p_a: Polynomial<AB::Expr> = (a).clone().into();
+        let p_b: Polynomial<AB::Expr> = (b).clone().into();
+        let p_modulus: Polynomial<AB::Expr> = (modulus).clone().into();
+        let p_result: Polynomial<AB::Expr> = self.result.into();
+        let p_carry: Polynomial<AB::Expr> = self.carry.into();
+        let is_add: AB::Expr = is_add.into();
+        let is_sub: AB::Expr = is_sub.into();
+        let is_mul: AB::Expr = is_mul.into();
+
+        // As in `eval_with_modulus`, subtraction is checked as `result + b = a`.
+        let p_op = (&p_a + &p_b) * is_add.clone()
+            + (&p_result + &p_b) * is_sub.clone()
+            + (&p_a * &p_b) * is_mul.clone();
+        let p_op_result = &p_result * (is_add + is_mul) + &p_a * is_sub;
+        let p_vanishing = p_op - &p_op_result - &(&p_carry * &p_modulus);
+        let p_witness_low = self.witness_low.0.iter().into();
+        let p_witness_high = self.witness_high.0.iter().into();
+        eval_field_operation::<AB, P>(builder, &p_vanishing, &p_witness_low, &p_witness_high);
+
+        self.eval_range_checks(builder, shard, channel, is_real);
+    }
+
+    /// Range checks for the result, carry, and witness columns.
+    fn eval_range_checks<AB: SP1AirBuilder<Var = V>>(
+        &self,
+        builder: &mut AB,
+        shard: impl Into<AB::Expr> + Clone,
+        channel: impl Into<AB::Expr> + Clone,
+        is_real: impl Into<AB::Expr> + Clone,
+    ) where
+        V: Into<AB::Expr>,
+    {
         builder.slice_range_check_u8(
             &self.result.0,
             shard.clone(),
@@ -232,13 +288,13 @@ impl<V: Copy, P: FieldParameters> FieldOpCols<V, P> {
             is_real.clone(),
         );
         builder.slice_range_check_u8(
-            p_witness_low.coefficients(),
+            &self.witness_low.0,
             shard.clone(),
             channel.clone(),
             is_real.clone(),
         );
         builder.slice_range_check_u8(
-            p_witness_high.coefficients(),
+            &self.witness_high.0,
             shard.clone(),
             channel.clone(),
             is_real,
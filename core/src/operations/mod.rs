@@ -8,6 +8,7 @@ mod add;
 mod add4;
 mod add5;
 mod and;
+mod baby_bear_range;
 pub mod field;
 mod fixed_rotate_right;
 mod fixed_shift_right;
@@ -22,6 +23,7 @@ pub use add::*;
 pub use add4::*;
 pub use add5::*;
 pub use and::*;
+pub use baby_bear_range::*;
 pub use fixed_rotate_right::*;
 pub use fixed_shift_right::*;
 pub use is_equal_word::*;
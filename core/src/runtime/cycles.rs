@@ -0,0 +1,155 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::{Deserialize, Serialize};
+
+/// A span of the program annotated with `cycle-tracker-start` and `cycle-tracker-end`, aggregated
+/// over all the times it was entered from the same parent span.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CycleSpan {
+    /// The name of the span.
+    pub name: String,
+    /// The cycles executed in the span, including its children.
+    pub cycles: u64,
+    /// The number of times the span was entered.
+    pub count: u64,
+    /// The spans entered inside of this span, in the order they were first entered.
+    pub children: Vec<CycleSpan>,
+}
+
+impl CycleSpan {
+    /// Creates a span named `name` which was never entered.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// The child of the span named `name`.
+    pub fn child(&self, name: &str) -> Option<&CycleSpan> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// The cycles executed in the span outside of its children.
+    pub fn self_cycles(&self) -> u64 {
+        let children = self.children.iter().map(|child| child.cycles).sum::<u64>();
+        self.cycles.saturating_sub(children)
+    }
+
+    fn fmt_with_depth(&self, f: &mut Formatter<'_>, depth: usize) -> FmtResult {
+        writeln!(
+            f,
+            "{}{}: {} cycles ({} calls)",
+            "  ".repeat(depth + 1),
+            self.name,
+            self.cycles,
+            self.count
+        )?;
+        for child in self.children.iter() {
+            child.fmt_with_depth(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for CycleSpan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.fmt_with_depth(f, 0)
+    }
+}
+
+/// The spans of the cycle tracker which are open, from the outermost to the innermost.
+#[derive(Debug, Clone, Default)]
+pub struct CycleSpanStack {
+    open: Vec<(String, u64)>,
+}
+
+impl CycleSpanStack {
+    /// Opens the span `name` at the cycle `clk`.
+    pub fn start(&mut self, name: &str, clk: u64) {
+        self.open.push((name.to_string(), clk));
+    }
+
+    /// Closes the span `name` at the cycle `clk`, and adds its cycles to the tree of `spans`.
+    ///
+    /// The spans opened inside of `name` which are still open are closed with it. A span which is
+    /// not open is ignored.
+    pub fn end(&mut self, name: &str, clk: u64, spans: &mut Vec<CycleSpan>) {
+        let Some(index) = self.open.iter().rposition(|(open, _)| open == name) else {
+            return;
+        };
+        while self.open.len() > index {
+            let (_, start) = self.open.last().unwrap();
+            let cycles = clk.saturating_sub(*start);
+            let mut level = &mut *spans;
+            let (path, span) = self.open.split_at(self.open.len() - 1);
+            for (parent, _) in path {
+                level = &mut find_or_insert(level, parent).children;
+            }
+            let span = find_or_insert(level, &span[0].0);
+            span.cycles += cycles;
+            span.count += 1;
+            self.open.pop();
+        }
+    }
+
+    /// The number of open spans.
+    pub fn depth(&self) -> usize {
+        self.open.len()
+    }
+}
+
+/// The span named `name` of `spans`, which is added if there is none.
+fn find_or_insert<'a>(spans: &'a mut Vec<CycleSpan>, name: &str) -> &'a mut CycleSpan {
+    match spans.iter().position(|span| span.name == name) {
+        Some(index) => &mut spans[index],
+        None => {
+            spans.push(CycleSpan::new(name));
+            spans.last_mut().unwrap()
+        }
+    }
+}
+
+/// The span at `path` of the tree of `spans`, where `path` holds the names of the span and of its
+/// parents from the outermost.
+pub fn find_cycle_span<'a>(spans: &'a [CycleSpan], path: &[&str]) -> Option<&'a CycleSpan> {
+    let (first, rest) = path.split_first()?;
+    let span = spans.iter().find(|span| span.name == *first)?;
+    rest.iter().try_fold(span, |span, name| span.child(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_span_stack() {
+        let mut stack = CycleSpanStack::default();
+        let mut spans = Vec::new();
+
+        stack.start("main", 0);
+        for i in 0..3 {
+            stack.start("hash", 10 + 100 * i);
+            stack.end("hash", 40 + 100 * i, &mut spans);
+        }
+        stack.start("verify", 400);
+        stack.start("hash", 410);
+        // Closing the outer span also closes the inner one.
+        stack.end("verify", 450, &mut spans);
+        stack.end("missing", 460, &mut spans);
+        stack.end("main", 500, &mut spans);
+        assert_eq!(stack.depth(), 0);
+
+        let main = find_cycle_span(&spans, &["main"]).unwrap();
+        assert_eq!((main.cycles, main.count), (500, 1));
+        assert_eq!(main.children.len(), 2);
+        let hash = find_cycle_span(&spans, &["main", "hash"]).unwrap();
+        assert_eq!((hash.cycles, hash.count), (90, 3));
+        let verify = find_cycle_span(&spans, &["main", "verify"]).unwrap();
+        assert_eq!((verify.cycles, verify.self_cycles()), (50, 10));
+        let nested = find_cycle_span(&spans, &["main", "verify", "hash"]).unwrap();
+        assert_eq!((nested.cycles, nested.count), (40, 1));
+        assert_eq!(main.self_cycles(), 500 - 90 - 50);
+        assert!(find_cycle_span(&spans, &["hash"]).is_none());
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::BTreeSet;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, ToSocketAddrs};
+
+use super::{ExecutionError, Register, Runtime, WatchKind, WatchpointHit};
+
+/// The number of registers reported to the debugger: `x0..x31` followed by the program counter.
+const NUM_GDB_REGISTERS: u32 = 33;
+
+/// The maximum size of a packet, advertised to the debugger in `qSupported`.
+const PACKET_SIZE: usize = 0x4000;
+
+/// A packet read from the debugger.
+enum Packet {
+    /// The data of a packet with a valid checksum.
+    Data(String),
+    /// A packet whose checksum does not match its data, or which exceeds [PACKET_SIZE].
+    Corrupt,
+}
+
+/// The reason the guest stopped, reported to the debugger after `c` and `s` packets.
+enum StopReason {
+    /// A breakpoint was hit or a single step completed.
+    Trap,
+    /// A watchpoint was hit.
+    Watch(WatchpointHit),
+    /// The program halted with the given exit code.
+    Exited(u32),
+    /// Execution failed with an error other than a non-zero exit code.
+    Fault,
+}
+
+/// A minimal GDB remote serial protocol server for debugging guest programs.
+///
+/// The stub supports reading registers and memory, setting software breakpoints by PC and
+/// watchpoints on memory ranges, single-stepping and continuing, which is enough for `gdb` or `lldb` to attach with
+/// `target remote <addr>` (use `set architecture riscv:rv32` in gdb).
+///
+/// Reference: https://sourceware.org/gdb/current/onlinedocs/gdb.html/Remote-Protocol.html
+pub struct GdbStub<'a> {
+    runtime: &'a mut Runtime,
+    breakpoints: BTreeSet<u32>,
+    exit_code: Option<u32>,
+}
+
+impl<'a> GdbStub<'a> {
+    pub fn new(runtime: &'a mut Runtime) -> Self {
+        // Events are not needed while debugging and would otherwise grow without bound.
+        runtime.emit_events = false;
+        Self {
+            runtime,
+            breakpoints: BTreeSet::new(),
+            exit_code: None,
+        }
+    }
+
+    /// Listen on `addr`, wait for a single debugger connection and serve it until it detaches.
+    pub fn listen<A: ToSocketAddrs>(&mut self, addr: A) -> io::Result<()> {
+        let listener = TcpListener::bind(addr)?;
+        tracing::info!("waiting for gdb connection on {}", listener.local_addr()?);
+        let (stream, peer) = listener.accept()?;
+        tracing::info!("gdb connected from {}", peer);
+        stream.set_nodelay(true)?;
+        self.serve(stream)
+    }
+
+    /// Serve remote protocol packets over `stream` until the debugger detaches or kills the
+    /// session, or the stream is closed.
+    pub fn serve<S: Read + Write>(&mut self, mut stream: S) -> io::Result<()> {
+        while let Some(packet) = read_packet(&mut stream)? {
+            // The debugger sends a packet again after a negative acknowledgement.
+            let packet = match packet {
+                Packet::Data(packet) => packet,
+                Packet::Corrupt => {
+                    stream.write_all(b"-")?;
+                    stream.flush()?;
+                    continue;
+                }
+            };
+            stream.write_all(b"+")?;
+            let (response, finished) = self.handle(&packet);
+            write_packet(&mut stream, &response)?;
+            if finished {
+                break;
+            }
+        }
+        stream.flush()
+    }
+
+    /// Handle a single packet, returning the response and whether the session is over.
+    fn handle(&mut self, packet: &str) -> (String, bool) {
+        let command = packet.get(..1).unwrap_or_default();
+        let args = packet.get(1..).unwrap_or_default();
+        let response = match command {
+            "?" => "S05".to_string(),
+            "g" => (0..NUM_GDB_REGISTERS)
+                .map(|i| encode_u32(self.read_register(i)))
+                .collect(),
+            "p" => match u32::from_str_radix(args, 16) {
+                Ok(i) if i < NUM_GDB_REGISTERS => encode_u32(self.read_register(i)),
+                _ => "E01".to_string(),
+            },
+            // Replies may be shorter than requested, and each byte takes two hex digits.
+            "m" => match parse_pair(args) {
+                Some((addr, len)) => hex::encode(
+                    (0..len.min(PACKET_SIZE as u32 / 2))
+                        .map(|i| self.runtime.byte(addr.wrapping_add(i)))
+                        .collect::<Vec<_>>(),
+                ),
+                None => "E01".to_string(),
+            },
+            "Z" | "z" => {
+                let insert = command == "Z";
+                match args
+                    .split_once(',')
+                    .and_then(|(kind, pair)| parse_pair(pair).map(|(addr, len)| (kind, addr, len)))
+                {
+                    Some(("0", addr, _)) => {
+                        if insert {
+                            self.breakpoints.insert(addr);
+                        } else {
+                            self.breakpoints.remove(&addr);
+                        }
+                        "OK".to_string()
+                    }
+                    Some((kind @ ("2" | "3" | "4"), addr, len)) => {
+                        let kind = match kind {
+                            "2" => WatchKind::Write,
+                            "3" => WatchKind::Read,
+                            _ => WatchKind::Access,
+                        };
+                        let range = addr..addr.saturating_add(len);
+                        if insert {
+                            self.runtime.hooks.watch(range, kind);
+                        } else {
+                            self.runtime.hooks.unwatch(range, kind);
+                        }
+                        "OK".to_string()
+                    }
+                    // Hardware breakpoints are not supported.
+                    _ => String::new(),
+                }
+            }
+            "c" => self.resume(false),
+            "s" => self.resume(true),
+            "H" | "T" => "OK".to_string(),
+            "D" => return ("OK".to_string(), true),
+            "k" => return (String::new(), true),
+            _ if packet.starts_with("qSupported") => format!("PacketSize={:x}", PACKET_SIZE),
+            _ if packet == "qAttached" => "1".to_string(),
+            _ if packet == "qC" => "QC1".to_string(),
+            _ if packet == "qfThreadInfo" => "m1".to_string(),
+            _ if packet == "qsThreadInfo" => "l".to_string(),
+            _ => String::new(),
+        };
+        (response, false)
+    }
+
+    /// Read register `i`, where `x0..x31` are numbered `0..32` and the program counter is `32`.
+    fn read_register(&self, i: u32) -> u32 {
+        if i == 32 {
+            self.runtime.state.pc
+        } else {
+            self.runtime.register(Register::from_u32(i))
+        }
+    }
+
+    /// Execute until a breakpoint is hit or the program halts, or for a single cycle if `step`.
+    fn resume(&mut self, step: bool) -> String {
+        // Once the program has halted there is nothing left to execute.
+        if let Some(code) = self.exit_code {
+            return format!("W{:02x}", code as u8);
+        }
+
+        let reason = loop {
+            match self.runtime.step() {
+                Ok(true) => break StopReason::Exited(0),
+                Ok(false) => {}
+                Err(ExecutionError::HaltWithNonZeroExitCode(code)) => {
+                    break StopReason::Exited(code)
+                }
+                Err(ExecutionError::Panicked(panic)) => {
+                    tracing::error!("program panicked at {}", panic);
+                    break StopReason::Exited(1);
+                }
+                Err(ExecutionError::Watchpoint(hit)) => break StopReason::Watch(hit),
+                Err(e) => {
+                    tracing::error!("guest execution failed: {}", e);
+                    break StopReason::Fault;
+                }
+            }
+            if step || self.breakpoints.contains(&self.runtime.state.pc) {
+                break StopReason::Trap;
+            }
+        };
+        match reason {
+            StopReason::Trap => "S05".to_string(),
+            StopReason::Watch(hit) => {
+                let kind = match hit.kind {
+                    WatchKind::Write => "watch",
+                    _ => "rwatch",
+                };
+                format!("T05{}:{:x};", kind, hit.addr)
+            }
+            StopReason::Exited(code) => {
+                self.exit_code = Some(code);
+                format!("W{:02x}", code as u8)
+            }
+            StopReason::Fault => "S0b".to_string(),
+        }
+    }
+}
+
+impl Runtime {
+    /// Run the program under a GDB remote stub listening on `addr`.
+    pub fn run_gdb<A: ToSocketAddrs>(&mut self, addr: A) -> io::Result<()> {
+        GdbStub::new(self).listen(addr)
+    }
+}
+
+/// Read the next `$<data>#<checksum>` packet from `stream`, skipping acknowledgements and
+/// interrupt requests. Returns `None` once the stream is closed.
+fn read_packet<S: Read>(stream: &mut S) -> io::Result<Option<Packet>> {
+    let mut byte = [0u8; 1];
+
+    // Wait for the start of a packet.
+    loop {
+        if stream.read(&mut byte)? == 0 {
+            return Ok(None);
+        }
+        if byte[0] == b'$' {
+            break;
+        }
+    }
+
+    // Read the packet body up to the checksum delimiter, dropping what exceeds the packet size.
+    let mut data = Vec::new();
+    let mut oversized = false;
+    loop {
+        if stream.read(&mut byte)? == 0 {
+            return Ok(None);
+        }
+        if byte[0] == b'#' {
+            break;
+        }
+        if data.len() < PACKET_SIZE {
+            data.push(byte[0]);
+        } else {
+            oversized = true;
+        }
+    }
+
+    // Read the two checksum digits, the sum of the body bytes modulo 256.
+    let mut checksum = [0u8; 2];
+    stream.read_exact(&mut checksum)?;
+    let expected = data.iter().fold(0u8, |acc, b| acc.wrapping_add(*b));
+    let valid = std::str::from_utf8(&checksum)
+        .ok()
+        .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+        == Some(expected);
+    if oversized || !valid {
+        return Ok(Some(Packet::Corrupt));
+    }
+
+    Ok(Some(Packet::Data(
+        String::from_utf8_lossy(&data).into_owned(),
+    )))
+}
+
+/// Write `data` to `stream` as a `$<data>#<checksum>` packet.
+fn write_packet<S: Write>(stream: &mut S, data: &str) -> io::Result<()> {
+    let checksum = data.bytes().fold(0u8, |acc, b| acc.wrapping_add(b));
+    write!(stream, "${}#{:02x}", data, checksum)?;
+    stream.flush()
+}
+
+/// Encode a word as hex in target (little-endian) byte order.
+fn encode_u32(value: u32) -> String {
+    hex::encode(value.to_le_bytes())
+}
+
+/// Parse a pair of comma-separated hex numbers, e.g. `addr,length`.
+fn parse_pair(args: &str) -> Option<(u32, u32)> {
+    let (a, b) = args.split_once(',')?;
+    Some((
+        u32::from_str_radix(a, 16).ok()?,
+        u32::from_str_radix(b, 16).ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Write};
+
+    use super::{write_packet, GdbStub, PACKET_SIZE};
+    use crate::runtime::{tests::simple_program, Runtime};
+    use crate::utils::SP1CoreOpts;
+
+    /// An in-memory stream that replays scripted debugger input and records the stub's output.
+    struct ScriptedStream {
+        input: Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Read for ScriptedStream {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for ScriptedStream {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_gdb_breakpoint_and_continue() {
+        let mut input = Vec::new();
+        for packet in ["Z0,8,4", "c", "p20", "c", "p1f", "k"] {
+            write_packet(&mut input, packet).unwrap();
+        }
+        let mut stream = ScriptedStream {
+            input: Cursor::new(input),
+            output: Vec::new(),
+        };
+
+        let mut runtime = Runtime::new(simple_program(), SP1CoreOpts::default());
+        GdbStub::new(&mut runtime).serve(&mut stream).unwrap();
+
+        let mut expected = Vec::new();
+        // Breakpoint set, hit at pc = 8, pc read back, program exits, x31 = 42.
+        for response in ["OK", "S05", "08000000", "W00", "2a000000", ""] {
+            expected.extend_from_slice(b"+");
+            write_packet(&mut expected, response).unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(stream.output).unwrap(),
+            String::from_utf8(expected).unwrap()
+        );
+        assert_eq!(runtime.state.global_clk, 3);
+    }
+
+    #[test]
+    fn test_gdb_corrupt_packet_and_long_read() {
+        // A packet with a wrong checksum, then a read far larger than a packet.
+        let mut input = b"$p20#00".to_vec();
+        for packet in ["m0,ffffffff", "k"] {
+            write_packet(&mut input, packet).unwrap();
+        }
+        let mut stream = ScriptedStream {
+            input: Cursor::new(input),
+            output: Vec::new(),
+        };
+
+        let mut runtime = Runtime::new(simple_program(), SP1CoreOpts::default());
+        GdbStub::new(&mut runtime).serve(&mut stream).unwrap();
+
+        let mut expected = b"-+".to_vec();
+        write_packet(&mut expected, &"00".repeat(PACKET_SIZE / 2)).unwrap();
+        expected.extend_from_slice(b"+");
+        write_packet(&mut expected, "").unwrap();
+        assert_eq!(stream.output, expected);
+    }
+}
@@ -0,0 +1,303 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::Range;
+
+use super::{Runtime, SyscallCode};
+
+/// A callback fired before a syscall is executed, with the syscall code and its two arguments.
+pub type SyscallHook = Box<dyn FnMut(&Runtime, SyscallCode, u32, u32) + Send>;
+
+/// A callback fired after a memory write, with the address and the value written.
+pub type WriteHook = Box<dyn FnMut(&Runtime, u32, u32) + Send>;
+
+/// A callback fired periodically, after every given number of cycles.
+pub type CycleHook = Box<dyn FnMut(&Runtime) + Send>;
+
+/// A callback fired when a watchpoint is hit.
+pub type WatchHook = Box<dyn FnMut(&Runtime, &WatchpointHit) + Send>;
+
+/// The kind of memory access a watchpoint triggers on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKind {
+    Read,
+    Write,
+    Access,
+}
+
+impl WatchKind {
+    fn matches(self, access: WatchKind) -> bool {
+        self == WatchKind::Access || self == access
+    }
+}
+
+/// A memory access which triggered a watchpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchpointHit {
+    /// Whether the access was a read or a write.
+    pub kind: WatchKind,
+
+    /// The word address accessed, or the register index for register accesses.
+    pub addr: u32,
+
+    /// The value read, or the value written.
+    pub value: u32,
+
+    /// The program counter of the instruction performing the access.
+    pub pc: u32,
+
+    /// The global clock of the instruction performing the access.
+    pub clk: u64,
+}
+
+impl Display for WatchpointHit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let access = match self.kind {
+            WatchKind::Write => "write",
+            _ => "read",
+        };
+        write!(
+            f,
+            "{} of 0x{:08x} at 0x{:08x} (pc 0x{:08x}, clk {})",
+            access, self.value, self.addr, self.pc, self.clk
+        )
+    }
+}
+
+/// A watched address range. Watchpoints without a callback pause execution when hit.
+struct Watchpoint {
+    range: Range<u32>,
+    kind: WatchKind,
+    hook: Option<WatchHook>,
+}
+
+/// Host callbacks which observe the execution of a program.
+///
+/// Hooks are fired in constrained and unconstrained mode alike, and receive a shared reference to
+/// the runtime so they can inspect registers, memory and the execution state, but they cannot
+/// modify the execution.
+#[derive(Default)]
+pub struct HookRegistry {
+    syscall_hooks: Vec<SyscallHook>,
+    write_hooks: Vec<(Range<u32>, WriteHook)>,
+    cycle_hooks: Vec<(u64, CycleHook)>,
+    watchpoints: Vec<Watchpoint>,
+
+    /// The first watchpoint hit by the current instruction which should pause execution.
+    pub(crate) pending_watchpoint: Option<WatchpointHit>,
+}
+
+impl HookRegistry {
+    /// Register a callback fired before every syscall.
+    pub fn on_syscall<F: FnMut(&Runtime, SyscallCode, u32, u32) + Send + 'static>(
+        &mut self,
+        hook: F,
+    ) {
+        self.syscall_hooks.push(Box::new(hook));
+    }
+
+    /// Register a callback fired after every memory write to an address in `range`. Registers are
+    /// addressed by their index, so `0..32` watches all register writes.
+    pub fn on_write<F: FnMut(&Runtime, u32, u32) + Send + 'static>(
+        &mut self,
+        range: Range<u32>,
+        hook: F,
+    ) {
+        self.write_hooks.push((range, Box::new(hook)));
+    }
+
+    /// Register a callback fired after every `interval` cycles.
+    pub fn on_cycles<F: FnMut(&Runtime) + Send + 'static>(&mut self, interval: u64, hook: F) {
+        assert!(interval > 0, "cycle hook interval must be positive");
+        self.cycle_hooks.push((interval, Box::new(hook)));
+    }
+
+    /// Watch accesses of `kind` to the byte addresses in `range`, pausing execution with
+    /// [ExecutionError::Watchpoint](super::ExecutionError::Watchpoint) after the accessing
+    /// instruction. Registers are addressed by their index.
+    ///
+    /// Execution is resumed by calling `run` (or `step`) again.
+    pub fn watch(&mut self, range: Range<u32>, kind: WatchKind) {
+        self.watchpoints.push(Watchpoint {
+            range,
+            kind,
+            hook: None,
+        });
+    }
+
+    /// Watch accesses of `kind` to the byte addresses in `range`, invoking `hook` instead of
+    /// pausing execution.
+    pub fn on_watch<F: FnMut(&Runtime, &WatchpointHit) + Send + 'static>(
+        &mut self,
+        range: Range<u32>,
+        kind: WatchKind,
+        hook: F,
+    ) {
+        self.watchpoints.push(Watchpoint {
+            range,
+            kind,
+            hook: Some(Box::new(hook)),
+        });
+    }
+
+    /// Remove the pausing watchpoints on exactly `range` and `kind`.
+    pub fn unwatch(&mut self, range: Range<u32>, kind: WatchKind) {
+        self.watchpoints
+            .retain(|w| w.hook.is_some() || w.range != range || w.kind != kind);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.syscall_hooks.is_empty()
+            && self.write_hooks.is_empty()
+            && self.cycle_hooks.is_empty()
+            && self.watchpoints.is_empty()
+    }
+}
+
+impl Runtime {
+    /// Fire the syscall hooks for `code`.
+    pub(crate) fn fire_syscall_hooks(&mut self, code: SyscallCode, arg1: u32, arg2: u32) {
+        if self.hooks.syscall_hooks.is_empty() {
+            return;
+        }
+        let mut hooks = std::mem::take(&mut self.hooks.syscall_hooks);
+        for hook in hooks.iter_mut() {
+            hook(self, code, arg1, arg2);
+        }
+        self.hooks.syscall_hooks = hooks;
+    }
+
+    /// Fire the write hooks watching `addr`.
+    pub(crate) fn fire_write_hooks(&mut self, addr: u32, value: u32) {
+        if self.hooks.write_hooks.is_empty() {
+            return;
+        }
+        let mut hooks = std::mem::take(&mut self.hooks.write_hooks);
+        for (range, hook) in hooks.iter_mut() {
+            if range.contains(&addr) {
+                hook(self, addr, value);
+            }
+        }
+        self.hooks.write_hooks = hooks;
+    }
+
+    /// Check the watchpoints covering the word (or register) at `addr` after an access of `kind`.
+    pub(crate) fn fire_watchpoints(&mut self, kind: WatchKind, addr: u32, value: u32) {
+        if self.hooks.watchpoints.is_empty() {
+            return;
+        }
+        // Registers are a single address wide, memory words cover four bytes.
+        let end = if addr < 32 { addr + 1 } else { addr + 4 };
+        let hit = WatchpointHit {
+            kind,
+            addr,
+            value,
+            pc: self.state.pc,
+            clk: self.state.global_clk,
+        };
+        let mut watchpoints = std::mem::take(&mut self.hooks.watchpoints);
+        for watchpoint in watchpoints.iter_mut() {
+            if !watchpoint.kind.matches(kind)
+                || watchpoint.range.end <= addr
+                || end <= watchpoint.range.start
+            {
+                continue;
+            }
+            match watchpoint.hook.as_mut() {
+                Some(hook) => hook(self, &hit),
+                None => {
+                    self.hooks.pending_watchpoint.get_or_insert(hit);
+                }
+            }
+        }
+        self.hooks.watchpoints = watchpoints;
+    }
+
+    /// Fire the cycle hooks whose interval divides the global clock.
+    pub(crate) fn fire_cycle_hooks(&mut self) {
+        if self.hooks.cycle_hooks.is_empty() {
+            return;
+        }
+        let mut hooks = std::mem::take(&mut self.hooks.cycle_hooks);
+        for (interval, hook) in hooks.iter_mut() {
+            if self.state.global_clk % *interval == 0 {
+                hook(self);
+            }
+        }
+        self.hooks.cycle_hooks = hooks;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{WatchKind, WatchpointHit};
+    use crate::runtime::{tests::simple_program, ExecutionError, Register, Runtime};
+    use crate::utils::SP1CoreOpts;
+
+    #[test]
+    fn test_hooks() {
+        let mut runtime = Runtime::new(simple_program(), SP1CoreOpts::default());
+
+        let writes = Arc::new(Mutex::new(Vec::new()));
+        let cycles = Arc::new(Mutex::new(Vec::new()));
+
+        let writes_clone = writes.clone();
+        runtime.hooks.on_write(30..32, move |_, addr, value| {
+            writes_clone.lock().unwrap().push((addr, value));
+        });
+        let cycles_clone = cycles.clone();
+        runtime.hooks.on_cycles(2, move |rt| {
+            cycles_clone.lock().unwrap().push(rt.state.global_clk);
+        });
+
+        runtime.run().unwrap();
+
+        assert_eq!(*writes.lock().unwrap(), vec![(30, 37), (31, 42)]);
+        assert_eq!(*cycles.lock().unwrap(), vec![2]);
+    }
+
+    #[test]
+    fn test_watchpoints() {
+        let mut runtime = Runtime::new(simple_program(), SP1CoreOpts::default());
+
+        let reads = Arc::new(Mutex::new(Vec::new()));
+        let reads_clone = reads.clone();
+        runtime
+            .hooks
+            .on_watch(29..30, WatchKind::Read, move |_, hit| {
+                reads_clone.lock().unwrap().push(*hit);
+            });
+        runtime.hooks.watch(30..31, WatchKind::Write);
+
+        // The write to x30 by the second instruction pauses execution.
+        match runtime.run() {
+            Err(ExecutionError::Watchpoint(hit)) => assert_eq!(
+                hit,
+                WatchpointHit {
+                    kind: WatchKind::Write,
+                    addr: 30,
+                    value: 37,
+                    pc: 4,
+                    clk: 1,
+                }
+            ),
+            result => panic!("expected a watchpoint, got {:?}", result),
+        }
+        assert_eq!(runtime.state.pc, 8);
+        assert!(reads.lock().unwrap().is_empty());
+
+        // Resuming runs to completion, reading x29 in the last instruction.
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X31), 42);
+        assert_eq!(
+            *reads.lock().unwrap(),
+            vec![WatchpointHit {
+                kind: WatchKind::Read,
+                addr: 29,
+                value: 5,
+                pc: 8,
+                clk: 2,
+            }]
+        );
+    }
+}
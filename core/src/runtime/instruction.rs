@@ -76,6 +76,23 @@ impl Instruction {
                 | Opcode::SB
                 | Opcode::SH
                 | Opcode::SW
+        ) || self.is_atomic_instruction()
+    }
+
+    /// Returns if the instruction is an atomic memory instruction.
+    pub const fn is_atomic_instruction(&self) -> bool {
+        matches!(
+            self.opcode,
+            Opcode::SC
+                | Opcode::AMOSWAP
+                | Opcode::AMOADD
+                | Opcode::AMOXOR
+                | Opcode::AMOAND
+                | Opcode::AMOOR
+                | Opcode::AMOMIN
+                | Opcode::AMOMAX
+                | Opcode::AMOMINU
+                | Opcode::AMOMAXU
         )
     }
 
@@ -1,12 +1,13 @@
-use std::io::Read;
+use std::io::{Read, Write};
 
+use crate::io::InputStream;
 use crate::stark::{ShardProof, StarkVerifyingKey};
 use crate::utils::BabyBearPoseidon2;
 
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use super::Runtime;
+use super::{ExecutionError, Runtime};
 
 impl Read for Runtime {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
@@ -32,6 +33,43 @@ impl Runtime {
         }
     }
 
+    /// Set a stream of input chunks which is read from once the buffered input is consumed. Fails if
+    /// another execution already read the stream.
+    pub fn write_stream(&mut self, stream: &InputStream) -> Result<(), ExecutionError> {
+        let stream = stream
+            .claim()
+            .map_err(ExecutionError::InputStreamConsumed)?;
+        self.input_source = Some(stream);
+        Ok(())
+    }
+
+    /// Buffer all the chunks of a stream, for an execution which is replayed from checkpoints.
+    /// Fails if another execution already read the stream, or if reading it fails.
+    pub fn buffer_stream(&mut self, stream: &InputStream) -> Result<(), ExecutionError> {
+        let stream = stream
+            .claim()
+            .map_err(ExecutionError::InputStreamConsumed)?;
+        for chunk in stream {
+            self.state
+                .input_stream
+                .push(chunk.map_err(ExecutionError::InputStreamRead)?);
+        }
+        Ok(())
+    }
+
+    /// If the input stream is exhausted, pull the next chunk from the input source, if any.
+    pub(crate) fn fetch_input(&mut self) -> Result<(), ExecutionError> {
+        if self.state.input_stream_ptr < self.state.input_stream.len() {
+            return Ok(());
+        }
+        if let Some(chunk) = self.input_source.as_mut().and_then(|source| source.next()) {
+            self.state
+                .input_stream
+                .push(chunk.map_err(ExecutionError::InputStreamRead)?);
+        }
+        Ok(())
+    }
+
     pub fn write_proof(
         &mut self,
         proof: ShardProof<BabyBearPoseidon2>,
@@ -40,6 +78,19 @@ impl Runtime {
         self.state.proof_stream.push((proof, vk));
     }
 
+    /// Stream the public values to `sink` as the program writes them, instead of buffering them in
+    /// memory. Their digest is still computed, see [Runtime::public_values_digest]. Proofs carry
+    /// their public values, so the provers always buffer them.
+    pub fn stream_public_values<W: Write + Send + 'static>(&mut self, sink: W) {
+        self.public_values_sink = Some(Box::new(sink));
+    }
+
+    /// The SHA-256 digest of the bytes written to the public values so far, which is the committed
+    /// value digest once the program has halted.
+    pub fn public_values_digest(&self) -> [u8; 32] {
+        self.state.public_values_hasher.digest()
+    }
+
     pub fn read_public_values<T: DeserializeOwned>(&mut self) -> T {
         let result = bincode::deserialize_from::<_, T>(self);
         result.unwrap()
@@ -62,6 +113,7 @@ pub mod tests {
     use crate::utils::tests::IO_ELF;
     use crate::utils::{self, prove_simple, BabyBearBlake3, SP1CoreOpts};
     use serde::Deserialize;
+    use sha2::Digest;
 
     #[derive(Serialize, Deserialize, Debug, PartialEq)]
     struct MyPointUnaligned {
@@ -105,6 +157,42 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_io_stream_public_values() {
+        utils::setup_logger();
+        let points = points();
+        let program = Program::from(IO_ELF);
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.write_stdin(&points.0);
+        runtime.write_stdin(&points.1);
+        runtime.run().unwrap();
+        let public_values = runtime.state.public_values_stream.clone();
+        let digest: [u8; 32] = sha2::Sha256::digest(&public_values).into();
+        assert_eq!(runtime.public_values_digest(), digest);
+
+        let mut file = tempfile::tempfile().unwrap();
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.write_stdin(&points.0);
+        runtime.write_stdin(&points.1);
+        runtime.stream_public_values(file.try_clone().unwrap());
+        runtime.run().unwrap();
+        assert!(runtime.state.public_values_stream.is_empty());
+        assert_eq!(runtime.public_values_digest(), digest);
+
+        // The digest of the streamed public values is kept in the checkpoints of the execution.
+        let mut checkpoint = Vec::new();
+        runtime.checkpoint(&mut checkpoint).unwrap();
+        let resumed =
+            Runtime::resume(program, checkpoint.as_slice(), SP1CoreOpts::default()).unwrap();
+        assert_eq!(resumed.public_values_digest(), digest);
+
+        drop(runtime);
+        let mut streamed = Vec::new();
+        std::io::Seek::rewind(&mut file).unwrap();
+        file.read_to_end(&mut streamed).unwrap();
+        assert_eq!(streamed, public_values);
+    }
+
     #[test]
     fn test_io_prove() {
         utils::setup_logger();
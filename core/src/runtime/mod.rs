@@ -1,37 +1,52 @@
+mod cycles;
+mod gdb;
+mod hooks;
 mod instruction;
 mod io;
 mod memory;
 mod opcode;
+mod paged;
+mod profiler;
 mod program;
 mod record;
 mod register;
+mod replay;
 mod state;
 mod syscall;
 #[macro_use]
 mod utils;
 
+pub use cycles::*;
+pub use gdb::*;
+pub use hooks::*;
 pub use instruction::*;
 pub use memory::*;
 pub use opcode::*;
+pub use paged::*;
+pub use profiler::*;
 pub use program::*;
 pub use record::*;
 pub use register::*;
+pub use replay::*;
 pub use state::*;
 pub use syscall::*;
 pub use utils::*;
 
-use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::fs::File;
 use std::io::BufWriter;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::sync::Arc;
 
 use thiserror::Error;
 
+use crate::air::USER_PV_NUM_WORDS;
 use crate::bytes::NUM_BYTE_LOOKUP_CHANNELS;
+use crate::disassembler::ElfError;
+use crate::io::{InputStream, InputStreamConsumed};
 use crate::memory::MemoryInitializeFinalizeEvent;
+use crate::syscall::GuestPanic;
 use crate::utils::SP1CoreOpts;
 use crate::{alu::AluEvent, cpu::CpuEvent};
 
@@ -63,9 +78,15 @@ pub struct Runtime {
     /// A counter for the number of cycles that have been executed in certain functions.
     pub cycle_tracker: HashMap<String, (u64, u32)>,
 
+    /// The spans of the cycle tracker which are open, whose cycles are added to the report.
+    pub cycle_spans: CycleSpanStack,
+
     /// A buffer for stdout and stderr IO.
     pub io_buf: HashMap<u32, String>,
 
+    /// Whether the lines the program writes to stdout and stderr are printed as they are written.
+    pub stream_guest_output: bool,
+
     /// A buffer for writing trace events to a file.
     pub trace_buf: Option<BufWriter<File>>,
 
@@ -79,6 +100,10 @@ pub struct Runtime {
 
     pub syscall_map: HashMap<SyscallCode, Arc<dyn Syscall>>,
 
+    /// The syscalls of the custom precompiles, by syscall number. See
+    /// [Runtime::register_custom_syscall].
+    pub custom_syscall_map: HashMap<u32, Arc<dyn Syscall>>,
+
     pub max_syscall_cycles: u32,
 
     pub emit_events: bool,
@@ -88,15 +113,80 @@ pub struct Runtime {
 
     /// Whether we should write to the report.
     pub should_report: bool,
+
+    /// The maximum number of cycles to execute before returning [`ExecutionError::OutOfGas`].
+    pub max_cycles: Option<u64>,
+
+    /// The maximum number of bytes of memory to touch before returning
+    /// [`ExecutionError::MemoryLimitExceeded`].
+    pub max_memory: Option<u64>,
+
+    /// Whether the program may halt with a nonzero exit code, instead of failing with
+    /// [`ExecutionError::HaltWithNonZeroExitCode`].
+    pub allow_nonzero_exit_code: bool,
+
+    /// The percentage of the heap the program may use before a warning is logged when the report
+    /// is filled in.
+    pub heap_warning_percent: Option<u64>,
+
+    /// The panic the program reported before halting, if any.
+    pub guest_panic: Option<GuestPanic>,
+
+    /// A sampling profiler for the guest program, if profiling is enabled.
+    pub profiler: Option<Profiler>,
+
+    /// A source of input chunks pulled on demand once the input stream is exhausted.
+    pub input_source: Option<InputStream>,
+
+    /// Host callbacks fired on syscalls, watched memory writes and cycle intervals.
+    pub hooks: HookRegistry,
+
+    /// The nondeterministic inputs consumed so far, if recording is enabled.
+    pub recording: Option<ExecutionRecording>,
+
+    /// A writer that the public values are streamed to, instead of being buffered in
+    /// `state.public_values_stream`.
+    pub public_values_sink: Option<Box<dyn Write + Send>>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct ExecutionReport {
     pub instruction_counts: HashMap<Opcode, u64>,
     pub syscall_counts: HashMap<SyscallCode, u64>,
+    /// The number of cycles executed in each shard, indexed by shard number starting at 1.
+    pub shard_cycles: Vec<u64>,
+    /// The number of distinct memory addresses (including registers) touched by the program,
+    /// each of which becomes a row in the memory initialize and finalize tables.
+    pub touched_memory_addresses: u64,
+    /// The bytes from the start of the heap to the highest address the program touched in it,
+    /// which is the high-water mark of its allocator.
+    pub heap_bytes: u64,
+    /// The bytes of the heap, from its start to the memory reserved for the system.
+    pub heap_limit: u64,
+    /// The exit code the program halted with.
+    pub exit_code: u32,
+    /// The words the program committed as its user public values.
+    pub user_values: [u32; USER_PV_NUM_WORDS],
+    /// The panic of the program, if it panicked.
+    pub panic: Option<GuestPanic>,
+    /// The text the program wrote to stdout, without the markers of the cycle tracker.
+    pub stdout: String,
+    /// The text the program wrote to stderr.
+    pub stderr: String,
+    /// The spans annotated with the cycle tracker which were entered at the top level of the
+    /// program, with the spans nested in them.
+    pub cycle_tracker: Vec<CycleSpan>,
 }
 
 impl ExecutionReport {
+    pub fn total_cycle_count(&self) -> u64 {
+        self.shard_cycles.iter().sum()
+    }
+
+    pub fn num_shards(&self) -> usize {
+        self.shard_cycles.len()
+    }
+
     pub fn total_instruction_count(&self) -> u64 {
         self.instruction_counts.values().sum()
     }
@@ -104,6 +194,20 @@ impl ExecutionReport {
     pub fn total_syscall_count(&self) -> u64 {
         self.syscall_counts.values().sum()
     }
+
+    /// The percentage of the heap the program used.
+    pub fn heap_usage_percent(&self) -> f64 {
+        if self.heap_limit == 0 {
+            return 0.0;
+        }
+        self.heap_bytes as f64 * 100.0 / self.heap_limit as f64
+    }
+
+    /// The span of the cycle tracker at `path`, which holds the names of the span and of the
+    /// spans it is nested in, from the outermost.
+    pub fn cycle_span(&self, path: &[&str]) -> Option<&CycleSpan> {
+        find_cycle_span(&self.cycle_tracker, path)
+    }
 }
 
 impl Display for ExecutionReport {
@@ -128,6 +232,32 @@ impl Display for ExecutionReport {
         }
         writeln!(f, "Total Syscall Count: {}", self.total_syscall_count())?;
 
+        writeln!(f, "Shard Cycles:")?;
+        for (i, cycles) in self.shard_cycles.iter().enumerate() {
+            writeln!(f, "  shard {}: {}", i + 1, cycles)?;
+        }
+        writeln!(f, "Total Cycles: {}", self.total_cycle_count())?;
+        writeln!(
+            f,
+            "Touched Memory Addresses: {}",
+            self.touched_memory_addresses
+        )?;
+        writeln!(
+            f,
+            "Heap Usage: {} of {} bytes ({:.2}%)",
+            self.heap_bytes,
+            self.heap_limit,
+            self.heap_usage_percent()
+        )?;
+        writeln!(f, "Exit Code: {}", self.exit_code)?;
+
+        if !self.cycle_tracker.is_empty() {
+            writeln!(f, "Cycle Tracker:")?;
+            for span in self.cycle_tracker.iter() {
+                write!(f, "{}", span)?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -136,6 +266,8 @@ impl Display for ExecutionReport {
 pub enum ExecutionError {
     #[error("execution failed with exit code {0}")]
     HaltWithNonZeroExitCode(u32),
+    #[error("program panicked at {0}")]
+    Panicked(GuestPanic),
     #[error("invalid memory access for opcode {0} and address {1}")]
     InvalidMemoryAccess(Opcode, u32),
     #[error("unimplemented syscall {0}")]
@@ -144,6 +276,22 @@ pub enum ExecutionError {
     Breakpoint(),
     #[error("got unimplemented as opcode")]
     Unimplemented(),
+    #[error("out of gas: executed {0} cycles, last pc 0x{1:08x}")]
+    OutOfGas(u64, u32),
+    #[error("memory limit exceeded: touched {0} bytes at pc 0x{1:08x}, clk {2}")]
+    MemoryLimitExceeded(u64, u32, u64),
+    #[error("watchpoint hit: {0}")]
+    Watchpoint(WatchpointHit),
+    #[error("invalid ELF: {0}")]
+    InvalidElf(ElfError),
+    #[error("{0}")]
+    InputStreamConsumed(InputStreamConsumed),
+    #[error("failed to read the input stream: {0}")]
+    InputStreamRead(std::io::Error),
+    #[error("failed to write the public values: {0}")]
+    PublicValuesWrite(std::io::Error),
+    #[error("invalid arguments to syscall {0}: {1}")]
+    InvalidSyscallArgument(SyscallCode, &'static str),
 }
 
 impl Runtime {
@@ -182,15 +330,53 @@ impl Runtime {
             shard_size: (opts.shard_size as u32) * 4,
             shard_batch_size: opts.shard_batch_size as u32,
             cycle_tracker: HashMap::new(),
+            cycle_spans: CycleSpanStack::default(),
             io_buf: HashMap::new(),
+            stream_guest_output: opts.stream_guest_output,
             trace_buf,
             unconstrained: false,
             unconstrained_state: ForkState::default(),
             syscall_map,
+            custom_syscall_map: HashMap::new(),
             emit_events: true,
             max_syscall_cycles,
             report: Default::default(),
             should_report: false,
+            max_cycles: opts.max_cycles,
+            max_memory: opts.max_memory,
+            allow_nonzero_exit_code: opts.allow_nonzero_exit_code,
+            heap_warning_percent: opts.heap_warning_percent,
+            guest_panic: None,
+            profiler: None,
+            input_source: None,
+            hooks: HookRegistry::default(),
+            recording: None,
+            public_values_sink: None,
+        }
+    }
+
+    /// Fills in the heap usage of the report, and warns if it is above
+    /// [Runtime::heap_warning_percent].
+    fn report_heap_usage(&mut self) {
+        let heap_start = self.program.heap_start();
+        let heap_top = self
+            .state
+            .memory
+            .keys()
+            .filter(|addr| (heap_start..HEAP_END).contains(addr))
+            .max();
+        self.report.heap_bytes = heap_top.map_or(0, |top| (top + 4 - heap_start) as u64);
+        self.report.heap_limit = HEAP_END.saturating_sub(heap_start) as u64;
+        if let Some(percent) = self.heap_warning_percent {
+            if self.report.heap_bytes * 100 >= self.report.heap_limit * percent {
+                tracing::warn!(
+                    "the program used {} of the {} bytes of its heap ({:.2}%), and fails once it \
+                     runs out",
+                    self.report.heap_bytes,
+                    self.report.heap_limit,
+                    self.report.heap_usage_percent()
+                );
+            }
         }
     }
 
@@ -205,12 +391,35 @@ impl Runtime {
         runtime
     }
 
+    /// Serialize the current execution state to `writer` so it can later be resumed with
+    /// [Runtime::resume].
+    ///
+    /// The state includes the registers, memory, input/output streams and shard counters, but not
+    /// the events recorded so far, so it should be taken between calls to `execute_*`.
+    pub fn checkpoint<W: Write>(&self, writer: W) -> bincode::Result<()> {
+        assert!(
+            !self.unconstrained,
+            "cannot checkpoint inside an unconstrained block"
+        );
+        bincode::serialize_into(writer, &self.state)
+    }
+
+    /// Resume a runtime from a checkpoint previously written by [Runtime::checkpoint].
+    pub fn resume<R: Read>(
+        program: Program,
+        reader: R,
+        opts: SP1CoreOpts,
+    ) -> bincode::Result<Self> {
+        let state: ExecutionState = bincode::deserialize_from(reader)?;
+        Ok(Self::recover(program, state, opts))
+    }
+
     /// Get the current values of the registers.
     pub fn registers(&self) -> [u32; 32] {
         let mut registers = [0; 32];
         for i in 0..32 {
             let addr = Register::from_u32(i as u32) as u32;
-            registers[i] = match self.state.memory.get(&addr) {
+            registers[i] = match self.state.memory.get(addr) {
                 Some(record) => record.value,
                 None => 0,
             };
@@ -221,17 +430,23 @@ impl Runtime {
     /// Get the current value of a register.
     pub fn register(&self, register: Register) -> u32 {
         let addr = register as u32;
-        match self.state.memory.get(&addr) {
+        match self.state.memory.get(addr) {
             Some(record) => record.value,
             None => 0,
         }
     }
 
-    /// Get the current value of a word.
+    /// Get the current value of a word. A word which was never accessed has the value it will be
+    /// initialized with, such as the words written by `HINT_READ`.
     pub fn word(&self, addr: u32) -> u32 {
-        match self.state.memory.get(&addr) {
+        match self.state.memory.get(addr) {
             Some(record) => record.value,
-            None => 0,
+            None => self
+                .state
+                .uninitialized_memory
+                .get(addr)
+                .copied()
+                .unwrap_or(0),
         }
     }
 
@@ -280,7 +495,7 @@ impl Runtime {
             Entry::Occupied(entry) => entry.into_mut(),
             Entry::Vacant(entry) => {
                 // If addr has a specific value to be initialized with, use that, otherwise 0.
-                let value = self.state.uninitialized_memory.remove(&addr).unwrap_or(0);
+                let value = self.state.uninitialized_memory.remove(addr).unwrap_or(0);
 
                 // Do not emit memory initialize events for address 0 as that is done in initialize.
                 if addr != 0 {
@@ -301,6 +516,9 @@ impl Runtime {
         record.shard = shard;
         record.timestamp = timestamp;
 
+        // Check any watchpoints on this address.
+        self.fire_watchpoints(WatchKind::Read, addr, value);
+
         // Construct the memory read record.
         MemoryReadRecord::new(value, shard, timestamp, prev_shard, prev_timestamp)
     }
@@ -328,7 +546,7 @@ impl Runtime {
             Entry::Occupied(entry) => entry.into_mut(),
             Entry::Vacant(entry) => {
                 // If addr has a specific value to be initialized with, use that, otherwise 0.
-                let value = self.state.uninitialized_memory.remove(&addr).unwrap_or(0);
+                let value = self.state.uninitialized_memory.remove(addr).unwrap_or(0);
 
                 // Do not emit memory initialize events for address 0 as that is done in initialize.
                 if addr != 0 {
@@ -350,6 +568,10 @@ impl Runtime {
         record.shard = shard;
         record.timestamp = timestamp;
 
+        // Notify any hooks and watchpoints watching this address.
+        self.fire_write_hooks(addr, value);
+        self.fire_watchpoints(WatchKind::Write, addr, value);
+
         // Construct the memory write record.
         MemoryWriteRecord::new(
             value,
@@ -557,6 +779,16 @@ impl Runtime {
         (a, b, c, addr, memory_value)
     }
 
+    /// Fetch the destination register, the input operand values and the value in memory for an
+    /// atomic memory instruction, whose address is the value of op_b.
+    fn atomic_rr(&mut self, instruction: Instruction) -> (Register, u32, u32, u32) {
+        let (rd, rs1, rs2) = instruction.r_type();
+        let c = self.rr(rs2, MemoryAccessPosition::C);
+        let b = self.rr(rs1, MemoryAccessPosition::B);
+        let memory_value = self.word(align(b));
+        (rd, b, c, memory_value)
+    }
+
     /// Fetch the input operand values for a branch instruction.
     fn branch_rr(&mut self, instruction: Instruction) -> (u32, u32, u32) {
         let (rs1, rs2, imm) = instruction.b_type();
@@ -736,6 +968,48 @@ impl Runtime {
                 self.mw_cpu(align(addr), value, MemoryAccessPosition::Memory);
             }
 
+            // Atomic memory instructions, which write the previous value in memory to rd, except
+            // for SC, which always succeeds and writes zero.
+            Opcode::SC
+            | Opcode::AMOSWAP
+            | Opcode::AMOADD
+            | Opcode::AMOXOR
+            | Opcode::AMOAND
+            | Opcode::AMOOR
+            | Opcode::AMOMIN
+            | Opcode::AMOMAX
+            | Opcode::AMOMINU
+            | Opcode::AMOMAXU => {
+                (rd, b, c, memory_read_value) = self.atomic_rr(instruction);
+                addr = b;
+                if addr % 4 != 0 {
+                    return Err(ExecutionError::InvalidMemoryAccess(
+                        instruction.opcode,
+                        addr,
+                    ));
+                }
+                let value = match instruction.opcode {
+                    Opcode::SC | Opcode::AMOSWAP => c,
+                    Opcode::AMOADD => memory_read_value.wrapping_add(c),
+                    Opcode::AMOXOR => memory_read_value ^ c,
+                    Opcode::AMOAND => memory_read_value & c,
+                    Opcode::AMOOR => memory_read_value | c,
+                    Opcode::AMOMIN => (memory_read_value as i32).min(c as i32) as u32,
+                    Opcode::AMOMAX => (memory_read_value as i32).max(c as i32) as u32,
+                    Opcode::AMOMINU => memory_read_value.min(c),
+                    Opcode::AMOMAXU => memory_read_value.max(c),
+                    _ => unreachable!(),
+                };
+                a = if instruction.opcode == Opcode::SC {
+                    0
+                } else {
+                    memory_read_value
+                };
+                memory_store_value = Some(value);
+                self.mw_cpu(align(addr), value, MemoryAccessPosition::Memory);
+                self.rw(rd, a);
+            }
+
             // B-type instructions.
             Opcode::BEQ => {
                 (a, b, c) = self.branch_rr(instruction);
@@ -806,34 +1080,63 @@ impl Runtime {
                 let syscall_id = self.register(t0);
                 c = self.rr(Register::X11, MemoryAccessPosition::C);
                 b = self.rr(Register::X10, MemoryAccessPosition::B);
-                let syscall = SyscallCode::from_u32(syscall_id);
-
-                if self.should_report && !self.unconstrained {
-                    self.report
-                        .syscall_counts
-                        .entry(syscall)
-                        .and_modify(|c| *c += 1)
-                        .or_insert(1);
+
+                // The syscalls of custom precompiles have no `SyscallCode`, so they are not
+                // reported and do not fire the syscall hooks.
+                let custom_syscall = self.custom_syscall_map.get(&syscall_id).cloned();
+                let syscall = custom_syscall
+                    .is_none()
+                    .then(|| SyscallCode::from_u32(syscall_id));
+
+                if let Some(syscall) = syscall {
+                    if self.should_report && !self.unconstrained {
+                        self.report
+                            .syscall_counts
+                            .entry(syscall)
+                            .and_modify(|c| *c += 1)
+                            .or_insert(1);
+                    }
+
+                    self.fire_syscall_hooks(syscall, b, c);
                 }
 
-                let syscall_impl = self.get_syscall(syscall).cloned();
+                let syscall_impl = match syscall {
+                    Some(syscall) => self.get_syscall(syscall).cloned(),
+                    None => custom_syscall,
+                };
                 let mut precompile_rt = SyscallContext::new(self);
                 let (precompile_next_pc, precompile_cycles, returned_exit_code) =
                     if let Some(syscall_impl) = syscall_impl {
                         // Executing a syscall optionally returns a value to write to the t0 register.
                         // If it returns None, we just keep the syscall_id in t0.
                         let res = syscall_impl.execute(&mut precompile_rt, b, c);
+                        if let Some(error) = precompile_rt.error.take() {
+                            return Err(error);
+                        }
                         if let Some(val) = res {
                             a = val;
                         } else {
                             a = syscall_id;
                         }
 
-                        // If the syscall is `HALT` and the exit code is non-zero, return an error.
-                        if syscall == SyscallCode::HALT && precompile_rt.exit_code != 0 {
-                            return Err(ExecutionError::HaltWithNonZeroExitCode(
-                                precompile_rt.exit_code,
-                            ));
+                        // If the syscall is `HALT` and the exit code is non-zero, return an error
+                        // unless nonzero exit codes are allowed.
+                        if syscall == Some(SyscallCode::HALT) {
+                            let exit_code = precompile_rt.exit_code;
+                            if exit_code != 0 && !precompile_rt.rt.allow_nonzero_exit_code {
+                                return Err(match precompile_rt.rt.guest_panic.take() {
+                                    Some(panic) => ExecutionError::Panicked(panic),
+                                    None => ExecutionError::HaltWithNonZeroExitCode(exit_code),
+                                });
+                            }
+                            precompile_rt.rt.report.exit_code = exit_code;
+                            precompile_rt.rt.report.user_values =
+                                precompile_rt.rt.record.public_values.user_values;
+                            precompile_rt
+                                .rt
+                                .report
+                                .panic
+                                .clone_from(&precompile_rt.rt.guest_panic);
                         }
 
                         (
@@ -958,6 +1261,16 @@ impl Runtime {
     /// Executes one cycle of the program, returning whether the program has finished.
     #[inline]
     fn execute_cycle(&mut self) -> Result<bool, ExecutionError> {
+        // If the cycle budget has been exhausted, stop before executing another instruction.
+        if let Some(max_cycles) = self.max_cycles {
+            if self.state.global_clk >= max_cycles {
+                return Err(ExecutionError::OutOfGas(
+                    self.state.global_clk,
+                    self.state.pc,
+                ));
+            }
+        }
+
         // Fetch the instruction at the current program counter.
         let instruction = self.fetch();
 
@@ -965,11 +1278,43 @@ impl Runtime {
         self.log(&instruction);
 
         // Execute the instruction.
+        let pc = self.state.pc;
         self.execute_instruction(instruction)?;
 
+        // If the instruction touched memory beyond the limit, stop and blame it.
+        if let Some(max_memory) = self.max_memory {
+            let touched = self.state.memory.len() as u64 * 4;
+            if touched > max_memory {
+                return Err(ExecutionError::MemoryLimitExceeded(
+                    touched,
+                    pc,
+                    self.state.global_clk,
+                ));
+            }
+        }
+
+        // Sample the call stack if profiling is enabled.
+        if let Some(profiler) = self.profiler.as_mut() {
+            if !self.unconstrained {
+                profiler.record(self.state.global_clk, pc, &instruction, self.state.pc);
+            }
+        }
+
         // Increment the clock.
         self.state.global_clk += 1;
 
+        // Fire the hooks registered for this cycle.
+        self.fire_cycle_hooks();
+
+        // Count the cycle towards the current shard.
+        if self.should_report && !self.unconstrained {
+            let shard = self.state.current_shard as usize;
+            if self.report.shard_cycles.len() < shard {
+                self.report.shard_cycles.resize(shard, 0);
+            }
+            self.report.shard_cycles[shard - 1] += 1;
+        }
+
         // If there's not enough cycles left for another instruction, move to the next shard.
         // We multiply by 4 because clk is incremented by 4 for each normal instruction.
         if !self.unconstrained && self.max_syscall_cycles + self.state.clk >= self.shard_size {
@@ -978,8 +1323,17 @@ impl Runtime {
             self.state.channel = 0;
         }
 
-        Ok(self.state.pc.wrapping_sub(self.program.pc_base)
-            >= (self.program.instructions.len() * 4) as u32)
+        let done = self.state.pc.wrapping_sub(self.program.pc_base)
+            >= (self.program.instructions.len() * 4) as u32;
+
+        // Pause if the instruction hit a watchpoint, unless the program has finished anyway.
+        if let Some(hit) = self.hooks.pending_watchpoint.take() {
+            if !done {
+                return Err(ExecutionError::Watchpoint(hit));
+            }
+        }
+
+        Ok(done)
     }
 
     /// Execute up to `self.shard_batch_size` cycles, returning the events emitted and whether the program ended.
@@ -1035,9 +1389,24 @@ impl Runtime {
         Ok(())
     }
 
-    pub fn dry_run(&mut self) {
+    /// Executes a single cycle of the program, returning whether the program has finished.
+    pub fn step(&mut self) -> Result<bool, ExecutionError> {
+        if self.state.global_clk == 0 {
+            self.initialize();
+        }
+
+        let done = self.execute_cycle()?;
+        if done {
+            self.postprocess();
+        }
+
+        Ok(done)
+    }
+
+    pub fn dry_run(&mut self) -> Result<(), ExecutionError> {
         self.emit_events = false;
-        while !self.execute().unwrap() {}
+        while !self.execute()? {}
+        Ok(())
     }
 
     /// Executes up to `self.shard_batch_size` cycles of the program, returning whether the program has finished.
@@ -1081,7 +1450,7 @@ impl Runtime {
         );
         // Flush remaining stdout/stderr
         for (fd, buf) in self.io_buf.iter() {
-            if !buf.is_empty() {
+            if self.stream_guest_output && !buf.is_empty() {
                 match fd {
                     1 => {
                         println!("stdout: {}", buf);
@@ -1099,12 +1468,17 @@ impl Runtime {
             buf.flush().unwrap();
         }
 
+        if self.should_report {
+            self.report.touched_memory_addresses = self.state.memory.len() as u64;
+            self.report_heap_usage();
+        }
+
         // SECTION: Set up all MemoryInitializeFinalizeEvents needed for memory argument.
         let memory_finalize_events = &mut self.record.memory_finalize_events;
 
         // We handle the addr = 0 case separately, as we constrain it to be 0 in the first row
         // of the memory finalize table so it must be first in the array of events.
-        let addr_0_record = self.state.memory.get(&0u32);
+        let addr_0_record = self.state.memory.get(0u32);
 
         let addr_0_final_record = match addr_0_record {
             Some(record) => record,
@@ -1119,19 +1493,31 @@ impl Runtime {
             addr_0_final_record,
         ));
 
-        for addr in self.state.memory.keys() {
-            if addr == &0 {
+        for (addr, record) in self.state.memory.iter() {
+            if addr == 0 {
                 continue; // We handle addr = 0 separately above.
             }
 
-            let record = *self.state.memory.get(addr).unwrap();
-
             memory_finalize_events.push(MemoryInitializeFinalizeEvent::finalize_from_record(
-                *addr, &record,
+                addr, record,
             ));
         }
     }
 
+    /// Registers the syscall of a custom precompile, of the syscall number `code`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `code` is not a valid number for a custom syscall, as checked by
+    /// [check_custom_syscall_code].
+    pub fn register_custom_syscall(&mut self, code: u32, syscall: Arc<dyn Syscall>) {
+        if let Err(e) = check_custom_syscall_code(code, syscall.as_ref()) {
+            panic!("{}", e);
+        }
+        self.max_syscall_cycles = self.max_syscall_cycles.max(syscall.num_extra_cycles());
+        self.custom_syscall_map.insert(code, syscall);
+    }
+
     fn get_syscall(&mut self, code: SyscallCode) -> Option<&Arc<dyn Syscall>> {
         self.syscall_map.get(&code)
     }
@@ -1148,7 +1534,7 @@ pub mod tests {
         },
     };
 
-    use super::{Instruction, Opcode, Program, Runtime};
+    use super::{ExecutionError, Instruction, Opcode, Program, Runtime, SyscallCode, HEAP_END};
 
     pub fn simple_program() -> Program {
         let instructions = vec![
@@ -1179,6 +1565,139 @@ pub mod tests {
         assert_eq!(runtime.register(Register::X31), 42);
     }
 
+    #[test]
+    fn test_nonzero_exit_code() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::HALT as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 3, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        match runtime.run() {
+            Err(ExecutionError::HaltWithNonZeroExitCode(3)) => {}
+            res => panic!("expected a nonzero exit code error, got {:?}", res),
+        }
+
+        let opts = SP1CoreOpts {
+            allow_nonzero_exit_code: true,
+            ..Default::default()
+        };
+        let mut runtime = Runtime::new(program, opts);
+        runtime.run().unwrap();
+        assert_eq!(runtime.report.exit_code, 3);
+    }
+
+    #[test]
+    fn test_simple_program_run_report() {
+        let program = simple_program();
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.report.shard_cycles, vec![3]);
+        assert_eq!(runtime.report.total_instruction_count(), 3);
+        // Registers x0, x29, x30 and x31.
+        assert_eq!(runtime.report.touched_memory_addresses, 4);
+    }
+
+    #[test]
+    fn test_guest_panic() {
+        let message = b"panicked at src/main.rs:7:9:\noops";
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::PANIC as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, message.len() as u32, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::HALT as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 1, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let mut program = Program::new(instructions, 0, 0);
+        for (i, word) in message.chunks(4).enumerate() {
+            let mut bytes = [0u8; 4];
+            bytes[..word.len()].copy_from_slice(word);
+            program
+                .memory_image
+                .insert(0x1000 + 4 * i as u32, u32::from_le_bytes(bytes));
+        }
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        match runtime.run() {
+            Err(ExecutionError::Panicked(panic)) => {
+                assert_eq!(panic.message, "oops");
+                assert_eq!(panic.file.as_deref(), Some("src/main.rs"));
+                assert_eq!((panic.line, panic.column), (Some(7), Some(9)));
+            }
+            res => panic!("expected a panic error, got {:?}", res),
+        }
+
+        let opts = SP1CoreOpts {
+            allow_nonzero_exit_code: true,
+            ..Default::default()
+        };
+        let mut runtime = Runtime::new(program, opts);
+        runtime.run().unwrap();
+        assert_eq!(runtime.report.exit_code, 1);
+        assert_eq!(runtime.report.panic.unwrap().message, "oops");
+    }
+
+    #[test]
+    fn test_guest_output_report() {
+        let write = |fd: u32, addr: u32, len: u32| {
+            vec![
+                Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, fd, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, addr, false, true),
+                Instruction::new(Opcode::ADD, 12, 0, len, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]
+        };
+        let instructions = [
+            write(1, 0x1000, 4),
+            write(2, 0x1004, 4),
+            write(1, 0x1000, 3),
+        ]
+        .concat();
+        let mut program = Program::new(instructions, 0, 0);
+        program
+            .memory_image
+            .insert(0x1000, u32::from_le_bytes(*b"hi\nx"));
+        program
+            .memory_image
+            .insert(0x1004, u32::from_le_bytes(*b"err\n"));
+
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.report.stdout, "hi\nxhi\n");
+        assert_eq!(runtime.report.stderr, "err\n");
+    }
+
+    #[test]
+    fn test_hints_read_before_input() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::WRITE as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, 4, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0x1000, false, true),
+            Instruction::new(Opcode::ADD, 12, 0, 3, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::HINT_LEN as u32, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 31, 5, 0, false, true),
+        ];
+        let mut program = Program::new(instructions, 0, 0);
+        program
+            .memory_image
+            .insert(0x1000, u32::from_le_bytes(*b"abc\0"));
+
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.write_stdin_slice(&[1, 2, 3, 4, 5]);
+        runtime.run().unwrap();
+        // The hint of 3 bytes is read before the input of 5 bytes.
+        assert_eq!(runtime.register(Register::X31), 3);
+        assert_eq!(runtime.state.input_stream[0], b"abc");
+        assert_eq!(runtime.state.pending_hints, 1);
+    }
+
     #[test]
     fn test_ssz_withdrawals_program_run_report() {
         let program = ssz_withdrawals_program();
@@ -1226,9 +1745,120 @@ pub mod tests {
                     (HALT, 1),
                 ]
                 .into(),
+                ..runtime.report.clone()
             }
         });
         assert_eq!(runtime.report.total_instruction_count(), 2757356);
+        assert_eq!(runtime.report.total_cycle_count(), 2757356);
+        assert_eq!(runtime.report.num_shards(), 1);
+    }
+
+    #[test]
+    fn test_memory_program_run_report() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 5, false, true),
+            Instruction::new(Opcode::SW, 29, 0, 0x1000, false, true),
+            Instruction::new(Opcode::SW, 29, 0, 0x1004, false, true),
+            Instruction::new(Opcode::SW, 29, 0, 0x1000, false, true),
+            Instruction::new(Opcode::LW, 28, 0, 0x1004, false, true),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(runtime.report.shard_cycles, vec![5]);
+        // Registers x0, x28 and x29, and the words at 0x1000 and 0x1004.
+        assert_eq!(runtime.report.touched_memory_addresses, 5);
+    }
+
+    #[test]
+    fn test_max_cycles() {
+        let program = simple_program();
+        let opts = SP1CoreOpts {
+            max_cycles: Some(2),
+            ..Default::default()
+        };
+        let mut runtime = Runtime::new(program, opts);
+        match runtime.run() {
+            Err(ExecutionError::OutOfGas(cycles, pc)) => {
+                assert_eq!(cycles, 2);
+                assert_eq!(pc, 8);
+            }
+            res => panic!("expected out of gas error, got {:?}", res),
+        }
+
+        let program = simple_program();
+        let opts = SP1CoreOpts {
+            max_cycles: Some(3),
+            ..Default::default()
+        };
+        let mut runtime = Runtime::new(program, opts);
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X31), 42);
+    }
+
+    #[test]
+    fn test_max_memory() {
+        // The program touches x0, x29, x30 and x31, so writing x31 exceeds a 12 byte limit.
+        let opts = SP1CoreOpts {
+            max_memory: Some(12),
+            ..Default::default()
+        };
+        let mut runtime = Runtime::new(simple_program(), opts);
+        match runtime.run() {
+            Err(ExecutionError::MemoryLimitExceeded(touched, pc, clk)) => {
+                assert_eq!(touched, 16);
+                assert_eq!(pc, 8);
+                assert_eq!(clk, 2);
+            }
+            res => panic!("expected memory limit error, got {:?}", res),
+        }
+
+        let opts = SP1CoreOpts {
+            max_memory: Some(16),
+            ..Default::default()
+        };
+        let mut runtime = Runtime::new(simple_program(), opts);
+        runtime.run().unwrap();
+    }
+
+    #[test]
+    fn test_heap_usage_report() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 5, false, true),
+            Instruction::new(Opcode::SW, 29, 0, 0x1800, false, true),
+            Instruction::new(Opcode::SW, 29, 0, 0x2000, false, true),
+        ];
+        let mut program = Program::new(instructions, 0, 0);
+        program.memory_image.insert(0x1000, 1);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        // The heap starts after the memory image, and ends at its highest touched word.
+        assert_eq!(runtime.report.heap_bytes, 0x2004 - 0x1004);
+        assert_eq!(runtime.report.heap_limit, (HEAP_END - 0x1004) as u64);
+        assert!(runtime.report.heap_usage_percent() < 1.0);
+    }
+
+    #[test]
+    fn test_checkpoint_resume() {
+        let opts = SP1CoreOpts {
+            max_cycles: Some(2),
+            ..Default::default()
+        };
+        let mut runtime = Runtime::new(simple_program(), opts);
+        assert!(runtime.run().is_err());
+
+        let mut checkpoint = Vec::new();
+        runtime.checkpoint(&mut checkpoint).unwrap();
+
+        let mut runtime = Runtime::resume(
+            simple_program(),
+            checkpoint.as_slice(),
+            SP1CoreOpts::default(),
+        )
+        .unwrap();
+        assert_eq!(runtime.state.global_clk, 2);
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(Register::X31), 42);
     }
 
     #[test]
@@ -1841,4 +2471,89 @@ pub mod tests {
         assert_eq!(runtime.register(Register::X12), 0x12346525);
         assert_eq!(runtime.register(Register::X11), 0x65256525);
     }
+
+    pub fn simple_atomic_program() -> Program {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 0x27654320, false, true),
+            Instruction::new(Opcode::ADD, 30, 0, 0xfffffffe, false, true),
+            Instruction::new(Opcode::ADD, 31, 0, 5, false, true),
+            // SC and AMOSWAP
+            Instruction::new(Opcode::SC, 28, 29, 30, false, false),
+            Instruction::new(Opcode::AMOSWAP, 27, 29, 31, false, false),
+            // AMOADD, AMOXOR, AMOAND and AMOOR
+            Instruction::new(Opcode::AMOADD, 26, 29, 31, false, false),
+            Instruction::new(Opcode::AMOXOR, 25, 29, 31, false, false),
+            Instruction::new(Opcode::AMOOR, 24, 29, 30, false, false),
+            Instruction::new(Opcode::AMOAND, 23, 29, 31, false, false),
+            // AMOMIN, AMOMAX, AMOMINU and AMOMAXU
+            Instruction::new(Opcode::AMOMAX, 0, 29, 30, false, false),
+            Instruction::new(Opcode::AMOMIN, 22, 29, 30, false, false),
+            Instruction::new(Opcode::AMOMAXU, 21, 29, 31, false, false),
+            Instruction::new(Opcode::AMOMINU, 20, 29, 31, false, false),
+            Instruction::new(Opcode::LW, 19, 29, 0, false, true),
+        ];
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_simple_atomic_program_run() {
+        let program = simple_atomic_program();
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        // Assert SC & AMOSWAP cases
+        assert_eq!(runtime.register(Register::X28), 0);
+        assert_eq!(runtime.register(Register::X27), 0xfffffffe);
+
+        // Assert AMOADD, AMOXOR, AMOOR & AMOAND cases
+        assert_eq!(runtime.register(Register::X26), 5);
+        assert_eq!(runtime.register(Register::X25), 10);
+        assert_eq!(runtime.register(Register::X24), 15);
+        assert_eq!(runtime.register(Register::X23), 0xffffffff);
+
+        // Assert AMOMAX, AMOMIN, AMOMAXU & AMOMINU cases
+        assert_eq!(runtime.register(Register::X0), 0);
+        assert_eq!(runtime.register(Register::X22), 5);
+        assert_eq!(runtime.register(Register::X21), 0xfffffffe);
+        assert_eq!(runtime.register(Register::X20), 0xfffffffe);
+        assert_eq!(runtime.register(Register::X19), 5);
+    }
+
+    #[test]
+    fn test_atomic_unaligned_address() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 0x27654322, false, true),
+            Instruction::new(Opcode::AMOADD, 28, 29, 29, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        match runtime.run() {
+            Err(ExecutionError::InvalidMemoryAccess(Opcode::AMOADD, 0x27654322)) => {}
+            res => panic!("expected an invalid memory access error, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_syscall_unaligned_pointer() {
+        let instructions = vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::SHA_EXTEND as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, 0x1002, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, 0, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        // The first word `SHA_EXTEND` reads is `w[1]`.
+        match runtime.run() {
+            Err(ExecutionError::InvalidMemoryAccess(Opcode::ECALL, 0x1006)) => {}
+            res => panic!("expected an invalid memory access error, got {:?}", res),
+        }
+    }
 }
@@ -60,6 +60,18 @@ pub enum Opcode {
 
     // Miscellaneaous instructions.
     UNIMP = 39,
+
+    // Atomic memory instructions. LR.W is executed as a LW, so it has no opcode of its own.
+    SC = 40,
+    AMOSWAP = 41,
+    AMOADD = 42,
+    AMOXOR = 43,
+    AMOAND = 44,
+    AMOOR = 45,
+    AMOMIN = 46,
+    AMOMAX = 47,
+    AMOMINU = 48,
+    AMOMAXU = 49,
 }
 
 impl Display for Opcode {
@@ -109,6 +121,16 @@ impl Opcode {
             Opcode::REM => "rem",
             Opcode::REMU => "remu",
             Opcode::UNIMP => "unimp",
+            Opcode::SC => "sc.w",
+            Opcode::AMOSWAP => "amoswap.w",
+            Opcode::AMOADD => "amoadd.w",
+            Opcode::AMOXOR => "amoxor.w",
+            Opcode::AMOAND => "amoand.w",
+            Opcode::AMOOR => "amoor.w",
+            Opcode::AMOMIN => "amomin.w",
+            Opcode::AMOMAX => "amomax.w",
+            Opcode::AMOMINU => "amominu.w",
+            Opcode::AMOMAXU => "amomaxu.w",
         }
     }
 }
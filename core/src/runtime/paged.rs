@@ -0,0 +1,307 @@
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::marker::PhantomData;
+
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The log2 of the number of words in a page.
+const LOG_PAGE_LEN: usize = 10;
+
+/// The number of words in a page.
+const PAGE_LEN: usize = 1 << LOG_PAGE_LEN;
+
+/// The number of registers, which are addressed by their index rather than a word address.
+const NUM_REGISTERS: usize = 32;
+
+type Page<V> = Box<[Option<V>]>;
+
+/// A sparse map from addresses to values which allocates memory a page at a time.
+///
+/// Addresses `0..32` are the registers and are stored separately. All other addresses written to
+/// must be word aligned, and are grouped into pages of [PAGE_LEN] consecutive words which are only
+/// allocated once an address in them is written to, so large untouched regions cost a single
+/// pointer in the page table.
+#[derive(Clone)]
+pub struct PagedMemory<V> {
+    registers: [Option<V>; NUM_REGISTERS],
+    pages: Vec<Option<Page<V>>>,
+    len: usize,
+}
+
+/// A view into a single address of a [PagedMemory], which may either be vacant or occupied.
+pub enum Entry<'a, V> {
+    Occupied(OccupiedEntry<'a, V>),
+    Vacant(VacantEntry<'a, V>),
+}
+
+/// A view into an occupied address of a [PagedMemory].
+pub struct OccupiedEntry<'a, V> {
+    slot: &'a mut Option<V>,
+}
+
+/// A view into a vacant address of a [PagedMemory].
+pub struct VacantEntry<'a, V> {
+    slot: &'a mut Option<V>,
+    len: &'a mut usize,
+}
+
+impl<'a, V> OccupiedEntry<'a, V> {
+    pub fn get(&self) -> &V {
+        self.slot.as_ref().unwrap()
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        self.slot.as_mut().unwrap()
+    }
+}
+
+impl<'a, V> VacantEntry<'a, V> {
+    pub fn insert(self, value: V) -> &'a mut V {
+        *self.len += 1;
+        self.slot.insert(value)
+    }
+}
+
+impl<V: Copy> PagedMemory<V> {
+    pub fn new() -> Self {
+        Self {
+            registers: [None; NUM_REGISTERS],
+            pages: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Split an aligned address into its page index and offset within the page.
+    #[inline]
+    fn locate(addr: u32) -> (usize, usize) {
+        let word = (addr >> 2) as usize;
+        (word >> LOG_PAGE_LEN, word & (PAGE_LEN - 1))
+    }
+
+    /// Get the slot for `addr`, allocating its page if necessary.
+    ///
+    /// Callers must check that `addr` is aligned: the CPU and `SyscallContext` turn unaligned
+    /// guest addresses into an `InvalidMemoryAccess` error before they reach memory.
+    #[inline]
+    fn slot_mut<'a>(
+        registers: &'a mut [Option<V>; NUM_REGISTERS],
+        pages: &'a mut Vec<Option<Page<V>>>,
+        addr: u32,
+    ) -> &'a mut Option<V> {
+        if (addr as usize) < NUM_REGISTERS {
+            return &mut registers[addr as usize];
+        }
+        assert_eq!(addr % 4, 0, "address {} is not aligned", addr);
+        let (page, offset) = Self::locate(addr);
+        if pages.len() <= page {
+            pages.resize_with(page + 1, || None);
+        }
+        let page = pages[page].get_or_insert_with(|| vec![None; PAGE_LEN].into_boxed_slice());
+        &mut page[offset]
+    }
+
+    #[inline]
+    pub fn get(&self, addr: u32) -> Option<&V> {
+        if (addr as usize) < NUM_REGISTERS {
+            return self.registers[addr as usize].as_ref();
+        } else if addr % 4 != 0 {
+            return None;
+        }
+        let (page, offset) = Self::locate(addr);
+        self.pages.get(page)?.as_ref()?[offset].as_ref()
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, addr: u32) -> Option<&mut V> {
+        if (addr as usize) < NUM_REGISTERS {
+            return self.registers[addr as usize].as_mut();
+        } else if addr % 4 != 0 {
+            return None;
+        }
+        let (page, offset) = Self::locate(addr);
+        self.pages.get_mut(page)?.as_mut()?[offset].as_mut()
+    }
+
+    #[inline]
+    pub fn entry(&mut self, addr: u32) -> Entry<'_, V> {
+        let Self {
+            registers,
+            pages,
+            len,
+        } = self;
+        let slot = Self::slot_mut(registers, pages, addr);
+        if slot.is_some() {
+            Entry::Occupied(OccupiedEntry { slot })
+        } else {
+            Entry::Vacant(VacantEntry { slot, len })
+        }
+    }
+
+    /// Insert a value at `addr`, returning the previous value if there was one.
+    #[inline]
+    pub fn insert(&mut self, addr: u32, value: V) -> Option<V> {
+        let prev = Self::slot_mut(&mut self.registers, &mut self.pages, addr).replace(value);
+        if prev.is_none() {
+            self.len += 1;
+        }
+        prev
+    }
+
+    /// Remove the value at `addr`, returning it if there was one.
+    #[inline]
+    pub fn remove(&mut self, addr: u32) -> Option<V> {
+        let prev = if (addr as usize) < NUM_REGISTERS {
+            self.registers[addr as usize].take()
+        } else if addr % 4 != 0 {
+            None
+        } else {
+            let (page, offset) = Self::locate(addr);
+            self.pages
+                .get_mut(page)
+                .and_then(|page| page.as_mut())
+                .and_then(|page| page[offset].take())
+        };
+        if prev.is_some() {
+            self.len -= 1;
+        }
+        prev
+    }
+
+    /// The number of addresses with a value.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterate over the addresses with a value and their values, in increasing address order.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &V)> + '_ {
+        let registers = self
+            .registers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, value)| value.as_ref().map(|value| (i as u32, value)));
+        let pages = self
+            .pages
+            .iter()
+            .enumerate()
+            .filter_map(|(i, page)| page.as_ref().map(|page| (i, page)))
+            .flat_map(|(i, page)| {
+                page.iter().enumerate().filter_map(move |(offset, value)| {
+                    let addr = (((i << LOG_PAGE_LEN) + offset) << 2) as u32;
+                    value.as_ref().map(|value| (addr, value))
+                })
+            });
+        registers.chain(pages)
+    }
+
+    /// Iterate over the addresses with a value, in increasing order.
+    pub fn keys(&self) -> impl Iterator<Item = u32> + '_ {
+        self.iter().map(|(addr, _)| addr)
+    }
+}
+
+impl<V: Copy> Default for PagedMemory<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Copy> FromIterator<(u32, V)> for PagedMemory<V> {
+    fn from_iter<I: IntoIterator<Item = (u32, V)>>(iter: I) -> Self {
+        let mut memory = Self::new();
+        for (addr, value) in iter {
+            memory.insert(addr, value);
+        }
+        memory
+    }
+}
+
+impl<V: Copy + Debug> Debug for PagedMemory<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<V: Copy + Serialize> Serialize for PagedMemory<V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len))?;
+        for (addr, value) in self.iter() {
+            map.serialize_entry(&addr, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de, V: Copy + Deserialize<'de>> Deserialize<'de> for PagedMemory<V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PagedMemoryVisitor<V>(PhantomData<V>);
+
+        impl<'de, V: Copy + Deserialize<'de>> Visitor<'de> for PagedMemoryVisitor<V> {
+            type Value = PagedMemory<V>;
+
+            fn expecting(&self, f: &mut Formatter) -> FmtResult {
+                f.write_str("a map from addresses to values")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut memory = PagedMemory::new();
+                while let Some((addr, value)) = access.next_entry()? {
+                    memory.insert(addr, value);
+                }
+                Ok(memory)
+            }
+        }
+
+        deserializer.deserialize_map(PagedMemoryVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Entry, PagedMemory};
+
+    #[test]
+    fn test_paged_memory() {
+        let mut memory = PagedMemory::<u32>::new();
+        assert!(memory.is_empty());
+
+        assert_eq!(memory.insert(5, 1), None);
+        assert_eq!(memory.insert(0x0020_0000, 2), None);
+        assert_eq!(memory.insert(0x7800_0000, 3), None);
+        assert_eq!(memory.insert(0x0020_0000, 4), Some(2));
+        assert_eq!(memory.len(), 3);
+
+        assert_eq!(memory.get(5), Some(&1));
+        assert_eq!(memory.get(0x0020_0000), Some(&4));
+        assert_eq!(memory.get(0x0020_0004), None);
+        assert_eq!(memory.get(0xffff_fffc), None);
+
+        match memory.entry(0x0020_0004) {
+            Entry::Vacant(entry) => *entry.insert(5) += 1,
+            Entry::Occupied(_) => panic!("expected a vacant entry"),
+        }
+        match memory.entry(0x0020_0004) {
+            Entry::Occupied(entry) => assert_eq!(*entry.get(), 6),
+            Entry::Vacant(_) => panic!("expected an occupied entry"),
+        }
+
+        assert_eq!(memory.remove(0x7800_0000), Some(3));
+        assert_eq!(memory.remove(0x7800_0000), None);
+        assert_eq!(
+            memory.iter().collect::<Vec<_>>(),
+            vec![(5, &1), (0x0020_0000, &4), (0x0020_0004, &6)]
+        );
+
+        let bytes = bincode::serialize(&memory).unwrap();
+        let deserialized: PagedMemory<u32> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(
+            deserialized.iter().collect::<Vec<_>>(),
+            memory.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(deserialized.len(), 3);
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+
+use addr2line::gimli::{EndianRcSlice, RunTimeEndian};
+use addr2line::object::File;
+use elf::abi::STT_FUNC;
+use elf::endian::LittleEndian;
+use elf::ElfBytes;
+
+use super::{Instruction, Opcode, Register};
+use crate::disassembler::ElfError;
+
+/// The DWARF debug info of a guest ELF, which tells the functions inlined at each address.
+type DebugInfo = addr2line::Context<EndianRcSlice<RunTimeEndian>>;
+
+/// A function symbol from the guest ELF, covering the addresses `[start, end)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSymbol {
+    pub start: u32,
+    pub end: u32,
+    pub name: String,
+}
+
+/// A sampling profiler for guest programs.
+///
+/// Every `sample_rate` cycles the profiler records the current call stack, reconstructed from
+/// `jal`/`jalr` instructions that link through `ra`. The samples are symbolized against the DWARF
+/// debug info of the guest ELF if it has any, which also names the frames of inlined functions,
+/// and against its function symbols otherwise. The samples can be written in the folded stack
+/// format understood by flamegraph tools, e.g.
+/// `rustfilt < profile.folded | inferno-flamegraph > flamegraph.svg`.
+#[derive(Debug, Clone)]
+pub struct Profiler {
+    /// Function symbols sorted by start address.
+    symbols: Vec<FunctionSymbol>,
+
+    /// The guest ELF, whose debug info symbolizes the samples when they are written.
+    elf: Option<Vec<u8>>,
+
+    /// How many cycles to execute between samples.
+    sample_rate: u64,
+
+    /// The addresses of the call instructions for each active frame.
+    call_stack: Vec<u32>,
+
+    /// The number of samples taken for each call stack, keyed by the addresses of its frames.
+    samples: HashMap<Vec<u32>, u64>,
+}
+
+impl Profiler {
+    /// Create a profiler for the given guest ELF, sampling every `sample_rate` cycles. Fails if the
+    /// ELF or its symbol table cannot be parsed.
+    pub fn new(elf: &[u8], sample_rate: u64) -> Result<Self, ElfError> {
+        let bytes = elf.to_vec();
+        let elf = ElfBytes::<LittleEndian>::minimal_parse(elf)
+            .map_err(|e| ElfError::Malformed(e.to_string()))?;
+        let mut symbols = Vec::new();
+        let symbol_table = elf
+            .symbol_table()
+            .map_err(|e| ElfError::Malformed(e.to_string()))?;
+        if let Some((symtab, strtab)) = symbol_table {
+            for sym in symtab.iter() {
+                if sym.st_symtype() != STT_FUNC || sym.st_size == 0 {
+                    continue;
+                }
+                let name = strtab.get(sym.st_name as usize).unwrap_or("<unknown>");
+                symbols.push(FunctionSymbol {
+                    start: sym.st_value as u32,
+                    end: (sym.st_value + sym.st_size) as u32,
+                    name: name.to_string(),
+                });
+            }
+        }
+        let mut profiler = Self::from_symbols(symbols, sample_rate);
+        profiler.elf = Some(bytes);
+        Ok(profiler)
+    }
+
+    /// Create a profiler from an explicit list of function symbols.
+    pub fn from_symbols(mut symbols: Vec<FunctionSymbol>, sample_rate: u64) -> Self {
+        assert!(sample_rate > 0, "sample rate must be positive");
+        symbols.sort_by_key(|sym| sym.start);
+        Self {
+            symbols,
+            elf: None,
+            sample_rate,
+            call_stack: Vec::new(),
+            samples: HashMap::new(),
+        }
+    }
+
+    /// Record an executed instruction at `pc` on cycle `clk` which moved execution to `next_pc`.
+    pub fn record(&mut self, clk: u64, pc: u32, instruction: &Instruction, next_pc: u32) {
+        if clk % self.sample_rate == 0 {
+            let stack = self
+                .call_stack
+                .iter()
+                .copied()
+                .chain(std::iter::once(pc))
+                .collect();
+            *self.samples.entry(stack).or_insert(0) += 1;
+        }
+
+        let ra = Register::X1 as u32;
+        match instruction.opcode {
+            // A jump that links through `ra` is a call.
+            Opcode::JAL | Opcode::JALR if instruction.op_a == ra => self.call_stack.push(pc),
+            // `jalr x0, 0(ra)` is a return.
+            Opcode::JALR
+                if instruction.op_a == Register::X0 as u32
+                    && instruction.op_b == ra
+                    && instruction.op_c == 0 =>
+            {
+                self.call_stack.pop();
+            }
+            _ => {}
+        }
+
+        // Bound the stack in case of calls that never return (e.g. into `panic`).
+        if self.call_stack.len() > 4096 || next_pc == 0 {
+            self.call_stack.clear();
+        }
+    }
+
+    /// Find the index of the symbol containing `addr`.
+    fn lookup(&self, addr: u32) -> Option<usize> {
+        let idx = self.symbols.partition_point(|sym| sym.start <= addr);
+        idx.checked_sub(1).filter(|&i| addr < self.symbols[i].end)
+    }
+
+    /// The total number of samples taken.
+    pub fn num_samples(&self) -> u64 {
+        self.samples.values().sum()
+    }
+
+    /// Write the samples in the folded stack format, one `caller;callee count` line per stack.
+    pub fn write_folded<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let debug_info = self.elf.as_deref().and_then(Self::debug_info);
+        let mut frames = HashMap::new();
+        let mut lines = BTreeMap::new();
+        for (stack, count) in self.samples.iter() {
+            let names = stack
+                .iter()
+                .flat_map(|addr| {
+                    frames
+                        .entry(*addr)
+                        .or_insert_with(|| self.frames(*addr, debug_info.as_ref()))
+                        .clone()
+                })
+                .collect::<Vec<_>>();
+            *lines.entry(names.join(";")).or_insert(0) += *count;
+        }
+        for (stack, count) in lines {
+            writeln!(writer, "{} {}", stack, count)?;
+        }
+        Ok(())
+    }
+
+    /// The names of the frames at `addr`, from the function which contains it to the innermost
+    /// function inlined into it. Falls back to the symbol containing `addr` if the debug info does
+    /// not cover it.
+    fn frames(&self, addr: u32, debug_info: Option<&DebugInfo>) -> Vec<String> {
+        let mut names = Vec::new();
+        if let Some(Ok(mut frames)) =
+            debug_info.map(|debug_info| debug_info.find_frames(addr as u64).skip_all_loads())
+        {
+            // The frames are ordered from the innermost inlined function outwards.
+            while let Ok(Some(frame)) = frames.next() {
+                if let Some(name) = frame.function.as_ref().and_then(|f| f.raw_name().ok()) {
+                    names.push(name.into_owned());
+                }
+            }
+            names.reverse();
+        }
+        if names.is_empty() {
+            names.push(match self.lookup(addr) {
+                Some(i) => self.symbols[i].name.clone(),
+                None => "<unknown>".to_string(),
+            });
+        }
+        names
+    }
+
+    /// Parse the DWARF debug info of `elf`, or `None` if it has none.
+    fn debug_info(elf: &[u8]) -> Option<DebugInfo> {
+        let file = File::parse(elf).ok()?;
+        DebugInfo::new(&file).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FunctionSymbol, Profiler};
+    use crate::runtime::{Instruction, Opcode, Program, Runtime};
+    use crate::utils::SP1CoreOpts;
+
+    const PROFILE_ELF: &[u8] =
+        include_bytes!("../../../tests/profile/elf/riscv32im-succinct-zkvm-elf");
+
+    #[test]
+    fn test_profiler_folded_stacks() {
+        let symbols = vec![
+            FunctionSymbol {
+                start: 0x100,
+                end: 0x200,
+                name: "main".to_string(),
+            },
+            FunctionSymbol {
+                start: 0x200,
+                end: 0x300,
+                name: "hash".to_string(),
+            },
+        ];
+        let mut profiler = Profiler::from_symbols(symbols, 1);
+
+        let add = Instruction::new(Opcode::ADD, 5, 0, 1, false, true);
+        let call = Instruction::new(Opcode::JAL, 1, 0x100, 0, true, true);
+        let ret = Instruction::new(Opcode::JALR, 0, 1, 0, false, true);
+
+        profiler.record(0, 0x100, &add, 0x104);
+        profiler.record(1, 0x104, &call, 0x204);
+        profiler.record(2, 0x204, &add, 0x208);
+        profiler.record(3, 0x208, &add, 0x20c);
+        profiler.record(4, 0x20c, &ret, 0x108);
+        profiler.record(5, 0x108, &add, 0x10c);
+
+        let mut folded = Vec::new();
+        profiler.write_folded(&mut folded).unwrap();
+        assert_eq!(String::from_utf8(folded).unwrap(), "main 3\nmain;hash 3\n");
+        assert_eq!(profiler.num_samples(), 6);
+    }
+    #[test]
+    fn test_profiler_inlined_functions() {
+        assert!(Profiler::new(b"not an elf", 10).is_err());
+
+        let mut runtime = Runtime::new(Program::from(PROFILE_ELF), SP1CoreOpts::default());
+        runtime.profiler = Some(Profiler::new(PROFILE_ELF, 10).unwrap());
+        runtime.run_untraced().unwrap();
+
+        let mut folded = Vec::new();
+        runtime.profiler.unwrap().write_folded(&mut folded).unwrap();
+        let folded = String::from_utf8(folded).unwrap();
+
+        // The function is always inlined, so only the debug info of the ELF names its frames.
+        assert!(folded
+            .lines()
+            .any(|line| line.contains("sum_of_squares;") && line.contains(";inlined_square")));
+    }
+}
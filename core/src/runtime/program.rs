@@ -18,3 +18,19 @@ pub struct Program {
     /// The initial memory image, useful for global constants.
     pub memory_image: BTreeMap<u32, u32>,
 }
+
+/// The address where the stack of programs starts, from which it grows down.
+pub const STACK_TOP: u32 = 0x0020_0400;
+
+/// The address where the memory reserved for the system starts, which ends the heap of programs.
+pub const HEAP_END: u32 = 0x0C00_0000;
+
+impl Program {
+    /// The address where the heap of the program starts, right after its memory image.
+    pub fn heap_start(&self) -> u32 {
+        match self.memory_image.last_key_value() {
+            Some((addr, _)) => addr + 4,
+            None => self.pc_base + 4 * self.instructions.len() as u32,
+        }
+    }
+}
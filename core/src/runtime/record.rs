@@ -5,11 +5,11 @@ use std::sync::Arc;
 
 use itertools::Itertools;
 use p3_field::AbstractField;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use super::program::Program;
 use super::Opcode;
-use crate::air::PublicValues;
+use crate::air::{PublicValues, PV_VERSION};
 use crate::alu::AluEvent;
 use crate::bytes::event::ByteRecord;
 use crate::bytes::ByteLookupEvent;
@@ -17,9 +17,17 @@ use crate::cpu::CpuEvent;
 use crate::runtime::MemoryInitializeFinalizeEvent;
 use crate::runtime::MemoryRecordEnum;
 use crate::stark::MachineRecord;
+use crate::syscall::precompiles::aes::{AesRoundEvent, GhashMulEvent};
+use crate::syscall::precompiles::bigint::BigUintMulModEvent;
 use crate::syscall::precompiles::blake3::Blake3CompressInnerEvent;
+use crate::syscall::precompiles::chacha20::ChaCha20PermuteEvent;
+use crate::syscall::precompiles::ecrecover::Secp256k1EcrecoverEvent;
 use crate::syscall::precompiles::edwards::EdDecompressEvent;
-use crate::syscall::precompiles::keccak256::KeccakPermuteEvent;
+use crate::syscall::precompiles::fp::FpOpEvent;
+use crate::syscall::precompiles::gf256::Gf256MacEvent;
+use crate::syscall::precompiles::keccak256::{KeccakAbsorbEvent, KeccakPermuteEvent};
+use crate::syscall::precompiles::poseidon2::Poseidon2PermuteEvent;
+use crate::syscall::precompiles::ripemd160::Ripemd160CompressEvent;
 use crate::syscall::precompiles::sha256::{ShaCompressEvent, ShaExtendEvent};
 use crate::syscall::precompiles::uint256::Uint256MulEvent;
 use crate::syscall::precompiles::ECDecompressEvent;
@@ -73,6 +81,8 @@ pub struct ExecutionRecord {
 
     pub keccak_permute_events: Vec<KeccakPermuteEvent>,
 
+    pub keccak_absorb_events: Vec<KeccakAbsorbEvent>,
+
     pub ed_add_events: Vec<ECAddEvent>,
 
     pub ed_decompress_events: Vec<EdDecompressEvent>,
@@ -101,6 +111,38 @@ pub struct ExecutionRecord {
 
     pub bls12381_decompress_events: Vec<ECDecompressEvent>,
 
+    pub bls12381_fp_events: Vec<FpOpEvent>,
+
+    pub secp256r1_add_events: Vec<ECAddEvent>,
+
+    pub secp256r1_double_events: Vec<ECDoubleEvent>,
+
+    pub uint2048_mulmod_events: Vec<BigUintMulModEvent>,
+
+    pub uint4096_mulmod_events: Vec<BigUintMulModEvent>,
+
+    pub uint384_mulmod_events: Vec<BigUintMulModEvent>,
+
+    pub uint512_mulmod_events: Vec<BigUintMulModEvent>,
+
+    pub aes_round_events: Vec<AesRoundEvent>,
+
+    pub ghash_mul_events: Vec<GhashMulEvent>,
+
+    pub chacha20_permute_events: Vec<ChaCha20PermuteEvent>,
+
+    pub ripemd160_compress_events: Vec<Ripemd160CompressEvent>,
+
+    pub secp256k1_ecrecover_events: Vec<Secp256k1EcrecoverEvent>,
+
+    pub gf256_mac_events: Vec<Gf256MacEvent>,
+
+    pub poseidon2_permute_events: Vec<Poseidon2PermuteEvent>,
+
+    /// The events of the custom precompiles, by syscall number, serialized with bincode. See
+    /// [crate::stark::PrecompileExtension].
+    pub custom_precompile_events: BTreeMap<u32, Vec<Vec<u8>>>,
+
     /// The public values.
     pub public_values: PublicValues<u32, u32>,
 }
@@ -124,17 +166,25 @@ pub struct ShardingConfig {
     pub bls12381_add_len: usize,
     pub bls12381_double_len: usize,
     pub uint256_mul_len: usize,
+    pub secp256r1_add_len: usize,
+    pub secp256r1_double_len: usize,
+    pub uint2048_mulmod_len: usize,
+    pub uint4096_mulmod_len: usize,
+    pub uint384_mulmod_len: usize,
+    pub uint512_mulmod_len: usize,
+    pub aes_round_len: usize,
+    pub ghash_mul_len: usize,
+    pub chacha20_permute_len: usize,
+    pub ripemd160_compress_len: usize,
+    pub secp256k1_ecrecover_len: usize,
+    pub gf256_mac_len: usize,
+    pub poseidon2_len: usize,
+    pub custom_precompile_len: usize,
 }
 
 impl ShardingConfig {
-    pub const fn shard_size(&self) -> usize {
-        self.shard_size
-    }
-}
-
-impl Default for ShardingConfig {
-    fn default() -> Self {
-        let shard_size = SP1CoreOpts::default().shard_size;
+    /// Create a sharding config whose chip event thresholds are derived from `shard_size`.
+    pub const fn new(shard_size: usize) -> Self {
         Self {
             shard_size,
             add_len: shard_size,
@@ -154,8 +204,32 @@ impl Default for ShardingConfig {
             bls12381_add_len: shard_size,
             bls12381_double_len: shard_size,
             uint256_mul_len: shard_size,
+            secp256r1_add_len: shard_size,
+            secp256r1_double_len: shard_size,
+            uint2048_mulmod_len: shard_size,
+            uint4096_mulmod_len: shard_size,
+            uint384_mulmod_len: shard_size,
+            uint512_mulmod_len: shard_size,
+            aes_round_len: shard_size,
+            ghash_mul_len: shard_size,
+            chacha20_permute_len: shard_size,
+            ripemd160_compress_len: shard_size,
+            secp256k1_ecrecover_len: shard_size,
+            gf256_mac_len: shard_size,
+            poseidon2_len: shard_size,
+            custom_precompile_len: shard_size,
         }
     }
+
+    pub const fn shard_size(&self) -> usize {
+        self.shard_size
+    }
+}
+
+impl Default for ShardingConfig {
+    fn default() -> Self {
+        Self::new(SP1CoreOpts::default().shard_size)
+    }
 }
 
 impl MachineRecord for ExecutionRecord {
@@ -198,6 +272,10 @@ impl MachineRecord for ExecutionRecord {
             "keccak_permute_events".to_string(),
             self.keccak_permute_events.len(),
         );
+        stats.insert(
+            "keccak_absorb_events".to_string(),
+            self.keccak_absorb_events.len(),
+        );
         stats.insert("ed_add_events".to_string(), self.ed_add_events.len());
         stats.insert(
             "ed_decompress_events".to_string(),
@@ -241,6 +319,57 @@ impl MachineRecord for ExecutionRecord {
             "bls12381_decompress_events".to_string(),
             self.bls12381_decompress_events.len(),
         );
+        stats.insert(
+            "bls12381_fp_events".to_string(),
+            self.bls12381_fp_events.len(),
+        );
+        stats.insert(
+            "secp256r1_add_events".to_string(),
+            self.secp256r1_add_events.len(),
+        );
+        stats.insert(
+            "secp256r1_double_events".to_string(),
+            self.secp256r1_double_events.len(),
+        );
+        stats.insert(
+            "uint2048_mulmod_events".to_string(),
+            self.uint2048_mulmod_events.len(),
+        );
+        stats.insert(
+            "uint4096_mulmod_events".to_string(),
+            self.uint4096_mulmod_events.len(),
+        );
+        stats.insert(
+            "uint384_mulmod_events".to_string(),
+            self.uint384_mulmod_events.len(),
+        );
+        stats.insert(
+            "uint512_mulmod_events".to_string(),
+            self.uint512_mulmod_events.len(),
+        );
+        stats.insert("aes_round_events".to_string(), self.aes_round_events.len());
+        stats.insert("ghash_mul_events".to_string(), self.ghash_mul_events.len());
+        stats.insert(
+            "chacha20_permute_events".to_string(),
+            self.chacha20_permute_events.len(),
+        );
+        stats.insert(
+            "ripemd160_compress_events".to_string(),
+            self.ripemd160_compress_events.len(),
+        );
+        stats.insert(
+            "secp256k1_ecrecover_events".to_string(),
+            self.secp256k1_ecrecover_events.len(),
+        );
+        stats.insert("gf256_mac_events".to_string(), self.gf256_mac_events.len());
+        stats.insert(
+            "poseidon2_permute_events".to_string(),
+            self.poseidon2_permute_events.len(),
+        );
+        stats.insert(
+            "custom_precompile_events".to_string(),
+            self.custom_precompile_events.values().map(Vec::len).sum(),
+        );
         stats
     }
 
@@ -260,6 +389,8 @@ impl MachineRecord for ExecutionRecord {
             .append(&mut other.sha_compress_events);
         self.keccak_permute_events
             .append(&mut other.keccak_permute_events);
+        self.keccak_absorb_events
+            .append(&mut other.keccak_absorb_events);
         self.ed_add_events.append(&mut other.ed_add_events);
         self.ed_decompress_events
             .append(&mut other.ed_decompress_events);
@@ -282,6 +413,37 @@ impl MachineRecord for ExecutionRecord {
             .append(&mut other.uint256_mul_events);
         self.bls12381_decompress_events
             .append(&mut other.bls12381_decompress_events);
+        self.bls12381_fp_events
+            .append(&mut other.bls12381_fp_events);
+        self.secp256r1_add_events
+            .append(&mut other.secp256r1_add_events);
+        self.secp256r1_double_events
+            .append(&mut other.secp256r1_double_events);
+        self.uint2048_mulmod_events
+            .append(&mut other.uint2048_mulmod_events);
+        self.uint4096_mulmod_events
+            .append(&mut other.uint4096_mulmod_events);
+        self.uint384_mulmod_events
+            .append(&mut other.uint384_mulmod_events);
+        self.uint512_mulmod_events
+            .append(&mut other.uint512_mulmod_events);
+        self.aes_round_events.append(&mut other.aes_round_events);
+        self.ghash_mul_events.append(&mut other.ghash_mul_events);
+        self.chacha20_permute_events
+            .append(&mut other.chacha20_permute_events);
+        self.ripemd160_compress_events
+            .append(&mut other.ripemd160_compress_events);
+        self.secp256k1_ecrecover_events
+            .append(&mut other.secp256k1_ecrecover_events);
+        self.gf256_mac_events.append(&mut other.gf256_mac_events);
+        self.poseidon2_permute_events
+            .append(&mut other.poseidon2_permute_events);
+        for (code, mut events) in take(&mut other.custom_precompile_events) {
+            self.custom_precompile_events
+                .entry(code)
+                .or_default()
+                .append(&mut events);
+        }
 
         // Merge the byte lookups.
         for (shard, events_map) in std::mem::take(&mut other.byte_lookups).into_iter() {
@@ -339,10 +501,13 @@ impl MachineRecord for ExecutionRecord {
                 // read it (e.g. when the halt instruction is the only instruction in the last shard).
                 // It seems overly complex to set the public_values_digest for the last two shards, so we just set it
                 // for all of the shards.
+                shard.public_values.version = PV_VERSION;
                 shard.public_values.committed_value_digest =
                     self.public_values.committed_value_digest;
                 shard.public_values.deferred_proofs_digest =
                     self.public_values.deferred_proofs_digest;
+                shard.public_values.user_values = self.public_values.user_values;
+                shard.public_values.vk_digest = self.public_values.vk_digest;
                 shard.public_values.shard = current_shard;
                 shard.public_values.start_pc = shard.cpu_events[0].pc;
                 shard.public_values.next_pc = last_shard_cpu_event.next_pc;
@@ -432,6 +597,16 @@ impl MachineRecord for ExecutionRecord {
             shard.keccak_permute_events.extend_from_slice(keccak_chunk);
         }
 
+        // Keccak sponge absorb events.
+        for (keccak_absorb_chunk, shard) in take(&mut self.keccak_absorb_events)
+            .chunks_mut(config.keccak_len)
+            .zip(shards.iter_mut())
+        {
+            shard
+                .keccak_absorb_events
+                .extend_from_slice(keccak_absorb_chunk);
+        }
+
         // secp256k1 curve add events.
         for (secp256k1_add_chunk, shard) in take(&mut self.secp256k1_add_events)
             .chunks_mut(config.secp256k1_add_len)
@@ -452,6 +627,142 @@ impl MachineRecord for ExecutionRecord {
                 .extend_from_slice(secp256k1_double_chunk);
         }
 
+        // secp256r1 curve add events.
+        for (secp256r1_add_chunk, shard) in take(&mut self.secp256r1_add_events)
+            .chunks_mut(config.secp256r1_add_len)
+            .zip(shards.iter_mut())
+        {
+            shard
+                .secp256r1_add_events
+                .extend_from_slice(secp256r1_add_chunk);
+        }
+
+        // secp256r1 curve double events.
+        for (secp256r1_double_chunk, shard) in take(&mut self.secp256r1_double_events)
+            .chunks_mut(config.secp256r1_double_len)
+            .zip(shards.iter_mut())
+        {
+            shard
+                .secp256r1_double_events
+                .extend_from_slice(secp256r1_double_chunk);
+        }
+
+        // Uint2048 mulmod events.
+        for (uint2048_mulmod_chunk, shard) in take(&mut self.uint2048_mulmod_events)
+            .chunks_mut(config.uint2048_mulmod_len)
+            .zip(shards.iter_mut())
+        {
+            shard
+                .uint2048_mulmod_events
+                .extend_from_slice(uint2048_mulmod_chunk);
+        }
+
+        // Uint4096 mulmod events.
+        for (uint4096_mulmod_chunk, shard) in take(&mut self.uint4096_mulmod_events)
+            .chunks_mut(config.uint4096_mulmod_len)
+            .zip(shards.iter_mut())
+        {
+            shard
+                .uint4096_mulmod_events
+                .extend_from_slice(uint4096_mulmod_chunk);
+        }
+
+        // Uint384 mulmod events.
+        for (uint384_mulmod_chunk, shard) in take(&mut self.uint384_mulmod_events)
+            .chunks_mut(config.uint384_mulmod_len)
+            .zip(shards.iter_mut())
+        {
+            shard
+                .uint384_mulmod_events
+                .extend_from_slice(uint384_mulmod_chunk);
+        }
+
+        // Uint512 mulmod events.
+        for (uint512_mulmod_chunk, shard) in take(&mut self.uint512_mulmod_events)
+            .chunks_mut(config.uint512_mulmod_len)
+            .zip(shards.iter_mut())
+        {
+            shard
+                .uint512_mulmod_events
+                .extend_from_slice(uint512_mulmod_chunk);
+        }
+
+        // AES round events.
+        for (aes_round_chunk, shard) in take(&mut self.aes_round_events)
+            .chunks_mut(config.aes_round_len)
+            .zip(shards.iter_mut())
+        {
+            shard.aes_round_events.extend_from_slice(aes_round_chunk);
+        }
+
+        // GHASH mul events.
+        for (ghash_mul_chunk, shard) in take(&mut self.ghash_mul_events)
+            .chunks_mut(config.ghash_mul_len)
+            .zip(shards.iter_mut())
+        {
+            shard.ghash_mul_events.extend_from_slice(ghash_mul_chunk);
+        }
+
+        // ChaCha20 permute events.
+        for (chacha20_permute_chunk, shard) in take(&mut self.chacha20_permute_events)
+            .chunks_mut(config.chacha20_permute_len)
+            .zip(shards.iter_mut())
+        {
+            shard
+                .chacha20_permute_events
+                .extend_from_slice(chacha20_permute_chunk);
+        }
+
+        // RIPEMD-160 compress events.
+        for (ripemd160_compress_chunk, shard) in take(&mut self.ripemd160_compress_events)
+            .chunks_mut(config.ripemd160_compress_len)
+            .zip(shards.iter_mut())
+        {
+            shard
+                .ripemd160_compress_events
+                .extend_from_slice(ripemd160_compress_chunk);
+        }
+
+        // Secp256k1 ecrecover events.
+        for (secp256k1_ecrecover_chunk, shard) in take(&mut self.secp256k1_ecrecover_events)
+            .chunks_mut(config.secp256k1_ecrecover_len)
+            .zip(shards.iter_mut())
+        {
+            shard
+                .secp256k1_ecrecover_events
+                .extend_from_slice(secp256k1_ecrecover_chunk);
+        }
+
+        // GF(2^8) multiply-accumulate events.
+        for (gf256_mac_chunk, shard) in take(&mut self.gf256_mac_events)
+            .chunks_mut(config.gf256_mac_len)
+            .zip(shards.iter_mut())
+        {
+            shard.gf256_mac_events.extend_from_slice(gf256_mac_chunk);
+        }
+
+        // Poseidon2 permute events.
+        for (poseidon2_chunk, shard) in take(&mut self.poseidon2_permute_events)
+            .chunks_mut(config.poseidon2_len)
+            .zip(shards.iter_mut())
+        {
+            shard
+                .poseidon2_permute_events
+                .extend_from_slice(poseidon2_chunk);
+        }
+
+        // Custom precompile events.
+        for (code, mut events) in take(&mut self.custom_precompile_events) {
+            for (chunk, shard) in events
+                .chunks_mut(config.custom_precompile_len)
+                .zip(shards.iter_mut())
+            {
+                shard
+                    .custom_precompile_events
+                    .insert(code, chunk.iter_mut().map(take).collect());
+            }
+        }
+
         // bn254 curve add events.
         for (bn254_add_chunk, shard) in take(&mut self.bn254_add_events)
             .chunks_mut(config.bn254_add_len)
@@ -517,6 +828,9 @@ impl MachineRecord for ExecutionRecord {
         // Bls12-381 decompress events .
         first.bls12381_decompress_events = std::mem::take(&mut self.bls12381_decompress_events);
 
+        // Bls12-381 base field arithmetic events.
+        first.bls12381_fp_events = std::mem::take(&mut self.bls12381_fp_events);
+
         // Put the memory records in the last shard.
         let last_shard = shards.last_mut().unwrap();
 
@@ -554,6 +868,35 @@ impl ExecutionRecord {
         self.lt_events.push(lt_event);
     }
 
+    /// Adds an event of the custom precompile of the syscall number `code`.
+    pub fn add_custom_precompile_event<E: Serialize>(&mut self, code: u32, event: &E) {
+        let bytes = bincode::serialize(event).expect("failed to serialize precompile event");
+        self.custom_precompile_events
+            .entry(code)
+            .or_default()
+            .push(bytes);
+    }
+
+    /// The events of the custom precompile of the syscall number `code`.
+    pub fn custom_precompile_events<E: DeserializeOwned>(&self, code: u32) -> Vec<E> {
+        self.custom_precompile_events
+            .get(&code)
+            .map(|events| {
+                events
+                    .iter()
+                    .map(|bytes| {
+                        bincode::deserialize(bytes).expect("failed to deserialize precompile event")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The number of events of the custom precompile of the syscall number `code`.
+    pub fn num_custom_precompile_events(&self, code: u32) -> usize {
+        self.custom_precompile_events.get(&code).map_or(0, Vec::len)
+    }
+
     pub fn add_alu_events(&mut self, alu_events: HashMap<Opcode, Vec<AluEvent>>) {
         let keys = alu_events.keys().sorted();
         for opcode in keys {
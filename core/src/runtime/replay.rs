@@ -0,0 +1,174 @@
+use std::io::{Read, Write};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::stark::{ShardProof, StarkVerifyingKey};
+use crate::utils::{BabyBearPoseidon2, SP1CoreOpts};
+
+use super::{Program, Runtime};
+
+/// The nondeterministic inputs consumed by an execution, in the order they were consumed, along
+/// with the outputs it produced.
+///
+/// A recording is self contained, so an execution reported on one machine can be reproduced
+/// exactly on another with [Runtime::replay].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionRecording {
+    /// The program that was executed.
+    pub program: Program,
+
+    /// The input vecs read by the program through hints, including any pulled from a stream.
+    pub inputs: Vec<Vec<u8>>,
+
+    /// The proofs verified by the program.
+    pub proofs: Vec<(
+        ShardProof<BabyBearPoseidon2>,
+        StarkVerifyingKey<BabyBearPoseidon2>,
+    )>,
+
+    /// The seed of the randomness drawn by the program.
+    #[serde(default)]
+    pub random_seed: [u8; 32],
+
+    /// The number of cycles executed.
+    pub cycles: u64,
+
+    /// The public values committed by the program.
+    pub public_values: Vec<u8>,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    #[error("replay executed {1} cycles but the recording executed {0}")]
+    CycleMismatch(u64, u64),
+
+    #[error("replay committed different public values than the recording")]
+    PublicValuesMismatch,
+}
+
+impl ExecutionRecording {
+    pub fn new(program: Program) -> Self {
+        Self {
+            program,
+            inputs: Vec::new(),
+            proofs: Vec::new(),
+            random_seed: [0; 32],
+            cycles: 0,
+            public_values: Vec::new(),
+        }
+    }
+
+    /// Write the recording to `writer`.
+    pub fn save<W: Write>(&self, writer: W) -> bincode::Result<()> {
+        bincode::serialize_into(writer, self)
+    }
+
+    /// Read a recording previously written by [ExecutionRecording::save].
+    pub fn load<R: Read>(reader: R) -> bincode::Result<Self> {
+        bincode::deserialize_from(reader)
+    }
+}
+
+impl Runtime {
+    /// Start recording the nondeterministic inputs consumed by the program.
+    pub fn start_recording(&mut self) {
+        self.recording = Some(ExecutionRecording::new(self.program.as_ref().clone()));
+    }
+
+    /// Stop recording and return the recording, with the cycles executed and public values
+    /// committed so far.
+    pub fn finish_recording(&mut self) -> Option<ExecutionRecording> {
+        let mut recording = self.recording.take()?;
+        recording.random_seed = self.state.random_seed;
+        recording.cycles = self.state.global_clk;
+        recording
+            .public_values
+            .clone_from(&self.state.public_values_stream);
+        Some(recording)
+    }
+
+    /// Create a runtime which reproduces the execution captured in `recording`.
+    pub fn replay(recording: &ExecutionRecording, opts: SP1CoreOpts) -> Self {
+        let mut runtime = Self::new(recording.program.clone(), opts);
+        runtime.write_vecs(&recording.inputs);
+        runtime.state.random_seed = recording.random_seed;
+        for (proof, vk) in recording.proofs.iter() {
+            runtime.write_proof(proof.clone(), vk.clone());
+        }
+        runtime
+    }
+
+    /// Check that the execution so far matches the outputs captured in `recording`.
+    pub fn check_replay(&self, recording: &ExecutionRecording) -> Result<(), ReplayError> {
+        if self.state.global_clk != recording.cycles {
+            return Err(ReplayError::CycleMismatch(
+                recording.cycles,
+                self.state.global_clk,
+            ));
+        }
+        if self.state.public_values_stream != recording.public_values {
+            return Err(ReplayError::PublicValuesMismatch);
+        }
+        Ok(())
+    }
+
+    /// Record an input vec consumed by the program, if recording.
+    pub(crate) fn record_input(&mut self, input: &[u8]) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.inputs.push(input.to_vec());
+        }
+    }
+
+    /// Record the proof at `index` of the proof stream being consumed by the program, if
+    /// recording.
+    pub(crate) fn record_proof(&mut self, index: usize) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording
+                .proofs
+                .push(self.state.proof_stream[index].clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExecutionRecording, ReplayError};
+    use crate::io::InputStream;
+    use crate::runtime::{Program, Runtime};
+    use crate::utils::tests::IO_ELF;
+    use crate::utils::SP1CoreOpts;
+
+    #[test]
+    fn test_record_replay() {
+        let mut runtime = Runtime::new(Program::from(IO_ELF), SP1CoreOpts::default());
+        let first = bincode::serialize(&(3usize, 5usize, true)).unwrap();
+        let second = bincode::serialize(&(8usize, 19usize, true)).unwrap();
+        runtime.write_stdin_slice(&first);
+        runtime
+            .write_stream(&InputStream::new(vec![second].into_iter()))
+            .unwrap();
+        runtime.start_recording();
+        runtime.run().unwrap();
+        let recording = runtime.finish_recording().unwrap();
+        assert_eq!(recording.inputs.len(), 2);
+
+        let mut bytes = Vec::new();
+        recording.save(&mut bytes).unwrap();
+        let recording = ExecutionRecording::load(bytes.as_slice()).unwrap();
+
+        let mut replay = Runtime::replay(&recording, SP1CoreOpts::default());
+        replay.run().unwrap();
+        assert_eq!(replay.check_replay(&recording), Ok(()));
+
+        let mut truncated = recording.clone();
+        truncated.cycles -= 1;
+        assert_eq!(
+            replay.check_replay(&truncated),
+            Err(ReplayError::CycleMismatch(
+                truncated.cycles,
+                recording.cycles
+            ))
+        );
+    }
+}
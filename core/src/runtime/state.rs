@@ -3,13 +3,14 @@ use std::collections::HashMap;
 use nohash_hasher::BuildNoHashHasher;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
+use sha2::digest::generic_array::GenericArray;
 
 use crate::{
     stark::{ShardProof, StarkVerifyingKey},
     utils::BabyBearPoseidon2,
 };
 
-use super::{ExecutionRecord, MemoryAccessRecord, MemoryRecord};
+use super::{ExecutionRecord, MemoryAccessRecord, MemoryRecord, PagedMemory};
 
 /// Holds data describing the current state of a program's execution.
 #[serde_as]
@@ -34,11 +35,11 @@ pub struct ExecutionState {
 
     /// The memory which instructions operate over. Values contain the memory value and last shard
     /// + timestamp that each memory address was accessed.
-    pub memory: HashMap<u32, MemoryRecord, BuildNoHashHasher<u32>>,
+    pub memory: PagedMemory<MemoryRecord>,
 
     /// Uninitialized memory addresses that have a specific value they should be initialized with.
     /// SyscallHintRead uses this to write hint data into uninitialized memory.
-    pub uninitialized_memory: HashMap<u32, u32, BuildNoHashHasher<u32>>,
+    pub uninitialized_memory: PagedMemory<u32>,
 
     /// A stream of input values (global to the entire program).
     pub input_stream: Vec<Vec<u8>>,
@@ -46,6 +47,20 @@ pub struct ExecutionState {
     /// A ptr to the current position in the input stream incremented by HINT_READ opcode.
     pub input_stream_ptr: usize,
 
+    /// The number of values the program hinted to itself which it has not read yet. They are at
+    /// the current position of the input stream, so that the program reads them before the rest
+    /// of its input.
+    #[serde(default)]
+    pub pending_hints: usize,
+
+    /// The seed of the randomness the host provides to the program with the `RANDOM` syscall.
+    #[serde(default)]
+    pub random_seed: [u8; 32],
+
+    /// The number of times the program drew randomness from [ExecutionState::random_seed].
+    #[serde(default)]
+    pub random_draws: u64,
+
     /// A stream of proofs inputted to the program.
     pub proof_stream: Vec<(
         ShardProof<BabyBearPoseidon2>,
@@ -60,6 +75,14 @@ pub struct ExecutionState {
 
     /// A ptr to the current position in the public values stream, incremented when reading from public_values_stream.
     pub public_values_stream_ptr: usize,
+
+    /// The leaves of the Merkle tree of public outputs, in the order the program appended them.
+    pub public_leaves: Vec<Vec<u8>>,
+
+    /// The running digest of all the public values, including those streamed to a sink which are
+    /// not in [ExecutionState::public_values_stream].
+    #[serde(default)]
+    pub public_values_hasher: PublicValuesHasher,
 }
 
 impl ExecutionState {
@@ -71,14 +94,105 @@ impl ExecutionState {
             clk: 0,
             channel: 0,
             pc: pc_start,
-            memory: HashMap::default(),
-            uninitialized_memory: HashMap::default(),
+            memory: PagedMemory::new(),
+            uninitialized_memory: PagedMemory::new(),
             input_stream: Vec::new(),
             input_stream_ptr: 0,
+            pending_hints: 0,
+            random_seed: [0; 32],
+            random_draws: 0,
             public_values_stream: Vec::new(),
             public_values_stream_ptr: 0,
+            public_leaves: Vec::new(),
             proof_stream: Vec::new(),
             proof_stream_ptr: 0,
+            public_values_hasher: PublicValuesHasher::default(),
+        }
+    }
+}
+
+/// The initial hash value of SHA-256.
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// A running SHA-256 which is serialized with the execution state, so that an execution resumed
+/// from a checkpoint keeps the digest of the public values written before the checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicValuesHasher {
+    state: [u32; 8],
+    /// The bytes of the current block which is not full yet.
+    pending: Vec<u8>,
+    /// The number of bytes hashed so far.
+    len: u64,
+}
+
+impl Default for PublicValuesHasher {
+    fn default() -> Self {
+        Self {
+            state: SHA256_IV,
+            pending: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl PublicValuesHasher {
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.len += data.len() as u64;
+        if !self.pending.is_empty() {
+            let n = data.len().min(64 - self.pending.len());
+            self.pending.extend_from_slice(&data[..n]);
+            data = &data[n..];
+            if self.pending.len() < 64 {
+                return;
+            }
+            let block = std::mem::take(&mut self.pending);
+            sha2::compress256(&mut self.state, &[*GenericArray::from_slice(&block)]);
+        }
+        let blocks = data.chunks_exact(64);
+        self.pending = blocks.remainder().to_vec();
+        let blocks = blocks
+            .map(|block| *GenericArray::from_slice(block))
+            .collect::<Vec<_>>();
+        sha2::compress256(&mut self.state, &blocks);
+    }
+
+    /// The SHA-256 digest of the bytes hashed so far.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = self.clone();
+        let bit_len = self.len.wrapping_mul(8);
+        let padding = (119 - self.len % 64) % 64;
+        hasher.update(&[0x80]);
+        hasher.update(&vec![0; padding as usize]);
+        hasher.update(&bit_len.to_be_bytes());
+        debug_assert!(hasher.pending.is_empty());
+        let mut digest = [0u8; 32];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(hasher.state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sha2::{Digest, Sha256};
+
+    use super::PublicValuesHasher;
+
+    #[test]
+    fn test_public_values_hasher() {
+        let data = (0..300u32).map(|i| i as u8).collect::<Vec<_>>();
+        for len in [0, 1, 55, 56, 63, 64, 65, 119, 120, 128, 300] {
+            // Hash the data in uneven pieces, with a serialization round trip in the middle.
+            let mut hasher = PublicValuesHasher::default();
+            for piece in data[..len].chunks(7) {
+                hasher.update(piece);
+                hasher = bincode::deserialize(&bincode::serialize(&hasher).unwrap()).unwrap();
+            }
+            let expected: [u8; 32] = Sha256::digest(&data[..len]).into();
+            assert_eq!(hasher.digest(), expected, "length {}", len);
         }
     }
 }
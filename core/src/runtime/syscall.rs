@@ -1,26 +1,41 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use strum_macros::EnumIter;
 
-use crate::runtime::{Register, Runtime};
+use crate::operations::field::field_op::FieldOperation;
+use crate::runtime::{ExecutionError, Opcode, Register, Runtime};
 use crate::stark::Blake3CompressInnerChip;
+use crate::syscall::precompiles::aes::{AesRoundSyscall, GhashMulChip};
+use crate::syscall::precompiles::bigint::{
+    BigUintMulModChip, Uint2048, Uint384, Uint4096, Uint512,
+};
+use crate::syscall::precompiles::chacha20::ChaCha20PermuteChip;
+use crate::syscall::precompiles::ecrecover::Secp256k1EcrecoverChip;
 use crate::syscall::precompiles::edwards::EdAddAssignChip;
 use crate::syscall::precompiles::edwards::EdDecompressChip;
-use crate::syscall::precompiles::keccak256::KeccakPermuteChip;
+use crate::syscall::precompiles::fp::FpOpSyscall;
+use crate::syscall::precompiles::gf256::Gf256MacChip;
+use crate::syscall::precompiles::keccak256::{KeccakAbsorbChip, KeccakPermuteChip};
+use crate::syscall::precompiles::poseidon2::Poseidon2PermuteChip;
+use crate::syscall::precompiles::ripemd160::Ripemd160CompressChip;
 use crate::syscall::precompiles::sha256::{ShaCompressChip, ShaExtendChip};
 use crate::syscall::precompiles::uint256::Uint256MulChip;
 use crate::syscall::precompiles::weierstrass::WeierstrassAddAssignChip;
 use crate::syscall::precompiles::weierstrass::WeierstrassDecompressChip;
 use crate::syscall::precompiles::weierstrass::WeierstrassDoubleAssignChip;
 use crate::syscall::{
-    SyscallCommit, SyscallCommitDeferred, SyscallEnterUnconstrained, SyscallExitUnconstrained,
-    SyscallHalt, SyscallHintLen, SyscallHintRead, SyscallVerifySP1Proof, SyscallWrite,
+    SyscallCommit, SyscallCommitDeferred, SyscallCommitUserValue, SyscallCycleCount,
+    SyscallEnterUnconstrained, SyscallExitUnconstrained, SyscallHalt, SyscallHintLen,
+    SyscallHintRead, SyscallPanic, SyscallRandom, SyscallVerifySP1Proof, SyscallWrite,
 };
 use crate::utils::ec::edwards::ed25519::{Ed25519, Ed25519Parameters};
-use crate::utils::ec::weierstrass::bls12_381::Bls12381;
-use crate::utils::ec::weierstrass::{bn254::Bn254, secp256k1::Secp256k1};
+use crate::utils::ec::weierstrass::bls12_381::{Bls12381, Bls12381BaseField};
+use crate::utils::ec::weierstrass::bn254::Bn254;
+use crate::utils::ec::weierstrass::secp256k1::Secp256k1;
+use crate::utils::ec::weierstrass::secp256r1::Secp256r1;
 use crate::{runtime::ExecutionRecord, runtime::MemoryReadRecord, runtime::MemoryWriteRecord};
 
 /// A system call is invoked by the the `ecall` instruction with a specific value in register t0.
@@ -103,6 +118,73 @@ pub enum SyscallCode {
 
     /// Executes the `BLS12381_DOUBLE` precompile.
     BLS12381_DOUBLE = 0x00_00_01_1F,
+
+    /// Executes the `BLS12381_FP_ADD` precompile.
+    BLS12381_FP_ADD = 0x00_01_01_20,
+
+    /// Executes the `BLS12381_FP_SUB` precompile.
+    BLS12381_FP_SUB = 0x00_01_01_21,
+
+    /// Executes the `BLS12381_FP_MUL` precompile.
+    BLS12381_FP_MUL = 0x00_01_01_22,
+
+    /// Executes the `SECP256R1_ADD` precompile.
+    SECP256R1_ADD = 0x00_01_01_26,
+
+    /// Executes the `SECP256R1_DOUBLE` precompile.
+    SECP256R1_DOUBLE = 0x00_00_01_27,
+
+    /// Executes the `UINT2048_MULMOD` precompile.
+    UINT2048_MULMOD = 0x00_01_01_28,
+
+    /// Executes the `UINT4096_MULMOD` precompile.
+    UINT4096_MULMOD = 0x00_01_01_29,
+
+    /// Executes the `KECCAK_ABSORB` precompile.
+    KECCAK_ABSORB = 0x00_02_01_2A,
+
+    /// Executes the `POSEIDON2_PERMUTE` precompile.
+    POSEIDON2_PERMUTE = 0x00_00_01_2B,
+
+    /// Executes the `UINT384_MULMOD` precompile.
+    UINT384_MULMOD = 0x00_01_01_2C,
+
+    /// Executes the `UINT512_MULMOD` precompile.
+    UINT512_MULMOD = 0x00_01_01_2D,
+
+    /// Executes the `AES_ENCRYPT_ROUND` precompile.
+    AES_ENCRYPT_ROUND = 0x00_01_01_2E,
+
+    /// Executes the `AES_ENCRYPT_LAST_ROUND` precompile.
+    AES_ENCRYPT_LAST_ROUND = 0x00_01_01_2F,
+
+    /// Executes the `GHASH_MUL` precompile.
+    GHASH_MUL = 0x00_01_01_30,
+
+    /// Executes the `CHACHA20_PERMUTE` precompile.
+    CHACHA20_PERMUTE = 0x00_50_01_31,
+
+    /// Executes the `RIPEMD160_COMPRESS` precompile.
+    #[allow(clippy::mistyped_literal_suffixes)]
+    RIPEMD160_COMPRESS = 0x00_50_01_32,
+
+    /// Executes the `SECP256K1_ECRECOVER` precompile.
+    SECP256K1_ECRECOVER = 0x00_01_01_33,
+
+    /// Executes the `GF256_MAC` precompile.
+    GF256_MAC = 0x00_01_01_34,
+
+    /// Executes the `COMMIT_USER_VALUE` precompile.
+    COMMIT_USER_VALUE = 0x00_00_00_35,
+
+    /// Reports the panic message of the program.
+    PANIC = 0x00_00_00_F2,
+
+    /// Returns a word of the global clock.
+    CYCLE_COUNT = 0x00_00_00_F3,
+
+    /// Hints the next random bytes drawn from the seed of the host.
+    RANDOM = 0x00_00_00_F4,
 }
 
 impl SyscallCode {
@@ -133,6 +215,29 @@ impl SyscallCode {
             0x00_00_00_F1 => SyscallCode::HINT_READ,
             0x00_00_01_1D => SyscallCode::UINT256_MUL,
             0x00_00_01_1C => SyscallCode::BLS12381_DECOMPRESS,
+            0x00_01_01_20 => SyscallCode::BLS12381_FP_ADD,
+            0x00_01_01_21 => SyscallCode::BLS12381_FP_SUB,
+            0x00_01_01_22 => SyscallCode::BLS12381_FP_MUL,
+            0x00_01_01_26 => SyscallCode::SECP256R1_ADD,
+            0x00_00_01_27 => SyscallCode::SECP256R1_DOUBLE,
+            0x00_01_01_28 => SyscallCode::UINT2048_MULMOD,
+            0x00_01_01_29 => SyscallCode::UINT4096_MULMOD,
+            0x00_02_01_2A => SyscallCode::KECCAK_ABSORB,
+            0x00_00_01_2B => SyscallCode::POSEIDON2_PERMUTE,
+            0x00_01_01_2C => SyscallCode::UINT384_MULMOD,
+            0x00_01_01_2D => SyscallCode::UINT512_MULMOD,
+            0x00_01_01_2E => SyscallCode::AES_ENCRYPT_ROUND,
+            0x00_01_01_2F => SyscallCode::AES_ENCRYPT_LAST_ROUND,
+            0x00_01_01_30 => SyscallCode::GHASH_MUL,
+            0x00_50_01_31 => SyscallCode::CHACHA20_PERMUTE,
+            #[allow(clippy::mistyped_literal_suffixes)]
+            0x00_50_01_32 => SyscallCode::RIPEMD160_COMPRESS,
+            0x00_01_01_33 => SyscallCode::SECP256K1_ECRECOVER,
+            0x00_01_01_34 => SyscallCode::GF256_MAC,
+            0x00_00_00_35 => SyscallCode::COMMIT_USER_VALUE,
+            0x00_00_00_F2 => SyscallCode::PANIC,
+            0x00_00_00_F3 => SyscallCode::CYCLE_COUNT,
+            0x00_00_00_F4 => SyscallCode::RANDOM,
             _ => panic!("invalid syscall number: {}", value),
         }
     }
@@ -150,6 +255,41 @@ impl SyscallCode {
     }
 }
 
+/// The syscall ids, the first byte of the syscall numbers, which are reserved for the syscalls of
+/// custom precompiles, so that they never collide with the syscalls of SP1.
+pub const CUSTOM_SYSCALL_IDS: RangeInclusive<u32> = 0x80..=0xEF;
+
+/// Checks that `code` is a valid syscall number for a custom precompile whose syscall is
+/// `syscall`: its id is in [CUSTOM_SYSCALL_IDS], it is sent to the table of the precompile, and
+/// its number of extra cycles is [Syscall::num_extra_cycles], as the CPU table checks.
+pub fn check_custom_syscall_code(code: u32, syscall: &dyn Syscall) -> Result<(), String> {
+    let [id, send_to_table, num_cycles, high] = code.to_le_bytes();
+    if !CUSTOM_SYSCALL_IDS.contains(&(id as u32)) {
+        return Err(format!(
+            "the id {:#x} of the custom syscall {:#x} is not in {:#x}..={:#x}",
+            id,
+            code,
+            CUSTOM_SYSCALL_IDS.start(),
+            CUSTOM_SYSCALL_IDS.end()
+        ));
+    }
+    if send_to_table != 1 || high != 0 {
+        return Err(format!(
+            "the custom syscall {:#x} is not of the form 0x00_XX_01_YY",
+            code
+        ));
+    }
+    if num_cycles as u32 != syscall.num_extra_cycles() {
+        return Err(format!(
+            "the custom syscall {:#x} encodes {} extra cycles, but its syscall takes {}",
+            code,
+            num_cycles,
+            syscall.num_extra_cycles()
+        ));
+    }
+    Ok(())
+}
+
 impl fmt::Display for SyscallCode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", self)
@@ -179,6 +319,8 @@ pub struct SyscallContext<'a> {
     pub(crate) next_pc: u32,
     /// This is the exit_code used for the HALT syscall
     pub(crate) exit_code: u32,
+    /// The first error raised while executing the syscall, returned once the syscall is done.
+    pub(crate) error: Option<ExecutionError>,
     pub(crate) rt: &'a mut Runtime,
 }
 
@@ -191,6 +333,7 @@ impl<'a> SyscallContext<'a> {
             clk,
             next_pc: runtime.state.pc.wrapping_add(4),
             exit_code: 0,
+            error: None,
             rt: runtime,
         }
     }
@@ -208,6 +351,9 @@ impl<'a> SyscallContext<'a> {
     }
 
     pub fn mr(&mut self, addr: u32) -> (MemoryReadRecord, u32) {
+        if !self.check_aligned(addr) {
+            return (MemoryReadRecord::default(), 0);
+        }
         let record = self.rt.mr(addr, self.current_shard, self.clk);
         (record, record.value)
     }
@@ -224,6 +370,9 @@ impl<'a> SyscallContext<'a> {
     }
 
     pub fn mw(&mut self, addr: u32, value: u32) -> MemoryWriteRecord {
+        if !self.check_aligned(addr) {
+            return MemoryWriteRecord::default();
+        }
         self.rt.mw(addr, value, self.current_shard, self.clk)
     }
 
@@ -265,6 +414,21 @@ impl<'a> SyscallContext<'a> {
     pub fn set_exit_code(&mut self, exit_code: u32) {
         self.exit_code = exit_code;
     }
+
+    /// Fail the syscall with `error`. Execution stops with the first error once the syscall
+    /// returns, so the values it reads and writes after failing are never used.
+    pub fn fail(&mut self, error: ExecutionError) {
+        self.error.get_or_insert(error);
+    }
+
+    /// Check that a guest-supplied word address is aligned, failing the syscall if it is not.
+    fn check_aligned(&mut self, addr: u32) -> bool {
+        let aligned = addr % 4 == 0;
+        if !aligned {
+            self.fail(ExecutionError::InvalidMemoryAccess(Opcode::ECALL, addr));
+        }
+        aligned
+    }
 }
 
 pub fn default_syscall_map() -> HashMap<SyscallCode, Arc<dyn Syscall>> {
@@ -284,6 +448,10 @@ pub fn default_syscall_map() -> HashMap<SyscallCode, Arc<dyn Syscall>> {
         SyscallCode::KECCAK_PERMUTE,
         Arc::new(KeccakPermuteChip::new()),
     );
+    syscall_map.insert(
+        SyscallCode::KECCAK_ABSORB,
+        Arc::new(KeccakAbsorbChip::new()),
+    );
     syscall_map.insert(
         SyscallCode::SECP256K1_ADD,
         Arc::new(WeierstrassAddAssignChip::<Secp256k1>::new()),
@@ -316,10 +484,6 @@ pub fn default_syscall_map() -> HashMap<SyscallCode, Arc<dyn Syscall>> {
         SyscallCode::BLS12381_DOUBLE,
         Arc::new(WeierstrassDoubleAssignChip::<Bls12381>::new()),
     );
-    syscall_map.insert(
-        SyscallCode::BLAKE3_COMPRESS_INNER,
-        Arc::new(Blake3CompressInnerChip::new()),
-    );
     syscall_map.insert(SyscallCode::UINT256_MUL, Arc::new(Uint256MulChip::new()));
     syscall_map.insert(
         SyscallCode::ENTER_UNCONSTRAINED,
@@ -335,17 +499,86 @@ pub fn default_syscall_map() -> HashMap<SyscallCode, Arc<dyn Syscall>> {
         SyscallCode::COMMIT_DEFERRED_PROOFS,
         Arc::new(SyscallCommitDeferred::new()),
     );
+    syscall_map.insert(
+        SyscallCode::COMMIT_USER_VALUE,
+        Arc::new(SyscallCommitUserValue::new()),
+    );
     syscall_map.insert(
         SyscallCode::VERIFY_SP1_PROOF,
         Arc::new(SyscallVerifySP1Proof::new()),
     );
     syscall_map.insert(SyscallCode::HINT_LEN, Arc::new(SyscallHintLen::new()));
     syscall_map.insert(SyscallCode::HINT_READ, Arc::new(SyscallHintRead::new()));
+    syscall_map.insert(SyscallCode::PANIC, Arc::new(SyscallPanic::new()));
+    syscall_map.insert(SyscallCode::CYCLE_COUNT, Arc::new(SyscallCycleCount::new()));
+    syscall_map.insert(SyscallCode::RANDOM, Arc::new(SyscallRandom::new()));
     syscall_map.insert(
         SyscallCode::BLS12381_DECOMPRESS,
         Arc::new(WeierstrassDecompressChip::<Bls12381>::new()),
     );
     syscall_map.insert(SyscallCode::UINT256_MUL, Arc::new(Uint256MulChip::new()));
+    syscall_map.insert(
+        SyscallCode::BLS12381_FP_ADD,
+        Arc::new(FpOpSyscall::<Bls12381BaseField>::new(FieldOperation::Add)),
+    );
+    syscall_map.insert(
+        SyscallCode::BLS12381_FP_SUB,
+        Arc::new(FpOpSyscall::<Bls12381BaseField>::new(FieldOperation::Sub)),
+    );
+    syscall_map.insert(
+        SyscallCode::BLS12381_FP_MUL,
+        Arc::new(FpOpSyscall::<Bls12381BaseField>::new(FieldOperation::Mul)),
+    );
+    syscall_map.insert(
+        SyscallCode::SECP256R1_ADD,
+        Arc::new(WeierstrassAddAssignChip::<Secp256r1>::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::SECP256R1_DOUBLE,
+        Arc::new(WeierstrassDoubleAssignChip::<Secp256r1>::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::UINT2048_MULMOD,
+        Arc::new(BigUintMulModChip::<Uint2048>::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::UINT4096_MULMOD,
+        Arc::new(BigUintMulModChip::<Uint4096>::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::POSEIDON2_PERMUTE,
+        Arc::new(Poseidon2PermuteChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::UINT384_MULMOD,
+        Arc::new(BigUintMulModChip::<Uint384>::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::UINT512_MULMOD,
+        Arc::new(BigUintMulModChip::<Uint512>::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::AES_ENCRYPT_ROUND,
+        Arc::new(AesRoundSyscall::new(false)),
+    );
+    syscall_map.insert(
+        SyscallCode::AES_ENCRYPT_LAST_ROUND,
+        Arc::new(AesRoundSyscall::new(true)),
+    );
+    syscall_map.insert(SyscallCode::GHASH_MUL, Arc::new(GhashMulChip::new()));
+    syscall_map.insert(
+        SyscallCode::CHACHA20_PERMUTE,
+        Arc::new(ChaCha20PermuteChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::RIPEMD160_COMPRESS,
+        Arc::new(Ripemd160CompressChip::new()),
+    );
+    syscall_map.insert(
+        SyscallCode::SECP256K1_ECRECOVER,
+        Arc::new(Secp256k1EcrecoverChip::new()),
+    );
+    syscall_map.insert(SyscallCode::GF256_MAC, Arc::new(Gf256MacChip::new()));
 
     syscall_map
 }
@@ -359,10 +592,6 @@ mod tests {
     fn test_syscalls_in_default_map() {
         let default_syscall_map = default_syscall_map();
         for code in SyscallCode::iter() {
-            if code == SyscallCode::BLAKE3_COMPRESS_INNER {
-                // Blake3 is currently disabled.
-                continue;
-            }
             default_syscall_map.get(&code).unwrap();
         }
     }
@@ -443,6 +672,68 @@ mod tests {
                 SyscallCode::BLS12381_DECOMPRESS => {
                     assert_eq!(code as u32, sp1_zkvm::syscalls::BLS12381_DECOMPRESS)
                 }
+                SyscallCode::BLS12381_FP_ADD => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::BLS12381_FP_ADD)
+                }
+                SyscallCode::BLS12381_FP_SUB => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::BLS12381_FP_SUB)
+                }
+                SyscallCode::BLS12381_FP_MUL => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::BLS12381_FP_MUL)
+                }
+                SyscallCode::SECP256R1_ADD => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::SECP256R1_ADD)
+                }
+                SyscallCode::SECP256R1_DOUBLE => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::SECP256R1_DOUBLE)
+                }
+                SyscallCode::UINT2048_MULMOD => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT2048_MULMOD)
+                }
+                SyscallCode::UINT4096_MULMOD => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT4096_MULMOD)
+                }
+                SyscallCode::KECCAK_ABSORB => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::KECCAK_ABSORB)
+                }
+                SyscallCode::POSEIDON2_PERMUTE => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::POSEIDON2_PERMUTE)
+                }
+                SyscallCode::UINT384_MULMOD => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT384_MULMOD)
+                }
+                SyscallCode::UINT512_MULMOD => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::UINT512_MULMOD)
+                }
+                SyscallCode::AES_ENCRYPT_ROUND => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::AES_ENCRYPT_ROUND)
+                }
+                SyscallCode::AES_ENCRYPT_LAST_ROUND => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::AES_ENCRYPT_LAST_ROUND)
+                }
+                SyscallCode::GHASH_MUL => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::GHASH_MUL)
+                }
+                SyscallCode::CHACHA20_PERMUTE => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::CHACHA20_PERMUTE)
+                }
+                SyscallCode::RIPEMD160_COMPRESS => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::RIPEMD160_COMPRESS)
+                }
+                SyscallCode::SECP256K1_ECRECOVER => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::SECP256K1_ECRECOVER)
+                }
+                SyscallCode::GF256_MAC => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::GF256_MAC)
+                }
+                SyscallCode::COMMIT_USER_VALUE => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::COMMIT_USER_VALUE)
+                }
+                SyscallCode::PANIC => assert_eq!(code as u32, sp1_zkvm::syscalls::PANIC),
+                SyscallCode::CYCLE_COUNT => {
+                    assert_eq!(code as u32, sp1_zkvm::syscalls::CYCLE_COUNT)
+                }
+                SyscallCode::RANDOM => assert_eq!(code as u32, sp1_zkvm::syscalls::RANDOM),
             }
         }
     }
@@ -1,10 +1,13 @@
 use super::StarkMachine;
 pub use crate::air::SP1AirBuilder;
 use crate::air::{MachineAir, SP1_PROOF_NUM_PV_ELTS};
+use crate::lookup::InteractionBuilder;
 use crate::memory::{MemoryChipType, MemoryProgramChip};
-use crate::stark::Chip;
+use crate::stark::{Chip, NoPrecompiles, PrecompileExtension};
 use crate::StarkGenericConfig;
+use p3_air::Air;
 use p3_field::PrimeField32;
+use p3_uni_stark::SymbolicAirBuilder;
 pub use riscv_chips::*;
 use tracing::instrument;
 
@@ -21,10 +24,20 @@ pub(crate) mod riscv_chips {
     pub use crate::cpu::CpuChip;
     pub use crate::memory::MemoryChip;
     pub use crate::program::ProgramChip;
+    pub use crate::syscall::precompiles::aes::{AesRoundChip, GhashMulChip};
+    pub use crate::syscall::precompiles::bigint::BigUintMulModChip;
+    pub use crate::syscall::precompiles::bigint::{Uint2048, Uint384, Uint4096, Uint512};
     pub use crate::syscall::precompiles::blake3::Blake3CompressInnerChip;
+    pub use crate::syscall::precompiles::chacha20::ChaCha20PermuteChip;
+    pub use crate::syscall::precompiles::ecrecover::Secp256k1EcrecoverChip;
     pub use crate::syscall::precompiles::edwards::EdAddAssignChip;
     pub use crate::syscall::precompiles::edwards::EdDecompressChip;
+    pub use crate::syscall::precompiles::fp::FpOpChip;
+    pub use crate::syscall::precompiles::gf256::Gf256MacChip;
+    pub use crate::syscall::precompiles::keccak256::KeccakAbsorbChip;
     pub use crate::syscall::precompiles::keccak256::KeccakPermuteChip;
+    pub use crate::syscall::precompiles::poseidon2::Poseidon2PermuteChip;
+    pub use crate::syscall::precompiles::ripemd160::Ripemd160CompressChip;
     pub use crate::syscall::precompiles::sha256::ShaCompressChip;
     pub use crate::syscall::precompiles::sha256::ShaExtendChip;
     pub use crate::syscall::precompiles::uint256::Uint256MulChip;
@@ -33,9 +46,11 @@ pub(crate) mod riscv_chips {
     pub use crate::syscall::precompiles::weierstrass::WeierstrassDoubleAssignChip;
     pub use crate::utils::ec::edwards::ed25519::Ed25519Parameters;
     pub use crate::utils::ec::edwards::EdwardsCurve;
+    pub use crate::utils::ec::weierstrass::bls12_381::Bls12381BaseField;
     pub use crate::utils::ec::weierstrass::bls12_381::Bls12381Parameters;
     pub use crate::utils::ec::weierstrass::bn254::Bn254Parameters;
     pub use crate::utils::ec::weierstrass::secp256k1::Secp256k1Parameters;
+    pub use crate::utils::ec::weierstrass::secp256r1::Secp256r1Parameters;
     pub use crate::utils::ec::weierstrass::SwCurve;
 }
 
@@ -44,8 +59,11 @@ pub(crate) mod riscv_chips {
 /// This enum contains all the different AIRs that are used in the Sp1 RISC-V IOP. Each variant is
 /// a different AIR that is used to encode a different part of the RISC-V execution, and the
 /// different AIR variants have a joint lookup argument.
+///
+/// The machine includes the chips of the precompiles of the extension `E`, which are defined
+/// outside of this crate, as described in [crate::stark::PrecompileExtension].
 #[derive(MachineAir)]
-pub enum RiscvAir<F: PrimeField32> {
+pub enum RiscvAir<F: PrimeField32, E: PrecompileExtension<F> = NoPrecompiles> {
     /// An AIR that containts a preprocessed program table and a lookup for the instructions.
     Program(ProgramChip),
     /// An AIR for the RISC-V CPU. Each row represents a cpu cycle.
@@ -88,7 +106,7 @@ pub enum RiscvAir<F: PrimeField32> {
     Secp256k1Double(WeierstrassDoubleAssignChip<SwCurve<Secp256k1Parameters>>),
     /// A precompile for the Keccak permutation.
     KeccakP(KeccakPermuteChip),
-    /// A precompile for the Blake3 compression function. (Disabled by default.)
+    /// A precompile for the Blake3 compression function.
     Blake3Compress(Blake3CompressInnerChip),
     /// A precompile for addition on the Elliptic curve bn254.
     Bn254Add(WeierstrassAddAssignChip<SwCurve<Bn254Parameters>>),
@@ -102,20 +120,69 @@ pub enum RiscvAir<F: PrimeField32> {
     Uint256Mul(Uint256MulChip),
     /// A precompile for decompressing a point on the BLS12-381 curve.
     Bls12381Decompress(WeierstrassDecompressChip<SwCurve<Bls12381Parameters>>),
+    /// A precompile for arithmetic in the base field of the BLS12-381 curve.
+    Bls12381Fp(FpOpChip<Bls12381BaseField>),
+    /// A precompile for addition on the Elliptic curve secp256r1.
+    Secp256r1Add(WeierstrassAddAssignChip<SwCurve<Secp256r1Parameters>>),
+    /// A precompile for doubling a point on the Elliptic curve secp256r1.
+    Secp256r1Double(WeierstrassDoubleAssignChip<SwCurve<Secp256r1Parameters>>),
+    /// A precompile for 2048-bit modular multiplication.
+    Uint2048MulMod(BigUintMulModChip<Uint2048>),
+    /// A precompile for 4096-bit modular multiplication.
+    Uint4096MulMod(BigUintMulModChip<Uint4096>),
+    /// A precompile for 384-bit modular multiplication.
+    Uint384MulMod(BigUintMulModChip<Uint384>),
+    /// A precompile for 512-bit modular multiplication.
+    Uint512MulMod(BigUintMulModChip<Uint512>),
+    /// A precompile for AES encryption rounds.
+    AesRound(AesRoundChip),
+    /// A precompile for multiplication in the GHASH field of AES-GCM.
+    GhashMul(GhashMulChip),
+    /// A precompile for the rounds of the ChaCha20 block function.
+    ChaCha20Permute(ChaCha20PermuteChip),
+    /// A precompile for the RIPEMD-160 compression function.
+    Ripemd160Compress(Ripemd160CompressChip),
+    /// A precompile for recovering the secp256k1 public key of an ECDSA signature.
+    Secp256k1Ecrecover(Secp256k1EcrecoverChip),
+    /// A precompile for multiply-accumulates of byte vectors over GF(2^8).
+    Gf256Mac(Gf256MacChip),
+    /// A precompile for absorbing a block into a Keccak sponge.
+    KeccakAbsorb(KeccakAbsorbChip),
+    /// A precompile for the Poseidon2 permutation over BabyBear.
+    Poseidon2Permute(Poseidon2PermuteChip),
+    /// A chip of a precompile of the extension of the machine.
+    Extension(E),
 }
 
 impl<F: PrimeField32> RiscvAir<F> {
-    #[instrument("construct RiscvAir machine", level = "debug", skip_all)]
     pub fn machine<SC: StarkGenericConfig<Val = F>>(config: SC) -> StarkMachine<SC, Self> {
-        let chips = Self::get_all()
+        Self::machine_with_precompiles(config)
+    }
+
+    /// Get all the different RISC-V AIRs.
+    pub fn get_all() -> Vec<Self> {
+        Self::get_all_with_precompiles()
+    }
+}
+
+impl<F: PrimeField32, E: PrecompileExtension<F>> RiscvAir<F, E> {
+    /// The machine of the RISC-V AIRs and of the chips of the extension `E`.
+    #[instrument("construct RiscvAir machine", level = "debug", skip_all)]
+    pub fn machine_with_precompiles<SC: StarkGenericConfig<Val = F>>(
+        config: SC,
+    ) -> StarkMachine<SC, Self>
+    where
+        E: Air<InteractionBuilder<F>> + Air<SymbolicAirBuilder<F>>,
+    {
+        let chips = Self::get_all_with_precompiles()
             .into_iter()
             .map(Chip::new)
             .collect::<Vec<_>>();
         StarkMachine::new(config, chips, SP1_PROOF_NUM_PV_ELTS)
     }
 
-    /// Get all the different RISC-V AIRs.
-    pub fn get_all() -> Vec<Self> {
+    /// Get all the different RISC-V AIRs and the chips of the extension `E`.
+    pub fn get_all_with_precompiles() -> Vec<Self> {
         // The order of the chips is important, as it is used to determine the order of trace
         // generation. In the future, we will detect that order automatically.
         let mut chips = vec![];
@@ -152,6 +219,40 @@ impl<F: PrimeField32> RiscvAir<F> {
         chips.push(RiscvAir::Uint256Mul(uint256_mul));
         let bls12381_decompress = WeierstrassDecompressChip::<SwCurve<Bls12381Parameters>>::new();
         chips.push(RiscvAir::Bls12381Decompress(bls12381_decompress));
+        let bls12381_fp = FpOpChip::<Bls12381BaseField>::new();
+        chips.push(RiscvAir::Bls12381Fp(bls12381_fp));
+        let secp256r1_add_assign = WeierstrassAddAssignChip::<SwCurve<Secp256r1Parameters>>::new();
+        chips.push(RiscvAir::Secp256r1Add(secp256r1_add_assign));
+        let secp256r1_double_assign =
+            WeierstrassDoubleAssignChip::<SwCurve<Secp256r1Parameters>>::new();
+        chips.push(RiscvAir::Secp256r1Double(secp256r1_double_assign));
+        let uint2048_mulmod = BigUintMulModChip::<Uint2048>::new();
+        chips.push(RiscvAir::Uint2048MulMod(uint2048_mulmod));
+        let uint4096_mulmod = BigUintMulModChip::<Uint4096>::new();
+        chips.push(RiscvAir::Uint4096MulMod(uint4096_mulmod));
+        let uint384_mulmod = BigUintMulModChip::<Uint384>::new();
+        chips.push(RiscvAir::Uint384MulMod(uint384_mulmod));
+        let uint512_mulmod = BigUintMulModChip::<Uint512>::new();
+        chips.push(RiscvAir::Uint512MulMod(uint512_mulmod));
+        let aes_round = AesRoundChip::new();
+        chips.push(RiscvAir::AesRound(aes_round));
+        let ghash_mul = GhashMulChip::new();
+        chips.push(RiscvAir::GhashMul(ghash_mul));
+        let chacha20_permute = ChaCha20PermuteChip::new();
+        chips.push(RiscvAir::ChaCha20Permute(chacha20_permute));
+        let ripemd160_compress = Ripemd160CompressChip::new();
+        chips.push(RiscvAir::Ripemd160Compress(ripemd160_compress));
+        let secp256k1_ecrecover = Secp256k1EcrecoverChip::new();
+        chips.push(RiscvAir::Secp256k1Ecrecover(secp256k1_ecrecover));
+        let gf256_mac = Gf256MacChip::new();
+        chips.push(RiscvAir::Gf256Mac(gf256_mac));
+        let blake3_compress_inner = Blake3CompressInnerChip::new();
+        chips.push(RiscvAir::Blake3Compress(blake3_compress_inner));
+        let keccak_absorb = KeccakAbsorbChip::new();
+        chips.push(RiscvAir::KeccakAbsorb(keccak_absorb));
+        let poseidon2_permute = Poseidon2PermuteChip::new();
+        chips.push(RiscvAir::Poseidon2Permute(poseidon2_permute));
+        chips.extend(E::chips().into_iter().map(RiscvAir::Extension));
         let add = AddSubChip::default();
         chips.push(RiscvAir::Add(add));
         let bitwise = BitwiseChip::default();
@@ -179,15 +280,15 @@ impl<F: PrimeField32> RiscvAir<F> {
     }
 }
 
-impl<F: PrimeField32> PartialEq for RiscvAir<F> {
+impl<F: PrimeField32, E: PrecompileExtension<F>> PartialEq for RiscvAir<F, E> {
     fn eq(&self, other: &Self) -> bool {
         self.name() == other.name()
     }
 }
 
-impl<F: PrimeField32> Eq for RiscvAir<F> {}
+impl<F: PrimeField32, E: PrecompileExtension<F>> Eq for RiscvAir<F, E> {}
 
-impl<F: PrimeField32> core::hash::Hash for RiscvAir<F> {
+impl<F: PrimeField32, E: PrecompileExtension<F>> core::hash::Hash for RiscvAir<F, E> {
     fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.name().hash(state);
     }
@@ -13,7 +13,7 @@ use crate::{
 
 use super::{
     eval_permutation_constraints, generate_permutation_trace, permutation_trace_width,
-    PROOF_MAX_NUM_PVS,
+    LookupArgument, PROOF_MAX_NUM_PVS,
 };
 
 /// An Air that encodes lookups based on interactions.
@@ -26,6 +26,8 @@ pub struct Chip<F: Field, A> {
     receives: Vec<Interaction<F>>,
     /// The relative log degree of the quotient polynomial, i.e. `log2(max_constraint_degree - 1)`.
     log_quotient_degree: usize,
+    /// The layout of the lookup argument of the permutation trace.
+    lookup_argument: LookupArgument,
 }
 
 impl<F: Field, A> Chip<F, A> {
@@ -43,6 +45,18 @@ impl<F: Field, A> Chip<F, A> {
     pub const fn log_quotient_degree(&self) -> usize {
         self.log_quotient_degree
     }
+
+    /// The layout of the lookup argument of the permutation trace.
+    pub const fn lookup_argument(&self) -> LookupArgument {
+        self.lookup_argument
+    }
+
+    /// Sets the layout of the lookup argument of the permutation trace. The constraints of both
+    /// layouts have the same degree, so the quotient degree of the chip does not change.
+    pub fn with_lookup_argument(mut self, lookup_argument: LookupArgument) -> Self {
+        self.lookup_argument = lookup_argument;
+        self
+    }
 }
 
 impl<F: PrimeField32, A: MachineAir<F>> Chip<F, A> {
@@ -80,6 +94,7 @@ where
             sends,
             receives,
             log_quotient_degree,
+            lookup_argument: LookupArgument::default(),
         }
     }
 
@@ -98,12 +113,13 @@ where
         self.receives.iter().filter(|i| i.kind == kind).count()
     }
 
+    /// Generates the permutation trace of the chip, and returns it with its cumulative sum.
     pub fn generate_permutation_trace<EF: ExtensionField<F>>(
         &self,
         preprocessed: Option<&RowMajorMatrix<F>>,
         main: &mut RowMajorMatrix<F>,
         random_elements: &[EF],
-    ) -> RowMajorMatrix<EF>
+    ) -> (RowMajorMatrix<EF>, EF)
     where
         F: PrimeField,
     {
@@ -115,6 +131,7 @@ where
             main,
             random_elements,
             batch_size,
+            self.lookup_argument,
         )
     }
 
@@ -123,6 +140,7 @@ where
         permutation_trace_width(
             self.sends().len() + self.receives().len(),
             self.logup_batch_size(),
+            self.lookup_argument,
         )
     }
 
@@ -197,7 +215,13 @@ where
         self.air.eval(builder);
         // Evaluate permutation constraints.
         let batch_size = self.logup_batch_size();
-        eval_permutation_constraints(&self.sends, &self.receives, batch_size, builder);
+        eval_permutation_constraints(
+            &self.sends,
+            &self.receives,
+            batch_size,
+            self.lookup_argument,
+            builder,
+        );
     }
 }
 
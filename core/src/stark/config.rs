@@ -1,73 +1,8 @@
-use p3_challenger::{CanObserve, CanSample, FieldChallenger};
-use p3_commit::{Pcs, PolynomialSpace};
-use p3_field::{ExtensionField, Field, PrimeField};
-use serde::{de::DeserializeOwned, Serialize};
-
-pub type Domain<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
-    <SC as StarkGenericConfig>::Challenge,
-    <SC as StarkGenericConfig>::Challenger,
->>::Domain;
-
-pub type Val<SC> = <<<SC as StarkGenericConfig>::Pcs as Pcs<
-    <SC as StarkGenericConfig>::Challenge,
-    <SC as StarkGenericConfig>::Challenger,
->>::Domain as PolynomialSpace>::Val;
-
-pub type Dom<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
-    <SC as StarkGenericConfig>::Challenge,
-    <SC as StarkGenericConfig>::Challenger,
->>::Domain;
-
-pub type PackedVal<SC> = <Val<SC> as Field>::Packing;
-
-pub type PackedChallenge<SC> =
-    <<SC as StarkGenericConfig>::Challenge as ExtensionField<Val<SC>>>::ExtensionPacking;
-
-pub type Com<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
-    <SC as StarkGenericConfig>::Challenge,
-    <SC as StarkGenericConfig>::Challenger,
->>::Commitment;
-
-pub type OpeningProof<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
-    <SC as StarkGenericConfig>::Challenge,
-    <SC as StarkGenericConfig>::Challenger,
->>::Proof;
-
-pub type OpeningError<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
-    <SC as StarkGenericConfig>::Challenge,
-    <SC as StarkGenericConfig>::Challenger,
->>::Error;
-
-pub type PcsProverData<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
-    <SC as StarkGenericConfig>::Challenge,
-    <SC as StarkGenericConfig>::Challenger,
->>::ProverData;
-
-pub type Challenge<SC> = <SC as StarkGenericConfig>::Challenge;
-pub type Challenger<SC> = <SC as StarkGenericConfig>::Challenger;
-
-pub trait StarkGenericConfig: Send + Sync + Serialize + DeserializeOwned + Clone {
-    type Val: PrimeField;
-
-    type Domain: PolynomialSpace<Val = Self::Val> + Sync;
-
-    /// The PCS used to commit to trace polynomials.
-    type Pcs: Pcs<Self::Challenge, Self::Challenger, Domain = Self::Domain> + Sync;
-
-    /// The field from which most random challenges are drawn.
-    type Challenge: ExtensionField<Self::Val>;
-
-    /// The challenger (Fiat-Shamir) implementation used.
-    type Challenger: FieldChallenger<Val<Self>>
-        + CanObserve<<Self::Pcs as Pcs<Self::Challenge, Self::Challenger>>::Commitment>
-        + CanSample<Self::Challenge>;
-
-    /// Get the PCS used by this configuration.
-    fn pcs(&self) -> &Self::Pcs;
-
-    /// Initialize a new challenger.
-    fn challenger(&self) -> Self::Challenger;
-}
+pub use sp1_stark_verifier::{
+    Challenge, Challenger, Com, Dom, Domain, DomainSeparator, FriParameters, LookupArgument,
+    MerkleHash, OpeningError, OpeningProof, PackedChallenge, PackedVal, PcsProverData,
+    StarkGenericConfig, Val,
+};
 
 pub struct UniConfig<SC>(pub SC);
 
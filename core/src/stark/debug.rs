@@ -15,6 +15,14 @@ use p3_matrix::{dense::RowMajorMatrix, Matrix};
 use super::{MachineChip, StarkGenericConfig, Val};
 use crate::air::{EmptyMessageBuilder, MachineAir, MultiTableAirBuilder};
 
+/// A constraint of a chip which does not hold on a row of its trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstraintFailure {
+    pub row: usize,
+    /// The index of the constraint, in the order in which the chip evaluates its constraints.
+    pub constraint: usize,
+}
+
 /// Checks that the constraints of the given AIR are satisfied, including the permutation trace.
 ///
 /// Note that this does not actually verify the proof.
@@ -23,23 +31,90 @@ pub fn debug_constraints<SC, A>(
     preprocessed: Option<&RowMajorMatrix<Val<SC>>>,
     main: &RowMajorMatrix<Val<SC>>,
     perm: &RowMajorMatrix<SC::Challenge>,
+    cumulative_sum: SC::Challenge,
     perm_challenges: &[SC::Challenge],
     public_values: Vec<Val<SC>>,
 ) where
     SC: StarkGenericConfig,
     Val<SC>: PrimeField32,
     A: MachineAir<Val<SC>> + for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
+{
+    let result = eval_constraints::<SC, A>(
+        chip,
+        preprocessed,
+        main,
+        perm,
+        cumulative_sum,
+        perm_challenges,
+        &public_values,
+        true,
+    );
+    if let Err(failure) = result {
+        let height = main.height();
+        eprintln!("local: {:?}", &*main.row_slice(failure.row));
+        eprintln!("next:  {:?}", &*main.row_slice((failure.row + 1) % height));
+        eprintln!("failed at row {} of chip {}", failure.row, chip.name());
+        exit(1);
+    }
+}
+
+/// Checks that the constraints of the given AIR are satisfied, including the permutation trace,
+/// and returns the first constraint which does not hold.
+///
+/// Unlike [debug_constraints], this does not print anything nor exit when a constraint fails.
+pub fn check_constraints<SC, A>(
+    chip: &MachineChip<SC, A>,
+    preprocessed: Option<&RowMajorMatrix<Val<SC>>>,
+    main: &RowMajorMatrix<Val<SC>>,
+    perm: &RowMajorMatrix<SC::Challenge>,
+    cumulative_sum: SC::Challenge,
+    perm_challenges: &[SC::Challenge],
+    public_values: &[Val<SC>],
+) -> Result<(), ConstraintFailure>
+where
+    SC: StarkGenericConfig,
+    Val<SC>: PrimeField32,
+    A: MachineAir<Val<SC>> + for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
+{
+    eval_constraints::<SC, A>(
+        chip,
+        preprocessed,
+        main,
+        perm,
+        cumulative_sum,
+        perm_challenges,
+        public_values,
+        false,
+    )
+}
+
+/// Evaluates the constraints of a chip on every row of its trace, and stops at the first row with
+/// a constraint which does not hold. If `verbose`, the failing constraint is printed with a
+/// backtrace.
+#[allow(clippy::too_many_arguments)]
+fn eval_constraints<SC, A>(
+    chip: &MachineChip<SC, A>,
+    preprocessed: Option<&RowMajorMatrix<Val<SC>>>,
+    main: &RowMajorMatrix<Val<SC>>,
+    perm: &RowMajorMatrix<SC::Challenge>,
+    cumulative_sum: SC::Challenge,
+    perm_challenges: &[SC::Challenge],
+    public_values: &[Val<SC>],
+    verbose: bool,
+) -> Result<(), ConstraintFailure>
+where
+    SC: StarkGenericConfig,
+    Val<SC>: PrimeField32,
+    A: MachineAir<Val<SC>> + for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
 {
     assert_eq!(main.height(), perm.height());
     let height = main.height();
     if height == 0 {
-        return;
+        return Ok(());
     }
 
-    let cumulative_sum = perm.row_slice(perm.height() - 1).last().copied().unwrap();
-
     // Check that constraints are satisfied.
-    (0..height).for_each(|i| {
+    (0..height).try_for_each(|i| {
         let i_next = (i + 1) % height;
 
         let main_local = main.row_slice(i);
@@ -65,7 +140,6 @@ pub fn debug_constraints<SC, A>(
         let perm_next = perm.row_slice(i_next);
         let perm_next = &(*perm_next);
 
-        let public_values = public_values.to_vec();
         let mut builder = DebugConstraintBuilder {
             preprocessed: VerticalPair::new(
                 RowMajorMatrixView::new_row(&preprocessed_local),
@@ -84,7 +158,10 @@ pub fn debug_constraints<SC, A>(
             is_first_row: Val::<SC>::zero(),
             is_last_row: Val::<SC>::zero(),
             is_transition: Val::<SC>::one(),
-            public_values: &public_values,
+            public_values,
+            verbose,
+            num_constraints: 0,
+            failed_constraint: None,
         };
         if i == 0 {
             builder.is_first_row = Val::<SC>::one();
@@ -96,13 +173,17 @@ pub fn debug_constraints<SC, A>(
         let result = catch_unwind_silent(AssertUnwindSafe(|| {
             chip.eval(&mut builder);
         }));
-        if result.is_err() {
-            eprintln!("local: {:?}", main_local);
-            eprintln!("next:  {:?}", main_next);
-            eprintln!("failed at row {} of chip {}", i, chip.name());
-            exit(1);
+
+        // A chip which panics fails at the constraint it was evaluating.
+        let failed_constraint = match result {
+            Ok(()) => builder.failed_constraint,
+            Err(_) => builder.failed_constraint.or(Some(builder.num_constraints)),
+        };
+        match failed_constraint {
+            Some(constraint) => Err(ConstraintFailure { row: i, constraint }),
+            None => Ok(()),
         }
-    });
+    })
 }
 
 fn catch_unwind_silent<F: FnOnce() -> R + panic::UnwindSafe, R>(f: F) -> std::thread::Result<R> {
@@ -113,7 +194,8 @@ fn catch_unwind_silent<F: FnOnce() -> R + panic::UnwindSafe, R>(f: F) -> std::th
     result
 }
 
-/// Checks that all the interactions between the chips has been satisfied.
+/// Checks that all the interactions between the chips has been satisfied, from permutation traces
+/// of the batched layout, whose last entry is the cumulative sum.
 ///
 /// Note that this does not actually verify the proof.
 pub fn debug_cumulative_sums<F: Field, EF: ExtensionField<F>>(perms: &[RowMajorMatrix<EF>]) {
@@ -135,6 +217,12 @@ pub struct DebugConstraintBuilder<'a, F: Field, EF: ExtensionField<F>> {
     pub(crate) is_last_row: F,
     pub(crate) is_transition: F,
     pub(crate) public_values: &'a [F],
+    /// Whether a failing constraint is printed and aborts the evaluation of the row.
+    pub(crate) verbose: bool,
+    /// The number of constraints evaluated so far.
+    pub(crate) num_constraints: usize,
+    /// The index of the first constraint which did not hold.
+    pub(crate) failed_constraint: Option<usize>,
 }
 
 impl<'a, F, EF> ExtensionBuilder for DebugConstraintBuilder<'a, F, EF>
@@ -150,7 +238,10 @@ where
     where
         I: Into<Self::ExprEF>,
     {
-        assert_eq!(x.into(), EF::zero(), "constraints must evaluate to zero");
+        let x = x.into();
+        if self.check(x == EF::zero()) {
+            assert_eq!(x, EF::zero(), "constraints must evaluate to zero");
+        }
     }
 }
 
@@ -187,9 +278,24 @@ where
     F: Field,
     EF: ExtensionField<F>,
 {
+    /// Records the evaluation of a constraint, and returns whether it failed in verbose mode, in
+    /// which case the caller reports it and panics.
+    #[inline]
+    fn check(&mut self, holds: bool) -> bool {
+        let index = self.num_constraints;
+        self.num_constraints += 1;
+        if holds {
+            return false;
+        }
+        if self.failed_constraint.is_none() {
+            self.failed_constraint = Some(index);
+        }
+        self.verbose
+    }
+
     #[inline]
-    fn debug_constraint(&self, x: F, y: F) {
-        if x != y {
+    fn debug_constraint(&mut self, x: F, y: F) {
+        if self.check(x == y) {
             let backtrace = std::backtrace::Backtrace::force_capture();
             eprintln!("constraint failed: {:?} != {:?}\n{}", x, y, backtrace);
             panic!();
@@ -242,7 +348,7 @@ where
     /// Assert that `x` is a boolean, i.e. either 0 or 1.
     fn assert_bool<I: Into<Self::Expr>>(&mut self, x: I) {
         let x = x.into();
-        if x != F::zero() && x != F::one() {
+        if self.check(x == F::zero() || x == F::one()) {
             let backtrace = std::backtrace::Backtrace::force_capture();
             eprintln!("constraint failed: {:?} is not a bool\n{}", x, backtrace);
             panic!();
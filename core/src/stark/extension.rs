@@ -0,0 +1,304 @@
+//! Precompiles defined outside of this crate.
+//!
+//! A downstream crate adds a precompile to the RISC-V machine without forking it by:
+//!
+//! 1. choosing a syscall number of the form `0x00_XX_01_YY`, where `XX` is the number of extra
+//!    cycles of the syscall and its id `YY` is in [crate::runtime::CUSTOM_SYSCALL_IDS];
+//! 2. implementing [Syscall] to execute the precompile, which records its events with
+//!    [ExecutionRecord::add_custom_precompile_event] and returns `None`, since the CPU constrains
+//!    the register of the syscall number to be unchanged by a precompile;
+//! 3. implementing [MachineAir] and [Air] for a chip which reads the events with
+//!    [ExecutionRecord::custom_precompile_events] and receives the syscall with
+//!    [crate::air::AluAirBuilder::receive_syscall];
+//! 4. implementing [PrecompileExtension] for the chip, or for an enum of several chips deriving
+//!    `MachineAir`.
+//!
+//! The machine `RiscvAir::<F, E>::machine_with_precompiles` then includes the chips of the
+//! extension `E`, and [crate::utils::prove_with_precompiles] proves programs which call the
+//! syscalls of the extension.
+
+use std::sync::Arc;
+
+use p3_air::{Air, BaseAir};
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use p3_uni_stark::SymbolicAirBuilder;
+
+use crate::air::{MachineAir, SP1AirBuilder};
+use crate::lookup::InteractionBuilder;
+use crate::runtime::{ExecutionRecord, Program, Runtime, Syscall};
+use crate::stark::{
+    DebugConstraintBuilder, ProverConstraintFolder, StarkGenericConfig, Val,
+    VerifierConstraintFolder,
+};
+
+/// The chips and syscalls of precompiles defined outside of this crate, which the RISC-V machine
+/// includes next to its own chips.
+pub trait PrecompileExtension<F: PrimeField32>:
+    MachineAir<F, Record = ExecutionRecord, Program = Program> + Sized + Send + Sync
+{
+    /// The chips of the extension.
+    fn chips() -> Vec<Self>;
+
+    /// The syscalls of the precompiles of the extension, with their syscall numbers.
+    fn syscalls() -> Vec<(u32, Arc<dyn Syscall>)>;
+
+    /// Registers the syscalls of the extension in `runtime`.
+    fn register_syscalls(runtime: &mut Runtime) {
+        for (code, syscall) in Self::syscalls() {
+            runtime.register_custom_syscall(code, syscall);
+        }
+    }
+}
+
+/// An extension whose chips can be proven and verified with the config `SC`, which holds for any
+/// extension whose chips implement [Air] for every [SP1AirBuilder].
+pub trait PrecompileAir<SC: StarkGenericConfig>:
+    PrecompileExtension<Val<SC>>
+    + for<'a> Air<ProverConstraintFolder<'a, SC>>
+    + Air<InteractionBuilder<Val<SC>>>
+    + Air<SymbolicAirBuilder<Val<SC>>>
+    + for<'a> Air<VerifierConstraintFolder<'a, SC>>
+    + for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>
+where
+    Val<SC>: PrimeField32,
+{
+}
+
+impl<SC: StarkGenericConfig, E> PrecompileAir<SC> for E
+where
+    Val<SC>: PrimeField32,
+    E: PrecompileExtension<Val<SC>>
+        + for<'a> Air<ProverConstraintFolder<'a, SC>>
+        + Air<InteractionBuilder<Val<SC>>>
+        + Air<SymbolicAirBuilder<Val<SC>>>
+        + for<'a> Air<VerifierConstraintFolder<'a, SC>>
+        + for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
+{
+}
+
+/// The extension without any precompile, which is the default extension of the machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoPrecompiles {}
+
+impl<F> BaseAir<F> for NoPrecompiles {
+    fn width(&self) -> usize {
+        match *self {}
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for NoPrecompiles {
+    type Record = ExecutionRecord;
+
+    type Program = Program;
+
+    fn name(&self) -> String {
+        match *self {}
+    }
+
+    fn generate_trace(&self, _: &ExecutionRecord, _: &mut ExecutionRecord) -> RowMajorMatrix<F> {
+        match *self {}
+    }
+
+    fn included(&self, _: &ExecutionRecord) -> bool {
+        match *self {}
+    }
+}
+
+impl<AB: SP1AirBuilder> Air<AB> for NoPrecompiles {
+    fn eval(&self, _: &mut AB) {
+        match *self {}
+    }
+}
+
+impl<F: PrimeField32> PrecompileExtension<F> for NoPrecompiles {
+    fn chips() -> Vec<Self> {
+        vec![]
+    }
+
+    fn syscalls() -> Vec<(u32, Arc<dyn Syscall>)> {
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::{Borrow, BorrowMut};
+    use std::mem::size_of;
+
+    use p3_field::AbstractField;
+    use p3_matrix::Matrix;
+    use serde::{Deserialize, Serialize};
+    use sp1_derive::AlignedBorrow;
+
+    use super::*;
+    use crate::io::SP1Stdin;
+    use crate::runtime::{Instruction, Opcode, SyscallContext};
+    use crate::stark::RiscvAir;
+    use crate::utils::{
+        pad_rows, prove_with_precompiles, setup_logger, BabyBearPoseidon2, ProgressReporter,
+        SP1CoreOpts,
+    };
+
+    /// The syscall number of the test precompile, with the id `0x80` and no extra cycles.
+    const SWAP_CODE: u32 = 0x00_00_01_80;
+
+    const NUM_SWAP_COLS: usize = size_of::<SwapCols<u8>>();
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct SwapEvent {
+        shard: u32,
+        channel: u32,
+        clk: u32,
+        a: u32,
+        b: u32,
+    }
+
+    #[derive(AlignedBorrow)]
+    #[repr(C)]
+    struct SwapCols<T> {
+        shard: T,
+        channel: T,
+        clk: T,
+        a: T,
+        b: T,
+        is_real: T,
+    }
+
+    /// A precompile which records its two arguments. Its chip only receives the syscall, which is
+    /// enough to check that the interactions of the CPU and of the extension are balanced.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct SwapChip;
+
+    impl Syscall for SwapChip {
+        fn execute(&self, ctx: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+            let event = SwapEvent {
+                shard: ctx.current_shard(),
+                channel: ctx.current_channel(),
+                clk: ctx.clk,
+                a: arg1,
+                b: arg2,
+            };
+            ctx.record_mut()
+                .add_custom_precompile_event(SWAP_CODE, &event);
+            None
+        }
+    }
+
+    impl<F> BaseAir<F> for SwapChip {
+        fn width(&self) -> usize {
+            NUM_SWAP_COLS
+        }
+    }
+
+    impl<F: PrimeField32> MachineAir<F> for SwapChip {
+        type Record = ExecutionRecord;
+
+        type Program = Program;
+
+        fn name(&self) -> String {
+            "Swap".to_string()
+        }
+
+        fn generate_trace(
+            &self,
+            input: &ExecutionRecord,
+            _: &mut ExecutionRecord,
+        ) -> RowMajorMatrix<F> {
+            let mut rows = input
+                .custom_precompile_events::<SwapEvent>(SWAP_CODE)
+                .into_iter()
+                .map(|event| {
+                    let mut row = [F::zero(); NUM_SWAP_COLS];
+                    let cols: &mut SwapCols<F> = row.as_mut_slice().borrow_mut();
+                    cols.shard = F::from_canonical_u32(event.shard);
+                    cols.channel = F::from_canonical_u32(event.channel);
+                    cols.clk = F::from_canonical_u32(event.clk);
+                    cols.a = F::from_wrapped_u32(event.a);
+                    cols.b = F::from_wrapped_u32(event.b);
+                    cols.is_real = F::one();
+                    row
+                })
+                .collect::<Vec<_>>();
+            pad_rows(&mut rows, || [F::zero(); NUM_SWAP_COLS]);
+            RowMajorMatrix::new(rows.into_iter().flatten().collect(), NUM_SWAP_COLS)
+        }
+
+        fn included(&self, shard: &ExecutionRecord) -> bool {
+            shard.num_custom_precompile_events(SWAP_CODE) > 0
+        }
+    }
+
+    impl<AB: SP1AirBuilder> Air<AB> for SwapChip {
+        fn eval(&self, builder: &mut AB) {
+            let main = builder.main();
+            let local = main.row_slice(0);
+            let local: &SwapCols<AB::Var> = (*local).borrow();
+            builder.assert_bool(local.is_real);
+            builder.receive_syscall(
+                local.shard,
+                local.channel,
+                local.clk,
+                AB::F::from_canonical_u32(SWAP_CODE & 0xff),
+                local.a,
+                local.b,
+                local.is_real,
+            );
+        }
+    }
+
+    impl<F: PrimeField32> PrecompileExtension<F> for SwapChip {
+        fn chips() -> Vec<Self> {
+            vec![SwapChip]
+        }
+
+        fn syscalls() -> Vec<(u32, Arc<dyn Syscall>)> {
+            vec![(SWAP_CODE, Arc::new(SwapChip))]
+        }
+    }
+
+    #[test]
+    fn test_custom_precompile_prove() {
+        setup_logger();
+        let mut instructions = Vec::new();
+        for (a, b) in [(1, 2), (0xabcd, 0x1234), (7, 0)] {
+            instructions.extend([
+                Instruction::new(Opcode::ADD, 5, 0, SWAP_CODE, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, a, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, b, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        let program = Program::new(instructions, 0, 0);
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        <SwapChip as PrecompileExtension<p3_baby_bear::BabyBear>>::register_syscalls(&mut runtime);
+        runtime.run().unwrap();
+        assert_eq!(runtime.register(crate::runtime::Register::X5), SWAP_CODE);
+        assert_eq!(runtime.record.num_custom_precompile_events(SWAP_CODE), 3);
+
+        let config = BabyBearPoseidon2::new();
+        let (proof, _) = prove_with_precompiles::<_, SwapChip>(
+            program.clone(),
+            &SP1Stdin::new(),
+            config.clone(),
+            SP1CoreOpts::default(),
+            &ProgressReporter::default(),
+        )
+        .unwrap();
+
+        let machine = RiscvAir::<_, SwapChip>::machine_with_precompiles(config);
+        assert!(machine.chips().iter().any(|chip| chip.name() == "Swap"));
+        let (_, vk) = machine.setup(&program);
+        let mut challenger = machine.config().challenger();
+        machine.verify(&vk, &proof, &mut challenger).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_custom_syscall_code_checked() {
+        let mut runtime = Runtime::new(Program::new(vec![], 0, 0), SP1CoreOpts::default());
+        // The id of a built-in syscall is rejected.
+        runtime.register_custom_syscall(0x00_00_01_05, Arc::new(SwapChip));
+    }
+}
@@ -1,17 +1,15 @@
-use std::{
-    marker::PhantomData,
-    ops::{Add, Mul, MulAssign, Sub},
-};
+use std::ops::{Add, Mul, MulAssign, Sub};
 
 use p3_field::{AbstractField, ExtensionField, Field};
 use p3_matrix::dense::RowMajorMatrixView;
 use p3_matrix::stack::VerticalPair;
 
-use super::{Challenge, PackedChallenge, PackedVal, StarkGenericConfig, Val};
+use super::{PackedChallenge, PackedVal, StarkGenericConfig, Val};
 use crate::air::{EmptyMessageBuilder, MultiTableAirBuilder};
 use p3_air::{
     AirBuilder, AirBuilderWithPublicValues, ExtensionBuilder, PairBuilder, PermutationAirBuilder,
 };
+pub use sp1_stark_verifier::{GenericVerifierConstraintFolder, VerifierConstraintFolder};
 
 /// A folder for prover constraints.
 pub struct ProverConstraintFolder<'a, SC: StarkGenericConfig> {
@@ -125,174 +123,6 @@ impl<'a, SC: StarkGenericConfig> AirBuilderWithPublicValues for ProverConstraint
     }
 }
 
-pub type VerifierConstraintFolder<'a, SC> = GenericVerifierConstraintFolder<
-    'a,
-    Val<SC>,
-    Challenge<SC>,
-    Val<SC>,
-    Challenge<SC>,
-    Challenge<SC>,
->;
-
-/// A folder for verifier constraints.
-pub struct GenericVerifierConstraintFolder<'a, F, EF, PubVar, Var, Expr> {
-    pub preprocessed: VerticalPair<RowMajorMatrixView<'a, Var>, RowMajorMatrixView<'a, Var>>,
-    pub main: VerticalPair<RowMajorMatrixView<'a, Var>, RowMajorMatrixView<'a, Var>>,
-    pub perm: VerticalPair<RowMajorMatrixView<'a, Var>, RowMajorMatrixView<'a, Var>>,
-    pub perm_challenges: &'a [Var],
-    pub cumulative_sum: Var,
-    pub is_first_row: Var,
-    pub is_last_row: Var,
-    pub is_transition: Var,
-    pub alpha: Var,
-    pub accumulator: Expr,
-    pub public_values: &'a [PubVar],
-    pub _marker: PhantomData<(F, EF)>,
-}
-
-impl<'a, F, EF, PubVar, Var, Expr> AirBuilder
-    for GenericVerifierConstraintFolder<'a, F, EF, PubVar, Var, Expr>
-where
-    F: Field,
-    EF: ExtensionField<F>,
-    Expr: AbstractField
-        + From<F>
-        + Add<Var, Output = Expr>
-        + Add<F, Output = Expr>
-        + Sub<Var, Output = Expr>
-        + Sub<F, Output = Expr>
-        + Mul<Var, Output = Expr>
-        + Mul<F, Output = Expr>
-        + MulAssign<EF>,
-    Var: Into<Expr>
-        + Copy
-        + Add<F, Output = Expr>
-        + Add<Var, Output = Expr>
-        + Add<Expr, Output = Expr>
-        + Sub<F, Output = Expr>
-        + Sub<Var, Output = Expr>
-        + Sub<Expr, Output = Expr>
-        + Mul<F, Output = Expr>
-        + Mul<Var, Output = Expr>
-        + Mul<Expr, Output = Expr>
-        + Send
-        + Sync,
-    PubVar: Into<Expr> + Copy,
-{
-    type F = F;
-    type Expr = Expr;
-    type Var = Var;
-    type M = VerticalPair<RowMajorMatrixView<'a, Var>, RowMajorMatrixView<'a, Var>>;
-
-    fn main(&self) -> Self::M {
-        self.main
-    }
-
-    fn is_first_row(&self) -> Self::Expr {
-        self.is_first_row.into()
-    }
-
-    fn is_last_row(&self) -> Self::Expr {
-        self.is_last_row.into()
-    }
-
-    fn is_transition_window(&self, size: usize) -> Self::Expr {
-        if size == 2 {
-            self.is_transition.into()
-        } else {
-            panic!("uni-stark only supports a window size of 2")
-        }
-    }
-
-    fn assert_zero<I: Into<Self::Expr>>(&mut self, x: I) {
-        let x: Expr = x.into();
-        self.accumulator *= self.alpha.into();
-        self.accumulator += x;
-    }
-}
-
-impl<'a, F, EF, PubVar, Var, Expr> ExtensionBuilder
-    for GenericVerifierConstraintFolder<'a, F, EF, PubVar, Var, Expr>
-where
-    F: Field,
-    EF: ExtensionField<F>,
-    Expr: AbstractField<F = EF>
-        + From<F>
-        + Add<Var, Output = Expr>
-        + Add<F, Output = Expr>
-        + Sub<Var, Output = Expr>
-        + Sub<F, Output = Expr>
-        + Mul<Var, Output = Expr>
-        + Mul<F, Output = Expr>
-        + MulAssign<EF>,
-    Var: Into<Expr>
-        + Copy
-        + Add<F, Output = Expr>
-        + Add<Var, Output = Expr>
-        + Add<Expr, Output = Expr>
-        + Sub<F, Output = Expr>
-        + Sub<Var, Output = Expr>
-        + Sub<Expr, Output = Expr>
-        + Mul<F, Output = Expr>
-        + Mul<Var, Output = Expr>
-        + Mul<Expr, Output = Expr>
-        + Send
-        + Sync,
-    PubVar: Into<Expr> + Copy,
-{
-    type EF = EF;
-    type ExprEF = Expr;
-    type VarEF = Var;
-
-    fn assert_zero_ext<I>(&mut self, x: I)
-    where
-        I: Into<Self::ExprEF>,
-    {
-        self.assert_zero(x)
-    }
-}
-
-impl<'a, F, EF, PubVar, Var, Expr> PermutationAirBuilder
-    for GenericVerifierConstraintFolder<'a, F, EF, PubVar, Var, Expr>
-where
-    F: Field,
-    EF: ExtensionField<F>,
-    Expr: AbstractField<F = EF>
-        + From<F>
-        + Add<Var, Output = Expr>
-        + Add<F, Output = Expr>
-        + Sub<Var, Output = Expr>
-        + Sub<F, Output = Expr>
-        + Mul<Var, Output = Expr>
-        + Mul<F, Output = Expr>
-        + MulAssign<EF>,
-    Var: Into<Expr>
-        + Copy
-        + Add<F, Output = Expr>
-        + Add<Var, Output = Expr>
-        + Add<Expr, Output = Expr>
-        + Sub<F, Output = Expr>
-        + Sub<Var, Output = Expr>
-        + Sub<Expr, Output = Expr>
-        + Mul<F, Output = Expr>
-        + Mul<Var, Output = Expr>
-        + Mul<Expr, Output = Expr>
-        + Send
-        + Sync,
-    PubVar: Into<Expr> + Copy,
-{
-    type MP = VerticalPair<RowMajorMatrixView<'a, Var>, RowMajorMatrixView<'a, Var>>;
-    type RandomVar = Var;
-
-    fn permutation(&self) -> Self::MP {
-        self.perm
-    }
-
-    fn permutation_randomness(&self) -> &[Self::Var] {
-        self.perm_challenges
-    }
-}
-
 impl<'a, F, EF, PubVar, Var, Expr> MultiTableAirBuilder
     for GenericVerifierConstraintFolder<'a, F, EF, PubVar, Var, Expr>
 where
@@ -329,40 +159,6 @@ where
     }
 }
 
-impl<'a, F, EF, PubVar, Var, Expr> PairBuilder
-    for GenericVerifierConstraintFolder<'a, F, EF, PubVar, Var, Expr>
-where
-    F: Field,
-    EF: ExtensionField<F>,
-    Expr: AbstractField<F = EF>
-        + From<F>
-        + Add<Var, Output = Expr>
-        + Add<F, Output = Expr>
-        + Sub<Var, Output = Expr>
-        + Sub<F, Output = Expr>
-        + Mul<Var, Output = Expr>
-        + Mul<F, Output = Expr>
-        + MulAssign<EF>,
-    Var: Into<Expr>
-        + Copy
-        + Add<F, Output = Expr>
-        + Add<Var, Output = Expr>
-        + Add<Expr, Output = Expr>
-        + Sub<F, Output = Expr>
-        + Sub<Var, Output = Expr>
-        + Sub<Expr, Output = Expr>
-        + Mul<F, Output = Expr>
-        + Mul<Var, Output = Expr>
-        + Mul<Expr, Output = Expr>
-        + Send
-        + Sync,
-    PubVar: Into<Expr> + Copy,
-{
-    fn preprocessed(&self) -> Self::M {
-        self.preprocessed
-    }
-}
-
 impl<'a, F, EF, PubVar, Var, Expr> EmptyMessageBuilder
     for GenericVerifierConstraintFolder<'a, F, EF, PubVar, Var, Expr>
 where
@@ -393,39 +189,3 @@ where
     PubVar: Into<Expr> + Copy,
 {
 }
-
-impl<'a, F, EF, PubVar, Var, Expr> AirBuilderWithPublicValues
-    for GenericVerifierConstraintFolder<'a, F, EF, PubVar, Var, Expr>
-where
-    F: Field,
-    EF: ExtensionField<F>,
-    Expr: AbstractField<F = EF>
-        + From<F>
-        + Add<Var, Output = Expr>
-        + Add<F, Output = Expr>
-        + Sub<Var, Output = Expr>
-        + Sub<F, Output = Expr>
-        + Mul<Var, Output = Expr>
-        + Mul<F, Output = Expr>
-        + MulAssign<EF>,
-    Var: Into<Expr>
-        + Copy
-        + Add<F, Output = Expr>
-        + Add<Var, Output = Expr>
-        + Add<Expr, Output = Expr>
-        + Sub<F, Output = Expr>
-        + Sub<Var, Output = Expr>
-        + Sub<Expr, Output = Expr>
-        + Mul<F, Output = Expr>
-        + Mul<Var, Output = Expr>
-        + Mul<Expr, Output = Expr>
-        + Send
-        + Sync,
-    PubVar: Into<Expr> + Copy,
-{
-    type PublicVar = PubVar;
-
-    fn public_values(&self) -> &[Self::PublicVar] {
-        self.public_values
-    }
-}
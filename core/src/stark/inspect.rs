@@ -0,0 +1,199 @@
+//! A summary of the structure of a shard proof, to triage proofs which fail to verify.
+//!
+//! The summary is read from the proof alone: the chips of the shard with the heights of their
+//! traces, the number of public values, and the shape of the FRI proof. The parameters of FRI which
+//! are not part of the proof, such as the blowup factor, are recorded in the verifying key.
+
+use std::fmt::{self, Display, Formatter};
+
+use p3_commit::Mmcs;
+use p3_field::{ExtensionField, Field};
+use p3_fri::TwoAdicFriPcsProof;
+use serde::{Deserialize, Serialize};
+
+use super::{OpeningProof, ShardProof, StarkGenericConfig};
+
+/// The structure of a shard proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardProofSummary {
+    /// The chips of the shard, in the order of their traces in the proof.
+    pub chips: Vec<ChipSummary>,
+    /// The number of public values of the shard.
+    pub num_public_values: usize,
+    /// The shape of the proof of the low degree of the opened polynomials.
+    pub fri: FriProofSummary,
+}
+
+/// The traces of a chip of a shard proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChipSummary {
+    /// The name of the chip.
+    pub name: String,
+    /// The logarithm of the height of the traces of the chip.
+    pub log_degree: usize,
+    /// The number of columns of the preprocessed trace of the chip.
+    pub preprocessed_width: usize,
+    /// The number of columns of the main trace of the chip.
+    pub main_width: usize,
+    /// The number of columns of the permutation trace of the chip, in extension field elements.
+    pub permutation_width: usize,
+    /// The number of chunks of the quotient of the chip.
+    pub quotient_chunks: usize,
+}
+
+/// The shape of a FRI proof.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FriProofSummary {
+    /// The number of queries of the proof.
+    pub num_queries: usize,
+    /// The number of folding layers of the proof.
+    pub num_layers: usize,
+}
+
+impl ChipSummary {
+    /// The height of the traces of the chip.
+    pub const fn height(&self) -> usize {
+        1 << self.log_degree
+    }
+}
+
+/// An opening proof of a PCS whose FRI proof can be summarized.
+pub trait OpeningProofSummary {
+    /// The shape of the FRI proof.
+    fn fri_summary(&self) -> FriProofSummary;
+}
+
+impl<Val, Challenge, InputMmcs, FriMmcs> OpeningProofSummary
+    for TwoAdicFriPcsProof<Val, Challenge, InputMmcs, FriMmcs>
+where
+    Val: Field,
+    Challenge: ExtensionField<Val>,
+    InputMmcs: Mmcs<Val>,
+    FriMmcs: Mmcs<Challenge>,
+{
+    fn fri_summary(&self) -> FriProofSummary {
+        FriProofSummary {
+            num_queries: self.fri_proof.query_proofs.len(),
+            num_layers: self.fri_proof.commit_phase_commits.len(),
+        }
+    }
+}
+
+/// A proof whose structure can be summarized.
+pub trait ProofSummary {
+    /// The structure of the proof.
+    fn summary(&self) -> ShardProofSummary;
+}
+
+impl<SC: StarkGenericConfig> ProofSummary for ShardProof<SC>
+where
+    OpeningProof<SC>: OpeningProofSummary,
+{
+    fn summary(&self) -> ShardProofSummary {
+        let mut names = vec![String::new(); self.opened_values.chips.len()];
+        for (name, &index) in self.chip_ordering.iter() {
+            names[index].clone_from(name);
+        }
+        let chips = self
+            .opened_values
+            .chips
+            .iter()
+            .zip(names)
+            .map(|(values, name)| ChipSummary {
+                name,
+                log_degree: values.log_degree,
+                preprocessed_width: values.preprocessed.local.len(),
+                main_width: values.main.local.len(),
+                permutation_width: values.permutation.local.len(),
+                quotient_chunks: values.quotient.len(),
+            })
+            .collect();
+
+        ShardProofSummary {
+            chips,
+            num_public_values: self.public_values.len(),
+            fri: self.opening_proof.fri_summary(),
+        }
+    }
+}
+
+impl ShardProofSummary {
+    /// The number of rows of the traces of all the chips.
+    pub fn total_rows(&self) -> usize {
+        self.chips.iter().map(ChipSummary::height).sum()
+    }
+}
+
+impl Display for ShardProofSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "shard proof: {} chips, {} rows, {} public values",
+            self.chips.len(),
+            self.total_rows(),
+            self.num_public_values
+        )?;
+        writeln!(
+            f,
+            "  fri: {} queries, {} layers",
+            self.fri.num_queries, self.fri.num_layers
+        )?;
+        writeln!(
+            f,
+            "  {:<32} {:>10} {:>6} {:>6} {:>6} {:>6}",
+            "chip", "height", "prep", "main", "perm", "quot"
+        )?;
+        for chip in self.chips.iter() {
+            writeln!(
+                f,
+                "  {:<32} {:>10} {:>6} {:>6} {:>6} {:>6}",
+                chip.name,
+                chip.height(),
+                chip.preprocessed_width,
+                chip.main_width,
+                chip.permutation_width,
+                chip.quotient_chunks
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::SP1Stdin;
+    use crate::runtime::tests::fibonacci_program;
+    use crate::utils::{prove, setup_logger, BabyBearPoseidon2, SP1CoreOpts};
+
+    #[test]
+    fn test_shard_proof_summary() {
+        setup_logger();
+        let (proof, _) = prove(
+            fibonacci_program(),
+            &SP1Stdin::new(),
+            BabyBearPoseidon2::new(),
+            SP1CoreOpts::default(),
+        )
+        .unwrap();
+
+        for shard in proof.shard_proofs.iter() {
+            let summary = shard.summary();
+            assert_eq!(summary.chips.len(), shard.chip_ordering.len());
+            for (index, (chip, values)) in summary
+                .chips
+                .iter()
+                .zip(shard.opened_values.chips.iter())
+                .enumerate()
+            {
+                assert_eq!(shard.chip_ordering[&chip.name], index);
+                assert_eq!(chip.log_degree, values.log_degree);
+                assert!(chip.main_width > 0);
+            }
+            assert!(summary.chips.iter().any(|chip| chip.name == "CPU"));
+            assert_eq!(summary.num_public_values, shard.public_values.len());
+            assert!(summary.fri.num_queries > 0);
+            assert!(summary.fri.num_layers > 0);
+        }
+    }
+}
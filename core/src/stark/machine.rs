@@ -1,27 +1,31 @@
 use itertools::Itertools;
 use p3_air::Air;
+use p3_baby_bear::BabyBear;
 use p3_challenger::CanObserve;
 use p3_challenger::FieldChallenger;
 use p3_commit::Pcs;
+use p3_commit::TwoAdicMultiplicativeCoset;
 use p3_field::AbstractField;
 use p3_field::Field;
 use p3_field::PrimeField32;
+use p3_field::TwoAdicField;
 use p3_matrix::dense::RowMajorMatrix;
-use p3_matrix::Dimensions;
 use p3_matrix::Matrix;
 use p3_maybe_rayon::prelude::*;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde::Serialize;
+use sp1_primitives::poseidon2_hash;
 use std::cmp::Reverse;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::fmt::Debug;
+use thiserror::Error;
 use tracing::instrument;
 
-use super::debug_constraints;
-use super::Dom;
+use super::{check_constraints, debug_constraints};
 use crate::air::MachineAir;
 use crate::air::MachineProgram;
+use crate::air::PublicValuesError;
 use crate::lookup::debug_interactions_with_all_chips;
 use crate::lookup::InteractionBuilder;
 use crate::lookup::InteractionKind;
@@ -31,13 +35,20 @@ use crate::stark::ProverConstraintFolder;
 use crate::stark::ShardProof;
 use crate::stark::VerifierConstraintFolder;
 use crate::utils::SP1CoreOpts;
+use crate::utils::DIGEST_SIZE;
 
 use super::Chip;
 use super::Com;
+use super::DomainSeparator;
+use super::FriParameters;
+use super::LookupArgument;
 use super::MachineProof;
+use super::MerkleHash;
 use super::PcsProverData;
+use super::ProofVerificationError;
 use super::Prover;
 use super::StarkGenericConfig;
+use super::StarkVerifyingKey;
 use super::Val;
 use super::VerificationError;
 use super::Verifier;
@@ -53,6 +64,12 @@ pub struct StarkMachine<SC: StarkGenericConfig, A> {
 
     /// The number of public values elements that the machine uses
     num_pv_elts: usize,
+
+    /// The layout of the lookup argument of the chips.
+    lookup_argument: LookupArgument,
+
+    /// The tag of the proofs of the machine.
+    domain_separator: DomainSeparator,
 }
 
 impl<SC: StarkGenericConfig, A> StarkMachine<SC, A> {
@@ -61,8 +78,40 @@ impl<SC: StarkGenericConfig, A> StarkMachine<SC, A> {
             config,
             chips,
             num_pv_elts,
+            lookup_argument: LookupArgument::Batched,
+            domain_separator: DomainSeparator::NONE,
         }
     }
+
+    /// Sets the layout of the lookup argument of all the chips of the machine, which changes its
+    /// verifying keys. The recursion programs verify proofs of the batched layout.
+    pub fn with_lookup_argument(mut self, lookup_argument: LookupArgument) -> Self {
+        self.chips = self
+            .chips
+            .into_iter()
+            .map(|chip| chip.with_lookup_argument(lookup_argument))
+            .collect();
+        self.lookup_argument = lookup_argument;
+        self
+    }
+
+    /// The layout of the lookup argument of the chips of the machine.
+    pub const fn lookup_argument(&self) -> LookupArgument {
+        self.lookup_argument
+    }
+
+    /// Sets the tag which the challenger absorbs after the verifying key, which changes the
+    /// verifying keys of the machine. The recursion programs verify the core proofs of the
+    /// machine they are built for.
+    pub const fn with_domain_separator(mut self, domain_separator: DomainSeparator) -> Self {
+        self.domain_separator = domain_separator;
+        self
+    }
+
+    /// The tag of the proofs of the machine.
+    pub const fn domain_separator(&self) -> DomainSeparator {
+        self.domain_separator
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -73,36 +122,51 @@ pub struct StarkProvingKey<SC: StarkGenericConfig> {
     pub pc_start: Val<SC>,
     pub traces: Vec<RowMajorMatrix<Val<SC>>>,
     pub data: PcsProverData<SC>,
-    pub chip_ordering: HashMap<String, usize>,
+    pub chip_ordering: BTreeMap<String, usize>,
+    pub domain_separator: DomainSeparator,
 }
 
 impl<SC: StarkGenericConfig> StarkProvingKey<SC> {
     pub fn observe_into(&self, challenger: &mut SC::Challenger) {
         challenger.observe(self.commit.clone());
         challenger.observe(self.pc_start);
+        challenger.observe_slice(&self.domain_separator.elements::<Val<SC>>());
     }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-#[serde(bound(serialize = "Dom<SC>: Serialize"))]
-#[serde(bound(deserialize = "Dom<SC>: DeserializeOwned"))]
-pub struct StarkVerifyingKey<SC: StarkGenericConfig> {
-    pub commit: Com<SC>,
-    pub pc_start: Val<SC>,
-    pub chip_information: Vec<(String, Dom<SC>, Dimensions)>,
-    pub chip_ordering: HashMap<String, usize>,
-}
+/// A verifying key with a digest, which is committed in the public values of each shard so that a
+/// proof is bound to its program.
+pub trait VerifyingKeyDigest {
+    /// The Poseidon2 digest of the key, as computed by the recursion verifier.
+    fn digest_babybear(&self) -> [BabyBear; DIGEST_SIZE];
 
-impl<SC: StarkGenericConfig> StarkVerifyingKey<SC> {
-    pub fn observe_into(&self, challenger: &mut SC::Challenger) {
-        challenger.observe(self.commit.clone());
-        challenger.observe(self.pc_start);
+    /// The digest of the key as canonical `u32`s.
+    fn digest_u32(&self) -> [u32; DIGEST_SIZE] {
+        self.digest_babybear().map(|f| f.as_canonical_u32())
     }
 }
 
-impl<SC: StarkGenericConfig> Debug for StarkVerifyingKey<SC> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("VerifyingKey").finish()
+impl<SC: StarkGenericConfig<Val = BabyBear, Domain = TwoAdicMultiplicativeCoset<BabyBear>>>
+    VerifyingKeyDigest for StarkVerifyingKey<SC>
+where
+    Com<SC>: AsRef<[BabyBear; DIGEST_SIZE]>,
+{
+    fn digest_babybear(&self) -> [BabyBear; DIGEST_SIZE] {
+        let prep_domains = self.chip_information.iter().map(|(_, domain, _)| domain);
+        let num_inputs = DIGEST_SIZE + 1 + (4 * prep_domains.len());
+        let mut inputs = Vec::with_capacity(num_inputs);
+        inputs.extend(self.commit.as_ref());
+        inputs.push(self.pc_start);
+        for domain in prep_domains {
+            inputs.push(BabyBear::from_canonical_usize(domain.log_n));
+            let size = 1 << domain.log_n;
+            inputs.push(BabyBear::from_canonical_usize(size));
+            let g = BabyBear::two_adic_generator(domain.log_n);
+            inputs.push(domain.shift);
+            inputs.push(g);
+        }
+
+        poseidon2_hash(inputs)
     }
 }
 
@@ -138,7 +202,7 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
 
     pub fn shard_chips_ordered<'a, 'b>(
         &'a self,
-        chip_ordering: &'b HashMap<String, usize>,
+        chip_ordering: &'b BTreeMap<String, usize>,
     ) -> impl Iterator<Item = &'b MachineChip<SC, A>>
     where
         'a: 'b,
@@ -212,7 +276,7 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
             .iter()
             .enumerate()
             .map(|(i, (name, _))| (name.to_owned(), i))
-            .collect::<HashMap<_, _>>();
+            .collect::<BTreeMap<_, _>>();
 
         // Get the preprocessed traces
         let traces = named_preprocessed_traces
@@ -229,12 +293,17 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                 traces,
                 data,
                 chip_ordering: chip_ordering.clone(),
+                domain_separator: self.domain_separator,
             },
             StarkVerifyingKey {
                 commit,
                 pc_start,
                 chip_information,
                 chip_ordering,
+                merkle_hash: self.config.merkle_hash(),
+                fri_parameters: self.config.fri_parameters(),
+                lookup_argument: self.lookup_argument(),
+                domain_separator: self.domain_separator,
             },
         )
     }
@@ -305,49 +374,36 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
         SC::Challenger: Clone,
         A: for<'a> Air<VerifierConstraintFolder<'a, SC>>,
     {
-        // Observe the preprocessed commitment.
-        vk.observe_into(challenger);
-        tracing::debug_span!("observe challenges for all shards").in_scope(|| {
-            proof.shard_proofs.iter().for_each(|proof| {
-                challenger.observe(proof.commitment.main_commit.clone());
-                challenger.observe_slice(&proof.public_values[0..self.num_pv_elts()]);
+        if vk.domain_separator != self.domain_separator {
+            return Err(MachineVerificationError::DomainSeparatorMismatch {
+                expected: self.domain_separator,
+                found: vk.domain_separator,
             });
-        });
-
-        // Verify the shard proofs.
-        if proof.shard_proofs.is_empty() {
-            return Err(MachineVerificationError::EmptyProof);
         }
-
-        tracing::debug_span!("verify shard proofs").in_scope(|| {
-            for (i, shard_proof) in proof.shard_proofs.iter().enumerate() {
-                tracing::debug_span!("verifying shard", segment = i).in_scope(|| {
-                    let chips = self
-                        .shard_chips_ordered(&shard_proof.chip_ordering)
-                        .collect::<Vec<_>>();
-                    Verifier::verify_shard(
-                        &self.config,
-                        vk,
-                        &chips,
-                        &mut challenger.clone(),
-                        shard_proof,
-                    )
-                    .map_err(MachineVerificationError::InvalidSegmentProof)
-                })?;
+        Verifier::verify_proof(
+            &self.config,
+            vk,
+            &self.chips,
+            self.num_pv_elts(),
+            challenger,
+            proof,
+        )
+        .map_err(|e| match e {
+            ProofVerificationError::EmptyProof => MachineVerificationError::EmptyProof,
+            ProofVerificationError::InvalidShardProof(e) => {
+                MachineVerificationError::InvalidSegmentProof(e)
             }
-
-            Ok(())
-        })?;
-
-        // Verify the cumulative sum is 0.
-        tracing::debug_span!("verify cumulative sum is 0").in_scope(|| {
-            let mut sum = SC::Challenge::zero();
-            for proof in proof.shard_proofs.iter() {
-                sum += proof.cumulative_sum();
+            ProofVerificationError::NonZeroCumulativeSum => {
+                MachineVerificationError::NonZeroCumulativeSum
+            }
+            ProofVerificationError::MerkleHashMismatch { expected, found } => {
+                MachineVerificationError::MerkleHashMismatch { expected, found }
             }
-            match sum.is_zero() {
-                true => Ok(()),
-                false => Err(MachineVerificationError::NonZeroCumulativeSum),
+            ProofVerificationError::FriParametersMismatch { expected, found } => {
+                MachineVerificationError::FriParametersMismatch { expected, found }
+            }
+            ProofVerificationError::LookupArgumentMismatch { expected, found } => {
+                MachineVerificationError::LookupArgumentMismatch { expected, found }
             }
         })
     }
@@ -366,6 +422,58 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
         let shards = self.shard(record, &<A::Record as MachineRecord>::Config::default());
 
         tracing::debug!("checking constraints for each shard");
+        let cumulative_sum = self
+            .eval_shard_constraints(pk, &shards, challenger, true)
+            .expect("failing constraints exit");
+
+        // If the cumulative sum is not zero, debug the interactions.
+        if !cumulative_sum.is_zero() {
+            debug_interactions_with_all_chips::<SC, A>(
+                self,
+                pk,
+                &shards,
+                InteractionKind::all_kinds(),
+            );
+            panic!("Cumulative sum is not zero");
+        }
+    }
+
+    /// Checks the constraints of every chip on the traces of `shards`, and returns the first
+    /// constraint which does not hold.
+    ///
+    /// Returns the cumulative sum of the interactions of the shards, which is zero over all the
+    /// shards of an execution if the interactions between the chips balance.
+    #[instrument("check constraints", level = "debug", skip_all)]
+    pub fn check_shard_constraints(
+        &self,
+        pk: &StarkProvingKey<SC>,
+        shards: &[A::Record],
+        challenger: &mut SC::Challenger,
+    ) -> Result<SC::Challenge, ConstraintCheckError>
+    where
+        SC::Val: PrimeField32,
+        A: for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
+    {
+        self.eval_shard_constraints(pk, shards, challenger, false)
+    }
+
+    fn eval_shard_constraints(
+        &self,
+        pk: &StarkProvingKey<SC>,
+        shards: &[A::Record],
+        challenger: &mut SC::Challenger,
+        verbose: bool,
+    ) -> Result<SC::Challenge, ConstraintCheckError>
+    where
+        SC::Val: PrimeField32,
+        A: for<'a> Air<DebugConstraintBuilder<'a, Val<SC>, SC::Challenge>>,
+    {
+        // Obtain the challenges used for the permutation argument, which are the same for all the
+        // shards so that their interactions balance.
+        let mut permutation_challenges: Vec<SC::Challenge> = Vec::new();
+        for _ in 0..2 {
+            permutation_challenges.push(challenger.sample_ext_element());
+        }
 
         let mut cumulative_sum = SC::Challenge::zero();
         for shard in shards.iter() {
@@ -387,13 +495,6 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                 .zip(pre_traces)
                 .collect::<Vec<_>>();
 
-            // Get a permutation challenge.
-            // Obtain the challenges used for the permutation argument.
-            let mut permutation_challenges: Vec<SC::Challenge> = Vec::new();
-            for _ in 0..2 {
-                permutation_challenges.push(challenger.sample_ext_element());
-            }
-
             // Generate the permutation traces.
             let mut permutation_traces = Vec::with_capacity(chips.len());
             let mut cumulative_sums = Vec::with_capacity(chips.len());
@@ -402,17 +503,11 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                     .par_iter()
                     .zip(traces.par_iter_mut())
                     .map(|(chip, (main_trace, pre_trace))| {
-                        let perm_trace = chip.generate_permutation_trace(
+                        chip.generate_permutation_trace(
                             *pre_trace,
                             main_trace,
                             &permutation_challenges,
-                        );
-                        let cumulative_sum = perm_trace
-                            .row_slice(main_trace.height() - 1)
-                            .last()
-                            .copied()
-                            .unwrap();
-                        (perm_trace, cumulative_sum)
+                        )
                     })
                     .unzip_into_vecs(&mut permutation_traces, &mut cumulative_sums);
             });
@@ -440,31 +535,58 @@ impl<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> StarkMachine<SC, A> {
                         .chip_ordering
                         .get(&chips[i].name())
                         .map(|index| &pk.traces[*index]);
-                    debug_constraints::<SC, A>(
-                        chips[i],
-                        permutation_trace,
-                        &traces[i].0,
-                        &permutation_traces[i],
-                        &permutation_challenges,
-                        shard.public_values(),
-                    );
+                    if verbose {
+                        debug_constraints::<SC, A>(
+                            chips[i],
+                            permutation_trace,
+                            &traces[i].0,
+                            &permutation_traces[i],
+                            cumulative_sums[i],
+                            &permutation_challenges,
+                            shard.public_values(),
+                        );
+                    } else {
+                        check_constraints::<SC, A>(
+                            chips[i],
+                            permutation_trace,
+                            &traces[i].0,
+                            &permutation_traces[i],
+                            cumulative_sums[i],
+                            &permutation_challenges,
+                            &shard.public_values::<Val<SC>>(),
+                        )
+                        .map_err(|failure| {
+                            ConstraintCheckError::ConstraintFailed {
+                                shard: shard.index(),
+                                chip: chips[i].name(),
+                                row: failure.row,
+                                constraint: failure.constraint,
+                            }
+                        })?;
+                    }
                 }
-            });
+                Ok::<_, ConstraintCheckError>(())
+            })?;
         }
 
-        // If the cumulative sum is not zero, debug the interactions.
-        if !cumulative_sum.is_zero() {
-            debug_interactions_with_all_chips::<SC, A>(
-                self,
-                pk,
-                &shards,
-                InteractionKind::all_kinds(),
-            );
-            panic!("Cumulative sum is not zero");
-        }
+        Ok(cumulative_sum)
     }
 }
 
+/// An error of [StarkMachine::check_shard_constraints].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConstraintCheckError {
+    #[error("constraint {constraint} of chip {chip} fails at row {row} of shard {shard}")]
+    ConstraintFailed {
+        shard: u32,
+        chip: String,
+        row: usize,
+        constraint: usize,
+    },
+    #[error("the interactions between the chips do not balance")]
+    NonZeroCumulativeSum,
+}
+
 pub enum MachineVerificationError<SC: StarkGenericConfig> {
     InvalidSegmentProof(VerificationError<SC>),
     InvalidGlobalProof(VerificationError<SC>),
@@ -473,6 +595,28 @@ pub enum MachineVerificationError<SC: StarkGenericConfig> {
     DebugInteractionsFailed,
     EmptyProof,
     InvalidPublicValues(&'static str),
+    MalformedPublicValues(PublicValuesError),
+    NonZeroExitCode(u32),
+    MerkleHashMismatch {
+        expected: MerkleHash,
+        found: MerkleHash,
+    },
+    FriParametersMismatch {
+        expected: FriParameters,
+        found: FriParameters,
+    },
+    LookupArgumentMismatch {
+        expected: LookupArgument,
+        found: LookupArgument,
+    },
+    DomainSeparatorMismatch {
+        expected: DomainSeparator,
+        found: DomainSeparator,
+    },
+    CircuitVersionMismatch {
+        expected: String,
+        found: String,
+    },
 }
 
 impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
@@ -499,6 +643,47 @@ impl<SC: StarkGenericConfig> Debug for MachineVerificationError<SC> {
             MachineVerificationError::InvalidPublicValues(s) => {
                 write!(f, "Invalid public values: {}", s)
             }
+            MachineVerificationError::MalformedPublicValues(e) => {
+                write!(f, "Malformed public values: {}", e)
+            }
+            MachineVerificationError::NonZeroExitCode(code) => {
+                write!(f, "The program exited with code {}", code)
+            }
+            MachineVerificationError::MerkleHashMismatch { expected, found } => {
+                write!(
+                    f,
+                    "The verifying key uses the {:?} Merkle hash, but the machine uses {:?}",
+                    found, expected
+                )
+            }
+            MachineVerificationError::FriParametersMismatch { expected, found } => {
+                write!(
+                    f,
+                    "The verifying key uses the FRI parameters {:?}, but the machine uses {:?}",
+                    found, expected
+                )
+            }
+            MachineVerificationError::LookupArgumentMismatch { expected, found } => {
+                write!(
+                    f,
+                    "The verifying key uses the {:?} lookup argument, but the machine uses {:?}",
+                    found, expected
+                )
+            }
+            MachineVerificationError::DomainSeparatorMismatch { expected, found } => {
+                write!(
+                    f,
+                    "The verifying key uses the domain separator {:?}, but the machine uses {:?}",
+                    found, expected
+                )
+            }
+            MachineVerificationError::CircuitVersionMismatch { expected, found } => {
+                write!(
+                    f,
+                    "Proof produced by incompatible prover version {}, verifier expects {}",
+                    found, expected
+                )
+            }
         }
     }
 }
@@ -515,23 +700,46 @@ impl<SC: StarkGenericConfig> std::error::Error for MachineVerificationError<SC>
 #[allow(non_snake_case)]
 pub mod tests {
 
+    use p3_baby_bear::BabyBear;
+    use p3_field::{AbstractField, Field};
+    use p3_matrix::Matrix;
+
+    use crate::air::MachineAir;
     use crate::io::SP1Stdin;
     use crate::runtime::tests::fibonacci_program;
+    use crate::runtime::tests::simple_atomic_program;
     use crate::runtime::tests::simple_memory_program;
     use crate::runtime::tests::simple_program;
     use crate::runtime::tests::ssz_withdrawals_program;
     use crate::runtime::Instruction;
     use crate::runtime::Opcode;
     use crate::runtime::Program;
+    use crate::runtime::{ExecutionRecord, Runtime, ShardingConfig};
+    use crate::stark::check_constraints;
+    use crate::stark::DomainSeparator;
+    use crate::stark::LocalProver;
+    use crate::stark::LookupArgument;
+    use crate::stark::MachineRecord;
+    use crate::stark::MachineVerificationError;
+    use crate::stark::MerkleHash;
     use crate::stark::RiscvAir;
+    use crate::stark::ShardMainData;
+    use crate::stark::StarkGenericConfig;
     use crate::stark::StarkProvingKey;
     use crate::stark::StarkVerifyingKey;
+    use crate::stark::{Com, OpeningProof, PcsProverData};
     use crate::utils;
+    use crate::utils::baby_bear_poseidon2::Challenge;
     use crate::utils::prove;
     use crate::utils::run_test;
     use crate::utils::setup_logger;
     use crate::utils::BabyBearPoseidon2;
     use crate::utils::SP1CoreOpts;
+    use crate::utils::SecurityPreset;
+    use crate::utils::TraceStorage;
+    use crate::utils::{with_baby_bear_config, BabyBearConfigVisitor};
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
 
     #[test]
     fn test_simple_prove() {
@@ -688,12 +896,92 @@ pub mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn test_fibonacci_prove_disk_traces() {
+        setup_logger();
+        let program = fibonacci_program();
+        let mut opts = SP1CoreOpts::default();
+        opts.shard_size = 1 << 12;
+        opts.shard_batch_size = 2;
+        opts.trace_storage = TraceStorage::Disk;
+        let config = BabyBearPoseidon2::new();
+        let (proof, _) = prove(program.clone(), &SP1Stdin::new(), config.clone(), opts).unwrap();
+
+        let machine = RiscvAir::machine(config);
+        let (_, vk) = machine.setup(&program);
+        let mut challenger = machine.config().challenger();
+        machine.verify(&vk, &proof, &mut challenger).unwrap();
+    }
+
+    #[test]
+    fn test_fibonacci_check_constraints() {
+        setup_logger();
+        let program = fibonacci_program();
+        let mut opts = SP1CoreOpts::default();
+        opts.shard_size = 1 << 12;
+        opts.shard_batch_size = 2;
+        utils::check_program_constraints(program, &SP1Stdin::new(), BabyBearPoseidon2::new(), opts)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_check_constraints_failure() {
+        setup_logger();
+        let program = simple_program();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let (pk, _) = machine.setup(&program);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let shards = machine.shard(runtime.record, &ShardingConfig::default());
+        let mut challenger = machine.config().challenger();
+        let cumulative_sum = machine
+            .check_shard_constraints(&pk, &shards, &mut challenger)
+            .unwrap();
+        assert!(cumulative_sum.is_zero());
+
+        // Corrupt the first row of the trace of the AddSub chip.
+        let chip = machine
+            .chips()
+            .iter()
+            .find(|chip| chip.name() == "AddSub")
+            .unwrap();
+        let mut main = chip.generate_trace(&shards[0], &mut ExecutionRecord::default());
+        let challenges = [
+            Challenge::from_canonical_u32(3),
+            Challenge::from_canonical_u32(7),
+        ];
+        let (perm, cumulative_sum) = chip.generate_permutation_trace(None, &mut main, &challenges);
+        let width = main.width();
+        for value in main.values[0..width].iter_mut() {
+            *value += BabyBear::one();
+        }
+
+        let public_values = shards[0].public_values::<BabyBear>();
+        let failure = check_constraints::<BabyBearPoseidon2, _>(
+            chip,
+            None,
+            &main,
+            &perm,
+            cumulative_sum,
+            &challenges,
+            &public_values,
+        )
+        .unwrap_err();
+        assert_eq!(failure.row, 0);
+    }
+
     #[test]
     fn test_simple_memory_program_prove() {
         let program = simple_memory_program();
         run_test(program).unwrap();
     }
 
+    #[test]
+    fn test_simple_atomic_program_prove() {
+        let program = simple_atomic_program();
+        run_test(program).unwrap();
+    }
+
     #[test]
     #[ignore]
     fn test_ssz_withdrawal() {
@@ -738,5 +1026,197 @@ pub mod tests {
             assert_eq!(a.2.width, b.2.width);
         }
         assert_eq!(vk.chip_ordering, deserialized_vk.chip_ordering);
+        assert_eq!(vk.merkle_hash, deserialized_vk.merkle_hash);
+        assert_eq!(vk.fri_parameters, deserialized_vk.fri_parameters);
+    }
+
+    #[test]
+    fn test_compact_lookup_argument() {
+        setup_logger();
+        let program = fibonacci_program();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new())
+            .with_lookup_argument(LookupArgument::Compact);
+        let batched = RiscvAir::machine(BabyBearPoseidon2::new());
+        for (chip, batched_chip) in machine.chips().iter().zip(batched.chips()) {
+            assert_eq!(
+                chip.permutation_width(),
+                (batched_chip.permutation_width() - 1).max(1)
+            );
+        }
+
+        // The constraints of the compact layout hold on the traces of the shards.
+        let (pk, vk) = machine.setup(&program);
+        assert_eq!(vk.lookup_argument, LookupArgument::Compact);
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let shards = machine.shard(runtime.record, &ShardingConfig::default());
+        let mut challenger = machine.config().challenger();
+        let cumulative_sum = machine
+            .check_shard_constraints(&pk, &shards, &mut challenger)
+            .unwrap();
+        assert!(cumulative_sum.is_zero());
+
+        let mut opts = SP1CoreOpts::default();
+        opts.shard_size = 1 << 12;
+        opts.lookup_argument = LookupArgument::Compact;
+        let (proof, _) = prove(
+            program.clone(),
+            &SP1Stdin::new(),
+            BabyBearPoseidon2::new(),
+            opts,
+        )
+        .unwrap();
+        let mut challenger = machine.config().challenger();
+        machine.verify(&vk, &proof, &mut challenger).unwrap();
+
+        // A machine of the batched layout rejects the key of the compact layout.
+        let mut challenger = batched.config().challenger();
+        assert!(matches!(
+            batched.verify(&vk, &proof, &mut challenger),
+            Err(MachineVerificationError::LookupArgumentMismatch {
+                expected: LookupArgument::Batched,
+                found: LookupArgument::Compact,
+            })
+        ));
+    }
+
+    #[test]
+    fn test_domain_separator() {
+        setup_logger();
+        let program = simple_program();
+        let domain_separator = DomainSeparator::new(env!("CARGO_PKG_VERSION"), "riscv");
+        assert_eq!(
+            domain_separator.tag(),
+            Some(format!("{}/riscv", env!("CARGO_PKG_VERSION")).as_bytes())
+        );
+        let machine =
+            RiscvAir::machine(BabyBearPoseidon2::new()).with_domain_separator(domain_separator);
+        let untagged = RiscvAir::machine(BabyBearPoseidon2::new());
+
+        let opts = SP1CoreOpts {
+            domain_separator,
+            ..Default::default()
+        };
+        let (proof, _) = prove(
+            program.clone(),
+            &SP1Stdin::new(),
+            BabyBearPoseidon2::new(),
+            opts,
+        )
+        .unwrap();
+        let (_, vk) = machine.setup(&program);
+        assert_eq!(vk.domain_separator, domain_separator);
+        let mut challenger = machine.config().challenger();
+        machine.verify(&vk, &proof, &mut challenger).unwrap();
+
+        // A machine without the tag rejects the key.
+        let mut challenger = untagged.config().challenger();
+        assert!(matches!(
+            untagged.verify(&vk, &proof, &mut challenger),
+            Err(MachineVerificationError::DomainSeparatorMismatch { .. })
+        ));
+
+        // A proof without the tag is not replayed against the key with the tag.
+        let (untagged_proof, _) = prove(
+            program,
+            &SP1Stdin::new(),
+            BabyBearPoseidon2::new(),
+            SP1CoreOpts::default(),
+        )
+        .unwrap();
+        let mut challenger = machine.config().challenger();
+        assert!(machine
+            .verify(&vk, &untagged_proof, &mut challenger)
+            .is_err());
+    }
+
+    #[test]
+    fn test_security_preset() {
+        setup_logger();
+        let program = simple_program();
+        let machine = RiscvAir::machine(BabyBearPoseidon2::with_preset(SecurityPreset::Bits80));
+        let (pk, vk) = machine.setup(&program);
+        assert_eq!(
+            vk.fri_parameters,
+            SecurityPreset::Bits80.fri_parameters(1, 100)
+        );
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let mut challenger = machine.config().challenger();
+        let proof = machine.prove::<LocalProver<_, _>>(
+            &pk,
+            runtime.record,
+            &mut challenger,
+            SP1CoreOpts::default(),
+        );
+        let mut challenger = machine.config().challenger();
+        machine.verify(&vk, &proof, &mut challenger).unwrap();
+
+        // A verifier of another preset rejects the key, instead of verifying the proof with
+        // parameters which it was not proven with.
+        let machine = RiscvAir::machine(BabyBearPoseidon2::new());
+        let mut challenger = machine.config().challenger();
+        assert!(matches!(
+            machine.verify(&vk, &proof, &mut challenger),
+            Err(MachineVerificationError::FriParametersMismatch { .. })
+        ));
+    }
+
+    /// Proves a program with a config, and checks that its key is only accepted with its hash.
+    struct ProveWithHash(Program);
+
+    impl BabyBearConfigVisitor for ProveWithHash {
+        type Output = MerkleHash;
+
+        fn visit<SC>(self, config: SC) -> MerkleHash
+        where
+            SC: StarkGenericConfig<Val = BabyBear>,
+            SC::Challenger: Clone,
+            OpeningProof<SC>: Send + Sync,
+            Com<SC>: Send + Sync,
+            PcsProverData<SC>: Send + Sync,
+            ShardMainData<SC>: Serialize + DeserializeOwned,
+        {
+            let machine = RiscvAir::machine(config);
+            let (pk, mut vk) = machine.setup(&self.0);
+            let mut runtime = Runtime::new(self.0, SP1CoreOpts::default());
+            runtime.run().unwrap();
+
+            let mut challenger = machine.config().challenger();
+            let proof = machine.prove::<LocalProver<_, _>>(
+                &pk,
+                runtime.record,
+                &mut challenger,
+                SP1CoreOpts::default(),
+            );
+            let mut challenger = machine.config().challenger();
+            machine.verify(&vk, &proof, &mut challenger).unwrap();
+
+            let merkle_hash = vk.merkle_hash;
+            vk.merkle_hash = match merkle_hash {
+                MerkleHash::Poseidon2 => MerkleHash::Blake3,
+                MerkleHash::Blake3 | MerkleHash::Keccak => MerkleHash::Poseidon2,
+            };
+            let mut challenger = machine.config().challenger();
+            assert!(matches!(
+                machine.verify(&vk, &proof, &mut challenger),
+                Err(MachineVerificationError::MerkleHashMismatch { expected, .. })
+                    if expected == merkle_hash
+            ));
+            merkle_hash
+        }
+    }
+
+    #[test]
+    fn test_merkle_hash() {
+        setup_logger();
+        for hash in [
+            MerkleHash::Poseidon2,
+            MerkleHash::Blake3,
+            MerkleHash::Keccak,
+        ] {
+            let merkle_hash = with_baby_bear_config(hash, ProveWithHash(simple_program()));
+            assert_eq!(merkle_hash, hash);
+        }
     }
 }
@@ -2,12 +2,16 @@ mod air;
 mod chip;
 mod config;
 mod debug;
+mod extension;
 mod folder;
+mod inspect;
 mod machine;
 mod permutation;
 mod prover;
 mod quotient;
 mod record;
+mod scheduler;
+mod size;
 mod types;
 mod util;
 mod verifier;
@@ -16,12 +20,16 @@ pub use air::*;
 pub use chip::*;
 pub use config::*;
 pub use debug::*;
+pub use extension::*;
 pub use folder::*;
+pub use inspect::*;
 pub use machine::*;
 pub use permutation::*;
 pub use prover::*;
 pub use quotient::*;
 pub use record::*;
+pub use scheduler::*;
+pub use size::*;
 pub use types::*;
 pub use verifier::*;
 
@@ -7,6 +7,7 @@ use p3_matrix::{dense::RowMajorMatrix, Matrix};
 use p3_maybe_rayon::prelude::*;
 use rayon_scan::ScanParallelIterator;
 
+use super::LookupArgument;
 use crate::{air::MultiTableAirBuilder, lookup::Interaction};
 
 #[inline]
@@ -52,15 +53,27 @@ pub fn populate_permutation_row<F: PrimeField, EF: ExtensionField<F>>(
     }
 }
 
+/// The width of the permutation trace of a chip, in extension field elements. The compact layout
+/// keeps the running sum column even for a chip without interactions.
 #[inline]
-pub const fn permutation_trace_width(num_interactions: usize, batch_size: usize) -> usize {
-    num_interactions.div_ceil(batch_size) + 1
+pub const fn permutation_trace_width(
+    num_interactions: usize,
+    batch_size: usize,
+    lookup_argument: LookupArgument,
+) -> usize {
+    let num_batches = num_interactions.div_ceil(batch_size);
+    match lookup_argument {
+        LookupArgument::Batched => num_batches + 1,
+        LookupArgument::Compact if num_batches == 0 => 1,
+        LookupArgument::Compact => num_batches,
+    }
 }
 
-/// Generates the permutation trace for the given chip and main trace based on a variant of LogUp.
+/// Generates the permutation trace for the given chip and main trace based on a variant of LogUp,
+/// and returns it with its cumulative sum.
 ///
-/// The permutation trace has (N+1)*EF::NUM_COLS columns, where N is the number of interactions in
-/// the chip.
+/// With N batches of interactions, the permutation trace has N+1 columns of extension field
+/// elements in the batched layout, and N columns in the compact layout.
 pub(crate) fn generate_permutation_trace<F: PrimeField, EF: ExtensionField<F>>(
     sends: &[Interaction<F>],
     receives: &[Interaction<F>],
@@ -68,7 +81,8 @@ pub(crate) fn generate_permutation_trace<F: PrimeField, EF: ExtensionField<F>>(
     main: &RowMajorMatrix<F>,
     random_elements: &[EF],
     batch_size: usize,
-) -> RowMajorMatrix<EF> {
+    lookup_argument: LookupArgument,
+) -> (RowMajorMatrix<EF>, EF) {
     // Generate the RLC elements to uniquely identify each interaction.
     let alpha = random_elements[0];
 
@@ -82,7 +96,9 @@ pub(crate) fn generate_permutation_trace<F: PrimeField, EF: ExtensionField<F>>(
     //
     // where f_{i, c_k} is the value at row i for column c_k. The computed value is essentially a
     // fingerprint for the interaction.
-    let permutation_trace_width = permutation_trace_width(sends.len() + receives.len(), batch_size);
+    let num_batches = (sends.len() + receives.len()).div_ceil(batch_size);
+    let permutation_trace_width =
+        permutation_trace_width(sends.len() + receives.len(), batch_size, lookup_argument);
     let height = main.height();
 
     let mut permutation_trace = RowMajorMatrix::new(
@@ -131,41 +147,45 @@ pub(crate) fn generate_permutation_trace<F: PrimeField, EF: ExtensionField<F>>(
     }
 
     let zero = EF::zero();
-    let cumulative_sums = permutation_trace
+    let row_sums = permutation_trace
         .par_rows_mut()
-        .map(|row| {
-            row[0..permutation_trace_width - 1]
-                .iter()
-                .copied()
-                .sum::<EF>()
-        })
+        .map(|row| row[0..num_batches].iter().copied().sum::<EF>())
         .collect::<Vec<_>>();
 
-    let cumulative_sums = cumulative_sums
-        .into_par_iter()
+    let cumulative_sums = row_sums
+        .par_iter()
+        .copied()
         .scan(|a, b| *a + *b, zero)
         .collect::<Vec<_>>();
+    let cumulative_sum = cumulative_sums.last().copied().unwrap_or(zero);
 
+    // The batched layout keeps the running sum up to each row in its last column, while the
+    // compact layout overwrites the sum of the last batch with the running sum before each row.
     permutation_trace
         .par_rows_mut()
-        .zip_eq(cumulative_sums.into_par_iter())
-        .for_each(|(row, cumulative_sum)| {
-            *row.last_mut().unwrap() = cumulative_sum;
+        .zip_eq(cumulative_sums.into_par_iter().zip_eq(row_sums))
+        .for_each(|(row, (cumulative_sum, row_sum))| {
+            *row.last_mut().unwrap() = match lookup_argument {
+                LookupArgument::Batched => cumulative_sum,
+                LookupArgument::Compact => cumulative_sum - row_sum,
+            };
         });
 
-    permutation_trace
+    (permutation_trace, cumulative_sum)
 }
 
 /// Evaluates the permutation constraints for the given chip.
 ///
 /// In particular, the constraints checked here are:
-///     - The running sum column starts at zero.
+///     - The running sum column starts at zero, or at the sum of the first row in the batched
+///       layout.
 ///     - That the RLC per interaction is computed correctly.
 ///     - The running sum column ends at the (currently) given cumalitive sum.
 pub fn eval_permutation_constraints<F, AB>(
     sends: &[Interaction<F>],
     receives: &[Interaction<F>],
     batch_size: usize,
+    lookup_argument: LookupArgument,
     builder: &mut AB,
 ) where
     F: Field,
@@ -191,80 +211,86 @@ pub fn eval_permutation_constraints<F, AB>(
     let perm_next = perm.row_slice(1);
     let perm_next: &[AB::VarEF] = (*perm_next).borrow();
 
-    // Ensure that each batch sum m_i/f_i is computed correctly.
-    let interaction_chunks = &sends
-        .iter()
-        .map(|int| (int, true))
-        .chain(receives.iter().map(|int| (int, false)))
-        .chunks(batch_size);
-
     assert_eq!(
-        interaction_chunks.into_iter().count(),
-        perm_width - 1,
+        perm_width,
+        permutation_trace_width(sends.len() + receives.len(), batch_size, lookup_argument),
         "Number of sends: {}, receives: {}, batch size: {}, perm width: {}",
         sends.len(),
         receives.len(),
         batch_size,
-        perm_width - 1
-    );
-    assert_eq!(
-        perm_width,
-        permutation_trace_width(sends.len() + receives.len(), batch_size)
+        perm_width
     );
 
-    for (entry, chunk) in perm_local[0..perm_local.len() - 1]
+    // The numerator and denominator of the sum m_i/rlc_i of each batch of interactions.
+    let interaction_chunks = &sends
         .iter()
-        .zip(interaction_chunks)
-    {
-        // Assert that the i-eth entry is equal to the sum_i m_i/rlc_i by constraints:
-        // entry * \prod_i rlc_i = \sum_i m_i * \prod_{j!=i} rlc_j.
-
-        // First, we calculate the random linear combinations and multiplicities with the correct
-        // sign depending on wetther the interaction is a send or a recieve.
-        let mut rlcs: Vec<AB::ExprEF> = Vec::with_capacity(batch_size);
-        let mut multiplicities: Vec<AB::Expr> = Vec::with_capacity(batch_size);
-        for (interaction, is_send) in chunk {
-            let mut rlc = alpha.clone();
-            let mut betas = beta.powers();
-            rlc += betas.next().unwrap()
-                * AB::ExprEF::from_canonical_usize(interaction.argument_index());
-            for (field, beta) in interaction.values.iter().zip(betas.clone()) {
-                let elem = field.apply::<AB::Expr, AB::Var>(&preprocessed_local, main_local);
-                rlc += beta * elem;
+        .map(|int| (int, true))
+        .chain(receives.iter().map(|int| (int, false)))
+        .chunks(batch_size);
+    let mut fractions = interaction_chunks
+        .into_iter()
+        .map(|chunk| {
+            // First, we calculate the random linear combinations and multiplicities with the
+            // correct sign depending on wetther the interaction is a send or a recieve.
+            let mut rlcs: Vec<AB::ExprEF> = Vec::with_capacity(batch_size);
+            let mut multiplicities: Vec<AB::Expr> = Vec::with_capacity(batch_size);
+            for (interaction, is_send) in chunk {
+                let mut rlc = alpha.clone();
+                let mut betas = beta.powers();
+                rlc += betas.next().unwrap()
+                    * AB::ExprEF::from_canonical_usize(interaction.argument_index());
+                for (field, beta) in interaction.values.iter().zip(betas.clone()) {
+                    let elem = field.apply::<AB::Expr, AB::Var>(&preprocessed_local, main_local);
+                    rlc += beta * elem;
+                }
+                rlcs.push(rlc);
+
+                let send_factor = if is_send { AB::F::one() } else { -AB::F::one() };
+                multiplicities.push(
+                    interaction
+                        .multiplicity
+                        .apply::<AB::Expr, AB::Var>(&preprocessed_local, main_local)
+                        * send_factor,
+                );
             }
-            rlcs.push(rlc);
 
-            let send_factor = if is_send { AB::F::one() } else { -AB::F::one() };
-            multiplicities.push(
-                interaction
-                    .multiplicity
-                    .apply::<AB::Expr, AB::Var>(&preprocessed_local, main_local)
-                    * send_factor,
-            );
-        }
-
-        // Now we can calculate the numerator and denominator of the combined batch.
-        let mut product = AB::ExprEF::one();
-        let mut numerator = AB::ExprEF::zero();
-        for (i, (m, rlc)) in multiplicities.into_iter().zip(rlcs.iter()).enumerate() {
-            // Calculate the running product of all rlcs.
-            product *= rlc.clone();
-            // Calculate the product of all but the current rlc.
-            let mut all_but_current = AB::ExprEF::one();
-            for other_rlc in rlcs
-                .iter()
-                .enumerate()
-                .filter(|(j, _)| i != *j)
-                .map(|(_, rlc)| rlc)
-            {
-                all_but_current *= other_rlc.clone();
+            // Now we can calculate the numerator and denominator of the combined batch.
+            let mut product = AB::ExprEF::one();
+            let mut numerator = AB::ExprEF::zero();
+            for (i, (m, rlc)) in multiplicities.into_iter().zip(rlcs.iter()).enumerate() {
+                // Calculate the running product of all rlcs.
+                product *= rlc.clone();
+                // Calculate the product of all but the current rlc.
+                let mut all_but_current = AB::ExprEF::one();
+                for other_rlc in rlcs
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| i != *j)
+                    .map(|(_, rlc)| rlc)
+                {
+                    all_but_current *= other_rlc.clone();
+                }
+                numerator += AB::ExprEF::from_base(m) * all_but_current;
             }
-            numerator += AB::ExprEF::from_base(m) * all_but_current;
-        }
+            (product, numerator)
+        })
+        .collect::<Vec<_>>();
 
-        // Finally, assert that the entry is equal to the numerator divided by the product.
+    // In the compact layout, the sum of the last batch is not in a column of its own.
+    let last_fraction = match lookup_argument {
+        LookupArgument::Batched => None,
+        LookupArgument::Compact => Some(
+            fractions
+                .pop()
+                .unwrap_or((AB::ExprEF::one(), AB::ExprEF::zero())),
+        ),
+    };
+
+    // Assert that the i-eth entry is equal to the sum_i m_i/rlc_i by constraints:
+    // entry * \prod_i rlc_i = \sum_i m_i * \prod_{j!=i} rlc_j.
+    for (entry, (product, numerator)) in perm_local[0..perm_width - 1].iter().zip(fractions) {
         let entry: AB::ExprEF = (*entry).into();
-        builder.assert_eq_ext(product.clone() * entry.clone(), numerator);
+        builder.assert_eq_ext(product * entry, numerator);
     }
 
     let sum_local = perm_local[..perm_width - 1]
@@ -272,21 +298,36 @@ pub fn eval_permutation_constraints<F, AB>(
         .map(|x| (*x).into())
         .sum::<AB::ExprEF>();
 
-    let sum_next = perm_next[..perm_width - 1]
-        .iter()
-        .map(|x| (*x).into())
-        .sum::<AB::ExprEF>();
-
     let phi_local: AB::ExprEF = (*perm_local.last().unwrap()).into();
     let phi_next: AB::ExprEF = (*perm_next.last().unwrap()).into();
-    builder
-        .when_transition()
-        .assert_eq_ext(phi_next - phi_local.clone(), sum_next);
+    let cumulative_sum: AB::ExprEF = builder.cumulative_sum().into();
+
+    match last_fraction {
+        None => {
+            let sum_next = perm_next[..perm_width - 1]
+                .iter()
+                .map(|x| (*x).into())
+                .sum::<AB::ExprEF>();
 
-    builder.when_first_row().assert_eq_ext(phi_local, sum_local);
+            builder
+                .when_transition()
+                .assert_eq_ext(phi_next - phi_local.clone(), sum_next);
 
-    let cumulative_sum = builder.cumulative_sum();
-    builder
-        .when_last_row()
-        .assert_eq_ext(*perm_local.last().unwrap(), cumulative_sum);
+            builder.when_first_row().assert_eq_ext(phi_local, sum_local);
+
+            builder
+                .when_last_row()
+                .assert_eq_ext(*perm_local.last().unwrap(), cumulative_sum);
+        }
+        Some((product, numerator)) => {
+            // The running sum before the next row, which wraps around to zero after the last row,
+            // is the running sum before this row plus the sum of all the batches of this row.
+            let is_last_row = AB::ExprEF::from_base(builder.is_last_row());
+            let last_entry =
+                phi_next - phi_local.clone() - sum_local + is_last_row * cumulative_sum;
+            builder.assert_eq_ext(product * last_entry, numerator);
+
+            builder.when_first_row().assert_zero_ext(phi_local);
+        }
+    }
 }
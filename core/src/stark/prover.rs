@@ -2,7 +2,6 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::cmp::Reverse;
 use std::marker::PhantomData;
-use std::sync::atomic::{AtomicU32, Ordering};
 
 use itertools::Itertools;
 use p3_air::Air;
@@ -17,7 +16,9 @@ use p3_matrix::Matrix;
 use p3_maybe_rayon::prelude::*;
 use p3_util::log2_strict_usize;
 
-use super::{quotient_values, PcsProverData, StarkMachine, Val};
+use super::{
+    estimate_shard_memory, quotient_values, PcsProverData, ShardScheduler, StarkMachine, Val,
+};
 use super::{types::*, StarkGenericConfig};
 use super::{Com, OpeningProof};
 use super::{StarkProvingKey, VerifierConstraintFolder};
@@ -29,16 +30,6 @@ use crate::stark::PackedChallenge;
 use crate::stark::ProverConstraintFolder;
 use crate::utils::SP1CoreOpts;
 
-fn chunk_vec<T>(mut vec: Vec<T>, chunk_size: usize) -> Vec<Vec<T>> {
-    let mut result = Vec::new();
-    while !vec.is_empty() {
-        let current_chunk_size = std::cmp::min(chunk_size, vec.len());
-        let current_chunk = vec.drain(..current_chunk_size).collect::<Vec<T>>();
-        result.push(current_chunk);
-    }
-    result
-}
-
 pub trait Prover<SC: StarkGenericConfig, A: MachineAir<Val<SC>>> {
     fn prove_shards(
         machine: &StarkMachine<SC, A>,
@@ -93,53 +84,33 @@ where
                 });
         });
 
-        let finished = AtomicU32::new(0);
-
         // Generate a proof for each segment. Note that we clone the challenger so we can observe
         // identical global challenges across the segments.
-        let chunking_multiplier = opts.shard_chunking_multiplier;
-        let chunk_size = std::cmp::max(chunking_multiplier * shards.len() / num_cpus::get(), 1);
         let config = machine.config();
         let reconstruct_commitments = opts.reconstruct_commitments;
-        let shard_data_chunks = chunk_vec(shard_data, chunk_size);
-        let shard_chunks = chunk_vec(shards, chunk_size);
+        let scheduler = ShardScheduler::from_opts(&opts);
         let parent_span = tracing::debug_span!("open_shards");
         let shard_proofs = parent_span.in_scope(|| {
-            shard_data_chunks
-                .into_par_iter()
-                .zip(shard_chunks.into_par_iter())
-                .map(|(datas, shards)| {
-                    datas
-                        .into_iter()
-                        .zip(shards)
-                        .map(|(data, shard)| {
-                            tracing::debug_span!(parent: &parent_span, "prove shard opening")
-                                .in_scope(|| {
-                                    let idx = shard.index() as usize;
-                                    let data = if reconstruct_commitments {
-                                        Self::commit_main(config, machine, &shard, idx)
-                                    } else {
-                                        data.materialize()
-                                            .expect("failed to materialize shard main data")
-                                    };
-                                    let ordering = data.chip_ordering.clone();
-                                    let chips =
-                                        machine.shard_chips_ordered(&ordering).collect::<Vec<_>>();
-                                    let proof = Self::prove_shard(
-                                        config,
-                                        pk,
-                                        &chips,
-                                        data,
-                                        &mut challenger.clone(),
-                                    );
-                                    finished.fetch_add(1, Ordering::Relaxed);
-                                    proof
-                                })
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .flatten()
-                .collect::<Vec<_>>()
+            scheduler.map(
+                shard_data.into_iter().zip(shards).collect(),
+                |(_, shard)| estimate_shard_memory(&shard.stats()),
+                |(data, shard)| {
+                    tracing::debug_span!(parent: &parent_span, "prove shard opening").in_scope(
+                        || {
+                            let idx = shard.index() as usize;
+                            let data = if reconstruct_commitments {
+                                Self::commit_main(config, machine, &shard, idx)
+                            } else {
+                                data.materialize()
+                                    .expect("failed to materialize shard main data")
+                            };
+                            let ordering = data.chip_ordering.clone();
+                            let chips = machine.shard_chips_ordered(&ordering).collect::<Vec<_>>();
+                            Self::prove_shard(config, pk, &chips, data, &mut challenger.clone())
+                        },
+                    )
+                },
+            )
         });
 
         MachineProof { shard_proofs }
@@ -285,17 +256,11 @@ where
                         .chip_ordering
                         .get(&chip.name())
                         .map(|&index| &pk.traces[index]);
-                    let perm_trace = chip.generate_permutation_trace(
+                    chip.generate_permutation_trace(
                         preprocessed_trace,
                         main_trace,
                         &permutation_challenges,
-                    );
-                    let cumulative_sum = perm_trace
-                        .row_slice(main_trace.height() - 1)
-                        .last()
-                        .copied()
-                        .unwrap();
-                    (perm_trace, cumulative_sum)
+                    )
                 })
                 .unzip_into_vecs(&mut permutation_traces, &mut cumulative_sums);
         });
@@ -321,10 +286,7 @@ where
                 permutation_traces
                     .into_iter()
                     .zip(trace_domains.iter())
-                    .map(|(perm_trace, domain)| {
-                        let trace = perm_trace.flatten_to_base();
-                        (*domain, trace.to_owned())
-                    })
+                    .map(|(perm_trace, domain)| (*domain, perm_trace.flatten_to_base()))
                     .collect::<Vec<_>>()
             });
 
@@ -356,29 +318,19 @@ where
                 .map(|(i, quotient_domain)| {
                     tracing::debug_span!(parent: &parent_span, "compute quotient values for domain")
                         .in_scope(|| {
-                            let preprocessed_trace_on_quotient_domains = pk
-                                .chip_ordering
-                                .get(&chips[i].name())
-                                .map(|&index| {
+                            // The evaluations on the quotient domain are views of the
+                            // low-degree extensions, whose rows are copied one chunk at a time.
+                            let preprocessed_trace_on_quotient_domains =
+                                pk.chip_ordering.get(&chips[i].name()).map(|&index| {
                                     pcs.get_evaluations_on_domain(&pk.data, index, *quotient_domain)
-                                        .to_row_major_matrix()
-                                })
-                                .unwrap_or_else(|| {
-                                    RowMajorMatrix::new_col(vec![
-                                        SC::Val::zero();
-                                        quotient_domain.size()
-                                    ])
                                 });
-                            let main_trace_on_quotient_domains = pcs
-                                .get_evaluations_on_domain(
-                                    &shard_data.main_data,
-                                    i,
-                                    *quotient_domain,
-                                )
-                                .to_row_major_matrix();
+                            let main_trace_on_quotient_domains = pcs.get_evaluations_on_domain(
+                                &shard_data.main_data,
+                                i,
+                                *quotient_domain,
+                            );
                             let permutation_trace_on_quotient_domains = pcs
-                                .get_evaluations_on_domain(&permutation_data, i, *quotient_domain)
-                                .to_row_major_matrix();
+                                .get_evaluations_on_domain(&permutation_data, i, *quotient_domain);
                             quotient_values(
                                 chips[i],
                                 cumulative_sums[i],
@@ -557,39 +509,33 @@ where
     {
         let config = machine.config();
 
-        // Get the number of shards that is the threshold for saving shards to disk instead of
-        // keeping all the shards in memory.
+        // Commit to the shards as the memory budget allows. Unless the commitments are
+        // reconstructed when proving, the main data of the shards is kept in memory.
         let reconstruct_commitments = opts.reconstruct_commitments;
-        let finished = AtomicU32::new(0);
-        let chunk_size = std::cmp::max(shards.len() / num_cpus::get(), 1);
+        let scheduler = ShardScheduler::from_opts(&opts);
         let parent_span = tracing::debug_span!("commit to all shards");
         let (commitments, shard_main_data): (Vec<_>, Vec<_>) = parent_span.in_scope(|| {
-            shards
-                .par_chunks(chunk_size)
-                .map(|shard_batch| {
-                    shard_batch
-                        .iter()
-                        .map(|shard| {
-                            tracing::debug_span!(parent: &parent_span, "commit to shard").in_scope(
-                                || {
-                                    let index = shard.index();
-                                    let data =
-                                        Self::commit_main(config, machine, shard, index as usize);
-                                    finished.fetch_add(1, Ordering::Relaxed);
-                                    let commitment = data.main_commit.clone();
-                                    let data = if reconstruct_commitments {
-                                        ShardMainDataWrapper::Empty()
-                                    } else {
-                                        data.to_in_memory()
-                                    };
-                                    (commitment, data)
-                                },
-                            )
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .flatten()
-                .collect::<Vec<_>>()
+            scheduler
+                .map(
+                    shards.iter().collect(),
+                    |shard| estimate_shard_memory(&shard.stats()),
+                    |shard| {
+                        tracing::debug_span!(parent: &parent_span, "commit to shard").in_scope(
+                            || {
+                                let index = shard.index();
+                                let data =
+                                    Self::commit_main(config, machine, shard, index as usize);
+                                let commitment = data.main_commit.clone();
+                                let data = if reconstruct_commitments {
+                                    ShardMainDataWrapper::Empty()
+                                } else {
+                                    data.to_in_memory()
+                                };
+                                (commitment, data)
+                            },
+                        )
+                    },
+                )
                 .into_iter()
                 .unzip()
         });
@@ -1,8 +1,12 @@
+use std::ops::Range;
+
 use p3_air::Air;
 use p3_commit::PolynomialSpace;
 use p3_field::AbstractExtensionField;
 use p3_field::AbstractField;
+use p3_field::Field;
 use p3_field::PackedValue;
+use p3_matrix::dense::RowMajorMatrix;
 use p3_matrix::dense::RowMajorMatrixView;
 use p3_matrix::stack::VerticalPair;
 use p3_matrix::Matrix;
@@ -19,15 +23,39 @@ use super::PackedVal;
 use super::StarkGenericConfig;
 use super::Val;
 
+/// The number of rows of the quotient domain whose quotient values are computed at once. The rows
+/// of the traces on the quotient domain are only copied for one chunk at a time, which bounds the
+/// scratch memory of a chip to the rows of a chunk instead of the whole quotient domain.
+pub const QUOTIENT_CHUNK_SIZE: usize = 1 << 14;
+
+/// Copies the rows `rows` of `mat`, wrapping around its height, into a matrix of the rows of a
+/// chunk. A chip without a matrix gets a single column of zeros.
+fn chunk_rows<F, Mat>(mat: Option<&Mat>, rows: Range<usize>, height: usize) -> RowMajorMatrix<F>
+where
+    F: Field,
+    Mat: Matrix<F>,
+{
+    match mat {
+        Some(mat) => {
+            let mut values = Vec::with_capacity(rows.len() * mat.width());
+            for row in rows {
+                values.extend(mat.row(row % height));
+            }
+            RowMajorMatrix::new(values, mat.width())
+        }
+        None => RowMajorMatrix::new_col(vec![F::zero(); rows.len()]),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
-pub fn quotient_values<SC, A, Mat>(
+pub fn quotient_values<SC, A, PrepMat, MainMat, PermMat>(
     chip: &Chip<Val<SC>, A>,
     cumulative_sum: SC::Challenge,
     trace_domain: Domain<SC>,
     quotient_domain: Domain<SC>,
-    preprocessed_trace_on_quotient_domain: Mat,
-    main_trace_on_quotient_domain: Mat,
-    permutation_trace_on_quotient_domain: Mat,
+    preprocessed_trace_on_quotient_domain: Option<PrepMat>,
+    main_trace_on_quotient_domain: MainMat,
+    permutation_trace_on_quotient_domain: PermMat,
     perm_challenges: &[PackedChallenge<SC>],
     alpha: SC::Challenge,
     public_values: &[Val<SC>],
@@ -35,12 +63,11 @@ pub fn quotient_values<SC, A, Mat>(
 where
     A: for<'a> Air<ProverConstraintFolder<'a, SC>> + MachineAir<Val<SC>>,
     SC: StarkGenericConfig,
-    Mat: Matrix<Val<SC>> + Sync,
+    PrepMat: Matrix<Val<SC>>,
+    MainMat: Matrix<Val<SC>>,
+    PermMat: Matrix<Val<SC>>,
 {
     let quotient_size = quotient_domain.size();
-    let prep_width = preprocessed_trace_on_quotient_domain.width();
-    let main_width = main_trace_on_quotient_domain.width();
-    let perm_width = permutation_trace_on_quotient_domain.width();
     let sels = trace_domain.selectors_on_coset(quotient_domain);
 
     let qdb = log2_strict_usize(quotient_domain.size()) - log2_strict_usize(trace_domain.size());
@@ -56,108 +83,130 @@ where
         chip.name()
     );
 
-    (0..quotient_size)
-        .into_par_iter()
-        .step_by(PackedVal::<SC>::WIDTH)
-        .flat_map_iter(|i_start| {
-            let wrap = |i| i % quotient_size;
-            let i_range = i_start..i_start + PackedVal::<SC>::WIDTH;
-
-            let is_first_row = *PackedVal::<SC>::from_slice(&sels.is_first_row[i_range.clone()]);
-            let is_last_row = *PackedVal::<SC>::from_slice(&sels.is_last_row[i_range.clone()]);
-            let is_transition = *PackedVal::<SC>::from_slice(&sels.is_transition[i_range.clone()]);
-            let inv_zeroifier = *PackedVal::<SC>::from_slice(&sels.inv_zeroifier[i_range.clone()]);
-
-            let prep_local: Vec<_> = (0..prep_width)
-                .map(|col| {
-                    PackedVal::<SC>::from_fn(|offset| {
-                        preprocessed_trace_on_quotient_domain.get(wrap(i_start + offset), col)
-                    })
-                })
-                .collect();
-            let prep_next: Vec<_> = (0..prep_width)
-                .map(|col| {
-                    PackedVal::<SC>::from_fn(|offset| {
-                        preprocessed_trace_on_quotient_domain
-                            .get(wrap(i_start + next_step + offset), col)
-                    })
-                })
-                .collect();
+    let chunk_size = QUOTIENT_CHUNK_SIZE.min(quotient_size);
+    let mut values = Vec::with_capacity(quotient_size);
+    for chunk_start in (0..quotient_size).step_by(chunk_size) {
+        // The rows of the chunk and the next rows of its last rows.
+        let rows = chunk_start..chunk_start + chunk_size + next_step;
+        let preprocessed = chunk_rows(
+            preprocessed_trace_on_quotient_domain.as_ref(),
+            rows.clone(),
+            quotient_size,
+        );
+        let main = chunk_rows(
+            Some(&main_trace_on_quotient_domain),
+            rows.clone(),
+            quotient_size,
+        );
+        let permutation = chunk_rows(
+            Some(&permutation_trace_on_quotient_domain),
+            rows,
+            quotient_size,
+        );
+        let prep_width = preprocessed.width();
+        let main_width = main.width();
+        let perm_width = permutation.width();
 
-            let local: Vec<_> = (0..main_width)
-                .map(|col| {
-                    PackedVal::<SC>::from_fn(|offset| {
-                        main_trace_on_quotient_domain.get(wrap(i_start + offset), col)
+        let chunk_values = (0..chunk_size)
+            .into_par_iter()
+            .step_by(PackedVal::<SC>::WIDTH)
+            .flat_map_iter(|i_start| {
+                let i_range = chunk_start + i_start..chunk_start + i_start + PackedVal::<SC>::WIDTH;
+
+                let is_first_row =
+                    *PackedVal::<SC>::from_slice(&sels.is_first_row[i_range.clone()]);
+                let is_last_row = *PackedVal::<SC>::from_slice(&sels.is_last_row[i_range.clone()]);
+                let is_transition =
+                    *PackedVal::<SC>::from_slice(&sels.is_transition[i_range.clone()]);
+                let inv_zeroifier =
+                    *PackedVal::<SC>::from_slice(&sels.inv_zeroifier[i_range.clone()]);
+
+                let prep_local: Vec<_> = (0..prep_width)
+                    .map(|col| {
+                        PackedVal::<SC>::from_fn(|offset| preprocessed.get(i_start + offset, col))
                     })
-                })
-                .collect();
-            let next: Vec<_> = (0..main_width)
-                .map(|col| {
-                    PackedVal::<SC>::from_fn(|offset| {
-                        main_trace_on_quotient_domain.get(wrap(i_start + next_step + offset), col)
+                    .collect();
+                let prep_next: Vec<_> = (0..prep_width)
+                    .map(|col| {
+                        PackedVal::<SC>::from_fn(|offset| {
+                            preprocessed.get(i_start + next_step + offset, col)
+                        })
                     })
-                })
-                .collect();
+                    .collect();
 
-            let perm_local: Vec<_> = (0..perm_width)
-                .step_by(ext_degree)
-                .map(|col| {
-                    PackedChallenge::<SC>::from_base_fn(|i| {
+                let local: Vec<_> = (0..main_width)
+                    .map(|col| PackedVal::<SC>::from_fn(|offset| main.get(i_start + offset, col)))
+                    .collect();
+                let next: Vec<_> = (0..main_width)
+                    .map(|col| {
                         PackedVal::<SC>::from_fn(|offset| {
-                            permutation_trace_on_quotient_domain
-                                .get(wrap(i_start + offset), col + i)
+                            main.get(i_start + next_step + offset, col)
                         })
                     })
-                })
-                .collect();
+                    .collect();
 
-            let perm_next: Vec<_> = (0..perm_width)
-                .step_by(ext_degree)
-                .map(|col| {
-                    PackedChallenge::<SC>::from_base_fn(|i| {
-                        PackedVal::<SC>::from_fn(|offset| {
-                            permutation_trace_on_quotient_domain
-                                .get(wrap(i_start + next_step + offset), col + i)
+                let perm_local: Vec<_> = (0..perm_width)
+                    .step_by(ext_degree)
+                    .map(|col| {
+                        PackedChallenge::<SC>::from_base_fn(|i| {
+                            PackedVal::<SC>::from_fn(|offset| {
+                                permutation.get(i_start + offset, col + i)
+                            })
+                        })
+                    })
+                    .collect();
+
+                let perm_next: Vec<_> = (0..perm_width)
+                    .step_by(ext_degree)
+                    .map(|col| {
+                        PackedChallenge::<SC>::from_base_fn(|i| {
+                            PackedVal::<SC>::from_fn(|offset| {
+                                permutation.get(i_start + next_step + offset, col + i)
+                            })
                         })
                     })
+                    .collect();
+
+                let accumulator = PackedChallenge::<SC>::zero();
+                let mut folder = ProverConstraintFolder {
+                    preprocessed: VerticalPair::new(
+                        RowMajorMatrixView::new_row(&prep_local),
+                        RowMajorMatrixView::new_row(&prep_next),
+                    ),
+                    main: VerticalPair::new(
+                        RowMajorMatrixView::new_row(&local),
+                        RowMajorMatrixView::new_row(&next),
+                    ),
+                    perm: VerticalPair::new(
+                        RowMajorMatrixView::new_row(&perm_local),
+                        RowMajorMatrixView::new_row(&perm_next),
+                    ),
+                    perm_challenges,
+                    cumulative_sum,
+                    is_first_row,
+                    is_last_row,
+                    is_transition,
+                    alpha,
+                    accumulator,
+                    public_values,
+                };
+                chip.eval(&mut folder);
+
+                // quotient(x) = constraints(x) / Z_H(x)
+                let quotient = folder.accumulator * inv_zeroifier;
+
+                // "Transpose" D packed base coefficients into WIDTH scalar extension coefficients.
+                (0..PackedVal::<SC>::WIDTH).map(move |idx_in_packing| {
+                    let quotient_value = (0..<SC::Challenge as AbstractExtensionField<Val<SC>>>::D)
+                        .map(|coeff_idx| {
+                            quotient.as_base_slice()[coeff_idx].as_slice()[idx_in_packing]
+                        })
+                        .collect::<Vec<_>>();
+                    SC::Challenge::from_base_slice(&quotient_value)
                 })
-                .collect();
-
-            let accumulator = PackedChallenge::<SC>::zero();
-            let mut folder = ProverConstraintFolder {
-                preprocessed: VerticalPair::new(
-                    RowMajorMatrixView::new_row(&prep_local),
-                    RowMajorMatrixView::new_row(&prep_next),
-                ),
-                main: VerticalPair::new(
-                    RowMajorMatrixView::new_row(&local),
-                    RowMajorMatrixView::new_row(&next),
-                ),
-                perm: VerticalPair::new(
-                    RowMajorMatrixView::new_row(&perm_local),
-                    RowMajorMatrixView::new_row(&perm_next),
-                ),
-                perm_challenges,
-                cumulative_sum,
-                is_first_row,
-                is_last_row,
-                is_transition,
-                alpha,
-                accumulator,
-                public_values,
-            };
-            chip.eval(&mut folder);
-
-            // quotient(x) = constraints(x) / Z_H(x)
-            let quotient = folder.accumulator * inv_zeroifier;
-
-            // "Transpose" D packed base coefficients into WIDTH scalar extension coefficients.
-            (0..PackedVal::<SC>::WIDTH).map(move |idx_in_packing| {
-                let quotient_value = (0..<SC::Challenge as AbstractExtensionField<Val<SC>>>::D)
-                    .map(|coeff_idx| quotient.as_base_slice()[coeff_idx].as_slice()[idx_in_packing])
-                    .collect::<Vec<_>>();
-                SC::Challenge::from_base_slice(&quotient_value)
             })
-        })
-        .collect()
+            .collect::<Vec<_>>();
+        values.extend(chunk_values);
+    }
+    values
 }
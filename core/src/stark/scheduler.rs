@@ -0,0 +1,195 @@
+//! A scheduler of the work on the shards of a proof, which bounds the memory of the shards in
+//! flight.
+//!
+//! Committing to all the shards of a batch at once needs the traces of all of them in memory, so
+//! the [ShardScheduler] instead starts the work on a shard only once the estimated memory of the
+//! shards in flight leaves room for it. The work is run by the work-stealing pool of rayon, so the
+//! shards which fit in the budget keep all the cores busy.
+
+use std::collections::HashMap;
+use std::sync::mpsc::channel;
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+use crate::utils::{available_memory, SP1CoreOpts};
+
+/// The approximate prover memory, in bytes, needed per event of a shard. Calibrated against the
+/// memory per cycle of the shard size tuner, for shards with about four events per cycle.
+pub const BYTES_PER_EVENT: u64 = 2 * 1024;
+
+/// The fraction of the available memory the scheduler plans for by default, leaving headroom for
+/// the rest of the process.
+const MEMORY_HEADROOM_PERCENT: u64 = 80;
+
+/// How long a worker of the pool which waits for memory sleeps when it has no other work to run.
+const WORKER_WAIT: Duration = Duration::from_millis(1);
+
+/// Estimates the prover memory of a shard from the number of events in its `stats`.
+pub fn estimate_shard_memory(stats: &HashMap<String, usize>) -> u64 {
+    stats.values().map(|&count| count as u64).sum::<u64>() * BYTES_PER_EVENT
+}
+
+/// A budget of memory shared by the shards in flight.
+#[derive(Debug)]
+pub struct MemoryBudget {
+    limit: u64,
+    in_use: Mutex<u64>,
+    released: Condvar,
+}
+
+/// The memory of a shard in flight, which is given back to the budget when dropped.
+#[derive(Debug)]
+pub struct MemoryPermit<'a> {
+    budget: &'a MemoryBudget,
+    bytes: u64,
+}
+
+impl MemoryBudget {
+    /// Creates a budget of `limit` bytes.
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            in_use: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    /// The limit of the budget in bytes.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// The bytes of the budget in use.
+    pub fn in_use(&self) -> u64 {
+        *self.in_use.lock().unwrap()
+    }
+
+    /// Waits until `bytes` fit in the budget and takes them. A request larger than the whole
+    /// budget is granted once nothing else is in flight, so that every shard is eventually run.
+    pub fn acquire(&self, bytes: u64) -> MemoryPermit<'_> {
+        let mut in_use = self.in_use.lock().unwrap();
+        while *in_use > 0 && in_use.saturating_add(bytes) > self.limit {
+            if rayon::current_thread_index().is_some() {
+                // A worker of the pool runs the pending work while it waits, since that work may
+                // hold the memory it waits for.
+                drop(in_use);
+                if rayon::yield_now() != Some(rayon::Yield::Executed) {
+                    std::thread::sleep(WORKER_WAIT);
+                }
+                in_use = self.in_use.lock().unwrap();
+            } else {
+                in_use = self.released.wait(in_use).unwrap();
+            }
+        }
+        *in_use += bytes;
+        MemoryPermit {
+            budget: self,
+            bytes,
+        }
+    }
+}
+
+impl Drop for MemoryPermit<'_> {
+    fn drop(&mut self) {
+        *self.budget.in_use.lock().unwrap() -= self.bytes;
+        self.budget.released.notify_all();
+    }
+}
+
+/// Runs the work on the shards of a proof in parallel, with the estimated memory of the shards in
+/// flight bounded by a [MemoryBudget].
+#[derive(Debug)]
+pub struct ShardScheduler {
+    budget: MemoryBudget,
+}
+
+impl ShardScheduler {
+    /// Creates a scheduler with a budget of `limit` bytes.
+    pub fn new(limit: u64) -> Self {
+        Self {
+            budget: MemoryBudget::new(limit),
+        }
+    }
+
+    /// Creates a scheduler with the budget of `opts.shard_memory_budget`, or with most of the
+    /// memory available on this machine if it is not set. The budget is unbounded if the
+    /// available memory cannot be determined.
+    pub fn from_opts(opts: &SP1CoreOpts) -> Self {
+        let limit = opts
+            .shard_memory_budget
+            .or_else(|| available_memory().map(|memory| memory / 100 * MEMORY_HEADROOM_PERCENT))
+            .unwrap_or(u64::MAX);
+        Self::new(limit)
+    }
+
+    /// The budget of the scheduler.
+    pub fn budget(&self) -> &MemoryBudget {
+        &self.budget
+    }
+
+    /// Runs `f` on each item in parallel, starting the items in order once their estimated memory
+    /// `cost` fits in the budget, and returns the results in the order of the items. The memory of
+    /// an item is given back to the budget when `f` returns, so `f` should only return what is
+    /// kept of the item, such as its commitment or its proof.
+    pub fn map<T, R, C, F>(&self, items: Vec<T>, cost: C, f: F) -> Vec<R>
+    where
+        T: Send,
+        R: Send,
+        C: Fn(&T) -> u64,
+        F: Fn(T) -> R + Sync,
+    {
+        let num_items = items.len();
+        let (sender, receiver) = channel();
+        let f = &f;
+        rayon::in_place_scope(|scope| {
+            for (index, item) in items.into_iter().enumerate() {
+                let permit = self.budget.acquire(cost(&item));
+                let sender = sender.clone();
+                scope.spawn(move |_| {
+                    let result = f(item);
+                    drop(permit);
+                    sender.send((index, result)).unwrap();
+                });
+            }
+        });
+        drop(sender);
+
+        let mut results = receiver.into_iter().collect::<Vec<_>>();
+        debug_assert_eq!(results.len(), num_items);
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn test_shard_scheduler() {
+        let scheduler = ShardScheduler::new(10);
+        let peak = AtomicU64::new(0);
+        let costs = vec![4, 3, 5, 2, 6, 1, 4, 3];
+        let results = scheduler.map(
+            costs.clone(),
+            |&cost| cost,
+            |cost| {
+                peak.fetch_max(scheduler.budget().in_use(), Ordering::Relaxed);
+                std::thread::sleep(Duration::from_millis(5));
+                cost * 2
+            },
+        );
+        assert_eq!(
+            results,
+            costs.iter().map(|cost| cost * 2).collect::<Vec<_>>()
+        );
+        assert!(peak.load(Ordering::Relaxed) <= 10);
+        assert_eq!(scheduler.budget().in_use(), 0);
+
+        // An item larger than the whole budget still runs, on its own.
+        let results = scheduler.map(vec![20, 1], |&cost| cost, |cost| cost);
+        assert_eq!(results, vec![20, 1]);
+    }
+}
@@ -0,0 +1,304 @@
+//! A breakdown of the size of a shard proof into its components.
+//!
+//! The sizes are the number of bytes of each component in the bincode encoding of the proof, which
+//! is the encoding of the saved proofs. The components do not include the lengths of the vectors
+//! which hold them, so their sum is slightly less than the total size of the proof.
+
+use std::fmt::{self, Display, Formatter};
+
+use p3_commit::Mmcs;
+use p3_field::{ExtensionField, Field};
+use p3_fri::TwoAdicFriPcsProof;
+use serde::{Deserialize, Serialize};
+
+use super::{OpeningProof, ShardProof, StarkGenericConfig};
+
+/// The round of the preprocessed traces of the chips, which is the first of the rounds opened by
+/// a shard proof.
+const PREPROCESSED_ROUND: usize = 0;
+
+/// The round of the main traces of the chips.
+const MAIN_ROUND: usize = 1;
+
+/// The round of the permutation traces of the chips.
+const PERMUTATION_ROUND: usize = 2;
+
+/// The round of the quotient chunks of the chips.
+const QUOTIENT_ROUND: usize = 3;
+
+/// The size in bytes of a component of a proof.
+fn size_of<T: Serialize + ?Sized>(value: &T) -> usize {
+    bincode::serialized_size(value).unwrap() as usize
+}
+
+/// The sizes of the components of a shard proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardProofSize {
+    /// The size of the whole proof.
+    pub total: usize,
+    /// The commitments to the main, permutation and quotient traces.
+    pub commitments: usize,
+    /// The public values of the shard.
+    pub public_values: usize,
+    /// The names of the chips of the shard and their indices.
+    pub chip_ordering: usize,
+    /// The sizes of the components of each chip.
+    pub chips: Vec<ChipProofSize>,
+    /// The values of the preprocessed traces opened at the queries of FRI.
+    pub preprocessed_openings: usize,
+    /// The Merkle paths of the rows of the traces opened at the queries of FRI.
+    pub merkle_paths: usize,
+    /// The proof of the low degree of the opened polynomials.
+    pub fri: FriProofSize,
+}
+
+/// The sizes of the components of a shard proof which are specific to a chip.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChipProofSize {
+    /// The name of the chip.
+    pub name: String,
+    /// The evaluations of the traces of the chip at the out-of-domain point.
+    pub opened_values: usize,
+    /// The rows of the main, permutation and quotient traces of the chip opened at the queries of
+    /// FRI.
+    pub query_openings: usize,
+}
+
+/// The sizes of the components of a FRI proof.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FriProofSize {
+    /// The size of the commitment and of the openings at the queries of each folding layer.
+    pub layers: Vec<usize>,
+    /// The final polynomial.
+    pub final_poly: usize,
+    /// The witness of the proof of work.
+    pub pow_witness: usize,
+}
+
+/// The sizes of the components of an opening proof of a PCS.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpeningProofSize {
+    /// The size of the opened rows of each matrix of each round, over all the queries.
+    pub round_openings: Vec<Vec<usize>>,
+    /// The size of the Merkle paths of each round, over all the queries.
+    pub round_merkle_paths: Vec<usize>,
+    /// The sizes of the components of the FRI proof.
+    pub fri: FriProofSize,
+}
+
+impl OpeningProofSize {
+    /// The size of the opened rows of each matrix of `round`, which is empty if the proof has no
+    /// such round.
+    pub fn openings_of_round(&self, round: usize) -> &[usize] {
+        self.round_openings
+            .get(round)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+/// An opening proof whose size can be broken down into its components.
+pub trait OpeningProofBreakdown {
+    /// The sizes of the components of the proof.
+    fn size_breakdown(&self) -> OpeningProofSize;
+}
+
+impl<Val, Challenge, InputMmcs, FriMmcs> OpeningProofBreakdown
+    for TwoAdicFriPcsProof<Val, Challenge, InputMmcs, FriMmcs>
+where
+    Val: Field,
+    Challenge: ExtensionField<Val>,
+    InputMmcs: Mmcs<Val>,
+    FriMmcs: Mmcs<Challenge>,
+{
+    fn size_breakdown(&self) -> OpeningProofSize {
+        let mut round_openings: Vec<Vec<usize>> = Vec::new();
+        let mut round_merkle_paths = Vec::new();
+        for query in self.query_openings.iter() {
+            for (round, opening) in query.iter().enumerate() {
+                if round_openings.len() <= round {
+                    round_openings.push(vec![0; opening.opened_values.len()]);
+                    round_merkle_paths.push(0);
+                }
+                for (size, values) in round_openings[round]
+                    .iter_mut()
+                    .zip(opening.opened_values.iter())
+                {
+                    *size += size_of(values);
+                }
+                round_merkle_paths[round] += size_of(&opening.opening_proof);
+            }
+        }
+
+        let fri_proof = &self.fri_proof;
+        let mut layers = fri_proof
+            .commit_phase_commits
+            .iter()
+            .map(size_of)
+            .collect::<Vec<_>>();
+        for query in fri_proof.query_proofs.iter() {
+            for (size, step) in layers.iter_mut().zip(query.commit_phase_openings.iter()) {
+                *size += size_of(step);
+            }
+        }
+
+        OpeningProofSize {
+            round_openings,
+            round_merkle_paths,
+            fri: FriProofSize {
+                layers,
+                final_poly: size_of(&fri_proof.final_poly),
+                pow_witness: size_of(&fri_proof.pow_witness),
+            },
+        }
+    }
+}
+
+/// A proof whose size can be broken down into its components.
+pub trait ProofSizeBreakdown {
+    /// The sizes of the components of the proof.
+    fn size_breakdown(&self) -> ShardProofSize;
+}
+
+impl<SC: StarkGenericConfig> ProofSizeBreakdown for ShardProof<SC>
+where
+    OpeningProof<SC>: OpeningProofBreakdown,
+{
+    fn size_breakdown(&self) -> ShardProofSize {
+        let opening = self.opening_proof.size_breakdown();
+
+        let mut names = vec![String::new(); self.opened_values.chips.len()];
+        for (name, &index) in self.chip_ordering.iter() {
+            names[index].clone_from(name);
+        }
+        let mut quotient_openings = opening.openings_of_round(QUOTIENT_ROUND).iter();
+        let chips = self
+            .opened_values
+            .chips
+            .iter()
+            .zip(names)
+            .enumerate()
+            .map(|(index, (values, name))| {
+                let trace_openings = [MAIN_ROUND, PERMUTATION_ROUND]
+                    .into_iter()
+                    .filter_map(|round| opening.openings_of_round(round).get(index))
+                    .sum::<usize>();
+                let quotient_openings = quotient_openings
+                    .by_ref()
+                    .take(values.quotient.len())
+                    .sum::<usize>();
+                ChipProofSize {
+                    name,
+                    opened_values: size_of(values),
+                    query_openings: trace_openings + quotient_openings,
+                }
+            })
+            .collect();
+
+        ShardProofSize {
+            total: size_of(self),
+            commitments: size_of(&self.commitment),
+            public_values: size_of(&self.public_values),
+            chip_ordering: size_of(&self.chip_ordering),
+            chips,
+            preprocessed_openings: opening.openings_of_round(PREPROCESSED_ROUND).iter().sum(),
+            merkle_paths: opening.round_merkle_paths.iter().sum(),
+            fri: opening.fri,
+        }
+    }
+}
+
+impl ShardProofSize {
+    /// The size of the components of the chips.
+    pub fn chips_total(&self) -> usize {
+        self.chips
+            .iter()
+            .map(|chip| chip.opened_values + chip.query_openings)
+            .sum()
+    }
+}
+
+impl FriProofSize {
+    /// The size of the whole FRI proof.
+    pub fn total(&self) -> usize {
+        self.layers.iter().sum::<usize>() + self.final_poly + self.pow_witness
+    }
+}
+
+impl Display for ShardProofSize {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let percent = |size: usize| 100.0 * size as f64 / self.total.max(1) as f64;
+        let row = |f: &mut Formatter<'_>, name: &str, size: usize| {
+            writeln!(f, "  {:<32} {:>12} {:>7.2}%", name, size, percent(size))
+        };
+
+        writeln!(f, "shard proof: {} bytes", self.total)?;
+        row(f, "commitments", self.commitments)?;
+        row(f, "public values", self.public_values)?;
+        row(f, "chip ordering", self.chip_ordering)?;
+        row(f, "preprocessed openings", self.preprocessed_openings)?;
+        row(f, "merkle paths", self.merkle_paths)?;
+        row(f, "fri", self.fri.total())?;
+        for (i, layer) in self.fri.layers.iter().enumerate() {
+            row(f, &format!("  layer {}", i), *layer)?;
+        }
+        row(f, "  final polynomial", self.fri.final_poly)?;
+        row(f, "  proof of work", self.fri.pow_witness)?;
+        row(f, "chips", self.chips_total())?;
+
+        let mut chips = self.chips.iter().collect::<Vec<_>>();
+        chips.sort_by_key(|chip| std::cmp::Reverse(chip.opened_values + chip.query_openings));
+        for chip in chips {
+            row(
+                f,
+                &format!("  {}", chip.name),
+                chip.opened_values + chip.query_openings,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::SP1Stdin;
+    use crate::runtime::tests::fibonacci_program;
+    use crate::utils::{prove, setup_logger, BabyBearPoseidon2, SP1CoreOpts};
+
+    #[test]
+    fn test_shard_proof_size() {
+        setup_logger();
+        let (proof, _) = prove(
+            fibonacci_program(),
+            &SP1Stdin::new(),
+            BabyBearPoseidon2::new(),
+            SP1CoreOpts::default(),
+        )
+        .unwrap();
+
+        for shard in proof.shard_proofs.iter() {
+            let size = shard.size_breakdown();
+            assert_eq!(size.total, bincode::serialize(shard).unwrap().len());
+            assert_eq!(size.chips.len(), shard.chip_ordering.len());
+            assert!(size
+                .chips
+                .iter()
+                .all(|chip| shard.chip_ordering.contains_key(&chip.name)
+                    && chip.opened_values > 0
+                    && chip.query_openings > 0));
+            assert!(!size.fri.layers.is_empty());
+
+            // The components account for the whole proof, except the lengths of the vectors.
+            let components = size.commitments
+                + size.public_values
+                + size.chip_ordering
+                + size.preprocessed_openings
+                + size.merkle_paths
+                + size.fri.total()
+                + size.chips_total();
+            assert!(components <= size.total);
+            assert!(components * 5 >= size.total * 4);
+        }
+    }
+}
@@ -1,21 +1,18 @@
-use std::{
-    collections::HashMap,
-    fmt::Debug,
-    fs::File,
-    io::{BufReader, BufWriter, Seek},
-};
+use std::{collections::BTreeMap, fs::File, io::BufWriter};
 
-use bincode::{deserialize_from, Error};
+use bincode::Error;
+use memmap2::Mmap;
 use p3_matrix::dense::RowMajorMatrix;
-use p3_matrix::dense::RowMajorMatrixView;
-use p3_matrix::stack::VerticalPair;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use size::Size;
 use tracing::trace;
 
-use super::{Challenge, Com, OpeningProof, PcsProverData, StarkGenericConfig, Val};
+use super::{Com, PcsProverData, StarkGenericConfig, Val};
 
-pub type QuotientOpenedValues<T> = Vec<T>;
+pub use sp1_stark_verifier::{
+    AirOpenedValues, ChipOpenedValues, MachineProof, QuotientOpenedValues, ShardCommitment,
+    ShardOpenedValues, ShardProof, StarkVerifyingKey, PROOF_MAX_NUM_PVS,
+};
 
 #[derive(Serialize, Deserialize)]
 #[serde(bound(serialize = "PcsProverData<SC>: Serialize"))]
@@ -24,7 +21,7 @@ pub struct ShardMainData<SC: StarkGenericConfig> {
     pub traces: Vec<RowMajorMatrix<Val<SC>>>,
     pub main_commit: Com<SC>,
     pub main_data: PcsProverData<SC>,
-    pub chip_ordering: HashMap<String, usize>,
+    pub chip_ordering: BTreeMap<String, usize>,
     pub index: usize,
     pub public_values: Vec<SC::Val>,
 }
@@ -34,7 +31,7 @@ impl<SC: StarkGenericConfig> ShardMainData<SC> {
         traces: Vec<RowMajorMatrix<Val<SC>>>,
         main_commit: Com<SC>,
         main_data: PcsProverData<SC>,
-        chip_ordering: HashMap<String, usize>,
+        chip_ordering: BTreeMap<String, usize>,
         index: usize,
         public_values: Vec<Val<SC>>,
     ) -> Self {
@@ -83,9 +80,10 @@ impl<SC: StarkGenericConfig> ShardMainDataWrapper<SC> {
         match self {
             Self::InMemory(data) => Ok(data),
             Self::TempFile(file, _) => {
-                let mut buffer = BufReader::new(&file);
-                buffer.seek(std::io::SeekFrom::Start(0))?;
-                let data = deserialize_from(&mut buffer)?;
+                // SAFETY: the file is an anonymous temporary file owned by the wrapper, so it is
+                // not modified while it is mapped.
+                let mmap = unsafe { Mmap::map(&file)? };
+                let data = bincode::deserialize(&mmap)?;
                 Ok(data)
             }
             Self::Empty() => unreachable!(),
@@ -93,87 +91,6 @@ impl<SC: StarkGenericConfig> ShardMainDataWrapper<SC> {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ShardCommitment<C> {
-    pub main_commit: C,
-    pub permutation_commit: C,
-    pub quotient_commit: C,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AirOpenedValues<T> {
-    pub local: Vec<T>,
-    pub next: Vec<T>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ChipOpenedValues<T: Serialize> {
-    pub preprocessed: AirOpenedValues<T>,
-    pub main: AirOpenedValues<T>,
-    pub permutation: AirOpenedValues<T>,
-    pub quotient: Vec<Vec<T>>,
-    pub cumulative_sum: T,
-    pub log_degree: usize,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ShardOpenedValues<T: Serialize> {
-    pub chips: Vec<ChipOpenedValues<T>>,
-}
-
-/// The maximum number of elements that can be stored in the public values vec.  Both SP1 and recursive
-/// proofs need to pad their public_values vec to this length.  This is required since the recursion
-/// verification program expects the public values vec to be fixed length.
-pub const PROOF_MAX_NUM_PVS: usize = 240;
-
-#[derive(Serialize, Deserialize, Clone)]
-#[serde(bound = "")]
-pub struct ShardProof<SC: StarkGenericConfig> {
-    pub commitment: ShardCommitment<Com<SC>>,
-    pub opened_values: ShardOpenedValues<Challenge<SC>>,
-    pub opening_proof: OpeningProof<SC>,
-    pub chip_ordering: HashMap<String, usize>,
-    pub public_values: Vec<Val<SC>>,
-}
-
-impl<SC: StarkGenericConfig> Debug for ShardProof<SC> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ShardProof").finish()
-    }
-}
-
-impl<T: Send + Sync + Clone> AirOpenedValues<T> {
-    pub fn view(&self) -> VerticalPair<RowMajorMatrixView<'_, T>, RowMajorMatrixView<'_, T>> {
-        let a = RowMajorMatrixView::new_row(&self.local);
-        let b = RowMajorMatrixView::new_row(&self.next);
-        VerticalPair::new(a, b)
-    }
-}
-
-impl<SC: StarkGenericConfig> ShardProof<SC> {
-    pub fn cumulative_sum(&self) -> Challenge<SC> {
-        self.opened_values
-            .chips
-            .iter()
-            .map(|c| c.cumulative_sum)
-            .sum()
-    }
-}
-
-#[derive(Serialize, Deserialize, Clone)]
-#[serde(bound = "")]
-pub struct MachineProof<SC: StarkGenericConfig> {
-    pub shard_proofs: Vec<ShardProof<SC>>,
-}
-
-impl<SC: StarkGenericConfig> Debug for MachineProof<SC> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Proof")
-            .field("shard_proofs", &self.shard_proofs.len())
-            .finish()
-    }
-}
-
 /// PublicValuesDigest is a hash of all the public values that a zkvm program has committed to.
 pub struct PublicValuesDigest(pub [u8; 32]);
 
@@ -1,3 +1,4 @@
+use crate::air::USER_PV_NUM_WORDS;
 use crate::runtime::{Syscall, SyscallContext};
 
 /// A syscall that commits a word of the public values digest.
@@ -43,3 +44,62 @@ impl Syscall for SyscallCommitDeferred {
         None
     }
 }
+
+/// Commit to one word of the public values set by the guest program. Takes in an index and a word.
+pub struct SyscallCommitUserValue;
+
+impl SyscallCommitUserValue {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for SyscallCommitUserValue {
+    fn execute(&self, ctx: &mut SyscallContext, word_idx: u32, word: u32) -> Option<u32> {
+        let rt = &mut ctx.rt;
+
+        assert!(
+            (word_idx as usize) < USER_PV_NUM_WORDS,
+            "user public value index {} is out of bounds",
+            word_idx
+        );
+        rt.record.public_values.user_values[word_idx as usize] = word;
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::{self, run_test, SP1CoreOpts};
+
+    #[test]
+    fn test_commit_user_value() {
+        utils::setup_logger();
+        let mut instructions = vec![];
+        for (index, word) in [(0, 42), (3, 0xdead_beef)] {
+            instructions.extend([
+                Instruction::new(
+                    Opcode::ADD,
+                    5,
+                    0,
+                    SyscallCode::COMMIT_USER_VALUE as u32,
+                    false,
+                    true,
+                ),
+                Instruction::new(Opcode::ADD, 10, 0, index, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, word, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        let program = Program::new(instructions, 0, 0);
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let user_values = runtime.record.public_values.user_values;
+        assert_eq!(user_values, [42, 0, 0, 0xdead_beef, 0, 0, 0, 0]);
+
+        run_test(program).unwrap();
+    }
+}
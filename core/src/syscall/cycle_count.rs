@@ -0,0 +1,64 @@
+use crate::runtime::{Syscall, SyscallContext};
+
+/// A syscall which returns the lower word of the global clock of the execution.
+///
+/// The returned value is not constrained, so a program must not rely on it for its correctness.
+pub struct SyscallCycleCount;
+
+impl SyscallCycleCount {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for SyscallCycleCount {
+    fn execute(&self, ctx: &mut SyscallContext, _: u32, _: u32) -> Option<u32> {
+        Some(ctx.rt.state.global_clk as u32)
+    }
+}
+
+impl Default for SyscallCycleCount {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::SP1Stdin;
+    use crate::runtime::{Instruction, Opcode, Program, Register, Runtime, SyscallCode};
+    use crate::utils::{run_test_io, setup_logger, SP1CoreOpts};
+
+    const CYCLE_COUNT_ELF: &[u8] =
+        include_bytes!("../../../tests/cycle-count/elf/riscv32im-succinct-zkvm-elf");
+
+    #[test]
+    fn test_cycle_count() {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 29, 0, 1, false, true),
+            Instruction::new(Opcode::ADD, 29, 29, 1, false, true),
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::CYCLE_COUNT as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 30, 5, 0, false, true),
+        ];
+        let mut runtime = Runtime::new(Program::new(instructions, 0, 0), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        // The syscall is the fourth instruction, executed after three cycles.
+        assert_eq!(runtime.register(Register::X30), 3);
+    }
+
+    #[test]
+    fn test_cycle_count_prove() {
+        setup_logger();
+        let program = Program::from(CYCLE_COUNT_ELF);
+        let mut public_values = run_test_io(program, SP1Stdin::new()).unwrap();
+        assert!(public_values.read::<u32>() > 0);
+    }
+}
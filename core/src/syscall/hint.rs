@@ -11,6 +11,10 @@ impl SyscallHintLen {
 
 impl Syscall for SyscallHintLen {
     fn execute(&self, ctx: &mut SyscallContext, _arg1: u32, _arg2: u32) -> Option<u32> {
+        if let Err(error) = ctx.rt.fetch_input() {
+            ctx.fail(error);
+            return None;
+        }
         if ctx.rt.state.input_stream_ptr >= ctx.rt.state.input_stream.len() {
             panic!("not enough vecs in hint input stream");
         }
@@ -29,11 +33,22 @@ impl SyscallHintRead {
 
 impl Syscall for SyscallHintRead {
     fn execute(&self, ctx: &mut SyscallContext, ptr: u32, len: u32) -> Option<u32> {
+        if let Err(error) = ctx.rt.fetch_input() {
+            ctx.fail(error);
+            return None;
+        }
         if ctx.rt.state.input_stream_ptr >= ctx.rt.state.input_stream.len() {
             panic!("not enough vecs in hint input stream");
         }
-        let vec = &ctx.rt.state.input_stream[ctx.rt.state.input_stream_ptr];
+        // The input is never read again once consumed, so take it to free the memory.
+        let vec = std::mem::take(&mut ctx.rt.state.input_stream[ctx.rt.state.input_stream_ptr]);
         ctx.rt.state.input_stream_ptr += 1;
+        if ctx.rt.state.pending_hints > 0 {
+            // A hint is written again when the execution is replayed, so it is not recorded.
+            ctx.rt.state.pending_hints -= 1;
+        } else {
+            ctx.rt.record_input(&vec);
+        }
         assert!(
             !ctx.rt.unconstrained,
             "hint read should not be used in a unconstrained block"
@@ -57,12 +72,15 @@ impl Syscall for SyscallHintRead {
 
             // Save the data into runtime state so the runtime will use the desired data instead of
             // 0 when first reading/writing from this address.
-            ctx.rt
+            if ctx
+                .rt
                 .state
                 .uninitialized_memory
-                .entry(ptr + i)
-                .and_modify(|_| panic!("hint read address is initialized already"))
-                .or_insert(word);
+                .insert(ptr + i, word)
+                .is_some()
+            {
+                panic!("hint read address is initialized already");
+            }
         }
         None
     }
@@ -75,12 +93,15 @@ mod tests {
     use crate::{
         io::SP1Stdin,
         runtime::Program,
-        utils::{prove, setup_logger, BabyBearPoseidon2, SP1CoreOpts},
+        utils::{prove, run_test_io, setup_logger, BabyBearPoseidon2, SP1CoreOpts},
     };
 
     const HINT_IO_ELF: &[u8] =
         include_bytes!("../../../tests/hint-io/elf/riscv32im-succinct-zkvm-elf");
 
+    const FREE_LIST_HINT_ELF: &[u8] =
+        include_bytes!("../../../tests/free-list-hint/elf/riscv32im-succinct-zkvm-elf");
+
     #[test]
     fn test_hint_io() {
         setup_logger();
@@ -98,4 +119,22 @@ mod tests {
         let config = BabyBearPoseidon2::new();
         prove(program, &stdin, config, SP1CoreOpts::default()).unwrap();
     }
+
+    #[test]
+    fn test_hint_read_after_free() {
+        setup_logger();
+
+        let mut rng = rand::thread_rng();
+        let mut data = vec![0u8; 1021];
+        rng.fill_bytes(&mut data);
+
+        let mut stdin = SP1Stdin::new();
+        stdin.write_vec(data.clone());
+        stdin.write(&data);
+
+        // The program frees a written block of the size of each hint before reading it.
+        let program = Program::from(FREE_LIST_HINT_ELF);
+        let public_values = run_test_io(program, stdin).unwrap();
+        assert_eq!(public_values.as_slice(), [data.clone(), data].concat());
+    }
 }
@@ -1,14 +1,20 @@
 mod commit;
+mod cycle_count;
 mod halt;
 mod hint;
+mod panic;
 pub mod precompiles;
+mod random;
 mod unconstrained;
 mod verify;
 mod write;
 
 pub use commit::*;
+pub use cycle_count::*;
 pub use halt::*;
 pub use hint::*;
+pub use panic::*;
+pub use random::*;
 pub use unconstrained::*;
 pub use verify::*;
 pub use write::*;
@@ -0,0 +1,106 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use serde::{Deserialize, Serialize};
+
+use crate::runtime::{Syscall, SyscallContext};
+
+/// The panic of a program, as reported by its panic handler before it halts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuestPanic {
+    /// The message of the panic.
+    pub message: String,
+    /// The file of the program where the panic happened, if the message gives it.
+    pub file: Option<String>,
+    /// The line of the panic in `file`.
+    pub line: Option<u32>,
+    /// The column of the panic in `file`.
+    pub column: Option<u32>,
+}
+
+impl GuestPanic {
+    /// Parses the message printed by the panic handler of the standard library, of the form
+    /// `panicked at <file>:<line>:<column>:\n<message>`. A message of any other form is kept whole,
+    /// without a location.
+    pub fn parse(text: &str) -> Self {
+        let unlocated = || Self {
+            message: text.trim_end().to_string(),
+            file: None,
+            line: None,
+            column: None,
+        };
+        let Some((location, message)) = text
+            .strip_prefix("panicked at ")
+            .and_then(|rest| rest.split_once(":\n"))
+        else {
+            return unlocated();
+        };
+        let mut parts = location.rsplitn(3, ':');
+        let (Some(column), Some(line), Some(file)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return unlocated();
+        };
+        let (Ok(line), Ok(column)) = (line.parse(), column.parse()) else {
+            return unlocated();
+        };
+        Self {
+            message: message.trim_end().to_string(),
+            file: Some(file.to_string()),
+            line: Some(line),
+            column: Some(column),
+        }
+    }
+}
+
+impl Display for GuestPanic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match (&self.file, self.line, self.column) {
+            (Some(file), Some(line), Some(column)) => {
+                write!(f, "{}:{}:{}: {}", file, line, column, self.message)
+            }
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// A syscall which reports the panic message of the program. The panic handler halts the program
+/// with exit code 1 right after it.
+pub struct SyscallPanic;
+
+impl SyscallPanic {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for SyscallPanic {
+    fn execute(&self, ctx: &mut SyscallContext, msg_ptr: u32, len: u32) -> Option<u32> {
+        let rt = &mut ctx.rt;
+        let bytes = (0..len).map(|i| rt.byte(msg_ptr + i)).collect::<Vec<u8>>();
+        rt.guest_panic = Some(GuestPanic::parse(&String::from_utf8_lossy(&bytes)));
+        None
+    }
+}
+
+impl Default for SyscallPanic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GuestPanic;
+
+    #[test]
+    fn test_parse_guest_panic() {
+        let panic = GuestPanic::parse("panicked at src/main.rs:12:5:\nindex out of bounds\n");
+        assert_eq!(panic.message, "index out of bounds");
+        assert_eq!(panic.file.as_deref(), Some("src/main.rs"));
+        assert_eq!((panic.line, panic.column), (Some(12), Some(5)));
+        assert_eq!(panic.to_string(), "src/main.rs:12:5: index out of bounds");
+
+        let panic = GuestPanic::parse("custom handler message");
+        assert_eq!(panic.message, "custom handler message");
+        assert_eq!(panic.file, None);
+    }
+}
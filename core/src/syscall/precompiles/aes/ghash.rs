@@ -0,0 +1,390 @@
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+
+use p3_air::{Air, BaseAir};
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+
+use super::{AES_STATE_BYTES, AES_STATE_WORDS};
+use crate::air::{MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::runtime::{
+    ExecutionRecord, MemoryReadRecord, MemoryWriteRecord, Program, Syscall, SyscallCode,
+};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+
+/// The number of bits of a GHASH block.
+pub const GHASH_BITS: usize = 8 * AES_STATE_BYTES;
+
+/// The number of coefficients of the product of two blocks, before the reduction.
+const PRODUCT_BITS: usize = 2 * GHASH_BITS - 1;
+
+pub const NUM_GHASH_MUL_COLS: usize = size_of::<GhashMulCols<u8>>();
+
+/// The reductions of `x^k` modulo the GCM polynomial `x^128 + x^7 + x^2 + x + 1` for each
+/// coefficient `k` of a product, where bit `m` is the coefficient of `x^m`.
+const REDUCTIONS: [u128; PRODUCT_BITS] = reductions();
+
+const fn reductions() -> [u128; PRODUCT_BITS] {
+    let mut table = [0u128; PRODUCT_BITS];
+    let mut power = 1u128;
+    let mut k = 0;
+    while k < PRODUCT_BITS {
+        table[k] = power;
+        let overflow = power >> 127 == 1;
+        power <<= 1;
+        if overflow {
+            power ^= 0x87;
+        }
+        k += 1;
+    }
+    table
+}
+
+/// The coefficient of `x^i` in a block. As in GCM, the coefficient of `x^0` is the most
+/// significant bit of the first byte.
+const fn block_bit(block: &[u8; AES_STATE_BYTES], i: usize) -> u8 {
+    (block[i / 8] >> (7 - i % 8)) & 1
+}
+
+/// The coefficients of the product of two blocks as polynomials, before the reduction.
+fn product_coefficients(
+    x: &[u8; AES_STATE_BYTES],
+    h: &[u8; AES_STATE_BYTES],
+) -> [u32; PRODUCT_BITS] {
+    let mut coefficients = [0u32; PRODUCT_BITS];
+    for i in 0..GHASH_BITS {
+        for j in 0..GHASH_BITS {
+            coefficients[i + j] += (block_bit(x, i) & block_bit(h, j)) as u32;
+        }
+    }
+    coefficients
+}
+
+/// The sums of the product bits whose reductions contain `x^m`, for each coefficient `m` of the
+/// result.
+fn reduction_sums(product_bits: &[u32; PRODUCT_BITS]) -> [u32; GHASH_BITS] {
+    core::array::from_fn(|m| {
+        (0..PRODUCT_BITS)
+            .filter(|&k| (REDUCTIONS[k] >> m) & 1 == 1)
+            .map(|k| product_bits[k])
+            .sum()
+    })
+}
+
+/// Multiplies two blocks in the GHASH field GF(2^128).
+pub fn gf128_mul(x: &[u8; AES_STATE_BYTES], h: &[u8; AES_STATE_BYTES]) -> [u8; AES_STATE_BYTES] {
+    let product_bits = product_coefficients(x, h).map(|c| c & 1);
+    let result_bits = reduction_sums(&product_bits).map(|s| s & 1);
+    let mut result = [0u8; AES_STATE_BYTES];
+    for (m, bit) in result_bits.iter().enumerate() {
+        result[m / 8] |= (*bit as u8) << (7 - m % 8);
+    }
+    result
+}
+
+/// GHASH multiplication event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GhashMulEvent {
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub h_ptr: u32,
+    pub x_records: [MemoryWriteRecord; AES_STATE_WORDS],
+    pub h_records: [MemoryReadRecord; AES_STATE_WORDS],
+}
+
+/// A set of columns for a multiplication in GF(2^128).
+///
+/// The carry-less product is computed over the bits of the operands: each coefficient of the
+/// product is a sum of products of bits, whose parity is the coefficient over GF(2). The same holds
+/// for the reduction, which is linear over GF(2).
+#[derive(AlignedBorrow, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct GhashMulCols<T> {
+    pub shard: T,
+    pub channel: T,
+    pub clk: T,
+    pub x_ptr: T,
+    pub h_ptr: T,
+
+    pub x_memory: [MemoryWriteCols<T>; AES_STATE_WORDS],
+    pub h_memory: [MemoryReadCols<T>; AES_STATE_WORDS],
+
+    pub x_bits: [T; GHASH_BITS],
+    pub h_bits: [T; GHASH_BITS],
+
+    /// The parities and halves of the coefficients of the product.
+    pub product_bits: [T; PRODUCT_BITS],
+    pub product_carries: [T; PRODUCT_BITS],
+
+    /// The parities and halves of the sums of the reduction.
+    pub result_bits: [T; GHASH_BITS],
+    pub result_carries: [T; GHASH_BITS],
+
+    pub is_real: T,
+}
+
+/// A chip for the `GHASH_MUL` syscall, which overwrites a 16-byte block with its product by the
+/// 16-byte block at the second argument in the GHASH field of AES-GCM.
+#[derive(Default)]
+pub struct GhashMulChip;
+
+impl GhashMulChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for GhashMulChip {
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let start_clk = rt.clk;
+        let x_ptr = arg1;
+        if x_ptr % 4 != 0 {
+            panic!();
+        }
+        let h_ptr = arg2;
+        if h_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // We can read x with slice_unsafe because we write the product to it later.
+        let x = rt.slice_unsafe(x_ptr, AES_STATE_WORDS);
+        let (h_records, h) = rt.mr_slice(h_ptr, AES_STATE_WORDS);
+
+        // When we write to x, we want the clk to be incremented because x and h could be the same.
+        rt.clk += 1;
+
+        let to_bytes = |words: &[u32]| -> [u8; AES_STATE_BYTES] {
+            words
+                .iter()
+                .flat_map(|word| word.to_le_bytes())
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap()
+        };
+        let product = gf128_mul(&to_bytes(&x), &to_bytes(&h));
+        let product_words = product
+            .chunks_exact(WORD_SIZE)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let x_records = rt.mw_slice(x_ptr, &product_words);
+
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut().ghash_mul_events.push(GhashMulEvent {
+            shard,
+            channel,
+            clk: start_clk,
+            x_ptr,
+            h_ptr,
+            x_records: x_records.try_into().unwrap(),
+            h_records: h_records.try_into().unwrap(),
+        });
+
+        None
+    }
+
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for GhashMulChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "GhashMul".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut rows = Vec::new();
+        let mut new_byte_lookup_events = Vec::new();
+
+        for event in input.ghash_mul_events.iter() {
+            let mut row = vec![F::zero(); NUM_GHASH_MUL_COLS];
+            let cols: &mut GhashMulCols<F> = row.as_mut_slice().borrow_mut();
+
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.channel = F::from_canonical_u32(event.channel);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+            cols.h_ptr = F::from_canonical_u32(event.h_ptr);
+            cols.is_real = F::one();
+
+            for i in 0..AES_STATE_WORDS {
+                cols.x_memory[i].populate(
+                    event.channel,
+                    event.x_records[i],
+                    &mut new_byte_lookup_events,
+                );
+                cols.h_memory[i].populate(
+                    event.channel,
+                    event.h_records[i],
+                    &mut new_byte_lookup_events,
+                );
+            }
+
+            let x: [u8; AES_STATE_BYTES] = core::array::from_fn(|i| {
+                event.x_records[i / WORD_SIZE].prev_value.to_le_bytes()[i % WORD_SIZE]
+            });
+            let h: [u8; AES_STATE_BYTES] = core::array::from_fn(|i| {
+                event.h_records[i / WORD_SIZE].value.to_le_bytes()[i % WORD_SIZE]
+            });
+            for i in 0..GHASH_BITS {
+                cols.x_bits[i] = F::from_canonical_u8(block_bit(&x, i));
+                cols.h_bits[i] = F::from_canonical_u8(block_bit(&h, i));
+            }
+
+            let coefficients = product_coefficients(&x, &h);
+            let product_bits = coefficients.map(|c| c & 1);
+            let product_carries = coefficients.map(|c| c >> 1);
+            let sums = reduction_sums(&product_bits);
+            let result_bits = sums.map(|s| s & 1);
+            let result_carries = sums.map(|s| s >> 1);
+            cols.product_bits = product_bits.map(F::from_canonical_u32);
+            cols.product_carries = product_carries.map(F::from_canonical_u32);
+            cols.result_bits = result_bits.map(F::from_canonical_u32);
+            cols.result_carries = result_carries.map(F::from_canonical_u32);
+
+            let carries = product_carries
+                .iter()
+                .chain(result_carries.iter())
+                .map(|c| *c as u8)
+                .collect::<Vec<_>>();
+            new_byte_lookup_events.add_u8_range_checks(event.shard, event.channel, &carries);
+
+            rows.push(row);
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || vec![F::zero(); NUM_GHASH_MUL_COLS]);
+
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_GHASH_MUL_COLS,
+        )
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.ghash_mul_events.is_empty()
+    }
+}
+
+impl<F> BaseAir<F> for GhashMulChip {
+    fn width(&self) -> usize {
+        NUM_GHASH_MUL_COLS
+    }
+}
+
+impl<AB> Air<AB> for GhashMulChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &GhashMulCols<AB::Var> = (*local).borrow();
+
+        builder.assert_bool(local.is_real);
+        for bit in local
+            .x_bits
+            .iter()
+            .chain(local.h_bits.iter())
+            .chain(local.product_bits.iter())
+            .chain(local.result_bits.iter())
+        {
+            builder.assert_bool(*bit);
+        }
+
+        // The bytes of the operands and of the result are the bits of the blocks.
+        let from_bits = |bits: &[AB::Var]| -> AB::Expr {
+            bits.iter()
+                .enumerate()
+                .map(|(k, bit)| *bit * AB::F::from_canonical_u32(1 << (7 - k)))
+                .sum()
+        };
+        for i in 0..AES_STATE_BYTES {
+            let bits = 8 * i..8 * (i + 1);
+            let (word, byte) = (i / WORD_SIZE, i % WORD_SIZE);
+            builder.assert_eq(
+                local.x_memory[word].prev_value().0[byte],
+                from_bits(&local.x_bits[bits.clone()]),
+            );
+            builder.assert_eq(
+                local.h_memory[word].value().0[byte],
+                from_bits(&local.h_bits[bits.clone()]),
+            );
+            builder.assert_eq(
+                local.x_memory[word].value().0[byte],
+                from_bits(&local.result_bits[bits]),
+            );
+        }
+
+        // Each coefficient of the carry-less product is the parity of a sum of products of bits.
+        let two = AB::F::from_canonical_u32(2);
+        for k in 0..PRODUCT_BITS {
+            let sum: AB::Expr = (k.saturating_sub(GHASH_BITS - 1)..=k.min(GHASH_BITS - 1))
+                .map(|i| local.x_bits[i] * local.h_bits[k - i])
+                .sum();
+            builder.assert_eq(sum, local.product_bits[k] + local.product_carries[k] * two);
+        }
+
+        // Likewise, each coefficient of the reduced product is the parity of a sum of product bits.
+        for m in 0..GHASH_BITS {
+            let sum: AB::Expr = (0..PRODUCT_BITS)
+                .filter(|&k| (REDUCTIONS[k] >> m) & 1 == 1)
+                .map(|k| local.product_bits[k].into())
+                .sum();
+            builder.assert_eq(sum, local.result_bits[m] + local.result_carries[m] * two);
+        }
+
+        // The carries are bytes, so that each parity is unique.
+        let carries = local
+            .product_carries
+            .iter()
+            .chain(local.result_carries.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        builder.slice_range_check_u8(&carries, local.shard, local.channel, local.is_real);
+
+        // Read h and write x at the next clk, since they could be the same.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.h_ptr,
+            &local.h_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::from_canonical_u32(1),
+            local.x_ptr,
+            &local.x_memory,
+            local.is_real,
+        );
+
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            AB::F::from_canonical_u32(SyscallCode::GHASH_MUL.syscall_id()),
+            local.x_ptr,
+            local.h_ptr,
+            local.is_real,
+        );
+    }
+}
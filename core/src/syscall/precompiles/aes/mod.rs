@@ -0,0 +1,141 @@
+mod ghash;
+mod round;
+
+pub use ghash::*;
+pub use round::*;
+
+#[cfg(test)]
+mod tests {
+    use super::{gf128_mul, xtime, AES_STATE_BYTES, AES_STATE_WORDS};
+    use crate::bytes::utils::AES_SBOX;
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::{self, run_test, SP1CoreOpts};
+
+    const STATE_PTR: u32 = 0x1000;
+    const KEYS_PTR: u32 = 0x2000;
+    const H_PTR: u32 = 0x3000;
+
+    fn ecall(code: SyscallCode, arg1: u32, arg2: u32) -> Vec<Instruction> {
+        vec![
+            Instruction::new(Opcode::ADD, 5, 0, code as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, arg1, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, arg2, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]
+    }
+
+    fn insert_block(program: &mut Program, ptr: u32, block: &[u8]) {
+        for (i, chunk) in block.chunks_exact(4).enumerate() {
+            let word = u32::from_le_bytes(chunk.try_into().unwrap());
+            program.memory_image.insert(ptr + 4 * i as u32, word);
+        }
+    }
+
+    fn read_block(runtime: &Runtime, ptr: u32) -> [u8; AES_STATE_BYTES] {
+        let words = (0..AES_STATE_WORDS as u32).map(|i| runtime.word(ptr + 4 * i));
+        words
+            .flat_map(|word| word.to_le_bytes())
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap()
+    }
+
+    /// The AES-128 key schedule.
+    fn round_keys(key: &[u8; 16]) -> Vec<[u8; AES_STATE_BYTES]> {
+        let mut words = key
+            .chunks_exact(4)
+            .map(|chunk| <[u8; 4]>::try_from(chunk).unwrap())
+            .collect::<Vec<_>>();
+        let mut rcon = 1u8;
+        for i in 4..44 {
+            let mut temp = words[i - 1];
+            if i % 4 == 0 {
+                temp.rotate_left(1);
+                temp = temp.map(|b| AES_SBOX[b as usize]);
+                temp[0] ^= rcon;
+                rcon = xtime(rcon);
+            }
+            words.push(core::array::from_fn(|j| words[i - 4][j] ^ temp[j]));
+        }
+        words
+            .chunks_exact(4)
+            .map(|chunk| chunk.concat().try_into().unwrap())
+            .collect()
+    }
+
+    /// The multiplication algorithm of the GCM specification.
+    fn gf128_mul_reference(x: &[u8; 16], y: &[u8; 16]) -> [u8; 16] {
+        let (x, mut v) = (u128::from_be_bytes(*x), u128::from_be_bytes(*y));
+        let mut z = 0u128;
+        for i in 0..128 {
+            if (x >> (127 - i)) & 1 == 1 {
+                z ^= v;
+            }
+            v = if v & 1 == 1 {
+                (v >> 1) ^ (0xe1 << 120)
+            } else {
+                v >> 1
+            };
+        }
+        z.to_be_bytes()
+    }
+
+    #[test]
+    fn test_aes128_encrypt() {
+        utils::setup_logger();
+        // The example vector of FIPS 197, appendix C.1.
+        let key: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let plaintext = hex::decode("00112233445566778899aabbccddeeff").unwrap();
+        let keys = round_keys(&key);
+
+        let mut instructions = vec![];
+        for round in 1..=10 {
+            let code = if round == 10 {
+                SyscallCode::AES_ENCRYPT_LAST_ROUND
+            } else {
+                SyscallCode::AES_ENCRYPT_ROUND
+            };
+            let key_ptr = KEYS_PTR + (AES_STATE_BYTES * round) as u32;
+            instructions.extend(ecall(code, STATE_PTR, key_ptr));
+        }
+        let mut program = Program::new(instructions, 0, 0);
+        let initial_state = plaintext
+            .iter()
+            .zip(keys[0].iter())
+            .map(|(p, k)| p ^ k)
+            .collect::<Vec<_>>();
+        insert_block(&mut program, STATE_PTR, &initial_state);
+        insert_block(&mut program, KEYS_PTR, &keys.concat());
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        assert_eq!(
+            hex::encode(read_block(&runtime, STATE_PTR)),
+            "69c4e0d86a7b0430d8cdb78070b4c55a"
+        );
+
+        run_test(program).unwrap();
+    }
+
+    #[test]
+    fn test_ghash_mul() {
+        utils::setup_logger();
+        let x: [u8; 16] = core::array::from_fn(|i| (i as u8).wrapping_mul(37) ^ 0xa5);
+        let h: [u8; 16] = core::array::from_fn(|i| (i as u8).wrapping_mul(91) ^ 0x3c);
+        assert_eq!(gf128_mul(&x, &h), gf128_mul_reference(&x, &h));
+
+        // Computes x * h * h.
+        let mut instructions = ecall(SyscallCode::GHASH_MUL, STATE_PTR, H_PTR);
+        instructions.extend(ecall(SyscallCode::GHASH_MUL, STATE_PTR, H_PTR));
+        let mut program = Program::new(instructions, 0, 0);
+        insert_block(&mut program, STATE_PTR, &x);
+        insert_block(&mut program, H_PTR, &h);
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let expected = gf128_mul_reference(&gf128_mul_reference(&x, &h), &h);
+        assert_eq!(read_block(&runtime, STATE_PTR), expected);
+
+        run_test(program).unwrap();
+    }
+}
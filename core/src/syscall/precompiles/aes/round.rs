@@ -0,0 +1,435 @@
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+
+use p3_air::{Air, BaseAir};
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+
+use crate::air::{MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::utils::AES_SBOX;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::runtime::{
+    ExecutionRecord, MemoryReadRecord, MemoryWriteRecord, Program, Syscall, SyscallCode,
+};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+
+/// The number of bytes of the AES state.
+pub const AES_STATE_BYTES: usize = 16;
+
+/// The number of words of the AES state and of a round key.
+pub const AES_STATE_WORDS: usize = AES_STATE_BYTES / WORD_SIZE;
+
+pub const NUM_AES_ROUND_COLS: usize = size_of::<AesRoundCols<u8>>();
+
+/// The index of the byte of the state that `ShiftRows` moves to index `i`, where the state is
+/// stored column by column.
+pub const fn shift_rows_source(i: usize) -> usize {
+    let (column, row) = (i / 4, i % 4);
+    4 * ((column + row) % 4) + row
+}
+
+/// Multiplies a byte by `x` in the AES field GF(2^8).
+pub const fn xtime(b: u8) -> u8 {
+    (b << 1) ^ if b & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+/// Applies an AES encryption round to the state: `SubBytes`, `ShiftRows`, `MixColumns` unless it
+/// is the last round, and `AddRoundKey`.
+pub fn aes_round(
+    state: &[u8; AES_STATE_BYTES],
+    round_key: &[u8; AES_STATE_BYTES],
+    is_last_round: bool,
+) -> [u8; AES_STATE_BYTES] {
+    let shifted: [u8; AES_STATE_BYTES] =
+        core::array::from_fn(|i| AES_SBOX[state[shift_rows_source(i)] as usize]);
+    core::array::from_fn(|i| {
+        let mixed = if is_last_round {
+            shifted[i]
+        } else {
+            let a = |k: usize| shifted[4 * (i / 4) + (i + k) % 4];
+            xtime(a(0)) ^ xtime(a(1)) ^ a(1) ^ a(2) ^ a(3)
+        };
+        mixed ^ round_key[i]
+    })
+}
+
+/// AES encryption round event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AesRoundEvent {
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub is_last_round: bool,
+    pub state_ptr: u32,
+    pub key_ptr: u32,
+    pub state_records: [MemoryWriteRecord; AES_STATE_WORDS],
+    pub key_records: [MemoryReadRecord; AES_STATE_WORDS],
+}
+
+/// A set of columns for an AES encryption round.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct AesRoundCols<T> {
+    pub shard: T,
+    pub channel: T,
+    pub clk: T,
+    pub state_ptr: T,
+    pub key_ptr: T,
+
+    pub state_memory: [MemoryWriteCols<T>; AES_STATE_WORDS],
+    pub key_memory: [MemoryReadCols<T>; AES_STATE_WORDS],
+
+    /// The substitution box applied to each byte of the input state.
+    pub sbox: [T; AES_STATE_BYTES],
+
+    /// The most significant bits of the shifted state, and the shifted state multiplied by `x`.
+    pub xtime_msb: [T; AES_STATE_BYTES],
+    pub xtime: [T; AES_STATE_BYTES],
+
+    /// The partial XORs of `MixColumns`, and its output.
+    pub mix_partial: [[T; 3]; AES_STATE_BYTES],
+    pub mixed: [T; AES_STATE_BYTES],
+
+    /// The output of `MixColumns`, or of `ShiftRows` in the last round, before adding the key.
+    pub selected: [T; AES_STATE_BYTES],
+
+    pub is_round: T,
+    pub is_last_round: T,
+    pub is_real: T,
+}
+
+/// The executor of the `AES_ENCRYPT_ROUND` and `AES_ENCRYPT_LAST_ROUND` syscalls, which overwrite
+/// a 16-byte state with the output of an AES round using the 16-byte round key at the second
+/// argument.
+pub struct AesRoundSyscall {
+    is_last_round: bool,
+}
+
+impl AesRoundSyscall {
+    pub const fn new(is_last_round: bool) -> Self {
+        Self { is_last_round }
+    }
+}
+
+impl Syscall for AesRoundSyscall {
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let start_clk = rt.clk;
+        let state_ptr = arg1;
+        if state_ptr % 4 != 0 {
+            panic!();
+        }
+        let key_ptr = arg2;
+        if key_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // We can read the state with slice_unsafe because we write the new state to it later.
+        let state = rt.slice_unsafe(state_ptr, AES_STATE_WORDS);
+        let (key_records, key) = rt.mr_slice(key_ptr, AES_STATE_WORDS);
+
+        // When we write the state, we want the clk to be incremented because the state and the key
+        // could be the same.
+        rt.clk += 1;
+
+        let to_bytes = |words: &[u32]| -> [u8; AES_STATE_BYTES] {
+            words
+                .iter()
+                .flat_map(|word| word.to_le_bytes())
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap()
+        };
+        let output = aes_round(&to_bytes(&state), &to_bytes(&key), self.is_last_round);
+        let output_words = output
+            .chunks_exact(WORD_SIZE)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let state_records = rt.mw_slice(state_ptr, &output_words);
+
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut().aes_round_events.push(AesRoundEvent {
+            shard,
+            channel,
+            clk: start_clk,
+            is_last_round: self.is_last_round,
+            state_ptr,
+            key_ptr,
+            state_records: state_records.try_into().unwrap(),
+            key_records: key_records.try_into().unwrap(),
+        });
+
+        None
+    }
+
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+}
+
+/// A chip for AES encryption rounds, sharing the rows of the `AES_ENCRYPT_ROUND` and
+/// `AES_ENCRYPT_LAST_ROUND` syscalls. The substitution box and the XORs are lookups into the byte
+/// table.
+#[derive(Default)]
+pub struct AesRoundChip;
+
+impl AesRoundChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for AesRoundChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "AesRound".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut rows = Vec::new();
+        let mut new_byte_lookup_events = Vec::new();
+
+        for event in input.aes_round_events.iter() {
+            let mut row = [F::zero(); NUM_AES_ROUND_COLS];
+            let cols: &mut AesRoundCols<F> = row.as_mut_slice().borrow_mut();
+
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.channel = F::from_canonical_u32(event.channel);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.state_ptr = F::from_canonical_u32(event.state_ptr);
+            cols.key_ptr = F::from_canonical_u32(event.key_ptr);
+            cols.is_round = F::from_bool(!event.is_last_round);
+            cols.is_last_round = F::from_bool(event.is_last_round);
+            cols.is_real = F::one();
+
+            for i in 0..AES_STATE_WORDS {
+                cols.state_memory[i].populate(
+                    event.channel,
+                    event.state_records[i],
+                    &mut new_byte_lookup_events,
+                );
+                cols.key_memory[i].populate(
+                    event.channel,
+                    event.key_records[i],
+                    &mut new_byte_lookup_events,
+                );
+            }
+
+            let state: [u8; AES_STATE_BYTES] = core::array::from_fn(|i| {
+                event.state_records[i / WORD_SIZE].prev_value.to_le_bytes()[i % WORD_SIZE]
+            });
+            let key: [u8; AES_STATE_BYTES] = core::array::from_fn(|i| {
+                event.key_records[i / WORD_SIZE].value.to_le_bytes()[i % WORD_SIZE]
+            });
+            let mut blu = |opcode: ByteOpcode, a: u8, b: u8, c: u8| {
+                new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent::new(
+                    event.shard,
+                    event.channel,
+                    opcode,
+                    a as u32,
+                    0,
+                    b as u32,
+                    c as u32,
+                ));
+            };
+
+            let sbox: [u8; AES_STATE_BYTES] = core::array::from_fn(|i| AES_SBOX[state[i] as usize]);
+            let shifted: [u8; AES_STATE_BYTES] =
+                core::array::from_fn(|i| sbox[shift_rows_source(i)]);
+            for i in 0..AES_STATE_BYTES {
+                blu(ByteOpcode::AesSbox, sbox[i], state[i], 0);
+                let msb = shifted[i] >> 7;
+                blu(ByteOpcode::MSB, msb, shifted[i], 0);
+                blu(
+                    ByteOpcode::XOR,
+                    xtime(shifted[i]),
+                    shifted[i] << 1,
+                    0x1b * msb,
+                );
+                cols.sbox[i] = F::from_canonical_u8(sbox[i]);
+                cols.xtime_msb[i] = F::from_canonical_u8(msb);
+                cols.xtime[i] = F::from_canonical_u8(xtime(shifted[i]));
+            }
+
+            let mut selected = [0u8; AES_STATE_BYTES];
+            for i in 0..AES_STATE_BYTES {
+                let a = |k: usize| shifted[4 * (i / 4) + (i + k) % 4];
+                let xt = |k: usize| xtime(a(k));
+                let partial_0 = xt(0) ^ xt(1);
+                let partial_1 = partial_0 ^ a(1);
+                let partial_2 = partial_1 ^ a(2);
+                let mixed = partial_2 ^ a(3);
+                blu(ByteOpcode::XOR, partial_0, xt(0), xt(1));
+                blu(ByteOpcode::XOR, partial_1, partial_0, a(1));
+                blu(ByteOpcode::XOR, partial_2, partial_1, a(2));
+                blu(ByteOpcode::XOR, mixed, partial_2, a(3));
+                cols.mix_partial[i] = [partial_0, partial_1, partial_2].map(F::from_canonical_u8);
+                cols.mixed[i] = F::from_canonical_u8(mixed);
+
+                selected[i] = if event.is_last_round {
+                    shifted[i]
+                } else {
+                    mixed
+                };
+                blu(ByteOpcode::XOR, selected[i] ^ key[i], selected[i], key[i]);
+                cols.selected[i] = F::from_canonical_u8(selected[i]);
+            }
+            debug_assert_eq!(
+                core::array::from_fn::<u8, AES_STATE_BYTES, _>(|i| selected[i] ^ key[i]),
+                aes_round(&state, &key, event.is_last_round)
+            );
+
+            rows.push(row);
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_AES_ROUND_COLS]);
+
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_AES_ROUND_COLS,
+        )
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.aes_round_events.is_empty()
+    }
+}
+
+impl<F> BaseAir<F> for AesRoundChip {
+    fn width(&self) -> usize {
+        NUM_AES_ROUND_COLS
+    }
+}
+
+impl<AB> Air<AB> for AesRoundChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &AesRoundCols<AB::Var> = (*local).borrow();
+
+        builder.assert_bool(local.is_round);
+        builder.assert_bool(local.is_last_round);
+        builder.assert_bool(local.is_real);
+        builder.assert_eq(local.is_real, local.is_round + local.is_last_round);
+
+        let input = |i: usize| local.state_memory[i / WORD_SIZE].prev_value().0[i % WORD_SIZE];
+        let output = |i: usize| local.state_memory[i / WORD_SIZE].value().0[i % WORD_SIZE];
+        let key = |i: usize| local.key_memory[i / WORD_SIZE].value().0[i % WORD_SIZE];
+        let shifted = |i: usize| local.sbox[shift_rows_source(i)];
+
+        let xor = ByteOpcode::XOR.as_field::<AB::F>();
+        for i in 0..AES_STATE_BYTES {
+            // SubBytes.
+            builder.send_byte(
+                ByteOpcode::AesSbox.as_field::<AB::F>(),
+                local.sbox[i],
+                input(i),
+                AB::F::zero(),
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+
+            // The product by x is the shifted byte without its high bit, XORed with 0x1b if the
+            // high bit is set.
+            builder.send_byte(
+                ByteOpcode::MSB.as_field::<AB::F>(),
+                local.xtime_msb[i],
+                shifted(i),
+                AB::F::zero(),
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+            builder.send_byte(
+                xor,
+                local.xtime[i],
+                shifted(i) * AB::F::from_canonical_u32(2)
+                    - local.xtime_msb[i] * AB::F::from_canonical_u32(256),
+                local.xtime_msb[i] * AB::F::from_canonical_u32(0x1b),
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+        }
+
+        for i in 0..AES_STATE_BYTES {
+            // MixColumns computes 2 * a_0 + 3 * a_1 + a_2 + a_3 over the column of each byte.
+            let index = |k: usize| 4 * (i / 4) + (i + k) % 4;
+            let [partial_0, partial_1, partial_2] = local.mix_partial[i];
+            for (a, b, c) in [
+                (partial_0, local.xtime[index(0)], local.xtime[index(1)]),
+                (partial_1, partial_0, shifted(index(1))),
+                (partial_2, partial_1, shifted(index(2))),
+                (local.mixed[i], partial_2, shifted(index(3))),
+            ] {
+                builder.send_byte(xor, a, b, c, local.shard, local.channel, local.is_real);
+            }
+
+            // The last round skips MixColumns.
+            builder.assert_eq(
+                local.selected[i],
+                local.mixed[i] + local.is_last_round * (shifted(i) - local.mixed[i]),
+            );
+
+            // AddRoundKey.
+            builder.send_byte(
+                xor,
+                output(i),
+                local.selected[i],
+                key(i),
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+        }
+
+        // Read the key and write the state at the next clk, since they could be the same.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.key_ptr,
+            &local.key_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::from_canonical_u32(1),
+            local.state_ptr,
+            &local.state_memory,
+            local.is_real,
+        );
+
+        let syscall_id = AB::Expr::from_canonical_u32(SyscallCode::AES_ENCRYPT_ROUND.syscall_id())
+            * local.is_round
+            + AB::Expr::from_canonical_u32(SyscallCode::AES_ENCRYPT_LAST_ROUND.syscall_id())
+                * local.is_last_round;
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            syscall_id,
+            local.state_ptr,
+            local.key_ptr,
+            local.is_real,
+        );
+    }
+}
@@ -0,0 +1,528 @@
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+use std::marker::PhantomData;
+
+use generic_array::GenericArray;
+use itertools::izip;
+use num::{BigUint, Integer, Zero};
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use typenum::Unsigned;
+
+use super::{BigUintParameters, BigUintWidth};
+use crate::air::{BaseAirBuilder, MachineAir, Polynomial, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{value_as_limbs, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::params::{Limbs, NumLimbs, NumWords, NB_BITS_PER_LIMB};
+use crate::operations::field::util::compute_root_quotient_and_shift;
+use crate::runtime::{ExecutionError, ExecutionRecord, Opcode, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::{
+    bytes_to_words_le_vec, limbs_from_access, limbs_from_prev_access, pad_rows,
+    words_to_bytes_le_vec,
+};
+
+pub const fn num_biguint_mulmod_cols<P: NumLimbs + NumWords>() -> usize {
+    size_of::<BigUintMulModCols<u8, P>>()
+}
+
+/// Big integer modular multiplication event, computing `x = (x * y) % modulus`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BigUintMulModEvent {
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub x: Vec<u32>,
+    pub y_ptr: u32,
+    pub y: Vec<u32>,
+    pub modulus: Vec<u32>,
+    pub x_memory_records: Vec<MemoryWriteRecord>,
+    pub y_memory_records: Vec<MemoryReadRecord>,
+    pub modulus_memory_records: Vec<MemoryReadRecord>,
+}
+
+/// A set of columns for the big integer modular multiplication.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct BigUintMulModCols<T, P: NumLimbs + NumWords> {
+    pub is_real: T,
+    pub shard: T,
+    pub channel: T,
+    pub clk: T,
+
+    /// The pointer to the first input, which is overwritten with the result.
+    pub x_ptr: T,
+
+    /// The pointer to the second input, which contains the y value followed by the modulus.
+    pub y_ptr: T,
+
+    pub x_memory: GenericArray<MemoryWriteCols<T>, P::WordsFieldElement>,
+    pub y_memory: GenericArray<MemoryReadCols<T>, P::WordsFieldElement>,
+    pub modulus_memory: GenericArray<MemoryReadCols<T>, P::WordsFieldElement>,
+
+    /// The limbs of `(x * y) % modulus` and `(x * y) / modulus`.
+    pub result: Limbs<T, P::Limbs>,
+    pub quotient: Limbs<T, P::Limbs>,
+
+    /// The bytes of the shifted carry witness of `x * y - quotient * modulus - result`.
+    pub witness_low: Limbs<T, P::Witness>,
+    pub witness_mid: Limbs<T, P::Witness>,
+    pub witness_high: Limbs<T, P::Witness>,
+
+    /// Flags marking the most significant byte in which the result is smaller than the modulus.
+    pub result_lt_flags: Limbs<T, P::Limbs>,
+    pub result_comparison_byte: T,
+    pub modulus_comparison_byte: T,
+}
+
+impl<F: PrimeField32, P: BigUintParameters> BigUintMulModCols<F, P> {
+    fn populate_mulmod(
+        &mut self,
+        record: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        x: &BigUint,
+        y: &BigUint,
+        modulus: &BigUint,
+    ) {
+        // Padding rows have a zero modulus, for which the result and quotient are zero as well.
+        let (quotient, result) = if modulus.is_zero() {
+            (BigUint::zero(), BigUint::zero())
+        } else {
+            (x * y).div_rem(modulus)
+        };
+
+        let p_x = to_limbs_polynomial::<F, P>(x);
+        let p_y = to_limbs_polynomial::<F, P>(y);
+        let p_modulus = to_limbs_polynomial::<F, P>(modulus);
+        let p_result = to_limbs_polynomial::<F, P>(&result);
+        let p_quotient = to_limbs_polynomial::<F, P>(&quotient);
+        let p_vanishing = &(&p_x * &p_y) - &p_result - &p_quotient * &p_modulus;
+
+        let p_witness = compute_root_quotient_and_shift(
+            &p_vanishing,
+            P::WITNESS_OFFSET,
+            NB_BITS_PER_LIMB as u32,
+            P::Witness::USIZE,
+        );
+        let witness_byte = |shift: u32| {
+            p_witness
+                .iter()
+                .map(|w| F::from_canonical_u8((w.as_canonical_u32() >> shift) as u8))
+                .collect::<Vec<_>>()
+        };
+
+        self.result = p_result.into();
+        self.quotient = p_quotient.into();
+        self.witness_low = Limbs(witness_byte(0).try_into().unwrap());
+        self.witness_mid = Limbs(witness_byte(8).try_into().unwrap());
+        self.witness_high = Limbs(witness_byte(16).try_into().unwrap());
+
+        record.add_u8_range_checks_field(shard, channel, &self.result.0);
+        record.add_u8_range_checks_field(shard, channel, &self.quotient.0);
+        record.add_u8_range_checks_field(shard, channel, &self.witness_low.0);
+        record.add_u8_range_checks_field(shard, channel, &self.witness_mid.0);
+        record.add_u8_range_checks_field(shard, channel, &self.witness_high.0);
+        let scale = F::from_canonical_u32(1 << (NB_BITS_PER_LIMB - witness_high_bits::<P>()));
+        let witness_high_scaled = self.witness_high.0.iter().map(|&w| w * scale);
+        record.add_u8_range_checks_field(shard, channel, &witness_high_scaled.collect::<Vec<_>>());
+
+        // Padding rows have no flag set, since their result is equal to their zero modulus.
+        self.result_lt_flags = Limbs::default();
+        self.result_comparison_byte = F::zero();
+        self.modulus_comparison_byte = F::zero();
+        let mut result_bytes = result.to_bytes_le();
+        result_bytes.resize(P::Limbs::USIZE, 0u8);
+        let mut modulus_bytes = modulus.to_bytes_le();
+        modulus_bytes.resize(P::Limbs::USIZE, 0u8);
+        for (byte, modulus_byte, flag) in izip!(
+            result_bytes.iter().rev(),
+            modulus_bytes.iter().rev(),
+            self.result_lt_flags.0.iter_mut().rev()
+        ) {
+            if byte < modulus_byte {
+                *flag = F::one();
+                self.result_comparison_byte = F::from_canonical_u8(*byte);
+                self.modulus_comparison_byte = F::from_canonical_u8(*modulus_byte);
+                record.add_byte_lookup_event(ByteLookupEvent {
+                    opcode: ByteOpcode::LTU,
+                    shard,
+                    channel,
+                    a1: 1,
+                    a2: 0,
+                    b: *byte as u32,
+                    c: *modulus_byte as u32,
+                });
+                break;
+            }
+        }
+    }
+}
+
+/// The number of bits of the high byte of the carry witness, which keeps the shifted witness below
+/// `2 * WITNESS_OFFSET`.
+fn witness_high_bits<P: BigUintParameters>() -> usize {
+    (2 * P::WITNESS_OFFSET).trailing_zeros() as usize - 2 * NB_BITS_PER_LIMB
+}
+
+fn to_limbs_polynomial<F: PrimeField32, P: NumLimbs>(x: &BigUint) -> Polynomial<F> {
+    let mut bytes = x.to_bytes_le();
+    bytes.resize(P::Limbs::USIZE, 0u8);
+    Polynomial::from_coefficients(
+        &bytes
+            .into_iter()
+            .map(F::from_canonical_u8)
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// A chip for the modular multiplication of `P`-sized unsigned integers by a modulus read from
+/// memory, used for windowed modular exponentiation and wide field arithmetic in the guest.
+#[derive(Default)]
+pub struct BigUintMulModChip<P> {
+    _marker: PhantomData<P>,
+}
+
+impl<P: BigUintParameters> BigUintMulModChip<P> {
+    pub const fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    fn syscall_code() -> SyscallCode {
+        match P::WIDTH {
+            BigUintWidth::Uint2048 => SyscallCode::UINT2048_MULMOD,
+            BigUintWidth::Uint4096 => SyscallCode::UINT4096_MULMOD,
+            BigUintWidth::Uint384 => SyscallCode::UINT384_MULMOD,
+            BigUintWidth::Uint512 => SyscallCode::UINT512_MULMOD,
+        }
+    }
+}
+
+impl<P: BigUintParameters> Syscall for BigUintMulModChip<P> {
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let start_clk = rt.clk;
+        let x_ptr = arg1;
+        let y_ptr = arg2;
+        for ptr in [x_ptr, y_ptr] {
+            if ptr % 4 != 0 {
+                rt.fail(ExecutionError::InvalidMemoryAccess(Opcode::ECALL, ptr));
+                return None;
+            }
+        }
+
+        let num_words = <P as NumWords>::WordsFieldElement::USIZE;
+
+        // We can read x with slice_unsafe because we write the computed result to x later.
+        let x = rt.slice_unsafe(x_ptr, num_words);
+        let (y_memory_records, y) = rt.mr_slice(y_ptr, num_words);
+
+        // The modulus is stored after the y value.
+        let modulus_ptr = y_ptr + (num_words * WORD_SIZE) as u32;
+        let (modulus_memory_records, modulus) = rt.mr_slice(modulus_ptr, num_words);
+
+        // When we write to x, we want the clk to be incremented because x and y could be the same.
+        rt.clk += 1;
+
+        let a = BigUint::from_bytes_le(&words_to_bytes_le_vec(&x));
+        let b = BigUint::from_bytes_le(&words_to_bytes_le_vec(&y));
+        let m = BigUint::from_bytes_le(&words_to_bytes_le_vec(&modulus));
+        if m.is_zero() {
+            rt.fail(ExecutionError::InvalidSyscallArgument(
+                Self::syscall_code(),
+                "the modulus must be nonzero",
+            ));
+            return None;
+        }
+        // This bounds the quotient by 2^bits, so that it fits in the quotient columns.
+        if a >= m && b >= m {
+            rt.fail(ExecutionError::InvalidSyscallArgument(
+                Self::syscall_code(),
+                "one of the operands must be reduced modulo the modulus",
+            ));
+            return None;
+        }
+
+        let mut result_bytes = ((a * b) % m).to_bytes_le();
+        result_bytes.resize(num_words * WORD_SIZE, 0u8);
+        let result = bytes_to_words_le_vec(&result_bytes);
+
+        let x_memory_records = rt.mw_slice(x_ptr, &result);
+
+        let event = BigUintMulModEvent {
+            shard: rt.current_shard(),
+            channel: rt.current_channel(),
+            clk: start_clk,
+            x_ptr,
+            x,
+            y_ptr,
+            y,
+            modulus,
+            x_memory_records,
+            y_memory_records,
+            modulus_memory_records,
+        };
+        match P::WIDTH {
+            BigUintWidth::Uint2048 => rt.record_mut().uint2048_mulmod_events.push(event),
+            BigUintWidth::Uint4096 => rt.record_mut().uint4096_mulmod_events.push(event),
+            BigUintWidth::Uint384 => rt.record_mut().uint384_mulmod_events.push(event),
+            BigUintWidth::Uint512 => rt.record_mut().uint512_mulmod_events.push(event),
+        }
+
+        None
+    }
+
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+}
+
+impl<F: PrimeField32, P: BigUintParameters> MachineAir<F> for BigUintMulModChip<P> {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        match P::WIDTH {
+            BigUintWidth::Uint2048 => "Uint2048MulMod".to_string(),
+            BigUintWidth::Uint4096 => "Uint4096MulMod".to_string(),
+            BigUintWidth::Uint384 => "Uint384MulMod".to_string(),
+            BigUintWidth::Uint512 => "Uint512MulMod".to_string(),
+        }
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let events = match P::WIDTH {
+            BigUintWidth::Uint2048 => &input.uint2048_mulmod_events,
+            BigUintWidth::Uint4096 => &input.uint4096_mulmod_events,
+            BigUintWidth::Uint384 => &input.uint384_mulmod_events,
+            BigUintWidth::Uint512 => &input.uint512_mulmod_events,
+        };
+
+        let mut rows = Vec::new();
+        let mut new_byte_lookup_events = Vec::new();
+
+        for event in events.iter() {
+            let mut row = vec![F::zero(); num_biguint_mulmod_cols::<P>()];
+            let cols: &mut BigUintMulModCols<F, P> = row.as_mut_slice().borrow_mut();
+
+            let x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&event.x));
+            let y = BigUint::from_bytes_le(&words_to_bytes_le_vec(&event.y));
+            let modulus = BigUint::from_bytes_le(&words_to_bytes_le_vec(&event.modulus));
+
+            cols.is_real = F::one();
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.channel = F::from_canonical_u32(event.channel);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+            cols.y_ptr = F::from_canonical_u32(event.y_ptr);
+
+            for i in 0..cols.x_memory.len() {
+                cols.x_memory[i].populate(
+                    event.channel,
+                    event.x_memory_records[i],
+                    &mut new_byte_lookup_events,
+                );
+                cols.y_memory[i].populate(
+                    event.channel,
+                    event.y_memory_records[i],
+                    &mut new_byte_lookup_events,
+                );
+                cols.modulus_memory[i].populate(
+                    event.channel,
+                    event.modulus_memory_records[i],
+                    &mut new_byte_lookup_events,
+                );
+            }
+
+            cols.populate_mulmod(
+                &mut new_byte_lookup_events,
+                event.shard,
+                event.channel,
+                &x,
+                &y,
+                &modulus,
+            );
+
+            rows.push(row);
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row = vec![F::zero(); num_biguint_mulmod_cols::<P>()];
+            let cols: &mut BigUintMulModCols<F, P> = row.as_mut_slice().borrow_mut();
+            let zero = BigUint::zero();
+            cols.populate_mulmod(&mut vec![], 0, 0, &zero, &zero, &zero);
+            row
+        });
+
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            num_biguint_mulmod_cols::<P>(),
+        )
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        match P::WIDTH {
+            BigUintWidth::Uint2048 => !shard.uint2048_mulmod_events.is_empty(),
+            BigUintWidth::Uint4096 => !shard.uint4096_mulmod_events.is_empty(),
+            BigUintWidth::Uint384 => !shard.uint384_mulmod_events.is_empty(),
+            BigUintWidth::Uint512 => !shard.uint512_mulmod_events.is_empty(),
+        }
+    }
+}
+
+impl<F, P: BigUintParameters> BaseAir<F> for BigUintMulModChip<P> {
+    fn width(&self) -> usize {
+        num_biguint_mulmod_cols::<P>()
+    }
+}
+
+impl<AB, P: BigUintParameters> Air<AB> for BigUintMulModChip<P>
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <P as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &BigUintMulModCols<AB::Var, P> = (*local).borrow();
+
+        builder.assert_bool(local.is_real);
+
+        // The value of x is stored in the "prev_value" of x_memory, since we write to it later.
+        let x: Limbs<AB::Var, <P as NumLimbs>::Limbs> = limbs_from_prev_access(&local.x_memory);
+        let y: Limbs<AB::Var, <P as NumLimbs>::Limbs> = limbs_from_access(&local.y_memory);
+        let modulus: Limbs<AB::Var, <P as NumLimbs>::Limbs> =
+            limbs_from_access(&local.modulus_memory);
+
+        let p_x: Polynomial<AB::Expr> = x.into();
+        let p_y: Polynomial<AB::Expr> = y.into();
+        let p_modulus: Polynomial<AB::Expr> = modulus.into();
+        let p_result: Polynomial<AB::Expr> = local.result.into();
+        let p_quotient: Polynomial<AB::Expr> = local.quotient.into();
+        let p_vanishing = &(&p_x * &p_y) - &p_result - &p_quotient * &p_modulus;
+
+        // Reconstruct the witness from its bytes and shift it back.
+        let limb: AB::Expr = AB::F::from_canonical_u32(1 << NB_BITS_PER_LIMB).into();
+        let p_witness_low: Polynomial<AB::Expr> = local.witness_low.0.iter().into();
+        let p_witness_mid: Polynomial<AB::Expr> = local.witness_mid.0.iter().into();
+        let p_witness_high: Polynomial<AB::Expr> = local.witness_high.0.iter().into();
+        let p_witness_shifted = p_witness_low
+            + &(p_witness_mid * limb.clone())
+            + &(p_witness_high * (limb.clone() * limb.clone()));
+        let offset: AB::Expr = AB::F::from_canonical_u32(P::WITNESS_OFFSET as u32).into();
+        let len = p_witness_shifted.coefficients().len();
+        let p_witness = p_witness_shifted - Polynomial::new(vec![offset; len]);
+
+        // The vanishing polynomial has a root at 2^NB_BITS_PER_LIMB, with the witness as quotient.
+        let root_monomial = Polynomial::new(vec![-limb, AB::F::one().into()]);
+        let constraints = p_vanishing - &(p_witness * root_monomial);
+        for constr in constraints.as_coefficients() {
+            builder.assert_zero(constr);
+        }
+
+        for limbs in [
+            &local.result.0[..],
+            &local.quotient.0[..],
+            &local.witness_low.0[..],
+            &local.witness_mid.0[..],
+            &local.witness_high.0[..],
+        ] {
+            builder.slice_range_check_u8(limbs, local.shard, local.channel, local.is_real);
+        }
+
+        // Bound the shifted witness by `2 * WITNESS_OFFSET`, so that each carry is at most the
+        // offset in absolute value and the carry equation above cannot wrap around the field.
+        let scale = AB::F::from_canonical_u32(1 << (NB_BITS_PER_LIMB - witness_high_bits::<P>()));
+        let witness_high_scaled = local
+            .witness_high
+            .0
+            .iter()
+            .map(|&w| w * scale)
+            .collect::<Vec<AB::Expr>>();
+        builder.slice_range_check_u8(
+            &witness_high_scaled,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Assert that the result is smaller than the modulus. The flag marks the most significant
+        // byte in which they differ, which makes the result the unique remainder of x * y.
+        let mut sum_flags = AB::Expr::zero();
+        let mut is_inequality_visited = AB::Expr::zero();
+        let mut result_comparison_byte = AB::Expr::zero();
+        let mut modulus_comparison_byte = AB::Expr::zero();
+        for (&byte, &modulus_byte, &flag) in izip!(
+            local.result.0.iter().rev(),
+            modulus.0.iter().rev(),
+            local.result_lt_flags.0.iter().rev()
+        ) {
+            builder.assert_bool(flag);
+            sum_flags += flag.into();
+            is_inequality_visited += flag.into();
+            result_comparison_byte += byte * flag;
+            modulus_comparison_byte += modulus_byte * flag;
+            builder
+                .when_not(is_inequality_visited.clone())
+                .assert_eq(byte, modulus_byte);
+        }
+        builder.assert_eq(sum_flags, local.is_real);
+        builder.assert_eq(local.result_comparison_byte, result_comparison_byte);
+        builder.assert_eq(local.modulus_comparison_byte, modulus_comparison_byte);
+        builder.send_byte(
+            ByteOpcode::LTU.as_field::<AB::F>(),
+            AB::F::one(),
+            local.result_comparison_byte,
+            local.modulus_comparison_byte,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Assert that the correct result is being written to x_memory.
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.result, value_as_limbs(&local.x_memory));
+
+        // y and the modulus are read contiguously from y_ptr.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.y_ptr,
+            &[local.y_memory.to_vec(), local.modulus_memory.to_vec()].concat(),
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::from_canonical_u32(1), // We write x at +1 since x, y could be the same.
+            local.x_ptr,
+            &local.x_memory,
+            local.is_real,
+        );
+
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            AB::F::from_canonical_u32(Self::syscall_code().syscall_id()),
+            local.x_ptr,
+            local.y_ptr,
+            local.is_real,
+        );
+    }
+}
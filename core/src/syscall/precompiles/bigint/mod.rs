@@ -0,0 +1,184 @@
+mod air;
+
+pub use air::*;
+
+use serde::{Deserialize, Serialize};
+use typenum::{U1022, U126, U256, U48, U510, U512, U64, U94};
+
+use crate::operations::field::params::{NumLimbs, NumWords};
+
+/// The operand widths with a modular multiplication precompile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BigUintWidth {
+    Uint2048,
+    Uint4096,
+    Uint384,
+    Uint512,
+}
+
+/// The parameters of a [BigUintMulModChip] of a given operand width.
+///
+/// The 2048-bit and 4096-bit operands are too wide for the two-byte witness of `FieldOpCols`, so the
+/// carries of the multiplication are decomposed into three bytes and shifted by `WITNESS_OFFSET`.
+pub trait BigUintParameters: NumLimbs + NumWords + Copy + Send + Sync + 'static {
+    const WIDTH: BigUintWidth;
+
+    /// A power-of-two bound on the absolute value of the coefficients of the carry witness. The
+    /// shifted witness is constrained below `2 * WITNESS_OFFSET`, which keeps every term of the
+    /// carry equation far below the field modulus.
+    const WITNESS_OFFSET: usize;
+}
+
+/// The parameters of 2048-bit unsigned integers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Uint2048;
+
+impl NumLimbs for Uint2048 {
+    type Limbs = U256;
+    type Witness = U510;
+}
+
+impl BigUintParameters for Uint2048 {
+    const WIDTH: BigUintWidth = BigUintWidth::Uint2048;
+    const WITNESS_OFFSET: usize = 1usize << 17;
+}
+
+/// The parameters of 4096-bit unsigned integers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Uint4096;
+
+impl NumLimbs for Uint4096 {
+    type Limbs = U512;
+    type Witness = U1022;
+}
+
+impl BigUintParameters for Uint4096 {
+    const WIDTH: BigUintWidth = BigUintWidth::Uint4096;
+    const WITNESS_OFFSET: usize = 1usize << 18;
+}
+
+/// The parameters of 384-bit unsigned integers, wide enough for the BLS12-381 base field.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Uint384;
+
+impl NumLimbs for Uint384 {
+    type Limbs = U48;
+    type Witness = U94;
+}
+
+impl BigUintParameters for Uint384 {
+    const WIDTH: BigUintWidth = BigUintWidth::Uint384;
+    const WITNESS_OFFSET: usize = 1usize << 15;
+}
+
+/// The parameters of 512-bit unsigned integers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Uint512;
+
+impl NumLimbs for Uint512 {
+    type Limbs = U64;
+    type Witness = U126;
+}
+
+impl BigUintParameters for Uint512 {
+    const WIDTH: BigUintWidth = BigUintWidth::Uint512;
+    const WITNESS_OFFSET: usize = 1usize << 16;
+}
+
+#[cfg(test)]
+mod tests {
+    use num::{BigUint, One};
+    use typenum::Unsigned;
+
+    use super::{BigUintParameters, Uint2048, Uint384, Uint4096, Uint512};
+    use crate::runtime::{ExecutionError, Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::{self, bytes_to_words_le_vec, run_test, words_to_bytes_le_vec, SP1CoreOpts};
+
+    const X_PTR: u32 = 0x1000;
+    const Y_PTR: u32 = 0x4000;
+
+    fn to_words<P: BigUintParameters>(x: &BigUint) -> Vec<u32> {
+        let mut bytes = x.to_bytes_le();
+        bytes.resize(4 * P::WordsFieldElement::USIZE, 0u8);
+        bytes_to_words_le_vec(&bytes)
+    }
+
+    /// Computes `x^2 * y mod modulus` with two multiplications.
+    fn test_mulmod<P: BigUintParameters>(code: SyscallCode) {
+        utils::setup_logger();
+        let bits = 32 * P::WordsFieldElement::USIZE;
+        let modulus = (BigUint::one() << bits) - 189u32;
+        let x = &modulus - 2u32;
+        let y = (BigUint::one() << (bits - 3)) + 12345u32;
+
+        let mut instructions = vec![];
+        for y_ptr in [X_PTR, Y_PTR] {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 5, 0, code as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, X_PTR, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, y_ptr, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        let mut program = Program::new(instructions, 0, 0);
+        let mut y_words = to_words::<P>(&y);
+        y_words.extend(to_words::<P>(&modulus));
+        for (ptr, words) in [(X_PTR, to_words::<P>(&x)), (Y_PTR, y_words)] {
+            for (i, word) in words.into_iter().enumerate() {
+                program.memory_image.insert(ptr + 4 * i as u32, word);
+            }
+        }
+        // Squaring x reads the modulus right after x.
+        for (i, word) in to_words::<P>(&modulus).into_iter().enumerate() {
+            let ptr = X_PTR + 4 * (P::WordsFieldElement::USIZE + i) as u32;
+            program.memory_image.insert(ptr, word);
+        }
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let words = (0..P::WordsFieldElement::USIZE as u32)
+            .map(|i| runtime.word(X_PTR + 4 * i))
+            .collect::<Vec<_>>();
+        let result = BigUint::from_bytes_le(&words_to_bytes_le_vec(&words));
+        assert_eq!(result, (&x * &x * &y) % &modulus);
+
+        run_test(program).unwrap();
+    }
+
+    #[test]
+    fn test_mulmod_zero_modulus() {
+        let code = SyscallCode::UINT384_MULMOD;
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, code as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, X_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, Y_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let program = Program::new(instructions, 0, 0);
+        let mut runtime = Runtime::new(program, SP1CoreOpts::default());
+        match runtime.run() {
+            Err(ExecutionError::InvalidSyscallArgument(c, _)) if c == code => {}
+            res => panic!("expected an invalid syscall argument error, got {:?}", res),
+        }
+    }
+
+    #[test]
+    fn test_uint2048_mulmod() {
+        test_mulmod::<Uint2048>(SyscallCode::UINT2048_MULMOD);
+    }
+
+    #[test]
+    fn test_uint4096_mulmod() {
+        test_mulmod::<Uint4096>(SyscallCode::UINT4096_MULMOD);
+    }
+
+    #[test]
+    fn test_uint384_mulmod() {
+        test_mulmod::<Uint384>(SyscallCode::UINT384_MULMOD);
+    }
+
+    #[test]
+    fn test_uint512_mulmod() {
+        test_mulmod::<Uint512>(SyscallCode::UINT512_MULMOD);
+    }
+}
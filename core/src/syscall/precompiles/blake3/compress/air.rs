@@ -35,8 +35,8 @@ where
 
         self.constrain_g_operation(builder, local);
 
-        // TODO: constraint ecall_receive column.
-        // TODO: constraint clk column to increment by 1 within same invocation of syscall.
+        self.constrain_invocation(builder, local, next);
+
         builder.receive_syscall(
             local.shard,
             local.channel,
@@ -116,6 +116,63 @@ impl Blake3CompressInnerChip {
         }
     }
 
+    /// Constrains the rows of each invocation of the syscall to be contiguous, starting with the
+    /// row receiving the syscall and ending with the last operation of the last round.
+    fn constrain_invocation<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Blake3CompressInnerCols<AB::Var>,
+        next: &Blake3CompressInnerCols<AB::Var>,
+    ) {
+        // The syscall is received on the first operation of the first round.
+        builder.assert_eq(
+            local.ecall_receive,
+            local.is_real * local.is_round_index_n[0] * local.is_operation_index_n[0],
+        );
+
+        // The trace starts with an invocation, and padding rows are only at the end.
+        builder
+            .when_first_row()
+            .assert_eq(local.ecall_receive, local.is_real);
+        builder
+            .when_transition()
+            .when_not(local.is_real)
+            .assert_zero(next.is_real);
+
+        // Within an invocation, the clock is incremented on every call of `g`, and the shard,
+        // channel and pointers are the same on every row.
+        builder
+            .when_transition()
+            .when(next.is_real)
+            .when_not(next.ecall_receive)
+            .assert_eq(local.clk + AB::F::one(), next.clk);
+        for (local_value, next_value) in [
+            (local.shard, next.shard),
+            (local.channel, next.channel),
+            (local.state_ptr, next.state_ptr),
+            (local.message_ptr, next.message_ptr),
+        ] {
+            builder
+                .when_transition()
+                .when(next.is_real)
+                .when_not(next.ecall_receive)
+                .assert_eq(local_value, next_value);
+        }
+
+        // The last real row is the last operation of the last round.
+        for flag in [
+            local.is_round_index_n[ROUND_COUNT - 1],
+            local.is_operation_index_n[OPERATION_COUNT - 1],
+        ] {
+            builder
+                .when_transition()
+                .when(local.is_real)
+                .when_not(next.is_real)
+                .assert_one(flag);
+            builder.when_last_row().when(local.is_real).assert_one(flag);
+        }
+    }
+
     /// Constrain the memory access for the state and the message.
     fn constrain_memory<AB: SP1AirBuilder>(
         &self,
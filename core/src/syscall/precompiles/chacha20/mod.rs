@@ -0,0 +1,3 @@
+mod permute;
+
+pub use permute::*;
@@ -0,0 +1,251 @@
+use core::borrow::Borrow;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::AbstractField;
+use p3_matrix::Matrix;
+
+use super::columns::{ChaCha20PermuteCols, NUM_CHACHA20_PERMUTE_COLS};
+use super::quarter_round::QuarterRoundOperation;
+use super::{
+    ChaCha20PermuteChip, DOUBLE_ROUND_COUNT, NUM_STATE_WORDS_PER_CALL, OPERATION_COUNT,
+    QUARTER_ROUND_INDEX,
+};
+use crate::air::{BaseAirBuilder, SP1AirBuilder, WORD_SIZE};
+use crate::runtime::SyscallCode;
+
+impl<F> BaseAir<F> for ChaCha20PermuteChip {
+    fn width(&self) -> usize {
+        NUM_CHACHA20_PERMUTE_COLS
+    }
+}
+
+impl<AB> Air<AB> for ChaCha20PermuteChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &ChaCha20PermuteCols<AB::Var> = (*local).borrow();
+        let next: &ChaCha20PermuteCols<AB::Var> = (*next).borrow();
+
+        self.constrain_control_flow_flags(builder, local, next);
+
+        self.constrain_memory(builder, local);
+
+        self.constrain_quarter_round(builder, local);
+
+        self.constrain_invocation(builder, local, next);
+
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            AB::F::from_canonical_u32(SyscallCode::CHACHA20_PERMUTE.syscall_id()),
+            local.state_ptr,
+            AB::Expr::zero(),
+            local.ecall_receive,
+        );
+    }
+}
+
+impl ChaCha20PermuteChip {
+    /// Constrains the given index is correct for the given selector. The `selector` is an
+    /// `n`-dimensional boolean array whose `i`-th element is true if and only if the index is `i`.
+    fn constrain_index_selector<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        selector: &[AB::Var],
+        index: AB::Var,
+        is_real: AB::Var,
+    ) {
+        let mut acc: AB::Expr = AB::F::zero().into();
+        for i in 0..selector.len() {
+            acc += selector[i].into();
+            builder.assert_bool(selector[i])
+        }
+        builder
+            .when(is_real)
+            .assert_eq(acc, AB::F::from_canonical_usize(1));
+        for i in 0..selector.len() {
+            builder
+                .when(selector[i])
+                .assert_eq(index, AB::F::from_canonical_usize(i));
+        }
+    }
+
+    /// Constrains the control flow flags such as the operation index and the double round index.
+    fn constrain_control_flow_flags<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &ChaCha20PermuteCols<AB::Var>,
+        next: &ChaCha20PermuteCols<AB::Var>,
+    ) {
+        self.constrain_index_selector(
+            builder,
+            &local.is_operation_index_n,
+            local.operation_index,
+            local.is_real,
+        );
+        self.constrain_index_selector(
+            builder,
+            &local.is_double_round_index_n,
+            local.double_round_index,
+            local.is_real,
+        );
+
+        // If this is the i-th operation, then the next row should be the (i+1)-th operation.
+        for i in 0..OPERATION_COUNT {
+            builder.when_transition().when(next.is_real).assert_eq(
+                local.is_operation_index_n[i],
+                next.is_operation_index_n[(i + 1) % OPERATION_COUNT],
+            );
+        }
+
+        // If this is the last operation, the double round index should be incremented. Otherwise,
+        // the double round index should remain the same.
+        for i in 0..OPERATION_COUNT {
+            if i + 1 < OPERATION_COUNT {
+                builder
+                    .when_transition()
+                    .when(local.is_operation_index_n[i])
+                    .assert_eq(local.double_round_index, next.double_round_index);
+            } else {
+                builder
+                    .when_transition()
+                    .when(local.is_operation_index_n[i])
+                    .when_not(local.is_double_round_index_n[DOUBLE_ROUND_COUNT - 1])
+                    .assert_eq(
+                        local.double_round_index + AB::F::from_canonical_u16(1),
+                        next.double_round_index,
+                    );
+
+                builder
+                    .when_transition()
+                    .when(local.is_operation_index_n[i])
+                    .when(local.is_double_round_index_n[DOUBLE_ROUND_COUNT - 1])
+                    .assert_zero(next.double_round_index);
+            }
+        }
+    }
+
+    /// Constrains the rows of each invocation of the syscall to be contiguous, starting with the
+    /// row receiving the syscall and ending with the last operation of the last double round.
+    fn constrain_invocation<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &ChaCha20PermuteCols<AB::Var>,
+        next: &ChaCha20PermuteCols<AB::Var>,
+    ) {
+        // The syscall is received on the first operation of the first double round.
+        builder.assert_eq(
+            local.ecall_receive,
+            local.is_real * local.is_double_round_index_n[0] * local.is_operation_index_n[0],
+        );
+
+        // The trace starts with an invocation, and padding rows are only at the end.
+        builder
+            .when_first_row()
+            .assert_eq(local.ecall_receive, local.is_real);
+        builder
+            .when_transition()
+            .when_not(local.is_real)
+            .assert_zero(next.is_real);
+
+        // Within an invocation, the clock is incremented on every quarter round, and the shard,
+        // channel and state pointer are the same on every row.
+        builder
+            .when_transition()
+            .when(next.is_real)
+            .when_not(next.ecall_receive)
+            .assert_eq(local.clk + AB::F::one(), next.clk);
+        for (local_value, next_value) in [
+            (local.shard, next.shard),
+            (local.channel, next.channel),
+            (local.state_ptr, next.state_ptr),
+        ] {
+            builder
+                .when_transition()
+                .when(next.is_real)
+                .when_not(next.ecall_receive)
+                .assert_eq(local_value, next_value);
+        }
+
+        // The last real row is the last operation of the last double round.
+        for flag in [
+            local.is_double_round_index_n[DOUBLE_ROUND_COUNT - 1],
+            local.is_operation_index_n[OPERATION_COUNT - 1],
+        ] {
+            builder
+                .when_transition()
+                .when(local.is_real)
+                .when_not(next.is_real)
+                .assert_one(flag);
+            builder.when_last_row().when(local.is_real).assert_one(flag);
+        }
+    }
+
+    /// Constrain the memory access for the state.
+    fn constrain_memory<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &ChaCha20PermuteCols<AB::Var>,
+    ) {
+        // Calculate the 4 indices to read from the state. This corresponds to a, b, c, and d.
+        for i in 0..NUM_STATE_WORDS_PER_CALL {
+            let mut index_to_read = AB::Expr::from_canonical_usize(0);
+            for operation in 0..OPERATION_COUNT {
+                index_to_read += AB::Expr::from_canonical_usize(QUARTER_ROUND_INDEX[operation][i])
+                    * local.is_operation_index_n[operation];
+            }
+            builder.assert_eq(local.state_index[i], index_to_read);
+        }
+
+        // Read & write the state.
+        for i in 0..NUM_STATE_WORDS_PER_CALL {
+            builder.eval_memory_access(
+                local.shard,
+                local.channel,
+                local.clk,
+                local.state_ptr + local.state_index[i] * AB::F::from_canonical_usize(WORD_SIZE),
+                &local.state_reads_writes[i],
+                local.is_real,
+            );
+        }
+    }
+
+    /// Constrains the input and the output of the quarter round.
+    fn constrain_quarter_round<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &ChaCha20PermuteCols<AB::Var>,
+    ) {
+        builder.assert_bool(local.is_real);
+
+        let input = [
+            local.state_reads_writes[0].prev_value,
+            local.state_reads_writes[1].prev_value,
+            local.state_reads_writes[2].prev_value,
+            local.state_reads_writes[3].prev_value,
+        ];
+
+        QuarterRoundOperation::<AB::F>::eval(
+            builder,
+            input,
+            local.quarter_round,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // The results of the quarter round should be written to the memory.
+        for i in 0..NUM_STATE_WORDS_PER_CALL {
+            for j in 0..WORD_SIZE {
+                builder.when(local.is_real).assert_eq(
+                    local.state_reads_writes[i].access.value[j],
+                    local.quarter_round.result[i][j],
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use std::mem::size_of;
+
+use sp1_derive::AlignedBorrow;
+
+use crate::memory::MemoryReadWriteCols;
+
+use super::quarter_round::QuarterRoundOperation;
+use super::DOUBLE_ROUND_COUNT;
+use super::NUM_STATE_WORDS_PER_CALL;
+use super::OPERATION_COUNT;
+
+pub const NUM_CHACHA20_PERMUTE_COLS: usize = size_of::<ChaCha20PermuteCols<u8>>();
+
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ChaCha20PermuteCols<T> {
+    pub shard: T,
+    pub channel: T,
+    pub clk: T,
+    pub ecall_receive: T,
+
+    /// The pointer to the state.
+    pub state_ptr: T,
+
+    /// Reads and writes a part of the state.
+    pub state_reads_writes: [MemoryReadWriteCols<T>; NUM_STATE_WORDS_PER_CALL],
+
+    /// Indicates which quarter round of the double round is being performed.
+    pub operation_index: T,
+    pub is_operation_index_n: [T; OPERATION_COUNT],
+
+    /// Indicates which double round is being performed.
+    pub double_round_index: T,
+    pub is_double_round_index_n: [T; DOUBLE_ROUND_COUNT],
+
+    /// The indices to pass to the quarter round.
+    pub state_index: [T; NUM_STATE_WORDS_PER_CALL],
+
+    /// The quarter round to perform.
+    pub quarter_round: QuarterRoundOperation<T>,
+
+    /// Indicates if the current call is real or not.
+    pub is_real: T,
+}
@@ -0,0 +1,60 @@
+use crate::runtime::MemoryWriteRecord;
+use crate::runtime::Syscall;
+use crate::syscall::precompiles::chacha20::{
+    quarter_round_func, ChaCha20PermuteChip, ChaCha20PermuteEvent, DOUBLE_ROUND_COUNT,
+    NUM_STATE_WORDS_PER_CALL, OPERATION_COUNT, QUARTER_ROUND_INDEX,
+};
+use crate::syscall::precompiles::SyscallContext;
+
+impl Syscall for ChaCha20PermuteChip {
+    fn num_extra_cycles(&self) -> u32 {
+        (DOUBLE_ROUND_COUNT * OPERATION_COUNT) as u32
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let state_ptr = arg1;
+        if arg2 != 0 {
+            panic!("Expected arg2 to be 0, got {}", arg2);
+        }
+
+        let start_clk = rt.clk;
+        let mut state_writes = [[[MemoryWriteRecord::default(); NUM_STATE_WORDS_PER_CALL];
+            OPERATION_COUNT]; DOUBLE_ROUND_COUNT];
+
+        for double_round in 0..DOUBLE_ROUND_COUNT {
+            for operation in 0..OPERATION_COUNT {
+                let state_index = QUARTER_ROUND_INDEX[operation];
+
+                // Read the input to the quarter round.
+                let input = state_index.map(|index| rt.word_unsafe(state_ptr + (index as u32) * 4));
+
+                // Call the quarter round.
+                let results = quarter_round_func(input);
+
+                // Write the state.
+                for i in 0..NUM_STATE_WORDS_PER_CALL {
+                    state_writes[double_round][operation][i] =
+                        rt.mw(state_ptr + (state_index[i] as u32) * 4, results[i]);
+                }
+
+                // Increment the clock for the next quarter round.
+                rt.clk += 1;
+            }
+        }
+
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+
+        rt.record_mut()
+            .chacha20_permute_events
+            .push(ChaCha20PermuteEvent {
+                shard,
+                channel,
+                clk: start_clk,
+                state_ptr,
+                state_writes,
+            });
+
+        None
+    }
+}
@@ -0,0 +1,154 @@
+//! This module contains the implementation of the `chacha20_permute` precompile, the 20 rounds of
+//! the ChaCha20 block function of RFC 8439.
+//!
+//! Pseudo-code.
+//!
+//! state = [0u32; 16]
+//!
+//! for double_round in 0..10 {
+//!    for operation in 0..8 {
+//!       // * Pick 4 indices a, b, c, d for the state, based on the operation index. The first
+//!       //   four operations are the column rounds, and the last four the diagonal rounds.
+//!       //
+//!       // Each call of quarter_round becomes one row in the trace.
+//!       quarter_round(&mut state[a], &mut state[b], &mut state[c], &mut state[d]);
+//!   }
+//! }
+//!
+//! Note that this precompile is only the rounds of the block function. The ChaCha20 block
+//! function adds the input state to the permuted state after the rounds.
+mod air;
+mod columns;
+mod execute;
+mod quarter_round;
+mod trace;
+
+use crate::runtime::MemoryWriteRecord;
+
+use serde::{Deserialize, Serialize};
+
+/// The number of double rounds in the ChaCha20 block function.
+pub(crate) const DOUBLE_ROUND_COUNT: usize = 10;
+
+/// The number of quarter rounds in a double round.
+pub(crate) const OPERATION_COUNT: usize = 8;
+
+/// The number of `Word`s in the state that we pass to a quarter round.
+pub(crate) const NUM_STATE_WORDS_PER_CALL: usize = 4;
+
+/// The `i`-th row of `QUARTER_ROUND_INDEX` is the indices used for the `i`-th quarter round of a
+/// double round.
+pub(crate) const QUARTER_ROUND_INDEX: [[usize; NUM_STATE_WORDS_PER_CALL]; OPERATION_COUNT] = [
+    [0, 4, 8, 12],
+    [1, 5, 9, 13],
+    [2, 6, 10, 14],
+    [3, 7, 11, 15],
+    [0, 5, 10, 15],
+    [1, 6, 11, 12],
+    [2, 7, 8, 13],
+    [3, 4, 9, 14],
+];
+
+pub(crate) const fn quarter_round_func(input: [u32; 4]) -> [u32; 4] {
+    let mut a = input[0];
+    let mut b = input[1];
+    let mut c = input[2];
+    let mut d = input[3];
+    a = a.wrapping_add(b);
+    d = (d ^ a).rotate_left(16);
+    c = c.wrapping_add(d);
+    b = (b ^ c).rotate_left(12);
+    a = a.wrapping_add(b);
+    d = (d ^ a).rotate_left(8);
+    c = c.wrapping_add(d);
+    b = (b ^ c).rotate_left(7);
+    [a, b, c, d]
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChaCha20PermuteEvent {
+    pub clk: u32,
+    pub shard: u32,
+    pub channel: u32,
+    pub state_ptr: u32,
+    pub state_writes:
+        [[[MemoryWriteRecord; NUM_STATE_WORDS_PER_CALL]; OPERATION_COUNT]; DOUBLE_ROUND_COUNT],
+}
+
+pub struct ChaCha20PermuteChip {}
+
+impl ChaCha20PermuteChip {
+    pub const fn new() -> Self {
+        Self {}
+    }
+}
+
+#[cfg(test)]
+pub mod permute_tests {
+    use crate::runtime::Instruction;
+    use crate::runtime::Opcode;
+    use crate::runtime::Register;
+    use crate::runtime::Runtime;
+    use crate::runtime::SyscallCode;
+    use crate::utils::{run_test, setup_logger, SP1CoreOpts};
+    use crate::Program;
+
+    const STATE_SIZE: usize = 16;
+    const STATE_PTR: u32 = 100;
+
+    /// The input state of the block function example of RFC 8439, section 2.3.2.
+    const INPUT_STATE: [u32; STATE_SIZE] = [
+        0x61707865, 0x3320646e, 0x79622d32, 0x6b206574, 0x03020100, 0x07060504, 0x0b0a0908,
+        0x0f0e0d0c, 0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c, 0x00000001, 0x09000000,
+        0x4a000000, 0x00000000,
+    ];
+
+    pub fn chacha20_permute_program() -> Program {
+        let mut instructions = vec![];
+        for (i, word) in INPUT_STATE.iter().enumerate() {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 29, 0, *word, false, true),
+                Instruction::new(Opcode::ADD, 30, 0, STATE_PTR + i as u32 * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            ]);
+        }
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::CHACHA20_PERMUTE as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, Register::X10 as u32, 0, STATE_PTR, false, true),
+            Instruction::new(Opcode::ADD, Register::X11 as u32, 0, 0, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_chacha20_permute_execute() {
+        setup_logger();
+        let mut runtime = Runtime::new(chacha20_permute_program(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        // The state after the 20 rounds, before the input state is added back.
+        let expected: [u32; STATE_SIZE] = [
+            0x837778ab, 0xe238d763, 0xa67ae21e, 0x5950bb2f, 0xc4f2d0c7, 0xfc62bb2f, 0x8fa018fc,
+            0x3f5ec7b7, 0x335271c2, 0xf29489f3, 0xeabda8fc, 0x82e46ebd, 0xd19c12b4, 0xb04e16de,
+            0x9e83d0cb, 0x4e3c50a2,
+        ];
+        for (i, word) in expected.iter().enumerate() {
+            assert_eq!(runtime.word(STATE_PTR + i as u32 * 4), *word);
+        }
+    }
+
+    #[test]
+    fn prove_babybear() {
+        setup_logger();
+        let program = chacha20_permute_program();
+        run_test(program).unwrap();
+    }
+}
@@ -0,0 +1,249 @@
+use p3_field::Field;
+use sp1_derive::AlignedBorrow;
+
+use crate::air::SP1AirBuilder;
+use crate::air::Word;
+use crate::air::WORD_SIZE;
+use crate::operations::AddOperation;
+use crate::operations::FixedRotateRightOperation;
+use crate::operations::XorOperation;
+use crate::runtime::ExecutionRecord;
+
+use super::quarter_round_func;
+/// A set of columns needed to compute the quarter round of the input state.
+///  ``` ignore
+/// fn quarter_round(state: &mut BlockWords, a: usize, b: usize, c: usize, d: usize) {
+///     state[a] = state[a].wrapping_add(state[b]);
+///     state[d] = (state[d] ^ state[a]).rotate_left(16);
+///     state[c] = state[c].wrapping_add(state[d]);
+///     state[b] = (state[b] ^ state[c]).rotate_left(12);
+///     state[a] = state[a].wrapping_add(state[b]);
+///     state[d] = (state[d] ^ state[a]).rotate_left(8);
+///     state[c] = state[c].wrapping_add(state[d]);
+///     state[b] = (state[b] ^ state[c]).rotate_left(7);
+/// }
+///  ```
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct QuarterRoundOperation<T> {
+    pub a_plus_b: AddOperation<T>,
+    pub d_xor_a: XorOperation<T>,
+    // Rotate left by 16 bits by just shifting bytes.
+    pub c_plus_d: AddOperation<T>,
+    pub b_xor_c: XorOperation<T>,
+    pub b_xor_c_rotate_left_12: FixedRotateRightOperation<T>,
+    pub a_plus_b_2: AddOperation<T>,
+    pub d_xor_a_2: XorOperation<T>,
+    // Rotate left by 8 bits by just shifting bytes.
+    pub c_plus_d_2: AddOperation<T>,
+    pub b_xor_c_2: XorOperation<T>,
+    pub b_xor_c_2_rotate_left_7: FixedRotateRightOperation<T>,
+    /// `state[a]`, `state[b]`, `state[c]`, `state[d]` after all the steps.
+    pub result: [Word<T>; 4],
+}
+
+impl<F: Field> QuarterRoundOperation<F> {
+    pub fn populate(
+        &mut self,
+        record: &mut ExecutionRecord,
+        shard: u32,
+        channel: u32,
+        input: [u32; 4],
+    ) -> [u32; 4] {
+        let mut a = input[0];
+        let mut b = input[1];
+        let mut c = input[2];
+        let mut d = input[3];
+
+        // First 4 steps.
+        {
+            // a = a + b.
+            a = self.a_plus_b.populate(record, shard, channel, a, b);
+
+            // d = (d ^ a).rotate_left(16).
+            d = self.d_xor_a.populate(record, shard, channel, d, a);
+            d = d.rotate_left(16);
+
+            // c = c + d.
+            c = self.c_plus_d.populate(record, shard, channel, c, d);
+
+            // b = (b ^ c).rotate_left(12), which is a rotation right by 20.
+            b = self.b_xor_c.populate(record, shard, channel, b, c);
+            b = self
+                .b_xor_c_rotate_left_12
+                .populate(record, shard, channel, b, 20);
+        }
+
+        // Second 4 steps.
+        {
+            // a = a + b.
+            a = self.a_plus_b_2.populate(record, shard, channel, a, b);
+
+            // d = (d ^ a).rotate_left(8).
+            d = self.d_xor_a_2.populate(record, shard, channel, d, a);
+            d = d.rotate_left(8);
+
+            // c = c + d.
+            c = self.c_plus_d_2.populate(record, shard, channel, c, d);
+
+            // b = (b ^ c).rotate_left(7), which is a rotation right by 25.
+            b = self.b_xor_c_2.populate(record, shard, channel, b, c);
+            b = self
+                .b_xor_c_2_rotate_left_7
+                .populate(record, shard, channel, b, 25);
+        }
+
+        let result = [a, b, c, d];
+        assert_eq!(result, quarter_round_func(input));
+        self.result = result.map(Word::from);
+        result
+    }
+
+    pub fn eval<AB: SP1AirBuilder>(
+        builder: &mut AB,
+        input: [Word<AB::Var>; 4],
+        cols: QuarterRoundOperation<AB::Var>,
+        shard: AB::Var,
+        channel: impl Into<AB::Expr> + Clone,
+        is_real: AB::Var,
+    ) {
+        builder.assert_bool(is_real);
+        let mut a = input[0];
+        let mut b = input[1];
+        let mut c = input[2];
+        let mut d = input[3];
+
+        // First 4 steps.
+        {
+            // a = a + b.
+            AddOperation::<AB::F>::eval(
+                builder,
+                a,
+                b,
+                cols.a_plus_b,
+                shard,
+                channel.clone(),
+                is_real.into(),
+            );
+            a = cols.a_plus_b.value;
+
+            // d = (d ^ a).rotate_left(16).
+            XorOperation::<AB::F>::eval(
+                builder,
+                d,
+                a,
+                cols.d_xor_a,
+                shard,
+                channel.clone(),
+                is_real,
+            );
+            d = cols.d_xor_a.value;
+            // Rotate left by 16 bits.
+            d = Word([d[2], d[3], d[0], d[1]]);
+
+            // c = c + d.
+            AddOperation::<AB::F>::eval(
+                builder,
+                c,
+                d,
+                cols.c_plus_d,
+                shard,
+                channel.clone(),
+                is_real.into(),
+            );
+            c = cols.c_plus_d.value;
+
+            // b = (b ^ c).rotate_left(12).
+            XorOperation::<AB::F>::eval(
+                builder,
+                b,
+                c,
+                cols.b_xor_c,
+                shard,
+                channel.clone(),
+                is_real,
+            );
+            b = cols.b_xor_c.value;
+            FixedRotateRightOperation::<AB::F>::eval(
+                builder,
+                b,
+                20,
+                cols.b_xor_c_rotate_left_12,
+                shard,
+                channel.clone(),
+                is_real,
+            );
+            b = cols.b_xor_c_rotate_left_12.value;
+        }
+
+        // Second 4 steps.
+        {
+            // a = a + b.
+            AddOperation::<AB::F>::eval(
+                builder,
+                a,
+                b,
+                cols.a_plus_b_2,
+                shard,
+                channel.clone(),
+                is_real.into(),
+            );
+            a = cols.a_plus_b_2.value;
+
+            // d = (d ^ a).rotate_left(8).
+            XorOperation::<AB::F>::eval(
+                builder,
+                d,
+                a,
+                cols.d_xor_a_2,
+                shard,
+                channel.clone(),
+                is_real,
+            );
+            d = cols.d_xor_a_2.value;
+            // Rotate left by 8 bits.
+            d = Word([d[3], d[0], d[1], d[2]]);
+
+            // c = c + d.
+            AddOperation::<AB::F>::eval(
+                builder,
+                c,
+                d,
+                cols.c_plus_d_2,
+                shard,
+                channel.clone(),
+                is_real.into(),
+            );
+            c = cols.c_plus_d_2.value;
+
+            // b = (b ^ c).rotate_left(7).
+            XorOperation::<AB::F>::eval(
+                builder,
+                b,
+                c,
+                cols.b_xor_c_2,
+                shard,
+                channel.clone(),
+                is_real,
+            );
+            b = cols.b_xor_c_2.value;
+            FixedRotateRightOperation::<AB::F>::eval(
+                builder,
+                b,
+                25,
+                cols.b_xor_c_2_rotate_left_7,
+                shard,
+                channel.clone(),
+                is_real,
+            );
+            b = cols.b_xor_c_2_rotate_left_7.value;
+        }
+
+        let results = [a, b, c, d];
+        for i in 0..4 {
+            for j in 0..WORD_SIZE {
+                builder.assert_eq(cols.result[i][j], results[i][j]);
+            }
+        }
+    }
+}
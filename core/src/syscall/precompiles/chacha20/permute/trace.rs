@@ -0,0 +1,110 @@
+use std::borrow::BorrowMut;
+
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+
+use super::columns::{ChaCha20PermuteCols, NUM_CHACHA20_PERMUTE_COLS};
+use super::{
+    ChaCha20PermuteChip, DOUBLE_ROUND_COUNT, NUM_STATE_WORDS_PER_CALL, OPERATION_COUNT,
+    QUARTER_ROUND_INDEX,
+};
+use crate::air::MachineAir;
+use crate::bytes::event::ByteRecord;
+use crate::runtime::ExecutionRecord;
+use crate::runtime::MemoryRecordEnum;
+use crate::runtime::Program;
+use crate::utils::pad_rows;
+
+impl<F: PrimeField32> MachineAir<F> for ChaCha20PermuteChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "ChaCha20Permute".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut rows = Vec::new();
+
+        let mut new_byte_lookup_events = Vec::new();
+
+        for event in input.chacha20_permute_events.iter() {
+            let shard = event.shard;
+            let channel = event.channel;
+            let mut clk = event.clk;
+            for double_round in 0..DOUBLE_ROUND_COUNT {
+                for operation in 0..OPERATION_COUNT {
+                    let mut row = [F::zero(); NUM_CHACHA20_PERMUTE_COLS];
+                    let cols: &mut ChaCha20PermuteCols<F> = row.as_mut_slice().borrow_mut();
+
+                    // Assign basic values to the columns.
+                    {
+                        cols.shard = F::from_canonical_u32(event.shard);
+                        cols.channel = F::from_canonical_u32(event.channel);
+                        cols.clk = F::from_canonical_u32(clk);
+
+                        cols.double_round_index = F::from_canonical_u32(double_round as u32);
+                        cols.is_double_round_index_n[double_round] = F::one();
+
+                        cols.operation_index = F::from_canonical_u32(operation as u32);
+                        cols.is_operation_index_n[operation] = F::one();
+
+                        for i in 0..NUM_STATE_WORDS_PER_CALL {
+                            cols.state_index[i] =
+                                F::from_canonical_usize(QUARTER_ROUND_INDEX[operation][i]);
+                        }
+
+                        if double_round == 0 && operation == 0 {
+                            cols.ecall_receive = F::one();
+                        }
+                    }
+
+                    // Memory columns.
+                    {
+                        cols.state_ptr = F::from_canonical_u32(event.state_ptr);
+                        for i in 0..NUM_STATE_WORDS_PER_CALL {
+                            cols.state_reads_writes[i].populate(
+                                channel,
+                                MemoryRecordEnum::Write(
+                                    event.state_writes[double_round][operation][i],
+                                ),
+                                &mut new_byte_lookup_events,
+                            );
+                        }
+                    }
+
+                    // Apply the quarter round.
+                    {
+                        let input = event.state_writes[double_round][operation]
+                            .map(|record| record.prev_value);
+                        cols.quarter_round.populate(output, shard, channel, input);
+                    }
+
+                    clk += 1;
+
+                    cols.is_real = F::one();
+
+                    rows.push(row);
+                }
+            }
+        }
+
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_CHACHA20_PERMUTE_COLS]);
+
+        // Convert the trace to a row major matrix.
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_CHACHA20_PERMUTE_COLS,
+        )
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.chacha20_permute_events.is_empty()
+    }
+}
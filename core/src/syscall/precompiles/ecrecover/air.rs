@@ -0,0 +1,497 @@
+use core::borrow::Borrow;
+
+use num::BigUint;
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::AbstractField;
+use p3_matrix::Matrix;
+
+use super::columns::{Secp256k1EcrecoverCols, NUM_SECP256K1_ECRECOVER_COLS};
+use super::{
+    neg_generator, Secp256k1EcrecoverChip, NUM_INPUT_WORDS, NUM_OUTPUT_WORDS, NUM_SCALAR_BITS,
+    NUM_SCALAR_WORDS, ROW_COUNTER_LEN,
+};
+use crate::air::{BaseAirBuilder, SP1AirBuilder, WORD_SIZE};
+use crate::operations::field::field_op::FieldOperation;
+use crate::operations::field::params::{FieldParameters, Limbs, NumLimbs};
+use crate::runtime::SyscallCode;
+use crate::utils::ec::weierstrass::secp256k1::{Secp256k1BaseField, Secp256k1Parameters};
+use crate::utils::ec::weierstrass::WeierstrassParameters;
+use crate::utils::{limbs_from_access, limbs_from_prev_access};
+
+type LimbsOf<T> = Limbs<T, <Secp256k1BaseField as NumLimbs>::Limbs>;
+
+impl<F> BaseAir<F> for Secp256k1EcrecoverChip {
+    fn width(&self) -> usize {
+        NUM_SECP256K1_ECRECOVER_COLS
+    }
+}
+
+impl<AB> Air<AB> for Secp256k1EcrecoverChip
+where
+    AB: SP1AirBuilder,
+    LimbsOf<AB::Var>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &Secp256k1EcrecoverCols<AB::Var> = (*local).borrow();
+        let next: &Secp256k1EcrecoverCols<AB::Var> = (*next).borrow();
+
+        self.constrain_control_flow_flags(builder, local, next);
+
+        self.constrain_memory(builder, local);
+
+        self.constrain_setup(builder, local);
+
+        self.constrain_scalar_bits(builder, local, next);
+
+        self.constrain_step(builder, local, next);
+
+        self.constrain_invocation(builder, local, next);
+
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            AB::F::from_canonical_u32(SyscallCode::SECP256K1_ECRECOVER.syscall_id()),
+            local.ptr,
+            local.is_odd,
+            local.ecall_receive,
+        );
+    }
+}
+
+impl Secp256k1EcrecoverChip {
+    /// The expression which is one on the given row of an invocation and zero on the others.
+    fn row_flag<AB: SP1AirBuilder>(
+        local: &Secp256k1EcrecoverCols<AB::Var>,
+        row_index: usize,
+    ) -> AB::Expr {
+        local.is_row_hi[row_index / ROW_COUNTER_LEN] * local.is_row_lo[row_index % ROW_COUNTER_LEN]
+    }
+
+    /// Constrains the row counters, which are one-hot and cycle through the 256 rows.
+    fn constrain_control_flow_flags<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Secp256k1EcrecoverCols<AB::Var>,
+        next: &Secp256k1EcrecoverCols<AB::Var>,
+    ) {
+        builder.assert_bool(local.is_real);
+        builder.assert_bool(local.is_odd);
+        for flags in [&local.is_row_hi[..], &local.is_row_lo[..]] {
+            let mut sum = AB::Expr::zero();
+            for flag in flags.iter() {
+                builder.assert_bool(*flag);
+                sum += (*flag).into();
+            }
+            builder.when(local.is_real).assert_one(sum);
+        }
+
+        // The low digit is incremented on every row.
+        for i in 0..ROW_COUNTER_LEN {
+            builder.when_transition().when(next.is_real).assert_eq(
+                local.is_row_lo[i],
+                next.is_row_lo[(i + 1) % ROW_COUNTER_LEN],
+            );
+        }
+
+        // The high digit is incremented after the last value of the low digit.
+        for i in 0..ROW_COUNTER_LEN {
+            builder
+                .when_transition()
+                .when(next.is_real)
+                .when_not(local.is_row_lo[ROW_COUNTER_LEN - 1])
+                .assert_eq(local.is_row_hi[i], next.is_row_hi[i]);
+            builder
+                .when_transition()
+                .when(next.is_real)
+                .when(local.is_row_lo[ROW_COUNTER_LEN - 1])
+                .assert_eq(
+                    local.is_row_hi[i],
+                    next.is_row_hi[(i + 1) % ROW_COUNTER_LEN],
+                );
+        }
+    }
+
+    /// Constrains the read of the input on the first row and the write of the public key on the
+    /// last row.
+    fn constrain_memory<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Secp256k1EcrecoverCols<AB::Var>,
+    ) where
+        LimbsOf<AB::Var>: Copy,
+    {
+        for i in 0..NUM_INPUT_WORDS {
+            builder.eval_memory_access(
+                local.shard,
+                local.channel,
+                local.clk,
+                local.ptr + AB::F::from_canonical_usize(i * WORD_SIZE),
+                &local.input_access[i],
+                local.ecall_receive,
+            );
+        }
+        for (i, input) in [local.z, local.r, local.s].into_iter().enumerate() {
+            let start = i * NUM_SCALAR_WORDS;
+            let memory: LimbsOf<AB::Var> =
+                limbs_from_prev_access(&local.input_access[start..start + NUM_SCALAR_WORDS]);
+            builder
+                .when(local.ecall_receive)
+                .assert_all_eq(input, memory);
+        }
+
+        // The public key is written over the input after it is read.
+        for i in 0..NUM_OUTPUT_WORDS {
+            builder.eval_memory_access(
+                local.shard,
+                local.channel,
+                local.clk + AB::F::one(),
+                local.ptr + AB::F::from_canonical_usize(i * WORD_SIZE),
+                &local.output_access[i],
+                local.is_last_row,
+            );
+        }
+        let public_key_x: LimbsOf<AB::Var> =
+            limbs_from_access(&local.output_access[..NUM_SCALAR_WORDS]);
+        let public_key_y: LimbsOf<AB::Var> =
+            limbs_from_access(&local.output_access[NUM_SCALAR_WORDS..]);
+        builder
+            .when(local.is_last_row)
+            .assert_all_eq(local.new_acc_x, public_key_x);
+        builder
+            .when(local.is_last_row)
+            .assert_all_eq(local.new_acc_y, public_key_y);
+    }
+
+    /// Constrains the lift of `r` to `R = (r, sqrt(r^3 + 7))`, the point `R - G`, the inverse of
+    /// `r` and the scalars, modulo the group order.
+    fn constrain_setup<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Secp256k1EcrecoverCols<AB::Var>,
+    ) where
+        LimbsOf<AB::Var>: Copy,
+    {
+        // R = (r, sqrt(r^3 + 7)), with r the reduced coordinate and the parity of sqrt given by
+        // `is_odd`.
+        local
+            .range_r
+            .eval(builder, &local.r, local.shard, local.channel, local.is_real);
+        local.r_squared.eval(
+            builder,
+            &local.r,
+            &local.r,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        local.r_cubed.eval(
+            builder,
+            &local.r_squared.result,
+            &local.r,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        local.r_cubed_plus_b.eval(
+            builder,
+            &local.r_cubed.result,
+            &Secp256k1BaseField::to_limbs_field::<AB::Expr, _>(&Secp256k1Parameters::b_int()),
+            FieldOperation::Add,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        local.r_y.eval(
+            builder,
+            &local.r_cubed_plus_b.result,
+            local.is_odd,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        let r_y = local.r_y.multiplication.result;
+
+        // R - G, where R is not G or -G.
+        let neg_g = neg_generator();
+        local.r_minus_g.eval(
+            builder,
+            &local.r,
+            &r_y,
+            &Secp256k1BaseField::to_limbs_field::<AB::Expr, _>(&neg_g.x),
+            &Secp256k1BaseField::to_limbs_field::<AB::Expr, _>(&neg_g.y),
+            local.is_real,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // r * r_inv = 1 modulo the group order, so that r is invertible.
+        let n = Secp256k1BaseField::to_limbs_field::<AB::Expr, _>(
+            &Secp256k1Parameters::prime_group_order(),
+        );
+        builder.slice_range_check_u8(&local.r_inv.0, local.shard, local.channel, local.is_real);
+        local.r_times_r_inv.eval_with_modulus(
+            builder,
+            &local.r,
+            &local.r_inv,
+            &n,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        builder.when(local.is_real).assert_all_eq(
+            local.r_times_r_inv.result,
+            Secp256k1BaseField::to_limbs_field::<AB::Expr, _>(&BigUint::from(1u32)),
+        );
+
+        // u1 = z * r_inv and u2 = s * r_inv, modulo the group order.
+        local.u1.eval_with_modulus(
+            builder,
+            &local.z,
+            &local.r_inv,
+            &n,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        local.u2.eval_with_modulus(
+            builder,
+            &local.s,
+            &local.r_inv,
+            &n,
+            FieldOperation::Mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+    }
+
+    /// Constrains the bits of the scalars, which are decomposed on the first row and shifted by
+    /// one on every row.
+    fn constrain_scalar_bits<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Secp256k1EcrecoverCols<AB::Var>,
+        next: &Secp256k1EcrecoverCols<AB::Var>,
+    ) {
+        for (bits, scalar, next_bits) in [
+            (&local.u1_bits, &local.u1.result, &next.u1_bits),
+            (&local.u2_bits, &local.u2.result, &next.u2_bits),
+        ] {
+            for bit in bits.iter() {
+                builder.assert_bool(*bit);
+            }
+
+            // On the first row, the bits are the little-endian decomposition of the scalar.
+            for (byte, byte_bits) in scalar.0.iter().zip(bits.chunks_exact(8)) {
+                let mut value = AB::Expr::zero();
+                for (i, bit) in byte_bits.iter().enumerate() {
+                    value += AB::Expr::from_canonical_u32(1 << i) * *bit;
+                }
+                builder.when(local.ecall_receive).assert_eq(*byte, value);
+            }
+
+            // The bits are shifted towards the most significant bit on every row.
+            for i in 0..NUM_SCALAR_BITS - 1 {
+                builder
+                    .when_transition()
+                    .when(next.is_real)
+                    .when_not(next.ecall_receive)
+                    .assert_eq(bits[i], next_bits[i + 1]);
+            }
+        }
+    }
+
+    /// Constrains the double-and-add of the row and the accumulator of the next row.
+    fn constrain_step<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Secp256k1EcrecoverCols<AB::Var>,
+        next: &Secp256k1EcrecoverCols<AB::Var>,
+    ) where
+        LimbsOf<AB::Var>: Copy,
+    {
+        let u1_bit = local.u1_bits[NUM_SCALAR_BITS - 1];
+        let u2_bit = local.u2_bits[NUM_SCALAR_BITS - 1];
+        builder.assert_bool(local.is_infinity);
+        builder.assert_eq(local.both_bits, u1_bit * u2_bit);
+        let any_bit = u1_bit + u2_bit - local.both_bits;
+        builder.assert_eq(
+            local.use_sum,
+            any_bit.clone() * (AB::Expr::one() - local.is_infinity),
+        );
+
+        // The addend is `-G` if the bit of `R` is not set, and `R` or `R - G` otherwise.
+        let neg_g = neg_generator();
+        let neg_g_x = Secp256k1BaseField::to_limbs_field::<AB::F, _>(&neg_g.x);
+        let neg_g_y = Secp256k1BaseField::to_limbs_field::<AB::F, _>(&neg_g.y);
+        let (r_minus_g_x, r_minus_g_y) = local.r_minus_g.result();
+        let r_y = local.r_y.multiplication.result;
+        for (addend, neg_g, r, r_minus_g) in [
+            (local.addend_x, neg_g_x, local.r, r_minus_g_x),
+            (local.addend_y, neg_g_y, r_y, r_minus_g_y),
+        ] {
+            for i in 0..addend.0.len() {
+                builder.when(local.is_real).assert_eq(
+                    addend[i],
+                    (AB::Expr::one() - u2_bit) * neg_g[i]
+                        + (u2_bit - local.both_bits) * r[i]
+                        + local.both_bits * r_minus_g[i],
+                );
+            }
+        }
+
+        // The sum of the doubled accumulator and the addend, whose `x` coordinates are distinct
+        // if the sum is used.
+        local.double.eval(
+            builder,
+            &local.acc_x,
+            &local.acc_y,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        let (doubled_x, doubled_y) = local.double.result();
+        local.add.eval(
+            builder,
+            &doubled_x,
+            &doubled_y,
+            &local.addend_x,
+            &local.addend_y,
+            local.use_sum,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        let (sum_x, sum_y) = local.add.result();
+
+        // The new accumulator is the double if no bit is set, the addend if the accumulator is the
+        // point at infinity, and the sum otherwise.
+        for (new_acc, doubled, addend, sum) in [
+            (local.new_acc_x, doubled_x, local.addend_x, sum_x),
+            (local.new_acc_y, doubled_y, local.addend_y, sum_y),
+        ] {
+            for i in 0..new_acc.0.len() {
+                builder.when(local.is_real).assert_eq(
+                    new_acc[i],
+                    doubled[i]
+                        + any_bit.clone() * (addend[i] - doubled[i])
+                        + local.use_sum * (sum[i] - addend[i]),
+                );
+            }
+        }
+        local.range_new_acc_x.eval(
+            builder,
+            &local.new_acc_x,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+        local.range_new_acc_y.eval(
+            builder,
+            &local.new_acc_y,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // The accumulator starts at the point at infinity, leaves it on the first set bit, and is
+        // a point of the curve on the last row.
+        let new_is_infinity = local.is_infinity - any_bit + local.use_sum;
+        builder
+            .when(local.ecall_receive)
+            .assert_one(local.is_infinity);
+        builder
+            .when(local.is_last_row)
+            .assert_zero(new_is_infinity.clone());
+        builder
+            .when_transition()
+            .when(next.is_real)
+            .when_not(next.ecall_receive)
+            .assert_eq(next.is_infinity, new_is_infinity);
+        builder
+            .when_transition()
+            .when(next.is_real)
+            .when_not(next.ecall_receive)
+            .assert_all_eq(next.acc_x, local.new_acc_x);
+        builder
+            .when_transition()
+            .when(next.is_real)
+            .when_not(next.ecall_receive)
+            .assert_all_eq(next.acc_y, local.new_acc_y);
+    }
+
+    /// Constrains the rows of each invocation of the syscall to be contiguous, starting with the
+    /// row receiving the syscall and ending with the last row.
+    fn constrain_invocation<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Secp256k1EcrecoverCols<AB::Var>,
+        next: &Secp256k1EcrecoverCols<AB::Var>,
+    ) {
+        // The syscall is received on the first row, and the last row is flagged.
+        builder.assert_eq(
+            local.ecall_receive,
+            local.is_real * Self::row_flag::<AB>(local, 0),
+        );
+        builder.assert_eq(
+            local.is_last_row,
+            local.is_real * Self::row_flag::<AB>(local, NUM_SCALAR_BITS - 1),
+        );
+
+        // The trace starts with an invocation, and padding rows are only at the end.
+        builder
+            .when_first_row()
+            .assert_eq(local.ecall_receive, local.is_real);
+        builder
+            .when_transition()
+            .when_not(local.is_real)
+            .assert_zero(next.is_real);
+
+        // Within an invocation, the shard, channel, clock, pointer and inputs are the same on
+        // every row.
+        for (local_value, next_value) in [
+            (local.shard, next.shard),
+            (local.channel, next.channel),
+            (local.clk, next.clk),
+            (local.ptr, next.ptr),
+            (local.is_odd, next.is_odd),
+        ] {
+            builder
+                .when_transition()
+                .when(next.is_real)
+                .when_not(next.ecall_receive)
+                .assert_eq(local_value, next_value);
+        }
+        for (local_value, next_value) in [
+            (local.z, next.z),
+            (local.r, next.r),
+            (local.s, next.s),
+            (local.r_inv, next.r_inv),
+        ] {
+            builder
+                .when_transition()
+                .when(next.is_real)
+                .when_not(next.ecall_receive)
+                .assert_all_eq(local_value, next_value);
+        }
+
+        // The last real row is the last row of an invocation.
+        builder
+            .when_transition()
+            .when(local.is_real)
+            .when_not(next.is_real)
+            .assert_one(local.is_last_row);
+        builder
+            .when_last_row()
+            .when(local.is_real)
+            .assert_one(local.is_last_row);
+    }
+}
@@ -0,0 +1,112 @@
+use std::mem::size_of;
+
+use sp1_derive::AlignedBorrow;
+
+use super::point::{AffineAddCols, AffineDoubleCols};
+use super::{NUM_INPUT_WORDS, NUM_OUTPUT_WORDS, NUM_SCALAR_BITS, ROW_COUNTER_LEN};
+use crate::memory::{MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::field_op::FieldOpCols;
+use crate::operations::field::field_sqrt::FieldSqrtCols;
+use crate::operations::field::params::{Limbs, NumLimbs};
+use crate::operations::field::range::FieldRangeCols;
+use crate::utils::ec::weierstrass::secp256k1::Secp256k1BaseField;
+
+pub const NUM_SECP256K1_ECRECOVER_COLS: usize = size_of::<Secp256k1EcrecoverCols<u8>>();
+
+type LimbsOf<T> = Limbs<T, <Secp256k1BaseField as NumLimbs>::Limbs>;
+
+/// A set of columns needed to compute a row of `secp256k1_ecrecover`, which processes one bit of
+/// both scalars.
+///
+/// The setup columns lift `r` to `R`, compute `R - G` and the scalars. They are computed on every
+/// row of a syscall from the same inputs.
+#[derive(AlignedBorrow, Debug, Clone)]
+#[repr(C)]
+pub struct Secp256k1EcrecoverCols<T> {
+    pub shard: T,
+    pub channel: T,
+    pub clk: T,
+    pub ecall_receive: T,
+
+    /// The pointer to the input, which the output is written over.
+    pub ptr: T,
+
+    /// The parity of the `y` coordinate of `R`.
+    pub is_odd: T,
+
+    /// The high and low digits in base 16 of the index of the row within the syscall.
+    pub is_row_hi: [T; ROW_COUNTER_LEN],
+    pub is_row_lo: [T; ROW_COUNTER_LEN],
+
+    /// Whether this is the last row of a real syscall.
+    pub is_last_row: T,
+
+    /// Reads the message hash, `r` and `s` on the first row.
+    pub input_access: [MemoryReadCols<T>; NUM_INPUT_WORDS],
+
+    /// Writes the public key on the last row.
+    pub output_access: [MemoryWriteCols<T>; NUM_OUTPUT_WORDS],
+
+    /// The message hash, `r` and `s`, which are kept on every row.
+    pub z: LimbsOf<T>,
+    pub r: LimbsOf<T>,
+    pub s: LimbsOf<T>,
+
+    /// The `y` coordinate of `R`, computed as `sqrt(r^3 + 7)` with parity `is_odd`.
+    pub(crate) range_r: FieldRangeCols<T, Secp256k1BaseField>,
+    pub(crate) r_squared: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) r_cubed: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) r_cubed_plus_b: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) r_y: FieldSqrtCols<T, Secp256k1BaseField>,
+
+    /// The point `R - G`.
+    pub(crate) r_minus_g: AffineAddCols<T>,
+
+    /// The inverse of `r` modulo the group order, and the check that `r * r_inv = 1`.
+    pub r_inv: LimbsOf<T>,
+    pub(crate) r_times_r_inv: FieldOpCols<T, Secp256k1BaseField>,
+
+    /// The scalars `u1 = z * r^-1` of `-G` and `u2 = s * r^-1` of `R`, modulo the group order.
+    pub(crate) u1: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) u2: FieldOpCols<T, Secp256k1BaseField>,
+
+    /// The bits of the scalars that are left to process. The bit of the current row is the most
+    /// significant one, and the bits are shifted by one on every row.
+    pub u1_bits: [T; NUM_SCALAR_BITS],
+    pub u2_bits: [T; NUM_SCALAR_BITS],
+
+    /// The product of the bits of the current row.
+    pub both_bits: T,
+
+    /// Whether the sum of the doubled accumulator and the addend is the new accumulator, which is
+    /// the case if a bit is set and the accumulator is not the point at infinity.
+    pub use_sum: T,
+
+    /// Whether the accumulator is the point at infinity, in which case its coordinates are not
+    /// used.
+    pub is_infinity: T,
+
+    /// The accumulator before the row.
+    pub acc_x: LimbsOf<T>,
+    pub acc_y: LimbsOf<T>,
+
+    /// The double of the accumulator.
+    pub(crate) double: AffineDoubleCols<T>,
+
+    /// The point added on the row: `-G`, `R` or `R - G` depending on the bits.
+    pub addend_x: LimbsOf<T>,
+    pub addend_y: LimbsOf<T>,
+
+    /// The sum of the doubled accumulator and the addend.
+    pub(crate) add: AffineAddCols<T>,
+
+    /// The accumulator after the row.
+    pub new_acc_x: LimbsOf<T>,
+    pub new_acc_y: LimbsOf<T>,
+
+    /// Checks that the accumulator after the row is reduced, so that the public key is.
+    pub(crate) range_new_acc_x: FieldRangeCols<T, Secp256k1BaseField>,
+    pub(crate) range_new_acc_y: FieldRangeCols<T, Secp256k1BaseField>,
+
+    pub is_real: T,
+}
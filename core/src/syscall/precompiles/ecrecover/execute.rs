@@ -0,0 +1,48 @@
+use num::BigUint;
+
+use super::{
+    recover, Secp256k1EcrecoverChip, Secp256k1EcrecoverEvent, NUM_INPUT_WORDS, NUM_SCALAR_WORDS,
+};
+use crate::runtime::Syscall;
+use crate::syscall::precompiles::SyscallContext;
+
+impl Syscall for Secp256k1EcrecoverChip {
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let ptr = arg1;
+        if ptr % 4 != 0 {
+            panic!();
+        }
+        assert!(arg2 <= 1, "the recovery id must be 0 or 1");
+        let is_odd = arg2 == 1;
+
+        let start_clk = rt.clk;
+        let (input_reads, input) = rt.mr_slice(ptr, NUM_INPUT_WORDS);
+        let [z, r, s] = [0, 1, 2]
+            .map(|i| BigUint::from_slice(&input[i * NUM_SCALAR_WORDS..(i + 1) * NUM_SCALAR_WORDS]));
+        let public_key = recover(&z, &r, &s, is_odd).expect("the signature is invalid");
+
+        // The public key is written over the input, so the clock is incremented.
+        rt.clk += 1;
+        let output_writes = rt.mw_slice(ptr, &public_key.to_words_le());
+
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut()
+            .secp256k1_ecrecover_events
+            .push(Secp256k1EcrecoverEvent {
+                shard,
+                channel,
+                clk: start_clk,
+                ptr,
+                is_odd,
+                input_reads,
+                output_writes,
+            });
+
+        None
+    }
+}
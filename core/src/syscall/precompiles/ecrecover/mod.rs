@@ -0,0 +1,250 @@
+//! This module contains the implementation of the `secp256k1_ecrecover` precompile, which recovers
+//! the secp256k1 public key of an ECDSA signature in a single syscall.
+//!
+//! Given a message hash `z`, a signature `(r, s)` and the parity `v` of the `y` coordinate of the
+//! nonce point `R`, the public key is `Q = r^-1 * (s * R - z * G)`. The chip lifts `r` to `R`,
+//! inverts `r` modulo the group order, and computes `Q = u1 * (-G) + u2 * R` with
+//! `u1 = z * r^-1` and `u2 = s * r^-1`, using a double-and-add over the bits of both scalars at
+//! once. Each syscall takes up 256 rows, one per bit, from the most significant bit down.
+mod air;
+mod columns;
+mod execute;
+mod point;
+mod trace;
+
+use num::{BigUint, One, Zero};
+use serde::{Deserialize, Serialize};
+
+use crate::operations::field::params::FieldParameters;
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::utils::ec::weierstrass::secp256k1::{
+    Secp256k1, Secp256k1BaseField, Secp256k1Parameters,
+};
+use crate::utils::ec::weierstrass::WeierstrassParameters;
+use crate::utils::ec::AffinePoint;
+
+/// The number of words of a 256-bit integer.
+pub(crate) const NUM_SCALAR_WORDS: usize = 8;
+
+/// The number of words of the input: the message hash, `r` and `s`.
+pub(crate) const NUM_INPUT_WORDS: usize = 3 * NUM_SCALAR_WORDS;
+
+/// The number of words of the output: the coordinates of the public key.
+pub(crate) const NUM_OUTPUT_WORDS: usize = 2 * NUM_SCALAR_WORDS;
+
+/// The number of bits of the scalars, which is also the number of rows of a syscall.
+pub(crate) const NUM_SCALAR_BITS: usize = 256;
+
+/// The number of values of each of the two one-hot row counters.
+pub(crate) const ROW_COUNTER_LEN: usize = 16;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secp256k1EcrecoverEvent {
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub ptr: u32,
+    pub is_odd: bool,
+    pub input_reads: Vec<MemoryReadRecord>,
+    pub output_writes: Vec<MemoryWriteRecord>,
+}
+
+/// The values of a recovery that are shared by all the rows of a syscall.
+#[derive(Debug, Clone)]
+pub(crate) struct RecoverySetup {
+    /// The nonce point `R`.
+    pub(crate) r_point: AffinePoint<Secp256k1>,
+    /// The point `R - G`.
+    pub(crate) r_minus_g: AffinePoint<Secp256k1>,
+    /// The inverse of `r` modulo the group order.
+    pub(crate) r_inv: BigUint,
+    /// The scalar of `-G`, `z * r^-1`.
+    pub(crate) u1: BigUint,
+    /// The scalar of `R`, `s * r^-1`.
+    pub(crate) u2: BigUint,
+}
+
+impl RecoverySetup {
+    /// Lifts `r` to the nonce point and computes the scalars. Returns `None` if `r` is not the `x`
+    /// coordinate of a point, if it is zero modulo the group order, or if `R` is `G` or `-G`.
+    pub(crate) fn new(z: &BigUint, r: &BigUint, s: &BigUint, is_odd: bool) -> Option<Self> {
+        let n = Secp256k1Parameters::prime_group_order();
+        if (r % &n).is_zero() {
+            return None;
+        }
+        let r_point = AffinePoint::new(r.clone(), lift_x(r, is_odd)?);
+        let neg_g = neg_generator();
+        if r_point.x == neg_g.x {
+            return None;
+        }
+        let r_minus_g = r_point.sw_add(&neg_g);
+        let r_inv = r.modpow(&(&n - 2u32), &n);
+        let u1 = (z * &r_inv) % &n;
+        let u2 = (s * &r_inv) % &n;
+        Some(Self {
+            r_point,
+            r_minus_g,
+            r_inv,
+            u1,
+            u2,
+        })
+    }
+
+    /// The point added on a row, selected by the bits of the scalars. It is `-G` if neither bit
+    /// is set.
+    pub(crate) fn addend(&self, u1_bit: bool, u2_bit: bool) -> AffinePoint<Secp256k1> {
+        match (u1_bit, u2_bit) {
+            (_, false) => neg_generator(),
+            (false, true) => self.r_point.clone(),
+            (true, true) => self.r_minus_g.clone(),
+        }
+    }
+}
+
+/// Computes the accumulator and the infinity flag after the row of the given bits. Returns `None`
+/// if one of the additions of the row is degenerate.
+pub(crate) fn recovery_step(
+    setup: &RecoverySetup,
+    acc: &AffinePoint<Secp256k1>,
+    is_infinity: bool,
+    u1_bit: bool,
+    u2_bit: bool,
+) -> Option<(AffinePoint<Secp256k1>, bool)> {
+    let doubled = acc.sw_double();
+    let addend = setup.addend(u1_bit, u2_bit);
+    // The chip computes the sum on every row, so it must not be degenerate even when unused.
+    if doubled.x == addend.x {
+        return None;
+    }
+    let sum = doubled.sw_add(&addend);
+    Some(match (u1_bit || u2_bit, is_infinity) {
+        (false, _) => (doubled, is_infinity),
+        (true, true) => (addend, false),
+        (true, false) => (sum, false),
+    })
+}
+
+/// Recovers the public key of the signature `(r, s)` of the message hash `z`, where `is_odd` is
+/// the parity of the `y` coordinate of the nonce point. Returns `None` if the signature is
+/// invalid or if the computation of the chip is degenerate.
+pub(crate) fn recover(
+    z: &BigUint,
+    r: &BigUint,
+    s: &BigUint,
+    is_odd: bool,
+) -> Option<AffinePoint<Secp256k1>> {
+    let setup = RecoverySetup::new(z, r, s, is_odd)?;
+    let (mut acc, mut is_infinity) = (Secp256k1::generator(), true);
+    for i in (0..NUM_SCALAR_BITS).rev() {
+        (acc, is_infinity) = recovery_step(
+            &setup,
+            &acc,
+            is_infinity,
+            setup.u1.bit(i as u64),
+            setup.u2.bit(i as u64),
+        )?;
+    }
+    (!is_infinity).then_some(acc)
+}
+
+/// The point `-G`.
+pub(crate) fn neg_generator() -> AffinePoint<Secp256k1> {
+    let (x, y) = Secp256k1Parameters::generator();
+    AffinePoint::new(x, Secp256k1BaseField::modulus() - y)
+}
+
+/// Returns the `y` coordinate with the given parity of the point with `x` coordinate `x`, if any.
+pub(crate) fn lift_x(x: &BigUint, is_odd: bool) -> Option<BigUint> {
+    let p = Secp256k1BaseField::modulus();
+    if x >= &p {
+        return None;
+    }
+    let rhs = (x * x * x + Secp256k1Parameters::b_int()) % &p;
+    // Since p = 3 mod 4, the square root of a quadratic residue is rhs^((p + 1) / 4).
+    let y = rhs.modpow(&((&p + BigUint::one()) >> 2), &p);
+    if (&y * &y) % &p != rhs {
+        return None;
+    }
+    if y.bit(0) == is_odd {
+        Some(y)
+    } else {
+        Some((&p - &y) % &p)
+    }
+}
+
+#[derive(Default)]
+pub struct Secp256k1EcrecoverChip;
+
+impl Secp256k1EcrecoverChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k256::ecdsa::SigningKey;
+
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::{self, run_test, SP1CoreOpts};
+
+    const PTR: u32 = 0x1000;
+
+    /// Converts big-endian bytes to little-endian words.
+    fn words_from_be_bytes(bytes: &[u8]) -> Vec<u32> {
+        let mut bytes = bytes.to_vec();
+        bytes.reverse();
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect()
+    }
+
+    fn ecrecover_program(input: &[u32], is_odd: bool) -> Program {
+        let instructions = vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::SECP256K1_ECRECOVER as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, is_odd as u32, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let mut program = Program::new(instructions, 0, 0);
+        for (i, word) in input.iter().enumerate() {
+            program.memory_image.insert(PTR + 4 * i as u32, *word);
+        }
+        program
+    }
+
+    #[test]
+    fn test_secp256k1_ecrecover() {
+        utils::setup_logger();
+        let signing_key = SigningKey::from_slice(&[0x2a; 32]).unwrap();
+        let msg_hash = [0x5c; 32];
+        let (signature, recovery_id) = signing_key.sign_prehash_recoverable(&msg_hash).unwrap();
+        let (r, s) = signature.split_bytes();
+
+        let mut input = words_from_be_bytes(&msg_hash);
+        input.extend(words_from_be_bytes(&r));
+        input.extend(words_from_be_bytes(&s));
+        let program = ecrecover_program(&input, recovery_id.is_y_odd());
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let output = (0..16)
+            .map(|i| runtime.word(PTR + 4 * i))
+            .collect::<Vec<_>>();
+
+        let pubkey = signing_key.verifying_key().to_encoded_point(false);
+        let mut expected = words_from_be_bytes(pubkey.x().unwrap());
+        expected.extend(words_from_be_bytes(pubkey.y().unwrap()));
+        assert_eq!(output, expected);
+
+        run_test(program).unwrap();
+    }
+}
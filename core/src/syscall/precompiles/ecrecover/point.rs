@@ -0,0 +1,475 @@
+use num::BigUint;
+use p3_field::PrimeField32;
+use sp1_derive::AlignedBorrow;
+
+use crate::air::{Polynomial, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
+use crate::operations::field::params::{FieldParameters, Limbs, NumLimbs};
+use crate::utils::ec::weierstrass::secp256k1::{Secp256k1, Secp256k1BaseField};
+use crate::utils::ec::AffinePoint;
+
+type LimbsOf<T> = Limbs<T, <Secp256k1BaseField as NumLimbs>::Limbs>;
+
+/// A set of columns to compute the sum of two points of secp256k1 with distinct `x` coordinates.
+///
+/// When `is_distinct` is set, the columns also assert that the `x` coordinates are distinct by
+/// inverting their difference, so that the slope is unique.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct AffineAddCols<T> {
+    pub(crate) slope_numerator: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) slope_denominator: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) slope_denominator_inverse: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) slope: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) slope_squared: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) p_x_plus_q_x: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) x3_ins: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) p_x_minus_x: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) slope_times_p_x_minus_x: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) y3_ins: FieldOpCols<T, Secp256k1BaseField>,
+}
+
+/// A set of columns to compute the double of a point of secp256k1.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct AffineDoubleCols<T> {
+    pub(crate) p_x_squared: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) slope_numerator: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) slope_denominator: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) slope: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) slope_squared: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) p_x_plus_p_x: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) x3_ins: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) p_x_minus_x: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) slope_times_p_x_minus_x: FieldOpCols<T, Secp256k1BaseField>,
+    pub(crate) y3_ins: FieldOpCols<T, Secp256k1BaseField>,
+}
+
+impl<F: PrimeField32> AffineAddCols<F> {
+    pub fn populate(
+        &mut self,
+        record: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        p: &AffinePoint<Secp256k1>,
+        q: &AffinePoint<Secp256k1>,
+        is_distinct: bool,
+    ) -> AffinePoint<Secp256k1> {
+        // slope = (q.y - p.y) / (q.x - p.x).
+        let slope = {
+            let slope_numerator = self.slope_numerator.populate(
+                record,
+                shard,
+                channel,
+                &q.y,
+                &p.y,
+                FieldOperation::Sub,
+            );
+            let slope_denominator = self.slope_denominator.populate(
+                record,
+                shard,
+                channel,
+                &q.x,
+                &p.x,
+                FieldOperation::Sub,
+            );
+            self.slope_denominator_inverse.populate(
+                record,
+                shard,
+                channel,
+                &BigUint::from(is_distinct as u32),
+                &slope_denominator,
+                FieldOperation::Div,
+            );
+            self.slope.populate(
+                record,
+                shard,
+                channel,
+                &slope_numerator,
+                &slope_denominator,
+                FieldOperation::Div,
+            )
+        };
+
+        // x = slope * slope - (p.x + q.x).
+        let x = {
+            let slope_squared = self.slope_squared.populate(
+                record,
+                shard,
+                channel,
+                &slope,
+                &slope,
+                FieldOperation::Mul,
+            );
+            let p_x_plus_q_x =
+                self.p_x_plus_q_x
+                    .populate(record, shard, channel, &p.x, &q.x, FieldOperation::Add);
+            self.x3_ins.populate(
+                record,
+                shard,
+                channel,
+                &slope_squared,
+                &p_x_plus_q_x,
+                FieldOperation::Sub,
+            )
+        };
+
+        // y = slope * (p.x - x) - p.y.
+        let y = {
+            let p_x_minus_x =
+                self.p_x_minus_x
+                    .populate(record, shard, channel, &p.x, &x, FieldOperation::Sub);
+            let slope_times_p_x_minus_x = self.slope_times_p_x_minus_x.populate(
+                record,
+                shard,
+                channel,
+                &slope,
+                &p_x_minus_x,
+                FieldOperation::Mul,
+            );
+            self.y3_ins.populate(
+                record,
+                shard,
+                channel,
+                &slope_times_p_x_minus_x,
+                &p.y,
+                FieldOperation::Sub,
+            )
+        };
+
+        AffinePoint::new(x, y)
+    }
+}
+
+impl<F: PrimeField32> AffineDoubleCols<F> {
+    pub fn populate(
+        &mut self,
+        record: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        p: &AffinePoint<Secp256k1>,
+    ) -> AffinePoint<Secp256k1> {
+        // slope = 3 * p.x * p.x / (2 * p.y), as a = 0 for secp256k1.
+        let slope = {
+            let p_x_squared =
+                self.p_x_squared
+                    .populate(record, shard, channel, &p.x, &p.x, FieldOperation::Mul);
+            let slope_numerator = self.slope_numerator.populate(
+                record,
+                shard,
+                channel,
+                &p_x_squared,
+                &BigUint::from(3u32),
+                FieldOperation::Mul,
+            );
+            let slope_denominator = self.slope_denominator.populate(
+                record,
+                shard,
+                channel,
+                &BigUint::from(2u32),
+                &p.y,
+                FieldOperation::Mul,
+            );
+            self.slope.populate(
+                record,
+                shard,
+                channel,
+                &slope_numerator,
+                &slope_denominator,
+                FieldOperation::Div,
+            )
+        };
+
+        // x = slope * slope - (p.x + p.x).
+        let x = {
+            let slope_squared = self.slope_squared.populate(
+                record,
+                shard,
+                channel,
+                &slope,
+                &slope,
+                FieldOperation::Mul,
+            );
+            let p_x_plus_p_x =
+                self.p_x_plus_p_x
+                    .populate(record, shard, channel, &p.x, &p.x, FieldOperation::Add);
+            self.x3_ins.populate(
+                record,
+                shard,
+                channel,
+                &slope_squared,
+                &p_x_plus_p_x,
+                FieldOperation::Sub,
+            )
+        };
+
+        // y = slope * (p.x - x) - p.y.
+        let y = {
+            let p_x_minus_x =
+                self.p_x_minus_x
+                    .populate(record, shard, channel, &p.x, &x, FieldOperation::Sub);
+            let slope_times_p_x_minus_x = self.slope_times_p_x_minus_x.populate(
+                record,
+                shard,
+                channel,
+                &slope,
+                &p_x_minus_x,
+                FieldOperation::Mul,
+            );
+            self.y3_ins.populate(
+                record,
+                shard,
+                channel,
+                &slope_times_p_x_minus_x,
+                &p.y,
+                FieldOperation::Sub,
+            )
+        };
+
+        AffinePoint::new(x, y)
+    }
+}
+
+impl<V: Copy> AffineAddCols<V>
+where
+    LimbsOf<V>: Copy,
+{
+    /// The coordinates of the sum.
+    pub fn result(&self) -> (LimbsOf<V>, LimbsOf<V>) {
+        (self.x3_ins.result, self.y3_ins.result)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn eval<AB: SP1AirBuilder<Var = V>>(
+        &self,
+        builder: &mut AB,
+        p_x: &(impl Into<Polynomial<AB::Expr>> + Clone),
+        p_y: &(impl Into<Polynomial<AB::Expr>> + Clone),
+        q_x: &(impl Into<Polynomial<AB::Expr>> + Clone),
+        q_y: &(impl Into<Polynomial<AB::Expr>> + Clone),
+        is_distinct: impl Into<AB::Expr>,
+        shard: V,
+        channel: V,
+        is_real: V,
+    ) where
+        V: Into<AB::Expr>,
+    {
+        // slope = (q.y - p.y) / (q.x - p.x).
+        self.slope_numerator.eval(
+            builder,
+            q_y,
+            p_y,
+            FieldOperation::Sub,
+            shard,
+            channel,
+            is_real,
+        );
+        self.slope_denominator.eval(
+            builder,
+            q_x,
+            p_x,
+            FieldOperation::Sub,
+            shard,
+            channel,
+            is_real,
+        );
+        // If `is_distinct` is set, the denominator has an inverse, so it is nonzero.
+        let is_distinct: [AB::Expr; 1] = [is_distinct.into()];
+        self.slope_denominator_inverse.eval(
+            builder,
+            &is_distinct.iter(),
+            &self.slope_denominator.result,
+            FieldOperation::Div,
+            shard,
+            channel,
+            is_real,
+        );
+        self.slope.eval(
+            builder,
+            &self.slope_numerator.result,
+            &self.slope_denominator.result,
+            FieldOperation::Div,
+            shard,
+            channel,
+            is_real,
+        );
+
+        // x = slope * slope - (p.x + q.x).
+        self.slope_squared.eval(
+            builder,
+            &self.slope.result,
+            &self.slope.result,
+            FieldOperation::Mul,
+            shard,
+            channel,
+            is_real,
+        );
+        self.p_x_plus_q_x.eval(
+            builder,
+            p_x,
+            q_x,
+            FieldOperation::Add,
+            shard,
+            channel,
+            is_real,
+        );
+        self.x3_ins.eval(
+            builder,
+            &self.slope_squared.result,
+            &self.p_x_plus_q_x.result,
+            FieldOperation::Sub,
+            shard,
+            channel,
+            is_real,
+        );
+
+        // y = slope * (p.x - x) - p.y.
+        self.p_x_minus_x.eval(
+            builder,
+            p_x,
+            &self.x3_ins.result,
+            FieldOperation::Sub,
+            shard,
+            channel,
+            is_real,
+        );
+        self.slope_times_p_x_minus_x.eval(
+            builder,
+            &self.slope.result,
+            &self.p_x_minus_x.result,
+            FieldOperation::Mul,
+            shard,
+            channel,
+            is_real,
+        );
+        self.y3_ins.eval(
+            builder,
+            &self.slope_times_p_x_minus_x.result,
+            p_y,
+            FieldOperation::Sub,
+            shard,
+            channel,
+            is_real,
+        );
+    }
+}
+
+impl<V: Copy> AffineDoubleCols<V>
+where
+    LimbsOf<V>: Copy,
+{
+    /// The coordinates of the double.
+    pub fn result(&self) -> (LimbsOf<V>, LimbsOf<V>) {
+        (self.x3_ins.result, self.y3_ins.result)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn eval<AB: SP1AirBuilder<Var = V>>(
+        &self,
+        builder: &mut AB,
+        p_x: &(impl Into<Polynomial<AB::Expr>> + Clone),
+        p_y: &(impl Into<Polynomial<AB::Expr>> + Clone),
+        shard: V,
+        channel: V,
+        is_real: V,
+    ) where
+        V: Into<AB::Expr>,
+    {
+        // slope = 3 * p.x * p.x / (2 * p.y).
+        self.p_x_squared.eval(
+            builder,
+            p_x,
+            p_x,
+            FieldOperation::Mul,
+            shard,
+            channel,
+            is_real,
+        );
+        self.slope_numerator.eval(
+            builder,
+            &self.p_x_squared.result,
+            &Secp256k1BaseField::to_limbs_field::<AB::Expr, _>(&BigUint::from(3u32)),
+            FieldOperation::Mul,
+            shard,
+            channel,
+            is_real,
+        );
+        self.slope_denominator.eval(
+            builder,
+            &Secp256k1BaseField::to_limbs_field::<AB::Expr, _>(&BigUint::from(2u32)),
+            p_y,
+            FieldOperation::Mul,
+            shard,
+            channel,
+            is_real,
+        );
+        self.slope.eval(
+            builder,
+            &self.slope_numerator.result,
+            &self.slope_denominator.result,
+            FieldOperation::Div,
+            shard,
+            channel,
+            is_real,
+        );
+
+        // x = slope * slope - (p.x + p.x).
+        self.slope_squared.eval(
+            builder,
+            &self.slope.result,
+            &self.slope.result,
+            FieldOperation::Mul,
+            shard,
+            channel,
+            is_real,
+        );
+        self.p_x_plus_p_x.eval(
+            builder,
+            p_x,
+            p_x,
+            FieldOperation::Add,
+            shard,
+            channel,
+            is_real,
+        );
+        self.x3_ins.eval(
+            builder,
+            &self.slope_squared.result,
+            &self.p_x_plus_p_x.result,
+            FieldOperation::Sub,
+            shard,
+            channel,
+            is_real,
+        );
+
+        // y = slope * (p.x - x) - p.y.
+        self.p_x_minus_x.eval(
+            builder,
+            p_x,
+            &self.x3_ins.result,
+            FieldOperation::Sub,
+            shard,
+            channel,
+            is_real,
+        );
+        self.slope_times_p_x_minus_x.eval(
+            builder,
+            &self.slope.result,
+            &self.p_x_minus_x.result,
+            FieldOperation::Mul,
+            shard,
+            channel,
+            is_real,
+        );
+        self.y3_ins.eval(
+            builder,
+            &self.slope_times_p_x_minus_x.result,
+            p_y,
+            FieldOperation::Sub,
+            shard,
+            channel,
+            is_real,
+        );
+    }
+}
@@ -0,0 +1,317 @@
+use std::borrow::BorrowMut;
+
+use num::BigUint;
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+
+use super::columns::{Secp256k1EcrecoverCols, NUM_SECP256K1_ECRECOVER_COLS};
+use super::{
+    neg_generator, RecoverySetup, Secp256k1EcrecoverChip, NUM_OUTPUT_WORDS, NUM_SCALAR_BITS,
+    NUM_SCALAR_WORDS, ROW_COUNTER_LEN,
+};
+use crate::air::MachineAir;
+use crate::bytes::event::ByteRecord;
+use crate::operations::field::field_op::FieldOperation;
+use crate::operations::field::params::FieldParameters;
+use crate::runtime::{ExecutionRecord, Program};
+use crate::utils::ec::weierstrass::secp256k1::{
+    Secp256k1, Secp256k1BaseField, Secp256k1Parameters,
+};
+use crate::utils::ec::weierstrass::WeierstrassParameters;
+use crate::utils::ec::AffinePoint;
+use crate::utils::pad_rows;
+
+impl<F: PrimeField32> MachineAir<F> for Secp256k1EcrecoverChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Secp256k1Ecrecover".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut rows = Vec::new();
+
+        let mut new_byte_lookup_events = Vec::new();
+
+        for event in input.secp256k1_ecrecover_events.iter() {
+            let shard = event.shard;
+            let channel = event.channel;
+            let input_words = event
+                .input_reads
+                .iter()
+                .map(|record| record.value)
+                .collect::<Vec<_>>();
+            let [z, r, s] = [0, 1, 2].map(|i| {
+                BigUint::from_slice(&input_words[i * NUM_SCALAR_WORDS..(i + 1) * NUM_SCALAR_WORDS])
+            });
+            let setup = RecoverySetup::new(&z, &r, &s, event.is_odd)
+                .expect("the event has a valid signature");
+
+            let mut acc = Secp256k1::generator();
+            let mut is_infinity = true;
+            for row_index in 0..NUM_SCALAR_BITS {
+                let mut row = vec![F::zero(); NUM_SECP256K1_ECRECOVER_COLS];
+                let cols: &mut Secp256k1EcrecoverCols<F> = row.as_mut_slice().borrow_mut();
+                let is_last_row = row_index + 1 == NUM_SCALAR_BITS;
+
+                // Assign basic values to the columns.
+                {
+                    cols.is_real = F::one();
+                    cols.shard = F::from_canonical_u32(shard);
+                    cols.channel = F::from_canonical_u32(channel);
+                    cols.clk = F::from_canonical_u32(event.clk);
+                    cols.ptr = F::from_canonical_u32(event.ptr);
+                    cols.is_odd = F::from_bool(event.is_odd);
+                    cols.is_row_hi[row_index / ROW_COUNTER_LEN] = F::one();
+                    cols.is_row_lo[row_index % ROW_COUNTER_LEN] = F::one();
+                    cols.ecall_receive = F::from_bool(row_index == 0);
+                    cols.is_last_row = F::from_bool(is_last_row);
+                }
+
+                // Memory columns.
+                {
+                    if row_index == 0 {
+                        for (access, record) in
+                            cols.input_access.iter_mut().zip(event.input_reads.iter())
+                        {
+                            access.populate(channel, *record, &mut new_byte_lookup_events);
+                        }
+                    }
+                    if is_last_row {
+                        for (access, record) in cols
+                            .output_access
+                            .iter_mut()
+                            .zip(event.output_writes.iter())
+                        {
+                            access.populate(channel, *record, &mut new_byte_lookup_events);
+                        }
+                    }
+                }
+
+                Self::populate_setup(
+                    cols,
+                    &mut new_byte_lookup_events,
+                    shard,
+                    channel,
+                    [&z, &r, &s],
+                    event.is_odd,
+                    &setup,
+                    true,
+                );
+
+                // The bits of the current row are the most significant ones.
+                for i in row_index..NUM_SCALAR_BITS {
+                    let bit = (i - row_index) as u64;
+                    cols.u1_bits[i] = F::from_bool(setup.u1.bit(bit));
+                    cols.u2_bits[i] = F::from_bool(setup.u2.bit(bit));
+                }
+
+                (acc, is_infinity) = Self::populate_step(
+                    cols,
+                    &mut new_byte_lookup_events,
+                    shard,
+                    channel,
+                    &setup,
+                    &acc,
+                    is_infinity,
+                );
+
+                if is_last_row {
+                    assert!(!is_infinity);
+                    let output_words = event
+                        .output_writes
+                        .iter()
+                        .map(|record| record.value)
+                        .collect::<Vec<_>>();
+                    assert_eq!(output_words.len(), NUM_OUTPUT_WORDS);
+                    assert_eq!(acc.to_words_le(), output_words);
+                }
+
+                rows.push(row);
+            }
+        }
+
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        // The padding rows compute a recovery step with `R = 2G` and zero scalars, so that all
+        // the field operations are satisfied. Their byte lookups are discarded.
+        let dummy_row = {
+            let mut row = vec![F::zero(); NUM_SECP256K1_ECRECOVER_COLS];
+            let cols: &mut Secp256k1EcrecoverCols<F> = row.as_mut_slice().borrow_mut();
+            let scalar = BigUint::from(0u32);
+            let r_point = Secp256k1::generator().sw_double();
+            let is_odd = r_point.y.bit(0);
+            let setup = RecoverySetup::new(&scalar, &r_point.x, &scalar, is_odd)
+                .expect("the dummy signature is valid");
+            cols.is_odd = F::from_bool(is_odd);
+            Self::populate_setup(
+                cols,
+                &mut vec![],
+                0,
+                0,
+                [&scalar, &r_point.x, &scalar],
+                is_odd,
+                &setup,
+                false,
+            );
+            Self::populate_step(
+                cols,
+                &mut vec![],
+                0,
+                0,
+                &setup,
+                &Secp256k1::generator(),
+                true,
+            );
+            row
+        };
+        pad_rows(&mut rows, || dummy_row.clone());
+
+        // Convert the trace to a row major matrix.
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_SECP256K1_ECRECOVER_COLS,
+        )
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.secp256k1_ecrecover_events.is_empty()
+    }
+}
+
+impl Secp256k1EcrecoverChip {
+    /// Populates the columns that lift `r` and compute the scalars, which are the same on every
+    /// row of a syscall.
+    #[allow(clippy::too_many_arguments)]
+    fn populate_setup<F: PrimeField32>(
+        cols: &mut Secp256k1EcrecoverCols<F>,
+        record: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        [z, r, s]: [&BigUint; 3],
+        is_odd: bool,
+        setup: &RecoverySetup,
+        is_real: bool,
+    ) {
+        cols.z = Secp256k1BaseField::to_limbs_field(z);
+        cols.r = Secp256k1BaseField::to_limbs_field(r);
+        cols.s = Secp256k1BaseField::to_limbs_field(s);
+
+        // R = (r, sqrt(r^3 + 7)).
+        cols.range_r.populate(record, shard, channel, r);
+        let r_squared = cols
+            .r_squared
+            .populate(record, shard, channel, r, r, FieldOperation::Mul);
+        let r_cubed =
+            cols.r_cubed
+                .populate(record, shard, channel, &r_squared, r, FieldOperation::Mul);
+        let r_cubed_plus_b = cols.r_cubed_plus_b.populate(
+            record,
+            shard,
+            channel,
+            &r_cubed,
+            &Secp256k1Parameters::b_int(),
+            FieldOperation::Add,
+        );
+        let r_y = cols
+            .r_y
+            .populate(record, shard, channel, &r_cubed_plus_b, |_| {
+                setup.r_point.y.clone()
+            });
+        assert_eq!(r_y.bit(0), is_odd);
+
+        // R - G, whose `x` coordinates are asserted to be distinct on real rows.
+        let r_minus_g = cols.r_minus_g.populate(
+            record,
+            shard,
+            channel,
+            &setup.r_point,
+            &neg_generator(),
+            is_real,
+        );
+        assert_eq!(r_minus_g, setup.r_minus_g);
+
+        // The scalars, modulo the group order.
+        let n = Secp256k1Parameters::prime_group_order();
+        cols.r_inv = Secp256k1BaseField::to_limbs_field(&setup.r_inv);
+        record.add_u8_range_checks_field(shard, channel, &cols.r_inv.0);
+        let one = cols.r_times_r_inv.populate_with_modulus(
+            record,
+            shard,
+            channel,
+            r,
+            &setup.r_inv,
+            &n,
+            FieldOperation::Mul,
+        );
+        assert_eq!(one, BigUint::from(1u32));
+        cols.u1.populate_with_modulus(
+            record,
+            shard,
+            channel,
+            z,
+            &setup.r_inv,
+            &n,
+            FieldOperation::Mul,
+        );
+        cols.u2.populate_with_modulus(
+            record,
+            shard,
+            channel,
+            s,
+            &setup.r_inv,
+            &n,
+            FieldOperation::Mul,
+        );
+    }
+
+    /// Populates the double-and-add of the row, whose bits are the most significant ones of
+    /// `u1_bits` and `u2_bits`. Returns the accumulator and the infinity flag after the row.
+    fn populate_step<F: PrimeField32>(
+        cols: &mut Secp256k1EcrecoverCols<F>,
+        record: &mut impl ByteRecord,
+        shard: u32,
+        channel: u32,
+        setup: &RecoverySetup,
+        acc: &AffinePoint<Secp256k1>,
+        is_infinity: bool,
+    ) -> (AffinePoint<Secp256k1>, bool) {
+        let u1_bit = cols.u1_bits[NUM_SCALAR_BITS - 1] == F::one();
+        let u2_bit = cols.u2_bits[NUM_SCALAR_BITS - 1] == F::one();
+        let any_bit = u1_bit || u2_bit;
+        let use_sum = any_bit && !is_infinity;
+
+        cols.acc_x = Secp256k1BaseField::to_limbs_field(&acc.x);
+        cols.acc_y = Secp256k1BaseField::to_limbs_field(&acc.y);
+        cols.is_infinity = F::from_bool(is_infinity);
+        cols.both_bits = F::from_bool(u1_bit && u2_bit);
+        cols.use_sum = F::from_bool(use_sum);
+
+        let doubled = cols.double.populate(record, shard, channel, acc);
+        let addend = setup.addend(u1_bit, u2_bit);
+        cols.addend_x = Secp256k1BaseField::to_limbs_field(&addend.x);
+        cols.addend_y = Secp256k1BaseField::to_limbs_field(&addend.y);
+        let sum = cols
+            .add
+            .populate(record, shard, channel, &doubled, &addend, use_sum);
+
+        let new_acc = match (any_bit, use_sum) {
+            (false, _) => doubled,
+            (true, false) => addend,
+            (true, true) => sum,
+        };
+        cols.new_acc_x = Secp256k1BaseField::to_limbs_field(&new_acc.x);
+        cols.new_acc_y = Secp256k1BaseField::to_limbs_field(&new_acc.y);
+        cols.range_new_acc_x
+            .populate(record, shard, channel, &new_acc.x);
+        cols.range_new_acc_y
+            .populate(record, shard, channel, &new_acc.y);
+
+        (new_acc, is_infinity && !any_bit)
+    }
+}
@@ -0,0 +1,338 @@
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+use std::marker::PhantomData;
+
+use generic_array::GenericArray;
+use num::{BigUint, Zero};
+use p3_air::{Air, BaseAir};
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+use typenum::Unsigned;
+
+use super::{FieldType, FpOpField};
+use crate::air::{BaseAirBuilder, MachineAir, Polynomial, SP1AirBuilder};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{value_as_limbs, MemoryReadCols, MemoryWriteCols};
+use crate::operations::field::field_op::{FieldOpCols, FieldOperation};
+use crate::operations::field::params::{FieldParameters, Limbs, NumLimbs, NumWords};
+use crate::runtime::{ExecutionRecord, Program, Syscall, SyscallCode};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::{
+    bytes_to_words_le_vec, limbs_from_access, limbs_from_prev_access, pad_rows,
+    words_to_bytes_le_vec,
+};
+
+pub const fn num_fp_op_cols<P: FieldParameters + NumWords>() -> usize {
+    size_of::<FpOpCols<u8, P>>()
+}
+
+/// Emulated field arithmetic event, computing `x = x op y` in the base field `P`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FpOpEvent {
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub x_ptr: u32,
+    pub x: Vec<u32>,
+    pub y_ptr: u32,
+    pub y: Vec<u32>,
+    pub op: FieldOperation,
+    pub x_memory_records: Vec<MemoryWriteRecord>,
+    pub y_memory_records: Vec<MemoryReadRecord>,
+}
+
+/// The syscall computing `x = x op y` for a single operation over the base field `P`.
+pub struct FpOpSyscall<P> {
+    op: FieldOperation,
+    _marker: PhantomData<P>,
+}
+
+impl<P> FpOpSyscall<P> {
+    pub const fn new(op: FieldOperation) -> Self {
+        Self {
+            op,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<P: FpOpField> Syscall for FpOpSyscall<P> {
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let start_clk = rt.clk;
+        let x_ptr = arg1;
+        if x_ptr % 4 != 0 {
+            panic!();
+        }
+        let y_ptr = arg2;
+        if y_ptr % 4 != 0 {
+            panic!();
+        }
+
+        let num_words = <P as NumWords>::WordsFieldElement::USIZE;
+
+        // We can read x with slice_unsafe because we write the computed result to x later.
+        let x = rt.slice_unsafe(x_ptr, num_words);
+        let (y_memory_records, y) = rt.mr_slice(y_ptr, num_words);
+
+        // When we write to x, we want the clk to be incremented because x and y could be the same.
+        rt.clk += 1;
+
+        let modulus = P::modulus();
+        let a = BigUint::from_bytes_le(&words_to_bytes_le_vec(&x));
+        let b = BigUint::from_bytes_le(&words_to_bytes_le_vec(&y));
+        assert!(
+            a < modulus && b < modulus,
+            "fp operands must be reduced modulo the field modulus"
+        );
+        let result = match self.op {
+            FieldOperation::Add => (a + b) % &modulus,
+            FieldOperation::Sub => (a + &modulus - b) % &modulus,
+            FieldOperation::Mul => (a * b) % &modulus,
+            FieldOperation::Div => panic!("fp division is not supported"),
+        };
+
+        let mut result_bytes = result.to_bytes_le();
+        result_bytes.resize(P::NB_LIMBS, 0u8);
+        let result = bytes_to_words_le_vec(&result_bytes);
+
+        let x_memory_records = rt.mw_slice(x_ptr, &result);
+
+        let event = FpOpEvent {
+            shard: rt.current_shard(),
+            channel: rt.current_channel(),
+            clk: start_clk,
+            x_ptr,
+            x,
+            y_ptr,
+            y,
+            op: self.op,
+            x_memory_records,
+            y_memory_records,
+        };
+        match P::FIELD_TYPE {
+            FieldType::Bls12381 => rt.record_mut().bls12381_fp_events.push(event),
+        }
+
+        None
+    }
+
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+}
+
+/// A set of columns for the emulated field arithmetic operations.
+#[derive(Debug, Clone, AlignedBorrow)]
+#[repr(C)]
+pub struct FpOpCols<T, P: FieldParameters + NumWords> {
+    pub is_real: T,
+    pub is_add: T,
+    pub is_sub: T,
+    pub is_mul: T,
+    pub shard: T,
+    pub channel: T,
+    pub clk: T,
+    pub x_ptr: T,
+    pub y_ptr: T,
+    pub x_access: GenericArray<MemoryWriteCols<T>, P::WordsFieldElement>,
+    pub y_access: GenericArray<MemoryReadCols<T>, P::WordsFieldElement>,
+    pub(crate) output: FieldOpCols<T, P>,
+}
+
+/// A chip for the addition, subtraction and multiplication syscalls over the base field `P`.
+#[derive(Default)]
+pub struct FpOpChip<P> {
+    _marker: PhantomData<P>,
+}
+
+impl<P: FpOpField> FpOpChip<P> {
+    pub const fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+
+    fn syscall_codes() -> [SyscallCode; 3] {
+        match P::FIELD_TYPE {
+            FieldType::Bls12381 => [
+                SyscallCode::BLS12381_FP_ADD,
+                SyscallCode::BLS12381_FP_SUB,
+                SyscallCode::BLS12381_FP_MUL,
+            ],
+        }
+    }
+}
+
+impl<F: PrimeField32, P: FpOpField> MachineAir<F> for FpOpChip<P> {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        match P::FIELD_TYPE {
+            FieldType::Bls12381 => "Bls12381FpOp".to_string(),
+        }
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let events = match P::FIELD_TYPE {
+            FieldType::Bls12381 => &input.bls12381_fp_events,
+        };
+
+        let mut rows = Vec::new();
+        let mut new_byte_lookup_events = Vec::new();
+
+        for event in events.iter() {
+            let mut row = vec![F::zero(); num_fp_op_cols::<P>()];
+            let cols: &mut FpOpCols<F, P> = row.as_mut_slice().borrow_mut();
+
+            let x = BigUint::from_bytes_le(&words_to_bytes_le_vec(&event.x));
+            let y = BigUint::from_bytes_le(&words_to_bytes_le_vec(&event.y));
+
+            cols.is_real = F::one();
+            cols.is_add = F::from_bool(event.op == FieldOperation::Add);
+            cols.is_sub = F::from_bool(event.op == FieldOperation::Sub);
+            cols.is_mul = F::from_bool(event.op == FieldOperation::Mul);
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.channel = F::from_canonical_u32(event.channel);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.x_ptr = F::from_canonical_u32(event.x_ptr);
+            cols.y_ptr = F::from_canonical_u32(event.y_ptr);
+
+            cols.output.populate(
+                &mut new_byte_lookup_events,
+                event.shard,
+                event.channel,
+                &x,
+                &y,
+                event.op,
+            );
+
+            for i in 0..cols.y_access.len() {
+                cols.y_access[i].populate(
+                    event.channel,
+                    event.y_memory_records[i],
+                    &mut new_byte_lookup_events,
+                );
+            }
+            for i in 0..cols.x_access.len() {
+                cols.x_access[i].populate(
+                    event.channel,
+                    event.x_memory_records[i],
+                    &mut new_byte_lookup_events,
+                );
+            }
+
+            rows.push(row);
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || {
+            let mut row = vec![F::zero(); num_fp_op_cols::<P>()];
+            let cols: &mut FpOpCols<F, P> = row.as_mut_slice().borrow_mut();
+            let zero = BigUint::zero();
+            cols.output
+                .populate(&mut vec![], 0, 0, &zero, &zero, FieldOperation::Add);
+            row
+        });
+
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            num_fp_op_cols::<P>(),
+        )
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        match P::FIELD_TYPE {
+            FieldType::Bls12381 => !shard.bls12381_fp_events.is_empty(),
+        }
+    }
+}
+
+impl<F, P: FpOpField> BaseAir<F> for FpOpChip<P> {
+    fn width(&self) -> usize {
+        num_fp_op_cols::<P>()
+    }
+}
+
+impl<AB, P: FpOpField> Air<AB> for FpOpChip<P>
+where
+    AB: SP1AirBuilder,
+    Limbs<AB::Var, <P as NumLimbs>::Limbs>: Copy,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &FpOpCols<AB::Var, P> = (*local).borrow();
+
+        // Exactly one of the operation flags is set on real rows, and none on padding rows.
+        builder.assert_bool(local.is_add);
+        builder.assert_bool(local.is_sub);
+        builder.assert_bool(local.is_mul);
+        builder.assert_bool(local.is_real);
+        builder.assert_eq(local.is_real, local.is_add + local.is_sub + local.is_mul);
+
+        // The value of x is stored in the "prev_value" of x_access, since we write to it later.
+        let x: Limbs<AB::Var, <P as NumLimbs>::Limbs> = limbs_from_prev_access(&local.x_access);
+        let y: Limbs<AB::Var, <P as NumLimbs>::Limbs> = limbs_from_access(&local.y_access);
+        let p_modulus: Polynomial<AB::Expr> =
+            Polynomial::from_iter(P::modulus_field_iter::<AB::F>().map(AB::Expr::from));
+
+        local.output.eval_variable(
+            builder,
+            &x,
+            &y,
+            &p_modulus,
+            local.is_add,
+            local.is_sub,
+            local.is_mul,
+            local.shard,
+            local.channel,
+            local.is_real,
+        );
+
+        // Assert that the correct result is being written to x_access.
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.output.result, value_as_limbs(&local.x_access));
+
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.y_ptr,
+            &local.y_access,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::from_canonical_u32(1), // We write x at +1 since x, y could be the same.
+            local.x_ptr,
+            &local.x_access,
+            local.is_real,
+        );
+
+        let [add, sub, mul] = Self::syscall_codes();
+        let syscall_id = AB::Expr::from_canonical_u32(add.syscall_id()) * local.is_add
+            + AB::Expr::from_canonical_u32(sub.syscall_id()) * local.is_sub
+            + AB::Expr::from_canonical_u32(mul.syscall_id()) * local.is_mul;
+
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            syscall_id,
+            local.x_ptr,
+            local.y_ptr,
+            local.is_real,
+        );
+    }
+}
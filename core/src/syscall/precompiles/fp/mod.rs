@@ -0,0 +1,80 @@
+mod air;
+
+pub use air::*;
+
+use crate::operations::field::params::{FieldParameters, NumWords};
+use crate::utils::ec::weierstrass::bls12_381::Bls12381BaseField;
+
+/// The base fields with emulated arithmetic precompiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Bls12381,
+}
+
+/// A base field supported by the [FpOpChip].
+pub trait FpOpField: FieldParameters + NumWords {
+    const FIELD_TYPE: FieldType;
+}
+
+impl FpOpField for Bls12381BaseField {
+    const FIELD_TYPE: FieldType = FieldType::Bls12381;
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+
+    use crate::operations::field::params::FieldParameters;
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::ec::weierstrass::bls12_381::Bls12381BaseField;
+    use crate::utils::{self, bytes_to_words_le_vec, run_test, words_to_bytes_le_vec, SP1CoreOpts};
+
+    const X_PTR: u32 = 0x1000;
+    const Y_PTR: u32 = 0x2000;
+
+    fn fp_program<P: FieldParameters>(x: &BigUint, y: &BigUint, ops: &[SyscallCode]) -> Program {
+        let mut instructions = vec![];
+        for op in ops {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 5, 0, *op as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, X_PTR, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, Y_PTR, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        let mut program = Program::new(instructions, 0, 0);
+        for (ptr, value) in [(X_PTR, x), (Y_PTR, y)] {
+            for (i, word) in bytes_to_words_le_vec(&P::to_limbs(value))
+                .into_iter()
+                .enumerate()
+            {
+                program.memory_image.insert(ptr + 4 * i as u32, word);
+            }
+        }
+        program
+    }
+
+    #[test]
+    fn test_bls12381_fp_ops() {
+        utils::setup_logger();
+        let p = Bls12381BaseField::modulus();
+        let x = &p - 3u32;
+        let y = BigUint::from(7u32);
+        let ops = [
+            SyscallCode::BLS12381_FP_ADD,
+            SyscallCode::BLS12381_FP_MUL,
+            SyscallCode::BLS12381_FP_SUB,
+        ];
+        let program = fp_program::<Bls12381BaseField>(&x, &y, &ops);
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let words = (0..12)
+            .map(|i| runtime.word(X_PTR + 4 * i))
+            .collect::<Vec<_>>();
+        let result = BigUint::from_bytes_le(&words_to_bytes_le_vec(&words));
+        assert_eq!(result, ((&x + &y) * &y + &p - &y) % &p);
+
+        run_test(program).unwrap();
+    }
+}
@@ -0,0 +1,290 @@
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+
+use p3_air::{Air, BaseAir};
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+
+use super::{GF256_MAC_BYTES, GF256_MAC_INPUT_WORDS, GF256_MAC_WORDS};
+use crate::air::{MachineAir, SP1AirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::bytes::utils::gf256_mul;
+use crate::bytes::{ByteLookupEvent, ByteOpcode};
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::runtime::{
+    ExecutionRecord, MemoryReadRecord, MemoryWriteRecord, Program, Syscall, SyscallCode,
+};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+
+pub const NUM_GF256_MAC_COLS: usize = size_of::<Gf256MacCols<u8>>();
+
+/// GF(2^8) multiply-accumulate event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Gf256MacEvent {
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub acc_ptr: u32,
+    pub input_ptr: u32,
+    pub acc_records: [MemoryWriteRecord; GF256_MAC_WORDS],
+    pub input_records: [MemoryReadRecord; GF256_MAC_INPUT_WORDS],
+}
+
+/// A set of columns for a multiply-accumulate of a 64-byte vector over GF(2^8).
+///
+/// Each product of the coefficient by a byte of the data is looked up in the GF(2^8)
+/// multiplication table of the byte chip, and is added to the accumulator with an XOR lookup.
+#[derive(AlignedBorrow, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Gf256MacCols<T> {
+    pub shard: T,
+    pub channel: T,
+    pub clk: T,
+    pub acc_ptr: T,
+    pub input_ptr: T,
+
+    pub acc_memory: [MemoryWriteCols<T>; GF256_MAC_WORDS],
+
+    /// The coefficient, followed by the data.
+    pub input_memory: [MemoryReadCols<T>; GF256_MAC_INPUT_WORDS],
+
+    /// The products of the coefficient by the bytes of the data.
+    pub products: [T; GF256_MAC_BYTES],
+
+    pub is_real: T,
+}
+
+/// A chip for the `GF256_MAC` syscall, which adds the product of a coefficient by 64 bytes of
+/// data to a 64-byte accumulator, elementwise in GF(2^8).
+///
+/// The first argument points to the accumulator, and the second one to the coefficient, which is
+/// a word smaller than 256, followed by the 16 words of the data.
+#[derive(Default)]
+pub struct Gf256MacChip;
+
+impl Gf256MacChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for Gf256MacChip {
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let start_clk = rt.clk;
+        let acc_ptr = arg1;
+        if acc_ptr % 4 != 0 {
+            panic!();
+        }
+        let input_ptr = arg2;
+        if input_ptr % 4 != 0 {
+            panic!();
+        }
+
+        // We can read the accumulator with slice_unsafe because we write the result to it later.
+        let acc = rt.slice_unsafe(acc_ptr, GF256_MAC_WORDS);
+        let (input_records, input) = rt.mr_slice(input_ptr, GF256_MAC_INPUT_WORDS);
+        let coefficient = input[0];
+        assert!(coefficient < 256, "the coefficient is not a byte");
+
+        // When we write the accumulator, we want the clk to be incremented because it could
+        // overlap with the input.
+        rt.clk += 1;
+
+        let result = acc
+            .iter()
+            .zip(input[1..].iter())
+            .map(|(acc, data)| {
+                let bytes = core::array::from_fn::<u8, WORD_SIZE, _>(|i| {
+                    acc.to_le_bytes()[i] ^ gf256_mul(coefficient as u8, data.to_le_bytes()[i])
+                });
+                u32::from_le_bytes(bytes)
+            })
+            .collect::<Vec<_>>();
+        let acc_records = rt.mw_slice(acc_ptr, &result);
+
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut().gf256_mac_events.push(Gf256MacEvent {
+            shard,
+            channel,
+            clk: start_clk,
+            acc_ptr,
+            input_ptr,
+            acc_records: acc_records.try_into().unwrap(),
+            input_records: input_records.try_into().unwrap(),
+        });
+
+        None
+    }
+
+    fn num_extra_cycles(&self) -> u32 {
+        1
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for Gf256MacChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Gf256Mac".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut rows = Vec::new();
+        let mut new_byte_lookup_events = Vec::new();
+
+        for event in input.gf256_mac_events.iter() {
+            let mut row = vec![F::zero(); NUM_GF256_MAC_COLS];
+            let cols: &mut Gf256MacCols<F> = row.as_mut_slice().borrow_mut();
+
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.channel = F::from_canonical_u32(event.channel);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.acc_ptr = F::from_canonical_u32(event.acc_ptr);
+            cols.input_ptr = F::from_canonical_u32(event.input_ptr);
+            cols.is_real = F::one();
+
+            for i in 0..GF256_MAC_WORDS {
+                cols.acc_memory[i].populate(
+                    event.channel,
+                    event.acc_records[i],
+                    &mut new_byte_lookup_events,
+                );
+            }
+            for i in 0..GF256_MAC_INPUT_WORDS {
+                cols.input_memory[i].populate(
+                    event.channel,
+                    event.input_records[i],
+                    &mut new_byte_lookup_events,
+                );
+            }
+
+            let coefficient = event.input_records[0].value as u8;
+            for i in 0..GF256_MAC_BYTES {
+                let (word, byte) = (i / WORD_SIZE, i % WORD_SIZE);
+                let data = event.input_records[1 + word].value.to_le_bytes()[byte];
+                let prev_acc = event.acc_records[word].prev_value.to_le_bytes()[byte];
+                let product = gf256_mul(coefficient, data);
+                cols.products[i] = F::from_canonical_u8(product);
+
+                new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent::new(
+                    event.shard,
+                    event.channel,
+                    ByteOpcode::Gf256Mul,
+                    product as u32,
+                    0,
+                    coefficient as u32,
+                    data as u32,
+                ));
+                new_byte_lookup_events.add_byte_lookup_event(ByteLookupEvent::new(
+                    event.shard,
+                    event.channel,
+                    ByteOpcode::XOR,
+                    (prev_acc ^ product) as u32,
+                    0,
+                    prev_acc as u32,
+                    product as u32,
+                ));
+            }
+
+            rows.push(row);
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || vec![F::zero(); NUM_GF256_MAC_COLS]);
+
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_GF256_MAC_COLS,
+        )
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.gf256_mac_events.is_empty()
+    }
+}
+
+impl<F> BaseAir<F> for Gf256MacChip {
+    fn width(&self) -> usize {
+        NUM_GF256_MAC_COLS
+    }
+}
+
+impl<AB> Air<AB> for Gf256MacChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Gf256MacCols<AB::Var> = (*local).borrow();
+
+        builder.assert_bool(local.is_real);
+
+        // The coefficient is a byte.
+        let coefficient = local.input_memory[0].value().0;
+        for byte in coefficient[1..].iter() {
+            builder.assert_zero(*byte);
+        }
+
+        // Each byte of the accumulator is added the product of the coefficient by the data.
+        for i in 0..GF256_MAC_BYTES {
+            let (word, byte) = (i / WORD_SIZE, i % WORD_SIZE);
+            builder.send_byte(
+                ByteOpcode::Gf256Mul.as_field::<AB::F>(),
+                local.products[i],
+                coefficient[0],
+                local.input_memory[1 + word].value().0[byte],
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+            builder.send_byte(
+                ByteOpcode::XOR.as_field::<AB::F>(),
+                local.acc_memory[word].value().0[byte],
+                local.acc_memory[word].prev_value().0[byte],
+                local.products[i],
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+        }
+
+        // Read the input and write the accumulator at the next clk, since they could overlap.
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk.into(),
+            local.input_ptr,
+            &local.input_memory,
+            local.is_real,
+        );
+        builder.eval_memory_access_slice(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::from_canonical_u32(1),
+            local.acc_ptr,
+            &local.acc_memory,
+            local.is_real,
+        );
+
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            AB::F::from_canonical_u32(SyscallCode::GF256_MAC.syscall_id()),
+            local.acc_ptr,
+            local.input_ptr,
+            local.is_real,
+        );
+    }
+}
@@ -0,0 +1,87 @@
+mod mac;
+
+pub use mac::*;
+
+/// The number of words of the accumulator of a multiply-accumulate.
+pub const GF256_MAC_WORDS: usize = 16;
+
+/// The number of bytes of the accumulator of a multiply-accumulate.
+pub const GF256_MAC_BYTES: usize = GF256_MAC_WORDS * 4;
+
+/// The number of words of the input of a multiply-accumulate, which are the coefficient and the
+/// data.
+pub const GF256_MAC_INPUT_WORDS: usize = GF256_MAC_WORDS + 1;
+
+#[cfg(test)]
+mod tests {
+    use super::{GF256_MAC_BYTES, GF256_MAC_WORDS};
+    use crate::bytes::utils::gf256_mul;
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::{self, run_test, SP1CoreOpts};
+
+    const ACC_PTR: u32 = 0x1000;
+    const INPUT_PTR: u32 = 0x2000;
+
+    fn ecall(code: SyscallCode, arg1: u32, arg2: u32) -> Vec<Instruction> {
+        vec![
+            Instruction::new(Opcode::ADD, 5, 0, code as u32, false, true),
+            Instruction::new(Opcode::ADD, 10, 0, arg1, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, arg2, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]
+    }
+
+    fn insert_bytes(program: &mut Program, ptr: u32, bytes: &[u8]) {
+        for (i, chunk) in bytes.chunks_exact(4).enumerate() {
+            let word = u32::from_le_bytes(chunk.try_into().unwrap());
+            program.memory_image.insert(ptr + 4 * i as u32, word);
+        }
+    }
+
+    #[test]
+    fn test_gf256_mac() {
+        utils::setup_logger();
+        let acc: [u8; GF256_MAC_BYTES] =
+            core::array::from_fn(|i| (i as u8).wrapping_mul(29) ^ 0x5a);
+        let data: [u8; GF256_MAC_BYTES] =
+            core::array::from_fn(|i| (i as u8).wrapping_mul(83) ^ 0xc3);
+        let coefficient = 0x8e;
+
+        // Adds coefficient * data to the accumulator twice, which cancels out.
+        let mut instructions = ecall(SyscallCode::GF256_MAC, ACC_PTR, INPUT_PTR);
+        instructions.extend(ecall(SyscallCode::GF256_MAC, ACC_PTR, INPUT_PTR));
+        let mut program = Program::new(instructions.clone(), 0, 0);
+        insert_bytes(&mut program, ACC_PTR, &acc);
+        program.memory_image.insert(INPUT_PTR, coefficient as u32);
+        insert_bytes(&mut program, INPUT_PTR + 4, &data);
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let result = (0..GF256_MAC_WORDS as u32)
+            .flat_map(|i| runtime.word(ACC_PTR + 4 * i).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(result, acc);
+
+        run_test(program).unwrap();
+
+        // A single multiply-accumulate.
+        let mut program = Program::new(instructions[..4].to_vec(), 0, 0);
+        insert_bytes(&mut program, ACC_PTR, &acc);
+        program.memory_image.insert(INPUT_PTR, coefficient as u32);
+        insert_bytes(&mut program, INPUT_PTR + 4, &data);
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let result = (0..GF256_MAC_WORDS as u32)
+            .flat_map(|i| runtime.word(ACC_PTR + 4 * i).to_le_bytes())
+            .collect::<Vec<_>>();
+        let expected = acc
+            .iter()
+            .zip(data.iter())
+            .map(|(a, d)| a ^ gf256_mul(coefficient, *d))
+            .collect::<Vec<_>>();
+        assert_eq!(result, expected);
+
+        run_test(program).unwrap();
+    }
+}
@@ -0,0 +1,305 @@
+use core::borrow::{Borrow, BorrowMut};
+use core::mem::size_of;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::{AbstractField, PrimeField32};
+use p3_matrix::dense::RowMajorMatrix;
+use p3_matrix::Matrix;
+use serde::{Deserialize, Serialize};
+use sp1_derive::AlignedBorrow;
+
+use super::KeccakPermuteChip;
+use crate::air::{MachineAir, SP1AirBuilder, WordAirBuilder, WORD_SIZE};
+use crate::bytes::event::ByteRecord;
+use crate::memory::{MemoryCols, MemoryReadCols, MemoryWriteCols};
+use crate::operations::XorOperation;
+use crate::runtime::{
+    ExecutionRecord, MemoryReadRecord, MemoryWriteRecord, Program, Syscall, SyscallCode,
+};
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::pad_rows;
+
+/// The largest rate of the sponge in 64-bit lanes, which is the rate of SHAKE128.
+pub const MAX_RATE_LANES: usize = 21;
+
+/// The largest rate of the sponge in 32-bit words.
+const MAX_RATE_WORDS: usize = MAX_RATE_LANES * 2;
+
+pub const NUM_KECCAK_ABSORB_COLS: usize = size_of::<KeccakAbsorbCols<u8>>();
+
+/// Keccak sponge absorb event, XORing a block into the rate of the state before permuting it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeccakAbsorbEvent {
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub state_ptr: u32,
+    pub block_ptr: u32,
+    pub rate_record: MemoryReadRecord,
+    pub block_records: Vec<MemoryReadRecord>,
+    pub state_records: Vec<MemoryWriteRecord>,
+}
+
+/// A set of columns for the Keccak sponge absorb.
+#[derive(AlignedBorrow, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct KeccakAbsorbCols<T> {
+    pub shard: T,
+    pub channel: T,
+    pub clk: T,
+    pub state_ptr: T,
+
+    /// The pointer to the block, whose first word is the rate in lanes.
+    pub block_ptr: T,
+    pub rate_memory: MemoryReadCols<T>,
+
+    /// Whether the i-th word of the state is in the rate of the sponge.
+    pub is_absorbed: [T; MAX_RATE_WORDS],
+
+    pub block_memory: [MemoryReadCols<T>; MAX_RATE_WORDS],
+    pub state_memory: [MemoryWriteCols<T>; MAX_RATE_WORDS],
+    pub xor: [XorOperation<T>; MAX_RATE_WORDS],
+
+    pub is_real: T,
+}
+
+/// A chip for the `KECCAK_ABSORB` syscall, which absorbs one block of a Keccak sponge with any rate
+/// of up to [MAX_RATE_LANES] lanes. The permutation itself is proven by [KeccakPermuteChip], to
+/// which this chip sends a `KECCAK_PERMUTE` syscall.
+#[derive(Default)]
+pub struct KeccakAbsorbChip;
+
+impl KeccakAbsorbChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl Syscall for KeccakAbsorbChip {
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let start_clk = rt.clk;
+        let state_ptr = arg1;
+        if state_ptr % 4 != 0 {
+            panic!();
+        }
+        let block_ptr = arg2;
+        if block_ptr % 4 != 0 {
+            panic!();
+        }
+
+        let (rate_record, rate) = rt.mr(block_ptr);
+        assert!(
+            (1..=MAX_RATE_LANES as u32).contains(&rate),
+            "keccak absorb rate must be between 1 and {} lanes, got {}",
+            MAX_RATE_LANES,
+            rate
+        );
+        let num_words = 2 * rate as usize;
+
+        // We can read the state with slice_unsafe because we write the absorbed state to it.
+        let state = rt.slice_unsafe(state_ptr, num_words);
+        let (block_records, block) = rt.mr_slice(block_ptr + WORD_SIZE as u32, num_words);
+        let absorbed = state
+            .iter()
+            .zip(block.iter())
+            .map(|(s, b)| s ^ b)
+            .collect::<Vec<_>>();
+        let state_records = rt.mw_slice(state_ptr, &absorbed);
+
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut()
+            .keccak_absorb_events
+            .push(KeccakAbsorbEvent {
+                shard,
+                channel,
+                clk: start_clk,
+                state_ptr,
+                block_ptr,
+                rate_record,
+                block_records,
+                state_records,
+            });
+
+        // Permute the state on the next cycle, as if the guest had called `KECCAK_PERMUTE`.
+        rt.clk += 1;
+        KeccakPermuteChip::new().execute(rt, state_ptr, 0)
+    }
+
+    fn num_extra_cycles(&self) -> u32 {
+        // One cycle for the absorb, and the extra cycle of the permutation.
+        2
+    }
+}
+
+impl<F: PrimeField32> MachineAir<F> for KeccakAbsorbChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "KeccakAbsorb".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut rows = Vec::new();
+        let mut new_byte_lookup_events = Vec::new();
+
+        for event in input.keccak_absorb_events.iter() {
+            let mut row = [F::zero(); NUM_KECCAK_ABSORB_COLS];
+            let cols: &mut KeccakAbsorbCols<F> = row.as_mut_slice().borrow_mut();
+
+            cols.is_real = F::one();
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.channel = F::from_canonical_u32(event.channel);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.state_ptr = F::from_canonical_u32(event.state_ptr);
+            cols.block_ptr = F::from_canonical_u32(event.block_ptr);
+            cols.rate_memory.populate(
+                event.channel,
+                event.rate_record,
+                &mut new_byte_lookup_events,
+            );
+
+            for i in 0..event.block_records.len() {
+                cols.is_absorbed[i] = F::one();
+                cols.block_memory[i].populate(
+                    event.channel,
+                    event.block_records[i],
+                    &mut new_byte_lookup_events,
+                );
+                cols.state_memory[i].populate(
+                    event.channel,
+                    event.state_records[i],
+                    &mut new_byte_lookup_events,
+                );
+                cols.xor[i].populate(
+                    output,
+                    event.shard,
+                    event.channel,
+                    event.state_records[i].prev_value,
+                    event.block_records[i].value,
+                );
+            }
+
+            rows.push(row);
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_KECCAK_ABSORB_COLS]);
+
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_KECCAK_ABSORB_COLS,
+        )
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.keccak_absorb_events.is_empty()
+    }
+}
+
+impl<F> BaseAir<F> for KeccakAbsorbChip {
+    fn width(&self) -> usize {
+        NUM_KECCAK_ABSORB_COLS
+    }
+}
+
+impl<AB> Air<AB> for KeccakAbsorbChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &KeccakAbsorbCols<AB::Var> = (*local).borrow();
+
+        builder.assert_bool(local.is_real);
+
+        // The absorbed words are a prefix of the state, of length twice the rate.
+        builder.assert_eq(local.is_absorbed[0], local.is_real);
+        let mut num_absorbed = AB::Expr::zero();
+        for i in 0..MAX_RATE_WORDS {
+            builder.assert_bool(local.is_absorbed[i]);
+            if i + 1 < MAX_RATE_WORDS {
+                builder
+                    .when(local.is_absorbed[i + 1])
+                    .assert_one(local.is_absorbed[i]);
+            }
+            num_absorbed += local.is_absorbed[i].into();
+        }
+        let rate = local.rate_memory.value();
+        builder
+            .when(local.is_real)
+            .assert_eq(num_absorbed, rate[0] * AB::F::from_canonical_u32(2));
+        for i in 1..WORD_SIZE {
+            builder.when(local.is_real).assert_zero(rate[i]);
+        }
+
+        builder.eval_memory_access(
+            local.shard,
+            local.channel,
+            local.clk,
+            local.block_ptr,
+            &local.rate_memory,
+            local.is_real,
+        );
+
+        // XOR the block into the rate of the state.
+        for i in 0..MAX_RATE_WORDS {
+            let offset = AB::F::from_canonical_usize(i * WORD_SIZE);
+            builder.eval_memory_access(
+                local.shard,
+                local.channel,
+                local.clk,
+                local.block_ptr + offset + AB::F::from_canonical_usize(WORD_SIZE),
+                &local.block_memory[i],
+                local.is_absorbed[i],
+            );
+            builder.eval_memory_access(
+                local.shard,
+                local.channel,
+                local.clk,
+                local.state_ptr + offset,
+                &local.state_memory[i],
+                local.is_absorbed[i],
+            );
+            XorOperation::<AB::F>::eval(
+                builder,
+                *local.state_memory[i].prev_value(),
+                *local.block_memory[i].value(),
+                local.xor[i],
+                local.shard,
+                local.channel,
+                local.is_absorbed[i],
+            );
+            builder
+                .when(local.is_absorbed[i])
+                .assert_word_eq(*local.state_memory[i].value(), local.xor[i].value);
+        }
+
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            AB::F::from_canonical_u32(SyscallCode::KECCAK_ABSORB.syscall_id()),
+            local.state_ptr,
+            local.block_ptr,
+            local.is_real,
+        );
+
+        // The permutation of the absorbed state is proven by the Keccak permute chip.
+        builder.send_syscall(
+            local.shard,
+            local.channel,
+            local.clk + AB::F::one(),
+            AB::F::from_canonical_u32(SyscallCode::KECCAK_PERMUTE.syscall_id()),
+            local.state_ptr,
+            AB::Expr::zero(),
+            local.is_real,
+        );
+    }
+}
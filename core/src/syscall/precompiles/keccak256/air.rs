@@ -133,7 +133,7 @@ where
 
 #[cfg(test)]
 mod test {
-    use crate::io::{SP1PublicValues, SP1Stdin};
+    use crate::io::SP1Stdin;
     use crate::runtime::Program;
     use crate::stark::{RiscvAir, StarkGenericConfig};
     use crate::utils::SP1CoreOpts;
@@ -172,9 +172,8 @@ mod test {
         let config = BabyBearPoseidon2::new();
 
         let program = Program::from(KECCAK256_ELF);
-        let (proof, public_values) =
+        let (proof, mut public_values) =
             prove(program, &stdin, config, SP1CoreOpts::default()).unwrap();
-        let mut public_values = SP1PublicValues::from(&public_values);
 
         let config = BabyBearPoseidon2::new();
         let mut challenger = config.challenger();
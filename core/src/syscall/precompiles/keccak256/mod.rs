@@ -1,8 +1,11 @@
+mod absorb;
 mod air;
 pub mod columns;
 mod execute;
 mod trace;
 
+pub use absorb::*;
+
 use p3_keccak_air::KeccakAir;
 use serde::{Deserialize, Serialize};
 
@@ -87,6 +90,51 @@ pub mod permute_tests {
         run_test(program).unwrap();
     }
 
+    #[test]
+    fn test_keccak_absorb_sha3_256() {
+        utils::setup_logger();
+        let state_ptr = 0x1000;
+        let block_ptr = 0x2000;
+        let instructions = vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::KECCAK_ABSORB as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, state_ptr, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, block_ptr, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        let mut program = Program::new(instructions, 0, 0);
+
+        // The padded empty message at the SHA3-256 rate of 17 lanes.
+        let rate = 17;
+        let mut block = vec![0u32; 2 * rate as usize];
+        block[0] = 0x06;
+        block[2 * rate as usize - 1] = 0x80 << 24;
+        program.memory_image.insert(block_ptr, rate);
+        for (i, word) in block.into_iter().enumerate() {
+            program
+                .memory_image
+                .insert(block_ptr + 4 * (i as u32 + 1), word);
+        }
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let digest = (0..8)
+            .flat_map(|i| runtime.word(state_ptr + 4 * i).to_le_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            hex::encode(digest),
+            "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"
+        );
+
+        run_test(program).unwrap();
+    }
+
     #[test]
     fn test_keccak_permute_program_prove() {
         utils::setup_logger();
@@ -1,6 +1,14 @@
+pub mod aes;
+pub mod bigint;
 pub mod blake3;
+pub mod chacha20;
+pub mod ecrecover;
 pub mod edwards;
+pub mod fp;
+pub mod gf256;
 pub mod keccak256;
+pub mod poseidon2;
+pub mod ripemd160;
 pub mod sha256;
 pub mod uint256;
 pub mod weierstrass;
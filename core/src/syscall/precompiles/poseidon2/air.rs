@@ -0,0 +1,166 @@
+use core::borrow::Borrow;
+
+use p3_air::{Air, BaseAir};
+use p3_field::AbstractField;
+use p3_matrix::Matrix;
+use sp1_primitives::RC_16_30_U32;
+
+use super::columns::{Poseidon2PermuteCols, NUM_POSEIDON2_PERMUTE_COLS};
+use super::{
+    external_linear_layer, internal_linear_layer, Poseidon2PermuteChip, NUM_EXTERNAL_ROUNDS,
+    NUM_INTERNAL_ROUNDS, WIDTH,
+};
+use crate::air::{BaseAirBuilder, SP1AirBuilder, Word, WORD_SIZE};
+use crate::memory::MemoryCols;
+use crate::operations::BabyBearWordRangeChecker;
+use crate::runtime::SyscallCode;
+
+impl<F> BaseAir<F> for Poseidon2PermuteChip {
+    fn width(&self) -> usize {
+        NUM_POSEIDON2_PERMUTE_COLS
+    }
+}
+
+impl<AB> Air<AB> for Poseidon2PermuteChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let local: &Poseidon2PermuteCols<AB::Var> = (*local).borrow();
+
+        builder.assert_bool(local.is_real);
+
+        // Read the input from the state and write the output to it.
+        for i in 0..WIDTH {
+            builder.eval_memory_access(
+                local.shard,
+                local.channel,
+                local.clk,
+                local.state_ptr + AB::F::from_canonical_usize(i * WORD_SIZE),
+                &local.state_memory[i],
+                local.is_real,
+            );
+            BabyBearWordRangeChecker::<AB::F>::range_check(
+                builder,
+                *local.state_memory[i].prev_value(),
+                local.input_range_checks[i],
+                local.is_real,
+            );
+            BabyBearWordRangeChecker::<AB::F>::range_check(
+                builder,
+                *local.state_memory[i].value(),
+                local.output_range_checks[i],
+                local.is_real,
+            );
+        }
+        let output_bytes = local
+            .state_memory
+            .iter()
+            .flat_map(|memory| memory.value().0)
+            .collect::<Vec<_>>();
+        builder.slice_range_check_u8(&output_bytes, local.shard, local.channel, local.is_real);
+
+        // Apply the initial linear layer.
+        let mut input: [AB::Expr; WIDTH] =
+            core::array::from_fn(|i| word_to_field::<AB>(local.state_memory[i].prev_value()));
+        external_linear_layer(&mut input);
+        builder
+            .when(local.is_real)
+            .assert_all_eq(local.external_rounds_state[0], input);
+
+        // Apply the first half of external rounds.
+        for r in 0..NUM_EXTERNAL_ROUNDS / 2 {
+            eval_external_round(builder, local, r);
+        }
+
+        // Apply the internal rounds.
+        eval_internal_rounds(builder, local);
+
+        // Apply the second half of external rounds.
+        for r in NUM_EXTERNAL_ROUNDS / 2..NUM_EXTERNAL_ROUNDS {
+            eval_external_round(builder, local, r);
+        }
+
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            AB::F::from_canonical_u32(SyscallCode::POSEIDON2_PERMUTE.syscall_id()),
+            local.state_ptr,
+            AB::Expr::zero(),
+            local.is_real,
+        );
+    }
+}
+
+/// The field element encoded by a word, which is canonical by the range checks.
+fn word_to_field<AB: SP1AirBuilder>(word: &Word<AB::Var>) -> AB::Expr {
+    word.0
+        .iter()
+        .enumerate()
+        .map(|(i, byte)| AB::Expr::from_canonical_u32(1 << (8 * i)) * *byte)
+        .sum()
+}
+
+fn eval_external_round<AB: SP1AirBuilder>(
+    builder: &mut AB,
+    local: &Poseidon2PermuteCols<AB::Var>,
+    r: usize,
+) {
+    let round = if r < NUM_EXTERNAL_ROUNDS / 2 {
+        r
+    } else {
+        r + NUM_INTERNAL_ROUNDS
+    };
+
+    // The round constants are only added on real rows, so that padding rows are all zero.
+    let mut state: [AB::Expr; WIDTH] = core::array::from_fn(|i| {
+        let add_rc: AB::Expr = local.external_rounds_state[r][i]
+            + local.is_real * AB::F::from_wrapped_u32(RC_16_30_U32[round][i]);
+        let sbox_deg_3 = local.external_rounds_sbox[r][i];
+        builder.assert_eq(sbox_deg_3, add_rc.clone() * add_rc.clone() * add_rc.clone());
+        sbox_deg_3 * sbox_deg_3 * add_rc
+    });
+
+    // Apply the linear layer.
+    external_linear_layer(&mut state);
+
+    let next_state: [AB::Expr; WIDTH] = if r == NUM_EXTERNAL_ROUNDS / 2 - 1 {
+        local.internal_rounds_state.map(Into::into)
+    } else if r == NUM_EXTERNAL_ROUNDS - 1 {
+        core::array::from_fn(|i| word_to_field::<AB>(local.state_memory[i].value()))
+    } else {
+        local.external_rounds_state[r + 1].map(Into::into)
+    };
+    builder.assert_all_eq(next_state, state);
+}
+
+fn eval_internal_rounds<AB: SP1AirBuilder>(
+    builder: &mut AB,
+    local: &Poseidon2PermuteCols<AB::Var>,
+) {
+    let mut state: [AB::Expr; WIDTH] = local.internal_rounds_state.map(Into::into);
+    for r in 0..NUM_INTERNAL_ROUNDS {
+        let round = r + NUM_EXTERNAL_ROUNDS / 2;
+        let s0: AB::Expr = if r == 0 {
+            state[0].clone()
+        } else {
+            local.internal_rounds_s0[r - 1].into()
+        };
+        let add_rc = s0 + local.is_real * AB::F::from_wrapped_u32(RC_16_30_U32[round][0]);
+        let sbox_deg_3 = local.internal_rounds_sbox[r];
+        builder.assert_eq(sbox_deg_3, add_rc.clone() * add_rc.clone() * add_rc.clone());
+        state[0] = sbox_deg_3 * sbox_deg_3 * add_rc;
+
+        // Apply the linear layer. The new state is a linear combination of the sbox outputs, so
+        // only its 0th element needs a column.
+        internal_linear_layer(&mut state);
+        if r < NUM_INTERNAL_ROUNDS - 1 {
+            builder.assert_eq(local.internal_rounds_s0[r], state[0].clone());
+        }
+    }
+
+    builder.assert_all_eq(local.external_rounds_state[NUM_EXTERNAL_ROUNDS / 2], state);
+}
@@ -0,0 +1,35 @@
+use core::mem::size_of;
+
+use sp1_derive::AlignedBorrow;
+
+use super::{NUM_EXTERNAL_ROUNDS, NUM_INTERNAL_ROUNDS, WIDTH};
+use crate::memory::MemoryWriteCols;
+use crate::operations::BabyBearWordRangeChecker;
+
+pub const NUM_POSEIDON2_PERMUTE_COLS: usize = size_of::<Poseidon2PermuteCols<u8>>();
+
+/// The columns of the Poseidon2 permutation, with one row per invocation.
+///
+/// As in the recursion Poseidon2 chip, the sbox is split into degree 3 constraints, and only the
+/// 0th state element has columns in the internal rounds.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Poseidon2PermuteCols<T> {
+    pub shard: T,
+    pub channel: T,
+    pub clk: T,
+    pub state_ptr: T,
+
+    /// The state, whose previous values are the input and whose values are the output.
+    pub state_memory: [MemoryWriteCols<T>; WIDTH],
+    pub input_range_checks: [BabyBearWordRangeChecker<T>; WIDTH],
+    pub output_range_checks: [BabyBearWordRangeChecker<T>; WIDTH],
+
+    pub external_rounds_state: [[T; WIDTH]; NUM_EXTERNAL_ROUNDS],
+    pub internal_rounds_state: [T; WIDTH],
+    pub internal_rounds_s0: [T; NUM_INTERNAL_ROUNDS - 1],
+    pub external_rounds_sbox: [[T; WIDTH]; NUM_EXTERNAL_ROUNDS],
+    pub internal_rounds_sbox: [T; NUM_INTERNAL_ROUNDS],
+
+    pub is_real: T,
+}
@@ -0,0 +1,52 @@
+use p3_baby_bear::BabyBear;
+use p3_field::{AbstractField, PrimeField32};
+use p3_symmetric::Permutation;
+
+use super::{Poseidon2PermuteChip, Poseidon2PermuteEvent, WIDTH};
+use crate::runtime::Syscall;
+use crate::syscall::precompiles::SyscallContext;
+use crate::utils::inner_perm;
+
+impl Syscall for Poseidon2PermuteChip {
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let start_clk = rt.clk;
+        let state_ptr = arg1;
+        if state_ptr % 4 != 0 {
+            panic!();
+        }
+        if arg2 != 0 {
+            panic!("Expected arg2 to be 0, got {}", arg2);
+        }
+
+        // We can read the state with slice_unsafe because we write the permuted state to it.
+        let state = rt.slice_unsafe(state_ptr, WIDTH);
+        let input: [BabyBear; WIDTH] = core::array::from_fn(|i| {
+            assert!(
+                state[i] < BabyBear::ORDER_U32,
+                "poseidon2 state element {} is not a canonical BabyBear element",
+                state[i]
+            );
+            BabyBear::from_canonical_u32(state[i])
+        });
+        let output = inner_perm().permute(input);
+        let values = output
+            .iter()
+            .map(|x| x.as_canonical_u32())
+            .collect::<Vec<_>>();
+        let state_records = rt.mw_slice(state_ptr, &values);
+
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut()
+            .poseidon2_permute_events
+            .push(Poseidon2PermuteEvent {
+                shard,
+                channel,
+                clk: start_clk,
+                state_ptr,
+                state_records,
+            });
+
+        None
+    }
+}
@@ -0,0 +1,138 @@
+#![allow(clippy::needless_range_loop)]
+
+mod air;
+pub mod columns;
+mod execute;
+mod trace;
+
+use p3_baby_bear::{MONTY_INVERSE, POSEIDON2_INTERNAL_MATRIX_DIAG_16_BABYBEAR_MONTY};
+use p3_field::{AbstractField, PrimeField32};
+use p3_poseidon2::matmul_internal;
+use serde::{Deserialize, Serialize};
+
+use crate::runtime::MemoryWriteRecord;
+
+/// The width of the permutation.
+pub const WIDTH: usize = 16;
+
+pub const NUM_EXTERNAL_ROUNDS: usize = 8;
+pub const NUM_INTERNAL_ROUNDS: usize = 13;
+pub const NUM_ROUNDS: usize = NUM_EXTERNAL_ROUNDS + NUM_INTERNAL_ROUNDS;
+
+/// Poseidon2 permutation event, permuting a state of BabyBear elements stored in memory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Poseidon2PermuteEvent {
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub state_ptr: u32,
+    pub state_records: Vec<MemoryWriteRecord>,
+}
+
+/// A chip for the `POSEIDON2_PERMUTE` syscall, which applies the Poseidon2 permutation used by the
+/// recursion verifier to a state of [WIDTH] canonical BabyBear elements, one per word.
+#[derive(Default)]
+pub struct Poseidon2PermuteChip;
+
+impl Poseidon2PermuteChip {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+pub fn apply_m_4<AF>(x: &mut [AF])
+where
+    AF: AbstractField,
+{
+    let t01 = x[0].clone() + x[1].clone();
+    let t23 = x[2].clone() + x[3].clone();
+    let t0123 = t01.clone() + t23.clone();
+    let t01123 = t0123.clone() + x[1].clone();
+    let t01233 = t0123.clone() + x[3].clone();
+    // The order here is important. Need to overwrite x[0] and x[2] after x[1] and x[3].
+    x[3] = t01233.clone() + x[0].double(); // 3*x[0] + x[1] + x[2] + 2*x[3]
+    x[1] = t01123.clone() + x[2].double(); // x[0] + 2*x[1] + 3*x[2] + x[3]
+    x[0] = t01123 + t01; // 2*x[0] + 3*x[1] + x[2] + x[3]
+    x[2] = t01233 + t23; // x[0] + x[1] + 2*x[2] + 3*x[3]
+}
+
+pub fn external_linear_layer<AF: AbstractField>(state: &mut [AF; WIDTH]) {
+    for j in (0..WIDTH).step_by(4) {
+        apply_m_4(&mut state[j..j + 4]);
+    }
+    let sums: [AF; 4] = core::array::from_fn(|k| {
+        (0..WIDTH)
+            .step_by(4)
+            .map(|j| state[j + k].clone())
+            .sum::<AF>()
+    });
+
+    for j in 0..WIDTH {
+        state[j] += sums[j % 4].clone();
+    }
+}
+
+pub fn internal_linear_layer<F: AbstractField>(state: &mut [F; WIDTH]) {
+    let matmul_constants: [<F as AbstractField>::F; WIDTH] =
+        POSEIDON2_INTERNAL_MATRIX_DIAG_16_BABYBEAR_MONTY
+            .iter()
+            .map(|x| <F as AbstractField>::F::from_wrapped_u32(x.as_canonical_u32()))
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+    matmul_internal(state, matmul_constants);
+    let monty_inverse = F::from_wrapped_u32(MONTY_INVERSE.as_canonical_u32());
+    state.iter_mut().for_each(|i| *i *= monty_inverse.clone());
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::{AbstractField, PrimeField32};
+    use p3_symmetric::Permutation;
+
+    use super::WIDTH;
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::{self, inner_perm, run_test, SP1CoreOpts};
+
+    #[test]
+    fn test_poseidon2_permute() {
+        utils::setup_logger();
+        let state_ptr = 0x1000;
+        let mut instructions = vec![];
+        // Permute the state twice, to check the chaining of permutations.
+        for _ in 0..2 {
+            instructions.extend(vec![
+                Instruction::new(
+                    Opcode::ADD,
+                    5,
+                    0,
+                    SyscallCode::POSEIDON2_PERMUTE as u32,
+                    false,
+                    true,
+                ),
+                Instruction::new(Opcode::ADD, 10, 0, state_ptr, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, 0, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        let mut program = Program::new(instructions, 0, 0);
+        let input: [BabyBear; WIDTH] =
+            core::array::from_fn(|i| BabyBear::from_canonical_u32(0x7800_0000 - 12345 * i as u32));
+        for (i, x) in input.iter().enumerate() {
+            program
+                .memory_image
+                .insert(state_ptr + 4 * i as u32, x.as_canonical_u32());
+        }
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let perm = inner_perm();
+        let expected = perm.permute(perm.permute(input));
+        for (i, x) in expected.iter().enumerate() {
+            assert_eq!(runtime.word(state_ptr + 4 * i as u32), x.as_canonical_u32());
+        }
+
+        run_test(program).unwrap();
+    }
+}
@@ -0,0 +1,149 @@
+use core::borrow::BorrowMut;
+
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+use sp1_primitives::RC_16_30_U32;
+
+use super::columns::{Poseidon2PermuteCols, NUM_POSEIDON2_PERMUTE_COLS};
+use super::{
+    external_linear_layer, internal_linear_layer, Poseidon2PermuteChip, NUM_EXTERNAL_ROUNDS,
+    NUM_INTERNAL_ROUNDS, WIDTH,
+};
+use crate::air::MachineAir;
+use crate::bytes::event::ByteRecord;
+use crate::runtime::{ExecutionRecord, Program};
+use crate::utils::pad_rows;
+
+impl<F: PrimeField32> MachineAir<F> for Poseidon2PermuteChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Poseidon2Permute".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut rows = Vec::new();
+        let mut new_byte_lookup_events = Vec::new();
+
+        for event in input.poseidon2_permute_events.iter() {
+            let mut row = [F::zero(); NUM_POSEIDON2_PERMUTE_COLS];
+            let cols: &mut Poseidon2PermuteCols<F> = row.as_mut_slice().borrow_mut();
+
+            cols.is_real = F::one();
+            cols.shard = F::from_canonical_u32(event.shard);
+            cols.channel = F::from_canonical_u32(event.channel);
+            cols.clk = F::from_canonical_u32(event.clk);
+            cols.state_ptr = F::from_canonical_u32(event.state_ptr);
+
+            for i in 0..WIDTH {
+                let record = event.state_records[i];
+                cols.state_memory[i].populate(event.channel, record, &mut new_byte_lookup_events);
+                cols.input_range_checks[i].populate(record.prev_value);
+                cols.output_range_checks[i].populate(record.value);
+            }
+            // The output is written by the permutation, so its bytes must be range checked.
+            let output_bytes = event
+                .state_records
+                .iter()
+                .flat_map(|record| record.value.to_le_bytes())
+                .collect::<Vec<_>>();
+            new_byte_lookup_events.add_u8_range_checks(event.shard, event.channel, &output_bytes);
+
+            let state: [F; WIDTH] =
+                core::array::from_fn(|i| F::from_canonical_u32(event.state_records[i].prev_value));
+            cols.external_rounds_state[0] = state;
+            external_linear_layer(&mut cols.external_rounds_state[0]);
+
+            // Apply the first half of external rounds.
+            for r in 0..NUM_EXTERNAL_ROUNDS / 2 {
+                let next_state = populate_external_round(cols, r);
+                if r == NUM_EXTERNAL_ROUNDS / 2 - 1 {
+                    cols.internal_rounds_state = next_state;
+                } else {
+                    cols.external_rounds_state[r + 1] = next_state;
+                }
+            }
+
+            // Apply the internal rounds.
+            cols.external_rounds_state[NUM_EXTERNAL_ROUNDS / 2] = populate_internal_rounds(cols);
+
+            // Apply the second half of external rounds.
+            for r in NUM_EXTERNAL_ROUNDS / 2..NUM_EXTERNAL_ROUNDS {
+                let next_state = populate_external_round(cols, r);
+                if r == NUM_EXTERNAL_ROUNDS - 1 {
+                    // The output of the last round is the value written to the state.
+                    for i in 0..WIDTH {
+                        assert_eq!(
+                            event.state_records[i].value,
+                            next_state[i].as_canonical_u32()
+                        );
+                    }
+                } else {
+                    cols.external_rounds_state[r + 1] = next_state;
+                }
+            }
+
+            rows.push(row);
+        }
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_POSEIDON2_PERMUTE_COLS]);
+
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_POSEIDON2_PERMUTE_COLS,
+        )
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.poseidon2_permute_events.is_empty()
+    }
+}
+
+fn populate_external_round<F: PrimeField32>(
+    cols: &mut Poseidon2PermuteCols<F>,
+    r: usize,
+) -> [F; WIDTH] {
+    let round = if r < NUM_EXTERNAL_ROUNDS / 2 {
+        r
+    } else {
+        r + NUM_INTERNAL_ROUNDS
+    };
+
+    // Add the round constants and apply the sboxes, keeping the degree 3 part in columns.
+    let mut state = [F::zero(); WIDTH];
+    for i in 0..WIDTH {
+        let add_rc = cols.external_rounds_state[r][i] + F::from_wrapped_u32(RC_16_30_U32[round][i]);
+        let sbox_deg_3 = add_rc * add_rc * add_rc;
+        cols.external_rounds_sbox[r][i] = sbox_deg_3;
+        state[i] = sbox_deg_3 * sbox_deg_3 * add_rc;
+    }
+
+    // Apply the linear layer.
+    external_linear_layer(&mut state);
+    state
+}
+
+fn populate_internal_rounds<F: PrimeField32>(cols: &mut Poseidon2PermuteCols<F>) -> [F; WIDTH] {
+    let mut state = cols.internal_rounds_state;
+    for r in 0..NUM_INTERNAL_ROUNDS {
+        // Only the 0th state element goes through the sbox in the internal rounds.
+        let round = r + NUM_EXTERNAL_ROUNDS / 2;
+        let add_rc = state[0] + F::from_wrapped_u32(RC_16_30_U32[round][0]);
+        let sbox_deg_3 = add_rc * add_rc * add_rc;
+        cols.internal_rounds_sbox[r] = sbox_deg_3;
+        state[0] = sbox_deg_3 * sbox_deg_3 * add_rc;
+
+        // Apply the linear layer.
+        internal_linear_layer(&mut state);
+        if r < NUM_INTERNAL_ROUNDS - 1 {
+            cols.internal_rounds_s0[r] = state[0];
+        }
+    }
+    state
+}
@@ -0,0 +1,367 @@
+use core::borrow::Borrow;
+
+use p3_air::{Air, AirBuilder, BaseAir};
+use p3_field::AbstractField;
+use p3_matrix::Matrix;
+
+use super::columns::{Ripemd160CompressCols, NUM_RIPEMD160_COMPRESS_COLS};
+use super::rotate::RotateLeftOperation;
+use super::step::StepOperation;
+use super::{
+    Ripemd160CompressChip, LEFT_K, LEFT_MSG_SCHEDULE, LEFT_ROTATIONS, RIGHT_K, RIGHT_MSG_SCHEDULE,
+    RIGHT_ROTATIONS, ROUND_COUNT, ROUND_LEN, STATE_SIZE, STEP_COUNT,
+};
+use crate::air::{BaseAirBuilder, SP1AirBuilder, Word, WordAirBuilder, WORD_SIZE};
+use crate::memory::MemoryCols;
+use crate::operations::AddOperation;
+use crate::runtime::SyscallCode;
+
+impl<F> BaseAir<F> for Ripemd160CompressChip {
+    fn width(&self) -> usize {
+        NUM_RIPEMD160_COMPRESS_COLS
+    }
+}
+
+impl<AB> Air<AB> for Ripemd160CompressChip
+where
+    AB: SP1AirBuilder,
+{
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let (local, next) = (main.row_slice(0), main.row_slice(1));
+        let local: &Ripemd160CompressCols<AB::Var> = (*local).borrow();
+        let next: &Ripemd160CompressCols<AB::Var> = (*next).borrow();
+
+        self.constrain_control_flow_flags(builder, local, next);
+
+        self.constrain_step_constants(builder, local);
+
+        self.constrain_memory(builder, local);
+
+        self.constrain_steps(builder, local, next);
+
+        self.constrain_finalize(builder, local);
+
+        self.constrain_invocation(builder, local, next);
+
+        builder.receive_syscall(
+            local.shard,
+            local.channel,
+            local.clk,
+            AB::F::from_canonical_u32(SyscallCode::RIPEMD160_COMPRESS.syscall_id()),
+            local.state_ptr,
+            local.block_ptr,
+            local.ecall_receive,
+        );
+    }
+}
+
+impl Ripemd160CompressChip {
+    /// The expression which is one on the given step and zero on the others.
+    fn step_flag<AB: SP1AirBuilder>(
+        local: &Ripemd160CompressCols<AB::Var>,
+        step: usize,
+    ) -> AB::Expr {
+        local.is_round_n[step / ROUND_LEN] * local.is_step_n[step % ROUND_LEN]
+    }
+
+    /// Constrains the round and step flags, which are one-hot and cycle through the 80 steps.
+    fn constrain_control_flow_flags<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Ripemd160CompressCols<AB::Var>,
+        next: &Ripemd160CompressCols<AB::Var>,
+    ) {
+        builder.assert_bool(local.is_real);
+        for flags in [&local.is_round_n[..], &local.is_step_n[..]] {
+            let mut sum = AB::Expr::zero();
+            for flag in flags.iter() {
+                builder.assert_bool(*flag);
+                sum += (*flag).into();
+            }
+            builder.when(local.is_real).assert_one(sum);
+        }
+
+        // If this is the i-th step of a round, the next row is the (i+1)-th step.
+        for i in 0..ROUND_LEN {
+            builder
+                .when_transition()
+                .when(next.is_real)
+                .assert_eq(local.is_step_n[i], next.is_step_n[(i + 1) % ROUND_LEN]);
+        }
+
+        // The round changes after the last step of a round, and stays the same otherwise.
+        for i in 0..ROUND_COUNT {
+            builder
+                .when_transition()
+                .when(next.is_real)
+                .when_not(local.is_step_n[ROUND_LEN - 1])
+                .assert_eq(local.is_round_n[i], next.is_round_n[i]);
+            builder
+                .when_transition()
+                .when(next.is_real)
+                .when(local.is_step_n[ROUND_LEN - 1])
+                .assert_eq(local.is_round_n[i], next.is_round_n[(i + 1) % ROUND_COUNT]);
+        }
+    }
+
+    /// Constrains the message indices, the round constants and the rotations of the current step.
+    fn constrain_step_constants<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Ripemd160CompressCols<AB::Var>,
+    ) {
+        let mut left_index = AB::Expr::zero();
+        let mut right_index = AB::Expr::zero();
+        let mut rotation_params = [
+            [AB::Expr::zero(), AB::Expr::zero(), AB::Expr::zero()],
+            [AB::Expr::zero(), AB::Expr::zero(), AB::Expr::zero()],
+        ];
+        for step in 0..STEP_COUNT {
+            let flag = Self::step_flag::<AB>(local, step);
+            left_index += flag.clone() * AB::F::from_canonical_usize(LEFT_MSG_SCHEDULE[step]);
+            right_index += flag.clone() * AB::F::from_canonical_usize(RIGHT_MSG_SCHEDULE[step]);
+            for (params, rotation) in rotation_params
+                .iter_mut()
+                .zip([LEFT_ROTATIONS[step], RIGHT_ROTATIONS[step]])
+            {
+                let (is_three_byte_shift, nb_bits_to_shift, carry_multiplier) =
+                    RotateLeftOperation::<AB::F>::params(rotation);
+                params[0] += flag.clone() * AB::F::from_bool(is_three_byte_shift);
+                params[1] += flag.clone() * AB::F::from_canonical_u32(nb_bits_to_shift);
+                params[2] += flag.clone() * AB::F::from_canonical_u32(carry_multiplier);
+            }
+        }
+        builder.assert_eq(local.block_index[0], left_index);
+        builder.assert_eq(local.block_index[1], right_index);
+
+        for (rotate, [is_three_byte_shift, nb_bits_to_shift, carry_multiplier]) in
+            [local.left_step.sum_rotate, local.right_step.sum_rotate]
+                .into_iter()
+                .zip(rotation_params)
+        {
+            builder.assert_eq(rotate.is_three_byte_shift, is_three_byte_shift);
+            builder.assert_eq(rotate.nb_bits_to_shift, nb_bits_to_shift);
+            builder.assert_eq(rotate.carry_multiplier, carry_multiplier);
+        }
+
+        for round in 0..ROUND_COUNT {
+            builder
+                .when(local.is_round_n[round])
+                .assert_all_eq(local.left_k, Word::<AB::F>::from(LEFT_K[round]));
+            builder
+                .when(local.is_round_n[round])
+                .assert_all_eq(local.right_k, Word::<AB::F>::from(RIGHT_K[round]));
+        }
+    }
+
+    /// Constrains the memory accesses of the state and the message block.
+    fn constrain_memory<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Ripemd160CompressCols<AB::Var>,
+    ) {
+        // The state is read on the first step and written on the last step.
+        for i in 0..STATE_SIZE {
+            builder.eval_memory_access(
+                local.shard,
+                local.channel,
+                local.clk,
+                local.state_ptr + AB::F::from_canonical_usize(i * WORD_SIZE),
+                &local.state_memory[i],
+                local.ecall_receive + local.is_last_step,
+            );
+
+            // On the first step, the state is unchanged and is the input of both lines.
+            builder.when(local.ecall_receive).assert_word_eq(
+                *local.state_memory[i].value(),
+                *local.state_memory[i].prev_value(),
+            );
+            for word in [local.h[i], local.left[i], local.right[i]] {
+                builder
+                    .when(local.ecall_receive)
+                    .assert_word_eq(word, *local.state_memory[i].prev_value());
+            }
+        }
+
+        // Read the message words of both lines.
+        for i in 0..2 {
+            builder.eval_memory_access(
+                local.shard,
+                local.channel,
+                local.clk,
+                local.block_ptr + local.block_index[i] * AB::F::from_canonical_usize(WORD_SIZE),
+                &local.block_reads[i],
+                local.is_real,
+            );
+        }
+    }
+
+    /// Constrains the steps of both lines, and the working variables of the next step.
+    fn constrain_steps<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Ripemd160CompressCols<AB::Var>,
+        next: &Ripemd160CompressCols<AB::Var>,
+    ) {
+        let round_flags: [AB::Expr; ROUND_COUNT] =
+            core::array::from_fn(|round| local.is_round_n[round].into());
+        // The right line uses the boolean functions in the reverse order.
+        let reversed_round_flags: [AB::Expr; ROUND_COUNT] =
+            core::array::from_fn(|round| local.is_round_n[ROUND_COUNT - 1 - round].into());
+
+        for (vars, x, k, flags, step, next_vars) in [
+            (
+                local.left,
+                local.block_reads[0].access.value,
+                local.left_k,
+                round_flags,
+                local.left_step,
+                next.left,
+            ),
+            (
+                local.right,
+                local.block_reads[1].access.value,
+                local.right_k,
+                reversed_round_flags,
+                local.right_step,
+                next.right,
+            ),
+        ] {
+            StepOperation::<AB::F>::eval(
+                builder,
+                vars,
+                x,
+                k,
+                flags,
+                step,
+                local.shard,
+                local.channel,
+                local.is_real,
+            );
+
+            // The working variables after the step are [e, t, b, c.rotate_left(10), d].
+            let new_vars = [vars[4], step.t.value, vars[1], step.c_rotate.value, vars[3]];
+            for (next_var, new_var) in next_vars.into_iter().zip(new_vars) {
+                builder
+                    .when_transition()
+                    .when(local.is_real)
+                    .when_not(local.is_last_step)
+                    .assert_word_eq(next_var, new_var);
+            }
+        }
+    }
+
+    /// Constrains the final additions and the written state on the last step.
+    fn constrain_finalize<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Ripemd160CompressCols<AB::Var>,
+    ) {
+        let left = [
+            local.left[4],
+            local.left_step.t.value,
+            local.left[1],
+            local.left_step.c_rotate.value,
+            local.left[3],
+        ];
+        let right = [
+            local.right[4],
+            local.right_step.t.value,
+            local.right[1],
+            local.right_step.c_rotate.value,
+            local.right[3],
+        ];
+        for i in 0..STATE_SIZE {
+            // The new state is h[i + 1] + left[i + 2] + right[i + 3].
+            AddOperation::<AB::F>::eval(
+                builder,
+                local.h[(i + 1) % STATE_SIZE],
+                left[(i + 2) % STATE_SIZE],
+                local.finalize_left[i],
+                local.shard,
+                local.channel,
+                local.is_last_step.into(),
+            );
+            AddOperation::<AB::F>::eval(
+                builder,
+                local.finalize_left[i].value,
+                right[(i + 3) % STATE_SIZE],
+                local.finalize_right[i],
+                local.shard,
+                local.channel,
+                local.is_last_step.into(),
+            );
+            builder.when(local.is_last_step).assert_word_eq(
+                *local.state_memory[i].value(),
+                local.finalize_right[i].value,
+            );
+        }
+    }
+
+    /// Constrains the rows of each invocation of the syscall to be contiguous, starting with the
+    /// row receiving the syscall and ending with the last step.
+    fn constrain_invocation<AB: SP1AirBuilder>(
+        &self,
+        builder: &mut AB,
+        local: &Ripemd160CompressCols<AB::Var>,
+        next: &Ripemd160CompressCols<AB::Var>,
+    ) {
+        // The syscall is received on the first step, and the last step is flagged.
+        builder.assert_eq(
+            local.ecall_receive,
+            local.is_real * Self::step_flag::<AB>(local, 0),
+        );
+        builder.assert_eq(
+            local.is_last_step,
+            local.is_real * Self::step_flag::<AB>(local, STEP_COUNT - 1),
+        );
+
+        // The trace starts with an invocation, and padding rows are only at the end.
+        builder
+            .when_first_row()
+            .assert_eq(local.ecall_receive, local.is_real);
+        builder
+            .when_transition()
+            .when_not(local.is_real)
+            .assert_zero(next.is_real);
+
+        // Within an invocation, the clock is incremented on every step, and the shard, channel,
+        // pointers and input state are the same on every row.
+        builder
+            .when_transition()
+            .when(next.is_real)
+            .when_not(next.ecall_receive)
+            .assert_eq(local.clk + AB::F::one(), next.clk);
+        for (local_value, next_value) in [
+            (local.shard, next.shard),
+            (local.channel, next.channel),
+            (local.state_ptr, next.state_ptr),
+            (local.block_ptr, next.block_ptr),
+        ] {
+            builder
+                .when_transition()
+                .when(next.is_real)
+                .when_not(next.ecall_receive)
+                .assert_eq(local_value, next_value);
+        }
+        for i in 0..STATE_SIZE {
+            builder
+                .when_transition()
+                .when(next.is_real)
+                .when_not(next.ecall_receive)
+                .assert_word_eq(local.h[i], next.h[i]);
+        }
+
+        // The last real row is the last step.
+        builder
+            .when_transition()
+            .when(local.is_real)
+            .when_not(next.is_real)
+            .assert_one(local.is_last_step);
+        builder
+            .when_last_row()
+            .when(local.is_real)
+            .assert_one(local.is_last_step);
+    }
+}
@@ -0,0 +1,68 @@
+use std::mem::size_of;
+
+use sp1_derive::AlignedBorrow;
+
+use super::step::StepOperation;
+use super::{ROUND_COUNT, ROUND_LEN, STATE_SIZE};
+use crate::air::Word;
+use crate::memory::{MemoryReadCols, MemoryReadWriteCols};
+use crate::operations::AddOperation;
+
+pub const NUM_RIPEMD160_COMPRESS_COLS: usize = size_of::<Ripemd160CompressCols<u8>>();
+
+/// A set of columns needed to compute a step of the RIPEMD-160 compression function on both
+/// lines.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Ripemd160CompressCols<T> {
+    pub shard: T,
+    pub channel: T,
+    pub clk: T,
+    pub ecall_receive: T,
+
+    /// The pointer to the state.
+    pub state_ptr: T,
+
+    /// The pointer to the message block.
+    pub block_ptr: T,
+
+    /// Reads the state on the first step, and writes it on the last step.
+    pub state_memory: [MemoryReadWriteCols<T>; STATE_SIZE],
+
+    /// Reads the message words of the left and the right line.
+    pub block_reads: [MemoryReadCols<T>; 2],
+
+    /// The indices of the message words of the left and the right line.
+    pub block_index: [T; 2],
+
+    /// Indicates which round is being performed.
+    pub is_round_n: [T; ROUND_COUNT],
+
+    /// Indicates which step of the round is being performed.
+    pub is_step_n: [T; ROUND_LEN],
+
+    /// Whether this is the last step of a real invocation.
+    pub is_last_step: T,
+
+    /// The input state, which is kept on every row for the final additions.
+    pub h: [Word<T>; STATE_SIZE],
+
+    /// The working variables of the left and the right line before the step.
+    pub left: [Word<T>; STATE_SIZE],
+    pub right: [Word<T>; STATE_SIZE],
+
+    /// The round constants of the left and the right line.
+    pub left_k: Word<T>,
+    pub right_k: Word<T>,
+
+    /// The steps of the left and the right line.
+    pub left_step: StepOperation<T>,
+    pub right_step: StepOperation<T>,
+
+    /// The final additions `h[i + 1] + left[i + 2]` and `+ right[i + 3]`, on the last step.
+    pub finalize_left: [AddOperation<T>; STATE_SIZE],
+    pub finalize_right: [AddOperation<T>; STATE_SIZE],
+
+    /// Indicates if the current call is real or not.
+    pub is_real: T,
+}
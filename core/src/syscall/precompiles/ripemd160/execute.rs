@@ -0,0 +1,83 @@
+use super::{
+    finalize_func, step_func, Ripemd160CompressChip, Ripemd160CompressEvent, BLOCK_SIZE, LEFT_K,
+    LEFT_MSG_SCHEDULE, LEFT_ROTATIONS, RIGHT_K, RIGHT_MSG_SCHEDULE, RIGHT_ROTATIONS, ROUND_COUNT,
+    ROUND_LEN, STATE_SIZE, STEP_COUNT,
+};
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord, Syscall};
+use crate::syscall::precompiles::SyscallContext;
+
+impl Syscall for Ripemd160CompressChip {
+    fn num_extra_cycles(&self) -> u32 {
+        STEP_COUNT as u32
+    }
+
+    fn execute(&self, rt: &mut SyscallContext, arg1: u32, arg2: u32) -> Option<u32> {
+        let state_ptr = arg1;
+        let block_ptr = arg2;
+        // The state and the block are accessed at the same clock cycles, so they must not overlap.
+        assert!(
+            state_ptr + (STATE_SIZE as u32) * 4 <= block_ptr
+                || block_ptr + (BLOCK_SIZE as u32) * 4 <= state_ptr,
+            "the state and the block must not overlap"
+        );
+
+        let start_clk = rt.clk;
+
+        // Read the state on the first step.
+        let mut state_reads = [MemoryReadRecord::default(); STATE_SIZE];
+        let mut h = [0u32; STATE_SIZE];
+        for i in 0..STATE_SIZE {
+            let (record, value) = rt.mr(state_ptr + i as u32 * 4);
+            state_reads[i] = record;
+            h[i] = value;
+        }
+
+        let mut left = h;
+        let mut right = h;
+        let mut block_reads = Vec::with_capacity(STEP_COUNT);
+        for step in 0..STEP_COUNT {
+            let round = step / ROUND_LEN;
+            let (left_record, left_x) = rt.mr(block_ptr + LEFT_MSG_SCHEDULE[step] as u32 * 4);
+            let (right_record, right_x) = rt.mr(block_ptr + RIGHT_MSG_SCHEDULE[step] as u32 * 4);
+            block_reads.push([left_record, right_record]);
+
+            left = step_func(left, round, left_x, LEFT_K[round], LEFT_ROTATIONS[step]);
+            right = step_func(
+                right,
+                ROUND_COUNT - 1 - round,
+                right_x,
+                RIGHT_K[round],
+                RIGHT_ROTATIONS[step],
+            );
+
+            // The state is written on the row of the last step.
+            if step + 1 < STEP_COUNT {
+                rt.clk += 1;
+            }
+        }
+
+        // Write the state on the last step.
+        let result = finalize_func(h, left, right);
+        let mut state_writes = [MemoryWriteRecord::default(); STATE_SIZE];
+        for i in 0..STATE_SIZE {
+            state_writes[i] = rt.mw(state_ptr + i as u32 * 4, result[i]);
+        }
+
+        let shard = rt.current_shard();
+        let channel = rt.current_channel();
+        rt.record_mut()
+            .ripemd160_compress_events
+            .push(Ripemd160CompressEvent {
+                shard,
+                channel,
+                clk: start_clk,
+                state_ptr,
+                block_ptr,
+                state_reads,
+                block_reads,
+                state_writes,
+            });
+
+        None
+    }
+}
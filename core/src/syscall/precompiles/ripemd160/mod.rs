@@ -0,0 +1,207 @@
+//! This module contains the implementation of the `ripemd160_compress` precompile, the
+//! compression function of RIPEMD-160 as used by Bitcoin's `hash160`.
+//!
+//! The compression function runs two lines of 80 steps over the same message block, and combines
+//! them with the input state at the end. In the AIR, each syscall takes up 80 rows, one per step,
+//! with the step of both lines computed on the same row. The state is read on the first row and
+//! written on the last row, and the two message words used by a step are read on its row.
+mod air;
+mod columns;
+mod execute;
+mod rotate;
+mod step;
+mod trace;
+
+use serde::{Deserialize, Serialize};
+
+use crate::runtime::{MemoryReadRecord, MemoryWriteRecord};
+
+/// The number of `Word`s in the RIPEMD-160 state.
+pub(crate) const STATE_SIZE: usize = 5;
+
+/// The number of `Word`s in a message block.
+pub(crate) const BLOCK_SIZE: usize = 16;
+
+/// The number of rounds of each line.
+pub(crate) const ROUND_COUNT: usize = 5;
+
+/// The number of steps in a round.
+pub(crate) const ROUND_LEN: usize = 16;
+
+/// The number of steps of each line.
+pub(crate) const STEP_COUNT: usize = ROUND_COUNT * ROUND_LEN;
+
+/// The message word selected by each step of the left line.
+pub(crate) const LEFT_MSG_SCHEDULE: [usize; STEP_COUNT] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5,
+    2, 14, 11, 8, 3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12, 1, 9, 11, 10, 0, 8, 12, 4,
+    13, 3, 7, 15, 14, 5, 6, 2, 4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+];
+
+/// The message word selected by each step of the right line.
+pub(crate) const RIGHT_MSG_SCHEDULE: [usize; STEP_COUNT] = [
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12, 6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12,
+    4, 9, 1, 2, 15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13, 8, 6, 4, 1, 3, 11, 15, 0, 5,
+    12, 2, 13, 9, 7, 10, 14, 12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+];
+
+/// The left rotation of each step of the left line.
+pub(crate) const LEFT_ROTATIONS: [usize; STEP_COUNT] = [
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8, 7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15,
+    9, 11, 7, 13, 12, 11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5, 11, 12, 14, 15, 14,
+    15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12, 9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6,
+];
+
+/// The left rotation of each step of the right line.
+pub(crate) const RIGHT_ROTATIONS: [usize; STEP_COUNT] = [
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6, 9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12,
+    7, 6, 15, 13, 11, 9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5, 15, 5, 8, 11, 14, 14,
+    6, 14, 6, 9, 12, 9, 12, 5, 15, 8, 8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11,
+];
+
+/// The constant added in each round of the left line.
+pub(crate) const LEFT_K: [u32; ROUND_COUNT] =
+    [0x00000000, 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xa953fd4e];
+
+/// The constant added in each round of the right line.
+pub(crate) const RIGHT_K: [u32; ROUND_COUNT] =
+    [0x50a28be6, 0x5c4dd124, 0x6d703ef3, 0x7a6d76e9, 0x00000000];
+
+/// The boolean function of the given round of the left line. The right line uses the functions
+/// in the reverse order.
+pub(crate) const fn f_func(round: usize, x: u32, y: u32, z: u32) -> u32 {
+    match round {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        3 => (x & z) | (y & !z),
+        _ => x ^ (y | !z),
+    }
+}
+
+/// Computes one step of a line on the working variables `[a, b, c, d, e]`.
+pub(crate) const fn step_func(
+    vars: [u32; STATE_SIZE],
+    f_round: usize,
+    x: u32,
+    k: u32,
+    rotation: usize,
+) -> [u32; STATE_SIZE] {
+    let [a, b, c, d, e] = vars;
+    let t = a
+        .wrapping_add(f_func(f_round, b, c, d))
+        .wrapping_add(x)
+        .wrapping_add(k)
+        .rotate_left(rotation as u32)
+        .wrapping_add(e);
+    [e, t, b, c.rotate_left(10), d]
+}
+
+/// Combines the input state with the working variables of both lines after the last step.
+pub(crate) const fn finalize_func(
+    h: [u32; STATE_SIZE],
+    left: [u32; STATE_SIZE],
+    right: [u32; STATE_SIZE],
+) -> [u32; STATE_SIZE] {
+    let mut result = [0u32; STATE_SIZE];
+    let mut i = 0;
+    while i < STATE_SIZE {
+        result[i] = h[(i + 1) % STATE_SIZE]
+            .wrapping_add(left[(i + 2) % STATE_SIZE])
+            .wrapping_add(right[(i + 3) % STATE_SIZE]);
+        i += 1;
+    }
+    result
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ripemd160CompressEvent {
+    pub shard: u32,
+    pub channel: u32,
+    pub clk: u32,
+    pub state_ptr: u32,
+    pub block_ptr: u32,
+    pub state_reads: [MemoryReadRecord; STATE_SIZE],
+    /// The reads of the message words of the left and the right line, for each step.
+    pub block_reads: Vec<[MemoryReadRecord; 2]>,
+    pub state_writes: [MemoryWriteRecord; STATE_SIZE],
+}
+
+#[derive(Default)]
+pub struct Ripemd160CompressChip;
+
+impl Ripemd160CompressChip {
+    pub const fn new() -> Self {
+        Self {}
+    }
+}
+
+#[cfg(test)]
+pub mod compress_tests {
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::{run_test, setup_logger, SP1CoreOpts};
+
+    use super::{BLOCK_SIZE, STATE_SIZE};
+
+    const STATE_PTR: u32 = 100;
+    const BLOCK_PTR: u32 = 1000;
+
+    /// The initial RIPEMD-160 state.
+    const IV: [u32; STATE_SIZE] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+    /// The padded block of the message "abc".
+    const ABC_BLOCK: [u32; BLOCK_SIZE] = [0x80636261, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 24, 0];
+
+    pub fn ripemd160_compress_program() -> Program {
+        let mut instructions = vec![];
+        for (i, word) in IV.iter().enumerate() {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 29, 0, *word, false, true),
+                Instruction::new(Opcode::ADD, 30, 0, STATE_PTR + i as u32 * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            ]);
+        }
+        for (i, word) in ABC_BLOCK.iter().enumerate() {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 29, 0, *word, false, true),
+                Instruction::new(Opcode::ADD, 30, 0, BLOCK_PTR + i as u32 * 4, false, true),
+                Instruction::new(Opcode::SW, 29, 30, 0, false, true),
+            ]);
+        }
+        instructions.extend(vec![
+            Instruction::new(
+                Opcode::ADD,
+                5,
+                0,
+                SyscallCode::RIPEMD160_COMPRESS as u32,
+                false,
+                true,
+            ),
+            Instruction::new(Opcode::ADD, 10, 0, STATE_PTR, false, true),
+            Instruction::new(Opcode::ADD, 11, 0, BLOCK_PTR, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ]);
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_ripemd160_compress_execute() {
+        setup_logger();
+        let mut runtime = Runtime::new(ripemd160_compress_program(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+
+        // RIPEMD-160("abc") = 8eb208f7e05d987a9b044a8e98c6b087f15a0bfc.
+        let expected: [u32; STATE_SIZE] =
+            [0xf708b28e, 0x7a985de0, 0x8e4a049b, 0x87b0c698, 0xfc0b5af1];
+        for (i, word) in expected.iter().enumerate() {
+            assert_eq!(runtime.word(STATE_PTR + i as u32 * 4), *word);
+        }
+    }
+
+    #[test]
+    fn prove_babybear() {
+        setup_logger();
+        let program = ripemd160_compress_program();
+        run_test(program).unwrap();
+    }
+}
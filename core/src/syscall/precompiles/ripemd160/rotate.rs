@@ -0,0 +1,175 @@
+use p3_field::AbstractField;
+use p3_field::Field;
+use sp1_derive::AlignedBorrow;
+
+use crate::air::SP1AirBuilder;
+use crate::air::Word;
+use crate::air::WORD_SIZE;
+use crate::bytes::event::ByteRecord;
+use crate::bytes::utils::shr_carry;
+use crate::bytes::ByteLookupEvent;
+use crate::bytes::ByteOpcode;
+use crate::runtime::ExecutionRecord;
+
+/// A set of columns needed to compute `rotateleft` of a word by an offset between 5 and 15 that
+/// varies from row to row.
+///
+/// The rotation is computed as a right rotation by `32 - rotation`, which is a byte shift by two
+/// or three bytes followed by a bit shift. The parameters of the rotation are columns, which the
+/// chip constrains to the rotation of the current row.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct RotateLeftOperation<T> {
+    /// Whether the byte shift is by three bytes rather than two.
+    pub is_three_byte_shift: T,
+
+    /// The number of bits to shift after the byte shift.
+    pub nb_bits_to_shift: T,
+
+    /// The multiplier of the carries, `2^(8 - nb_bits_to_shift)`.
+    pub carry_multiplier: T,
+
+    /// The input after the byte shift.
+    pub input_bytes_rotated: Word<T>,
+
+    /// The shift output of `shrcarry` on each byte of a word.
+    pub shift: Word<T>,
+
+    /// The carry output of `shrcarry` on each byte of a word.
+    pub carry: Word<T>,
+
+    /// The output value.
+    pub value: Word<T>,
+}
+
+impl<F: Field> RotateLeftOperation<F> {
+    /// The parameters of a left rotation: whether the byte shift is by three bytes, the number of
+    /// bits to shift, and the carry multiplier.
+    pub const fn params(rotation: usize) -> (bool, u32, u32) {
+        let rotation_right = 32 - rotation;
+        let nb_bits_to_shift = rotation_right % 8;
+        (
+            rotation_right / 8 == 3,
+            nb_bits_to_shift as u32,
+            1 << (8 - nb_bits_to_shift),
+        )
+    }
+
+    pub fn populate(
+        &mut self,
+        record: &mut ExecutionRecord,
+        shard: u32,
+        channel: u32,
+        input: u32,
+        rotation: usize,
+    ) -> u32 {
+        let expected = input.rotate_left(rotation as u32);
+        let (is_three_byte_shift, nb_bits_to_shift, carry_multiplier) = Self::params(rotation);
+        self.is_three_byte_shift = F::from_bool(is_three_byte_shift);
+        self.nb_bits_to_shift = F::from_canonical_u32(nb_bits_to_shift);
+        self.carry_multiplier = F::from_canonical_u32(carry_multiplier);
+
+        // Perform the byte shift.
+        let nb_bytes_to_shift = if is_three_byte_shift { 3 } else { 2 };
+        let input_bytes = input.to_le_bytes();
+        let input_bytes_rotated: [u8; WORD_SIZE] =
+            core::array::from_fn(|i| input_bytes[(i + nb_bytes_to_shift) % WORD_SIZE]);
+        self.input_bytes_rotated = Word(input_bytes_rotated.map(F::from_canonical_u8));
+
+        // For each byte, calculate the shift and carry. If it's not the first byte, calculate the
+        // new byte value using the current shifted byte and the last carry.
+        let mut first_shift = 0u32;
+        let mut last_carry = 0u32;
+        let mut value = [0u32; WORD_SIZE];
+        for i in (0..WORD_SIZE).rev() {
+            let b = input_bytes_rotated[i];
+            let c = nb_bits_to_shift as u8;
+            let (shift, carry) = shr_carry(b, c);
+
+            record.add_byte_lookup_event(ByteLookupEvent {
+                shard,
+                channel,
+                opcode: ByteOpcode::ShrCarry,
+                a1: shift as u32,
+                a2: carry as u32,
+                b: b as u32,
+                c: c as u32,
+            });
+
+            self.shift[i] = F::from_canonical_u8(shift);
+            self.carry[i] = F::from_canonical_u8(carry);
+
+            if i == WORD_SIZE - 1 {
+                first_shift = shift as u32;
+            } else {
+                value[i] = shift as u32 + last_carry * carry_multiplier;
+            }
+
+            last_carry = carry as u32;
+        }
+
+        // For the first byte, we didn't know the last carry so compute the rotated byte here.
+        value[WORD_SIZE - 1] = first_shift + last_carry * carry_multiplier;
+        self.value = Word(value.map(F::from_canonical_u32));
+
+        // Check that the value is correct.
+        assert_eq!(self.value.to_u32(), expected);
+
+        expected
+    }
+
+    pub fn eval<AB: SP1AirBuilder>(
+        builder: &mut AB,
+        input: Word<AB::Var>,
+        cols: RotateLeftOperation<AB::Var>,
+        shard: AB::Var,
+        channel: impl Into<AB::Expr> + Clone,
+        is_real: AB::Var,
+    ) {
+        builder.assert_bool(cols.is_three_byte_shift);
+
+        // Perform the byte shift.
+        for i in 0..WORD_SIZE {
+            let two_byte_shift = input[(i + 2) % WORD_SIZE];
+            let three_byte_shift = input[(i + 3) % WORD_SIZE];
+            builder.assert_eq(
+                cols.input_bytes_rotated[i],
+                two_byte_shift + cols.is_three_byte_shift * (three_byte_shift - two_byte_shift),
+            );
+        }
+
+        // For each byte, calculate the shift and carry. If it's not the first byte, calculate the
+        // new byte value using the current shifted byte and the last carry.
+        let mut first_shift = AB::Expr::zero();
+        let mut last_carry = AB::Expr::zero();
+        for i in (0..WORD_SIZE).rev() {
+            builder.send_byte_pair(
+                AB::F::from_canonical_u32(ByteOpcode::ShrCarry as u32),
+                cols.shift[i],
+                cols.carry[i],
+                cols.input_bytes_rotated[i],
+                cols.nb_bits_to_shift,
+                shard,
+                channel.clone(),
+                is_real,
+            );
+
+            if i == WORD_SIZE - 1 {
+                first_shift = cols.shift[i].into();
+            } else {
+                builder.assert_eq(
+                    cols.value[i],
+                    cols.shift[i] + last_carry * cols.carry_multiplier,
+                );
+            }
+
+            last_carry = cols.carry[i].into();
+        }
+
+        // For the first byte, we didn't know the last carry so compute the rotated byte here.
+        builder.assert_eq(
+            cols.value[WORD_SIZE - 1],
+            first_shift + last_carry * cols.carry_multiplier,
+        );
+    }
+}
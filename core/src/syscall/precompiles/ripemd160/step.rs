@@ -0,0 +1,282 @@
+use p3_field::AbstractField;
+use p3_field::Field;
+use sp1_derive::AlignedBorrow;
+
+use super::rotate::RotateLeftOperation;
+use super::{f_func, step_func, ROUND_COUNT, STATE_SIZE};
+use crate::air::SP1AirBuilder;
+use crate::air::Word;
+use crate::air::WORD_SIZE;
+use crate::operations::Add4Operation;
+use crate::operations::AddOperation;
+use crate::operations::AndOperation;
+use crate::operations::FixedRotateRightOperation;
+use crate::operations::NotOperation;
+use crate::operations::OrOperation;
+use crate::operations::XorOperation;
+use crate::runtime::ExecutionRecord;
+
+/// A set of columns needed to compute one step of a line of the RIPEMD-160 compression function.
+///  ``` ignore
+/// fn step(vars: &mut [u32; 5], f_round: usize, x: u32, k: u32, rotation: u32) {
+///     let [a, b, c, d, e] = *vars;
+///     let t = (a + f(f_round, b, c, d) + x + k).rotate_left(rotation) + e;
+///     *vars = [e, t, b, c.rotate_left(10), d];
+/// }
+///  ```
+///
+/// The boolean functions of all five rounds are computed, and the one of the current round is
+/// selected.
+#[derive(AlignedBorrow, Default, Debug, Clone, Copy)]
+#[repr(C)]
+pub struct StepOperation<T> {
+    pub b_xor_c: XorOperation<T>,
+    /// `b ^ c ^ d`.
+    pub f_0: XorOperation<T>,
+
+    pub c_xor_d: XorOperation<T>,
+    pub c_xor_d_and_b: AndOperation<T>,
+    /// `(b & c) | (!b & d)`, computed as `((c ^ d) & b) ^ d`.
+    pub f_1: XorOperation<T>,
+
+    pub not_c: NotOperation<T>,
+    pub b_or_not_c: OrOperation<T>,
+    /// `(b | !c) ^ d`.
+    pub f_2: XorOperation<T>,
+
+    pub b_xor_c_and_d: AndOperation<T>,
+    /// `(b & d) | (c & !d)`, computed as `((b ^ c) & d) ^ c`.
+    pub f_3: XorOperation<T>,
+
+    pub not_d: NotOperation<T>,
+    pub c_or_not_d: OrOperation<T>,
+    /// `b ^ (c | !d)`.
+    pub f_4: XorOperation<T>,
+
+    /// The boolean function of the current round.
+    pub f: Word<T>,
+
+    /// `a + f + x + k`.
+    pub sum: Add4Operation<T>,
+    pub sum_rotate: RotateLeftOperation<T>,
+    /// `t := (a + f + x + k).rotate_left(rotation) + e`.
+    pub t: AddOperation<T>,
+
+    /// `c.rotate_left(10)`, which is a rotation right by 22.
+    pub c_rotate: FixedRotateRightOperation<T>,
+}
+
+impl<F: Field> StepOperation<F> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn populate(
+        &mut self,
+        record: &mut ExecutionRecord,
+        shard: u32,
+        channel: u32,
+        vars: [u32; STATE_SIZE],
+        f_round: usize,
+        x: u32,
+        k: u32,
+        rotation: usize,
+    ) -> [u32; STATE_SIZE] {
+        let [a, b, c, d, e] = vars;
+
+        // Compute the boolean functions of all the rounds.
+        let b_xor_c = self.b_xor_c.populate(record, shard, channel, b, c);
+        let f_0 = self.f_0.populate(record, shard, channel, b_xor_c, d);
+
+        let c_xor_d = self.c_xor_d.populate(record, shard, channel, c, d);
+        let c_xor_d_and_b = self
+            .c_xor_d_and_b
+            .populate(record, shard, channel, c_xor_d, b);
+        let f_1 = self.f_1.populate(record, shard, channel, c_xor_d_and_b, d);
+
+        let not_c = self.not_c.populate(record, shard, channel, c);
+        let b_or_not_c = self.b_or_not_c.populate(record, shard, channel, b, not_c);
+        let f_2 = self.f_2.populate(record, shard, channel, b_or_not_c, d);
+
+        let b_xor_c_and_d = self
+            .b_xor_c_and_d
+            .populate(record, shard, channel, b_xor_c, d);
+        let f_3 = self.f_3.populate(record, shard, channel, b_xor_c_and_d, c);
+
+        let not_d = self.not_d.populate(record, shard, channel, d);
+        let c_or_not_d = self.c_or_not_d.populate(record, shard, channel, c, not_d);
+        let f_4 = self.f_4.populate(record, shard, channel, b, c_or_not_d);
+
+        let f = [f_0, f_1, f_2, f_3, f_4][f_round];
+        assert_eq!(f, f_func(f_round, b, c, d));
+        self.f = Word::from(f);
+
+        // t = (a + f + x + k).rotate_left(rotation) + e.
+        let sum = self.sum.populate(record, shard, channel, a, f, x, k);
+        let sum_rotate = self
+            .sum_rotate
+            .populate(record, shard, channel, sum, rotation);
+        let t = self.t.populate(record, shard, channel, sum_rotate, e);
+
+        // c.rotate_left(10).
+        let c_rotate = self.c_rotate.populate(record, shard, channel, c, 22);
+
+        let result = [e, t, b, c_rotate, d];
+        assert_eq!(result, step_func(vars, f_round, x, k, rotation));
+        result
+    }
+
+    /// Evaluates the step, where `f_round_flags` selects the boolean function of the current
+    /// round. The working variables after the step are `[e, t, b, c_rotate, d]`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn eval<AB: SP1AirBuilder>(
+        builder: &mut AB,
+        vars: [Word<AB::Var>; STATE_SIZE],
+        x: Word<AB::Var>,
+        k: Word<AB::Var>,
+        f_round_flags: [AB::Expr; ROUND_COUNT],
+        cols: StepOperation<AB::Var>,
+        shard: AB::Var,
+        channel: impl Into<AB::Expr> + Copy,
+        is_real: AB::Var,
+    ) {
+        let [a, b, c, d, e] = vars;
+
+        // Compute the boolean functions of all the rounds.
+        {
+            XorOperation::<AB::F>::eval(builder, b, c, cols.b_xor_c, shard, channel, is_real);
+            XorOperation::<AB::F>::eval(
+                builder,
+                cols.b_xor_c.value,
+                d,
+                cols.f_0,
+                shard,
+                channel,
+                is_real,
+            );
+
+            XorOperation::<AB::F>::eval(builder, c, d, cols.c_xor_d, shard, channel, is_real);
+            AndOperation::<AB::F>::eval(
+                builder,
+                cols.c_xor_d.value,
+                b,
+                cols.c_xor_d_and_b,
+                shard,
+                channel,
+                is_real,
+            );
+            XorOperation::<AB::F>::eval(
+                builder,
+                cols.c_xor_d_and_b.value,
+                d,
+                cols.f_1,
+                shard,
+                channel,
+                is_real,
+            );
+
+            NotOperation::<AB::F>::eval(builder, c, cols.not_c, shard, channel, is_real);
+            OrOperation::<AB::F>::eval(
+                builder,
+                b,
+                cols.not_c.value,
+                cols.b_or_not_c,
+                shard,
+                channel,
+                is_real,
+            );
+            XorOperation::<AB::F>::eval(
+                builder,
+                cols.b_or_not_c.value,
+                d,
+                cols.f_2,
+                shard,
+                channel,
+                is_real,
+            );
+
+            AndOperation::<AB::F>::eval(
+                builder,
+                cols.b_xor_c.value,
+                d,
+                cols.b_xor_c_and_d,
+                shard,
+                channel,
+                is_real,
+            );
+            XorOperation::<AB::F>::eval(
+                builder,
+                cols.b_xor_c_and_d.value,
+                c,
+                cols.f_3,
+                shard,
+                channel,
+                is_real,
+            );
+
+            NotOperation::<AB::F>::eval(builder, d, cols.not_d, shard, channel, is_real);
+            OrOperation::<AB::F>::eval(
+                builder,
+                c,
+                cols.not_d.value,
+                cols.c_or_not_d,
+                shard,
+                channel,
+                is_real,
+            );
+            XorOperation::<AB::F>::eval(
+                builder,
+                b,
+                cols.c_or_not_d.value,
+                cols.f_4,
+                shard,
+                channel,
+                is_real,
+            );
+        }
+
+        // Select the boolean function of the current round.
+        let candidates = [
+            cols.f_0.value,
+            cols.f_1.value,
+            cols.f_2.value,
+            cols.f_3.value,
+            cols.f_4.value,
+        ];
+        for i in 0..WORD_SIZE {
+            let mut selected = AB::Expr::zero();
+            for (flag, candidate) in f_round_flags.iter().zip(candidates.iter()) {
+                selected += flag.clone() * candidate[i];
+            }
+            builder.assert_eq(cols.f[i], selected);
+        }
+
+        // t = (a + f + x + k).rotate_left(rotation) + e.
+        Add4Operation::<AB::F>::eval(builder, a, cols.f, x, k, shard, channel, is_real, cols.sum);
+        RotateLeftOperation::<AB::F>::eval(
+            builder,
+            cols.sum.value,
+            cols.sum_rotate,
+            shard,
+            channel,
+            is_real,
+        );
+        AddOperation::<AB::F>::eval(
+            builder,
+            cols.sum_rotate.value,
+            e,
+            cols.t,
+            shard,
+            channel,
+            is_real.into(),
+        );
+
+        // c.rotate_left(10).
+        FixedRotateRightOperation::<AB::F>::eval(
+            builder,
+            c,
+            22,
+            cols.c_rotate,
+            shard,
+            channel,
+            is_real,
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::borrow::BorrowMut;
+
+use p3_field::PrimeField32;
+use p3_matrix::dense::RowMajorMatrix;
+
+use super::columns::{Ripemd160CompressCols, NUM_RIPEMD160_COMPRESS_COLS};
+use super::{
+    finalize_func, Ripemd160CompressChip, LEFT_K, LEFT_MSG_SCHEDULE, LEFT_ROTATIONS, RIGHT_K,
+    RIGHT_MSG_SCHEDULE, RIGHT_ROTATIONS, ROUND_COUNT, ROUND_LEN, STATE_SIZE, STEP_COUNT,
+};
+use crate::air::{MachineAir, Word};
+use crate::bytes::event::ByteRecord;
+use crate::runtime::{ExecutionRecord, MemoryRecordEnum, Program};
+use crate::utils::pad_rows;
+
+impl<F: PrimeField32> MachineAir<F> for Ripemd160CompressChip {
+    type Record = ExecutionRecord;
+    type Program = Program;
+
+    fn name(&self) -> String {
+        "Ripemd160Compress".to_string()
+    }
+
+    fn generate_trace(
+        &self,
+        input: &ExecutionRecord,
+        output: &mut ExecutionRecord,
+    ) -> RowMajorMatrix<F> {
+        let mut rows = Vec::new();
+
+        let mut new_byte_lookup_events = Vec::new();
+
+        for event in input.ripemd160_compress_events.iter() {
+            let shard = event.shard;
+            let channel = event.channel;
+            let h = event.state_reads.map(|record| record.value);
+            let mut left = h;
+            let mut right = h;
+            for step in 0..STEP_COUNT {
+                let mut row = [F::zero(); NUM_RIPEMD160_COMPRESS_COLS];
+                let cols: &mut Ripemd160CompressCols<F> = row.as_mut_slice().borrow_mut();
+                let round = step / ROUND_LEN;
+                let is_last_step = step + 1 == STEP_COUNT;
+
+                // Assign basic values to the columns.
+                {
+                    cols.shard = F::from_canonical_u32(shard);
+                    cols.channel = F::from_canonical_u32(channel);
+                    cols.clk = F::from_canonical_u32(event.clk + step as u32);
+                    cols.state_ptr = F::from_canonical_u32(event.state_ptr);
+                    cols.block_ptr = F::from_canonical_u32(event.block_ptr);
+
+                    cols.is_round_n[round] = F::one();
+                    cols.is_step_n[step % ROUND_LEN] = F::one();
+                    cols.ecall_receive = F::from_bool(step == 0);
+                    cols.is_last_step = F::from_bool(is_last_step);
+
+                    cols.h = h.map(Word::from);
+                    cols.left = left.map(Word::from);
+                    cols.right = right.map(Word::from);
+                    cols.left_k = Word::from(LEFT_K[round]);
+                    cols.right_k = Word::from(RIGHT_K[round]);
+                }
+
+                // Memory columns.
+                {
+                    if step == 0 {
+                        for i in 0..STATE_SIZE {
+                            cols.state_memory[i].populate(
+                                channel,
+                                MemoryRecordEnum::Read(event.state_reads[i]),
+                                &mut new_byte_lookup_events,
+                            );
+                        }
+                    }
+                    if is_last_step {
+                        for i in 0..STATE_SIZE {
+                            cols.state_memory[i].populate(
+                                channel,
+                                MemoryRecordEnum::Write(event.state_writes[i]),
+                                &mut new_byte_lookup_events,
+                            );
+                        }
+                    }
+
+                    cols.block_index = [
+                        F::from_canonical_usize(LEFT_MSG_SCHEDULE[step]),
+                        F::from_canonical_usize(RIGHT_MSG_SCHEDULE[step]),
+                    ];
+                    for i in 0..2 {
+                        cols.block_reads[i].populate(
+                            channel,
+                            event.block_reads[step][i],
+                            &mut new_byte_lookup_events,
+                        );
+                    }
+                }
+
+                // Apply the steps of both lines.
+                {
+                    let [left_record, right_record] = event.block_reads[step];
+                    left = cols.left_step.populate(
+                        output,
+                        shard,
+                        channel,
+                        left,
+                        round,
+                        left_record.value,
+                        LEFT_K[round],
+                        LEFT_ROTATIONS[step],
+                    );
+                    right = cols.right_step.populate(
+                        output,
+                        shard,
+                        channel,
+                        right,
+                        ROUND_COUNT - 1 - round,
+                        right_record.value,
+                        RIGHT_K[round],
+                        RIGHT_ROTATIONS[step],
+                    );
+                }
+
+                // Combine the lines with the input state on the last step.
+                if is_last_step {
+                    for i in 0..STATE_SIZE {
+                        let partial = cols.finalize_left[i].populate(
+                            output,
+                            shard,
+                            channel,
+                            h[(i + 1) % STATE_SIZE],
+                            left[(i + 2) % STATE_SIZE],
+                        );
+                        cols.finalize_right[i].populate(
+                            output,
+                            shard,
+                            channel,
+                            partial,
+                            right[(i + 3) % STATE_SIZE],
+                        );
+                    }
+                    assert_eq!(
+                        finalize_func(h, left, right),
+                        event.state_writes.map(|record| record.value)
+                    );
+                }
+
+                cols.is_real = F::one();
+
+                rows.push(row);
+            }
+        }
+
+        output.add_byte_lookup_events(new_byte_lookup_events);
+
+        pad_rows(&mut rows, || [F::zero(); NUM_RIPEMD160_COMPRESS_COLS]);
+
+        // Convert the trace to a row major matrix.
+        RowMajorMatrix::new(
+            rows.into_iter().flatten().collect::<Vec<_>>(),
+            NUM_RIPEMD160_COMPRESS_COLS,
+        )
+    }
+
+    fn included(&self, shard: &Self::Record) -> bool {
+        !shard.ripemd160_compress_events.is_empty()
+    }
+}
@@ -80,6 +80,7 @@ impl<E: EllipticCurve> Syscall for WeierstrassAddAssignChip<E> {
             CurveType::Secp256k1 => rt.record_mut().secp256k1_add_events.push(event),
             CurveType::Bn254 => rt.record_mut().bn254_add_events.push(event),
             CurveType::Bls12381 => rt.record_mut().bls12381_add_events.push(event),
+            CurveType::Secp256r1 => rt.record_mut().secp256r1_add_events.push(event),
             _ => panic!("Unsupported curve"),
         }
         None
@@ -210,6 +211,7 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
             CurveType::Secp256k1 => "Secp256k1AddAssign".to_string(),
             CurveType::Bn254 => "Bn254AddAssign".to_string(),
             CurveType::Bls12381 => "Bls12381AddAssign".to_string(),
+            CurveType::Secp256r1 => "Secp256r1AddAssign".to_string(),
             _ => panic!("Unsupported curve"),
         }
     }
@@ -223,6 +225,7 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
             CurveType::Secp256k1 => &input.secp256k1_add_events,
             CurveType::Bn254 => &input.bn254_add_events,
             CurveType::Bls12381 => &input.bls12381_add_events,
+            CurveType::Secp256r1 => &input.secp256r1_add_events,
             _ => panic!("Unsupported curve"),
         };
 
@@ -313,6 +316,7 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
             CurveType::Secp256k1 => !shard.secp256k1_add_events.is_empty(),
             CurveType::Bn254 => !shard.bn254_add_events.is_empty(),
             CurveType::Bls12381 => !shard.bls12381_add_events.is_empty(),
+            CurveType::Secp256r1 => !shard.secp256r1_add_events.is_empty(),
             _ => panic!("Unsupported curve"),
         }
     }
@@ -483,6 +487,9 @@ where
             CurveType::Bls12381 => {
                 AB::F::from_canonical_u32(SyscallCode::BLS12381_ADD.syscall_id())
             }
+            CurveType::Secp256r1 => {
+                AB::F::from_canonical_u32(SyscallCode::SECP256R1_ADD.syscall_id())
+            }
             _ => panic!("Unsupported curve"),
         };
 
@@ -502,13 +509,15 @@ where
 mod tests {
 
     use crate::{
-        runtime::Program,
+        runtime::{Instruction, Opcode, Program, Runtime, SyscallCode},
         utils::{
+            ec::{weierstrass::secp256r1::Secp256r1, AffinePoint, EllipticCurve},
             run_test, setup_logger,
             tests::{
                 BLS12381_ADD_ELF, BLS12381_DOUBLE_ELF, BLS12381_MUL_ELF, BN254_ADD_ELF,
                 BN254_MUL_ELF, SECP256K1_ADD_ELF, SECP256K1_MUL_ELF,
             },
+            SP1CoreOpts,
         },
     };
 
@@ -560,4 +569,42 @@ mod tests {
         let program = Program::from(BLS12381_MUL_ELF);
         run_test(program).unwrap();
     }
+
+    #[test]
+    fn test_secp256r1_add_double() {
+        setup_logger();
+        const P_PTR: u32 = 0x1000;
+        const Q_PTR: u32 = 0x2000;
+        let generator = Secp256r1::generator();
+
+        // Compute 3G as 2G + G.
+        let mut instructions = vec![];
+        for (code, arg2) in [
+            (SyscallCode::SECP256R1_DOUBLE, 0),
+            (SyscallCode::SECP256R1_ADD, Q_PTR),
+        ] {
+            instructions.extend(vec![
+                Instruction::new(Opcode::ADD, 5, 0, code as u32, false, true),
+                Instruction::new(Opcode::ADD, 10, 0, P_PTR, false, true),
+                Instruction::new(Opcode::ADD, 11, 0, arg2, false, true),
+                Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            ]);
+        }
+        let mut program = Program::new(instructions, 0, 0);
+        for ptr in [P_PTR, Q_PTR] {
+            for (i, word) in generator.to_words_le().into_iter().enumerate() {
+                program.memory_image.insert(ptr + 4 * i as u32, word);
+            }
+        }
+
+        let mut runtime = Runtime::new(program.clone(), SP1CoreOpts::default());
+        runtime.run().unwrap();
+        let words = (0..16)
+            .map(|i| runtime.word(P_PTR + 4 * i))
+            .collect::<Vec<_>>();
+        let expected = Secp256r1::ec_double(&generator) + &generator;
+        assert_eq!(AffinePoint::<Secp256r1>::from_words_le(&words), expected);
+
+        run_test(program).unwrap();
+    }
 }
@@ -17,6 +17,7 @@ use p3_maybe_rayon::prelude::ParallelSlice;
 use sp1_derive::AlignedBorrow;
 
 use crate::air::MachineAir;
+use crate::air::Polynomial;
 use crate::air::SP1AirBuilder;
 use crate::bytes::event::ByteRecord;
 use crate::bytes::ByteLookupEvent;
@@ -81,6 +82,7 @@ impl<E: EllipticCurve + WeierstrassParameters> Syscall for WeierstrassDoubleAssi
             CurveType::Secp256k1 => rt.record_mut().secp256k1_double_events.push(event),
             CurveType::Bn254 => rt.record_mut().bn254_double_events.push(event),
             CurveType::Bls12381 => rt.record_mut().bls12381_double_events.push(event),
+            CurveType::Secp256r1 => rt.record_mut().secp256r1_double_events.push(event),
             _ => panic!("Unsupported curve"),
         }
         None
@@ -103,13 +105,12 @@ impl<E: EllipticCurve + WeierstrassParameters> WeierstrassDoubleAssignChip<E> {
         shard: u32,
         channel: u32,
         cols: &mut WeierstrassDoubleAssignCols<F, E::BaseField>,
+        a: BigUint,
         p_x: BigUint,
         p_y: BigUint,
     ) {
         // This populates necessary field operations to double a point on a Weierstrass curve.
 
-        let a = E::a_int();
-
         // slope = slope_numerator / slope_denominator.
         let slope = {
             // slope_numerator = a + (p.x * p.x) * 3.
@@ -229,6 +230,7 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
             CurveType::Secp256k1 => "Secp256k1DoubleAssign".to_string(),
             CurveType::Bn254 => "Bn254DoubleAssign".to_string(),
             CurveType::Bls12381 => "Bls12381DoubleAssign".to_string(),
+            CurveType::Secp256r1 => "Secp256r1DoubleAssign".to_string(),
             _ => panic!("Unsupported curve"),
         }
     }
@@ -243,6 +245,7 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
             CurveType::Secp256k1 => &input.secp256k1_double_events,
             CurveType::Bn254 => &input.bn254_double_events,
             CurveType::Bls12381 => &input.bls12381_double_events,
+            CurveType::Secp256r1 => &input.secp256r1_double_events,
             _ => panic!("Unsupported curve"),
         };
 
@@ -280,6 +283,7 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
                             event.shard,
                             event.channel,
                             cols,
+                            E::a_int(),
                             p_x,
                             p_y,
                         );
@@ -311,8 +315,9 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
             let mut row = vec![F::zero(); num_weierstrass_double_cols::<E::BaseField>()];
             let cols: &mut WeierstrassDoubleAssignCols<F, E::BaseField> =
                 row.as_mut_slice().borrow_mut();
+            // The padding rows double the point zero with a = 0, so that the slope is 0 / 0.
             let zero = BigUint::zero();
-            Self::populate_field_ops(&mut vec![], 0, 0, cols, zero.clone(), zero.clone());
+            Self::populate_field_ops(&mut vec![], 0, 0, cols, zero.clone(), zero.clone(), zero);
             row
         });
 
@@ -328,6 +333,7 @@ impl<F: PrimeField32, E: EllipticCurve + WeierstrassParameters> MachineAir<F>
             CurveType::Secp256k1 => !shard.secp256k1_double_events.is_empty(),
             CurveType::Bn254 => !shard.bn254_double_events.is_empty(),
             CurveType::Bls12381 => !shard.bls12381_double_events.is_empty(),
+            CurveType::Secp256r1 => !shard.secp256r1_double_events.is_empty(),
             _ => panic!("Unsupported curve"),
         }
     }
@@ -353,9 +359,12 @@ where
         let p_x = limbs_from_prev_access(&row.p_access[0..num_words_field_element]);
         let p_y = limbs_from_prev_access(&row.p_access[num_words_field_element..]);
 
-        // a in the Weierstrass form: y^2 = x^3 + a * x + b.
+        // a in the Weierstrass form: y^2 = x^3 + a * x + b. It is zero on the padding rows, whose
+        // point is zero, since the slope would otherwise divide a non-zero a by zero.
         // TODO: U32 can't be hardcoded here?
-        let a = E::BaseField::to_limbs_field::<AB::Expr, _>(&E::a_int());
+        let is_real: AB::Expr = row.is_real.into();
+        let a: Polynomial<AB::Expr> =
+            Polynomial::from(E::BaseField::to_limbs_field::<AB::Expr, _>(&E::a_int())) * is_real;
 
         // slope = slope_numerator / slope_denominator.
         let slope = {
@@ -509,6 +518,9 @@ where
             CurveType::Bls12381 => {
                 AB::F::from_canonical_u32(SyscallCode::BLS12381_DOUBLE.syscall_id())
             }
+            CurveType::Secp256r1 => {
+                AB::F::from_canonical_u32(SyscallCode::SECP256R1_DOUBLE.syscall_id())
+            }
             _ => panic!("Unsupported curve"),
         };
 
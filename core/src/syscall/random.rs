@@ -0,0 +1,77 @@
+use crate::runtime::{Syscall, SyscallContext};
+
+/// The number of random bytes hinted by each call of the `RANDOM` syscall.
+pub const RANDOM_BYTES: usize = 32;
+
+/// A syscall which hints the next [RANDOM_BYTES] random bytes drawn from the seed of the host, to
+/// be read by the program before the rest of its input.
+///
+/// The bytes of the `n`-th draw are the keyed BLAKE3 hash of `n` under the seed, so an execution
+/// with the same seed, such as the re-execution of a shard from a checkpoint, draws the same bytes.
+/// The bytes are not constrained: the program commits them to its public values, so that the
+/// verifier sees the randomness the prover used.
+pub struct SyscallRandom;
+
+impl SyscallRandom {
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+/// The random bytes of the draw `draw` from `seed`.
+pub fn random_bytes(seed: &[u8; 32], draw: u64) -> [u8; RANDOM_BYTES] {
+    *blake3::keyed_hash(seed, &draw.to_le_bytes()).as_bytes()
+}
+
+impl Syscall for SyscallRandom {
+    fn execute(&self, ctx: &mut SyscallContext, _: u32, _: u32) -> Option<u32> {
+        let rt = &mut ctx.rt;
+        let bytes = random_bytes(&rt.state.random_seed, rt.state.random_draws);
+        rt.state.random_draws += 1;
+        let index = rt.state.input_stream_ptr + rt.state.pending_hints;
+        rt.state.input_stream.insert(index, bytes.to_vec());
+        rt.state.pending_hints += 1;
+        None
+    }
+}
+
+impl Default for SyscallRandom {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::random_bytes;
+    use crate::runtime::{Instruction, Opcode, Program, Runtime, SyscallCode};
+    use crate::utils::SP1CoreOpts;
+
+    fn random_program() -> Program {
+        let instructions = vec![
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::RANDOM as u32, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+            Instruction::new(Opcode::ADD, 5, 0, SyscallCode::RANDOM as u32, false, true),
+            Instruction::new(Opcode::ECALL, 5, 10, 11, false, false),
+        ];
+        Program::new(instructions, 0, 0)
+    }
+
+    #[test]
+    fn test_random() {
+        let seed = [7; 32];
+        let mut runtime = Runtime::new(random_program(), SP1CoreOpts::default());
+        runtime.state.random_seed = seed;
+        runtime.write_stdin_slice(&[1, 2, 3]);
+        runtime.run().unwrap();
+
+        // The random bytes are read in the order they were drawn, before the rest of the input.
+        assert_eq!(runtime.state.random_draws, 2);
+        assert_eq!(runtime.state.pending_hints, 2);
+        assert_eq!(runtime.state.input_stream[0], random_bytes(&seed, 0));
+        assert_eq!(runtime.state.input_stream[1], random_bytes(&seed, 1));
+        assert_eq!(runtime.state.input_stream[2], vec![1, 2, 3]);
+        assert_ne!(random_bytes(&seed, 0), random_bytes(&seed, 1));
+        assert_ne!(random_bytes(&seed, 0), random_bytes(&[0; 32], 0));
+    }
+}
@@ -52,7 +52,7 @@ impl Syscall for SyscallExitUnconstrained {
                         ctx.rt.state.memory.insert(addr, value);
                     }
                     None => {
-                        ctx.rt.state.memory.remove(&addr);
+                        ctx.rt.state.memory.remove(addr);
                     }
                 }
             }
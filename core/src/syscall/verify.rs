@@ -1,12 +1,15 @@
 use crate::{
     runtime::{Syscall, SyscallContext},
-    stark::{RiscvAir, StarkGenericConfig},
-    utils::BabyBearPoseidon2Inner,
+    stark::VerifyingKeyDigest,
 };
 
 /// Verifies an SP1 recursive verifier proof. Note that this syscall only verifies the proof during
 /// runtime. The actual constraint-level verification is deferred to the recursive layer, where
 /// proofs are witnessed and verified in order to reconstruct the deferred_proofs_digest.
+///
+/// At runtime, the syscall consumes the next proof of the proof stream and checks that it is a
+/// proof of the verifying key given by the program, so that a missing or misordered proof fails
+/// the execution instead of the compression of its shard proofs.
 pub struct SyscallVerifySP1Proof;
 
 impl SyscallVerifySP1Proof {
@@ -16,7 +19,6 @@ impl SyscallVerifySP1Proof {
 }
 
 impl Syscall for SyscallVerifySP1Proof {
-    #[allow(unused_variables, unused_mut)]
     fn execute(&self, ctx: &mut SyscallContext, vkey_ptr: u32, pv_digest_ptr: u32) -> Option<u32> {
         let rt = &mut ctx.rt;
 
@@ -29,27 +31,43 @@ impl Syscall for SyscallVerifySP1Proof {
             .map(|i| rt.word(vkey_ptr + i * 4))
             .collect::<Vec<u32>>();
 
-        let pv_digest = (0..8)
-            .map(|i| rt.word(pv_digest_ptr + i * 4))
-            .collect::<Vec<u32>>();
-
-        let (proof, proof_vk) = &rt.state.proof_stream[rt.state.proof_stream_ptr];
+        let proof_index = rt.state.proof_stream_ptr;
+        let Some((_, proof_vk)) = rt.state.proof_stream.get(proof_index) else {
+            panic!(
+                "the program verifies proof {} but only {} proofs were written to the stdin",
+                proof_index,
+                rt.state.proof_stream.len()
+            );
+        };
+        assert_eq!(
+            proof_vk.digest_u32()[..],
+            vkey[..],
+            "the program verifies proof {} against a different verifying key than its own",
+            proof_index
+        );
+        rt.record_proof(proof_index);
         rt.state.proof_stream_ptr += 1;
 
-        let config = BabyBearPoseidon2Inner::new();
-        let mut challenger = config.challenger();
-        // TODO: need to use RecursionAir here
-        let machine = RiscvAir::machine(config);
-
-        // TODO: Need to import PublicValues from recursion.
-        // Assert the commit in vkey from runtime inputs matches the one from syscall.
-        // Assert that the public values digest from runtime inputs matches the one from syscall.
+        // The public values digest is checked against the public values of the proof by the
+        // recursive verifier of the deferred proof.
+        None
+    }
+}
 
-        // TODO: Verify proof
-        // machine
-        //     .verify(proof_vk, proof, &mut challenger)
-        //     .expect("proof verification failed");
+#[cfg(test)]
+mod tests {
+    use crate::{
+        runtime::{Program, Runtime},
+        utils::{setup_logger, tests::VERIFY_PROOF_ELF, SP1CoreOpts},
+    };
 
-        None
+    #[test]
+    #[should_panic(expected = "only 0 proofs were written")]
+    fn test_verify_missing_proof() {
+        setup_logger();
+        let mut runtime = Runtime::new(Program::from(VERIFY_PROOF_ELF), SP1CoreOpts::default());
+        runtime.write_stdin(&[0u32; 8]);
+        runtime.write_stdin(&vec![b"input".to_vec()]);
+        runtime.run().unwrap();
     }
 }
@@ -1,5 +1,7 @@
+use std::io::Write;
+
 use crate::{
-    runtime::{Register, Syscall, SyscallContext},
+    runtime::{ExecutionError, Register, Syscall, SyscallContext},
     utils::num_to_comma_separated,
 };
 
@@ -16,7 +18,7 @@ impl Syscall for SyscallWrite {
         let a2 = Register::X12;
         let rt = &mut ctx.rt;
         let fd = arg1;
-        if fd == 1 || fd == 2 || fd == 3 || fd == 4 {
+        if (1..=5).contains(&fd) {
             let write_buf = arg2;
             let nbytes = rt.register(a2);
             // Read nbytes from memory starting at write_buf.
@@ -36,6 +38,9 @@ impl Syscall for SyscallWrite {
                     let depth = rt.cycle_tracker.len() as u32;
                     rt.cycle_tracker
                         .insert(fn_name.to_string(), (rt.state.global_clk, depth));
+                    if rt.should_report && !rt.unconstrained {
+                        rt.cycle_spans.start(fn_name, rt.state.global_clk);
+                    }
                     let padding = (0..depth).map(|_| "│ ").collect::<String>();
                     log::debug!("{}┌╴{}", padding, fn_name);
                 } else if s.contains("cycle-tracker-end:") {
@@ -46,6 +51,13 @@ impl Syscall for SyscallWrite {
                         .trim_end()
                         .trim_start();
                     let (start, depth) = rt.cycle_tracker.remove(fn_name).unwrap_or((0, 0));
+                    if rt.should_report && !rt.unconstrained {
+                        rt.cycle_spans.end(
+                            fn_name,
+                            rt.state.global_clk,
+                            &mut rt.report.cycle_tracker,
+                        );
+                    }
                     // Leftpad by 2 spaces for each depth.
                     let padding = (0..depth).map(|_| "│ ").collect::<String>();
                     log::info!(
@@ -54,8 +66,11 @@ impl Syscall for SyscallWrite {
                         num_to_comma_separated(rt.state.global_clk - start as u64)
                     );
                 } else {
+                    if rt.should_report {
+                        rt.report.stdout.push_str(s);
+                    }
                     let flush_s = update_io_buf(ctx, fd, s);
-                    if !flush_s.is_empty() {
+                    if ctx.rt.stream_guest_output {
                         flush_s
                             .into_iter()
                             .for_each(|line| println!("stdout: {}", line));
@@ -63,16 +78,33 @@ impl Syscall for SyscallWrite {
                 }
             } else if fd == 2 {
                 let s = core::str::from_utf8(slice).unwrap();
+                if rt.should_report {
+                    rt.report.stderr.push_str(s);
+                }
                 let flush_s = update_io_buf(ctx, fd, s);
-                if !flush_s.is_empty() {
+                if ctx.rt.stream_guest_output {
                     flush_s
                         .into_iter()
                         .for_each(|line| println!("stderr: {}", line));
                 }
             } else if fd == 3 {
-                rt.state.public_values_stream.extend_from_slice(slice);
+                rt.state.public_values_hasher.update(slice);
+                match rt.public_values_sink.as_mut() {
+                    Some(sink) => {
+                        if let Err(error) = sink.write_all(slice) {
+                            ctx.fail(ExecutionError::PublicValuesWrite(error));
+                            return None;
+                        }
+                    }
+                    None => rt.state.public_values_stream.extend_from_slice(slice),
+                }
             } else if fd == 4 {
-                rt.state.input_stream.push(slice.to_vec());
+                // The hints are read in the order they are written, before the rest of the input.
+                let index = rt.state.input_stream_ptr + rt.state.pending_hints;
+                rt.state.input_stream.insert(index, slice.to_vec());
+                rt.state.pending_hints += 1;
+            } else if fd == 5 {
+                rt.state.public_leaves.push(slice.to_vec());
             } else {
                 unreachable!()
             }
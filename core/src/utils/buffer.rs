@@ -1,4 +1,5 @@
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp1_precompiles::codec::IoCodec;
 
 /// A buffer of serializable/deserializable objects.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +38,13 @@ impl Buffer {
         result
     }
 
+    /// Read an object encoded with `C` from the buffer.
+    pub fn read_with<C: IoCodec, T: DeserializeOwned>(&mut self) -> T {
+        let (result, nb_bytes) = C::decode_prefix(&self.data[self.ptr..]);
+        self.ptr += nb_bytes;
+        result
+    }
+
     pub fn read_slice(&mut self, slice: &mut [u8]) {
         slice.copy_from_slice(&self.data[self.ptr..self.ptr + slice.len()]);
         self.ptr += slice.len();
@@ -49,6 +57,11 @@ impl Buffer {
         self.data.extend(tmp);
     }
 
+    /// Write the object encoded with `C` to the buffer.
+    pub fn write_with<C: IoCodec, T: Serialize>(&mut self, data: &T) {
+        self.data.extend(C::encode(data));
+    }
+
     /// Write the slice of bytes to the buffer.
     pub fn write_slice(&mut self, slice: &[u8]) {
         self.data.extend_from_slice(slice);
@@ -1,4 +1,4 @@
-use crate::stark::StarkGenericConfig;
+use crate::stark::{FriParameters, MerkleHash, StarkGenericConfig};
 use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
 use p3_challenger::DuplexChallenger;
 use p3_commit::ExtensionMmcs;
@@ -51,6 +51,15 @@ pub fn inner_perm() -> InnerPerm {
     poseidon2_init()
 }
 
+/// The parameters of a FRI config, which the verifying keys record.
+pub fn fri_parameters<M>(config: &FriConfig<M>) -> FriParameters {
+    FriParameters {
+        log_blowup: config.log_blowup,
+        num_queries: config.num_queries,
+        proof_of_work_bits: config.proof_of_work_bits,
+    }
+}
+
 /// The FRI config for sp1 proofs.
 pub fn sp1_fri_config() -> FriConfig<InnerChallengeMmcs> {
     let perm = inner_perm();
@@ -149,4 +158,12 @@ impl StarkGenericConfig for BabyBearPoseidon2Inner {
     fn challenger(&self) -> Self::Challenger {
         InnerChallenger::new(self.perm.clone())
     }
+
+    fn merkle_hash(&self) -> MerkleHash {
+        MerkleHash::Poseidon2
+    }
+
+    fn fri_parameters(&self) -> FriParameters {
+        fri_parameters(self.pcs.fri_config())
+    }
 }
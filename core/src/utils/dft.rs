@@ -0,0 +1,516 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Once;
+
+use p3_baby_bear::BabyBear;
+use p3_dft::{Radix2DitParallel, TwoAdicSubgroupDft};
+use p3_matrix::bitrev::BitReversedMatrixView;
+use p3_matrix::dense::RowMajorMatrix;
+
+/// The environment variable which selects the backend of the prover, `cpu` (the default), `cuda`
+/// or `metal`.
+pub const PROVER_BACKEND_ENV: &str = "SP1_PROVER_BACKEND";
+
+/// The hardware which runs the low-degree extensions of the traces, which dominate proving times.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProverBackend {
+    Cpu,
+    /// A CUDA device, which requires the `cuda` feature. Proving falls back to the CPU if there is
+    /// no device or a kernel fails.
+    Cuda,
+    /// The GPU of an Apple Silicon Mac, which requires the `metal` feature. Proving falls back to
+    /// the CPU if there is no device or a kernel fails.
+    Metal,
+}
+
+const BACKEND_UNSET: u8 = 0;
+const BACKEND_CPU: u8 = 1;
+const BACKEND_CUDA: u8 = 2;
+const BACKEND_METAL: u8 = 3;
+
+static BACKEND: AtomicU8 = AtomicU8::new(BACKEND_UNSET);
+
+/// Sets the backend of all the provers of the process.
+pub fn set_prover_backend(backend: ProverBackend) {
+    let backend = match backend {
+        ProverBackend::Cpu => BACKEND_CPU,
+        ProverBackend::Cuda => BACKEND_CUDA,
+        ProverBackend::Metal => BACKEND_METAL,
+    };
+    BACKEND.store(backend, Ordering::Relaxed);
+}
+
+/// The backend of the provers, which is set by [set_prover_backend] or else by the
+/// `SP1_PROVER_BACKEND` environment variable.
+pub fn prover_backend() -> ProverBackend {
+    match BACKEND.load(Ordering::Relaxed) {
+        BACKEND_CPU => ProverBackend::Cpu,
+        BACKEND_CUDA => ProverBackend::Cuda,
+        BACKEND_METAL => ProverBackend::Metal,
+        _ => {
+            let backend = match std::env::var(PROVER_BACKEND_ENV) {
+                Ok(value) if value.eq_ignore_ascii_case("cuda") => ProverBackend::Cuda,
+                Ok(value) if value.eq_ignore_ascii_case("metal") => ProverBackend::Metal,
+                _ => ProverBackend::Cpu,
+            };
+            set_prover_backend(backend);
+            backend
+        }
+    }
+}
+
+/// Computes a coset LDE on the device of the backend, or returns `None` if it has to be computed
+/// on the CPU.
+fn gpu_coset_lde_batch(
+    mat: &RowMajorMatrix<BabyBear>,
+    added_bits: usize,
+    shift: BabyBear,
+) -> Option<BitReversedMatrixView<RowMajorMatrix<BabyBear>>> {
+    static WARN_UNAVAILABLE: Once = Once::new();
+    let backend = prover_backend();
+    let available = match backend {
+        ProverBackend::Cpu => return None,
+        ProverBackend::Cuda => cuda::available(),
+        ProverBackend::Metal => metal::available(),
+    };
+    if !available {
+        WARN_UNAVAILABLE.call_once(|| {
+            tracing::warn!(
+                "no device is available for the {:?} backend, proving on the CPU instead",
+                backend
+            );
+        });
+        return None;
+    }
+    match backend {
+        ProverBackend::Cuda => cuda::coset_lde_batch(mat, added_bits, shift),
+        ProverBackend::Metal => metal::coset_lde_batch(mat, added_bits, shift),
+        ProverBackend::Cpu => None,
+    }
+}
+
+/// The DFT of the core prover, which computes the low-degree extensions of the traces on the
+/// backend of [prover_backend] and the rest with [Radix2DitParallel].
+///
+/// All the backends give the same evaluations, so proofs do not depend on the backend.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SP1Dft;
+
+impl TwoAdicSubgroupDft<BabyBear> for SP1Dft {
+    type Evaluations = BitReversedMatrixView<RowMajorMatrix<BabyBear>>;
+
+    fn dft_batch(&self, mat: RowMajorMatrix<BabyBear>) -> Self::Evaluations {
+        Radix2DitParallel {}.dft_batch(mat)
+    }
+
+    fn coset_lde_batch(
+        &self,
+        mat: RowMajorMatrix<BabyBear>,
+        added_bits: usize,
+        shift: BabyBear,
+    ) -> Self::Evaluations {
+        if let Some(lde) = gpu_coset_lde_batch(&mat, added_bits, shift) {
+            return lde;
+        }
+        Radix2DitParallel {}.coset_lde_batch(mat, added_bits, shift)
+    }
+}
+
+/// The inputs of the coset LDE kernels, which extend the columns of a `height x width` matrix to
+/// `height << added_bits` rows, in canonical form.
+///
+/// The kernels interpolate the columns with a decimation-in-frequency NTT by the `inv_twiddles`,
+/// reorder the coefficients and multiply them by `scale`, and evaluate them with a
+/// decimation-in-frequency NTT by the `twiddles`, so the output is in bit-reversed order.
+#[cfg(any(feature = "cuda", all(feature = "metal", target_os = "macos")))]
+struct LdeInputs {
+    height: usize,
+    width: usize,
+    log_height: usize,
+    added_bits: usize,
+    values: Vec<u32>,
+    /// The first `height / 2` powers of the inverse of the generator of the subgroup of order
+    /// `height`.
+    inv_twiddles: Vec<u32>,
+    /// The first `(height << added_bits) / 2` powers of the generator of the extended subgroup.
+    twiddles: Vec<u32>,
+    /// The `height` first powers of the shift, divided by `height`.
+    scale: Vec<u32>,
+}
+
+#[cfg(any(feature = "cuda", all(feature = "metal", target_os = "macos")))]
+impl LdeInputs {
+    /// The smallest height of a trace whose low-degree extension runs on a GPU. Smaller traces
+    /// are faster to extend on the CPU than to copy to the device and back.
+    const MIN_LOG_HEIGHT: usize = 12;
+
+    /// Prepares the inputs of the LDE of `mat`, or returns `None` if `mat` is too small.
+    fn new(mat: &RowMajorMatrix<BabyBear>, added_bits: usize, shift: BabyBear) -> Option<Self> {
+        use p3_field::{AbstractField, Field, PrimeField32, TwoAdicField};
+        use p3_matrix::Matrix;
+
+        let (height, width) = (mat.height(), mat.width());
+        let log_height = p3_util::log2_strict_usize(height);
+        if log_height < Self::MIN_LOG_HEIGHT || width == 0 {
+            return None;
+        }
+        let powers = |base: BabyBear, len: usize| {
+            base.powers()
+                .take(len)
+                .map(|x| x.as_canonical_u32())
+                .collect::<Vec<_>>()
+        };
+        let height_inv = BabyBear::from_canonical_usize(height).inverse();
+        Some(Self {
+            height,
+            width,
+            log_height,
+            added_bits,
+            values: mat.values.iter().map(|x| x.as_canonical_u32()).collect(),
+            inv_twiddles: powers(
+                BabyBear::two_adic_generator(log_height).inverse(),
+                height / 2,
+            ),
+            twiddles: powers(
+                BabyBear::two_adic_generator(log_height + added_bits),
+                (height << added_bits) / 2,
+            ),
+            scale: shift
+                .powers()
+                .take(height)
+                .map(|x| (x * height_inv).as_canonical_u32())
+                .collect(),
+        })
+    }
+
+    fn lde_height(&self) -> usize {
+        self.height << self.added_bits
+    }
+
+    /// Wraps the output of a kernel into the evaluations of the LDE.
+    fn evaluations(&self, out: Vec<u32>) -> BitReversedMatrixView<RowMajorMatrix<BabyBear>> {
+        use p3_field::AbstractField;
+        use p3_matrix::bitrev::BitReversableMatrix;
+
+        let out = out
+            .into_iter()
+            .map(BabyBear::from_canonical_u32)
+            .collect::<Vec<_>>();
+        RowMajorMatrix::new(out, self.width).bit_reverse_rows()
+    }
+}
+
+#[cfg(feature = "cuda")]
+mod cuda {
+    use std::sync::OnceLock;
+
+    use p3_baby_bear::BabyBear;
+    use p3_matrix::bitrev::BitReversedMatrixView;
+    use p3_matrix::dense::RowMajorMatrix;
+
+    use super::LdeInputs;
+
+    extern "C" {
+        fn sp1_cuda_device_count() -> i32;
+        fn sp1_cuda_babybear_coset_lde(
+            values: *const u32,
+            height: u32,
+            width: u32,
+            log_blowup: u32,
+            inv_twiddles: *const u32,
+            twiddles: *const u32,
+            scale: *const u32,
+            out: *mut u32,
+        ) -> i32;
+    }
+
+    /// Whether there is a CUDA device.
+    pub fn available() -> bool {
+        static AVAILABLE: OnceLock<bool> = OnceLock::new();
+        *AVAILABLE.get_or_init(|| unsafe { sp1_cuda_device_count() } > 0)
+    }
+
+    /// Computes the coset LDE of `mat` on the device, or returns `None` if `mat` is too small or
+    /// the kernel fails.
+    pub fn coset_lde_batch(
+        mat: &RowMajorMatrix<BabyBear>,
+        added_bits: usize,
+        shift: BabyBear,
+    ) -> Option<BitReversedMatrixView<RowMajorMatrix<BabyBear>>> {
+        let inputs = LdeInputs::new(mat, added_bits, shift)?;
+        let mut out = vec![0u32; inputs.lde_height() * inputs.width];
+        let err = unsafe {
+            sp1_cuda_babybear_coset_lde(
+                inputs.values.as_ptr(),
+                inputs.height as u32,
+                inputs.width as u32,
+                inputs.added_bits as u32,
+                inputs.inv_twiddles.as_ptr(),
+                inputs.twiddles.as_ptr(),
+                inputs.scale.as_ptr(),
+                out.as_mut_ptr(),
+            )
+        };
+        if err != 0 {
+            tracing::warn!(
+                "CUDA coset LDE failed with error {}, proving on the CPU instead",
+                err
+            );
+            return None;
+        }
+        Some(inputs.evaluations(out))
+    }
+}
+
+#[cfg(not(feature = "cuda"))]
+mod cuda {
+    use p3_baby_bear::BabyBear;
+    use p3_matrix::bitrev::BitReversedMatrixView;
+    use p3_matrix::dense::RowMajorMatrix;
+
+    pub fn available() -> bool {
+        false
+    }
+
+    pub fn coset_lde_batch(
+        _: &RowMajorMatrix<BabyBear>,
+        _: usize,
+        _: BabyBear,
+    ) -> Option<BitReversedMatrixView<RowMajorMatrix<BabyBear>>> {
+        None
+    }
+}
+
+#[cfg(all(feature = "metal", target_os = "macos"))]
+mod metal {
+    use std::ffi::c_void;
+    use std::sync::{Mutex, OnceLock};
+
+    use ::metal::{
+        Buffer, CommandQueue, CompileOptions, ComputeCommandEncoderRef, ComputePipelineState,
+        Device, MTLResourceOptions, MTLSize,
+    };
+    use p3_baby_bear::BabyBear;
+    use p3_matrix::bitrev::BitReversedMatrixView;
+    use p3_matrix::dense::RowMajorMatrix;
+
+    use super::LdeInputs;
+
+    const SHADER: &str = include_str!("../../metal/babybear_dft.metal");
+
+    #[repr(C)]
+    struct StageParams {
+        width: u32,
+        half_len: u32,
+        stride: u32,
+    }
+
+    #[repr(C)]
+    struct PadParams {
+        width: u32,
+        log_height: u32,
+    }
+
+    /// The device and the compiled kernels.
+    struct MetalContext {
+        device: Device,
+        queue: CommandQueue,
+        dif_stage: ComputePipelineState,
+        reverse_scale_pad: ComputePipelineState,
+    }
+
+    // SAFETY: Metal devices, queues and pipelines are thread safe, and encoding is serialized
+    // by the mutex of the context.
+    unsafe impl Send for MetalContext {}
+
+    impl MetalContext {
+        fn new() -> Result<Self, String> {
+            let device = Device::system_default().ok_or("no Metal device")?;
+            let library = device.new_library_with_source(SHADER, &CompileOptions::new())?;
+            let pipeline = |name: &str| {
+                let function = library.get_function(name, None)?;
+                device.new_compute_pipeline_state_with_function(&function)
+            };
+            Ok(Self {
+                dif_stage: pipeline("dif_stage")?,
+                reverse_scale_pad: pipeline("reverse_scale_pad")?,
+                queue: device.new_command_queue(),
+                device,
+            })
+        }
+
+        fn buffer(&self, data: &[u32]) -> Buffer {
+            self.device.new_buffer_with_data(
+                data.as_ptr() as *const c_void,
+                std::mem::size_of_val(data).max(4) as u64,
+                MTLResourceOptions::StorageModeShared,
+            )
+        }
+
+        fn dispatch(
+            &self,
+            encoder: &ComputeCommandEncoderRef,
+            pipeline: &ComputePipelineState,
+            rows: usize,
+            width: usize,
+        ) {
+            encoder.set_compute_pipeline_state(pipeline);
+            let threads = pipeline.max_total_threads_per_threadgroup().min(256);
+            encoder.dispatch_threads(
+                MTLSize::new(rows as u64, width as u64, 1),
+                MTLSize::new(threads, 1, 1),
+            );
+        }
+
+        /// Encodes the stages of a decimation-in-frequency NTT of the columns of `values`.
+        fn encode_dif(
+            &self,
+            encoder: &ComputeCommandEncoderRef,
+            values: &Buffer,
+            twiddles: &Buffer,
+            height: usize,
+            width: usize,
+        ) {
+            let twiddles_len = height / 2;
+            let mut half_len = height / 2;
+            while half_len >= 1 {
+                let params = StageParams {
+                    width: width as u32,
+                    half_len: half_len as u32,
+                    stride: (twiddles_len / half_len) as u32,
+                };
+                encoder.set_buffer(0, Some(values), 0);
+                encoder.set_buffer(1, Some(twiddles), 0);
+                encoder.set_bytes(
+                    2,
+                    std::mem::size_of::<StageParams>() as u64,
+                    &params as *const StageParams as *const c_void,
+                );
+                self.dispatch(encoder, &self.dif_stage, height / 2, width);
+                half_len /= 2;
+            }
+        }
+
+        fn coset_lde(&self, inputs: &LdeInputs) -> Vec<u32> {
+            let lde_len = inputs.lde_height() * inputs.width;
+            let values = self.buffer(&inputs.values);
+            let inv_twiddles = self.buffer(&inputs.inv_twiddles);
+            let twiddles = self.buffer(&inputs.twiddles);
+            let scale = self.buffer(&inputs.scale);
+            let lde = self.buffer(&vec![0u32; lde_len]);
+
+            let command_buffer = self.queue.new_command_buffer();
+            let encoder = command_buffer.new_compute_command_encoder();
+
+            // Interpolate, which gives the coefficients in bit-reversed order.
+            self.encode_dif(encoder, &values, &inv_twiddles, inputs.height, inputs.width);
+
+            // Put the coefficients in order, shift them to the coset and pad them.
+            let params = PadParams {
+                width: inputs.width as u32,
+                log_height: inputs.log_height as u32,
+            };
+            encoder.set_buffer(0, Some(&values), 0);
+            encoder.set_buffer(1, Some(&lde), 0);
+            encoder.set_buffer(2, Some(&scale), 0);
+            encoder.set_bytes(
+                3,
+                std::mem::size_of::<PadParams>() as u64,
+                &params as *const PadParams as *const c_void,
+            );
+            self.dispatch(
+                encoder,
+                &self.reverse_scale_pad,
+                inputs.height,
+                inputs.width,
+            );
+
+            // Evaluate over the extended coset.
+            self.encode_dif(encoder, &lde, &twiddles, inputs.lde_height(), inputs.width);
+            encoder.end_encoding();
+            command_buffer.commit();
+            command_buffer.wait_until_completed();
+
+            let out = lde.contents() as *const u32;
+            unsafe { std::slice::from_raw_parts(out, lde_len) }.to_vec()
+        }
+    }
+
+    fn context() -> Option<&'static Mutex<MetalContext>> {
+        static CONTEXT: OnceLock<Option<Mutex<MetalContext>>> = OnceLock::new();
+        CONTEXT
+            .get_or_init(|| match MetalContext::new() {
+                Ok(context) => Some(Mutex::new(context)),
+                Err(e) => {
+                    tracing::warn!("failed to initialize Metal: {}", e);
+                    None
+                }
+            })
+            .as_ref()
+    }
+
+    /// Whether there is a Metal device and the kernels compiled.
+    pub fn available() -> bool {
+        context().is_some()
+    }
+
+    /// Computes the coset LDE of `mat` on the device, or returns `None` if `mat` is too small.
+    pub fn coset_lde_batch(
+        mat: &RowMajorMatrix<BabyBear>,
+        added_bits: usize,
+        shift: BabyBear,
+    ) -> Option<BitReversedMatrixView<RowMajorMatrix<BabyBear>>> {
+        let inputs = LdeInputs::new(mat, added_bits, shift)?;
+        let out = context()?.lock().unwrap().coset_lde(&inputs);
+        Some(inputs.evaluations(out))
+    }
+}
+
+#[cfg(not(all(feature = "metal", target_os = "macos")))]
+mod metal {
+    use p3_baby_bear::BabyBear;
+    use p3_matrix::bitrev::BitReversedMatrixView;
+    use p3_matrix::dense::RowMajorMatrix;
+
+    pub fn available() -> bool {
+        false
+    }
+
+    pub fn coset_lde_batch(
+        _: &RowMajorMatrix<BabyBear>,
+        _: usize,
+        _: BabyBear,
+    ) -> Option<BitReversedMatrixView<RowMajorMatrix<BabyBear>>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p3_field::AbstractField;
+    use p3_matrix::Matrix;
+
+    #[test]
+    fn test_coset_lde_batch() {
+        let (log_height, width) = (13, 3);
+        let values = (0..(width << log_height) as u32)
+            .map(|i| BabyBear::from_canonical_u32(i.wrapping_mul(0x9e3779b9) % 0x78000001))
+            .collect::<Vec<_>>();
+        let mat = RowMajorMatrix::new(values, width);
+        let shift = BabyBear::generator();
+        let expected = Radix2DitParallel {}.coset_lde_batch(mat.clone(), 1, shift);
+
+        for backend in [
+            ProverBackend::Cuda,
+            ProverBackend::Metal,
+            ProverBackend::Cpu,
+        ] {
+            set_prover_backend(backend);
+            let lde = SP1Dft.coset_lde_batch(mat.clone(), 1, shift);
+            assert_eq!(lde.height(), expected.height());
+            for i in 0..lde.height() {
+                assert_eq!(lde.row_slice(i).to_vec(), expected.row_slice(i).to_vec());
+            }
+        }
+    }
+}
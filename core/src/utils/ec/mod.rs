@@ -28,6 +28,7 @@ pub enum CurveType {
     Bn254,
     Ed25519,
     Bls12381,
+    Secp256r1,
 }
 
 impl Display for CurveType {
@@ -37,6 +38,7 @@ impl Display for CurveType {
             CurveType::Bn254 => write!(f, "Bn254"),
             CurveType::Ed25519 => write!(f, "Ed25519"),
             CurveType::Bls12381 => write!(f, "Bls12381"),
+            CurveType::Secp256r1 => write!(f, "Secp256r1"),
         }
     }
 }
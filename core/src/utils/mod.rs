@@ -1,18 +1,24 @@
 mod buffer;
 mod config;
+mod dft;
 pub mod ec;
 mod logger;
 mod options;
 mod programs;
+mod progress;
 mod prove;
 mod tracer;
+mod tune;
 
 pub use buffer::*;
 pub use config::*;
+pub use dft::*;
 pub use logger::*;
 pub use options::*;
+pub use progress::*;
 pub use prove::*;
 pub use tracer::*;
+pub use tune::*;
 
 #[cfg(test)]
 pub use programs::*;
@@ -1,9 +1,41 @@
-#[derive(Debug, Clone, Copy)]
+use serde::{Deserialize, Serialize};
+
+use crate::stark::{DomainSeparator, FriParameters, LookupArgument};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct SP1CoreOpts {
     pub shard_size: usize,
     pub shard_batch_size: usize,
-    pub shard_chunking_multiplier: usize,
+    /// The memory, in bytes, which the shards being committed or proven at the same time may use,
+    /// as estimated by [`crate::stark::estimate_shard_memory`]. `None` means most of the memory
+    /// available on the machine.
+    pub shard_memory_budget: Option<u64>,
     pub reconstruct_commitments: bool,
+    /// The maximum number of cycles the program may execute before halting with
+    /// [`crate::runtime::ExecutionError::OutOfGas`]. `None` means the run is unbounded.
+    pub max_cycles: Option<u64>,
+    /// The maximum number of bytes of guest memory (including registers and the program's memory
+    /// image) the program may touch before halting with
+    /// [`crate::runtime::ExecutionError::MemoryLimitExceeded`]. `None` means memory is unbounded.
+    pub max_memory: Option<u64>,
+    /// Whether the program may halt with a nonzero exit code. If not, execution fails with
+    /// [`crate::runtime::ExecutionError::HaltWithNonZeroExitCode`], so no proof is generated.
+    pub allow_nonzero_exit_code: bool,
+    /// Where the traces of the shards are kept between their commitment and their proof.
+    pub trace_storage: TraceStorage,
+    /// The layout of the lookup argument of the core machine. The recursion programs only verify
+    /// core proofs of the default, batched layout.
+    pub lookup_argument: LookupArgument,
+    /// The tag which the challenger of the core machine absorbs after the verifying key, so that
+    /// its proofs are rejected by the keys of a machine with another tag. The recursion programs of
+    /// `SP1Prover` verify core proofs without a tag.
+    pub domain_separator: DomainSeparator,
+    /// Whether the lines the program writes to stdout and stderr are printed as they are written.
+    /// They are captured in [`crate::runtime::ExecutionReport`] either way.
+    pub stream_guest_output: bool,
+    /// The percentage of the heap which the program may use before a warning is logged at the end
+    /// of an execution with a report. `None` disables the warning.
+    pub heap_warning_percent: Option<u64>,
 }
 
 impl Default for SP1CoreOpts {
@@ -11,8 +43,16 @@ impl Default for SP1CoreOpts {
         Self {
             shard_size: 1 << 22,
             shard_batch_size: 16,
-            shard_chunking_multiplier: 1,
+            shard_memory_budget: None,
             reconstruct_commitments: true,
+            max_cycles: None,
+            max_memory: None,
+            allow_nonzero_exit_code: false,
+            trace_storage: TraceStorage::default(),
+            lookup_argument: LookupArgument::default(),
+            domain_separator: DomainSeparator::NONE,
+            stream_guest_output: false,
+            heap_warning_percent: Some(90),
         }
     }
 }
@@ -24,3 +64,110 @@ impl SP1CoreOpts {
         opts
     }
 }
+
+/// Where the prover keeps the traces of the shards of a checkpointed proof (with a nonzero
+/// `shard_batch_size`) between their commitment and their proof.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TraceStorage {
+    /// The shards of each checkpoint are committed in parallel, and traced again from the
+    /// checkpoint to be proven.
+    #[default]
+    Memory,
+    /// The shards are committed one at a time, and their traces and commitment data are written to
+    /// temporary files which are memory-mapped back when the shard is proven, so that only one
+    /// shard is resident at a time. This bounds the memory of the prover to about the memory of a
+    /// shard, at the cost of throughput.
+    Disk,
+}
+
+/// The number of bits of the proof-of-work of the queries of FRI of the presets.
+const PROOF_OF_WORK_BITS: usize = 16;
+
+/// The conjectured security of the STARKs of a prover, which sets the number of queries of FRI of
+/// each of its configs for their blowup factor.
+///
+/// The conjectured security of a config is `log_blowup * num_queries + proof_of_work_bits`, and
+/// the parameters of FRI are recorded in the verifying keys, so that the proofs are verified with
+/// the parameters they were proven with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SecurityPreset {
+    /// The parameters of SP1, of about 116 bits of conjectured security, whose number of queries
+    /// can be changed with the `FRI_QUERIES` environment variable.
+    #[default]
+    Standard,
+    /// 80 bits of conjectured security, for tests and development.
+    Bits80,
+    /// 100 bits of conjectured security.
+    Bits100,
+    /// 128 bits of conjectured security.
+    Bits128,
+    /// A custom conjectured security, with a custom number of bits of proof-of-work.
+    Custom {
+        security_bits: usize,
+        proof_of_work_bits: usize,
+    },
+}
+
+impl SecurityPreset {
+    /// The parameters of FRI of the preset for a config with the given blowup factor, whose
+    /// number of queries is `standard_num_queries` with [SecurityPreset::Standard].
+    pub fn fri_parameters(self, log_blowup: usize, standard_num_queries: usize) -> FriParameters {
+        let (security_bits, proof_of_work_bits) = match self {
+            SecurityPreset::Standard => {
+                return FriParameters {
+                    log_blowup,
+                    num_queries: standard_num_queries,
+                    proof_of_work_bits: PROOF_OF_WORK_BITS,
+                }
+            }
+            SecurityPreset::Bits80 => (80, PROOF_OF_WORK_BITS),
+            SecurityPreset::Bits100 => (100, PROOF_OF_WORK_BITS),
+            SecurityPreset::Bits128 => (128, PROOF_OF_WORK_BITS),
+            SecurityPreset::Custom {
+                security_bits,
+                proof_of_work_bits,
+            } => (security_bits, proof_of_work_bits),
+        };
+        FriParameters {
+            log_blowup,
+            num_queries: security_bits
+                .saturating_sub(proof_of_work_bits)
+                .div_ceil(log_blowup)
+                .max(1),
+            proof_of_work_bits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_security_presets() {
+        let standard = SecurityPreset::Standard.fri_parameters(1, 100);
+        assert_eq!(standard.num_queries, 100);
+        assert_eq!(standard.conjectured_security_bits(), 116);
+
+        for (preset, bits) in [
+            (SecurityPreset::Bits80, 80),
+            (SecurityPreset::Bits100, 100),
+            (SecurityPreset::Bits128, 128),
+        ] {
+            for log_blowup in [1, 3, 4] {
+                let parameters = preset.fri_parameters(log_blowup, 100);
+                assert_eq!(parameters.log_blowup, log_blowup);
+                assert!(parameters.conjectured_security_bits() >= bits);
+                assert!(parameters.conjectured_security_bits() < bits + log_blowup);
+            }
+        }
+
+        let custom = SecurityPreset::Custom {
+            security_bits: 64,
+            proof_of_work_bits: 20,
+        }
+        .fri_parameters(2, 100);
+        assert_eq!(custom.num_queries, 22);
+        assert_eq!(custom.proof_of_work_bits, 20);
+    }
+}
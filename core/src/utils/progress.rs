@@ -0,0 +1,141 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::Duration;
+
+use web_time::Instant;
+
+/// A stage of proof generation which has just completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProvingStage {
+    /// The program was executed, and its trace split into checkpoints which are proven in turn.
+    Executed { cycles: u64, num_checkpoints: usize },
+    /// The shards of a checkpoint were committed to, for `num_shards` shards in total so far.
+    ShardsCommitted {
+        checkpoint: usize,
+        num_checkpoints: usize,
+        num_shards: usize,
+    },
+    /// The shard of index `shard` out of `num_shards` was proven.
+    ShardProved { shard: usize, num_shards: usize },
+    /// All the shards of the core proof were proven.
+    CoreProved { num_shards: usize },
+    /// A layer of the recursion tree was proven, with `num_proofs` proofs left to reduce. The
+    /// first layer verifies the core proofs and the deferred proofs.
+    RecursionLayer { layer: usize, num_proofs: usize },
+    /// The compressed proof was shrunk.
+    Shrunk,
+    /// The shrunk proof was wrapped into a proof over BN254.
+    Wrapped,
+    /// The BN254 proof was wrapped into a PLONK proof.
+    PlonkWrapped,
+    /// The BN254 proof was wrapped into a Groth16 proof.
+    Groth16Wrapped,
+}
+
+/// A progress event, which is a completed stage with the time elapsed since the reporter was
+/// created, or since it was teed for the receiver of [ProgressReporter::tee].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProvingEvent {
+    pub stage: ProvingStage,
+    pub elapsed: Duration,
+}
+
+/// Reports the stages of proof generation to a channel, so that a progress bar can be shown while
+/// a proof is generated.
+///
+/// The default reporter has no channel and ignores the stages.
+#[derive(Debug, Clone, Default)]
+pub struct ProgressReporter {
+    senders: Vec<(Sender<ProvingEvent>, Instant)>,
+}
+
+impl ProgressReporter {
+    /// Creates a reporter which sends the events to `sender`.
+    pub fn new(sender: Sender<ProvingEvent>) -> Self {
+        Self {
+            senders: vec![(sender, Instant::now())],
+        }
+    }
+
+    /// Creates a reporter and the receiver of its events.
+    pub fn channel() -> (Self, Receiver<ProvingEvent>) {
+        let (sender, receiver) = channel();
+        (Self::new(sender), receiver)
+    }
+
+    /// Creates a reporter which sends the events to the channels of this reporter and to a new
+    /// channel, whose receiver is returned. The events of the new channel are timed from the call
+    /// to this method.
+    pub fn tee(&self) -> (Self, Receiver<ProvingEvent>) {
+        let (sender, receiver) = channel();
+        let mut senders = self.senders.clone();
+        senders.push((sender, Instant::now()));
+        (Self { senders }, receiver)
+    }
+
+    /// Reports that a stage has completed. Events are dropped once the receiver is gone.
+    pub fn report(&self, stage: ProvingStage) {
+        for (sender, start) in self.senders.iter() {
+            let _ = sender.send(ProvingEvent {
+                stage,
+                elapsed: start.elapsed(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::SP1Stdin;
+    use crate::runtime::Program;
+    use crate::utils::tests::FIBONACCI_ELF;
+    use crate::utils::{prove_with_progress, setup_logger, BabyBearPoseidon2, SP1CoreOpts};
+
+    #[test]
+    fn test_prove_with_progress() {
+        setup_logger();
+        let (progress, events) = ProgressReporter::channel();
+        let program = Program::from(FIBONACCI_ELF);
+        let config = BabyBearPoseidon2::new();
+        let (proof, _) = prove_with_progress(
+            program,
+            &SP1Stdin::new(),
+            config,
+            SP1CoreOpts::default(),
+            &progress,
+        )
+        .unwrap();
+        drop(progress);
+
+        let stages = events.iter().map(|event| event.stage).collect::<Vec<_>>();
+        let num_shards = proof.shard_proofs.len();
+        assert!(matches!(stages[0], ProvingStage::Executed { .. }));
+        assert_eq!(
+            stages.last(),
+            Some(&ProvingStage::CoreProved { num_shards })
+        );
+        let proved = stages
+            .iter()
+            .filter(|stage| matches!(stage, ProvingStage::ShardProved { .. }))
+            .count();
+        assert_eq!(proved, num_shards);
+    }
+
+    #[test]
+    fn test_tee() {
+        let (progress, events) = ProgressReporter::channel();
+        let (teed, teed_events) = progress.tee();
+        progress.report(ProvingStage::Shrunk);
+        teed.report(ProvingStage::Wrapped);
+        drop((progress, teed));
+
+        let stages = |events: Receiver<ProvingEvent>| {
+            events.iter().map(|event| event.stage).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            stages(events),
+            vec![ProvingStage::Shrunk, ProvingStage::Wrapped]
+        );
+        assert_eq!(stages(teed_events), vec![ProvingStage::Wrapped]);
+    }
+}
@@ -4,27 +4,32 @@ use std::io::{Seek, Write};
 use web_time::Instant;
 
 pub use baby_bear_blake3::BabyBearBlake3;
+use p3_baby_bear::BabyBear;
 use p3_challenger::CanObserve;
-use p3_field::PrimeField32;
+use p3_field::{AbstractField, Field, PrimeField32};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use size::Size;
 use thiserror::Error;
 
-use crate::air::MachineAir;
+use crate::air::{MachineAir, PublicValues};
 use crate::io::{SP1PublicValues, SP1Stdin};
 use crate::lookup::InteractionBuilder;
-use crate::runtime::ExecutionError;
-use crate::runtime::{ExecutionRecord, ShardingConfig};
-use crate::stark::DebugConstraintBuilder;
+use crate::runtime::{ExecutionError, ExecutionReport};
+use crate::runtime::{ExecutionRecord, ExecutionState, ShardingConfig};
 use crate::stark::MachineProof;
 use crate::stark::ProverConstraintFolder;
 use crate::stark::StarkVerifyingKey;
 use crate::stark::Val;
 use crate::stark::VerifierConstraintFolder;
+use crate::stark::VerifyingKeyDigest;
+use crate::stark::{
+    estimate_shard_memory, MachineRecord, MerkleHash, ShardScheduler, StarkMachine,
+};
 use crate::stark::{Com, PcsProverData, RiscvAir, ShardProof, StarkProvingKey, UniConfig};
-use crate::stark::{MachineRecord, StarkMachine};
-use crate::utils::SP1CoreOpts;
+use crate::stark::{ConstraintCheckError, DebugConstraintBuilder};
+use crate::stark::{NoPrecompiles, PrecompileAir, PrecompileExtension};
+use crate::utils::{ProgressReporter, ProvingStage, SP1CoreOpts, TraceStorage};
 use crate::{
     runtime::{Program, Runtime},
     stark::StarkGenericConfig,
@@ -41,12 +46,56 @@ pub enum SP1CoreProverError {
     IoError(io::Error),
     #[error("serialization error: {0}")]
     SerializationError(bincode::Error),
+    #[error("constraint check failed: {0}")]
+    ConstraintCheckError(ConstraintCheckError),
+}
+
+/// A computation over a STARK config of BabyBear, which [with_baby_bear_config] runs with the
+/// config of the Merkle hash chosen at runtime.
+pub trait BabyBearConfigVisitor {
+    type Output;
+
+    fn visit<SC>(self, config: SC) -> Self::Output
+    where
+        SC: StarkGenericConfig<Val = BabyBear>,
+        SC::Challenger: Clone,
+        OpeningProof<SC>: Send + Sync,
+        Com<SC>: Send + Sync,
+        PcsProverData<SC>: Send + Sync,
+        ShardMainData<SC>: Serialize + DeserializeOwned;
+}
+
+/// Runs `visitor` with the config of BabyBear whose Merkle trees are hashed with `hash`:
+/// [BabyBearPoseidon2], [BabyBearBlake3] or [BabyBearKeccak].
+pub fn with_baby_bear_config<V: BabyBearConfigVisitor>(hash: MerkleHash, visitor: V) -> V::Output {
+    match hash {
+        MerkleHash::Poseidon2 => visitor.visit(BabyBearPoseidon2::new()),
+        MerkleHash::Blake3 => visitor.visit(BabyBearBlake3::new()),
+        MerkleHash::Keccak => visitor.visit(BabyBearKeccak::new()),
+    }
 }
 
 pub fn prove_simple<SC: StarkGenericConfig>(
     config: SC,
     runtime: Runtime,
 ) -> Result<MachineProof<SC>, SP1CoreProverError>
+where
+    SC::Challenger: Clone,
+    OpeningProof<SC>: Send + Sync,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    ShardMainData<SC>: Serialize + DeserializeOwned,
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+{
+    prove_simple_with_precompiles::<SC, NoPrecompiles>(config, runtime)
+}
+
+/// Proves the execution of `runtime` as [prove_simple] does, with the machine including the
+/// precompiles of the extension `E`.
+pub fn prove_simple_with_precompiles<SC: StarkGenericConfig, E: PrecompileAir<SC>>(
+    config: SC,
+    runtime: Runtime,
+) -> Result<MachineProof<SC>, SP1CoreProverError>
 where
     SC::Challenger: Clone,
     OpeningProof<SC>: Send + Sync,
@@ -56,14 +105,30 @@ where
     <SC as StarkGenericConfig>::Val: PrimeField32,
 {
     // Setup the machine.
-    let machine = RiscvAir::machine(config);
+    let machine = RiscvAir::<Val<SC>, E>::machine_with_precompiles(config);
     let (pk, _) = machine.setup(runtime.program.as_ref());
+    prove_runtime(&machine, &pk, runtime)
+}
 
+/// Proves the record of an executed `runtime` in one batch.
+fn prove_runtime<SC: StarkGenericConfig, E: PrecompileAir<SC>>(
+    machine: &StarkMachine<SC, RiscvAir<Val<SC>, E>>,
+    pk: &StarkProvingKey<SC>,
+    runtime: Runtime,
+) -> Result<MachineProof<SC>, SP1CoreProverError>
+where
+    SC::Challenger: Clone,
+    OpeningProof<SC>: Send + Sync,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    ShardMainData<SC>: Serialize + DeserializeOwned,
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+{
     // Prove the program.
     let mut challenger = machine.config().challenger();
     let proving_start = Instant::now();
     let proof = machine.prove::<LocalProver<_, _>>(
-        &pk,
+        pk,
         runtime.record,
         &mut challenger,
         SP1CoreOpts::default(),
@@ -88,7 +153,122 @@ pub fn prove<SC: StarkGenericConfig + Send + Sync>(
     stdin: &SP1Stdin,
     config: SC,
     opts: SP1CoreOpts,
-) -> Result<(MachineProof<SC>, Vec<u8>), SP1CoreProverError>
+) -> Result<(MachineProof<SC>, SP1PublicValues), SP1CoreProverError>
+where
+    SC::Challenger: Clone,
+    OpeningProof<SC>: Send + Sync,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    ShardMainData<SC>: Serialize + DeserializeOwned,
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+    StarkVerifyingKey<SC>: VerifyingKeyDigest,
+{
+    prove_with_progress(program, stdin, config, opts, &ProgressReporter::default())
+}
+
+/// Executes a program and checks the constraints of every chip on the traces of all its shards,
+/// without committing to the traces or proving them.
+///
+/// This tells whether a program would prove much faster than [prove] does. Fails with the first
+/// constraint which does not hold, with its chip, shard and row, and otherwise returns the public
+/// values and the report of the execution.
+pub fn check_program_constraints<SC: StarkGenericConfig>(
+    program: Program,
+    stdin: &SP1Stdin,
+    config: SC,
+    opts: SP1CoreOpts,
+) -> Result<(SP1PublicValues, ExecutionReport), SP1CoreProverError>
+where
+    SC::Challenger: Clone,
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+    StarkVerifyingKey<SC>: VerifyingKeyDigest,
+{
+    let mut runtime = Runtime::new(program.clone(), opts);
+    runtime.write_vecs(&stdin.buffer);
+    runtime.state.random_seed = stdin.random_seed;
+
+    // Shards are re-executed from checkpoints, so any streamed input has to be buffered.
+    if let Some(stream) = stdin.stream.as_ref() {
+        runtime
+            .buffer_stream(stream)
+            .map_err(SP1CoreProverError::ExecutionError)?;
+    }
+    for proof in stdin.proofs.iter() {
+        runtime.write_proof(proof.0.clone(), proof.1.clone());
+    }
+
+    // Setup the machine.
+    let machine = RiscvAir::machine(config)
+        .with_lookup_argument(opts.lookup_argument)
+        .with_domain_separator(opts.domain_separator);
+    let (pk, vk) = machine.setup(runtime.program.as_ref());
+
+    // Execute the program, saving checkpoints at the start of every `shard_batch_size` cycle range.
+    let mut checkpoints = Vec::new();
+    let (committed_values, mut public_values) = loop {
+        let (checkpoint, done) = runtime
+            .execute_state()
+            .map_err(SP1CoreProverError::ExecutionError)?;
+        checkpoints.push(checkpoint);
+        if done {
+            break (
+                SP1PublicValues::with_leaves(
+                    &runtime.state.public_values_stream,
+                    std::mem::take(&mut runtime.state.public_leaves),
+                ),
+                runtime.record.public_values,
+            );
+        }
+    };
+    public_values.vk_digest = vk.digest_u32();
+
+    // Check the shards of each checkpoint, with the same permutation challenges for all of them.
+    let mut challenger = machine.config().challenger();
+    vk.observe_into(&mut challenger);
+    let mut cumulative_sum = SC::Challenge::zero();
+    for state in checkpoints {
+        let shards = shard_checkpoint(&machine, program.clone(), state, public_values, opts);
+        cumulative_sum += machine
+            .check_shard_constraints(&pk, &shards, &mut challenger.clone())
+            .map_err(SP1CoreProverError::ConstraintCheckError)?;
+    }
+    if !cumulative_sum.is_zero() {
+        return Err(SP1CoreProverError::ConstraintCheckError(
+            ConstraintCheckError::NonZeroCumulativeSum,
+        ));
+    }
+    Ok((committed_values, runtime.report))
+}
+
+/// Proves a program as [prove] does, and reports the stages of the proof to `progress`.
+pub fn prove_with_progress<SC: StarkGenericConfig + Send + Sync>(
+    program: Program,
+    stdin: &SP1Stdin,
+    config: SC,
+    opts: SP1CoreOpts,
+    progress: &ProgressReporter,
+) -> Result<(MachineProof<SC>, SP1PublicValues), SP1CoreProverError>
+where
+    SC::Challenger: Clone,
+    OpeningProof<SC>: Send + Sync,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    ShardMainData<SC>: Serialize + DeserializeOwned,
+    <SC as StarkGenericConfig>::Val: PrimeField32,
+    StarkVerifyingKey<SC>: VerifyingKeyDigest,
+{
+    prove_with_precompiles::<SC, NoPrecompiles>(program, stdin, config, opts, progress)
+}
+
+/// Proves a program as [prove_with_progress] does, with the machine including the precompiles of
+/// the extension `E`, whose proofs are verified by `RiscvAir::<_, E>::machine_with_precompiles`.
+pub fn prove_with_precompiles<SC: StarkGenericConfig + Send + Sync, E: PrecompileAir<SC>>(
+    program: Program,
+    stdin: &SP1Stdin,
+    config: SC,
+    opts: SP1CoreOpts,
+    progress: &ProgressReporter,
+) -> Result<(MachineProof<SC>, SP1PublicValues), SP1CoreProverError>
 where
     SC::Challenger: Clone,
     OpeningProof<SC>: Send + Sync,
@@ -96,24 +276,42 @@ where
     PcsProverData<SC>: Send + Sync,
     ShardMainData<SC>: Serialize + DeserializeOwned,
     <SC as StarkGenericConfig>::Val: PrimeField32,
+    StarkVerifyingKey<SC>: VerifyingKeyDigest,
 {
     let proving_start = Instant::now();
 
     // Execute the program.
     let mut runtime = Runtime::new(program.clone(), opts);
+    E::register_syscalls(&mut runtime);
     runtime.write_vecs(&stdin.buffer);
+    runtime.state.random_seed = stdin.random_seed;
+
+    // Shards are re-executed from checkpoints, so any streamed input has to be buffered.
+    if let Some(stream) = stdin.stream.as_ref() {
+        runtime
+            .buffer_stream(stream)
+            .map_err(SP1CoreProverError::ExecutionError)?;
+    }
     for proof in stdin.proofs.iter() {
         runtime.write_proof(proof.0.clone(), proof.1.clone());
     }
 
     // Setup the machine.
-    let machine = RiscvAir::machine(config);
+    let machine = RiscvAir::<Val<SC>, E>::machine_with_precompiles(config)
+        .with_lookup_argument(opts.lookup_argument)
+        .with_domain_separator(opts.domain_separator);
     let (pk, vk) = machine.setup(runtime.program.as_ref());
+    let vk_digest = vk.digest_u32();
 
     // If we don't need to batch, we can just run the program normally and prove it.
     if opts.shard_batch_size == 0 {
         // Execute the runtime and collect all the events..
         runtime.run().map_err(SP1CoreProverError::ExecutionError)?;
+        runtime.record.public_values.vk_digest = vk_digest;
+        progress.report(ProvingStage::Executed {
+            cycles: runtime.state.global_clk,
+            num_checkpoints: 1,
+        });
 
         // If debugging is enabled, we will also debug the constraints.
         #[cfg(feature = "debug")]
@@ -123,14 +321,20 @@ where
         }
 
         // Generate the proof and return the proof and public values.
-        let public_values = std::mem::take(&mut runtime.state.public_values_stream);
-        let proof = prove_simple(machine.config().clone(), runtime)?;
+        let public_values = SP1PublicValues::with_leaves(
+            &runtime.state.public_values_stream,
+            std::mem::take(&mut runtime.state.public_leaves),
+        );
+        let proof = prove_runtime(&machine, &pk, runtime)?;
+        progress.report(ProvingStage::CoreProved {
+            num_shards: proof.shard_proofs.len(),
+        });
         return Ok((proof, public_values));
     }
 
     // Execute the program, saving checkpoints at the start of every `shard_batch_size` cycle range.
     let mut checkpoints = Vec::new();
-    let (public_values_stream, public_values) = loop {
+    let (committed_values, mut public_values) = loop {
         // Execute the runtime until we reach a checkpoint.
         let (checkpoint, done) = runtime
             .execute_state()
@@ -151,71 +355,153 @@ where
         // If we've reached the final checkpoint, break out of the loop.
         if done {
             break (
-                std::mem::take(&mut runtime.state.public_values_stream),
+                SP1PublicValues::with_leaves(
+                    &runtime.state.public_values_stream,
+                    std::mem::take(&mut runtime.state.public_leaves),
+                ),
                 runtime.record.public_values,
             );
         }
     };
+    public_values.vk_digest = vk_digest;
+    let num_checkpoints = checkpoints.len();
+    progress.report(ProvingStage::Executed {
+        cycles: runtime.state.global_clk,
+        num_checkpoints,
+    });
 
-    // For each checkpoint, generate events, shard them, commit shards, and observe in challenger.
-    let sharding_config = ShardingConfig::default();
-    let mut shard_main_datas = Vec::new();
     let mut challenger = machine.config().challenger();
     vk.observe_into(&mut challenger);
-    for checkpoint_file in checkpoints.iter_mut() {
-        let mut record = trace_checkpoint(program.clone(), checkpoint_file, opts);
-        record.public_values = public_values;
-        reset_seek(&mut *checkpoint_file);
+    let mut num_shards = 0;
+
+    // With traces on disk, commit to the shards one at a time and spill their main data, then
+    // prove them from the spilled data.
+    if opts.trace_storage == TraceStorage::Disk {
+        let mut spilled_shards = Vec::new();
+        for (checkpoint, checkpoint_file) in checkpoints.iter_mut().enumerate() {
+            let state = read_checkpoint(checkpoint_file);
+            let checkpoint_shards =
+                shard_checkpoint(&machine, program.clone(), state, public_values, opts);
+            num_shards += checkpoint_shards.len();
+            for shard in checkpoint_shards.into_iter() {
+                let pv = shard.public_values::<SC::Val>();
+                let data = tracing::info_span!("commit").in_scope(|| {
+                    LocalProver::commit_main(
+                        machine.config(),
+                        &machine,
+                        &shard,
+                        shard.index() as usize,
+                    )
+                });
+                drop(shard);
+                challenger.observe(data.main_commit.clone());
+                challenger.observe_slice(&pv[0..machine.num_pv_elts()]);
+                let file = tempfile::tempfile().map_err(SP1CoreProverError::IoError)?;
+                spilled_shards.push(
+                    data.save(file)
+                        .map_err(SP1CoreProverError::SerializationError)?,
+                );
+            }
+            progress.report(ProvingStage::ShardsCommitted {
+                checkpoint,
+                num_checkpoints,
+                num_shards,
+            });
+        }
+
+        let mut shard_proofs = Vec::<ShardProof<SC>>::new();
+        for spilled in spilled_shards.into_iter() {
+            let data = spilled
+                .materialize()
+                .map_err(SP1CoreProverError::SerializationError)?;
+            let chip_ordering = data.chip_ordering.clone();
+            let ordered_chips = machine
+                .shard_chips_ordered(&chip_ordering)
+                .collect::<Vec<_>>();
+            shard_proofs.push(LocalProver::prove_shard(
+                machine.config(),
+                &pk,
+                &ordered_chips,
+                data,
+                &mut challenger.clone(),
+            ));
+            progress.report(ProvingStage::ShardProved {
+                shard: shard_proofs.len() - 1,
+                num_shards,
+            });
+        }
+        let proof = MachineProof::<SC> { shard_proofs };
+        progress.report(ProvingStage::CoreProved { num_shards });
+        return Ok((proof, committed_values));
+    }
 
-        // Shard the record into shards.
-        let checkpoint_shards =
-            tracing::info_span!("shard").in_scope(|| machine.shard(record, &sharding_config));
+    // The shards of each checkpoint are traced while the shards of the previous checkpoint are
+    // committed or proven, and the scheduler keeps the shards in flight within its memory budget.
+    let scheduler = ShardScheduler::from_opts(&opts);
+    let trace_checkpoint = |file: &mut File| {
+        shard_checkpoint(
+            &machine,
+            program.clone(),
+            read_checkpoint(file),
+            public_values,
+            opts,
+        )
+    };
 
-        // Commit to each shard.
-        let (commitments, commit_data) = tracing::info_span!("commit")
-            .in_scope(|| LocalProver::commit_shards(&machine, &checkpoint_shards, opts));
-        shard_main_datas.push(commit_data);
+    // For each checkpoint, generate events, shard them, commit shards, and observe in challenger.
+    let mut next_shards = checkpoints.first_mut().map(trace_checkpoint);
+    for checkpoint in 0..num_checkpoints {
+        let checkpoint_shards = next_shards.take().unwrap();
+        let (commitments, traced) = rayon::join(
+            || {
+                tracing::info_span!("commit")
+                    .in_scope(|| LocalProver::commit_shards(&machine, &checkpoint_shards, opts).0)
+            },
+            || checkpoints.get_mut(checkpoint + 1).map(trace_checkpoint),
+        );
 
         // Observe the commitments.
         for (commitment, shard) in commitments.into_iter().zip(checkpoint_shards.iter()) {
             challenger.observe(commitment);
             challenger.observe_slice(&shard.public_values::<SC::Val>()[0..machine.num_pv_elts()]);
         }
+        num_shards += checkpoint_shards.len();
+        progress.report(ProvingStage::ShardsCommitted {
+            checkpoint,
+            num_checkpoints,
+            num_shards,
+        });
+        next_shards = traced;
     }
 
     // For each checkpoint, generate events and shard again, then prove the shards.
     let mut shard_proofs = Vec::<ShardProof<SC>>::new();
-    for mut checkpoint_file in checkpoints.into_iter() {
-        let checkpoint_shards = {
-            let mut events = trace_checkpoint(program.clone(), &checkpoint_file, opts);
-            events.public_values = public_values;
-            reset_seek(&mut checkpoint_file);
-            tracing::debug_span!("shard").in_scope(|| machine.shard(events, &sharding_config))
-        };
-        let mut checkpoint_proofs = checkpoint_shards
-            .into_iter()
-            .map(|shard| {
-                let config = machine.config();
-                let shard_data =
-                    LocalProver::commit_main(config, &machine, &shard, shard.index() as usize);
-
-                let chip_ordering = shard_data.chip_ordering.clone();
-                let ordered_chips = machine
-                    .shard_chips_ordered(&chip_ordering)
-                    .collect::<Vec<_>>()
-                    .to_vec();
-                LocalProver::prove_shard(
-                    config,
-                    &pk,
-                    &ordered_chips,
-                    shard_data,
-                    &mut challenger.clone(),
+    let mut next_shards = checkpoints.first_mut().map(trace_checkpoint);
+    for checkpoint in 0..num_checkpoints {
+        let checkpoint_shards = next_shards.take().unwrap();
+        let offset = shard_proofs.len();
+        let (proofs, traced) = rayon::join(
+            || {
+                scheduler.map(
+                    checkpoint_shards.into_iter().enumerate().collect(),
+                    |(_, shard)| estimate_shard_memory(&shard.stats()),
+                    |(index, shard)| {
+                        let proof = prove_checkpoint_shard(&machine, &pk, &shard, &challenger);
+                        progress.report(ProvingStage::ShardProved {
+                            shard: offset + index,
+                            num_shards,
+                        });
+                        proof
+                    },
                 )
-            })
-            .collect::<Vec<_>>();
-        shard_proofs.append(&mut checkpoint_proofs);
+            },
+            || checkpoints.get_mut(checkpoint + 1).map(trace_checkpoint),
+        );
+        shard_proofs.extend(proofs);
+        next_shards = traced;
     }
     let proof = MachineProof::<SC> { shard_proofs };
+    progress.report(ProvingStage::CoreProved { num_shards });
 
     // Print the summary.
     let proving_time = proving_start.elapsed().as_secs_f64();
@@ -227,7 +513,7 @@ where
         bincode::serialize(&proof).unwrap().len(),
     );
 
-    Ok((proof, public_values_stream))
+    Ok((proof, committed_values))
 }
 
 /// Runs a program and returns the public values stream.
@@ -238,6 +524,7 @@ pub fn run_test_io(
     let runtime = tracing::info_span!("runtime.run(...)").in_scope(|| {
         let mut runtime = Runtime::new(program, SP1CoreOpts::default());
         runtime.write_vecs(&inputs.buffer);
+        runtime.state.random_seed = inputs.random_seed;
         runtime.run().unwrap();
         runtime
     });
@@ -326,18 +613,69 @@ where
     Ok(proof)
 }
 
-fn trace_checkpoint(program: Program, file: &File, opts: SP1CoreOpts) -> ExecutionRecord {
-    let mut reader = std::io::BufReader::new(file);
+/// Reads the execution state of a checkpoint, and rewinds the file so that it can be read again.
+fn read_checkpoint(file: &mut File) -> ExecutionState {
+    let mut reader = std::io::BufReader::new(&*file);
     let state = bincode::deserialize_from(&mut reader).expect("failed to deserialize state");
-    let mut runtime = Runtime::recover(program.clone(), state, opts);
-    let (events, _) =
+    drop(reader);
+    file.seek(std::io::SeekFrom::Start(0))
+        .expect("failed to seek to start of tempfile");
+    state
+}
+
+/// Traces the execution from a checkpoint and splits its record into shards, whose public values
+/// are the final `public_values` of the program.
+///
+/// The same checkpoint always gives the same shards, so they can be traced once to commit to them
+/// and again to prove them, possibly on different machines.
+pub fn shard_checkpoint<SC: StarkGenericConfig, E: PrecompileExtension<Val<SC>>>(
+    machine: &StarkMachine<SC, RiscvAir<Val<SC>, E>>,
+    program: Program,
+    state: ExecutionState,
+    public_values: PublicValues<u32, u32>,
+    opts: SP1CoreOpts,
+) -> Vec<ExecutionRecord>
+where
+    Val<SC>: PrimeField32,
+{
+    let mut runtime = Runtime::recover(program, state, opts);
+    E::register_syscalls(&mut runtime);
+    let (mut record, _) =
         tracing::debug_span!("runtime.trace").in_scope(|| runtime.execute_record().unwrap());
-    events
+    record.public_values = public_values;
+    let sharding_config = ShardingConfig::new(opts.shard_size);
+    tracing::debug_span!("shard").in_scope(|| machine.shard(record, &sharding_config))
 }
 
-fn reset_seek(file: &mut File) {
-    file.seek(std::io::SeekFrom::Start(0))
-        .expect("failed to seek to start of tempfile");
+/// Commits to the main trace of a shard of a checkpoint and proves it, with a challenger which has
+/// observed the verifying key and the commitments of all the shards.
+pub fn prove_checkpoint_shard<SC: StarkGenericConfig + Send + Sync, E: PrecompileAir<SC>>(
+    machine: &StarkMachine<SC, RiscvAir<Val<SC>, E>>,
+    pk: &StarkProvingKey<SC>,
+    shard: &ExecutionRecord,
+    challenger: &SC::Challenger,
+) -> ShardProof<SC>
+where
+    SC::Challenger: Clone,
+    OpeningProof<SC>: Send + Sync,
+    Com<SC>: Send + Sync,
+    PcsProverData<SC>: Send + Sync,
+    ShardMainData<SC>: Serialize + DeserializeOwned,
+    Val<SC>: PrimeField32,
+{
+    let config = machine.config();
+    let shard_data = LocalProver::commit_main(config, machine, shard, shard.index() as usize);
+    let chip_ordering = shard_data.chip_ordering.clone();
+    let ordered_chips = machine
+        .shard_chips_ordered(&chip_ordering)
+        .collect::<Vec<_>>();
+    LocalProver::prove_shard(
+        config,
+        pk,
+        &ordered_chips,
+        shard_data,
+        &mut challenger.clone(),
+    )
 }
 
 #[cfg(debug_assertions)]
@@ -415,7 +753,6 @@ pub mod baby_bear_poseidon2 {
     use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
     use p3_challenger::DuplexChallenger;
     use p3_commit::ExtensionMmcs;
-    use p3_dft::Radix2DitParallel;
     use p3_field::{extension::BinomialExtensionField, Field};
     use p3_fri::{FriConfig, TwoAdicFriPcs};
     use p3_merkle_tree::FieldMerkleTreeMmcs;
@@ -425,7 +762,8 @@ pub mod baby_bear_poseidon2 {
     use serde::{Deserialize, Serialize};
     use sp1_primitives::RC_16_30;
 
-    use crate::stark::StarkGenericConfig;
+    use crate::stark::{FriParameters, MerkleHash, StarkGenericConfig};
+    use crate::utils::{fri_parameters, SP1Dft, SecurityPreset};
 
     pub type Val = BabyBear;
     pub type Challenge = BinomialExtensionField<Val, 4>;
@@ -441,7 +779,7 @@ pub mod baby_bear_poseidon2 {
         8,
     >;
     pub type ChallengeMmcs = ExtensionMmcs<Val, Challenge, ValMmcs>;
-    pub type Dft = Radix2DitParallel;
+    pub type Dft = SP1Dft;
     pub type Challenger = DuplexChallenger<Val, Perm, 16, 8>;
     type Pcs = TwoAdicFriPcs<Val, Dft, ValMmcs, ChallengeMmcs>;
 
@@ -467,39 +805,45 @@ pub mod baby_bear_poseidon2 {
     }
 
     pub fn default_fri_config() -> FriConfig<ChallengeMmcs> {
-        let perm = my_perm();
-        let hash = MyHash::new(perm.clone());
-        let compress = MyCompress::new(perm.clone());
-        let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
+        fri_config_with_preset(SecurityPreset::Standard)
+    }
+
+    pub fn compressed_fri_config() -> FriConfig<ChallengeMmcs> {
+        compressed_fri_config_with_preset(SecurityPreset::Standard)
+    }
+
+    /// The FRI config of the core and the recursion proofs with the given security preset.
+    pub fn fri_config_with_preset(preset: SecurityPreset) -> FriConfig<ChallengeMmcs> {
         let num_queries = match std::env::var("FRI_QUERIES") {
             Ok(value) => value.parse().unwrap(),
             Err(_) => 100,
         };
-        FriConfig {
-            log_blowup: 1,
-            num_queries,
-            proof_of_work_bits: 16,
-            mmcs: challenge_mmcs,
-        }
+        fri_config(preset.fri_parameters(1, num_queries))
     }
 
-    pub fn compressed_fri_config() -> FriConfig<ChallengeMmcs> {
-        let perm = my_perm();
-        let hash = MyHash::new(perm.clone());
-        let compress = MyCompress::new(perm.clone());
-        let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
+    /// The FRI config of the shrunk proofs with the given security preset.
+    pub fn compressed_fri_config_with_preset(preset: SecurityPreset) -> FriConfig<ChallengeMmcs> {
         let num_queries = match std::env::var("FRI_QUERIES") {
             Ok(value) => value.parse().unwrap(),
             Err(_) => 33,
         };
+        fri_config(preset.fri_parameters(3, num_queries))
+    }
+
+    fn fri_config(parameters: FriParameters) -> FriConfig<ChallengeMmcs> {
+        let perm = my_perm();
+        let hash = MyHash::new(perm.clone());
+        let compress = MyCompress::new(perm.clone());
+        let challenge_mmcs = ChallengeMmcs::new(ValMmcs::new(hash, compress));
         FriConfig {
-            log_blowup: 3,
-            num_queries,
-            proof_of_work_bits: 16,
+            log_blowup: parameters.log_blowup,
+            num_queries: parameters.num_queries,
+            proof_of_work_bits: parameters.proof_of_work_bits,
             mmcs: challenge_mmcs,
         }
     }
 
+    #[derive(Clone, Copy)]
     enum BabyBearPoseidon2Type {
         Default,
         Compressed,
@@ -511,45 +855,66 @@ pub mod baby_bear_poseidon2 {
         pub perm: Perm,
         pcs: Pcs,
         config_type: BabyBearPoseidon2Type,
+        preset: SecurityPreset,
     }
 
     impl BabyBearPoseidon2 {
         pub fn new() -> Self {
-            let perm = my_perm();
-            let hash = MyHash::new(perm.clone());
-            let compress = MyCompress::new(perm.clone());
-            let val_mmcs = ValMmcs::new(hash, compress);
-            let dft = Dft {};
-            let fri_config = default_fri_config();
-            let pcs = Pcs::new(27, dft, val_mmcs, fri_config);
-            Self {
-                pcs,
-                perm,
-                config_type: BabyBearPoseidon2Type::Default,
-            }
+            Self::with_preset(SecurityPreset::Standard)
         }
 
         pub fn compressed() -> Self {
+            Self::compressed_with_preset(SecurityPreset::Standard)
+        }
+
+        /// The config of the core and the recursion proofs with the given security preset.
+        pub fn with_preset(preset: SecurityPreset) -> Self {
+            Self::from_fri_config(
+                fri_config_with_preset(preset),
+                BabyBearPoseidon2Type::Default,
+                preset,
+            )
+        }
+
+        /// The config of the shrunk proofs with the given security preset.
+        pub fn compressed_with_preset(preset: SecurityPreset) -> Self {
+            Self::from_fri_config(
+                compressed_fri_config_with_preset(preset),
+                BabyBearPoseidon2Type::Compressed,
+                preset,
+            )
+        }
+
+        fn from_fri_config(
+            fri_config: FriConfig<ChallengeMmcs>,
+            config_type: BabyBearPoseidon2Type,
+            preset: SecurityPreset,
+        ) -> Self {
             let perm = my_perm();
             let hash = MyHash::new(perm.clone());
             let compress = MyCompress::new(perm.clone());
             let val_mmcs = ValMmcs::new(hash, compress);
             let dft = Dft {};
-            let fri_config = compressed_fri_config();
             let pcs = Pcs::new(27, dft, val_mmcs, fri_config);
             Self {
                 pcs,
                 perm,
-                config_type: BabyBearPoseidon2Type::Compressed,
+                config_type,
+                preset,
             }
         }
+
+        /// The security preset of the config.
+        pub const fn preset(&self) -> SecurityPreset {
+            self.preset
+        }
     }
 
     impl Clone for BabyBearPoseidon2 {
         fn clone(&self) -> Self {
             match self.config_type {
-                BabyBearPoseidon2Type::Default => Self::new(),
-                BabyBearPoseidon2Type::Compressed => Self::compressed(),
+                BabyBearPoseidon2Type::Default => Self::with_preset(self.preset),
+                BabyBearPoseidon2Type::Compressed => Self::compressed_with_preset(self.preset),
             }
         }
     }
@@ -590,6 +955,14 @@ pub mod baby_bear_poseidon2 {
         fn challenger(&self) -> Self::Challenger {
             Challenger::new(self.perm.clone())
         }
+
+        fn merkle_hash(&self) -> MerkleHash {
+            MerkleHash::Poseidon2
+        }
+
+        fn fri_parameters(&self) -> FriParameters {
+            fri_parameters(self.pcs.fri_config())
+        }
     }
 }
 
@@ -606,7 +979,8 @@ pub(super) mod baby_bear_keccak {
     use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
     use serde::{Deserialize, Serialize};
 
-    use crate::stark::StarkGenericConfig;
+    use crate::stark::{FriParameters, MerkleHash, StarkGenericConfig};
+    use crate::utils::fri_parameters;
 
     use super::LOG_DEGREE_BOUND;
 
@@ -650,7 +1024,6 @@ pub(super) mod baby_bear_keccak {
     }
 
     impl BabyBearKeccak {
-        #[allow(dead_code)]
         pub fn new() -> Self {
             let byte_hash = ByteHash {};
             let field_hash = FieldHash::new(byte_hash);
@@ -663,9 +1036,13 @@ pub(super) mod baby_bear_keccak {
 
             let dft = Dft {};
 
+            let num_queries = match std::env::var("FRI_QUERIES") {
+                Ok(value) => value.parse().unwrap(),
+                Err(_) => 100,
+            };
             let fri_config = FriConfig {
                 log_blowup: 1,
-                num_queries: 100,
+                num_queries,
                 proof_of_work_bits: 16,
                 mmcs: challenge_mmcs,
             };
@@ -704,6 +1081,14 @@ pub(super) mod baby_bear_keccak {
             let byte_hash = ByteHash {};
             Challenger::from_hasher(vec![], byte_hash)
         }
+
+        fn merkle_hash(&self) -> MerkleHash {
+            MerkleHash::Keccak
+        }
+
+        fn fri_parameters(&self) -> FriParameters {
+            fri_parameters(self.pcs.fri_config())
+        }
     }
 }
 
@@ -720,7 +1105,8 @@ pub(super) mod baby_bear_blake3 {
     use p3_symmetric::{CompressionFunctionFromHasher, SerializingHasher32};
     use serde::{Deserialize, Serialize};
 
-    use crate::stark::StarkGenericConfig;
+    use crate::stark::{FriParameters, MerkleHash, StarkGenericConfig};
+    use crate::utils::fri_parameters;
 
     use super::LOG_DEGREE_BOUND;
 
@@ -822,5 +1208,13 @@ pub(super) mod baby_bear_blake3 {
             let byte_hash = ByteHash {};
             Challenger::from_hasher(vec![], byte_hash)
         }
+
+        fn merkle_hash(&self) -> MerkleHash {
+            MerkleHash::Blake3
+        }
+
+        fn fri_parameters(&self) -> FriParameters {
+            fri_parameters(self.pcs.fri_config())
+        }
     }
 }
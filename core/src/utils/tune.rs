@@ -0,0 +1,154 @@
+use crate::io::SP1Stdin;
+use crate::runtime::{ExecutionError, ExecutionReport, Program, Runtime};
+
+use super::SP1CoreOpts;
+
+/// A rough estimate of the prover memory, in bytes, needed per cycle of a shard for programs which
+/// do not use precompiles. It is not measured: it assumes that the default shard size of `2^22`
+/// needs about 32 GiB, so the tuned shard size is a starting point rather than a guarantee.
+const BYTES_PER_CYCLE: u64 = 8 * 1024;
+
+/// The number of cycles each precompile invocation is charged as, since precompile tables are much
+/// wider than the CPU table. Like [BYTES_PER_CYCLE], this is an estimate, the same for every
+/// precompile.
+const PRECOMPILE_CYCLE_WEIGHT: u64 = 64;
+
+/// The fraction of the available memory the tuner is allowed to plan for, leaving headroom for
+/// the rest of the process.
+const MEMORY_HEADROOM_PERCENT: u64 = 80;
+
+/// The smallest shard size the tuner will choose.
+const MIN_LOG_SHARD_SIZE: usize = 16;
+
+/// The largest shard size the tuner will choose.
+const MAX_LOG_SHARD_SIZE: usize = 22;
+
+/// Estimate the prover memory needed per cycle of a shard from the event mix in `report`.
+pub fn estimate_bytes_per_cycle(report: &ExecutionReport) -> u64 {
+    let cycles = report.total_instruction_count().max(1);
+    let precompile_cycles = report
+        .syscall_counts
+        .iter()
+        .filter(|(code, _)| code.should_send() == 1)
+        .map(|(_, count)| count * PRECOMPILE_CYCLE_WEIGHT)
+        .sum::<u64>();
+    BYTES_PER_CYCLE * (cycles + precompile_cycles) / cycles
+}
+
+/// Pick the largest power of two shard size whose estimated memory usage, given the event mix in
+/// `report`, fits within `available_memory` bytes.
+pub fn tune_shard_size(report: &ExecutionReport, available_memory: u64) -> usize {
+    let budget = available_memory / 100 * MEMORY_HEADROOM_PERCENT;
+    let bytes_per_cycle = estimate_bytes_per_cycle(report);
+    (MIN_LOG_SHARD_SIZE..=MAX_LOG_SHARD_SIZE)
+        .rev()
+        .map(|log_shard_size| 1 << log_shard_size)
+        .find(|&shard_size: &usize| (shard_size as u64) * bytes_per_cycle <= budget)
+        .unwrap_or(1 << MIN_LOG_SHARD_SIZE)
+}
+
+/// The memory available to the process in bytes, read from `/proc/meminfo` where supported.
+pub fn available_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo
+        .lines()
+        .find(|line| line.starts_with("MemAvailable:"))?;
+    let kb = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kb * 1024)
+}
+
+impl SP1CoreOpts {
+    /// Tune the shard size for `program` by dry-running it on `stdin` and sizing shards to the
+    /// memory available on this machine.
+    ///
+    /// Only `shard_size` is tuned, from the estimates `BYTES_PER_CYCLE` and
+    /// `PRECOMPILE_CYCLE_WEIGHT`. The event thresholds of the chips are not tuned separately: they
+    /// follow the shard size as in [crate::runtime::ShardingConfig::new]. The other options keep
+    /// their defaults.
+    ///
+    /// Any streamed input is buffered into `stdin` first, so that the dry run sees the same input as
+    /// the proof, which drains the stream up front anyway. Falls back to the default options if the
+    /// available memory cannot be determined, and fails if the dry run does.
+    pub fn auto_tune(program: &Program, stdin: &mut SP1Stdin) -> Result<Self, ExecutionError> {
+        stdin.buffer_stream()?;
+        let mut opts = Self::default();
+        let Some(available_memory) = available_memory() else {
+            tracing::warn!("could not determine available memory, using default shard size");
+            return Ok(opts);
+        };
+
+        let mut runtime = Runtime::new(program.clone(), opts);
+        runtime.write_vecs(&stdin.buffer);
+        runtime.state.random_seed = stdin.random_seed;
+        for (proof, vkey) in stdin.proofs.iter() {
+            runtime.write_proof(proof.clone(), vkey.clone());
+        }
+        runtime.should_report = true;
+        runtime.dry_run()?;
+
+        opts.shard_size = tune_shard_size(&runtime.report, available_memory);
+        tracing::info!(
+            "tuned shard size to 2^{} for {} bytes of available memory",
+            opts.shard_size.trailing_zeros(),
+            available_memory
+        );
+        Ok(opts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::{InputStream, InputStreamConsumed, SP1Stdin};
+    use crate::runtime::{ExecutionError, ExecutionReport, Opcode, Program, SyscallCode};
+    use crate::utils::tests::FIBONACCI_IO_ELF;
+    use crate::utils::SP1CoreOpts;
+
+    use super::{estimate_bytes_per_cycle, tune_shard_size, BYTES_PER_CYCLE};
+
+    fn report(adds: u64, keccaks: u64) -> ExecutionReport {
+        ExecutionReport {
+            instruction_counts: [(Opcode::ADD, adds)].into(),
+            syscall_counts: [(SyscallCode::KECCAK_PERMUTE, keccaks)].into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_tune_shard_size() {
+        let gib = 1 << 30;
+
+        // Plain programs get the default shard size with enough memory.
+        assert_eq!(estimate_bytes_per_cycle(&report(1000, 0)), BYTES_PER_CYCLE);
+        assert_eq!(tune_shard_size(&report(1000, 0), 64 * gib), 1 << 22);
+        assert_eq!(tune_shard_size(&report(1000, 0), 16 * gib), 1 << 20);
+
+        // Precompile heavy programs get smaller shards.
+        assert!(estimate_bytes_per_cycle(&report(1000, 100)) > BYTES_PER_CYCLE);
+        assert_eq!(tune_shard_size(&report(1000, 100), 64 * gib), 1 << 19);
+
+        // The shard size never goes below the minimum.
+        assert_eq!(tune_shard_size(&report(1000, 0), 0), 1 << 16);
+    }
+
+    #[test]
+    fn test_auto_tune_stream() {
+        let program = Program::from(FIBONACCI_IO_ELF);
+        let chunks = || vec![bincode::serialize(&10u32).unwrap()].into_iter();
+
+        // The streamed input is buffered, so the dry run and the proof both see it.
+        let mut stdin = SP1Stdin::new();
+        stdin.stream = Some(InputStream::new(chunks()));
+        SP1CoreOpts::auto_tune(&program, &mut stdin).unwrap();
+        assert!(stdin.stream.is_none());
+        assert_eq!(stdin.buffer, chunks().collect::<Vec<_>>());
+
+        // A stream which was already read fails instead of tuning on partial input.
+        let mut stdin = SP1Stdin::new();
+        stdin.stream = Some(InputStream::new(chunks()));
+        stdin.stream.as_ref().unwrap().claim().unwrap();
+        assert!(matches!(
+            SP1CoreOpts::auto_tune(&program, &mut stdin),
+            Err(ExecutionError::InputStreamConsumed(InputStreamConsumed))
+        ));
+    }
+}
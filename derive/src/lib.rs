@@ -255,10 +255,33 @@ pub fn machine_air_derive(input: TokenStream) -> TokenStream {
                 .params
                 .push(syn::parse_quote! { AB: p3_air::PairBuilder + #builder_path });
 
-            let (air_impl_generics, _, _) = new_generics.split_for_impl();
+            // The variants whose field is a type parameter of the enum, such as the chips of an
+            // extension of the machine, need that parameter to be an AIR for the builder.
+            let type_params = generics
+                .type_params()
+                .map(|param| &param.ident)
+                .collect::<Vec<_>>();
+            let where_clause_air = new_generics.make_where_clause();
+            for (_, field) in variants.iter() {
+                if let syn::Type::Path(type_path) = &field.ty {
+                    if type_path.qself.is_none()
+                        && type_path
+                            .path
+                            .get_ident()
+                            .is_some_and(|ident| type_params.contains(&ident))
+                    {
+                        let field_ty = &field.ty;
+                        where_clause_air
+                            .predicates
+                            .push(syn::parse_quote! { #field_ty: p3_air::Air<AB> });
+                    }
+                }
+            }
+
+            let (air_impl_generics, _, air_where_clause) = new_generics.split_for_impl();
 
             let air = quote! {
-                impl #air_impl_generics p3_air::Air<AB> for #name #ty_generics #where_clause {
+                impl #air_impl_generics p3_air::Air<AB> for #name #ty_generics #air_where_clause {
                     fn eval(&self, builder: &mut AB) {
                         match self {
                             #(#eval_arms,)*
@@ -303,6 +326,69 @@ pub fn cycle_tracker(_attr: TokenStream, item: TokenStream) -> TokenStream {
     result.into()
 }
 
+/// Implements `SolidityAbi` for a struct, which is encoded as the tuple of its fields.
+///
+/// The trait is looked up at `sp1_zkvm::abi`, unless another path is set with
+/// `#[sol_abi_path = "sp1_sdk::abi"]`.
+#[proc_macro_derive(SolidityAbi, attributes(sol_abi_path))]
+pub fn solidity_abi_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let name = &ast.ident;
+    let abi_path = find_sol_abi_path(&ast.attrs);
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+
+    let fields = match &ast.data {
+        Data::Struct(data) => &data.fields,
+        _ => panic!("SolidityAbi can only be derived for structs"),
+    };
+    let types = fields.iter().map(|field| &field.ty).collect::<Vec<_>>();
+    let members = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => quote! { #ident },
+            None => {
+                let index = syn::Index::from(i);
+                quote! { #index }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let result = quote! {
+        impl #impl_generics #abi_path::SolidityAbi for #name #type_generics #where_clause {
+            const DYNAMIC: bool = false #(|| <#types as #abi_path::SolidityAbi>::DYNAMIC)*;
+
+            const HEAD_LEN: usize = if <Self as #abi_path::SolidityAbi>::DYNAMIC {
+                #abi_path::WORD_LEN
+            } else {
+                0 #(+ <#types as #abi_path::SolidityAbi>::HEAD_LEN)*
+            };
+
+            fn sol_type() -> String {
+                let types: Vec<String> = vec![#(<#types as #abi_path::SolidityAbi>::sol_type()),*];
+                format!("({})", types.join(","))
+            }
+
+            fn encode_body(&self, out: &mut Vec<u8>) {
+                let mut encoder = #abi_path::TupleEncoder::new(
+                    0 #(+ <#types as #abi_path::SolidityAbi>::HEAD_LEN)*
+                );
+                #(encoder.push(&self.#members);)*
+                encoder.finish(out);
+            }
+
+            fn decode_body(data: &[u8], offset: usize) -> #abi_path::Result<Self> {
+                let mut decoder = #abi_path::TupleDecoder::new(data, offset);
+                Ok(Self {
+                    #(#members: decoder.read()?,)*
+                })
+            }
+        }
+    };
+
+    result.into()
+}
+
 fn find_sp1_core_path(attrs: &[syn::Attribute]) -> syn::Ident {
     for attr in attrs {
         if attr.path.is_ident("sp1_core_path") {
@@ -360,3 +446,18 @@ fn find_builder_path(attrs: &[syn::Attribute]) -> syn::Path {
     }
     parse_quote!(crate::air::SP1AirBuilder<F = F>)
 }
+
+fn find_sol_abi_path(attrs: &[syn::Attribute]) -> syn::Path {
+    for attr in attrs {
+        if attr.path.is_ident("sol_abi_path") {
+            if let Ok(syn::Meta::NameValue(meta)) = attr.parse_meta() {
+                if let syn::Lit::Str(lit_str) = &meta.lit {
+                    if let Ok(path) = lit_str.parse::<syn::Path>() {
+                        return path;
+                    }
+                }
+            }
+        }
+    }
+    parse_quote!(sp1_zkvm::abi)
+}
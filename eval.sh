@@ -21,7 +21,7 @@ for program in "${programs[@]}"; do
 
     cd "$program_directory"
 
-    if ! RUSTFLAGS="-C passes=loweratomic -C link-arg=-Ttext=0x00200800 -C panic=abort" \
+    if ! RUSTFLAGS="-C target-feature=+a -C link-arg=-Ttext=0x00200800 -C panic=abort" \
         CARGO_NET_GIT_FETCH_WITH_CLI=true \
         cargo prove build; then
         echo "Failed to build $program, skipping..."
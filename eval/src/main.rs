@@ -1,10 +1,13 @@
 use clap::{command, Parser};
 use csv::WriterBuilder;
+use p3_baby_bear::BabyBear;
+use serde::de::DeserializeOwned;
 use serde::Serialize;
 use sp1_core::runtime::{Program, Runtime};
-use sp1_core::utils::{
-    prove_simple, BabyBearBlake3, BabyBearKeccak, BabyBearPoseidon2, SP1CoreOpts,
+use sp1_core::stark::{
+    Com, MerkleHash, OpeningProof, PcsProverData, ShardMainData, StarkGenericConfig,
 };
+use sp1_core::utils::{prove_simple, with_baby_bear_config, BabyBearConfigVisitor, SP1CoreOpts};
 use sp1_prover::utils::get_cycles;
 use sp1_prover::SP1Stdin;
 use std::fmt;
@@ -134,62 +137,47 @@ fn main() {
 fn run_evaluation(hashfn: &HashFnId, program: &Program, _elf: &[u8]) -> (f64, f64, f64) {
     // Note: While these benchmarks are useful for core proving, they are not useful for recursion
     // or end to end proving as we only support Poseidon for now.
-    match hashfn {
-        HashFnId::Blake3 => {
-            let opts = SP1CoreOpts::default();
-            let mut runtime = Runtime::new(program.clone(), opts);
-            let execution_start = Instant::now();
-            runtime.run().unwrap();
-            let execution_duration = execution_start.elapsed().as_secs_f64();
-
-            let config = BabyBearBlake3::new();
-            let prove_start = Instant::now();
-            let _proof = prove_simple(config.clone(), runtime);
-            let prove_duration = prove_start.elapsed().as_secs_f64();
-
-            let verify_start = Instant::now();
-            // SP1ProverImpl::verify_with_config(elf, &proof, config).unwrap();
-            let verify_duration = verify_start.elapsed().as_secs_f64();
-
-            (execution_duration, prove_duration, verify_duration)
-        }
-        HashFnId::Poseidon => {
-            let opts = SP1CoreOpts::default();
-            let mut runtime = Runtime::new(program.clone(), opts);
-            let execution_start = Instant::now();
-            runtime.run().unwrap();
-            let execution_duration = execution_start.elapsed().as_secs_f64();
-
-            let config = BabyBearPoseidon2::new();
-            let prove_start = Instant::now();
-            let _proof = prove_simple(config.clone(), runtime);
-            let prove_duration = prove_start.elapsed().as_secs_f64();
-
-            let verify_start = Instant::now();
-            // SP1ProverImpl::verify_with_config(elf, &proof, config).unwrap();
-            let verify_duration = verify_start.elapsed().as_secs_f64();
-
-            (execution_duration, prove_duration, verify_duration)
-        }
-        HashFnId::Keccak256 => {
-            let opts = SP1CoreOpts::default();
-            let mut runtime = Runtime::new(program.clone(), opts);
-            let execution_start = Instant::now();
-            runtime.run().unwrap();
-            let execution_duration = execution_start.elapsed().as_secs_f64();
-
-            let config = BabyBearKeccak::new();
-            let prove_start = Instant::now();
-            let _proof = prove_simple(config.clone(), runtime);
-            let prove_duration = prove_start.elapsed().as_secs_f64();
-
-            let verify_start = Instant::now();
-            // SP1ProverImpl::verify_with_config(elf, &proof, config).unwrap();
-            let verify_duration = verify_start.elapsed().as_secs_f64();
-
-            (execution_duration, prove_duration, verify_duration)
-        }
+    let hash = match hashfn {
+        HashFnId::Blake3 => MerkleHash::Blake3,
+        HashFnId::Poseidon => MerkleHash::Poseidon2,
+        HashFnId::Keccak256 => MerkleHash::Keccak,
         _ => panic!("Unsupported hash function"),
+    };
+    let opts = SP1CoreOpts::default();
+    let mut runtime = Runtime::new(program.clone(), opts);
+    let execution_start = Instant::now();
+    runtime.run().unwrap();
+    let execution_duration = execution_start.elapsed().as_secs_f64();
+
+    let (prove_duration, verify_duration) = with_baby_bear_config(hash, Evaluation(runtime));
+
+    (execution_duration, prove_duration, verify_duration)
+}
+
+/// The proof of an executed program with the config of the evaluated hash.
+struct Evaluation(Runtime);
+
+impl BabyBearConfigVisitor for Evaluation {
+    type Output = (f64, f64);
+
+    fn visit<SC>(self, config: SC) -> (f64, f64)
+    where
+        SC: StarkGenericConfig<Val = BabyBear>,
+        SC::Challenger: Clone,
+        OpeningProof<SC>: Send + Sync,
+        Com<SC>: Send + Sync,
+        PcsProverData<SC>: Send + Sync,
+        ShardMainData<SC>: Serialize + DeserializeOwned,
+    {
+        let prove_start = Instant::now();
+        let _proof = prove_simple(config, self.0);
+        let prove_duration = prove_start.elapsed().as_secs_f64();
+
+        let verify_start = Instant::now();
+        // SP1ProverImpl::verify_with_config(elf, &proof, config).unwrap();
+        let verify_duration = verify_start.elapsed().as_secs_f64();
+
+        (prove_duration, verify_duration)
     }
 }
 
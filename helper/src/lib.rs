@@ -1,63 +1,257 @@
+use cargo_metadata::Metadata;
 use chrono::Local;
+use sha2::{Digest, Sha256};
 use std::{
+    env, fs,
     io::{BufRead, BufReader},
+    path::{Path, PathBuf},
     process::{Command, Stdio},
     thread,
 };
 
+/// The prefix of the file in the target directory of a program with the fingerprint of the sources
+/// of its last build, which is followed by the name of the program since the programs of a
+/// workspace share its target directory.
+const FINGERPRINT_FILE: &str = "sp1-fingerprint";
+
+/// The environment variables which change the ELF that `cargo prove build` builds, and which are
+/// part of the fingerprint of a build.
+const FINGERPRINT_ENV: &[&str] = &[
+    "RUSTFLAGS",
+    "CARGO_BUILD_RUSTFLAGS",
+    "CARGO_PROFILE_RELEASE_OPT_LEVEL",
+    "CARGO_PROFILE_RELEASE_LTO",
+    "CARGO_PROFILE_RELEASE_CODEGEN_UNITS",
+    "CARGO_PROFILE_RELEASE_DEBUG",
+    "SP1_DOCKER_IMAGE",
+];
+
 fn current_datetime() -> String {
     let now = Local::now();
     now.format("%Y-%m-%d %H:%M:%S").to_string()
 }
 
+/// The options of `cargo prove build` when a program is built by [build_program_with_args].
+#[derive(Debug, Clone, Default)]
+pub struct BuildArgs {
+    /// Whether to build the program in the docker image of the toolchain, so that the ELF is the
+    /// same on every machine.
+    pub docker: bool,
+    /// The tag of the docker image, which defaults to the version of `cargo prove`.
+    pub tag: Option<String>,
+    /// The expected SHA-256 of the ELF as hex, which fails the build if the ELF differs.
+    pub elf_sha256: Option<String>,
+    /// The optimization level of the program, such as `3`, or `s` and `z` to optimize for size.
+    pub opt_level: Option<String>,
+    /// The link-time optimization of the program: `off`, `thin` or `fat`.
+    pub lto: Option<String>,
+    /// Whether to keep the unused sections of the program, which the linker removes by default.
+    pub no_gc_sections: bool,
+    /// Whether to unwind on panic instead of aborting, which adds the unwinding code to the
+    /// program.
+    pub panic_unwind: bool,
+}
+
+impl BuildArgs {
+    fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.docker {
+            args.push("--docker".to_string());
+        }
+        if let Some(tag) = &self.tag {
+            args.extend(["--tag".to_string(), tag.clone()]);
+        }
+        if let Some(elf_sha256) = &self.elf_sha256 {
+            args.extend(["--elf-sha256".to_string(), elf_sha256.clone()]);
+        }
+        if let Some(opt_level) = &self.opt_level {
+            args.extend(["--opt-level".to_string(), opt_level.clone()]);
+        }
+        if let Some(lto) = &self.lto {
+            args.extend(["--lto".to_string(), lto.clone()]);
+        }
+        if self.no_gc_sections {
+            args.push("--no-gc-sections".to_string());
+        }
+        if self.panic_unwind {
+            args.push("--panic-unwind".to_string());
+        }
+        args
+    }
+}
+
+/// The sources of the program of `metadata`: the manifests and sources of its local packages,
+/// which include its path dependencies, and the lockfile of its workspace.
+fn source_paths(metadata: &Metadata) -> Vec<PathBuf> {
+    let mut paths = vec![metadata
+        .workspace_root
+        .join("Cargo.lock")
+        .into_std_path_buf()];
+    for package in metadata.packages.iter().filter(|p| p.source.is_none()) {
+        let package_dir = package.manifest_path.parent().unwrap().as_std_path();
+        paths.push(package.manifest_path.clone().into_std_path_buf());
+        paths.push(package_dir.join("src"));
+        paths.push(package_dir.join("build.rs"));
+    }
+    // Cargo reruns the build script every time if a path it watches does not exist.
+    paths.retain(|path| path.exists());
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+/// Hashes the file or the files of the directory at `path` into `hasher`.
+fn hash_path(path: &Path, hasher: &mut Sha256) {
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        entries.sort();
+        for entry in entries {
+            hash_path(&entry, hasher);
+        }
+    } else if let Ok(contents) = fs::read(path) {
+        hash_field(hasher, path.as_os_str().as_encoded_bytes());
+        hash_field(hasher, &contents);
+    }
+}
+
+/// Hashes `bytes` into `hasher`, prefixed with their length so that consecutive fields cannot
+/// collide.
+fn hash_field(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_le_bytes());
+    hasher.update(bytes);
+}
+
+/// The output of `command --version`, or nothing if it cannot be run.
+fn version_of(command: &mut Command) -> Vec<u8> {
+    command
+        .arg("--version")
+        .output()
+        .map(|output| output.stdout)
+        .unwrap_or_default()
+}
+
+/// The fingerprint of a build of the sources at `paths` with `args`, which changes whenever the
+/// sources, the options, the toolchain or the [FINGERPRINT_ENV] variables change.
+fn fingerprint(paths: &[PathBuf], args: &BuildArgs) -> String {
+    let mut hasher = Sha256::new();
+    hash_field(&mut hasher, env!("CARGO_PKG_VERSION").as_bytes());
+    hash_field(
+        &mut hasher,
+        &version_of(Command::new("cargo").args(["prove"])),
+    );
+    hash_field(
+        &mut hasher,
+        &version_of(Command::new("rustc").env("RUSTUP_TOOLCHAIN", "succinct")),
+    );
+    for name in FINGERPRINT_ENV {
+        hash_field(&mut hasher, env::var(name).unwrap_or_default().as_bytes());
+    }
+    for arg in args.to_args() {
+        hash_field(&mut hasher, arg.as_bytes());
+    }
+    for path in paths {
+        hash_path(path, &mut hasher);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
 pub fn build_program(path: &str) {
+    build_program_with_args(path, BuildArgs::default())
+}
+
+/// Builds the program at `path` with the options of `args`, such as in the docker image of the
+/// toolchain for a reproducible ELF.
+///
+/// The program is built in its own target directory, so that cargo reuses the artifacts of its
+/// previous builds. The build is skipped altogether if the ELF exists and the sources of the
+/// program, including its path dependencies, and `args` are the same as at its last build.
+pub fn build_program_with_args(path: &str, args: BuildArgs) {
     println!("path: {:?}", path);
     let program_dir = std::path::Path::new(path);
 
-    // Tell cargo to rerun the script only if program/{src, Cargo.toml, Cargo.lock} changes
-    // Ref: https://doc.rust-lang.org/nightly/cargo/reference/build-scripts.html#rerun-if-changed
-    let dirs = vec![
-        program_dir.join("src"),
-        program_dir.join("Cargo.toml"),
-        program_dir.join("Cargo.lock"),
-    ];
-    for dir in dirs {
-        println!("cargo::rerun-if-changed={}", dir.display());
-    }
-
-    // Print a message so the user knows that their program was built. Cargo caches warnings emitted
-    // from build scripts, so we'll print the date/time when the program was built.
     let metadata_file = program_dir.join("Cargo.toml");
     let mut metadata_cmd = cargo_metadata::MetadataCommand::new();
     let metadata = metadata_cmd.manifest_path(metadata_file).exec().unwrap();
+
+    // Tell cargo to rerun the script only if the sources of the program change
+    // Ref: https://doc.rust-lang.org/nightly/cargo/reference/build-scripts.html#rerun-if-changed
+    let sources = source_paths(&metadata);
+    for path in sources.iter() {
+        println!("cargo::rerun-if-changed={}", path.display());
+    }
+    for name in FINGERPRINT_ENV {
+        println!("cargo::rerun-if-env-changed={}", name);
+    }
+
     let root_package = metadata.root_package();
     let root_package_name = root_package
         .as_ref()
         .map(|p| p.name.as_str())
         .unwrap_or("Program");
+
+    // Skip the build if the ELF was built from the same sources.
+    let fingerprint = fingerprint(&sources, &args);
+    let fingerprint_path = metadata
+        .target_directory
+        .join(format!("{}-{}", FINGERPRINT_FILE, root_package_name));
+    let elf_path = program_dir.join("elf").join("riscv32im-succinct-zkvm-elf");
+    if elf_path.exists() && fs::read_to_string(&fingerprint_path).ok() == Some(fingerprint.clone())
+    {
+        println!("{} is up to date", root_package_name);
+        return;
+    }
+
+    // Print a message so the user knows that their program was built. Cargo caches warnings emitted
+    // from build scripts, so we'll print the date/time when the program was built.
     println!(
         "cargo:warning={} built at {}",
         root_package_name,
         current_datetime()
     );
 
-    let status = execute_build_cmd(&program_dir)
+    let status = execute_build_cmd(&program_dir, &args)
         .unwrap_or_else(|_| panic!("Failed to build `{}`.", root_package_name));
     if !status.success() {
         panic!("Failed to build `{}`.", root_package_name);
     }
+    if elf_path.exists() && !is_clippy_driver() {
+        fs::create_dir_all(&metadata.target_directory).unwrap();
+        fs::write(&fingerprint_path, fingerprint).unwrap();
+    }
+}
+
+/// Builds the programs at `paths` concurrently, as with [build_program_with_args].
+///
+/// The programs of the same workspace share its target directory, so cargo builds their common
+/// dependencies once, while the ELF and the fingerprint of each program are its own.
+pub fn build_programs_with_args(paths: &[&str], args: BuildArgs) {
+    thread::scope(|scope| {
+        for path in paths {
+            let args = args.clone();
+            scope.spawn(move || build_program_with_args(path, args));
+        }
+    });
+}
+
+/// Whether RUSTC_WORKSPACE_WRAPPER is set to clippy-driver, i.e. `cargo clippy` is the current
+/// compiler.
+fn is_clippy_driver() -> bool {
+    std::env::var("RUSTC_WORKSPACE_WRAPPER")
+        .map(|val| val.contains("clippy-driver"))
+        .unwrap_or(false)
 }
 
 /// Executes the `cargo prove build` command in the program directory
 fn execute_build_cmd(
     program_dir: &impl AsRef<std::path::Path>,
+    args: &BuildArgs,
 ) -> Result<std::process::ExitStatus, std::io::Error> {
-    // Check if RUSTC_WORKSPACE_WRAPPER is set to clippy-driver (i.e. if `cargo clippy` is the current
-    // compiler). If so, don't execute `cargo prove build` because it breaks rust-analyzer's `cargo clippy` feature.
-    let is_clippy_driver = std::env::var("RUSTC_WORKSPACE_WRAPPER")
-        .map(|val| val.contains("clippy-driver"))
-        .unwrap_or(false);
-    if is_clippy_driver {
+    // If `cargo clippy` is the current compiler, don't execute `cargo prove build` because it
+    // breaks rust-analyzer's `cargo clippy` feature.
+    if is_clippy_driver() {
         println!("cargo:warning=Skipping build due to clippy invocation.");
         return Ok(std::process::ExitStatus::default());
     }
@@ -65,6 +259,7 @@ fn execute_build_cmd(
     let mut cmd = Command::new("cargo");
     cmd.current_dir(program_dir)
         .args(["prove", "build"])
+        .args(args.to_args())
         .env_remove("RUSTC")
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
@@ -0,0 +1,42 @@
+//! The program of `SP1Prover::aggregate`, which verifies the proofs of multiple programs proven with
+//! the zkVM and commits to their verifying keys and public values digests.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+/// Encode the verified proofs, as their verifying key hashes and public values digests, into a
+/// single byte array.
+///
+/// ( vkeys.len() as u32 || vkeys[0] || public_values_digests[0] || ... )
+pub fn commit_proof_pairs(vkeys: &[[u32; 8]], public_values_digests: &[[u8; 32]]) -> Vec<u8> {
+    assert_eq!(vkeys.len(), public_values_digests.len());
+    let mut res = Vec::with_capacity(4 + vkeys.len() * 64);
+
+    // Note we use big endian because abi.encodePacked in solidity does also
+    res.extend_from_slice(&(vkeys.len() as u32).to_be_bytes());
+    for (vkey, digest) in vkeys.iter().zip(public_values_digests.iter()) {
+        for word in vkey.iter() {
+            res.extend_from_slice(&word.to_le_bytes());
+        }
+        res.extend_from_slice(digest);
+    }
+
+    res
+}
+
+pub fn main() {
+    // Read the verification keys.
+    let vkeys = sp1_zkvm::io::read::<Vec<[u32; 8]>>();
+
+    // Read the digests of the public values.
+    let public_values_digests = sp1_zkvm::io::read::<Vec<[u8; 32]>>();
+
+    // Verify the proofs. The digests are checked against the proofs by the recursion prover.
+    assert_eq!(vkeys.len(), public_values_digests.len());
+    for (vkey, digest) in vkeys.iter().zip(public_values_digests.iter()) {
+        sp1_zkvm::precompiles::verify::verify_sp1_proof(vkey, digest);
+    }
+
+    let commitment = commit_proof_pairs(&vkeys, &public_values_digests);
+    sp1_zkvm::io::commit_slice(&commitment);
+}
@@ -2,17 +2,34 @@ use std::path::PathBuf;
 
 use clap::Parser;
 use sp1_core::utils::setup_logger;
-use sp1_prover::build::build_plonk_bn254_artifacts_with_dummy;
+use sp1_prover::build::{
+    build_plonk_bn254_artifacts_with_dummy, build_plonk_bn254_artifacts_with_srs_and_dummy,
+};
+use sp1_prover::srs::PlonkSrs;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
     #[clap(short, long)]
     build_dir: PathBuf,
+
+    /// The SRS to build the artifacts with, instead of the Aztec Ignition SRS.
+    #[clap(long, requires = "srs_sha256")]
+    srs: Option<PathBuf>,
+
+    /// The hex encoded SHA-256 checksum of the SRS.
+    #[clap(long, requires = "srs")]
+    srs_sha256: Option<String>,
 }
 
 pub fn main() {
     setup_logger();
     let args = Args::parse();
-    build_plonk_bn254_artifacts_with_dummy(args.build_dir);
+    match (args.srs, args.srs_sha256) {
+        (Some(path), Some(sha256)) => {
+            let srs = PlonkSrs::new(path, &sha256).unwrap();
+            build_plonk_bn254_artifacts_with_srs_and_dummy(&srs, args.build_dir).unwrap();
+        }
+        _ => build_plonk_bn254_artifacts_with_dummy(args.build_dir),
+    }
 }
@@ -5,10 +5,13 @@ use clap::Parser;
 use p3_baby_bear::BabyBear;
 use p3_field::PrimeField;
 use sp1_core::io::SP1Stdin;
-use sp1_prover::utils::{babybear_bytes_to_bn254, babybears_to_bn254, words_to_bytes};
+use sp1_prover::utils::{
+    babybear_bytes_to_bn254, babybear_words_to_bn254, babybears_to_bn254, words_to_bytes,
+};
 use sp1_prover::SP1Prover;
 use sp1_recursion_circuit::stark::build_wrap_circuit;
 use sp1_recursion_circuit::witness::Witnessable;
+use sp1_recursion_compiler::config::OuterConfig;
 use sp1_recursion_compiler::ir::Witness;
 use sp1_recursion_core::air::RecursionPublicValues;
 use sp1_recursion_gnark_ffi::PlonkBn254Prover;
@@ -60,11 +63,13 @@ pub fn main() {
         .try_into()
         .unwrap();
     let committed_values_digest = babybear_bytes_to_bn254(&committed_values_digest_bytes);
+    let user_values = babybear_words_to_bn254(&pv.user_values);
 
-    let mut witness = Witness::default();
+    let mut witness = Witness::<OuterConfig>::default();
     wrapped_proof.proof.write(&mut witness);
     witness.write_commited_values_digest(committed_values_digest);
     witness.write_vkey_hash(vkey_hash);
+    witness.write_user_values(&user_values);
 
     tracing::info!("sanity check gnark test");
     PlonkBn254Prover::test(constraints.clone(), witness.clone());
@@ -83,6 +88,7 @@ pub fn main() {
         &proof,
         &vkey_hash.as_canonical_biguint(),
         &committed_values_digest.as_canonical_biguint(),
+        &user_values.map(|value| value.as_canonical_biguint()),
         &build_dir,
     );
 
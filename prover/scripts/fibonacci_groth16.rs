@@ -57,6 +57,8 @@ fn main() {
             buffer: vec![bincode::serialize::<u32>(&iterations).unwrap()],
             ptr: 0,
             proofs: vec![],
+            stream: None,
+            random_seed: [0; 32],
         };
         let leaf_proving_start = Instant::now();
         let proof = prover.prove_core(&pk, &stdin).unwrap();
@@ -0,0 +1,297 @@
+//! Aggregation of the compressed proofs of different programs into a single proof.
+//!
+//! The aggregation program verifies each proof as a deferred proof, and commits to the hashes of the
+//! verifying keys of the programs and to the digests of their public values, so that the public
+//! values of the aggregated proof enumerate the (verifying key hash, public values digest) pair of
+//! every proof:
+//!
+//! ```text
+//! count as a big-endian u32
+//! || for each proof: the hash of its verifying key, as 8 little-endian u32s
+//!                    || the SHA-256 digest of its public values
+//! ```
+//!
+//! [decode_aggregated_proofs] decodes them into [AggregatedProof]s.
+
+use std::borrow::Borrow;
+
+use p3_baby_bear::BabyBear;
+use p3_field::PrimeField32;
+use sha2::{Digest, Sha256};
+use sp1_core::io::{SP1PublicValues, SP1Stdin};
+use sp1_core::stark::ShardProof;
+use sp1_core::utils::{SP1CoreOpts, SP1CoreProverError};
+use sp1_recursion_core::air::RecursionPublicValues;
+use thiserror::Error;
+
+use crate::{
+    words_to_bytes, HashableKey, InnerSC, SP1Prover, SP1ProvingKey, SP1RecursionProverError,
+    SP1ReduceProof, SP1VerifyingKey,
+};
+
+/// The ELF of the aggregation program.
+pub const AGGREGATION_ELF: &[u8] =
+    include_bytes!("../programs/aggregation/elf/riscv32im-succinct-zkvm-elf");
+
+#[derive(Error, Debug)]
+pub enum AggregationError {
+    #[error("no proofs to aggregate")]
+    NoProofs,
+    #[error("proof {index} is not a proof of its verifying key")]
+    VkeyMismatch { index: usize },
+    #[error("the public values of proof {index} do not match its committed values digest")]
+    PublicValuesMismatch { index: usize },
+    #[error("proof {index} is of a program which halted with exit code {exit_code}")]
+    NonZeroExitCode { index: usize, exit_code: u32 },
+    #[error("invalid aggregated public values")]
+    InvalidPublicValues,
+    #[error("core prover error: {0}")]
+    Core(#[from] SP1CoreProverError),
+    #[error("recursion prover error: {0}")]
+    Recursion(#[from] SP1RecursionProverError),
+}
+
+/// A compressed proof to aggregate, with the verifying key of its program and its public values.
+#[derive(Clone, Copy)]
+pub struct AggregationInput<'a> {
+    pub proof: &'a ShardProof<InnerSC>,
+    pub vk: &'a SP1VerifyingKey,
+    pub public_values: &'a SP1PublicValues,
+}
+
+/// A proof verified by an aggregated proof, as committed by the aggregation program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregatedProof {
+    /// The hash of the verifying key of the program, as in [HashableKey::hash_u32].
+    pub vkey_hash: [u32; 8],
+    /// The SHA-256 digest of the public values of the proof.
+    pub public_values_digest: [u8; 32],
+}
+
+impl AggregatedProof {
+    /// Whether this is a proof of the program of `vk` with the public values `public_values`.
+    pub fn is_proof_of(&self, vk: &SP1VerifyingKey, public_values: &SP1PublicValues) -> bool {
+        self.vkey_hash == vk.hash_u32()
+            && self.public_values_digest[..] == Sha256::digest(public_values.as_slice())[..]
+    }
+}
+
+/// A reader of the fields of the aggregated public values.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], AggregationError> {
+        if self.bytes.len() < len {
+            return Err(AggregationError::InvalidPublicValues);
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn take_u32(&mut self) -> Result<u32, AggregationError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+/// Decodes the public values of an aggregated proof into the proofs it verified.
+pub fn decode_aggregated_proofs(
+    public_values: &[u8],
+) -> Result<Vec<AggregatedProof>, AggregationError> {
+    let mut reader = Reader {
+        bytes: public_values,
+    };
+    let count = reader.take_u32()? as usize;
+    let proofs = (0..count)
+        .map(|_| {
+            let vkey_hash = reader.take(32)?;
+            let public_values_digest = reader.take(32)?;
+            Ok(AggregatedProof {
+                vkey_hash: core::array::from_fn(|i| {
+                    u32::from_le_bytes(vkey_hash[4 * i..4 * i + 4].try_into().unwrap())
+                }),
+                public_values_digest: public_values_digest.try_into().unwrap(),
+            })
+        })
+        .collect::<Result<Vec<_>, AggregationError>>()?;
+    if !reader.bytes.is_empty() {
+        return Err(AggregationError::InvalidPublicValues);
+    }
+    Ok(proofs)
+}
+
+impl SP1Prover {
+    /// The proving and verifying keys of the aggregation program, which are set up on first use.
+    pub fn aggregation_keys(&self) -> &(SP1ProvingKey, SP1VerifyingKey) {
+        self.aggregation_keys
+            .get_or_init(|| self.setup(AGGREGATION_ELF))
+    }
+
+    /// The input of the aggregation program which aggregates `inputs`.
+    pub fn aggregation_stdin(inputs: &[AggregationInput]) -> SP1Stdin {
+        let mut stdin = SP1Stdin::new();
+        let vkey_hashes = inputs
+            .iter()
+            .map(|input| input.vk.hash_u32())
+            .collect::<Vec<_>>();
+        stdin.write(&vkey_hashes);
+        let public_values_digests = inputs
+            .iter()
+            .map(|input| <[u8; 32]>::from(Sha256::digest(input.public_values.as_slice())))
+            .collect::<Vec<_>>();
+        stdin.write(&public_values_digests);
+        // The proofs are not read by the program, but verified as deferred proofs by the
+        // recursion prover.
+        for input in inputs {
+            stdin.write_proof(input.proof.clone(), input.vk.vk.clone());
+        }
+        stdin
+    }
+
+    /// Aggregates the compressed proofs of possibly different programs into a single compressed
+    /// proof of the aggregation program, whose public values enumerate the verifying key and the
+    /// digest of the public values of each proof, as decoded by [decode_aggregated_proofs].
+    ///
+    /// The aggregated proof is verified with [SP1Prover::verify_compressed] against the verifying
+    /// key of [SP1Prover::aggregation_keys], and can be shrunk and wrapped like any compressed
+    /// proof.
+    pub fn aggregate(
+        &self,
+        inputs: &[AggregationInput],
+    ) -> Result<(SP1ReduceProof<InnerSC>, SP1PublicValues), AggregationError> {
+        if inputs.is_empty() {
+            return Err(AggregationError::NoProofs);
+        }
+
+        // Check the proofs against their keys and public values, since a mismatch would only
+        // surface as a failure of the execution of the aggregation program.
+        for (index, input) in inputs.iter().enumerate() {
+            let pv: &RecursionPublicValues<BabyBear> =
+                input.proof.public_values.as_slice().borrow();
+            if pv.sp1_vk_digest != input.vk.hash_babybear() {
+                return Err(AggregationError::VkeyMismatch { index });
+            }
+            let digest = words_to_bytes(&pv.committed_value_digest)
+                .iter()
+                .map(|byte| byte.as_canonical_u32() as u8)
+                .collect::<Vec<_>>();
+            if digest[..] != Sha256::digest(input.public_values.as_slice())[..] {
+                return Err(AggregationError::PublicValuesMismatch { index });
+            }
+            let exit_code = pv.exit_code.as_canonical_u32();
+            if exit_code != 0 {
+                return Err(AggregationError::NonZeroExitCode { index, exit_code });
+            }
+        }
+
+        let stdin = Self::aggregation_stdin(inputs);
+        let (pk, vk) = self.aggregation_keys();
+        let proof = self.prove_core_with_opts(pk, &stdin, SP1CoreOpts::default())?;
+        let public_values = proof.public_values.clone();
+        let deferred_proofs = stdin
+            .proofs
+            .iter()
+            .map(|(proof, _)| proof.clone())
+            .collect();
+        let proof = self.compress(vk, proof, deferred_proofs)?;
+        Ok((proof, public_values))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use serial_test::serial;
+    use sp1_core::utils::setup_logger;
+
+    #[test]
+    fn test_decode_aggregated_proofs() {
+        let mut bytes = 2u32.to_be_bytes().to_vec();
+        for (vkey_hash, digest) in [([1u32, 2, 3, 4, 5, 6, 7, 8], [1u8; 32]), ([9; 8], [2; 32])] {
+            for word in vkey_hash {
+                bytes.extend_from_slice(&word.to_le_bytes());
+            }
+            bytes.extend_from_slice(&digest);
+        }
+
+        let proofs = decode_aggregated_proofs(&bytes).unwrap();
+        assert_eq!(
+            proofs,
+            vec![
+                AggregatedProof {
+                    vkey_hash: [1, 2, 3, 4, 5, 6, 7, 8],
+                    public_values_digest: [1; 32],
+                },
+                AggregatedProof {
+                    vkey_hash: [9; 8],
+                    public_values_digest: [2; 32],
+                },
+            ]
+        );
+
+        assert!(decode_aggregated_proofs(&bytes[..bytes.len() - 1]).is_err());
+        bytes.push(0);
+        assert!(decode_aggregated_proofs(&bytes).is_err());
+    }
+
+    /// Tests the aggregation of the compressed proofs of two different programs.
+    #[test]
+    #[serial]
+    fn test_aggregate() -> Result<()> {
+        setup_logger();
+        let fibonacci_elf = include_bytes!("../../tests/fibonacci/elf/riscv32im-succinct-zkvm-elf");
+        let keccak_elf = include_bytes!("../../tests/keccak256/elf/riscv32im-succinct-zkvm-elf");
+
+        let prover = SP1Prover::new();
+        let (fibonacci_pk, fibonacci_vk) = prover.setup(fibonacci_elf);
+        let (keccak_pk, keccak_vk) = prover.setup(keccak_elf);
+
+        tracing::info!("prove fibonacci");
+        let proof = prover.prove_core(&fibonacci_pk, &SP1Stdin::new())?;
+        let fibonacci_pv = proof.public_values.clone();
+        let fibonacci_proof = prover.compress(&fibonacci_vk, proof, vec![])?;
+
+        tracing::info!("prove keccak");
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&1usize);
+        stdin.write(&vec![0u8, 1, 2]);
+        let proof = prover.prove_core(&keccak_pk, &stdin)?;
+        let keccak_pv = proof.public_values.clone();
+        let keccak_proof = prover.compress(&keccak_vk, proof, vec![])?;
+
+        tracing::info!("aggregate");
+        let (proof, public_values) = prover.aggregate(&[
+            AggregationInput {
+                proof: &fibonacci_proof.proof,
+                vk: &fibonacci_vk,
+                public_values: &fibonacci_pv,
+            },
+            AggregationInput {
+                proof: &keccak_proof.proof,
+                vk: &keccak_vk,
+                public_values: &keccak_pv,
+            },
+        ])?;
+        prover.verify_compressed(&proof, &prover.aggregation_keys().1)?;
+
+        let proofs = decode_aggregated_proofs(public_values.as_slice())?;
+        assert_eq!(proofs.len(), 2);
+        assert!(proofs[0].is_proof_of(&fibonacci_vk, &fibonacci_pv));
+        assert!(proofs[1].is_proof_of(&keccak_vk, &keccak_pv));
+        assert!(!proofs[1].is_proof_of(&keccak_vk, &fibonacci_pv));
+
+        // The public values of the proofs must match their committed values digests.
+        assert!(matches!(
+            prover.aggregate(&[AggregationInput {
+                proof: &keccak_proof.proof,
+                vk: &keccak_vk,
+                public_values: &fibonacci_pv,
+            }]),
+            Err(AggregationError::PublicValuesMismatch { index: 0 })
+        ));
+        Ok(())
+    }
+}
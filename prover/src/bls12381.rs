@@ -0,0 +1,216 @@
+//! The wrap of the shrunk proofs into a STARK proven over the scalar field of BLS12-381, and its
+//! wrap into a Groth16 proof over BLS12-381.
+//!
+//! The wrap program is the same as the one of [SP1Prover::wrap_bn254], but it is proven with
+//! Poseidon2 over the scalar field of BLS12-381, so that the circuit which verifies it is proven
+//! over BLS12-381 and its proofs can be verified by the chains which only have precompiles of
+//! BLS12-381. The keys of the wrap over BLS12-381 are set up on first use.
+//!
+//! There is no PLONK wrap over BLS12-381, since PLONK needs a universal setup over the curve.
+
+use std::borrow::Borrow;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::Result;
+use num_bigint::BigUint;
+use p3_baby_bear::BabyBear;
+use p3_field::{AbstractField, PrimeField};
+use sp1_core::io::SP1PublicValues;
+use sp1_core::stark::{
+    Challenge, LocalProver, MachineProof, MachineVerificationError, StarkGenericConfig,
+    StarkMachine, StarkProvingKey, StarkVerifyingKey, Val,
+};
+use sp1_core::utils::SP1CoreOpts;
+use sp1_recursion_circuit::witness::Witnessable;
+use sp1_recursion_compiler::config::OuterBls12381Config;
+use sp1_recursion_compiler::ir::Witness;
+use sp1_recursion_core::air::RecursionPublicValues;
+use sp1_recursion_core::runtime::Runtime as RecursionRuntime;
+use sp1_recursion_gnark_ffi::groth16_bls12381::{Groth16Bls12381Proof, Groth16Bls12381Prover};
+use sp1_recursion_program::hints::Hintable;
+use sp1_recursion_program::machine::SP1RootMemoryLayout;
+
+use crate::verify::verify_plonk_bn254_public_inputs;
+use crate::{
+    HashableKey, InnerSC, OuterBls12381SC, SP1Prover, SP1RecursionProverError, SP1ReduceProof,
+    SP1VerifyingKey, WrapAir,
+};
+
+/// The machine and the keys of the wrap program proven over the scalar field of BLS12-381.
+pub struct WrapBls12381Keys {
+    pub machine:
+        StarkMachine<OuterBls12381SC, WrapAir<<OuterBls12381SC as StarkGenericConfig>::Val>>,
+    pub pk: StarkProvingKey<OuterBls12381SC>,
+    pub vk: StarkVerifyingKey<OuterBls12381SC>,
+}
+
+impl SP1Prover {
+    /// The machine and the keys of the wrap over BLS12-381, which are set up on first use.
+    pub fn wrap_bls12381_keys(&self) -> &WrapBls12381Keys {
+        self.wrap_bls12381_keys.get_or_init(|| {
+            let machine = WrapAir::wrap_machine(OuterBls12381SC::default());
+            let (pk, vk) = machine.setup(&self.wrap_program);
+            WrapBls12381Keys { machine, pk, vk }
+        })
+    }
+
+    /// Wrap a shrunk proof into a STARK proven over the scalar field of BLS12-381.
+    #[tracing::instrument(name = "wrap_bls12381", level = "info", skip_all)]
+    pub fn wrap_bls12381(
+        &self,
+        compressed_proof: SP1ReduceProof<InnerSC>,
+    ) -> Result<SP1ReduceProof<OuterBls12381SC>, SP1RecursionProverError> {
+        let keys = self.wrap_bls12381_keys();
+        let input = SP1RootMemoryLayout {
+            machine: &self.shrink_machine,
+            proof: compressed_proof.proof,
+            is_reduce: false,
+        };
+
+        // Run the wrap program.
+        let mut runtime = RecursionRuntime::<Val<InnerSC>, Challenge<InnerSC>, _>::new(
+            &self.wrap_program,
+            self.shrink_machine.config().perm.clone(),
+        );
+
+        let mut witness_stream = Vec::new();
+        witness_stream.extend(input.write());
+
+        runtime.witness_stream = witness_stream.into();
+        runtime.run();
+        runtime.print_stats();
+        tracing::debug!("Wrap program executed successfully");
+
+        // Prove the wrap program.
+        let opts = SP1CoreOpts::recursion();
+        let mut wrap_challenger = keys.machine.config().challenger();
+        let mut wrap_proof = keys.machine.prove::<LocalProver<_, _>>(
+            &keys.pk,
+            runtime.record,
+            &mut wrap_challenger,
+            opts,
+        );
+        let mut wrap_challenger = keys.machine.config().challenger();
+        match keys
+            .machine
+            .verify(&keys.vk, &wrap_proof, &mut wrap_challenger)
+        {
+            Ok(_) => tracing::info!("Proof verified successfully"),
+            Err(MachineVerificationError::NonZeroCumulativeSum) => {
+                tracing::info!("Proof verification failed: NonZeroCumulativeSum")
+            }
+            e => panic!("Proof verification failed: {:?}", e),
+        }
+        tracing::info!("Wrapping successful");
+
+        Ok(SP1ReduceProof {
+            proof: wrap_proof.shard_proofs.pop().unwrap(),
+        })
+    }
+
+    /// Verify a wrap proof over BLS12-381.
+    pub fn verify_wrap_bls12381(
+        &self,
+        proof: &SP1ReduceProof<OuterBls12381SC>,
+        vk: &SP1VerifyingKey,
+    ) -> Result<(), MachineVerificationError<OuterBls12381SC>> {
+        let keys = self.wrap_bls12381_keys();
+        let mut challenger = keys.machine.config().challenger();
+        let machine_proof = MachineProof {
+            shard_proofs: vec![proof.proof.clone()],
+        };
+        keys.machine
+            .verify(&keys.vk, &machine_proof, &mut challenger)?;
+
+        // Validate public values
+        let public_values: &RecursionPublicValues<_> =
+            proof.proof.public_values.as_slice().borrow();
+
+        // `is_complete` should be 1. In the reduce program, this ensures that the proof is fully reduced.
+        if public_values.is_complete != BabyBear::one() {
+            return Err(MachineVerificationError::InvalidPublicValues(
+                "is_complete is not 1",
+            ));
+        }
+
+        // Verify that the proof is for the sp1 vkey we are expecting.
+        let vkey_hash = vk.hash_babybear();
+        if public_values.sp1_vk_digest != vkey_hash {
+            return Err(MachineVerificationError::InvalidPublicValues(
+                "sp1 vk hash mismatch",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Wrap the STARK proven over the scalar field of BLS12-381 into a Groth16 proof over
+    /// BLS12-381.
+    #[tracing::instrument(name = "wrap_groth16_bls12381", level = "info", skip_all)]
+    pub fn wrap_groth16_bls12381(
+        &self,
+        proof: SP1ReduceProof<OuterBls12381SC>,
+        build_dir: &Path,
+    ) -> Groth16Bls12381Proof {
+        let vkey_digest = proof.sp1_vkey_digest_bls12381();
+        let commited_values_digest = proof.sp1_commited_values_digest_bls12381();
+        let user_values = proof.sp1_user_values_bls12381();
+
+        let mut witness = Witness::<OuterBls12381Config>::default();
+        proof.proof.write(&mut witness);
+        witness.write_commited_values_digest(commited_values_digest);
+        witness.write_vkey_hash(vkey_digest);
+        witness.write_user_values(&user_values);
+
+        let prover = Groth16Bls12381Prover::new();
+        let proof = prover.prove(witness, build_dir.to_path_buf());
+
+        // Verify the proof.
+        prover.verify(
+            &proof,
+            &vkey_digest.as_canonical_biguint(),
+            &commited_values_digest.as_canonical_biguint(),
+            &user_values.map(|value| value.as_canonical_biguint()),
+            build_dir,
+        );
+
+        proof
+    }
+
+    /// Verify a Groth16 proof over BLS12-381, and that its public inputs match the vkey and the
+    /// public values.
+    ///
+    /// The public inputs are the same integers as the public inputs of the proofs over BN254, since
+    /// they are smaller than both scalar fields.
+    pub fn verify_groth16_bls12381(
+        &self,
+        proof: &Groth16Bls12381Proof,
+        vk: &SP1VerifyingKey,
+        public_values: &SP1PublicValues,
+        build_dir: &Path,
+    ) -> Result<()> {
+        vk.check_circuit_version::<InnerSC>()?;
+        let prover = Groth16Bls12381Prover::new();
+
+        let vkey_hash = BigUint::from_str(&proof.public_inputs[0])?;
+        let committed_values_digest = BigUint::from_str(&proof.public_inputs[1])?;
+        let user_values = proof.public_inputs[2..]
+            .iter()
+            .map(|value| BigUint::from_str(value))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Verify the proof with the corresponding public inputs.
+        prover.verify(
+            proof,
+            &vkey_hash,
+            &committed_values_digest,
+            &user_values,
+            build_dir,
+        );
+
+        verify_plonk_bn254_public_inputs(vk, public_values, &proof.public_inputs)?;
+
+        Ok(())
+    }
+}
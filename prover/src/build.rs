@@ -1,20 +1,29 @@
 use std::borrow::Borrow;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use p3_baby_bear::BabyBear;
 use sp1_core::stark::StarkVerifyingKey;
 use sp1_core::{io::SP1Stdin, stark::ShardProof};
-pub use sp1_recursion_circuit::stark::build_wrap_circuit;
+pub use sp1_recursion_circuit::stark::{build_wrap_circuit, build_wrap_circuit_bls12381};
 pub use sp1_recursion_circuit::witness::Witnessable;
 pub use sp1_recursion_compiler::ir::Witness;
-use sp1_recursion_compiler::{config::OuterConfig, constraints::Constraint};
+use sp1_recursion_compiler::{
+    config::{OuterBls12381Config, OuterConfig},
+    constraints::Constraint,
+};
 use sp1_recursion_core::air::RecursionPublicValues;
 pub use sp1_recursion_core::stark::utils::sp1_dev_mode;
-use sp1_recursion_gnark_ffi::PlonkBn254Prover;
+use sp1_recursion_gnark_ffi::{Groth16Bls12381Prover, Groth16Bn254Prover, PlonkBn254Prover};
 
-use crate::install::{install_plonk_bn254_artifacts, PLONK_BN254_ARTIFACTS_COMMIT};
-use crate::utils::{babybear_bytes_to_bn254, babybears_to_bn254, words_to_bytes};
-use crate::{OuterSC, SP1Prover};
+use crate::install::{
+    install_groth16_bn254_artifacts, install_plonk_bn254_artifacts, PLONK_BN254_ARTIFACTS_COMMIT,
+};
+use crate::srs::{PlonkSrs, PlonkSrsError};
+use crate::utils::{
+    babybear_bytes_to_bls12381, babybear_bytes_to_bn254, babybear_words_to_bls12381,
+    babybear_words_to_bn254, babybears_to_bls12381, babybears_to_bn254, words_to_bytes,
+};
+use crate::{OuterBls12381SC, OuterSC, SP1Prover};
 
 /// Tries to install the PLONK artifacts if they are not already installed.
 pub fn try_install_plonk_bn254_artifacts() -> PathBuf {
@@ -36,6 +45,22 @@ pub fn try_install_plonk_bn254_artifacts() -> PathBuf {
     build_dir
 }
 
+/// Tries to install the PLONK artifacts, and builds them inside the development directory instead
+/// if the released artifacts were not built for the wrap circuit of this version of the prover.
+pub fn try_install_or_build_plonk_bn254_artifacts(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+) -> PathBuf {
+    try_install_or_build_artifacts(
+        "plonk bn254",
+        template_vk,
+        template_proof,
+        try_install_plonk_bn254_artifacts,
+        plonk_bn254_artifacts_dev_dir(),
+        build_plonk_bn254_artifacts,
+    )
+}
+
 /// Tries to build the PLONK artifacts inside the development directory.
 ///
 /// TODO: Maybe add some additional logic here to handle rebuilding the artifacts if they are
@@ -92,6 +117,275 @@ pub fn build_plonk_bn254_artifacts_with_dummy(build_dir: impl Into<PathBuf>) {
     crate::build::build_plonk_bn254_artifacts(&wrap_vk, &wrapped_proof, build_dir.into());
 }
 
+/// Tries to build the PLONK artifacts with a user-supplied SRS, if they are not already built.
+pub fn try_build_plonk_bn254_artifacts_with_srs(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+    srs: &PlonkSrs,
+) -> Result<PathBuf, PlonkSrsError> {
+    let build_dir = srs.artifacts_dir();
+
+    if build_dir.exists() {
+        println!(
+            "[sp1] plonk bn254 artifacts for the srs at {} already seem to exist at {}. if you want to rebuild them, delete the directory",
+            srs.path.display(),
+            build_dir.display()
+        );
+    } else {
+        println!(
+            "[sp1] building plonk bn254 artifacts with the srs at {} in {}",
+            srs.path.display(),
+            build_dir.display()
+        );
+        if let Err(err) =
+            build_plonk_bn254_artifacts_with_srs(template_vk, template_proof, srs, &build_dir)
+        {
+            // Do not leave a directory which looks built behind.
+            std::fs::remove_dir_all(&build_dir)?;
+            return Err(err);
+        }
+    }
+    Ok(build_dir)
+}
+
+/// Build the plonk bn254 artifacts to the given directory for the given verification key and template
+/// proof, with a user-supplied SRS instead of the Aztec Ignition SRS.
+///
+/// The SRS is copied to the directory and checked against its checksum before the circuit is
+/// built, so that a corrupted or tampered SRS is never used.
+pub fn build_plonk_bn254_artifacts_with_srs(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+    srs: &PlonkSrs,
+    build_dir: impl Into<PathBuf>,
+) -> Result<(), PlonkSrsError> {
+    let build_dir = build_dir.into();
+    std::fs::create_dir_all(&build_dir)?;
+    srs.copy_to(&build_dir.join("srs.bin"))?;
+    build_plonk_bn254_artifacts(template_vk, template_proof, build_dir);
+    Ok(())
+}
+
+/// Builds the plonk bn254 artifacts to the given directory with a user-supplied SRS, with a dummy
+/// template proof.
+pub fn build_plonk_bn254_artifacts_with_srs_and_dummy(
+    srs: &PlonkSrs,
+    build_dir: impl Into<PathBuf>,
+) -> Result<(), PlonkSrsError> {
+    // Check the SRS before spending a while on the dummy proof.
+    srs.validate()?;
+    let (wrap_vk, wrapped_proof) = dummy_proof();
+    build_plonk_bn254_artifacts_with_srs(&wrap_vk, &wrapped_proof, srs, build_dir)
+}
+
+/// Tries to install the Groth16 artifacts if they are not already installed.
+pub fn try_install_groth16_bn254_artifacts() -> PathBuf {
+    let build_dir = groth16_bn254_artifacts_dir();
+
+    if build_dir.exists() {
+        println!(
+            "[sp1] groth16 bn254 artifacts already seem to exist at {}. if you want to re-download them, delete the directory",
+            build_dir.display()
+        );
+    } else {
+        println!(
+            "[sp1] groth16 bn254 artifacts for commit {} do not exist at {}. downloading...",
+            PLONK_BN254_ARTIFACTS_COMMIT,
+            build_dir.display()
+        );
+        install_groth16_bn254_artifacts(build_dir.clone());
+    }
+    build_dir
+}
+
+/// Tries to install the Groth16 artifacts, and builds them inside the development directory
+/// instead if the released artifacts were not built for the wrap circuit of this version of the
+/// prover.
+pub fn try_install_or_build_groth16_bn254_artifacts(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+) -> PathBuf {
+    try_install_or_build_artifacts(
+        "groth16 bn254",
+        template_vk,
+        template_proof,
+        try_install_groth16_bn254_artifacts,
+        groth16_bn254_artifacts_dev_dir(),
+        build_groth16_bn254_artifacts,
+    )
+}
+
+/// Installs the released artifacts, and checks that their constraints are the constraints of the
+/// wrap circuit for the template proof. If they are not, the artifacts are built in `dev_dir`,
+/// unless they were already built there for the same circuit.
+fn try_install_or_build_artifacts(
+    name: &str,
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+    install: fn() -> PathBuf,
+    dev_dir: PathBuf,
+    build: fn(&StarkVerifyingKey<OuterSC>, &ShardProof<OuterSC>, PathBuf),
+) -> PathBuf {
+    let constraints = build_wrap_circuit(template_vk, template_proof.clone());
+    let constraints = serde_json::to_vec(&constraints).expect("failed to serialize constraints");
+
+    let build_dir = install();
+    if artifacts_match_constraints(&build_dir, &constraints) {
+        return build_dir;
+    }
+    if artifacts_match_constraints(&dev_dir, &constraints) {
+        println!(
+            "[sp1] using the {} artifacts built for the current circuit at {}",
+            name,
+            dev_dir.display()
+        );
+        return dev_dir;
+    }
+    println!(
+        "[sp1] {} artifacts for commit {} were not built for the current circuit. building them in {}",
+        name,
+        PLONK_BN254_ARTIFACTS_COMMIT,
+        dev_dir.display()
+    );
+    build(template_vk, template_proof, dev_dir.clone());
+    dev_dir
+}
+
+/// Whether the artifacts in `build_dir` were built from the given serialized constraints.
+fn artifacts_match_constraints(build_dir: &Path, constraints: &[u8]) -> bool {
+    std::fs::read(build_dir.join("constraints.json"))
+        .map(|installed| installed == constraints)
+        .unwrap_or(false)
+}
+
+/// Tries to build the Groth16 artifacts inside the development directory.
+pub fn try_build_groth16_bn254_artifacts_dev(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+) -> PathBuf {
+    let build_dir = groth16_bn254_artifacts_dev_dir();
+    println!("[sp1] building groth16 bn254 artifacts in development mode");
+    build_groth16_bn254_artifacts(template_vk, template_proof, &build_dir);
+    build_dir
+}
+
+/// Gets the directory where the Groth16 artifacts are installed.
+fn groth16_bn254_artifacts_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap()
+        .join(".sp1")
+        .join("circuits")
+        .join("groth16_bn254")
+        .join(PLONK_BN254_ARTIFACTS_COMMIT)
+}
+
+/// Gets the directory where the Groth16 artifacts are installed in development mode.
+pub fn groth16_bn254_artifacts_dev_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap()
+        .join(".sp1")
+        .join("circuits")
+        .join("groth16_bn254")
+        .join("dev")
+}
+
+/// Build the groth16 bn254 artifacts to the given directory for the given verification key and
+/// template proof.
+pub fn build_groth16_bn254_artifacts(
+    template_vk: &StarkVerifyingKey<OuterSC>,
+    template_proof: &ShardProof<OuterSC>,
+    build_dir: impl Into<PathBuf>,
+) {
+    let build_dir = build_dir.into();
+    std::fs::create_dir_all(&build_dir).expect("failed to create build directory");
+    let (constraints, witness) = build_constraints_and_witness(template_vk, template_proof);
+    Groth16Bn254Prover::build(constraints, witness, build_dir);
+}
+
+/// Builds the groth16 bn254 artifacts to the given directory, with a dummy template proof.
+pub fn build_groth16_bn254_artifacts_with_dummy(build_dir: impl Into<PathBuf>) {
+    let (wrap_vk, wrapped_proof) = dummy_proof();
+    build_groth16_bn254_artifacts(&wrap_vk, &wrapped_proof, build_dir.into());
+}
+
+/// Tries to build the Groth16 artifacts over BLS12-381 if they are not already built.
+///
+/// Unlike the artifacts over BN254, the artifacts over BLS12-381 are not released, so they are
+/// built locally for the current version of the circuits, with a setup specific to the machine
+/// which builds them.
+pub fn try_build_groth16_bls12381_artifacts(
+    template_vk: &StarkVerifyingKey<OuterBls12381SC>,
+    template_proof: &ShardProof<OuterBls12381SC>,
+) -> PathBuf {
+    let build_dir = groth16_bls12381_artifacts_dir();
+
+    if build_dir.exists() {
+        println!(
+            "[sp1] groth16 bls12381 artifacts already seem to exist at {}. if you want to rebuild them, delete the directory",
+            build_dir.display()
+        );
+    } else {
+        println!(
+            "[sp1] building groth16 bls12381 artifacts for commit {} in {}",
+            PLONK_BN254_ARTIFACTS_COMMIT,
+            build_dir.display()
+        );
+        build_groth16_bls12381_artifacts(template_vk, template_proof, &build_dir);
+    }
+    build_dir
+}
+
+/// Tries to build the Groth16 artifacts over BLS12-381 inside the development directory.
+pub fn try_build_groth16_bls12381_artifacts_dev(
+    template_vk: &StarkVerifyingKey<OuterBls12381SC>,
+    template_proof: &ShardProof<OuterBls12381SC>,
+) -> PathBuf {
+    let build_dir = groth16_bls12381_artifacts_dev_dir();
+    println!("[sp1] building groth16 bls12381 artifacts in development mode");
+    build_groth16_bls12381_artifacts(template_vk, template_proof, &build_dir);
+    build_dir
+}
+
+/// Gets the directory where the Groth16 artifacts over BLS12-381 are built.
+pub fn groth16_bls12381_artifacts_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap()
+        .join(".sp1")
+        .join("circuits")
+        .join("groth16_bls12381")
+        .join(PLONK_BN254_ARTIFACTS_COMMIT)
+}
+
+/// Gets the directory where the Groth16 artifacts over BLS12-381 are built in development mode.
+pub fn groth16_bls12381_artifacts_dev_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap()
+        .join(".sp1")
+        .join("circuits")
+        .join("groth16_bls12381")
+        .join("dev")
+}
+
+/// Build the groth16 bls12381 artifacts to the given directory for the given verification key
+/// and template proof.
+pub fn build_groth16_bls12381_artifacts(
+    template_vk: &StarkVerifyingKey<OuterBls12381SC>,
+    template_proof: &ShardProof<OuterBls12381SC>,
+    build_dir: impl Into<PathBuf>,
+) {
+    let build_dir = build_dir.into();
+    std::fs::create_dir_all(&build_dir).expect("failed to create build directory");
+    let (constraints, witness) =
+        build_constraints_and_witness_bls12381(template_vk, template_proof);
+    Groth16Bls12381Prover::build(constraints, witness, build_dir);
+}
+
+/// Builds the groth16 bls12381 artifacts to the given directory, with a dummy template proof.
+pub fn build_groth16_bls12381_artifacts_with_dummy(build_dir: impl Into<PathBuf>) {
+    let (wrap_vk, wrapped_proof) = dummy_proof_bls12381();
+    build_groth16_bls12381_artifacts(&wrap_vk, &wrapped_proof, build_dir.into());
+}
+
 /// Build the verifier constraints and template witness for the circuit.
 pub fn build_constraints_and_witness(
     template_vk: &StarkVerifyingKey<OuterSC>,
@@ -107,12 +401,41 @@ pub fn build_constraints_and_witness(
         .try_into()
         .unwrap();
     let committed_values_digest = babybear_bytes_to_bn254(&committed_values_digest_bytes);
+    let user_values = babybear_words_to_bn254(&pv.user_values);
+
+    tracing::info!("building template witness");
+    let mut witness = Witness::default();
+    template_proof.write(&mut witness);
+    witness.write_commited_values_digest(committed_values_digest);
+    witness.write_vkey_hash(vkey_hash);
+    witness.write_user_values(&user_values);
+
+    (constraints, witness)
+}
+
+/// Build the verifier constraints and template witness for the circuit over BLS12-381.
+pub fn build_constraints_and_witness_bls12381(
+    template_vk: &StarkVerifyingKey<OuterBls12381SC>,
+    template_proof: &ShardProof<OuterBls12381SC>,
+) -> (Vec<Constraint>, Witness<OuterBls12381Config>) {
+    tracing::info!("building verifier constraints");
+    let constraints = tracing::info_span!("wrap circuit")
+        .in_scope(|| build_wrap_circuit_bls12381(template_vk, template_proof.clone()));
+
+    let pv: &RecursionPublicValues<BabyBear> = template_proof.public_values.as_slice().borrow();
+    let vkey_hash = babybears_to_bls12381(&pv.sp1_vk_digest);
+    let committed_values_digest_bytes: [BabyBear; 32] = words_to_bytes(&pv.committed_value_digest)
+        .try_into()
+        .unwrap();
+    let committed_values_digest = babybear_bytes_to_bls12381(&committed_values_digest_bytes);
+    let user_values = babybear_words_to_bls12381(&pv.user_values);
 
     tracing::info!("building template witness");
     let mut witness = Witness::default();
     template_proof.write(&mut witness);
     witness.write_commited_values_digest(committed_values_digest);
     witness.write_vkey_hash(vkey_hash);
+    witness.write_user_values(&user_values);
 
     (constraints, witness)
 }
@@ -144,3 +467,33 @@ pub fn dummy_proof() -> (StarkVerifyingKey<OuterSC>, ShardProof<OuterSC>) {
 
     (prover.wrap_vk, wrapped_proof.proof)
 }
+
+/// Generate a dummy proof over BLS12-381 that we can use to build the circuit over BLS12-381.
+pub fn dummy_proof_bls12381() -> (
+    StarkVerifyingKey<OuterBls12381SC>,
+    ShardProof<OuterBls12381SC>,
+) {
+    let elf = include_bytes!("fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+
+    tracing::info!("initializing prover");
+    let prover = SP1Prover::new();
+
+    tracing::info!("setup elf");
+    let (pk, vk) = prover.setup(elf);
+
+    tracing::info!("prove core");
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&500u32);
+    let core_proof = prover.prove_core(&pk, &stdin).unwrap();
+
+    tracing::info!("compress");
+    let compressed_proof = prover.compress(&vk, core_proof, vec![]).unwrap();
+
+    tracing::info!("shrink");
+    let shrink_proof = prover.shrink(compressed_proof).unwrap();
+
+    tracing::info!("wrap");
+    let wrapped_proof = prover.wrap_bls12381(shrink_proof).unwrap();
+
+    (prover.wrap_bls12381_keys().vk.clone(), wrapped_proof.proof)
+}
@@ -0,0 +1,415 @@
+//! Distributed proving of the shards of a core proof.
+//!
+//! The coordinator executes the program and splits its execution into checkpoints, which it sends
+//! to the workers over TCP. Each worker traces the shards of its checkpoints and commits to them,
+//! and then, once the coordinator has observed all the commitments, traces them again and proves
+//! them. Only the checkpoints, the commitments and the shard proofs go over the network, so no
+//! machine has to hold the traces of more than a checkpoint at a time.
+//!
+//! The core proof is then compressed, shrunk and wrapped on the coordinator with [SP1Prover].
+
+use std::collections::HashMap;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+use p3_baby_bear::BabyBear;
+use p3_challenger::CanObserve;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sp1_core::air::PublicValues;
+use sp1_core::runtime::{ExecutionError, ExecutionState, Program, Runtime};
+use sp1_core::stark::{
+    Com, LocalProver, MachineRecord, RiscvAir, ShardProof, StarkGenericConfig, StarkMachine,
+    StarkProvingKey, StarkVerifyingKey, VerifyingKeyDigest,
+};
+use sp1_core::utils::{prove_checkpoint_shard, shard_checkpoint, SP1CoreOpts, SecurityPreset};
+use thiserror::Error;
+
+use crate::{
+    CoreSC, InnerSC, SP1CoreProof, SP1CoreProofData, SP1Prover, SP1ProvingKey, SP1PublicValues,
+    SP1RecursionProverError, SP1ReduceProof, SP1Stdin,
+};
+
+/// The maximum length of a message, above which the message is rejected before its buffer is
+/// allocated.
+pub const MAX_MESSAGE_LEN: u64 = 1 << 32;
+
+/// The commitment to the main trace of a shard, with the public values the challenger observes.
+pub type ShardCommitment = (Com<CoreSC>, Vec<BabyBear>);
+
+/// A request from the coordinator to a worker.
+#[derive(Serialize, Deserialize)]
+pub enum WorkerRequest {
+    /// Trace the shards of a checkpoint and commit to them.
+    Commit {
+        elf: Vec<u8>,
+        checkpoint: ExecutionState,
+        public_values: PublicValues<u32, u32>,
+        opts: SP1CoreOpts,
+    },
+    /// Trace the shards of a checkpoint again and prove them, given the commitments of all the
+    /// shards of the execution in order.
+    Prove {
+        elf: Vec<u8>,
+        checkpoint: ExecutionState,
+        public_values: PublicValues<u32, u32>,
+        opts: SP1CoreOpts,
+        commitments: Vec<ShardCommitment>,
+    },
+}
+
+/// The response of a worker to a [WorkerRequest].
+#[derive(Serialize, Deserialize)]
+pub enum WorkerResponse {
+    Committed(Vec<ShardCommitment>),
+    Proved(Vec<ShardProof<CoreSC>>),
+}
+
+#[derive(Error, Debug)]
+pub enum DistributedProverError {
+    #[error("failed to execute program: {0}")]
+    ExecutionError(ExecutionError),
+    #[error("io error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("serialization error: {0}")]
+    SerializationError(#[from] bincode::Error),
+    #[error("recursion error: {0}")]
+    RecursionError(#[from] SP1RecursionProverError),
+    #[error("no workers were given")]
+    NoWorkers,
+    #[error("unexpected response from worker {0}")]
+    UnexpectedResponse(SocketAddr),
+}
+
+/// Writes a message as its length in little-endian bytes followed by its bincode encoding.
+fn write_message<T: Serialize>(writer: &mut impl Write, message: &T) -> bincode::Result<()> {
+    let bytes = bincode::serialize(message)?;
+    writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a message written by [write_message].
+fn read_message<T: DeserializeOwned>(reader: &mut impl Read) -> bincode::Result<T> {
+    let mut len = [0u8; 8];
+    reader.read_exact(&mut len)?;
+    let len = u64::from_le_bytes(len);
+    if len > MAX_MESSAGE_LEN {
+        return Err(Box::new(bincode::ErrorKind::SizeLimit));
+    }
+    let mut bytes = vec![0u8; len as usize];
+    reader.read_exact(&mut bytes)?;
+    bincode::deserialize(&bytes)
+}
+
+/// A worker, which commits to and proves the shards of the checkpoints it is sent.
+pub struct ShardWorker {
+    machine: StarkMachine<CoreSC, RiscvAir<BabyBear>>,
+    /// The keys of the programs the worker has proved shards of, by the SHA-256 digest of their ELF.
+    keys: Mutex<HashMap<[u8; 32], Arc<ProgramKeys>>>,
+}
+
+/// The proving and verifying keys of a program.
+type ProgramKeys = (StarkProvingKey<CoreSC>, StarkVerifyingKey<CoreSC>);
+
+impl ShardWorker {
+    pub fn new() -> Self {
+        Self::with_security_preset(SecurityPreset::Standard)
+    }
+
+    /// Creates a worker which proves the shards with the parameters of `preset`, which must be the
+    /// preset of the prover which sends the checkpoints.
+    pub fn with_security_preset(preset: SecurityPreset) -> Self {
+        Self {
+            machine: RiscvAir::machine(CoreSC::with_preset(preset)),
+            keys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the keys of the program of `elf`, which are only computed for the first request of
+    /// the program.
+    fn keys(&self, elf: &[u8], program: &Program) -> Arc<ProgramKeys> {
+        let digest: [u8; 32] = Sha256::digest(elf).into();
+        if let Some(keys) = self.keys.lock().unwrap().get(&digest) {
+            return keys.clone();
+        }
+        // Set up the program without holding the lock, so other programs are not blocked.
+        let keys = Arc::new(self.machine.setup(program));
+        self.keys
+            .lock()
+            .unwrap()
+            .entry(digest)
+            .or_insert(keys)
+            .clone()
+    }
+
+    /// Serves the connections of `listener` until it fails, each in its own thread.
+    pub fn serve(self, listener: TcpListener) -> std::io::Result<()> {
+        let worker = &self;
+        std::thread::scope(|s| {
+            for stream in listener.incoming() {
+                let stream = stream?;
+                s.spawn(move || {
+                    let peer = stream.peer_addr().ok();
+                    if let Err(e) = worker.handle_connection(stream) {
+                        tracing::warn!("connection from {:?} failed: {}", peer, e);
+                    }
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// Handles the requests of a connection until the coordinator closes it.
+    fn handle_connection(&self, stream: TcpStream) -> bincode::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = BufWriter::new(stream);
+        loop {
+            let request = match read_message::<WorkerRequest>(&mut reader) {
+                Ok(request) => request,
+                Err(e) => match *e {
+                    bincode::ErrorKind::Io(ref e)
+                        if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                    {
+                        return Ok(())
+                    }
+                    _ => return Err(e),
+                },
+            };
+            let response = self.handle(request);
+            write_message(&mut writer, &response)?;
+        }
+    }
+
+    /// Handles a request.
+    pub fn handle(&self, request: WorkerRequest) -> WorkerResponse {
+        match request {
+            WorkerRequest::Commit {
+                elf,
+                checkpoint,
+                public_values,
+                opts,
+            } => {
+                let program = Program::from(&elf);
+                let shards =
+                    shard_checkpoint(&self.machine, program, checkpoint, public_values, opts);
+                let (commitments, _) = LocalProver::commit_shards(&self.machine, &shards, opts);
+                let num_pv_elts = self.machine.num_pv_elts();
+                let commitments = commitments
+                    .into_iter()
+                    .zip(shards.iter())
+                    .map(|(commitment, shard)| {
+                        let public_values = shard.public_values::<BabyBear>();
+                        (commitment, public_values[0..num_pv_elts].to_vec())
+                    })
+                    .collect();
+                WorkerResponse::Committed(commitments)
+            }
+            WorkerRequest::Prove {
+                elf,
+                checkpoint,
+                public_values,
+                opts,
+                commitments,
+            } => {
+                let program = Program::from(&elf);
+                let keys = self.keys(&elf, &program);
+                let (pk, vk) = (&keys.0, &keys.1);
+                let mut challenger = self.machine.config().challenger();
+                vk.observe_into(&mut challenger);
+                for (commitment, shard_public_values) in commitments {
+                    challenger.observe(commitment);
+                    challenger.observe_slice(&shard_public_values);
+                }
+
+                let shards =
+                    shard_checkpoint(&self.machine, program, checkpoint, public_values, opts);
+                let proofs = shards
+                    .iter()
+                    .map(|shard| prove_checkpoint_shard(&self.machine, pk, shard, &challenger))
+                    .collect();
+                WorkerResponse::Proved(proofs)
+            }
+        }
+    }
+}
+
+/// A coordinator, which executes programs and has the workers at `workers` prove their shards.
+pub struct DistributedProver {
+    pub workers: Vec<SocketAddr>,
+}
+
+impl DistributedProver {
+    pub fn new(workers: Vec<SocketAddr>) -> Self {
+        Self { workers }
+    }
+
+    /// Generate shard proofs of an SP1 program with the workers.
+    ///
+    /// The checkpoints of `opts.shard_batch_size` shards are assigned to the workers in turn, so
+    /// the proof is the same as the one of [SP1Prover::prove_core_with_opts].
+    pub fn prove_core(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        opts: SP1CoreOpts,
+    ) -> Result<SP1CoreProof, DistributedProverError> {
+        if self.workers.is_empty() {
+            return Err(DistributedProverError::NoWorkers);
+        }
+
+        // Execute the program, saving the checkpoints.
+        let mut runtime = Runtime::new(Program::from(&pk.elf), opts);
+        runtime.write_vecs(&stdin.buffer);
+        runtime.state.random_seed = stdin.random_seed;
+        // Workers re-execute their shards from the checkpoints, so streamed input is buffered.
+        if let Some(stream) = stdin.stream.as_ref() {
+            runtime
+                .buffer_stream(stream)
+                .map_err(DistributedProverError::ExecutionError)?;
+        }
+        for (proof, vkey) in stdin.proofs.iter() {
+            runtime.write_proof(proof.clone(), vkey.clone());
+        }
+        let mut checkpoints = Vec::new();
+        loop {
+            let (checkpoint, done) = runtime
+                .execute_state()
+                .map_err(DistributedProverError::ExecutionError)?;
+            checkpoints.push(checkpoint);
+            if done {
+                break;
+            }
+        }
+        let mut public_values = runtime.record.public_values;
+        public_values.vk_digest = pk.vk.vk.digest_u32();
+        let committed_values = SP1PublicValues::with_leaves(
+            &runtime.state.public_values_stream,
+            std::mem::take(&mut runtime.state.public_leaves),
+        );
+        tracing::info!(
+            "executed {} cycles in {} checkpoints",
+            runtime.state.global_clk,
+            checkpoints.len()
+        );
+
+        // Commit to the shards of every checkpoint.
+        let commitments = self
+            .run(&checkpoints, |checkpoint| WorkerRequest::Commit {
+                elf: pk.elf.clone(),
+                checkpoint: checkpoint.clone(),
+                public_values,
+                opts,
+            })?
+            .into_iter()
+            .map(|(addr, response)| match response {
+                WorkerResponse::Committed(commitments) => Ok(commitments),
+                _ => Err(DistributedProverError::UnexpectedResponse(addr)),
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+
+        // Prove the shards of every checkpoint.
+        let shard_proofs = self
+            .run(&checkpoints, |checkpoint| WorkerRequest::Prove {
+                elf: pk.elf.clone(),
+                checkpoint: checkpoint.clone(),
+                public_values,
+                opts,
+                commitments: commitments.clone(),
+            })?
+            .into_iter()
+            .map(|(addr, response)| match response {
+                WorkerResponse::Proved(proofs) => Ok(proofs),
+                _ => Err(DistributedProverError::UnexpectedResponse(addr)),
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+
+        Ok(SP1CoreProof {
+            proof: SP1CoreProofData(shard_proofs),
+            stdin: stdin.clone(),
+            public_values: committed_values,
+        })
+    }
+
+    /// Generate shard proofs with the workers, and compress them on this machine with `prover`.
+    pub fn prove_compressed(
+        &self,
+        prover: &SP1Prover,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        opts: SP1CoreOpts,
+    ) -> Result<SP1ReduceProof<InnerSC>, DistributedProverError> {
+        let proof = self.prove_core(pk, stdin, opts)?;
+        let deferred_proofs = stdin.proofs.iter().map(|p| p.0.clone()).collect();
+        Ok(prover.compress(&pk.vk, proof, deferred_proofs)?)
+    }
+
+    /// Sends the request of each checkpoint to a worker, with the workers taking the checkpoints
+    /// in turn, and returns the responses in the order of the checkpoints.
+    fn run(
+        &self,
+        checkpoints: &[ExecutionState],
+        request: impl Fn(&ExecutionState) -> WorkerRequest + Sync,
+    ) -> Result<Vec<(SocketAddr, WorkerResponse)>, DistributedProverError> {
+        let num_workers = self.workers.len();
+        let responses = std::thread::scope(|s| {
+            let handles = self
+                .workers
+                .iter()
+                .enumerate()
+                .map(|(i, addr)| {
+                    let request = &request;
+                    s.spawn(move || {
+                        let stream = TcpStream::connect(addr)?;
+                        let mut reader = BufReader::new(stream.try_clone()?);
+                        let mut writer = BufWriter::new(stream);
+                        let mut responses = Vec::new();
+                        for index in (i..checkpoints.len()).step_by(num_workers) {
+                            write_message(&mut writer, &request(&checkpoints[index]))?;
+                            let response = read_message::<WorkerResponse>(&mut reader)?;
+                            responses.push((index, (*addr, response)));
+                        }
+                        Ok::<_, DistributedProverError>(responses)
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("worker thread panicked"))
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+
+        let mut responses = responses.into_iter().flatten().collect::<Vec<_>>();
+        responses.sort_by_key(|(index, _)| *index);
+        Ok(responses
+            .into_iter()
+            .map(|(_, response)| response)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_message_length() {
+        let mut bytes = Vec::new();
+        write_message(&mut bytes, &vec![1u32, 2, 3]).unwrap();
+        assert_eq!(
+            read_message::<Vec<u32>>(&mut bytes.as_slice()).unwrap(),
+            vec![1, 2, 3]
+        );
+
+        // A length above the cap is rejected before the buffer is allocated.
+        let bytes = u64::MAX.to_le_bytes();
+        assert!(matches!(
+            *read_message::<Vec<u32>>(&mut bytes.as_slice()).unwrap_err(),
+            bincode::ErrorKind::SizeLimit
+        ));
+    }
+}
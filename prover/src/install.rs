@@ -10,6 +10,10 @@ use crate::utils::block_on;
 pub const PLONK_BN254_ARTIFACTS_URL_BASE: &str = "https://sp1-circuits.s3-us-east-2.amazonaws.com";
 
 /// The current version of the plonk bn254 artifacts.
+///
+/// The artifacts must be rebuilt and this commit bumped whenever the wrap circuit changes. Until
+/// then, the provers build the artifacts locally, see
+/// [crate::build::try_install_or_build_plonk_bn254_artifacts].
 pub const PLONK_BN254_ARTIFACTS_COMMIT: &str = "e48c01ec";
 
 /// Install the latest plonk bn254 artifacts.
@@ -17,14 +21,35 @@ pub const PLONK_BN254_ARTIFACTS_COMMIT: &str = "e48c01ec";
 /// This function will download the latest plonk bn254 artifacts from the S3 bucket and extract them to
 /// the directory specified by [plonk_bn254_artifacts_dir()].
 pub fn install_plonk_bn254_artifacts(build_dir: PathBuf) {
+    install_artifacts(
+        format!(
+            "{}/{}.tar.gz",
+            PLONK_BN254_ARTIFACTS_URL_BASE, PLONK_BN254_ARTIFACTS_COMMIT
+        ),
+        build_dir,
+    )
+}
+
+/// Install the latest groth16 bn254 artifacts.
+///
+/// The groth16 bn254 artifacts are built from the same commit as the plonk bn254 artifacts, and
+/// published next to them.
+pub fn install_groth16_bn254_artifacts(build_dir: PathBuf) {
+    install_artifacts(
+        format!(
+            "{}/{}-groth16.tar.gz",
+            PLONK_BN254_ARTIFACTS_URL_BASE, PLONK_BN254_ARTIFACTS_COMMIT
+        ),
+        build_dir,
+    )
+}
+
+/// Downloads the tarball at `download_url` and extracts it to `build_dir`.
+fn install_artifacts(download_url: String, build_dir: PathBuf) {
     // Create the build directory.
     std::fs::create_dir_all(&build_dir).expect("failed to create build directory");
 
     // Download the artifacts.
-    let download_url = format!(
-        "{}/{}.tar.gz",
-        PLONK_BN254_ARTIFACTS_URL_BASE, PLONK_BN254_ARTIFACTS_COMMIT
-    );
     let mut artifacts_tar_gz_file =
         tempfile::NamedTempFile::new().expect("failed to create tempfile");
     let client = Client::builder()
@@ -0,0 +1,222 @@
+//! A cache on disk of the recursion programs and their keys.
+//!
+//! Building the recursion programs of [SP1Prover] and setting up their keys takes minutes, and
+//! their result only depends on the version of SP1, so [SP1Prover::new] loads them from the cache
+//! when they were built before by the same version, and saves them to the cache otherwise. The
+//! cache is at `~/.sp1/recursion/<version>` by default, and can be moved with the
+//! `SP1_RECURSION_CACHE_DIR` environment variable or disabled by setting `SP1_RECURSION_CACHE` to
+//! `false`.
+//!
+//! The keys of the provers of a [SecurityPreset] other than the standard one are cached in a
+//! subdirectory named after the preset.
+//!
+//! A cache written by a modified build of the same version of SP1 has the keys of the programs of
+//! that build, so remove the cache or disable it while developing the recursion programs.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+use p3_baby_bear::BabyBear;
+use serde::{Deserialize, Serialize};
+use sp1_core::encoding::{self, EncodingError};
+use sp1_core::stark::{RiscvAir, StarkMachine, StarkProvingKey, StarkVerifyingKey};
+use sp1_core::utils::SecurityPreset;
+use sp1_recursion_compiler::config::InnerConfig;
+use sp1_recursion_core::runtime::RecursionProgram;
+use sp1_recursion_program::machine::{
+    SP1CompressVerifier, SP1DeferredVerifier, SP1RecursiveVerifier, SP1RootVerifier,
+};
+
+use crate::install::PLONK_BN254_ARTIFACTS_COMMIT;
+use crate::{CompressAir, CoreSC, InnerSC, OuterSC, ReduceAir, SP1Prover, WrapAir};
+
+/// The environment variable which disables the cache when set to `false`.
+pub const RECURSION_CACHE_ENV: &str = "SP1_RECURSION_CACHE";
+
+/// The environment variable which sets the directory of the cache.
+pub const RECURSION_CACHE_DIR_ENV: &str = "SP1_RECURSION_CACHE_DIR";
+
+/// The magic bytes of the cached recursion keys.
+pub const RECURSION_KEYS_MAGIC: [u8; 4] = *b"SP1R";
+
+/// The name of the file of the recursion keys in the directory of the cache.
+const RECURSION_KEYS_FILE: &str = "recursion_keys.bin";
+
+/// The version of SP1 which the cached keys are built by.
+pub fn recursion_cache_version() -> String {
+    format!(
+        "{}-{}",
+        env!("CARGO_PKG_VERSION"),
+        PLONK_BN254_ARTIFACTS_COMMIT
+    )
+}
+
+/// The directory of the cache of the current version of SP1, or `None` if the cache is disabled.
+pub fn recursion_cache_dir() -> Option<PathBuf> {
+    if env::var(RECURSION_CACHE_ENV).is_ok_and(|value| value == "false") {
+        return None;
+    }
+    let root = match env::var(RECURSION_CACHE_DIR_ENV) {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => dirs::home_dir()?.join(".sp1").join("recursion"),
+    };
+    Some(root.join(recursion_cache_version()))
+}
+
+/// The directory of the cached keys of the provers of `preset` in the directory of the cache, since
+/// the recursion programs verify the proofs with the parameters of FRI of the preset.
+fn preset_cache_dir(dir: PathBuf, preset: SecurityPreset) -> PathBuf {
+    match preset {
+        SecurityPreset::Standard => dir,
+        SecurityPreset::Bits80 => dir.join("bits80"),
+        SecurityPreset::Bits100 => dir.join("bits100"),
+        SecurityPreset::Bits128 => dir.join("bits128"),
+        SecurityPreset::Custom {
+            security_bits,
+            proof_of_work_bits,
+        } => dir.join(format!("custom-{}-{}", security_bits, proof_of_work_bits)),
+    }
+}
+
+/// The recursion programs of [SP1Prover] and their keys.
+#[derive(Serialize, Deserialize)]
+pub struct RecursionKeys {
+    pub recursion_program: RecursionProgram<BabyBear>,
+    pub rec_pk: StarkProvingKey<InnerSC>,
+    pub rec_vk: StarkVerifyingKey<InnerSC>,
+    pub deferred_program: RecursionProgram<BabyBear>,
+    pub deferred_pk: StarkProvingKey<InnerSC>,
+    pub deferred_vk: StarkVerifyingKey<InnerSC>,
+    pub compress_program: RecursionProgram<BabyBear>,
+    pub compress_pk: StarkProvingKey<InnerSC>,
+    pub compress_vk: StarkVerifyingKey<InnerSC>,
+    pub shrink_program: RecursionProgram<BabyBear>,
+    pub shrink_pk: StarkProvingKey<InnerSC>,
+    pub shrink_vk: StarkVerifyingKey<InnerSC>,
+    pub wrap_program: RecursionProgram<BabyBear>,
+    pub wrap_pk: StarkProvingKey<OuterSC>,
+    pub wrap_vk: StarkVerifyingKey<OuterSC>,
+}
+
+impl RecursionKeys {
+    /// Builds the recursion programs and sets up their keys.
+    pub fn build(
+        core_machine: &StarkMachine<CoreSC, RiscvAir<BabyBear>>,
+        compress_machine: &StarkMachine<InnerSC, ReduceAir<BabyBear>>,
+        shrink_machine: &StarkMachine<InnerSC, CompressAir<BabyBear>>,
+        wrap_machine: &StarkMachine<OuterSC, WrapAir<BabyBear>>,
+    ) -> Self {
+        // Get the recursive verifier and setup the proving and verifying keys.
+        let recursion_program = SP1RecursiveVerifier::<InnerConfig, _>::build(core_machine);
+        let (rec_pk, rec_vk) = compress_machine.setup(&recursion_program);
+
+        // Get the deferred program and keys.
+        let deferred_program = SP1DeferredVerifier::<InnerConfig, _, _>::build(compress_machine);
+        let (deferred_pk, deferred_vk) = compress_machine.setup(&deferred_program);
+
+        // Make the reduce program and keys.
+        let compress_program = SP1CompressVerifier::<InnerConfig, _, _>::build(
+            compress_machine,
+            &rec_vk,
+            &deferred_vk,
+        );
+        let (compress_pk, compress_vk) = compress_machine.setup(&compress_program);
+
+        // Get the compress program and keys.
+        let shrink_program =
+            SP1RootVerifier::<InnerConfig, _, _>::build(compress_machine, &compress_vk, true);
+        let (shrink_pk, shrink_vk) = shrink_machine.setup(&shrink_program);
+
+        // Get the wrap program and keys.
+        let wrap_program =
+            SP1RootVerifier::<InnerConfig, _, _>::build(shrink_machine, &shrink_vk, false);
+        let (wrap_pk, wrap_vk) = wrap_machine.setup(&wrap_program);
+
+        Self {
+            recursion_program,
+            rec_pk,
+            rec_vk,
+            deferred_program,
+            deferred_pk,
+            deferred_vk,
+            compress_program,
+            compress_pk,
+            compress_vk,
+            shrink_program,
+            shrink_pk,
+            shrink_vk,
+            wrap_program,
+            wrap_pk,
+            wrap_vk,
+        }
+    }
+
+    /// Loads the keys saved by [RecursionKeys::save] in `dir`.
+    pub fn load(dir: &Path) -> Result<Self, EncodingError> {
+        encoding::load(RECURSION_KEYS_MAGIC, dir.join(RECURSION_KEYS_FILE))
+    }
+
+    /// Saves the keys in `dir`. The file is written next to its final path and then moved, so
+    /// that a concurrent [RecursionKeys::load] never reads a partial file.
+    pub fn save(&self, dir: &Path) -> Result<(), EncodingError> {
+        std::fs::create_dir_all(dir)?;
+        let bytes = encoding::encode(RECURSION_KEYS_MAGIC, self)?;
+        let file = tempfile::NamedTempFile::new_in(dir)?;
+        std::fs::write(file.path(), bytes)?;
+        file.persist(dir.join(RECURSION_KEYS_FILE))
+            .map_err(|e| e.error)?;
+        Ok(())
+    }
+
+    /// Loads the keys from the cache, or builds them and saves them to the cache if they are not
+    /// cached yet.
+    pub fn load_or_build(
+        core_machine: &StarkMachine<CoreSC, RiscvAir<BabyBear>>,
+        compress_machine: &StarkMachine<InnerSC, ReduceAir<BabyBear>>,
+        shrink_machine: &StarkMachine<InnerSC, CompressAir<BabyBear>>,
+        wrap_machine: &StarkMachine<OuterSC, WrapAir<BabyBear>>,
+    ) -> Self {
+        let preset = core_machine.config().preset();
+        let Some(dir) = recursion_cache_dir().map(|dir| preset_cache_dir(dir, preset)) else {
+            return Self::build(core_machine, compress_machine, shrink_machine, wrap_machine);
+        };
+        match Self::load(&dir) {
+            Ok(keys) => {
+                tracing::debug!("loaded the recursion keys from {:?}", dir);
+                return keys;
+            }
+            Err(EncodingError::IoError(e)) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!("ignoring the invalid recursion keys in {:?}: {}", dir, e),
+        }
+        let keys = Self::build(core_machine, compress_machine, shrink_machine, wrap_machine);
+        if let Err(e) = keys.save(&dir) {
+            tracing::warn!("failed to save the recursion keys to {:?}: {}", dir, e);
+        }
+        keys
+    }
+}
+
+impl SP1Prover {
+    /// Builds the recursion programs and their keys and saves them to the cache, unless they are
+    /// cached already, so that the next [SP1Prover::new] loads them. Returns the directory of the
+    /// cache, or `None` if the cache is disabled.
+    pub fn warm_recursion_cache() -> Result<Option<PathBuf>, EncodingError> {
+        let Some(dir) = recursion_cache_dir() else {
+            return Ok(None);
+        };
+        if RecursionKeys::load(&dir).is_err() {
+            let core_machine = RiscvAir::machine(CoreSC::default());
+            let compress_machine = ReduceAir::machine(InnerSC::default());
+            let shrink_machine = CompressAir::wrap_machine_dyn(InnerSC::compressed());
+            let wrap_machine = WrapAir::wrap_machine(OuterSC::default());
+            RecursionKeys::build(
+                &core_machine,
+                &compress_machine,
+                &shrink_machine,
+                &wrap_machine,
+            )
+            .save(&dir)?;
+        }
+        Ok(Some(dir))
+    }
+}
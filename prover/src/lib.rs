@@ -10,26 +10,37 @@
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::new_without_default)]
 
+pub mod aggregate;
+pub mod bls12381;
 pub mod build;
+pub mod distributed;
 pub mod install;
+pub mod key_cache;
+pub mod srs;
 pub mod types;
 pub mod utils;
 pub mod verify;
 
 use std::borrow::Borrow;
+use std::io::Write;
 use std::path::Path;
+use std::sync::OnceLock;
 
+use bls12381::WrapBls12381Keys;
+use key_cache::RecursionKeys;
 use p3_baby_bear::BabyBear;
 use p3_challenger::CanObserve;
 use p3_field::{AbstractField, PrimeField};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use rayon::prelude::*;
 use sp1_core::air::{PublicValues, Word};
+pub use sp1_core::encoding;
 pub use sp1_core::io::{SP1PublicValues, SP1Stdin};
-use sp1_core::runtime::{ExecutionError, ExecutionReport, Runtime};
+use sp1_core::runtime::{ExecutionError, ExecutionReport, Profiler, Runtime};
 use sp1_core::stark::{Challenge, StarkProvingKey};
 use sp1_core::stark::{Challenger, MachineVerificationError};
-use sp1_core::utils::{SP1CoreOpts, DIGEST_SIZE};
+pub use sp1_core::utils::SecurityPreset;
+use sp1_core::utils::{ProgressReporter, ProvingStage, SP1CoreOpts, DIGEST_SIZE};
 use sp1_core::{
     runtime::Program,
     stark::{
@@ -39,20 +50,27 @@ use sp1_core::{
 };
 use sp1_primitives::hash_deferred_proof;
 use sp1_recursion_circuit::witness::Witnessable;
-use sp1_recursion_compiler::config::InnerConfig;
+use sp1_recursion_compiler::config::{InnerConfig, OuterConfig};
 use sp1_recursion_compiler::ir::Witness;
 use sp1_recursion_core::{
     air::RecursionPublicValues,
     runtime::{RecursionProgram, Runtime as RecursionRuntime},
-    stark::{config::BabyBearPoseidon2Outer, RecursionAir},
+    stark::{
+        config::{BabyBearPoseidon2Outer, BabyBearPoseidon2OuterBls12381},
+        RecursionAir,
+    },
+};
+pub use sp1_recursion_gnark_ffi::groth16_bls12381::{
+    Groth16Bls12381Proof, GROTH16_EIP2537_VK_FILE,
 };
+use sp1_recursion_gnark_ffi::groth16_bn254::Groth16Bn254Prover;
+pub use sp1_recursion_gnark_ffi::groth16_bn254::{Groth16Bn254Proof, GROTH16_SOLANA_VK_FILE};
 pub use sp1_recursion_gnark_ffi::plonk_bn254::PlonkBn254Proof;
 use sp1_recursion_gnark_ffi::plonk_bn254::PlonkBn254Prover;
+pub use sp1_recursion_gnark_ffi::solidity::{ProgramVerifier, VerifierCalldata};
+pub use sp1_recursion_gnark_ffi::witness::NUM_PUBLIC_INPUTS;
 use sp1_recursion_program::hints::Hintable;
 pub use sp1_recursion_program::machine::ReduceProgramType;
-use sp1_recursion_program::machine::{
-    SP1CompressVerifier, SP1DeferredVerifier, SP1RecursiveVerifier, SP1RootVerifier,
-};
 pub use sp1_recursion_program::machine::{
     SP1DeferredMemoryLayout, SP1RecursionMemoryLayout, SP1ReduceMemoryLayout, SP1RootMemoryLayout,
 };
@@ -69,6 +87,9 @@ pub type InnerSC = BabyBearPoseidon2;
 /// The configuration for the outer prover.
 pub type OuterSC = BabyBearPoseidon2Outer;
 
+/// The configuration for the outer prover whose proofs are wrapped over BLS12-381.
+pub type OuterBls12381SC = BabyBearPoseidon2OuterBls12381;
+
 const REDUCE_DEGREE: usize = 3;
 const COMPRESS_DEGREE: usize = 9;
 const WRAP_DEGREE: usize = 9;
@@ -135,42 +156,59 @@ pub struct SP1Prover {
 
     /// The machine used for proving the wrapping step.
     pub wrap_machine: StarkMachine<OuterSC, WrapAir<<OuterSC as StarkGenericConfig>::Val>>,
+
+    /// The shape of the recursion tree of [SP1Prover::compress].
+    pub recursion_shape: RecursionShape,
+
+    /// The keys of the aggregation program, which are set up on first use.
+    aggregation_keys: OnceLock<(SP1ProvingKey, SP1VerifyingKey)>,
+
+    /// The machine and the keys of the wrap over BLS12-381, which are set up on first use.
+    wrap_bls12381_keys: OnceLock<WrapBls12381Keys>,
 }
 
 impl SP1Prover {
     /// Initializes a new [SP1Prover].
-    #[instrument(name = "initialize prover", level = "debug", skip_all)]
     pub fn new() -> Self {
-        let core_machine = RiscvAir::machine(CoreSC::default());
-
-        // Get the recursive verifier and setup the proving and verifying keys.
-        let recursion_program = SP1RecursiveVerifier::<InnerConfig, _>::build(&core_machine);
-        let compress_machine = ReduceAir::machine(InnerSC::default());
-        let (rec_pk, rec_vk) = compress_machine.setup(&recursion_program);
+        Self::with_security_preset(SecurityPreset::Standard)
+    }
 
-        // Get the deferred program and keys.
-        let deferred_program = SP1DeferredVerifier::<InnerConfig, _, _>::build(&compress_machine);
-        let (deferred_pk, deferred_vk) = compress_machine.setup(&deferred_program);
+    /// Initializes a new [SP1Prover] whose core, compress and shrink proofs have the conjectured
+    /// security of `preset`.
+    ///
+    /// The wrap proofs keep the parameters of [SecurityPreset::Standard], since the released
+    /// PLONK and Groth16 circuits verify them. The verifying keys of the programs depend on the
+    /// preset, so the proofs are only verified by a prover of the same preset.
+    #[instrument(name = "initialize prover", level = "debug", skip_all)]
+    pub fn with_security_preset(preset: SecurityPreset) -> Self {
+        let core_machine = RiscvAir::machine(CoreSC::with_preset(preset));
+        let compress_machine = ReduceAir::machine(InnerSC::with_preset(preset));
+        let shrink_machine = CompressAir::wrap_machine_dyn(InnerSC::compressed_with_preset(preset));
+        let wrap_machine = WrapAir::wrap_machine(OuterSC::default());
 
-        // Make the reduce program and keys.
-        let compress_program = SP1CompressVerifier::<InnerConfig, _, _>::build(
+        // Load the recursion programs and keys from the cache, or build them.
+        let RecursionKeys {
+            recursion_program,
+            rec_pk,
+            rec_vk,
+            deferred_program,
+            deferred_pk,
+            deferred_vk,
+            compress_program,
+            compress_pk,
+            compress_vk,
+            shrink_program,
+            shrink_pk,
+            shrink_vk,
+            wrap_program,
+            wrap_pk,
+            wrap_vk,
+        } = RecursionKeys::load_or_build(
+            &core_machine,
             &compress_machine,
-            &rec_vk,
-            &deferred_vk,
+            &shrink_machine,
+            &wrap_machine,
         );
-        let (compress_pk, compress_vk) = compress_machine.setup(&compress_program);
-
-        // Get the compress program, machine, and keys.
-        let shrink_program =
-            SP1RootVerifier::<InnerConfig, _, _>::build(&compress_machine, &compress_vk, true);
-        let shrink_machine = CompressAir::wrap_machine_dyn(InnerSC::compressed());
-        let (shrink_pk, shrink_vk) = shrink_machine.setup(&shrink_program);
-
-        // Get the wrap program, machine, and keys.
-        let wrap_program =
-            SP1RootVerifier::<InnerConfig, _, _>::build(&shrink_machine, &shrink_vk, false);
-        let wrap_machine = WrapAir::wrap_machine(OuterSC::default());
-        let (wrap_pk, wrap_vk) = wrap_machine.setup(&wrap_program);
 
         Self {
             recursion_program,
@@ -192,15 +230,27 @@ impl SP1Prover {
             compress_machine,
             shrink_machine,
             wrap_machine,
+            recursion_shape: RecursionShape::default(),
+            aggregation_keys: OnceLock::new(),
+            wrap_bls12381_keys: OnceLock::new(),
         }
     }
 
+    /// Sets the shape of the recursion tree of [SP1Prover::compress].
+    pub fn with_recursion_shape(mut self, recursion_shape: RecursionShape) -> Self {
+        self.recursion_shape = recursion_shape;
+        self
+    }
+
     /// Creates a proving key and a verifying key for a given RISC-V ELF.
     #[instrument(name = "setup", level = "debug", skip_all)]
     pub fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
         let program = Program::from(elf);
         let (pk, vk) = self.core_machine.setup(&program);
-        let vk = SP1VerifyingKey { vk };
+        let vk = SP1VerifyingKey {
+            vk,
+            circuit_version: encoding::SP1_CIRCUIT_VERSION.to_string(),
+        };
         let pk = SP1ProvingKey {
             pk,
             elf: elf.to_vec(),
@@ -210,23 +260,131 @@ impl SP1Prover {
     }
 
     /// Generate a proof of an SP1 program with the specified inputs.
-    #[instrument(name = "execute", level = "info", skip_all)]
     pub fn execute(
         elf: &[u8],
         stdin: &SP1Stdin,
     ) -> Result<(SP1PublicValues, ExecutionReport), ExecutionError> {
-        let program = Program::from(elf);
-        let opts = SP1CoreOpts::default();
+        Self::execute_with_opts(elf, stdin, SP1CoreOpts::default())
+    }
+
+    /// Execute an SP1 program with the specified inputs and core options.
+    ///
+    /// If `opts.max_cycles` is set, execution stops with [ExecutionError::OutOfGas] once the
+    /// cycle budget is exhausted.
+    #[instrument(name = "execute", level = "info", skip_all)]
+    pub fn execute_with_opts(
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        opts: SP1CoreOpts,
+    ) -> Result<(SP1PublicValues, ExecutionReport), ExecutionError> {
+        let mut runtime = Self::run_program(elf, stdin, opts, None)?;
+        Ok((
+            SP1PublicValues::with_leaves(
+                &runtime.state.public_values_stream,
+                std::mem::take(&mut runtime.state.public_leaves),
+            ),
+            runtime.report,
+        ))
+    }
+
+    /// Execute an SP1 program, streaming the public values to `sink` as they are committed instead
+    /// of buffering them, which keeps large outputs out of memory.
+    ///
+    /// Returns the SHA-256 digest of the public values, which is the committed value digest of a
+    /// proof of the execution.
+    #[instrument(name = "execute_streaming", level = "info", skip_all)]
+    pub fn execute_streaming<W: Write + Send + 'static>(
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        opts: SP1CoreOpts,
+        sink: W,
+    ) -> Result<([u8; 32], ExecutionReport), ExecutionError> {
+        let runtime = Self::run_program(elf, stdin, opts, Some(Box::new(sink)))?;
+        Ok((runtime.public_values_digest(), runtime.report))
+    }
+
+    /// Execute an SP1 program and check the constraints of every chip on the traces of its shards,
+    /// without committing to the traces or proving them.
+    ///
+    /// Fails with the chip, shard, row and index of the first constraint which does not hold, so
+    /// this quickly tells whether the program would prove.
+    #[instrument(name = "check_constraints", level = "info", skip_all)]
+    pub fn check_constraints(
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        opts: SP1CoreOpts,
+    ) -> Result<(SP1PublicValues, ExecutionReport), SP1CoreProverError> {
+        let program = Program::decode(elf)
+            .map_err(|err| SP1CoreProverError::ExecutionError(ExecutionError::InvalidElf(err)))?;
+        sp1_core::utils::check_program_constraints(program, stdin, CoreSC::default(), opts)
+    }
+
+    /// Run an SP1 program without tracing, and return the runtime once it has halted.
+    fn run_program(
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        opts: SP1CoreOpts,
+        public_values_sink: Option<Box<dyn Write + Send>>,
+    ) -> Result<Runtime, ExecutionError> {
+        let program = Program::decode(elf).map_err(ExecutionError::InvalidElf)?;
         let mut runtime = Runtime::new(program, opts);
+        runtime.public_values_sink = public_values_sink;
         runtime.write_vecs(&stdin.buffer);
+        runtime.state.random_seed = stdin.random_seed;
+        if let Some(stream) = stdin.stream.as_ref() {
+            runtime.write_stream(stream)?;
+        }
         for (proof, vkey) in stdin.proofs.iter() {
             runtime.write_proof(proof.clone(), vkey.clone());
         }
-        runtime.run_untraced()?;
-        Ok((
-            SP1PublicValues::from(&runtime.state.public_values_stream),
-            runtime.report,
-        ))
+
+        // If PROFILE_FILE is set, sample the guest call stack and write it out in folded format.
+        let profile_file = std::env::var("PROFILE_FILE").ok();
+        if profile_file.is_some() {
+            let sample_rate = match std::env::var("PROFILE_SAMPLE_RATE") {
+                Ok(rate) => rate
+                    .parse()
+                    .ok()
+                    .filter(|rate| *rate > 0)
+                    .unwrap_or_else(|| {
+                        tracing::warn!(
+                            "invalid PROFILE_SAMPLE_RATE {:?}, sampling every 100 cycles",
+                            rate
+                        );
+                        100
+                    }),
+                Err(_) => 100,
+            };
+            runtime.profiler =
+                Some(Profiler::new(elf, sample_rate).map_err(ExecutionError::InvalidElf)?);
+        }
+
+        // If RECORD_FILE is set, record the inputs consumed so the execution can be replayed with
+        // `Runtime::replay`, even if it fails.
+        let record_file = std::env::var("RECORD_FILE").ok();
+        if record_file.is_some() {
+            runtime.start_recording();
+        }
+
+        let result = runtime.run_untraced();
+
+        if let (Some(path), Some(recording)) = (record_file, runtime.finish_recording()) {
+            let file = std::fs::File::create(path).expect("failed to create recording file");
+            recording
+                .save(std::io::BufWriter::new(file))
+                .expect("failed to write recording");
+        }
+        result?;
+
+        if let (Some(path), Some(profiler)) = (profile_file, runtime.profiler.take()) {
+            // The execution succeeded, so a profile which cannot be written is not an error of it.
+            let written = std::fs::File::create(&path)
+                .and_then(|file| profiler.write_folded(std::io::BufWriter::new(file)));
+            if let Err(err) = written {
+                tracing::warn!("failed to write the profile to {}: {}", path, err);
+            }
+        }
+        Ok(runtime)
     }
 
     /// Generate shard proofs which split up and prove the valid execution of a RISC-V program with
@@ -237,11 +395,35 @@ impl SP1Prover {
         pk: &SP1ProvingKey,
         stdin: &SP1Stdin,
     ) -> Result<SP1CoreProof, SP1CoreProverError> {
-        let config = CoreSC::default();
+        self.prove_core_with_opts(pk, stdin, SP1CoreOpts::default())
+    }
+
+    /// Generate shard proofs with the given options.
+    ///
+    /// With `opts.allow_nonzero_exit_code`, programs which halt with a nonzero exit code are
+    /// proven too, and the exit code is part of the public values of the last shard.
+    pub fn prove_core_with_opts(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        opts: SP1CoreOpts,
+    ) -> Result<SP1CoreProof, SP1CoreProverError> {
+        self.prove_core_with_progress(pk, stdin, opts, &ProgressReporter::default())
+    }
+
+    /// Generate shard proofs with the given options, and report the execution and each shard to
+    /// `progress`.
+    pub fn prove_core_with_progress(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        opts: SP1CoreOpts,
+        progress: &ProgressReporter,
+    ) -> Result<SP1CoreProof, SP1CoreProverError> {
+        let config = self.core_machine.config().clone();
         let program = Program::from(&pk.elf);
-        let opts = SP1CoreOpts::default();
-        let (proof, public_values_stream) = sp1_core::utils::prove(program, stdin, config, opts)?;
-        let public_values = SP1PublicValues::from(&public_values_stream);
+        let (proof, public_values) =
+            sp1_core::utils::prove_with_progress(program, stdin, config, opts, progress)?;
         Ok(SP1CoreProof {
             proof: SP1CoreProofData(proof.shard_proofs),
             stdin: stdin.clone(),
@@ -324,6 +506,7 @@ impl SP1Prover {
                 end_shard: last_proof_pv.shard,
                 leaf_challenger: leaf_challenger.clone(),
                 committed_value_digest: last_proof_pv.committed_value_digest.to_vec(),
+                user_values: last_proof_pv.user_values.to_vec(),
                 deferred_proofs_digest: last_proof_pv.deferred_proofs_digest.to_vec(),
             });
 
@@ -341,10 +524,13 @@ impl SP1Prover {
         shard_proofs: &[ShardProof<InnerSC>],
         deferred_proofs: &[ShardProof<InnerSC>],
         batch_size: usize,
-    ) -> (
-        Vec<SP1RecursionMemoryLayout<'a, InnerSC, RiscvAir<BabyBear>>>,
-        Vec<SP1DeferredMemoryLayout<'a, InnerSC, RecursionAir<BabyBear, 3>>>,
-    ) {
+    ) -> Result<
+        (
+            Vec<SP1RecursionMemoryLayout<'a, InnerSC, RiscvAir<BabyBear>>>,
+            Vec<SP1DeferredMemoryLayout<'a, InnerSC, RecursionAir<BabyBear, 3>>>,
+        ),
+        SP1RecursionProverError,
+    > {
         let is_complete = shard_proofs.len() == 1 && deferred_proofs.is_empty();
         let core_inputs = self.get_recursion_core_inputs(
             &vk.vk,
@@ -353,8 +539,9 @@ impl SP1Prover {
             batch_size,
             is_complete,
         );
-        let last_proof_pv =
-            PublicValues::from_vec(shard_proofs.last().unwrap().public_values.clone());
+        let last_proof_pv = PublicValues::<Word<BabyBear>, BabyBear>::try_from(
+            shard_proofs.last().unwrap().public_values.clone(),
+        )?;
         let deferred_inputs = self.get_recursion_deferred_inputs(
             &vk.vk,
             leaf_challenger,
@@ -362,7 +549,7 @@ impl SP1Prover {
             deferred_proofs,
             batch_size,
         );
-        (core_inputs, deferred_inputs)
+        Ok((core_inputs, deferred_inputs))
     }
 
     /// Reduce shards proofs to a single shard proof using the recursion prover.
@@ -373,10 +560,26 @@ impl SP1Prover {
         proof: SP1CoreProof,
         deferred_proofs: Vec<ShardProof<InnerSC>>,
     ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
-        // Set the batch size for the reduction tree.
-        let batch_size = 2;
+        self.compress_with_progress(vk, proof, deferred_proofs, &ProgressReporter::default())
+    }
 
+    /// Reduce shards proofs to a single shard proof, and report each layer of the recursion tree
+    /// to `progress`.
+    pub fn compress_with_progress(
+        &self,
+        vk: &SP1VerifyingKey,
+        proof: SP1CoreProof,
+        deferred_proofs: Vec<ShardProof<InnerSC>>,
+        progress: &ProgressReporter,
+    ) -> Result<SP1ReduceProof<InnerSC>, SP1RecursionProverError> {
         let shard_proofs = &proof.proof.0;
+
+        // Set the batch size for the reduction tree.
+        let batch_size = self
+            .recursion_shape
+            .arity(shard_proofs.len() + deferred_proofs.len());
+        tracing::debug!("Recursion tree arity: {}", batch_size);
+
         // Get the leaf challenger.
         let mut leaf_challenger = self.core_machine.config().challenger();
         vk.vk.observe_into(&mut leaf_challenger);
@@ -392,7 +595,7 @@ impl SP1Prover {
             shard_proofs,
             &deferred_proofs,
             batch_size,
-        );
+        )?;
 
         let mut first_layer_proofs = Vec::new();
         let opts = SP1CoreOpts::recursion();
@@ -467,6 +670,11 @@ impl SP1Prover {
             .into_iter()
             .flat_map(|(proof, kind)| proof.shard_proofs.into_iter().map(move |p| (p, kind)))
             .collect::<Vec<_>>();
+        let mut layer = 0;
+        progress.report(ProvingStage::RecursionLayer {
+            layer,
+            num_proofs: reduce_proofs.len(),
+        });
 
         // Iterate over the recursive proof batches until there is one proof remaining.
         let mut is_complete;
@@ -504,6 +712,11 @@ impl SP1Prover {
                         .collect::<Vec<_>>()
                 })
                 .collect::<Vec<_>>();
+            layer += 1;
+            progress.report(ProvingStage::RecursionLayer {
+                layer,
+                num_proofs: reduce_proofs.len(),
+            });
 
             if reduce_proofs.len() == 1 {
                 break;
@@ -651,11 +864,13 @@ impl SP1Prover {
     ) -> PlonkBn254Proof {
         let vkey_digest = proof.sp1_vkey_digest_bn254();
         let commited_values_digest = proof.sp1_commited_values_digest_bn254();
+        let user_values = proof.sp1_user_values_bn254();
 
-        let mut witness = Witness::default();
+        let mut witness = Witness::<OuterConfig>::default();
         proof.proof.write(&mut witness);
         witness.write_commited_values_digest(commited_values_digest);
         witness.write_vkey_hash(vkey_digest);
+        witness.write_user_values(&user_values);
 
         let prover = PlonkBn254Prover::new();
         let proof = prover.prove(witness, build_dir.to_path_buf());
@@ -665,6 +880,39 @@ impl SP1Prover {
             &proof,
             &vkey_digest.as_canonical_biguint(),
             &commited_values_digest.as_canonical_biguint(),
+            &user_values.map(|value| value.as_canonical_biguint()),
+            build_dir,
+        );
+
+        proof
+    }
+
+    /// Wrap the STARK proven over a SNARK-friendly field into a Groth16 proof.
+    #[instrument(name = "wrap_groth16_bn254", level = "info", skip_all)]
+    pub fn wrap_groth16_bn254(
+        &self,
+        proof: SP1ReduceProof<OuterSC>,
+        build_dir: &Path,
+    ) -> Groth16Bn254Proof {
+        let vkey_digest = proof.sp1_vkey_digest_bn254();
+        let commited_values_digest = proof.sp1_commited_values_digest_bn254();
+        let user_values = proof.sp1_user_values_bn254();
+
+        let mut witness = Witness::<OuterConfig>::default();
+        proof.proof.write(&mut witness);
+        witness.write_commited_values_digest(commited_values_digest);
+        witness.write_vkey_hash(vkey_digest);
+        witness.write_user_values(&user_values);
+
+        let prover = Groth16Bn254Prover::new();
+        let proof = prover.prove(witness, build_dir.to_path_buf());
+
+        // Verify the proof.
+        prover.verify(
+            &proof,
+            &vkey_digest.as_canonical_biguint(),
+            &commited_values_digest.as_canonical_biguint(),
+            &user_values.map(|value| value.as_canonical_biguint()),
             build_dir,
         );
 
@@ -711,6 +959,38 @@ mod tests {
     /// Add `FRI_QUERIES`=1 to your environment for faster execution. Should only take a few minutes
     /// on a Mac M2. Note: This test always re-builds the plonk bn254 artifacts, so setting SP1_DEV is
     /// not needed.
+    #[test]
+    #[serial]
+    fn test_recursion_cache_dir() {
+        use key_cache::{
+            recursion_cache_dir, recursion_cache_version, RECURSION_CACHE_DIR_ENV,
+            RECURSION_CACHE_ENV,
+        };
+
+        std::env::set_var(RECURSION_CACHE_DIR_ENV, "/tmp/sp1-recursion");
+        assert_eq!(
+            recursion_cache_dir(),
+            Some(Path::new("/tmp/sp1-recursion").join(recursion_cache_version()))
+        );
+        std::env::set_var(RECURSION_CACHE_ENV, "false");
+        assert_eq!(recursion_cache_dir(), None);
+        std::env::remove_var(RECURSION_CACHE_ENV);
+        std::env::remove_var(RECURSION_CACHE_DIR_ENV);
+    }
+
+    #[test]
+    fn test_recursion_shape_arity() {
+        assert_eq!(RecursionShape::default().arity(100), 2);
+        assert_eq!(RecursionShape::Arity(4).arity(100), 4);
+        assert_eq!(RecursionShape::Arity(1).arity(100), 2);
+        assert_eq!(RecursionShape::SingleLayer.arity(100), 100);
+        assert_eq!(RecursionShape::SingleLayer.arity(1), 2);
+        assert_eq!(RecursionShape::Depth(2).arity(100), 10);
+        assert_eq!(RecursionShape::Depth(2).arity(101), 11);
+        assert_eq!(RecursionShape::Depth(3).arity(8), 2);
+        assert_eq!(RecursionShape::Depth(0).arity(5), 5);
+    }
+
     #[test]
     #[serial]
     fn test_e2e() -> Result<()> {
@@ -0,0 +1,158 @@
+//! Trusted setups supplied by the user to build the PLONK artifacts with.
+//!
+//! By default, the PLONK artifacts are downloaded, and were built with the Aztec Ignition SRS. A
+//! user with a stricter provenance requirement can instead build them with an SRS of their choice,
+//! such as one derived from the perpetual powers of tau, which is only used if it matches the
+//! SHA-256 checksum the user expects.
+
+use std::{
+    env,
+    fs::File,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::install::PLONK_BN254_ARTIFACTS_COMMIT;
+
+/// The environment variable with the path of the SRS to build the PLONK artifacts with.
+pub const PLONK_SRS_ENV: &str = "SP1_PLONK_SRS";
+
+/// The environment variable with the hex encoded SHA-256 checksum of the SRS of [PLONK_SRS_ENV].
+pub const PLONK_SRS_SHA256_ENV: &str = "SP1_PLONK_SRS_SHA256";
+
+#[derive(Error, Debug)]
+pub enum PlonkSrsError {
+    #[error("io error: {0}")]
+    IoError(#[from] io::Error),
+    #[error("invalid sha256 checksum {0}, expected 64 hex digits")]
+    InvalidChecksum(String),
+    #[error("{} is set without {}", PLONK_SRS_ENV, PLONK_SRS_SHA256_ENV)]
+    MissingChecksum,
+    #[error("the srs at {path} has sha256 checksum {found}, expected {expected}")]
+    ChecksumMismatch {
+        path: PathBuf,
+        expected: String,
+        found: String,
+    },
+}
+
+/// An SRS in the serialization of gnark's `kzg.SRS`, with its expected SHA-256 checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlonkSrs {
+    pub path: PathBuf,
+    pub sha256: [u8; 32],
+}
+
+impl PlonkSrs {
+    /// Creates an SRS from its path and its hex encoded SHA-256 checksum.
+    pub fn new(path: impl Into<PathBuf>, sha256: &str) -> Result<Self, PlonkSrsError> {
+        let bytes = hex::decode(sha256.trim_start_matches("0x"))
+            .map_err(|_| PlonkSrsError::InvalidChecksum(sha256.to_string()))?;
+        let sha256 = bytes
+            .try_into()
+            .map_err(|_| PlonkSrsError::InvalidChecksum(sha256.to_string()))?;
+        Ok(Self {
+            path: path.into(),
+            sha256,
+        })
+    }
+
+    /// Reads the SRS from [PLONK_SRS_ENV] and [PLONK_SRS_SHA256_ENV], if it is set.
+    pub fn from_env() -> Result<Option<Self>, PlonkSrsError> {
+        let Ok(path) = env::var(PLONK_SRS_ENV) else {
+            return Ok(None);
+        };
+        let sha256 = env::var(PLONK_SRS_SHA256_ENV).map_err(|_| PlonkSrsError::MissingChecksum)?;
+        Self::new(path, &sha256).map(Some)
+    }
+
+    /// Checks that the file of the SRS matches the checksum.
+    pub fn validate(&self) -> Result<(), PlonkSrsError> {
+        self.copy_validated(&mut io::sink())
+    }
+
+    /// Copies the SRS to `dest`, checking the checksum of the bytes which are copied, so that the
+    /// copy is valid even if the file changes meanwhile. The copy is removed if it is not valid.
+    pub fn copy_to(&self, dest: &Path) -> Result<(), PlonkSrsError> {
+        let result = self.copy_validated(&mut File::create(dest)?);
+        if result.is_err() {
+            std::fs::remove_file(dest)?;
+        }
+        result
+    }
+
+    /// The directory of the PLONK artifacts built with this SRS.
+    pub fn artifacts_dir(&self) -> PathBuf {
+        dirs::home_dir()
+            .unwrap()
+            .join(".sp1")
+            .join("circuits")
+            .join("plonk_bn254")
+            .join(format!(
+                "srs-{}-{}",
+                &hex::encode(self.sha256)[..16],
+                PLONK_BN254_ARTIFACTS_COMMIT
+            ))
+    }
+
+    fn copy_validated(&self, dest: &mut impl Write) -> Result<(), PlonkSrsError> {
+        let mut file = File::open(&self.path)?;
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0u8; 1 << 20];
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            dest.write_all(&buf[..n])?;
+        }
+        dest.flush()?;
+
+        let found: [u8; 32] = hasher.finalize().into();
+        if found != self.sha256 {
+            return Err(PlonkSrsError::ChecksumMismatch {
+                path: self.path.clone(),
+                expected: hex::encode(self.sha256),
+                found: hex::encode(found),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plonk_srs_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("srs.bin");
+        std::fs::write(&path, b"not really an srs").unwrap();
+        let checksum = hex::encode(Sha256::digest(b"not really an srs"));
+
+        let srs = PlonkSrs::new(&path, &checksum).unwrap();
+        srs.validate().unwrap();
+        let copy = dir.path().join("copy.bin");
+        srs.copy_to(&copy).unwrap();
+        assert_eq!(std::fs::read(&copy).unwrap(), b"not really an srs");
+
+        let wrong = PlonkSrs::new(&path, &hex::encode([0u8; 32])).unwrap();
+        assert!(matches!(
+            wrong.validate(),
+            Err(PlonkSrsError::ChecksumMismatch { .. })
+        ));
+        let copy = dir.path().join("wrong.bin");
+        assert!(wrong.copy_to(&copy).is_err());
+        assert!(!copy.exists());
+
+        assert!(matches!(
+            PlonkSrs::new(&path, "1234"),
+            Err(PlonkSrsError::InvalidChecksum(_))
+        ));
+    }
+}
@@ -1,24 +1,36 @@
 use std::borrow::Borrow;
-use std::{fs::File, path::Path};
+use std::path::Path;
 
 use anyhow::Result;
 use p3_baby_bear::BabyBear;
 use p3_bn254_fr::Bn254Fr;
 use p3_commit::{Pcs, TwoAdicMultiplicativeCoset};
 use p3_field::PrimeField;
-use p3_field::{AbstractField, PrimeField32, TwoAdicField};
+use p3_field::PrimeField32;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sp1_core::{
+    air::{PublicValuesError, USER_PV_NUM_WORDS},
     io::{SP1PublicValues, SP1Stdin},
-    stark::{ShardProof, StarkGenericConfig, StarkProvingKey, StarkVerifyingKey},
+    stark::{
+        MachineVerificationError, ShardProof, StarkGenericConfig, StarkProvingKey,
+        StarkVerifyingKey, VerifyingKeyDigest,
+    },
     utils::DIGEST_SIZE,
 };
-use sp1_primitives::poseidon2_hash;
+use sp1_recursion_core::stark::bls12_381::Bls12381Fr;
+use sp1_recursion_core::stark::config::BabyBearPoseidon2OuterBls12381;
 use sp1_recursion_core::{air::RecursionPublicValues, stark::config::BabyBearPoseidon2Outer};
+use sp1_recursion_gnark_ffi::groth16_bls12381::Groth16Bls12381Proof;
+use sp1_recursion_gnark_ffi::groth16_bn254::Groth16Bn254Proof;
 use sp1_recursion_gnark_ffi::plonk_bn254::PlonkBn254Proof;
 use thiserror::Error;
 
+use crate::encoding::{self, EncodingError, PROOF_MAGIC, SP1_CIRCUIT_VERSION, VKEY_MAGIC};
 use crate::utils::words_to_bytes_be;
+use crate::utils::{
+    babybear_bytes_to_bls12381, babybear_words_to_bls12381, babybear_words_to_bn254,
+    babybears_to_bls12381,
+};
 use crate::{utils::babybear_bytes_to_bn254, words_to_bytes};
 use crate::{utils::babybears_to_bn254, CoreSC, InnerSC};
 
@@ -35,6 +47,41 @@ pub struct SP1ProvingKey {
 #[derive(Clone, Serialize, Deserialize)]
 pub struct SP1VerifyingKey {
     pub vk: StarkVerifyingKey<CoreSC>,
+    /// The [SP1_CIRCUIT_VERSION] of the prover which computed the key.
+    pub circuit_version: String,
+}
+
+impl SP1VerifyingKey {
+    /// Checks that the key was computed by a prover of the circuits of this verifier.
+    pub fn check_circuit_version<SC: StarkGenericConfig>(
+        &self,
+    ) -> Result<(), MachineVerificationError<SC>> {
+        check_circuit_version(&self.circuit_version)
+    }
+
+    /// Saves the verifying key to a path, in the versioned encoding of [encoding].
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), EncodingError> {
+        encoding::save(VKEY_MAGIC, self, path)
+    }
+
+    /// Loads a verifying key saved by [SP1VerifyingKey::save].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, EncodingError> {
+        encoding::load(VKEY_MAGIC, path)
+    }
+}
+
+/// Checks that `circuit_version`, the circuit version recorded by a proof or a verifying key, is
+/// the [SP1_CIRCUIT_VERSION] of this verifier.
+pub fn check_circuit_version<SC: StarkGenericConfig>(
+    circuit_version: &str,
+) -> Result<(), MachineVerificationError<SC>> {
+    if circuit_version != SP1_CIRCUIT_VERSION {
+        return Err(MachineVerificationError::CircuitVersionMismatch {
+            expected: SP1_CIRCUIT_VERSION.to_string(),
+            found: circuit_version.to_string(),
+        });
+    }
+    Ok(())
 }
 
 /// A trait for keys that can be hashed into a digest.
@@ -79,30 +126,11 @@ where
     <SC::Pcs as Pcs<SC::Challenge, SC::Challenger>>::Commitment: AsRef<[BabyBear; DIGEST_SIZE]>,
 {
     fn hash_babybear(&self) -> [BabyBear; DIGEST_SIZE] {
-        let prep_domains = self.chip_information.iter().map(|(_, domain, _)| domain);
-        let num_inputs = DIGEST_SIZE + 1 + (4 * prep_domains.len());
-        let mut inputs = Vec::with_capacity(num_inputs);
-        inputs.extend(self.commit.as_ref());
-        inputs.push(self.pc_start);
-        for domain in prep_domains {
-            inputs.push(BabyBear::from_canonical_usize(domain.log_n));
-            let size = 1 << domain.log_n;
-            inputs.push(BabyBear::from_canonical_usize(size));
-            let g = BabyBear::two_adic_generator(domain.log_n);
-            inputs.push(domain.shift);
-            inputs.push(g);
-        }
-
-        poseidon2_hash(inputs)
+        self.digest_babybear()
     }
 
     fn hash_u32(&self) -> [u32; 8] {
-        self.hash_babybear()
-            .into_iter()
-            .map(|n| n.as_canonical_u32())
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap()
+        self.digest_u32()
     }
 }
 
@@ -117,14 +145,14 @@ pub struct SP1ProofWithMetadata<P: Clone> {
 }
 
 impl<P: Serialize + DeserializeOwned + Clone> SP1ProofWithMetadata<P> {
+    /// Saves the proof to a path, in the versioned encoding of [encoding].
     pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
-        bincode::serialize_into(File::create(path).expect("failed to open file"), self)
-            .map_err(Into::into)
+        encoding::save(PROOF_MAGIC, self, path).map_err(Into::into)
     }
 
+    /// Loads a proof saved by [SP1ProofWithMetadata::save].
     pub fn load(path: impl AsRef<Path>) -> Result<Self> {
-        bincode::deserialize_from(File::open(path).expect("failed to open file"))
-            .map_err(Into::into)
+        encoding::load(PROOF_MAGIC, path).map_err(Into::into)
     }
 }
 
@@ -149,6 +177,13 @@ pub type SP1PlonkBn254Proof = SP1ProofWithMetadata<SP1PlonkBn254ProofData>;
 /// An SP1 proof that has been wrapped into a single PLONK proof and can be verified onchain.
 pub type SP1PlonkProof = SP1ProofWithMetadata<SP1PlonkProofData>;
 
+/// An SP1 proof that has been wrapped into a single Groth16 proof and can be verified onchain.
+pub type SP1Groth16Bn254Proof = SP1ProofWithMetadata<SP1Groth16Bn254ProofData>;
+
+/// An SP1 proof that has been wrapped into a single Groth16 proof over BLS12-381 and can be
+/// verified onchain with the precompiles of BLS12-381.
+pub type SP1Groth16Bls12381Proof = SP1ProofWithMetadata<SP1Groth16Bls12381ProofData>;
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SP1CoreProofData(pub Vec<ShardProof<CoreSC>>);
 #[derive(Serialize, Deserialize, Clone)]
@@ -160,6 +195,12 @@ pub struct SP1PlonkBn254ProofData(pub PlonkBn254Proof);
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SP1PlonkProofData(pub PlonkBn254Proof);
 
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SP1Groth16Bn254ProofData(pub Groth16Bn254Proof);
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SP1Groth16Bls12381ProofData(pub Groth16Bls12381Proof);
+
 /// An intermediate proof which proves the execution over a range of shards.
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(bound(serialize = "ShardProof<SC>: Serialize"))]
@@ -168,6 +209,25 @@ pub struct SP1ReduceProof<SC: StarkGenericConfig> {
     pub proof: ShardProof<SC>,
 }
 
+impl SP1ReduceProof<InnerSC> {
+    /// The exit code the program halted with.
+    pub fn exit_code(&self) -> u32 {
+        let pv: &RecursionPublicValues<BabyBear> = self.proof.public_values.as_slice().borrow();
+        pv.exit_code.as_canonical_u32()
+    }
+
+    /// The public values of the reduced proof.
+    pub fn recursion_public_values(&self) -> RecursionPublicValues<BabyBear> {
+        *self.proof.public_values.as_slice().borrow()
+    }
+
+    /// The words the program committed as its user public values.
+    pub fn user_values(&self) -> [u32; USER_PV_NUM_WORDS] {
+        let pv: &RecursionPublicValues<BabyBear> = self.proof.public_values.as_slice().borrow();
+        pv.user_values.map(|word| word.to_u32())
+    }
+}
+
 impl SP1ReduceProof<BabyBearPoseidon2Outer> {
     pub fn sp1_vkey_digest_babybear(&self) -> [BabyBear; 8] {
         let proof = &self.proof;
@@ -188,6 +248,40 @@ impl SP1ReduceProof<BabyBearPoseidon2Outer> {
                 .unwrap();
         babybear_bytes_to_bn254(&committed_values_digest_bytes)
     }
+
+    pub fn sp1_user_values_bn254(&self) -> [Bn254Fr; USER_PV_NUM_WORDS] {
+        let proof = &self.proof;
+        let pv: &RecursionPublicValues<BabyBear> = proof.public_values.as_slice().borrow();
+        babybear_words_to_bn254(&pv.user_values)
+    }
+}
+
+impl SP1ReduceProof<BabyBearPoseidon2OuterBls12381> {
+    pub fn sp1_vkey_digest_babybear(&self) -> [BabyBear; 8] {
+        let proof = &self.proof;
+        let pv: &RecursionPublicValues<BabyBear> = proof.public_values.as_slice().borrow();
+        pv.sp1_vk_digest
+    }
+
+    pub fn sp1_vkey_digest_bls12381(&self) -> Bls12381Fr {
+        babybears_to_bls12381(&self.sp1_vkey_digest_babybear())
+    }
+
+    pub fn sp1_commited_values_digest_bls12381(&self) -> Bls12381Fr {
+        let proof = &self.proof;
+        let pv: &RecursionPublicValues<BabyBear> = proof.public_values.as_slice().borrow();
+        let committed_values_digest_bytes: [BabyBear; 32] =
+            words_to_bytes(&pv.committed_value_digest)
+                .try_into()
+                .unwrap();
+        babybear_bytes_to_bls12381(&committed_values_digest_bytes)
+    }
+
+    pub fn sp1_user_values_bls12381(&self) -> [Bls12381Fr; USER_PV_NUM_WORDS] {
+        let proof = &self.proof;
+        let pv: &RecursionPublicValues<BabyBear> = proof.public_values.as_slice().borrow();
+        babybear_words_to_bls12381(&pv.user_values)
+    }
 }
 
 /// A proof that can be reduced along with other proofs into one proof.
@@ -198,4 +292,49 @@ pub enum SP1ReduceProofWrapper {
 }
 
 #[derive(Error, Debug)]
-pub enum SP1RecursionProverError {}
+pub enum SP1RecursionProverError {
+    #[error("malformed public values: {0}")]
+    MalformedPublicValues(#[from] PublicValuesError),
+}
+
+/// The shape of the recursion tree which reduces the shard proofs and the deferred proofs of an
+/// execution into a single compressed proof.
+///
+/// Each proof of the tree verifies up to its fan-in proofs of the layer below, so a wider tree has
+/// fewer layers, and thus less sequential latency, for the cost of the memory and the time of the
+/// proofs of its wider recursion programs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecursionShape {
+    /// Each proof verifies up to `arity` proofs, and at least 2.
+    Arity(usize),
+    /// The fan-in is the smallest with which `depth` layers reduce all the proofs.
+    Depth(usize),
+    /// The first layer verifies all the shard proofs at once, and a second layer reduces its
+    /// proof with the proof of the deferred proofs.
+    SingleLayer,
+}
+
+impl RecursionShape {
+    /// The fan-in of the tree which reduces `num_proofs` proofs.
+    pub fn arity(&self, num_proofs: usize) -> usize {
+        match *self {
+            Self::Arity(arity) => arity.max(2),
+            Self::Depth(depth) => {
+                let depth = depth.clamp(1, u32::MAX as usize) as u32;
+                let mut arity = 2usize;
+                while arity.checked_pow(depth).is_some_and(|n| n < num_proofs) {
+                    arity += 1;
+                }
+                arity
+            }
+            Self::SingleLayer => num_proofs.max(2),
+        }
+    }
+}
+
+/// A binary tree.
+impl Default for RecursionShape {
+    fn default() -> Self {
+        Self::Arity(2)
+    }
+}
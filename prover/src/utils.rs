@@ -14,6 +14,7 @@ use sp1_core::{
     runtime::{Program, Runtime},
     utils::SP1CoreOpts,
 };
+use sp1_recursion_core::stark::bls12_381::Bls12381Fr;
 use tokio::{runtime, task::block_in_place};
 
 use crate::SP1CoreProofData;
@@ -31,7 +32,13 @@ pub fn get_cycles(elf: &[u8], stdin: &SP1Stdin) -> u64 {
     let program = Program::from(elf);
     let mut runtime = Runtime::new(program, SP1CoreOpts::default());
     runtime.write_vecs(&stdin.buffer);
-    runtime.dry_run();
+    runtime.state.random_seed = stdin.random_seed;
+    if let Some(stream) = stdin.stream.as_ref() {
+        runtime
+            .write_stream(stream)
+            .expect("the input stream was already read");
+    }
+    runtime.dry_run().expect("failed to execute program");
     runtime.state.global_clk
 }
 
@@ -49,33 +56,71 @@ pub fn words_to_bytes<T: Copy>(words: &[Word<T>]) -> Vec<T> {
 /// Convert 8 BabyBear words into a Bn254Fr field element by shifting by 31 bits each time. The last
 /// word becomes the least significant bits.
 pub fn babybears_to_bn254(digest: &[BabyBear; 8]) -> Bn254Fr {
-    let mut result = Bn254Fr::zero();
+    babybears_to_outer_field(digest)
+}
+
+/// Convert 32 BabyBear bytes into a Bn254Fr field element. The first byte's most significant 3 bits
+/// (which would become the 3 most significant bits) are truncated.
+pub fn babybear_bytes_to_bn254(bytes: &[BabyBear; 32]) -> Bn254Fr {
+    babybear_bytes_to_outer_field(bytes)
+}
+
+/// Convert 8 BabyBear words into a Bls12381Fr field element, with the same value as the element
+/// of [babybears_to_bn254].
+pub fn babybears_to_bls12381(digest: &[BabyBear; 8]) -> Bls12381Fr {
+    babybears_to_outer_field(digest)
+}
+
+/// Convert 32 BabyBear bytes into a Bls12381Fr field element, with the same value as the element
+/// of [babybear_bytes_to_bn254].
+pub fn babybear_bytes_to_bls12381(bytes: &[BabyBear; 32]) -> Bls12381Fr {
+    babybear_bytes_to_outer_field(bytes)
+}
+
+/// Convert BabyBear words into Bn254Fr field elements, one for the little-endian `u32` value of each
+/// word.
+pub fn babybear_words_to_bn254<const N: usize>(words: &[Word<BabyBear>; N]) -> [Bn254Fr; N] {
+    babybear_words_to_outer_field(words)
+}
+
+/// Convert BabyBear words into Bls12381Fr field elements, with the same values as the elements of
+/// [babybear_words_to_bn254].
+pub fn babybear_words_to_bls12381<const N: usize>(words: &[Word<BabyBear>; N]) -> [Bls12381Fr; N] {
+    babybear_words_to_outer_field(words)
+}
+
+fn babybears_to_outer_field<F: AbstractField>(digest: &[BabyBear; 8]) -> F {
+    let mut result = F::zero();
     for word in digest.iter() {
         // Since BabyBear prime is less than 2^31, we can shift by 31 bits each time and still be
         // within the Bn254Fr field, so we don't have to truncate the top 3 bits.
-        result *= Bn254Fr::from_canonical_u64(1 << 31);
-        result += Bn254Fr::from_canonical_u32(word.as_canonical_u32());
+        result *= F::from_canonical_u64(1 << 31);
+        result += F::from_canonical_u32(word.as_canonical_u32());
     }
     result
 }
 
-/// Convert 32 BabyBear bytes into a Bn254Fr field element. The first byte's most significant 3 bits
-/// (which would become the 3 most significant bits) are truncated.
-pub fn babybear_bytes_to_bn254(bytes: &[BabyBear; 32]) -> Bn254Fr {
-    let mut result = Bn254Fr::zero();
+fn babybear_bytes_to_outer_field<F: AbstractField>(bytes: &[BabyBear; 32]) -> F {
+    let mut result = F::zero();
     for (i, byte) in bytes.iter().enumerate() {
         debug_assert!(byte < &BabyBear::from_canonical_u32(256));
         if i == 0 {
             // 32 bytes is more than Bn254 prime, so we need to truncate the top 3 bits.
-            result = Bn254Fr::from_canonical_u32(byte.as_canonical_u32() & 0x1f);
+            result = F::from_canonical_u32(byte.as_canonical_u32() & 0x1f);
         } else {
-            result *= Bn254Fr::from_canonical_u32(256);
-            result += Bn254Fr::from_canonical_u32(byte.as_canonical_u32());
+            result *= F::from_canonical_u32(256);
+            result += F::from_canonical_u32(byte.as_canonical_u32());
         }
     }
     result
 }
 
+fn babybear_words_to_outer_field<F: AbstractField, const N: usize>(
+    words: &[Word<BabyBear>; N],
+) -> [F; N] {
+    words.map(|word| F::from_canonical_u32(word.to_u32()))
+}
+
 /// Utility method for converting u32 words to bytes in big endian.
 pub fn words_to_bytes_be(words: &[u32; 8]) -> [u8; 32] {
     let mut bytes = [0u8; 32];
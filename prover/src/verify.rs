@@ -5,13 +5,15 @@ use num_bigint::BigUint;
 use p3_baby_bear::BabyBear;
 use p3_field::{AbstractField, PrimeField};
 use sp1_core::{
-    air::PublicValues,
+    air::{PublicValues, Word},
     io::SP1PublicValues,
     stark::{MachineProof, MachineVerificationError, StarkGenericConfig},
     utils::BabyBearPoseidon2,
 };
 use sp1_recursion_core::{air::RecursionPublicValues, stark::config::BabyBearPoseidon2Outer};
-use sp1_recursion_gnark_ffi::{PlonkBn254Proof, PlonkBn254Prover};
+use sp1_recursion_gnark_ffi::{
+    Groth16Bn254Proof, Groth16Bn254Prover, PlonkBn254Proof, PlonkBn254Prover,
+};
 use thiserror::Error;
 
 use crate::{
@@ -38,6 +40,7 @@ impl SP1Prover {
         proof: &SP1CoreProofData,
         vk: &SP1VerifyingKey,
     ) -> Result<(), MachineVerificationError<CoreSC>> {
+        vk.check_circuit_version()?;
         let mut challenger = self.core_machine.config().challenger();
         let machine_proof = MachineProof {
             shard_proofs: proof.0.to_vec(),
@@ -46,8 +49,24 @@ impl SP1Prover {
             .verify(&vk.vk, &machine_proof, &mut challenger)?;
 
         // Verify shard transitions
+        let vk_digest = vk.hash_babybear();
         for (i, shard_proof) in proof.0.iter().enumerate() {
-            let public_values = PublicValues::from_vec(shard_proof.public_values.clone());
+            let public_values = PublicValues::<Word<BabyBear>, BabyBear>::try_from(
+                shard_proof.public_values.clone(),
+            )
+            .map_err(MachineVerificationError::MalformedPublicValues)?;
+            // Reject public values of another layout.
+            if !public_values.is_current_version() {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "unsupported public values version",
+                ));
+            }
+            // Every shard should be bound to the program being verified.
+            if public_values.vk_digest != vk_digest {
+                return Err(MachineVerificationError::InvalidPublicValues(
+                    "vk digest mismatch",
+                ));
+            }
             // Verify shard transitions
             if i == 0 {
                 // If it's the first shard, index should be 1.
@@ -63,8 +82,10 @@ impl SP1Prover {
                 }
             } else {
                 let prev_shard_proof = &proof.0[i - 1];
-                let prev_public_values =
-                    PublicValues::from_vec(prev_shard_proof.public_values.clone());
+                let prev_public_values = PublicValues::<Word<BabyBear>, BabyBear>::try_from(
+                    prev_shard_proof.public_values.clone(),
+                )
+                .map_err(MachineVerificationError::MalformedPublicValues)?;
                 // For non-first shards, the index should be the previous index + 1.
                 if public_values.shard != prev_public_values.shard + BabyBear::one() {
                     return Err(MachineVerificationError::InvalidPublicValues(
@@ -75,14 +96,15 @@ impl SP1Prover {
                 if public_values.start_pc != prev_public_values.next_pc {
                     return Err(MachineVerificationError::InvalidPublicValues("pc mismatch"));
                 }
-                // Digests and exit code should be the same in all shards.
+                // Digests, exit code and user values should be the same in all shards.
                 if public_values.committed_value_digest != prev_public_values.committed_value_digest
                     || public_values.deferred_proofs_digest
                         != prev_public_values.deferred_proofs_digest
                     || public_values.exit_code != prev_public_values.exit_code
+                    || public_values.user_values != prev_public_values.user_values
                 {
                     return Err(MachineVerificationError::InvalidPublicValues(
-                        "digest or exit code mismatch",
+                        "digest, exit code or user values mismatch",
                     ));
                 }
                 // The last shard should be halted. Halt is signaled with next_pc == 0.
@@ -109,6 +131,7 @@ impl SP1Prover {
         proof: &SP1ReduceProof<BabyBearPoseidon2>,
         vk: &SP1VerifyingKey,
     ) -> Result<(), MachineVerificationError<CoreSC>> {
+        vk.check_circuit_version()?;
         let mut challenger = self.compress_machine.config().challenger();
         let machine_proof = MachineProof {
             shard_proofs: vec![proof.proof.clone()],
@@ -152,6 +175,7 @@ impl SP1Prover {
         proof: &SP1ReduceProof<BabyBearPoseidon2>,
         vk: &SP1VerifyingKey,
     ) -> Result<(), MachineVerificationError<CoreSC>> {
+        vk.check_circuit_version()?;
         let mut challenger = self.shrink_machine.config().challenger();
         let machine_proof = MachineProof {
             shard_proofs: vec![proof.proof.clone()],
@@ -187,6 +211,7 @@ impl SP1Prover {
         proof: &SP1ReduceProof<BabyBearPoseidon2Outer>,
         vk: &SP1VerifyingKey,
     ) -> Result<(), MachineVerificationError<OuterSC>> {
+        vk.check_circuit_version()?;
         let mut challenger = self.wrap_machine.config().challenger();
         let machine_proof = MachineProof {
             shard_proofs: vec![proof.proof.clone()],
@@ -224,13 +249,56 @@ impl SP1Prover {
         public_values: &SP1PublicValues,
         build_dir: &Path,
     ) -> Result<()> {
+        vk.check_circuit_version::<CoreSC>()?;
         let prover = PlonkBn254Prover::new();
 
         let vkey_hash = BigUint::from_str(&proof.public_inputs[0])?;
         let committed_values_digest = BigUint::from_str(&proof.public_inputs[1])?;
+        let user_values = proof.public_inputs[2..]
+            .iter()
+            .map(|value| BigUint::from_str(value))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Verify the proof with the corresponding public inputs.
+        prover.verify(
+            proof,
+            &vkey_hash,
+            &committed_values_digest,
+            &user_values,
+            build_dir,
+        );
+
+        verify_plonk_bn254_public_inputs(vk, public_values, &proof.public_inputs)?;
+
+        Ok(())
+    }
+
+    /// Verify a Groth16 proof, and that its public inputs match the vkey and the public values.
+    pub fn verify_groth16_bn254(
+        &self,
+        proof: &Groth16Bn254Proof,
+        vk: &SP1VerifyingKey,
+        public_values: &SP1PublicValues,
+        build_dir: &Path,
+    ) -> Result<()> {
+        vk.check_circuit_version::<CoreSC>()?;
+        let prover = Groth16Bn254Prover::new();
+
+        let vkey_hash = BigUint::from_str(&proof.public_inputs[0])?;
+        let committed_values_digest = BigUint::from_str(&proof.public_inputs[1])?;
+        let user_values = proof.public_inputs[2..]
+            .iter()
+            .map(|value| BigUint::from_str(value))
+            .collect::<Result<Vec<_>, _>>()?;
 
         // Verify the proof with the corresponding public inputs.
-        prover.verify(proof, &vkey_hash, &committed_values_digest, build_dir);
+        prover.verify(
+            proof,
+            &vkey_hash,
+            &committed_values_digest,
+            &user_values,
+            build_dir,
+        );
 
         verify_plonk_bn254_public_inputs(vk, public_values, &proof.public_inputs)?;
 
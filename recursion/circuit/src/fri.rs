@@ -7,7 +7,6 @@ use p3_matrix::Dimensions;
 use p3_util::log2_strict_usize;
 use sp1_recursion_compiler::ir::{Builder, Config, Felt};
 use sp1_recursion_compiler::prelude::*;
-use sp1_recursion_core::stark::config::OuterChallengeMmcs;
 
 use crate::mmcs::verify_batch;
 use crate::types::FriChallenges;
@@ -18,9 +17,9 @@ use crate::types::TwoAdicPcsProofVariable;
 use crate::types::TwoAdicPcsRoundVariable;
 use crate::{challenger::MultiField32ChallengerVariable, DIGEST_SIZE};
 
-pub fn verify_shape_and_sample_challenges<C: Config>(
+pub fn verify_shape_and_sample_challenges<C: Config, M>(
     builder: &mut Builder<C>,
-    config: &FriConfig<OuterChallengeMmcs>,
+    config: &FriConfig<M>,
     proof: &FriProofVariable<C>,
     challenger: &mut MultiField32ChallengerVariable<C>,
 ) -> FriChallenges<C> {
@@ -47,9 +46,9 @@ pub fn verify_shape_and_sample_challenges<C: Config>(
     }
 }
 
-pub fn verify_two_adic_pcs<C: Config>(
+pub fn verify_two_adic_pcs<C: Config, M>(
     builder: &mut Builder<C>,
-    config: &FriConfig<OuterChallengeMmcs>,
+    config: &FriConfig<M>,
     proof: &TwoAdicPcsProofVariable<C>,
     challenger: &mut MultiField32ChallengerVariable<C>,
     rounds: Vec<TwoAdicPcsRoundVariable<C>>,
@@ -139,9 +138,9 @@ pub fn verify_two_adic_pcs<C: Config>(
     );
 }
 
-pub fn verify_challenges<C: Config>(
+pub fn verify_challenges<C: Config, M>(
     builder: &mut Builder<C>,
-    config: &FriConfig<OuterChallengeMmcs>,
+    config: &FriConfig<M>,
     proof: &FriProofVariable<C>,
     challenges: &FriChallenges<C>,
     reduced_openings: Vec<[Ext<C::F, C::EF>; 32]>,
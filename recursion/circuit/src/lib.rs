@@ -146,6 +146,24 @@ mod tests {
         PlonkBn254Prover::test::<OuterConfig>(constraints.clone(), witness);
     }
 
+    #[test]
+    fn test_commit_user_values() {
+        let mut builder = Builder::<OuterConfig>::default();
+        let user_values_bn254 = [7, 0, 0xdead_beef].map(Bn254Fr::from_canonical_u32);
+        for (i, user_value_bn254) in user_values_bn254.iter().enumerate() {
+            let user_value = builder.eval(*user_value_bn254);
+            builder.commit_user_value_circuit(i, user_value);
+        }
+
+        let mut backend = ConstraintCompiler::<OuterConfig>::default();
+        let constraints = backend.emit(builder.operations);
+
+        let mut witness = Witness::default();
+        witness.write_user_values(&user_values_bn254);
+
+        PlonkBn254Prover::test::<OuterConfig>(constraints.clone(), witness);
+    }
+
     #[test]
     #[should_panic]
     fn test_commit_vkey_fail() {
@@ -1,4 +1,4 @@
-//! An implementation of Poseidon2 over BN254.
+//! An implementation of Poseidon2 over the scalar field of BN254 or BLS12-381.
 
 use itertools::Itertools;
 use p3_field::AbstractField;
@@ -24,7 +24,7 @@ impl<C: Config> Poseidon2CircuitBuilder<C> for Builder<C> {
     }
 
     fn p2_hash(&mut self, input: &[Felt<C::F>]) -> OuterDigestVariable<C> {
-        assert!(C::N::bits() == p3_bn254_fr::Bn254Fr::bits());
+        assert!(C::N::bits() >= p3_bn254_fr::Bn254Fr::bits());
         assert!(C::F::bits() == p3_baby_bear::BabyBear::bits());
         let num_f_elms = C::N::bits() / C::F::bits();
         let mut state: [Var<C::N>; SPONGE_SIZE] = [
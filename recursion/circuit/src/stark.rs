@@ -1,27 +1,32 @@
+use std::array;
 use std::borrow::Borrow;
+use std::fmt::Debug;
 use std::marker::PhantomData;
 
 use crate::fri::verify_two_adic_pcs;
 use crate::types::OuterDigestVariable;
-use crate::utils::{babybear_bytes_to_bn254, babybears_to_bn254, words_to_bytes};
+use crate::utils::{
+    babybear_bytes_to_bn254, babybear_word_to_bn254, babybears_to_bn254, words_to_bytes,
+};
 use crate::witness::Witnessable;
 use p3_air::Air;
 use p3_baby_bear::BabyBear;
-use p3_bn254_fr::Bn254Fr;
 use p3_commit::TwoAdicMultiplicativeCoset;
 use p3_field::{AbstractField, TwoAdicField};
 use sp1_core::stark::{Com, ShardProof, PROOF_MAX_NUM_PVS};
 use sp1_core::{
-    air::MachineAir,
+    air::{MachineAir, USER_PV_NUM_WORDS},
     stark::{ShardCommitment, StarkGenericConfig, StarkMachine, StarkVerifyingKey},
 };
-use sp1_recursion_compiler::config::OuterConfig;
+use sp1_recursion_compiler::config::{OuterBls12381Config, OuterConfig};
 use sp1_recursion_compiler::constraints::{Constraint, ConstraintCompiler};
 use sp1_recursion_compiler::ir::{Builder, Config, Ext, Felt, Var};
 use sp1_recursion_compiler::ir::{Usize, Witness};
-use sp1_recursion_compiler::prelude::SymbolicVar;
 use sp1_recursion_core::air::RecursionPublicValues;
-use sp1_recursion_core::stark::config::{outer_fri_config, BabyBearPoseidon2Outer};
+use sp1_recursion_core::stark::config::{
+    BabyBearPoseidon2Outer, BabyBearPoseidon2OuterBls12381, OuterChallenge, OuterField,
+    OuterStarkConfig, OuterVal,
+};
 use sp1_recursion_core::stark::RecursionAirSkinnyDeg9;
 use sp1_recursion_program::commit::PolynomialSpaceVariable;
 use sp1_recursion_program::stark::RecursiveVerifierConstraintFolder;
@@ -58,8 +63,8 @@ where
         A: MachineAir<C::F> + for<'a> Air<RecursiveVerifierConstraintFolder<'a, C>>,
         C::F: TwoAdicField,
         C::EF: TwoAdicField,
-        Com<SC>: Into<[Bn254Fr; 1]>,
-        SymbolicVar<<C as Config>::N>: From<Bn254Fr>,
+        C::N: OuterField,
+        Com<SC>: Into<[C::N; 1]>,
     {
         let RecursionShardProofVariable {
             commitment,
@@ -184,7 +189,7 @@ where
         }
 
         let mut rounds = Vec::new();
-        let prep_commit_val: [Bn254Fr; 1] = vk.commit.clone().into();
+        let prep_commit_val: [C::N; 1] = vk.commit.clone().into();
         let prep_commit: OuterDigestVariable<C> = [builder.eval(prep_commit_val[0])];
         let prep_round = TwoAdicPcsRoundVariable {
             batch_commit: prep_commit,
@@ -206,7 +211,7 @@ where
         rounds.push(main_round);
         rounds.push(perm_round);
         rounds.push(quotient_round);
-        let config = outer_fri_config();
+        let config = C::N::outer_fri_config();
         verify_two_adic_pcs(builder, &config, &proof.opening_proof, challenger, rounds);
 
         for (i, sorted_chip) in sorted_chips.iter().enumerate() {
@@ -234,35 +239,57 @@ where
     }
 }
 
-type OuterSC = BabyBearPoseidon2Outer;
-type OuterF = <BabyBearPoseidon2Outer as StarkGenericConfig>::Val;
-type OuterC = OuterConfig;
-
+/// Builds the constraints of the circuit which verifies the wrap proofs of `wrap_vk` and which is
+/// wrapped by a SNARK over BN254.
 pub fn build_wrap_circuit(
-    wrap_vk: &StarkVerifyingKey<OuterSC>,
-    template_proof: ShardProof<OuterSC>,
+    wrap_vk: &StarkVerifyingKey<BabyBearPoseidon2Outer>,
+    template_proof: ShardProof<BabyBearPoseidon2Outer>,
 ) -> Vec<Constraint> {
-    let outer_config = OuterSC::new();
-    let outer_machine = RecursionAirSkinnyDeg9::<OuterF>::wrap_machine(outer_config);
+    build_outer_wrap_circuit::<OuterConfig>(wrap_vk, template_proof)
+}
+
+/// Builds the constraints of the circuit which verifies the wrap proofs of `wrap_vk` and which is
+/// wrapped by a SNARK over BLS12-381.
+pub fn build_wrap_circuit_bls12381(
+    wrap_vk: &StarkVerifyingKey<BabyBearPoseidon2OuterBls12381>,
+    template_proof: ShardProof<BabyBearPoseidon2OuterBls12381>,
+) -> Vec<Constraint> {
+    build_outer_wrap_circuit::<OuterBls12381Config>(wrap_vk, template_proof)
+}
 
-    let mut builder = Builder::<OuterConfig>::default();
+fn build_outer_wrap_circuit<C>(
+    wrap_vk: &StarkVerifyingKey<OuterStarkConfig<C::N>>,
+    template_proof: ShardProof<OuterStarkConfig<C::N>>,
+) -> Vec<Constraint>
+where
+    C: Config<F = OuterVal, EF = OuterChallenge> + Debug,
+    C::N: OuterField + Witnessable<C, WitnessVariable = Var<C::N>>,
+{
+    let outer_config = OuterStarkConfig::<C::N>::new();
+    let outer_machine = RecursionAirSkinnyDeg9::<OuterVal>::wrap_machine(outer_config);
+
+    let mut builder = Builder::<C>::default();
     let mut challenger = MultiField32ChallengerVariable::new(&mut builder);
 
-    let preprocessed_commit_val: [Bn254Fr; 1] = wrap_vk.commit.into();
-    let preprocessed_commit: OuterDigestVariable<OuterC> =
-        [builder.eval(preprocessed_commit_val[0])];
+    let preprocessed_commit_val: [C::N; 1] = wrap_vk.commit.into();
+    let preprocessed_commit: OuterDigestVariable<C> = [builder.eval(preprocessed_commit_val[0])];
     challenger.observe_commitment(&mut builder, preprocessed_commit);
     let pc_start = builder.eval(wrap_vk.pc_start);
     challenger.observe(&mut builder, pc_start);
 
-    let mut witness = Witness::default();
+    let mut witness = Witness::<C>::default();
     template_proof.write(&mut witness);
     let proof = template_proof.read(&mut builder);
 
-    let commited_values_digest = Bn254Fr::zero().read(&mut builder);
+    let commited_values_digest = C::N::zero().read(&mut builder);
     builder.commit_commited_values_digest_circuit(commited_values_digest);
-    let vkey_hash = Bn254Fr::zero().read(&mut builder);
+    let vkey_hash = C::N::zero().read(&mut builder);
     builder.commit_vkey_hash_circuit(vkey_hash);
+    let user_values: [Var<_>; USER_PV_NUM_WORDS] =
+        array::from_fn(|_| C::N::zero().read(&mut builder));
+    for (i, user_value) in user_values.iter().enumerate() {
+        builder.commit_user_value_circuit(i, *user_value);
+    }
 
     // Validate public values
     let mut pv_elements = Vec::new();
@@ -276,12 +303,12 @@ pub fn build_wrap_circuit(
     // fully accumulated.
     builder.assert_felt_eq(pv.is_complete, one_felt);
 
-    // Convert pv.sp1_vk_digest into Bn254
+    // Convert pv.sp1_vk_digest into a field element of the outer field
     let pv_vkey_hash = babybears_to_bn254(&mut builder, &pv.sp1_vk_digest);
     // Vkey hash must match the witnessed commited_values_digest that we are committing to.
     builder.assert_var_eq(pv_vkey_hash, vkey_hash);
 
-    // Convert pv.committed_value_digest into Bn254
+    // Convert pv.committed_value_digest into a field element of the outer field
     let pv_committed_values_digest_bytes: [Felt<_>; 32] =
         words_to_bytes(&pv.committed_value_digest)
             .try_into()
@@ -292,6 +319,12 @@ pub fn build_wrap_circuit(
     // Committed values digest must match the witnessed one that we are committing to.
     builder.assert_var_eq(pv_committed_values_digest, commited_values_digest);
 
+    // User values must match the witnessed ones that we are committing to.
+    for (word, user_value) in pv.user_values.iter().zip(user_values) {
+        let pv_user_value = babybear_word_to_bn254(&mut builder, word);
+        builder.assert_var_eq(pv_user_value, user_value);
+    }
+
     let chips = outer_machine
         .shard_chips_ordered(&template_proof.chip_ordering)
         .map(|chip| chip.name())
@@ -329,7 +362,7 @@ pub fn build_wrap_circuit(
     );
     challenger.observe_slice(&mut builder, pv_slice);
 
-    StarkVerifierCircuit::<OuterC, OuterSC>::verify_shard(
+    StarkVerifierCircuit::<C, OuterStarkConfig<C::N>>::verify_shard(
         &mut builder,
         wrap_vk,
         &outer_machine,
@@ -340,14 +373,14 @@ pub fn build_wrap_circuit(
         sorted_indices,
     );
 
-    let zero_ext: Ext<_, _> = builder.constant(<OuterConfig as Config>::EF::zero());
+    let zero_ext: Ext<_, _> = builder.constant(C::EF::zero());
     let cumulative_sum: Ext<_, _> = builder.eval(zero_ext);
     for chip in proof.opened_values.chips {
         builder.assign(cumulative_sum, cumulative_sum + chip.cumulative_sum);
     }
     builder.assert_ext_eq(cumulative_sum, zero_ext);
 
-    let mut backend = ConstraintCompiler::<OuterConfig>::default();
+    let mut backend = ConstraintCompiler::<C>::default();
     backend.emit(builder.operations)
 }
 
@@ -51,6 +51,22 @@ pub fn babybear_bytes_to_bn254<C: Config>(
     result
 }
 
+/// Convert a word of little-endian BabyBear bytes into a field element of the outer field with the
+/// value of the word as a u32.
+pub fn babybear_word_to_bn254<C: Config>(
+    builder: &mut Builder<C>,
+    word: &Word<Felt<C::F>>,
+) -> Var<C::N> {
+    let var_256: Var<_> = builder.constant(C::N::from_canonical_u32(256));
+    let result = builder.constant(C::N::zero());
+    for byte in word.0.iter().rev() {
+        let byte_bits = builder.num2bits_f_circuit(*byte);
+        let byte_var = builder.bits2num_v_circuit(&byte_bits);
+        builder.assign(result, result * var_256 + byte_var);
+    }
+    result
+}
+
 pub fn words_to_bytes<T: Copy>(words: &[Word<T>]) -> Vec<T> {
     words.iter().flat_map(|w| w.0).collect::<Vec<_>>()
 }
@@ -3,12 +3,13 @@ use sp1_core::stark::{
     AirOpenedValues, ChipOpenedValues, ShardCommitment, ShardOpenedValues, ShardProof,
 };
 use sp1_recursion_compiler::{
-    config::OuterConfig,
+    config::{OuterBls12381Config, OuterConfig},
     ir::{Builder, Config, Ext, Felt, Var, Witness},
 };
+use sp1_recursion_core::stark::bls12_381::Bls12381Fr;
 use sp1_recursion_core::stark::config::{
-    BabyBearPoseidon2Outer, OuterBatchOpening, OuterChallenge, OuterCommitPhaseStep, OuterDigest,
-    OuterFriProof, OuterPcsProof, OuterQueryProof, OuterVal,
+    OuterBatchOpening, OuterChallenge, OuterCommitPhaseStep, OuterDigest, OuterField,
+    OuterFriProof, OuterPcsProof, OuterQueryProof, OuterStarkConfig, OuterVal,
 };
 
 use crate::types::{
@@ -25,21 +26,31 @@ pub trait Witnessable<C: Config> {
     fn write(&self, witness: &mut Witness<C>);
 }
 
-type C = OuterConfig;
-
-impl Witnessable<C> for Bn254Fr {
+impl Witnessable<OuterConfig> for Bn254Fr {
     type WitnessVariable = Var<Bn254Fr>;
 
-    fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
+    fn read(&self, builder: &mut Builder<OuterConfig>) -> Self::WitnessVariable {
         builder.witness_var()
     }
 
-    fn write(&self, witness: &mut Witness<C>) {
+    fn write(&self, witness: &mut Witness<OuterConfig>) {
+        witness.vars.push(*self);
+    }
+}
+
+impl Witnessable<OuterBls12381Config> for Bls12381Fr {
+    type WitnessVariable = Var<Bls12381Fr>;
+
+    fn read(&self, builder: &mut Builder<OuterBls12381Config>) -> Self::WitnessVariable {
+        builder.witness_var()
+    }
+
+    fn write(&self, witness: &mut Witness<OuterBls12381Config>) {
         witness.vars.push(*self);
     }
 }
 
-impl Witnessable<C> for OuterVal {
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> Witnessable<C> for OuterVal {
     type WitnessVariable = Felt<OuterVal>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
@@ -51,7 +62,7 @@ impl Witnessable<C> for OuterVal {
     }
 }
 
-impl Witnessable<C> for OuterChallenge {
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> Witnessable<C> for OuterChallenge {
     type WitnessVariable = Ext<OuterVal, OuterChallenge>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
@@ -64,14 +75,15 @@ impl Witnessable<C> for OuterChallenge {
 }
 
 trait VectorWitnessable<C: Config>: Witnessable<C> {}
-impl VectorWitnessable<C> for Bn254Fr {}
-impl VectorWitnessable<C> for OuterVal {}
-impl VectorWitnessable<C> for OuterChallenge {}
-impl VectorWitnessable<C> for Vec<OuterVal> {}
-impl VectorWitnessable<C> for Vec<OuterChallenge> {}
-impl VectorWitnessable<C> for Vec<Vec<OuterVal>> {}
-
-impl<I: VectorWitnessable<C>> Witnessable<C> for Vec<I> {
+impl VectorWitnessable<OuterConfig> for Bn254Fr {}
+impl VectorWitnessable<OuterBls12381Config> for Bls12381Fr {}
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> VectorWitnessable<C> for OuterVal {}
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> VectorWitnessable<C> for OuterChallenge {}
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> VectorWitnessable<C> for Vec<OuterVal> {}
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> VectorWitnessable<C> for Vec<OuterChallenge> {}
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> VectorWitnessable<C> for Vec<Vec<OuterVal>> {}
+
+impl<C: Config, I: VectorWitnessable<C>> Witnessable<C> for Vec<I> {
     type WitnessVariable = Vec<I::WitnessVariable>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
@@ -83,7 +95,7 @@ impl<I: VectorWitnessable<C>> Witnessable<C> for Vec<I> {
     }
 }
 
-impl Witnessable<C> for OuterDigest {
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> Witnessable<C> for OuterDigest<C::N> {
     type WitnessVariable = OuterDigestVariable<C>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
@@ -94,9 +106,11 @@ impl Witnessable<C> for OuterDigest {
         witness.vars.push(self[0]);
     }
 }
-impl VectorWitnessable<C> for OuterDigest {}
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> VectorWitnessable<C> for OuterDigest<C::N> {}
 
-impl Witnessable<C> for ShardCommitment<OuterDigest> {
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> Witnessable<C>
+    for ShardCommitment<OuterDigest<C::N>>
+{
     type WitnessVariable = ShardCommitment<OuterDigestVariable<C>>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
@@ -117,7 +131,9 @@ impl Witnessable<C> for ShardCommitment<OuterDigest> {
     }
 }
 
-impl Witnessable<C> for AirOpenedValues<OuterChallenge> {
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> Witnessable<C>
+    for AirOpenedValues<OuterChallenge>
+{
     type WitnessVariable = AirOpenedValuesVariable<C>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
@@ -132,7 +148,9 @@ impl Witnessable<C> for AirOpenedValues<OuterChallenge> {
     }
 }
 
-impl Witnessable<C> for ChipOpenedValues<OuterChallenge> {
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> Witnessable<C>
+    for ChipOpenedValues<OuterChallenge>
+{
     type WitnessVariable = ChipOpenedValuesVariable<C>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
@@ -160,9 +178,14 @@ impl Witnessable<C> for ChipOpenedValues<OuterChallenge> {
         self.cumulative_sum.write(witness);
     }
 }
-impl VectorWitnessable<C> for ChipOpenedValues<OuterChallenge> {}
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> VectorWitnessable<C>
+    for ChipOpenedValues<OuterChallenge>
+{
+}
 
-impl Witnessable<C> for ShardOpenedValues<OuterChallenge> {
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> Witnessable<C>
+    for ShardOpenedValues<OuterChallenge>
+{
     type WitnessVariable = RecursionShardOpenedValuesVariable<C>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
@@ -175,7 +198,10 @@ impl Witnessable<C> for ShardOpenedValues<OuterChallenge> {
     }
 }
 
-impl Witnessable<C> for OuterBatchOpening {
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> Witnessable<C> for OuterBatchOpening<C::N>
+where
+    C::N: OuterField,
+{
     type WitnessVariable = BatchOpeningVariable<C>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
@@ -197,10 +223,21 @@ impl Witnessable<C> for OuterBatchOpening {
         self.opening_proof.write(witness);
     }
 }
-impl VectorWitnessable<C> for OuterBatchOpening {}
-impl VectorWitnessable<C> for Vec<OuterBatchOpening> {}
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> VectorWitnessable<C> for OuterBatchOpening<C::N> where
+    C::N: OuterField
+{
+}
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> VectorWitnessable<C>
+    for Vec<OuterBatchOpening<C::N>>
+where
+    C::N: OuterField,
+{
+}
 
-impl Witnessable<C> for OuterCommitPhaseStep {
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> Witnessable<C> for OuterCommitPhaseStep<C::N>
+where
+    C::N: OuterField,
+{
     type WitnessVariable = FriCommitPhaseProofStepVariable<C>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
@@ -217,9 +254,17 @@ impl Witnessable<C> for OuterCommitPhaseStep {
         self.opening_proof.write(witness);
     }
 }
-impl VectorWitnessable<C> for OuterCommitPhaseStep {}
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> VectorWitnessable<C>
+    for OuterCommitPhaseStep<C::N>
+where
+    C::N: OuterField,
+{
+}
 
-impl Witnessable<C> for OuterQueryProof {
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> Witnessable<C> for OuterQueryProof<C::N>
+where
+    C::N: OuterField,
+{
     type WitnessVariable = FriQueryProofVariable<C>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
@@ -233,9 +278,15 @@ impl Witnessable<C> for OuterQueryProof {
         self.commit_phase_openings.write(witness);
     }
 }
-impl VectorWitnessable<C> for OuterQueryProof {}
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> VectorWitnessable<C> for OuterQueryProof<C::N> where
+    C::N: OuterField
+{
+}
 
-impl Witnessable<C> for OuterFriProof {
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> Witnessable<C> for OuterFriProof<C::N>
+where
+    C::N: OuterField,
+{
     type WitnessVariable = FriProofVariable<C>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
@@ -243,7 +294,7 @@ impl Witnessable<C> for OuterFriProof {
             .commit_phase_commits
             .iter()
             .map(|commit| {
-                let commit: OuterDigest = (*commit).into();
+                let commit: OuterDigest<C::N> = (*commit).into();
                 commit.read(builder)
             })
             .collect();
@@ -260,7 +311,7 @@ impl Witnessable<C> for OuterFriProof {
 
     fn write(&self, witness: &mut Witness<C>) {
         self.commit_phase_commits.iter().for_each(|commit| {
-            let commit: OuterDigest = (*commit).into();
+            let commit: OuterDigest<C::N> = (*commit).into();
             commit.write(witness)
         });
         self.query_proofs.write(witness);
@@ -269,7 +320,10 @@ impl Witnessable<C> for OuterFriProof {
     }
 }
 
-impl Witnessable<C> for OuterPcsProof {
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> Witnessable<C> for OuterPcsProof<C::N>
+where
+    C::N: OuterField,
+{
     type WitnessVariable = TwoAdicPcsProofVariable<C>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
@@ -287,13 +341,17 @@ impl Witnessable<C> for OuterPcsProof {
     }
 }
 
-impl Witnessable<C> for ShardProof<BabyBearPoseidon2Outer> {
+impl<C: Config<F = OuterVal, EF = OuterChallenge>> Witnessable<C>
+    for ShardProof<OuterStarkConfig<C::N>>
+where
+    C::N: OuterField,
+{
     type WitnessVariable = RecursionShardProofVariable<C>;
 
     fn read(&self, builder: &mut Builder<C>) -> Self::WitnessVariable {
-        let main_commit: OuterDigest = self.commitment.main_commit.into();
-        let permutation_commit: OuterDigest = self.commitment.permutation_commit.into();
-        let quotient_commit: OuterDigest = self.commitment.quotient_commit.into();
+        let main_commit: OuterDigest<C::N> = self.commitment.main_commit.into();
+        let permutation_commit: OuterDigest<C::N> = self.commitment.permutation_commit.into();
+        let quotient_commit: OuterDigest<C::N> = self.commitment.quotient_commit.into();
         let commitment = ShardCommitment {
             main_commit: main_commit.read(builder),
             permutation_commit: permutation_commit.read(builder),
@@ -313,9 +371,9 @@ impl Witnessable<C> for ShardProof<BabyBearPoseidon2Outer> {
     }
 
     fn write(&self, witness: &mut Witness<C>) {
-        let main_commit: OuterDigest = self.commitment.main_commit.into();
-        let permutation_commit: OuterDigest = self.commitment.permutation_commit.into();
-        let quotient_commit: OuterDigest = self.commitment.quotient_commit.into();
+        let main_commit: OuterDigest<C::N> = self.commitment.main_commit.into();
+        let permutation_commit: OuterDigest<C::N> = self.commitment.permutation_commit.into();
+        let quotient_commit: OuterDigest<C::N> = self.commitment.quotient_commit.into();
         main_commit.write(witness);
         permutation_commit.write(witness);
         quotient_commit.write(witness);
@@ -372,6 +430,7 @@ mod tests {
                 exts: vec![OuterChallenge::one(), OuterChallenge::two()],
                 vkey_hash: Bn254Fr::one(),
                 commited_values_digest: Bn254Fr::one(),
+                user_values: vec![],
             },
         );
     }
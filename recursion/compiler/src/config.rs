@@ -2,6 +2,7 @@ use p3_baby_bear::BabyBear;
 use p3_bn254_fr::Bn254Fr;
 use p3_field::extension::BinomialExtensionField;
 use sp1_core::utils::{InnerChallenge, InnerVal};
+use sp1_recursion_core::stark::bls12_381::Bls12381Fr;
 
 use crate::{asm::AsmConfig, prelude::Config};
 
@@ -15,3 +16,13 @@ impl Config for OuterConfig {
     type F = BabyBear;
     type EF = BinomialExtensionField<BabyBear, 4>;
 }
+
+/// The config of the outer recursion circuit over the scalar field of BLS12-381.
+#[derive(Clone, Default, Debug)]
+pub struct OuterBls12381Config;
+
+impl Config for OuterBls12381Config {
+    type N = Bls12381Fr;
+    type F = BabyBear;
+    type EF = BinomialExtensionField<BabyBear, 4>;
+}
@@ -344,6 +344,10 @@ impl<C: Config + Debug> ConstraintCompiler<C> {
                     opcode: ConstraintOpcode::CommitCommitedValuesDigest,
                     args: vec![vec![a.id()]],
                 }),
+                DslIr::CircuitCommitUserValue(a, b) => constraints.push(Constraint {
+                    opcode: ConstraintOpcode::CommitUserValue,
+                    args: vec![vec![a.id()], vec![b.to_string()]],
+                }),
                 DslIr::CircuitFelts2Ext(a, b) => constraints.push(Constraint {
                     opcode: ConstraintOpcode::CircuitFelts2Ext,
                     args: vec![
@@ -45,5 +45,6 @@ pub enum ConstraintOpcode {
     WitnessE,
     CommitVkeyHash,
     CommitCommitedValuesDigest,
+    CommitUserValue,
     CircuitFelts2Ext,
 }
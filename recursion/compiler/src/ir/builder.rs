@@ -479,6 +479,11 @@ impl<C: Config> Builder<C> {
             .push(DslIr::CircuitCommitCommitedValuesDigest(var));
     }
 
+    pub fn commit_user_value_circuit(&mut self, index: usize, var: Var<C::N>) {
+        self.operations
+            .push(DslIr::CircuitCommitUserValue(var, index));
+    }
+
     pub fn cycle_tracker(&mut self, name: &str) {
         self.operations.push(DslIr::CycleTracker(name.to_string()));
     }
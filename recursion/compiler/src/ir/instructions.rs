@@ -248,6 +248,9 @@ pub enum DslIr<C: Config> {
     /// Asserts that the inputted var is equal the circuit's commited values digest public input. Should
     /// only be used when target is a gnark circuit.
     CircuitCommitCommitedValuesDigest(Var<C::N>),
+    /// Asserts that the inputted var is equal the circuit's user value public input at the given
+    /// index. Should only be used when target is a gnark circuit.
+    CircuitCommitUserValue(Var<C::N>, usize),
 
     // FRI specific instructions.
     /// Executes a FRI fold operation. 1st field is the size of the fri fold input array.  2nd field
@@ -49,6 +49,7 @@ pub struct Witness<C: Config> {
     pub exts: Vec<C::EF>,
     pub vkey_hash: C::N,
     pub commited_values_digest: C::N,
+    pub user_values: Vec<C::N>,
 }
 
 impl<C: Config> Witness<C> {
@@ -65,6 +66,11 @@ impl<C: Config> Witness<C> {
         self.vars.push(commited_values_digest);
         self.commited_values_digest = commited_values_digest
     }
+
+    pub fn write_user_values(&mut self, user_values: &[C::N]) {
+        self.vars.extend_from_slice(user_values);
+        self.user_values = user_values.to_vec();
+    }
 }
 
 impl<N: Field> Usize<N> {
@@ -6,7 +6,7 @@ use p3_field::PrimeField32;
 use p3_symmetric::CryptographicPermutation;
 use serde::{Deserialize, Serialize};
 use sp1_core::{
-    air::{Word, POSEIDON_NUM_WORDS},
+    air::{Word, POSEIDON_NUM_WORDS, USER_PV_NUM_WORDS},
     stark::PROOF_MAX_NUM_PVS,
     utils::indices_arr,
 };
@@ -68,6 +68,9 @@ pub struct RecursionPublicValues<T> {
     /// The hash of all the bytes that the program has written to public values.
     pub committed_value_digest: [Word<T>; PV_DIGEST_NUM_WORDS],
 
+    /// The words that the program has set as public values of its own.
+    pub user_values: [Word<T>; USER_PV_NUM_WORDS],
+
     /// The hash of all deferred proofs that have been witnessed in the VM.
     pub deferred_proofs_digest: [T; POSEIDON_NUM_WORDS],
 
@@ -1,62 +1,17 @@
 #![allow(clippy::needless_range_loop)]
 
 use crate::poseidon2_wide::external::WIDTH;
-use p3_baby_bear::{MONTY_INVERSE, POSEIDON2_INTERNAL_MATRIX_DIAG_16_BABYBEAR_MONTY};
-use p3_field::AbstractField;
-use p3_field::PrimeField32;
 
 mod columns;
 pub mod external;
 
 pub use external::Poseidon2WideChip;
-use p3_poseidon2::matmul_internal;
+pub use sp1_core::syscall::precompiles::poseidon2::apply_m_4;
+pub(crate) use sp1_core::syscall::precompiles::poseidon2::{
+    external_linear_layer, internal_linear_layer,
+};
 
 #[derive(Debug, Clone)]
 pub struct Poseidon2Event<F> {
     pub input: [F; WIDTH],
 }
-
-pub fn apply_m_4<AF>(x: &mut [AF])
-where
-    AF: AbstractField,
-{
-    let t01 = x[0].clone() + x[1].clone();
-    let t23 = x[2].clone() + x[3].clone();
-    let t0123 = t01.clone() + t23.clone();
-    let t01123 = t0123.clone() + x[1].clone();
-    let t01233 = t0123.clone() + x[3].clone();
-    // The order here is important. Need to overwrite x[0] and x[2] after x[1] and x[3].
-    x[3] = t01233.clone() + x[0].double(); // 3*x[0] + x[1] + x[2] + 2*x[3]
-    x[1] = t01123.clone() + x[2].double(); // x[0] + 2*x[1] + 3*x[2] + x[3]
-    x[0] = t01123 + t01; // 2*x[0] + 3*x[1] + x[2] + x[3]
-    x[2] = t01233 + t23; // x[0] + x[1] + 2*x[2] + 3*x[3]
-}
-
-pub(crate) fn external_linear_layer<AF: AbstractField>(state: &mut [AF; WIDTH]) {
-    for j in (0..WIDTH).step_by(4) {
-        apply_m_4(&mut state[j..j + 4]);
-    }
-    let sums: [AF; 4] = core::array::from_fn(|k| {
-        (0..WIDTH)
-            .step_by(4)
-            .map(|j| state[j + k].clone())
-            .sum::<AF>()
-    });
-
-    for j in 0..WIDTH {
-        state[j] += sums[j % 4].clone();
-    }
-}
-
-pub(crate) fn internal_linear_layer<F: AbstractField>(state: &mut [F; WIDTH]) {
-    let matmul_constants: [<F as AbstractField>::F; WIDTH] =
-        POSEIDON2_INTERNAL_MATRIX_DIAG_16_BABYBEAR_MONTY
-            .iter()
-            .map(|x| <F as AbstractField>::F::from_wrapped_u32(x.as_canonical_u32()))
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap();
-    matmul_internal(state, matmul_constants);
-    let monty_inverse = F::from_wrapped_u32(MONTY_INVERSE.as_canonical_u32());
-    state.iter_mut().for_each(|i| *i *= monty_inverse.clone());
-}
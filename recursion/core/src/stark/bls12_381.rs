@@ -0,0 +1,299 @@
+//! The scalar field of BLS12-381, over which the outer recursion can be hashed instead of the
+//! scalar field of BN254 so that the wrap proof can be verified by a SNARK over BLS12-381.
+
+use core::fmt::{self, Debug, Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter::{Product, Sum};
+use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use ff::{Field as FFField, PrimeField as FFPrimeField};
+use num_bigint::BigUint;
+use p3_field::{AbstractField, Field, Packable, PrimeField};
+use p3_poseidon2::{matmul_internal, DiffusionPermutation};
+use p3_symmetric::Permutation;
+use rand::distributions::{Distribution, Standard};
+use rand::Rng;
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[derive(ff::PrimeField)]
+#[PrimeFieldModulus = "52435875175126190479447740508185965837690552500527637822603658699938581184513"]
+#[PrimeFieldGenerator = "7"]
+#[PrimeFieldReprEndianness = "little"]
+pub struct FFBls12381Fr([u64; 4]);
+
+/// The scalar field of BLS12-381, `F_r` where
+/// `r = 52435875175126190479447740508185965837690552500527637822603658699938581184513`.
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
+pub struct Bls12381Fr {
+    pub value: FFBls12381Fr,
+}
+
+impl Bls12381Fr {
+    pub(crate) const fn new(value: FFBls12381Fr) -> Self {
+        Self { value }
+    }
+}
+
+impl Serialize for Bls12381Fr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.value.to_repr().as_ref())
+    }
+}
+
+impl<'de> Deserialize<'de> for Bls12381Fr {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let bytes: Vec<u8> = Deserialize::deserialize(d)?;
+        let mut repr = <FFBls12381Fr as FFPrimeField>::Repr::default();
+        if bytes.len() != repr.as_ref().len() {
+            return Err(serde::de::Error::custom("invalid field element length"));
+        }
+        repr.as_mut().copy_from_slice(&bytes);
+        Option::<FFBls12381Fr>::from(FFBls12381Fr::from_repr(repr))
+            .map(Self::new)
+            .ok_or_else(|| serde::de::Error::custom("invalid field element"))
+    }
+}
+
+impl Packable for Bls12381Fr {}
+
+impl Hash for Bls12381Fr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for byte in self.value.to_repr().as_ref().iter() {
+            state.write_u8(*byte);
+        }
+    }
+}
+
+impl Ord for Bls12381Fr {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        // The representation is little-endian, so the bytes are compared from the last one.
+        let lhs = self.value.to_repr();
+        let rhs = other.value.to_repr();
+        lhs.as_ref().iter().rev().cmp(rhs.as_ref().iter().rev())
+    }
+}
+
+impl PartialOrd for Bls12381Fr {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Bls12381Fr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        <FFBls12381Fr as Debug>::fmt(&self.value, f)
+    }
+}
+
+impl Debug for Bls12381Fr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.value, f)
+    }
+}
+
+impl AbstractField for Bls12381Fr {
+    type F = Self;
+
+    fn zero() -> Self {
+        Self::new(FFBls12381Fr::ZERO)
+    }
+
+    fn one() -> Self {
+        Self::new(FFBls12381Fr::ONE)
+    }
+
+    fn two() -> Self {
+        Self::new(FFBls12381Fr::from(2u64))
+    }
+
+    fn neg_one() -> Self {
+        Self::new(FFBls12381Fr::ZERO - FFBls12381Fr::ONE)
+    }
+
+    #[inline]
+    fn from_f(f: Self::F) -> Self {
+        f
+    }
+
+    fn from_bool(b: bool) -> Self {
+        Self::new(FFBls12381Fr::from(b as u64))
+    }
+
+    fn from_canonical_u8(n: u8) -> Self {
+        Self::new(FFBls12381Fr::from(n as u64))
+    }
+
+    fn from_canonical_u16(n: u16) -> Self {
+        Self::new(FFBls12381Fr::from(n as u64))
+    }
+
+    fn from_canonical_u32(n: u32) -> Self {
+        Self::new(FFBls12381Fr::from(n as u64))
+    }
+
+    fn from_canonical_u64(n: u64) -> Self {
+        Self::new(FFBls12381Fr::from(n))
+    }
+
+    fn from_canonical_usize(n: usize) -> Self {
+        Self::new(FFBls12381Fr::from(n as u64))
+    }
+
+    fn from_wrapped_u32(n: u32) -> Self {
+        Self::new(FFBls12381Fr::from(n as u64))
+    }
+
+    fn from_wrapped_u64(n: u64) -> Self {
+        Self::new(FFBls12381Fr::from(n))
+    }
+
+    fn generator() -> Self {
+        Self::new(FFBls12381Fr::MULTIPLICATIVE_GENERATOR)
+    }
+}
+
+impl Field for Bls12381Fr {
+    type Packing = Self;
+
+    fn is_zero(&self) -> bool {
+        self.value.is_zero().into()
+    }
+
+    fn try_inverse(&self) -> Option<Self> {
+        Option::<FFBls12381Fr>::from(self.value.invert()).map(Self::new)
+    }
+
+    fn order() -> BigUint {
+        let modulus = FFBls12381Fr::MODULUS.trim_start_matches("0x");
+        BigUint::parse_bytes(modulus.as_bytes(), 16).unwrap()
+    }
+}
+
+impl PrimeField for Bls12381Fr {
+    fn as_canonical_biguint(&self) -> BigUint {
+        BigUint::from_bytes_le(self.value.to_repr().as_ref())
+    }
+}
+
+impl Add for Bls12381Fr {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.value + rhs.value)
+    }
+}
+
+impl AddAssign for Bls12381Fr {
+    fn add_assign(&mut self, rhs: Self) {
+        self.value += rhs.value;
+    }
+}
+
+impl Sum for Bls12381Fr {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|x, y| x + y).unwrap_or(Self::zero())
+    }
+}
+
+impl Sub for Bls12381Fr {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.value - rhs.value)
+    }
+}
+
+impl SubAssign for Bls12381Fr {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.value -= rhs.value;
+    }
+}
+
+impl Neg for Bls12381Fr {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.value)
+    }
+}
+
+impl Mul for Bls12381Fr {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(self.value * rhs.value)
+    }
+}
+
+impl MulAssign for Bls12381Fr {
+    fn mul_assign(&mut self, rhs: Self) {
+        self.value *= rhs.value;
+    }
+}
+
+impl Product for Bls12381Fr {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|x, y| x * y).unwrap_or(Self::one())
+    }
+}
+
+impl Div for Bls12381Fr {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.inverse()
+    }
+}
+
+impl Distribution<Bls12381Fr> for Standard {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Bls12381Fr {
+        Bls12381Fr::new(FFBls12381Fr::random(rng))
+    }
+}
+
+/// The internal linear layer of Poseidon2 of width 3 over the scalar field of BLS12-381, whose
+/// diagonal minus the identity is `[1, 1, 2]`.
+#[derive(Debug, Clone, Default)]
+pub struct DiffusionMatrixBls12381;
+
+impl<AF: AbstractField<F = Bls12381Fr>> Permutation<[AF; 3]> for DiffusionMatrixBls12381 {
+    fn permute_mut(&self, state: &mut [AF; 3]) {
+        let mat_internal_diag_m_1 = [Bls12381Fr::one(), Bls12381Fr::one(), Bls12381Fr::two()];
+        matmul_internal::<Bls12381Fr, AF, 3>(state, mat_internal_diag_m_1);
+    }
+}
+
+impl<AF: AbstractField<F = Bls12381Fr>> DiffusionPermutation<AF, 3> for DiffusionMatrixBls12381 {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bls12381_fr() {
+        let order = Bls12381Fr::order();
+        assert_eq!(
+            order.to_string(),
+            "52435875175126190479447740508185965837690552500527637822603658699938581184513"
+        );
+        assert_eq!(Bls12381Fr::bits(), 255);
+
+        let a = Bls12381Fr::from_canonical_u32(1 << 31);
+        let b = Bls12381Fr::from_canonical_u32(3);
+        assert_eq!((a * b / b), a);
+        assert_eq!(a - a, Bls12381Fr::zero());
+        assert_eq!(
+            Bls12381Fr::neg_one() + Bls12381Fr::one(),
+            Bls12381Fr::zero()
+        );
+        assert_eq!(
+            Bls12381Fr::neg_one().as_canonical_biguint(),
+            order - BigUint::from(1u32)
+        );
+        assert!(a > b);
+
+        let bytes = bincode::serialize(&a).unwrap();
+        assert_eq!(bincode::deserialize::<Bls12381Fr>(&bytes).unwrap(), a);
+    }
+}
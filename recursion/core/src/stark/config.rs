@@ -4,147 +4,209 @@ use p3_challenger::MultiField32Challenger;
 use p3_commit::ExtensionMmcs;
 use p3_dft::Radix2DitParallel;
 use p3_field::extension::BinomialExtensionField;
+use p3_field::PrimeField;
 use p3_fri::BatchOpening;
 use p3_fri::CommitPhaseProofStep;
 use p3_fri::QueryProof;
 use p3_fri::{FriConfig, FriProof, TwoAdicFriPcs, TwoAdicFriPcsProof};
 use p3_merkle_tree::FieldMerkleTreeMmcs;
 use p3_poseidon2::Poseidon2;
-use p3_poseidon2::Poseidon2ExternalMatrixGeneral;
+use p3_poseidon2::{DiffusionPermutation, Poseidon2ExternalMatrixGeneral};
 use p3_symmetric::Hash;
 use p3_symmetric::{MultiField32PaddingFreeSponge, TruncatedPermutation};
 use serde::Deserialize;
 use serde::Serialize;
-use sp1_core::stark::StarkGenericConfig;
+use sp1_core::stark::{FriParameters, MerkleHash, StarkGenericConfig};
+use sp1_core::utils::fri_parameters;
 
-use super::poseidon2::bn254_poseidon2_rc3;
+use super::bls12_381::{Bls12381Fr, DiffusionMatrixBls12381};
+use super::poseidon2::{bls12381_poseidon2_rc3, bn254_poseidon2_rc3};
 use super::utils;
 
 /// A configuration for outer recursion.
+///
+/// The outer recursion hashes over a field `N` which is the scalar field of the curve of the SNARK
+/// that wraps it, the scalar field of BN254 by default.
 pub type OuterVal = BabyBear;
 pub type OuterChallenge = BinomialExtensionField<OuterVal, 4>;
-pub type OuterPerm = Poseidon2<Bn254Fr, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBN254, 3, 5>;
-pub type OuterHash = MultiField32PaddingFreeSponge<OuterVal, Bn254Fr, OuterPerm, 3, 16, 1>;
-pub type OuterDigestHash = Hash<Bn254Fr, Bn254Fr, 1>;
-pub type OuterDigest = [Bn254Fr; 1];
-pub type OuterCompress = TruncatedPermutation<OuterPerm, 2, 1, 3>;
-pub type OuterValMmcs = FieldMerkleTreeMmcs<BabyBear, Bn254Fr, OuterHash, OuterCompress, 1>;
-pub type OuterChallengeMmcs = ExtensionMmcs<OuterVal, OuterChallenge, OuterValMmcs>;
+pub type OuterPerm<N = Bn254Fr> =
+    Poseidon2<N, Poseidon2ExternalMatrixGeneral, <N as OuterField>::Diffusion, 3, 5>;
+pub type OuterHash<N = Bn254Fr> =
+    MultiField32PaddingFreeSponge<OuterVal, N, OuterPerm<N>, 3, 16, 1>;
+pub type OuterDigestHash<N = Bn254Fr> = Hash<N, N, 1>;
+pub type OuterDigest<N = Bn254Fr> = [N; 1];
+pub type OuterCompress<N = Bn254Fr> = TruncatedPermutation<OuterPerm<N>, 2, 1, 3>;
+pub type OuterValMmcs<N = Bn254Fr> =
+    FieldMerkleTreeMmcs<BabyBear, N, OuterHash<N>, OuterCompress<N>, 1>;
+pub type OuterChallengeMmcs<N = Bn254Fr> = ExtensionMmcs<OuterVal, OuterChallenge, OuterValMmcs<N>>;
 pub type OuterDft = Radix2DitParallel;
-pub type OuterChallenger = MultiField32Challenger<OuterVal, Bn254Fr, OuterPerm, 3>;
-pub type OuterPcs = TwoAdicFriPcs<OuterVal, OuterDft, OuterValMmcs, OuterChallengeMmcs>;
+pub type OuterChallenger<N = Bn254Fr> = MultiField32Challenger<OuterVal, N, OuterPerm<N>, 3>;
+pub type OuterPcs<N = Bn254Fr> =
+    TwoAdicFriPcs<OuterVal, OuterDft, OuterValMmcs<N>, OuterChallengeMmcs<N>>;
 
-pub type OuterQueryProof = QueryProof<OuterChallenge, OuterChallengeMmcs>;
-pub type OuterCommitPhaseStep = CommitPhaseProofStep<OuterChallenge, OuterChallengeMmcs>;
-pub type OuterFriProof = FriProof<OuterChallenge, OuterChallengeMmcs, OuterVal>;
-pub type OuterBatchOpening = BatchOpening<OuterVal, OuterValMmcs>;
-pub type OuterPcsProof =
-    TwoAdicFriPcsProof<OuterVal, OuterChallenge, OuterValMmcs, OuterChallengeMmcs>;
+pub type OuterQueryProof<N = Bn254Fr> = QueryProof<OuterChallenge, OuterChallengeMmcs<N>>;
+pub type OuterCommitPhaseStep<N = Bn254Fr> =
+    CommitPhaseProofStep<OuterChallenge, OuterChallengeMmcs<N>>;
+pub type OuterFriProof<N = Bn254Fr> = FriProof<OuterChallenge, OuterChallengeMmcs<N>, OuterVal>;
+pub type OuterBatchOpening<N = Bn254Fr> = BatchOpening<OuterVal, OuterValMmcs<N>>;
+pub type OuterPcsProof<N = Bn254Fr> =
+    TwoAdicFriPcsProof<OuterVal, OuterChallenge, OuterValMmcs<N>, OuterChallengeMmcs<N>>;
+
+/// A field over which the outer recursion can be hashed, with its Poseidon2 permutation of width 3.
+pub trait OuterField: PrimeField {
+    /// The internal linear layer of the permutation.
+    type Diffusion: DiffusionPermutation<Self, 3> + Clone + Default + Send + Sync;
+
+    /// The round constants of the permutation, in the order of the rounds.
+    fn poseidon2_round_constants() -> Vec<[Self; 3]>;
+
+    /// The permutation for outer recursion.
+    fn outer_perm() -> OuterPerm<Self> {
+        const ROUNDS_F: usize = 8;
+        let mut round_constants = Self::poseidon2_round_constants();
+        let rounds_p = round_constants.len() - ROUNDS_F;
+        let internal_start = ROUNDS_F / 2;
+        let internal_end = (ROUNDS_F / 2) + rounds_p;
+        let internal_round_constants = round_constants
+            .drain(internal_start..internal_end)
+            .map(|vec| vec[0])
+            .collect::<Vec<_>>();
+        let external_round_constants = round_constants;
+        OuterPerm::<Self>::new(
+            ROUNDS_F,
+            external_round_constants,
+            Poseidon2ExternalMatrixGeneral,
+            rounds_p,
+            internal_round_constants,
+            Self::Diffusion::default(),
+        )
+    }
+
+    /// The FRI config for outer recursion.
+    fn outer_fri_config() -> FriConfig<OuterChallengeMmcs<Self>> {
+        let perm = Self::outer_perm();
+        let hash = OuterHash::<Self>::new(perm.clone()).unwrap();
+        let compress = OuterCompress::<Self>::new(perm.clone());
+        let challenge_mmcs =
+            OuterChallengeMmcs::<Self>::new(OuterValMmcs::<Self>::new(hash, compress));
+        let num_queries = if utils::sp1_dev_mode() {
+            1
+        } else {
+            match std::env::var("FRI_QUERIES") {
+                Ok(value) => value.parse().unwrap(),
+                Err(_) => 25,
+            }
+        };
+        FriConfig {
+            log_blowup: 4,
+            num_queries,
+            proof_of_work_bits: 16,
+            mmcs: challenge_mmcs,
+        }
+    }
+}
+
+impl OuterField for Bn254Fr {
+    type Diffusion = DiffusionMatrixBN254;
+
+    fn poseidon2_round_constants() -> Vec<[Self; 3]> {
+        bn254_poseidon2_rc3()
+    }
+}
+
+impl OuterField for Bls12381Fr {
+    type Diffusion = DiffusionMatrixBls12381;
+
+    fn poseidon2_round_constants() -> Vec<[Self; 3]> {
+        bls12381_poseidon2_rc3()
+    }
+}
 
 /// The permutation for outer recursion.
 pub fn outer_perm() -> OuterPerm {
-    const ROUNDS_F: usize = 8;
-    const ROUNDS_P: usize = 56;
-    let mut round_constants = bn254_poseidon2_rc3();
-    let internal_start = ROUNDS_F / 2;
-    let internal_end = (ROUNDS_F / 2) + ROUNDS_P;
-    let internal_round_constants = round_constants
-        .drain(internal_start..internal_end)
-        .map(|vec| vec[0])
-        .collect::<Vec<_>>();
-    let external_round_constants = round_constants;
-    OuterPerm::new(
-        ROUNDS_F,
-        external_round_constants,
-        Poseidon2ExternalMatrixGeneral,
-        ROUNDS_P,
-        internal_round_constants,
-        DiffusionMatrixBN254,
-    )
+    Bn254Fr::outer_perm()
 }
 
 /// The FRI config for outer recursion.
 pub fn outer_fri_config() -> FriConfig<OuterChallengeMmcs> {
-    let perm = outer_perm();
-    let hash = OuterHash::new(perm.clone()).unwrap();
-    let compress = OuterCompress::new(perm.clone());
-    let challenge_mmcs = OuterChallengeMmcs::new(OuterValMmcs::new(hash, compress));
-    let num_queries = if utils::sp1_dev_mode() {
-        1
-    } else {
-        match std::env::var("FRI_QUERIES") {
-            Ok(value) => value.parse().unwrap(),
-            Err(_) => 25,
-        }
-    };
-    FriConfig {
-        log_blowup: 4,
-        num_queries,
-        proof_of_work_bits: 16,
-        mmcs: challenge_mmcs,
-    }
+    Bn254Fr::outer_fri_config()
 }
 
+/// The config of the outer recursion, whose proofs are wrapped by a SNARK over BN254.
+pub type BabyBearPoseidon2Outer = OuterStarkConfig<Bn254Fr>;
+
+/// The config of the outer recursion, whose proofs are wrapped by a SNARK over BLS12-381.
+pub type BabyBearPoseidon2OuterBls12381 = OuterStarkConfig<Bls12381Fr>;
+
 #[derive(Deserialize)]
-#[serde(from = "std::marker::PhantomData<BabyBearPoseidon2Outer>")]
-pub struct BabyBearPoseidon2Outer {
-    pub perm: OuterPerm,
-    pub pcs: OuterPcs,
+#[serde(from = "std::marker::PhantomData<OuterStarkConfig<N>>")]
+#[serde(bound = "")]
+pub struct OuterStarkConfig<N: OuterField> {
+    pub perm: OuterPerm<N>,
+    pub pcs: OuterPcs<N>,
 }
 
-impl Clone for BabyBearPoseidon2Outer {
+impl<N: OuterField> Clone for OuterStarkConfig<N> {
     fn clone(&self) -> Self {
         Self::new()
     }
 }
 
-impl Serialize for BabyBearPoseidon2Outer {
+impl<N: OuterField> Serialize for OuterStarkConfig<N> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        std::marker::PhantomData::<BabyBearPoseidon2Outer>.serialize(serializer)
+        std::marker::PhantomData::<OuterStarkConfig<N>>.serialize(serializer)
     }
 }
 
-impl From<std::marker::PhantomData<BabyBearPoseidon2Outer>> for BabyBearPoseidon2Outer {
-    fn from(_: std::marker::PhantomData<BabyBearPoseidon2Outer>) -> Self {
+impl<N: OuterField> From<std::marker::PhantomData<OuterStarkConfig<N>>> for OuterStarkConfig<N> {
+    fn from(_: std::marker::PhantomData<OuterStarkConfig<N>>) -> Self {
         Self::new()
     }
 }
 
-impl BabyBearPoseidon2Outer {
+impl<N: OuterField> OuterStarkConfig<N> {
     pub fn new() -> Self {
-        let perm = outer_perm();
-        let hash = OuterHash::new(perm.clone()).unwrap();
-        let compress = OuterCompress::new(perm.clone());
-        let val_mmcs = OuterValMmcs::new(hash, compress);
+        let perm = N::outer_perm();
+        let hash = OuterHash::<N>::new(perm.clone()).unwrap();
+        let compress = OuterCompress::<N>::new(perm.clone());
+        let val_mmcs = OuterValMmcs::<N>::new(hash, compress);
         let dft = OuterDft {};
-        let fri_config = outer_fri_config();
-        let pcs = OuterPcs::new(27, dft, val_mmcs, fri_config);
+        let fri_config = N::outer_fri_config();
+        let pcs = OuterPcs::<N>::new(27, dft, val_mmcs, fri_config);
         Self { pcs, perm }
     }
 }
 
-impl Default for BabyBearPoseidon2Outer {
+impl<N: OuterField> Default for OuterStarkConfig<N> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl StarkGenericConfig for BabyBearPoseidon2Outer {
+impl<N: OuterField> StarkGenericConfig for OuterStarkConfig<N> {
     type Val = OuterVal;
-    type Domain = <OuterPcs as p3_commit::Pcs<OuterChallenge, OuterChallenger>>::Domain;
-    type Pcs = OuterPcs;
+    type Domain = <OuterPcs<N> as p3_commit::Pcs<OuterChallenge, OuterChallenger<N>>>::Domain;
+    type Pcs = OuterPcs<N>;
     type Challenge = OuterChallenge;
-    type Challenger = OuterChallenger;
+    type Challenger = OuterChallenger<N>;
 
     fn pcs(&self) -> &Self::Pcs {
         &self.pcs
     }
 
     fn challenger(&self) -> Self::Challenger {
-        OuterChallenger::new(self.perm.clone()).unwrap()
+        OuterChallenger::<N>::new(self.perm.clone()).unwrap()
+    }
+
+    fn merkle_hash(&self) -> MerkleHash {
+        MerkleHash::Poseidon2
+    }
+
+    fn fri_parameters(&self) -> FriParameters {
+        fri_parameters(self.pcs.fri_config())
     }
 }
 
@@ -1,3 +1,4 @@
+pub mod bls12_381;
 pub mod config;
 pub mod poseidon2;
 pub mod utils;
@@ -3,9 +3,13 @@ use p3_bn254_fr::Bn254Fr;
 use p3_bn254_fr::FFBn254Fr;
 use zkhash::ark_ff::BigInteger;
 use zkhash::ark_ff::PrimeField;
+use zkhash::fields::bls12::FpBLS12 as ark_FpBLS12;
 use zkhash::fields::bn256::FpBN256 as ark_FpBN256;
+use zkhash::poseidon2::poseidon2_instance_bls12::RC3 as BLS12_RC3;
 use zkhash::poseidon2::poseidon2_instance_bn256::RC3;
 
+use super::bls12_381::{Bls12381Fr, FFBls12381Fr};
+
 fn bn254_from_ark_ff(input: ark_FpBN256) -> Bn254Fr {
     let bytes = input.into_bigint().to_bytes_le();
 
@@ -53,3 +57,37 @@ pub fn bn254_poseidon2_rc4() -> Vec<[Bn254Fr; 4]> {
         })
         .collect()
 }
+
+fn bls12381_from_ark_ff(input: ark_FpBLS12) -> Bls12381Fr {
+    let bytes = input.into_bigint().to_bytes_le();
+
+    let mut res = <FFBls12381Fr as ff::PrimeField>::Repr::default();
+
+    for (i, digit) in res.0.as_mut().iter_mut().enumerate() {
+        *digit = bytes[i];
+    }
+
+    let value = FFBls12381Fr::from_repr(res);
+
+    if value.is_some().into() {
+        Bls12381Fr {
+            value: value.unwrap(),
+        }
+    } else {
+        panic!("Invalid field element")
+    }
+}
+
+pub fn bls12381_poseidon2_rc3() -> Vec<[Bls12381Fr; 3]> {
+    BLS12_RC3
+        .iter()
+        .map(|vec| {
+            vec.iter()
+                .cloned()
+                .map(bls12381_from_ark_ff)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap()
+        })
+        .collect()
+}
@@ -4,16 +4,29 @@ package main
 #include "./babybear.h"
 
 typedef struct {
-	char *PublicInputs[2];
+	char *PublicInputs[10];
 	char *EncodedProof;
 	char *RawProof;
 } C_PlonkBn254Proof;
+
+typedef struct {
+	char *PublicInputs[10];
+	char *EncodedProof;
+	char *RawProof;
+} C_Groth16Bn254Proof;
+
+typedef struct {
+	char *PublicInputs[10];
+	char *EncodedProof;
+	char *RawProof;
+} C_Groth16Bls12381Proof;
 */
 import "C"
 import (
 	"encoding/json"
 	"fmt"
 	"os"
+	"strings"
 	"sync"
 
 	"github.com/consensys/gnark-crypto/ecc"
@@ -39,8 +52,9 @@ func ProvePlonkBn254(dataDir *C.char, witnessPath *C.char) *C.C_PlonkBn254Proof
 	}
 
 	structPtr := (*C.C_PlonkBn254Proof)(ms)
-	structPtr.PublicInputs[0] = C.CString(sp1PlonkBn254Proof.PublicInputs[0])
-	structPtr.PublicInputs[1] = C.CString(sp1PlonkBn254Proof.PublicInputs[1])
+	for i := 0; i < sp1.NUM_PUBLIC_INPUTS; i++ {
+		structPtr.PublicInputs[i] = C.CString(sp1PlonkBn254Proof.PublicInputs[i])
+	}
 	structPtr.EncodedProof = C.CString(sp1PlonkBn254Proof.EncodedProof)
 	structPtr.RawProof = C.CString(sp1PlonkBn254Proof.RawProof)
 	return structPtr
@@ -55,13 +69,100 @@ func BuildPlonkBn254(dataDir *C.char) {
 }
 
 //export VerifyPlonkBn254
-func VerifyPlonkBn254(dataDir *C.char, proof *C.char, vkeyHash *C.char, commitedValuesDigest *C.char) *C.char {
+func VerifyPlonkBn254(dataDir *C.char, proof *C.char, vkeyHash *C.char, commitedValuesDigest *C.char, userValues *C.char) *C.char {
+	dataDirString := C.GoString(dataDir)
+	proofString := C.GoString(proof)
+	vkeyHashString := C.GoString(vkeyHash)
+	commitedValuesDigestString := C.GoString(commitedValuesDigest)
+	userValuesStrings := strings.Split(C.GoString(userValues), ",")
+
+	err := sp1.Verify(dataDirString, proofString, vkeyHashString, commitedValuesDigestString, userValuesStrings)
+	if err != nil {
+		return C.CString(err.Error())
+	}
+	return nil
+}
+
+//export ProveGroth16Bn254
+func ProveGroth16Bn254(dataDir *C.char, witnessPath *C.char) *C.C_Groth16Bn254Proof {
+	dataDirString := C.GoString(dataDir)
+	witnessPathString := C.GoString(witnessPath)
+
+	sp1Groth16Bn254Proof := sp1.ProveGroth16(dataDirString, witnessPathString)
+
+	ms := C.malloc(C.sizeof_C_Groth16Bn254Proof)
+	if ms == nil {
+		return nil
+	}
+
+	structPtr := (*C.C_Groth16Bn254Proof)(ms)
+	for i := 0; i < sp1.NUM_PUBLIC_INPUTS; i++ {
+		structPtr.PublicInputs[i] = C.CString(sp1Groth16Bn254Proof.PublicInputs[i])
+	}
+	structPtr.EncodedProof = C.CString(sp1Groth16Bn254Proof.EncodedProof)
+	structPtr.RawProof = C.CString(sp1Groth16Bn254Proof.RawProof)
+	return structPtr
+}
+
+//export BuildGroth16Bn254
+func BuildGroth16Bn254(dataDir *C.char) {
+	dataDirString := C.GoString(dataDir)
+
+	sp1.BuildGroth16(dataDirString)
+}
+
+//export VerifyGroth16Bn254
+func VerifyGroth16Bn254(dataDir *C.char, proof *C.char, vkeyHash *C.char, commitedValuesDigest *C.char, userValues *C.char) *C.char {
+	dataDirString := C.GoString(dataDir)
+	proofString := C.GoString(proof)
+	vkeyHashString := C.GoString(vkeyHash)
+	commitedValuesDigestString := C.GoString(commitedValuesDigest)
+	userValuesStrings := strings.Split(C.GoString(userValues), ",")
+
+	err := sp1.VerifyGroth16(dataDirString, proofString, vkeyHashString, commitedValuesDigestString, userValuesStrings)
+	if err != nil {
+		return C.CString(err.Error())
+	}
+	return nil
+}
+
+//export ProveGroth16Bls12381
+func ProveGroth16Bls12381(dataDir *C.char, witnessPath *C.char) *C.C_Groth16Bls12381Proof {
+	dataDirString := C.GoString(dataDir)
+	witnessPathString := C.GoString(witnessPath)
+
+	sp1Groth16Bls12381Proof := sp1.ProveGroth16Bls12381(dataDirString, witnessPathString)
+
+	ms := C.malloc(C.sizeof_C_Groth16Bls12381Proof)
+	if ms == nil {
+		return nil
+	}
+
+	structPtr := (*C.C_Groth16Bls12381Proof)(ms)
+	for i := 0; i < sp1.NUM_PUBLIC_INPUTS; i++ {
+		structPtr.PublicInputs[i] = C.CString(sp1Groth16Bls12381Proof.PublicInputs[i])
+	}
+	structPtr.EncodedProof = C.CString(sp1Groth16Bls12381Proof.EncodedProof)
+	structPtr.RawProof = C.CString(sp1Groth16Bls12381Proof.RawProof)
+	return structPtr
+}
+
+//export BuildGroth16Bls12381
+func BuildGroth16Bls12381(dataDir *C.char) {
+	dataDirString := C.GoString(dataDir)
+
+	sp1.BuildGroth16Bls12381(dataDirString)
+}
+
+//export VerifyGroth16Bls12381
+func VerifyGroth16Bls12381(dataDir *C.char, proof *C.char, vkeyHash *C.char, commitedValuesDigest *C.char, userValues *C.char) *C.char {
 	dataDirString := C.GoString(dataDir)
 	proofString := C.GoString(proof)
 	vkeyHashString := C.GoString(vkeyHash)
 	commitedValuesDigestString := C.GoString(commitedValuesDigest)
+	userValuesStrings := strings.Split(C.GoString(userValues), ",")
 
-	err := sp1.Verify(dataDirString, proofString, vkeyHashString, commitedValuesDigestString)
+	err := sp1.VerifyGroth16Bls12381(dataDirString, proofString, vkeyHashString, commitedValuesDigestString, userValuesStrings)
 	if err != nil {
 		return C.CString(err.Error())
 	}
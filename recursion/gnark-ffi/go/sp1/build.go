@@ -5,9 +5,10 @@ import (
 	"fmt"
 	"log"
 	"os"
-	"strings"
+	"path/filepath"
 
 	"github.com/consensys/gnark-crypto/ecc"
+	kzg_bn254 "github.com/consensys/gnark-crypto/ecc/bn254/kzg"
 	"github.com/consensys/gnark-crypto/kzg"
 	"github.com/consensys/gnark/backend/plonk"
 	"github.com/consensys/gnark/frontend"
@@ -59,7 +60,8 @@ func Build(dataDir string) {
 	}
 	defer srsLagrangeFile.Close()
 
-	if !strings.Contains(dataDir, "dev") {
+	// Only the development directory is built with an unsafe SRS, whatever the path to it.
+	if filepath.Base(dataDir) != "dev" {
 		if _, err := os.Stat(srsFileName); os.IsNotExist(err) {
 			fmt.Println("downloading aztec ignition srs")
 			trusted_setup.DownloadAndSaveAztecIgnitionSrs(174, srsFileName)
@@ -82,6 +84,9 @@ func Build(dataDir string) {
 				panic(err)
 			}
 		} else {
+			// The SRS was downloaded before, or supplied by the user, so only check that it is a
+			// consistent KZG setup and compute its Lagrange form for this circuit.
+			fmt.Println("using the srs at", srsFileName)
 			srsFile, err := os.Open(srsFileName)
 			if err != nil {
 				panic(err)
@@ -93,15 +98,10 @@ func Build(dataDir string) {
 			if err != nil {
 				panic(err)
 			}
+			trusted_setup.SanityCheck(srs.(*kzg_bn254.SRS))
 
-			srsLagrangeFile, err := os.Open(srsLagrangeFileName)
-			if err != nil {
-				panic(err)
-			}
-			defer srsLagrangeFile.Close()
-
-			_, err = srsLagrange.ReadFrom(srsLagrangeFile)
-			srsLagrangeFile.Close()
+			srsLagrange = trusted_setup.ToLagrange(scs, srs)
+			_, err = srsLagrange.WriteTo(srsLagrangeFile)
 			if err != nil {
 				panic(err)
 			}
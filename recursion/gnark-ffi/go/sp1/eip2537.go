@@ -0,0 +1,93 @@
+package sp1
+
+import (
+	"bytes"
+	"encoding/binary"
+	"fmt"
+	"io"
+
+	curve "github.com/consensys/gnark-crypto/ecc/bls12-381"
+	"github.com/consensys/gnark-crypto/ecc/bls12-381/fp"
+	"github.com/consensys/gnark/backend/groth16"
+	groth16_bls12381 "github.com/consensys/gnark/backend/groth16/bls12-381"
+)
+
+// The precompiles of EIP-2537 encode an element of Fp as 64 big-endian bytes, the 48 bytes of the
+// element padded with 16 zero bytes, an element c0 + c1 * u of Fp2 as the encoding of c0 followed
+// by the encoding of c1, and a point as the encoding of x followed by the encoding of y, with the
+// point at infinity encoded as zeros.
+
+func writeEip2537Fp(buf *bytes.Buffer, e *fp.Element) {
+	var padding [16]byte
+	b := e.Bytes()
+	buf.Write(padding[:])
+	buf.Write(b[:])
+}
+
+func writeEip2537G1(buf *bytes.Buffer, p *curve.G1Affine) {
+	writeEip2537Fp(buf, &p.X)
+	writeEip2537Fp(buf, &p.Y)
+}
+
+func writeEip2537G2(buf *bytes.Buffer, p *curve.G2Affine) {
+	writeEip2537Fp(buf, &p.X.A0)
+	writeEip2537Fp(buf, &p.X.A1)
+	writeEip2537Fp(buf, &p.Y.A0)
+	writeEip2537Fp(buf, &p.Y.A1)
+}
+
+// MarshalEip2537Proof encodes the proof in the layout of the precompiles of EIP-2537, as A (G1),
+// B (G2) and C (G1), then the commitments (G1) and the proof of knowledge of the commitments (G1).
+func MarshalEip2537Proof(proof *groth16_bls12381.Proof) []byte {
+	var buf bytes.Buffer
+	writeEip2537G1(&buf, &proof.Ar)
+	writeEip2537G2(&buf, &proof.Bs)
+	writeEip2537G1(&buf, &proof.Krs)
+	for i := range proof.Commitments {
+		writeEip2537G1(&buf, &proof.Commitments[i])
+	}
+	writeEip2537G1(&buf, &proof.CommitmentPok)
+	return buf.Bytes()
+}
+
+// ExportEip2537VerifyingKey writes the verifying key in the layout of the precompiles of EIP-2537.
+// The points of G2 which the pairing check needs negated are negated, so the verifier does not
+// negate them.
+//
+// The layout is alpha (G1), -beta, -gamma, -delta (G2), the number of points of K as a big-endian
+// uint32 and the points of K (G1), then the number of commitment keys as a big-endian uint32 and
+// the G and GRootSigmaNeg points (G2) of each key.
+func ExportEip2537VerifyingKey(vk groth16.VerifyingKey, w io.Writer) error {
+	bvk := vk.(*groth16_bls12381.VerifyingKey)
+	if len(bvk.CommitmentKeys) > 1 {
+		return fmt.Errorf("the eip-2537 verifier supports at most one commitment, found %d", len(bvk.CommitmentKeys))
+	}
+	for _, committed := range bvk.PublicAndCommitmentCommitted {
+		if len(committed) > 0 {
+			return fmt.Errorf("the eip-2537 verifier does not support commitments to public inputs")
+		}
+	}
+
+	var betaNeg, gammaNeg, deltaNeg curve.G2Affine
+	betaNeg.Neg(&bvk.G2.Beta)
+	gammaNeg.Neg(&bvk.G2.Gamma)
+	deltaNeg.Neg(&bvk.G2.Delta)
+
+	var buf bytes.Buffer
+	writeEip2537G1(&buf, &bvk.G1.Alpha)
+	writeEip2537G2(&buf, &betaNeg)
+	writeEip2537G2(&buf, &gammaNeg)
+	writeEip2537G2(&buf, &deltaNeg)
+	binary.Write(&buf, binary.BigEndian, uint32(len(bvk.G1.K)))
+	for i := range bvk.G1.K {
+		writeEip2537G1(&buf, &bvk.G1.K[i])
+	}
+	binary.Write(&buf, binary.BigEndian, uint32(len(bvk.CommitmentKeys)))
+	for i := range bvk.CommitmentKeys {
+		writeEip2537G2(&buf, &bvk.CommitmentKeys[i].G)
+		writeEip2537G2(&buf, &bvk.CommitmentKeys[i].GRootSigmaNeg)
+	}
+
+	_, err := w.Write(buf.Bytes())
+	return err
+}
@@ -0,0 +1,373 @@
+package sp1
+
+import (
+	"bytes"
+	"crypto/sha256"
+	"encoding/binary"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	curve "github.com/consensys/gnark-crypto/ecc/bn254"
+	"github.com/consensys/gnark/backend"
+	"github.com/consensys/gnark/backend/groth16"
+	groth16_bls12381 "github.com/consensys/gnark/backend/groth16/bls12-381"
+	groth16_bn254 "github.com/consensys/gnark/backend/groth16/bn254"
+	"github.com/consensys/gnark/frontend"
+	"github.com/consensys/gnark/frontend/cs/r1cs"
+	"github.com/succinctlabs/sp1-recursion-gnark/sp1/babybear"
+)
+
+// The artifacts of the Groth16 circuit have the same names as the artifacts of the PLONK circuit,
+// in their own directory.
+//
+// The circuit is proved either over BN254 or over BLS12-381. The verifiers of Solidity and Solana
+// are only exported over BN254, and the verifying key in the layout of the precompiles of EIP-2537
+// is only exported over BLS12-381.
+
+// The commitments of the proofs are hashed to the field with SHA-256, as the Solidity and the
+// Solana verifiers hash them, instead of the default hash of gnark.
+func proverOptions() backend.ProverOption {
+	return backend.WithProverHashToFieldFunction(sha256.New())
+}
+
+func verifierOptions() backend.VerifierOption {
+	return backend.WithVerifierHashToFieldFunction(sha256.New())
+}
+
+func BuildGroth16(dataDir string) {
+	buildGroth16(dataDir, ecc.BN254)
+}
+
+func BuildGroth16Bls12381(dataDir string) {
+	buildGroth16(dataDir, ecc.BLS12_381)
+}
+
+func buildGroth16(dataDir string, curveID ecc.ID) {
+	os.Setenv("CONSTRAINTS_JSON", dataDir+"/"+CONSTRAINTS_JSON_FILE)
+
+	// Read the file.
+	data, err := os.ReadFile(dataDir + "/" + WITNESS_JSON_FILE)
+	if err != nil {
+		panic(err)
+	}
+	var witnessInput WitnessInput
+	err = json.Unmarshal(data, &witnessInput)
+	if err != nil {
+		panic(err)
+	}
+
+	// Compile the circuit.
+	circuit := NewCircuit(witnessInput)
+	r1cs, err := frontend.Compile(curveID.ScalarField(), r1cs.NewBuilder, &circuit)
+	if err != nil {
+		panic(err)
+	}
+
+	// Generate the proving and verifying key. Unlike PLONK, the setup of Groth16 is specific to
+	// the circuit.
+	pk, vk, err := groth16.Setup(r1cs)
+	if err != nil {
+		panic(err)
+	}
+
+	// Generate and verify a proof of the template witness.
+	assignment := NewCircuit(witnessInput)
+	witness, err := frontend.NewWitness(&assignment, curveID.ScalarField())
+	if err != nil {
+		panic(err)
+	}
+	proof, err := groth16.Prove(r1cs, pk, witness, proverOptions())
+	if err != nil {
+		panic(err)
+	}
+	publicWitness, err := witness.Public()
+	if err != nil {
+		panic(err)
+	}
+	err = groth16.Verify(proof, vk, publicWitness, verifierOptions())
+	if err != nil {
+		panic(err)
+	}
+
+	// Create the build directory.
+	os.MkdirAll(dataDir, 0755)
+
+	if curveID == ecc.BN254 {
+		// Write the solidity verifier.
+		solidityVerifierFile, err := os.Create(dataDir + "/" + GROTH16_VERIFIER_CONTRACT_PATH)
+		if err != nil {
+			panic(err)
+		}
+		defer solidityVerifierFile.Close()
+		err = vk.ExportSolidity(solidityVerifierFile)
+		if err != nil {
+			panic(err)
+		}
+
+		// Write the verifier key of the Solana verifier.
+		solanaVkFile, err := os.Create(dataDir + "/" + GROTH16_SOLANA_VK_PATH)
+		if err != nil {
+			panic(err)
+		}
+		defer solanaVkFile.Close()
+		err = ExportSolanaVerifyingKey(vk, solanaVkFile)
+		if err != nil {
+			panic(err)
+		}
+	} else {
+		// Write the verifier key in the layout of the precompiles of EIP-2537.
+		eip2537VkFile, err := os.Create(dataDir + "/" + GROTH16_EIP2537_VK_PATH)
+		if err != nil {
+			panic(err)
+		}
+		defer eip2537VkFile.Close()
+		err = ExportEip2537VerifyingKey(vk, eip2537VkFile)
+		if err != nil {
+			panic(err)
+		}
+	}
+
+	// Write the R1CS.
+	r1csFile, err := os.Create(dataDir + "/" + CIRCUIT_PATH)
+	if err != nil {
+		panic(err)
+	}
+	defer r1csFile.Close()
+	_, err = r1cs.WriteTo(r1csFile)
+	if err != nil {
+		panic(err)
+	}
+
+	// Write the verifier key.
+	vkFile, err := os.Create(dataDir + "/" + VK_PATH)
+	if err != nil {
+		panic(err)
+	}
+	defer vkFile.Close()
+	_, err = vk.WriteTo(vkFile)
+	if err != nil {
+		panic(err)
+	}
+
+	// Write the proving key.
+	pkFile, err := os.Create(dataDir + "/" + PK_PATH)
+	if err != nil {
+		panic(err)
+	}
+	defer pkFile.Close()
+	_, err = pk.WriteTo(pkFile)
+	if err != nil {
+		panic(err)
+	}
+}
+
+func ProveGroth16(dataDir string, witnessPath string) Proof {
+	return proveGroth16(dataDir, witnessPath, ecc.BN254)
+}
+
+func ProveGroth16Bls12381(dataDir string, witnessPath string) Proof {
+	return proveGroth16(dataDir, witnessPath, ecc.BLS12_381)
+}
+
+func proveGroth16(dataDir string, witnessPath string, curveID ecc.ID) Proof {
+	// Sanity check the required arguments have been provided.
+	if dataDir == "" {
+		panic("dataDirStr is required")
+	}
+	os.Setenv("CONSTRAINTS_JSON", dataDir+"/"+CONSTRAINTS_JSON_FILE)
+
+	// Read the R1CS.
+	r1csFile, err := os.Open(dataDir + "/" + CIRCUIT_PATH)
+	if err != nil {
+		panic(err)
+	}
+	r1cs := groth16.NewCS(curveID)
+	r1cs.ReadFrom(r1csFile)
+
+	// Read the proving key.
+	pkFile, err := os.Open(dataDir + "/" + PK_PATH)
+	if err != nil {
+		panic(err)
+	}
+	pk := groth16.NewProvingKey(curveID)
+	pk.UnsafeReadFrom(pkFile)
+
+	// Read the verifier key.
+	vkFile, err := os.Open(dataDir + "/" + VK_PATH)
+	if err != nil {
+		panic(err)
+	}
+	vk := groth16.NewVerifyingKey(curveID)
+	vk.ReadFrom(vkFile)
+
+	// Read the file.
+	data, err := os.ReadFile(witnessPath)
+	if err != nil {
+		panic(err)
+	}
+	var witnessInput WitnessInput
+	err = json.Unmarshal(data, &witnessInput)
+	if err != nil {
+		panic(err)
+	}
+
+	// Generate the witness.
+	assignment := NewCircuit(witnessInput)
+	witness, err := frontend.NewWitness(&assignment, curveID.ScalarField())
+	if err != nil {
+		panic(err)
+	}
+	publicWitness, err := witness.Public()
+	if err != nil {
+		panic(err)
+	}
+
+	// Generate the proof.
+	proof, err := groth16.Prove(r1cs, pk, witness, proverOptions())
+	if err != nil {
+		panic(err)
+	}
+
+	// Verify proof.
+	err = groth16.Verify(proof, vk, publicWitness, verifierOptions())
+	if err != nil {
+		panic(err)
+	}
+
+	return NewSP1Groth16Proof(&proof, witnessInput)
+}
+
+func VerifyGroth16(verifyCmdDataDir string, verifyCmdProof string, verifyCmdVkeyHash string, verifyCmdCommitedValuesDigest string, verifyCmdUserValues []string) error {
+	return verifyGroth16(verifyCmdDataDir, verifyCmdProof, verifyCmdVkeyHash, verifyCmdCommitedValuesDigest, verifyCmdUserValues, ecc.BN254)
+}
+
+func VerifyGroth16Bls12381(verifyCmdDataDir string, verifyCmdProof string, verifyCmdVkeyHash string, verifyCmdCommitedValuesDigest string, verifyCmdUserValues []string) error {
+	return verifyGroth16(verifyCmdDataDir, verifyCmdProof, verifyCmdVkeyHash, verifyCmdCommitedValuesDigest, verifyCmdUserValues, ecc.BLS12_381)
+}
+
+func verifyGroth16(verifyCmdDataDir string, verifyCmdProof string, verifyCmdVkeyHash string, verifyCmdCommitedValuesDigest string, verifyCmdUserValues []string, curveID ecc.ID) error {
+	// Sanity check the required arguments have been provided.
+	if verifyCmdDataDir == "" {
+		panic("--data is required")
+	}
+
+	// Decode the proof.
+	proofDecodedBytes, err := hex.DecodeString(verifyCmdProof)
+	if err != nil {
+		panic(err)
+	}
+	proof := groth16.NewProof(curveID)
+	if _, err := proof.ReadFrom(bytes.NewReader(proofDecodedBytes)); err != nil {
+		panic(err)
+	}
+
+	// Read the verifier key.
+	vkFile, err := os.Open(verifyCmdDataDir + "/" + VK_PATH)
+	if err != nil {
+		panic(err)
+	}
+	vk := groth16.NewVerifyingKey(curveID)
+	vk.ReadFrom(vkFile)
+
+	// Compute the public witness.
+	circuit := Circuit{
+		Vars:                 []frontend.Variable{},
+		Felts:                []babybear.Variable{},
+		Exts:                 []babybear.ExtensionVariable{},
+		VkeyHash:             verifyCmdVkeyHash,
+		CommitedValuesDigest: verifyCmdCommitedValuesDigest,
+		UserValues:           NewUserValues(verifyCmdUserValues),
+	}
+	witness, err := frontend.NewWitness(&circuit, curveID.ScalarField())
+	if err != nil {
+		panic(err)
+	}
+	publicWitness, err := witness.Public()
+	if err != nil {
+		panic(err)
+	}
+
+	// Verify proof.
+	err = groth16.Verify(proof, vk, publicWitness, verifierOptions())
+	return err
+}
+
+func NewSP1Groth16Proof(proof *groth16.Proof, witnessInput WitnessInput) Proof {
+	var buf bytes.Buffer
+	(*proof).WriteRawTo(&buf)
+	proofBytes := buf.Bytes()
+
+	publicInputs := NewPublicInputs(witnessInput)
+
+	// The proofs over BN254 are encoded for the Solidity verifier, and the proofs over BLS12-381
+	// for the precompiles of EIP-2537.
+	var encodedProof []byte
+	switch p := (*proof).(type) {
+	case *groth16_bn254.Proof:
+		encodedProof = p.MarshalSolidity()
+	case *groth16_bls12381.Proof:
+		encodedProof = MarshalEip2537Proof(p)
+	default:
+		panic("unsupported curve")
+	}
+
+	return Proof{
+		PublicInputs: publicInputs,
+		EncodedProof: hex.EncodeToString(encodedProof),
+		RawProof:     hex.EncodeToString(proofBytes),
+	}
+}
+
+// ExportSolanaVerifyingKey writes the verifying key in the layout of the Solana verifier. The
+// points are uncompressed and big-endian, as the alt_bn128 syscalls read them, and the points of
+// G2 which the pairing check needs negated are negated, so the verifier does not negate them.
+//
+// The layout is alpha (G1), -beta, -gamma, -delta (G2), the number of points of K as a big-endian
+// uint32 and the points of K (G1), then the number of commitment keys as a big-endian uint32 and
+// the G and GRootSigmaNeg points (G2) of each key.
+func ExportSolanaVerifyingKey(vk groth16.VerifyingKey, w io.Writer) error {
+	bvk := vk.(*groth16_bn254.VerifyingKey)
+	if len(bvk.CommitmentKeys) > 1 {
+		return fmt.Errorf("the solana verifier supports at most one commitment, found %d", len(bvk.CommitmentKeys))
+	}
+	for _, committed := range bvk.PublicAndCommitmentCommitted {
+		if len(committed) > 0 {
+			return fmt.Errorf("the solana verifier does not support commitments to public inputs")
+		}
+	}
+
+	var betaNeg, gammaNeg, deltaNeg curve.G2Affine
+	betaNeg.Neg(&bvk.G2.Beta)
+	gammaNeg.Neg(&bvk.G2.Gamma)
+	deltaNeg.Neg(&bvk.G2.Delta)
+
+	var buf bytes.Buffer
+	writeG1 := func(p *curve.G1Affine) {
+		b := p.RawBytes()
+		buf.Write(b[:])
+	}
+	writeG2 := func(p *curve.G2Affine) {
+		b := p.RawBytes()
+		buf.Write(b[:])
+	}
+	writeG1(&bvk.G1.Alpha)
+	writeG2(&betaNeg)
+	writeG2(&gammaNeg)
+	writeG2(&deltaNeg)
+	binary.Write(&buf, binary.BigEndian, uint32(len(bvk.G1.K)))
+	for i := range bvk.G1.K {
+		writeG1(&bvk.G1.K[i])
+	}
+	binary.Write(&buf, binary.BigEndian, uint32(len(bvk.CommitmentKeys)))
+	for i := range bvk.CommitmentKeys {
+		writeG2(&bvk.CommitmentKeys[i].G)
+		writeG2(&bvk.CommitmentKeys[i].GRootSigmaNeg)
+	}
+
+	_, err := w.Write(buf.Bytes())
+	return err
+}
@@ -1,6 +1,7 @@
 package poseidon2
 
 import (
+	"github.com/consensys/gnark-crypto/ecc"
 	"github.com/consensys/gnark/frontend"
 )
 
@@ -11,13 +12,21 @@ const DEGREE = 5
 
 type Poseidon2Chip struct {
 	api                   frontend.API
+	rc3                   *[NUM_EXTERNAL_ROUNDS + NUM_INTERNAL_ROUNDS][WIDTH]frontend.Variable
 	internal_linear_layer [WIDTH]frontend.Variable
 	zero, one             frontend.Variable
 }
 
+// NewChip returns a chip of the permutation over the scalar field of the curve of the circuit,
+// which is either BN254 or BLS12-381.
 func NewChip(api frontend.API) *Poseidon2Chip {
+	rc3 := &RC3
+	if api.Compiler().Field().Cmp(ecc.BLS12_381.ScalarField()) == 0 {
+		rc3 = &RC3_BLS12381
+	}
 	return &Poseidon2Chip{
 		api: api,
+		rc3: rc3,
 		internal_linear_layer: [WIDTH]frontend.Variable{
 			frontend.Variable(1),
 			frontend.Variable(1),
@@ -36,7 +45,7 @@ func (p *Poseidon2Chip) PermuteMut(state *[WIDTH]frontend.Variable) {
 	rounds := NUM_EXTERNAL_ROUNDS + NUM_INTERNAL_ROUNDS
 	rounds_f_beginning := NUM_EXTERNAL_ROUNDS / 2
 	for r := 0; r < rounds_f_beginning; r++ {
-		p.AddRc(state, RC3[r])
+		p.AddRc(state, p.rc3[r])
 		p.Sbox(state)
 		p.MatrixPermuteMut(state)
 	}
@@ -44,14 +53,14 @@ func (p *Poseidon2Chip) PermuteMut(state *[WIDTH]frontend.Variable) {
 	// The internal rounds.
 	p_end := rounds_f_beginning + NUM_INTERNAL_ROUNDS
 	for r := rounds_f_beginning; r < p_end; r++ {
-		state[0] = p.api.Add(state[0], RC3[r][0])
+		state[0] = p.api.Add(state[0], p.rc3[r][0])
 		state[0] = p.SboxP(state[0])
 		p.DiffusionPermuteMut(state)
 	}
 
 	// The second half of the external rounds.
 	for r := p_end; r < rounds; r++ {
-		p.AddRc(state, RC3[r])
+		p.AddRc(state, p.rc3[r])
 		p.Sbox(state)
 		p.MatrixPermuteMut(state)
 	}
@@ -50,3 +50,24 @@ func TestPoseidon2(t *testing.T) {
 	witness = TestPoseidon2Circuit{Input: input, ExpectedOutput: expected_output}
 	assert.ProverSucceeded(&circuit, &witness, test.WithCurves(ecc.BN254), test.WithBackends(backend.PLONK))
 }
+
+func TestPoseidon2Bls12381(t *testing.T) {
+	assert := test.NewAssert(t)
+	var circuit, witness TestPoseidon2Circuit
+
+	input := [WIDTH]frontend.Variable{
+		frontend.Variable(0),
+		frontend.Variable(1),
+		frontend.Variable(2),
+	}
+
+	expected_output := [WIDTH]frontend.Variable{
+		frontend.Variable("0x1B152349B1950B6A8CA75EE4407B6E26CA5CCA5650534E56EF3FD45761FBF5F0"),
+		frontend.Variable("0x4C5793C87D51BDC2C08A32108437DC0000BD0275868F09EBC5F36919AF5B3891"),
+		frontend.Variable("0x1FC8ED171E67902CA49863159FE5BA6325318843D13976143B8125F08B50DC6B"),
+	}
+
+	circuit = TestPoseidon2Circuit{Input: input, ExpectedOutput: expected_output}
+	witness = TestPoseidon2Circuit{Input: input, ExpectedOutput: expected_output}
+	assert.ProverSucceeded(&circuit, &witness, test.WithCurves(ecc.BLS12_381), test.WithBackends(backend.GROTH16))
+}
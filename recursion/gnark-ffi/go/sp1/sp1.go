@@ -16,13 +16,22 @@ var SRS_LAGRANGE_FILE string = "srs_lagrange.bin"
 var CONSTRAINTS_JSON_FILE string = "constraints.json"
 var WITNESS_JSON_FILE string = "witness.json"
 var VERIFIER_CONTRACT_PATH string = "PlonkVerifier.sol"
+var GROTH16_VERIFIER_CONTRACT_PATH string = "Groth16Verifier.sol"
+var GROTH16_SOLANA_VK_PATH string = "groth16_vk_solana.bin"
+var GROTH16_EIP2537_VK_PATH string = "groth16_vk_eip2537.bin"
 var CIRCUIT_PATH string = "circuit.bin"
 var VK_PATH string = "vk.bin"
 var PK_PATH string = "pk.bin"
 
+// The number of user values, which are public inputs after the vkey hash and the committed values
+// digest.
+const NUM_USER_VALUES = 8
+const NUM_PUBLIC_INPUTS = 2 + NUM_USER_VALUES
+
 type Circuit struct {
-	VkeyHash             frontend.Variable `gnark:",public"`
-	CommitedValuesDigest frontend.Variable `gnark:",public"`
+	VkeyHash             frontend.Variable                  `gnark:",public"`
+	CommitedValuesDigest frontend.Variable                  `gnark:",public"`
+	UserValues           [NUM_USER_VALUES]frontend.Variable `gnark:",public"`
 	Vars                 []frontend.Variable
 	Felts                []babybear.Variable
 	Exts                 []babybear.ExtensionVariable
@@ -39,12 +48,13 @@ type WitnessInput struct {
 	Exts                 [][]string `json:"exts"`
 	VkeyHash             string     `json:"vkey_hash"`
 	CommitedValuesDigest string     `json:"commited_values_digest"`
+	UserValues           []string   `json:"user_values"`
 }
 
 type Proof struct {
-	PublicInputs [2]string `json:"public_inputs"`
-	EncodedProof string    `json:"encoded_proof"`
-	RawProof     string    `json:"raw_proof"`
+	PublicInputs [NUM_PUBLIC_INPUTS]string `json:"public_inputs"`
+	EncodedProof string                    `json:"encoded_proof"`
+	RawProof     string                    `json:"raw_proof"`
 }
 
 func (circuit *Circuit) Define(api frontend.API) error {
@@ -184,6 +194,13 @@ func (circuit *Circuit) Define(api frontend.API) error {
 		case "CommitCommitedValuesDigest":
 			element := vars[cs.Args[0][0]]
 			api.AssertIsEqual(circuit.CommitedValuesDigest, element)
+		case "CommitUserValue":
+			i, err := strconv.Atoi(cs.Args[1][0])
+			if err != nil {
+				panic(err)
+			}
+			element := vars[cs.Args[0][0]]
+			api.AssertIsEqual(circuit.UserValues[i], element)
 		case "CircuitFelts2Ext":
 			exts[cs.Args[0][0]] = babybear.Felts2Ext(felts[cs.Args[1][0]], felts[cs.Args[2][0]], felts[cs.Args[3][0]], felts[cs.Args[4][0]])
 		default:
@@ -14,6 +14,12 @@ import (
 	"github.com/consensys/gnark/constraint"
 )
 
+// SanityCheck checks that the proving and verifying keys of a KZG SRS are consistent, by opening a
+// commitment to a random polynomial. It exits the process if they are not.
+func SanityCheck(srs *kzg_bn254.SRS) {
+	sanityCheck(srs)
+}
+
 func sanityCheck(srs *kzg_bn254.SRS) {
 	// we can now use the SRS to verify a proof
 	// create a polynomial
@@ -15,9 +15,7 @@ func NewSP1PlonkBn254Proof(proof *plonk.Proof, witnessInput WitnessInput) Proof
 	(*proof).WriteRawTo(&buf)
 	proofBytes := buf.Bytes()
 
-	var publicInputs [2]string
-	publicInputs[0] = witnessInput.VkeyHash
-	publicInputs[1] = witnessInput.CommitedValuesDigest
+	publicInputs := NewPublicInputs(witnessInput)
 
 	// Cast plonk proof into plonk_bn254 proof so we can call MarshalSolidity.
 	p := (*proof).(*plonk_bn254.Proof)
@@ -47,8 +45,41 @@ func NewCircuit(witnessInput WitnessInput) Circuit {
 	return Circuit{
 		VkeyHash:             witnessInput.VkeyHash,
 		CommitedValuesDigest: witnessInput.CommitedValuesDigest,
+		UserValues:           NewUserValues(witnessInput.UserValues),
 		Vars:                 vars,
 		Felts:                felts,
 		Exts:                 exts,
 	}
 }
+
+// NewUserValues assigns the user values of the circuit, which are zero unless they are given.
+func NewUserValues(userValues []string) [NUM_USER_VALUES]frontend.Variable {
+	var values [NUM_USER_VALUES]frontend.Variable
+	for i, value := range userValueStrings(userValues) {
+		values[i] = value
+	}
+	return values
+}
+
+// NewPublicInputs returns the public inputs of the circuit in their order in the public witness:
+// the vkey hash, the committed values digest and the user values.
+func NewPublicInputs(witnessInput WitnessInput) [NUM_PUBLIC_INPUTS]string {
+	var publicInputs [NUM_PUBLIC_INPUTS]string
+	publicInputs[0] = witnessInput.VkeyHash
+	publicInputs[1] = witnessInput.CommitedValuesDigest
+	for i, value := range userValueStrings(witnessInput.UserValues) {
+		publicInputs[2+i] = value
+	}
+	return publicInputs
+}
+
+func userValueStrings(userValues []string) [NUM_USER_VALUES]string {
+	var values [NUM_USER_VALUES]string
+	for i := 0; i < NUM_USER_VALUES; i++ {
+		values[i] = "0"
+		if i < len(userValues) && userValues[i] != "" {
+			values[i] = userValues[i]
+		}
+	}
+	return values
+}
@@ -5,9 +5,12 @@
 //! Although we cast to *mut c_char because the Go signatures can't be immutable, the Go functions
 //! should not modify the strings.
 
-use crate::PlonkBn254Proof;
+use crate::{Groth16Bls12381Proof, Groth16Bn254Proof, PlonkBn254Proof};
 use cfg_if::cfg_if;
-use std::ffi::{c_char, CString};
+use std::{
+    array,
+    ffi::{c_char, CString},
+};
 
 #[allow(warnings, clippy::all)]
 mod bind {
@@ -58,6 +61,7 @@ pub fn verify_plonk_bn254(
     proof: &str,
     vkey_hash: &str,
     committed_values_digest: &str,
+    user_values: &[String],
 ) -> Result<(), String> {
     cfg_if! {
         if #[cfg(feature = "plonk")] {
@@ -66,6 +70,7 @@ pub fn verify_plonk_bn254(
             let vkey_hash = CString::new(vkey_hash).expect("CString::new failed");
             let committed_values_digest =
                 CString::new(committed_values_digest).expect("CString::new failed");
+            let user_values = CString::new(user_values.join(",")).expect("CString::new failed");
 
             let err_ptr = unsafe {
                 bind::VerifyPlonkBn254(
@@ -73,6 +78,7 @@ pub fn verify_plonk_bn254(
                     proof.as_ptr() as *mut c_char,
                     vkey_hash.as_ptr() as *mut c_char,
                     committed_values_digest.as_ptr() as *mut c_char,
+                    user_values.as_ptr() as *mut c_char,
                 )
             };
             if err_ptr.is_null() {
@@ -110,6 +116,154 @@ pub fn test_plonk_bn254(witness_json: &str, constraints_json: &str) {
     }
 }
 
+pub fn prove_groth16_bn254(data_dir: &str, witness_path: &str) -> Groth16Bn254Proof {
+    cfg_if! {
+        if #[cfg(feature = "plonk")] {
+            let data_dir = CString::new(data_dir).expect("CString::new failed");
+            let witness_path = CString::new(witness_path).expect("CString::new failed");
+
+            let proof = unsafe {
+                let proof = bind::ProveGroth16Bn254(
+                    data_dir.as_ptr() as *mut c_char,
+                    witness_path.as_ptr() as *mut c_char,
+                );
+                // Safety: The pointer is returned from the go code and is guaranteed to be valid.
+                *proof
+            };
+
+            proof.into_rust()
+        } else {
+            panic!("plonk feature not enabled");
+        }
+    }
+}
+
+pub fn build_groth16_bn254(data_dir: &str) {
+    cfg_if! {
+        if #[cfg(feature = "plonk")] {
+            let data_dir = CString::new(data_dir).expect("CString::new failed");
+
+            unsafe {
+                bind::BuildGroth16Bn254(data_dir.as_ptr() as *mut c_char);
+            }
+        } else {
+            panic!("plonk feature not enabled");
+        }
+    }
+}
+
+pub fn verify_groth16_bn254(
+    data_dir: &str,
+    proof: &str,
+    vkey_hash: &str,
+    committed_values_digest: &str,
+    user_values: &[String],
+) -> Result<(), String> {
+    cfg_if! {
+        if #[cfg(feature = "plonk")] {
+            let data_dir = CString::new(data_dir).expect("CString::new failed");
+            let proof = CString::new(proof).expect("CString::new failed");
+            let vkey_hash = CString::new(vkey_hash).expect("CString::new failed");
+            let committed_values_digest =
+                CString::new(committed_values_digest).expect("CString::new failed");
+            let user_values = CString::new(user_values.join(",")).expect("CString::new failed");
+
+            let err_ptr = unsafe {
+                bind::VerifyGroth16Bn254(
+                    data_dir.as_ptr() as *mut c_char,
+                    proof.as_ptr() as *mut c_char,
+                    vkey_hash.as_ptr() as *mut c_char,
+                    committed_values_digest.as_ptr() as *mut c_char,
+                    user_values.as_ptr() as *mut c_char,
+                )
+            };
+            if err_ptr.is_null() {
+                Ok(())
+            } else {
+                // Safety: The error message is returned from the go code and is guaranteed to be valid.
+                let err = unsafe { CString::from_raw(err_ptr) };
+                Err(err.into_string().unwrap())
+            }
+        } else {
+            panic!("plonk feature not enabled");
+        }
+    }
+}
+
+pub fn prove_groth16_bls12381(data_dir: &str, witness_path: &str) -> Groth16Bls12381Proof {
+    cfg_if! {
+        if #[cfg(feature = "plonk")] {
+            let data_dir = CString::new(data_dir).expect("CString::new failed");
+            let witness_path = CString::new(witness_path).expect("CString::new failed");
+
+            let proof = unsafe {
+                let proof = bind::ProveGroth16Bls12381(
+                    data_dir.as_ptr() as *mut c_char,
+                    witness_path.as_ptr() as *mut c_char,
+                );
+                // Safety: The pointer is returned from the go code and is guaranteed to be valid.
+                *proof
+            };
+
+            proof.into_rust()
+        } else {
+            panic!("plonk feature not enabled");
+        }
+    }
+}
+
+pub fn build_groth16_bls12381(data_dir: &str) {
+    cfg_if! {
+        if #[cfg(feature = "plonk")] {
+            let data_dir = CString::new(data_dir).expect("CString::new failed");
+
+            unsafe {
+                bind::BuildGroth16Bls12381(data_dir.as_ptr() as *mut c_char);
+            }
+        } else {
+            panic!("plonk feature not enabled");
+        }
+    }
+}
+
+pub fn verify_groth16_bls12381(
+    data_dir: &str,
+    proof: &str,
+    vkey_hash: &str,
+    committed_values_digest: &str,
+    user_values: &[String],
+) -> Result<(), String> {
+    cfg_if! {
+        if #[cfg(feature = "plonk")] {
+            let data_dir = CString::new(data_dir).expect("CString::new failed");
+            let proof = CString::new(proof).expect("CString::new failed");
+            let vkey_hash = CString::new(vkey_hash).expect("CString::new failed");
+            let committed_values_digest =
+                CString::new(committed_values_digest).expect("CString::new failed");
+            let user_values = CString::new(user_values.join(",")).expect("CString::new failed");
+
+            let err_ptr = unsafe {
+                bind::VerifyGroth16Bls12381(
+                    data_dir.as_ptr() as *mut c_char,
+                    proof.as_ptr() as *mut c_char,
+                    vkey_hash.as_ptr() as *mut c_char,
+                    committed_values_digest.as_ptr() as *mut c_char,
+                    user_values.as_ptr() as *mut c_char,
+                )
+            };
+            if err_ptr.is_null() {
+                Ok(())
+            } else {
+                // Safety: The error message is returned from the go code and is guaranteed to be valid.
+                let err = unsafe { CString::from_raw(err_ptr) };
+                Err(err.into_string().unwrap())
+            }
+        } else {
+            panic!("plonk feature not enabled");
+        }
+    }
+}
+
 /// Converts a C string into a Rust String.
 ///
 /// # Safety
@@ -130,10 +284,37 @@ impl C_PlonkBn254Proof {
         // Safety: The raw pointers are not used anymore after converted into Rust strings.
         unsafe {
             PlonkBn254Proof {
-                public_inputs: [
-                    c_char_ptr_to_string(self.PublicInputs[0]),
-                    c_char_ptr_to_string(self.PublicInputs[1]),
-                ],
+                public_inputs: array::from_fn(|i| c_char_ptr_to_string(self.PublicInputs[i])),
+                encoded_proof: c_char_ptr_to_string(self.EncodedProof),
+                raw_proof: c_char_ptr_to_string(self.RawProof),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "plonk")]
+impl C_Groth16Bn254Proof {
+    /// Converts a C Groth16Bn254Proof into a Rust Groth16Bn254Proof, freeing the C strings.
+    fn into_rust(self) -> Groth16Bn254Proof {
+        // Safety: The raw pointers are not used anymore after converted into Rust strings.
+        unsafe {
+            Groth16Bn254Proof {
+                public_inputs: array::from_fn(|i| c_char_ptr_to_string(self.PublicInputs[i])),
+                encoded_proof: c_char_ptr_to_string(self.EncodedProof),
+                raw_proof: c_char_ptr_to_string(self.RawProof),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "plonk")]
+impl C_Groth16Bls12381Proof {
+    /// Converts a C Groth16Bls12381Proof into a Rust Groth16Bls12381Proof, freeing the C strings.
+    fn into_rust(self) -> Groth16Bls12381Proof {
+        // Safety: The raw pointers are not used anymore after converted into Rust strings.
+        unsafe {
+            Groth16Bls12381Proof {
+                public_inputs: array::from_fn(|i| c_char_ptr_to_string(self.PublicInputs[i])),
                 encoded_proof: c_char_ptr_to_string(self.EncodedProof),
                 raw_proof: c_char_ptr_to_string(self.RawProof),
             }
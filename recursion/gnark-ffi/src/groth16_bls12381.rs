@@ -0,0 +1,118 @@
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    ffi::{build_groth16_bls12381, prove_groth16_bls12381, verify_groth16_bls12381},
+    witness::{GnarkWitness, NUM_PUBLIC_INPUTS},
+};
+
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use sp1_recursion_compiler::{
+    constraints::Constraint,
+    ir::{Config, Witness},
+};
+
+/// The name of the verifying key in the layout of the precompiles of EIP-2537, written to the
+/// build directory by [Groth16Bls12381Prover::build].
+pub const GROTH16_EIP2537_VK_FILE: &str = "groth16_vk_eip2537.bin";
+
+/// A prover that can generate proofs with the Groth16 protocol over BLS12-381 using bindings to
+/// Gnark.
+///
+/// The circuit must be built over the scalar field of BLS12-381, as by
+/// `sp1_recursion_compiler::config::OuterBls12381Config`.
+#[derive(Debug, Clone)]
+pub struct Groth16Bls12381Prover;
+
+/// A zero-knowledge proof generated by the Groth16 protocol over BLS12-381 with a hex encoded
+/// gnark Groth16 proof.
+///
+/// The encoded proof is in the layout of the precompiles of EIP-2537: A, B and C, then the
+/// commitments and the proof of knowledge of the commitments, with each element of the base field
+/// padded to 64 bytes.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Groth16Bls12381Proof {
+    pub public_inputs: [String; NUM_PUBLIC_INPUTS],
+    pub encoded_proof: String,
+    pub raw_proof: String,
+}
+
+impl Groth16Bls12381Prover {
+    /// Creates a new [Groth16Bls12381Prover].
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Builds the Groth16 circuit over BLS12-381 locally.
+    ///
+    /// Like the setup of the Groth16 circuit over BN254, the setup is specific to the circuit, so
+    /// the keys written to `build_dir` change every time the circuit is built.
+    pub fn build<C: Config>(constraints: Vec<Constraint>, witness: Witness<C>, build_dir: PathBuf) {
+        let serialized = serde_json::to_string(&constraints).unwrap();
+
+        // Write constraints.
+        let constraints_path = build_dir.join("constraints.json");
+        let mut file = File::create(constraints_path).unwrap();
+        file.write_all(serialized.as_bytes()).unwrap();
+
+        // Write witness.
+        let witness_path = build_dir.join("witness.json");
+        let gnark_witness = GnarkWitness::new(witness);
+        let mut file = File::create(witness_path).unwrap();
+        let serialized = serde_json::to_string(&gnark_witness).unwrap();
+        file.write_all(serialized.as_bytes()).unwrap();
+
+        build_groth16_bls12381(build_dir.to_str().unwrap());
+    }
+
+    /// Generates a Groth16 proof over BLS12-381 with the artifacts in `build_dir`.
+    pub fn prove<C: Config>(
+        &self,
+        witness: Witness<C>,
+        build_dir: PathBuf,
+    ) -> Groth16Bls12381Proof {
+        // Write witness.
+        let mut witness_file = tempfile::NamedTempFile::new().unwrap();
+        let gnark_witness = GnarkWitness::new(witness);
+        let serialized = serde_json::to_string(&gnark_witness).unwrap();
+        witness_file.write_all(serialized.as_bytes()).unwrap();
+
+        prove_groth16_bls12381(
+            build_dir.to_str().unwrap(),
+            witness_file.path().to_str().unwrap(),
+        )
+    }
+
+    /// Verify a Groth16 proof over BLS12-381 and verify that the supplied vkey_hash,
+    /// committed_values_digest and user_values match.
+    pub fn verify(
+        &self,
+        proof: &Groth16Bls12381Proof,
+        vkey_hash: &BigUint,
+        committed_values_digest: &BigUint,
+        user_values: &[BigUint],
+        build_dir: &Path,
+    ) {
+        verify_groth16_bls12381(
+            build_dir.to_str().unwrap(),
+            &proof.raw_proof,
+            &vkey_hash.to_string(),
+            &committed_values_digest.to_string(),
+            &user_values
+                .iter()
+                .map(BigUint::to_string)
+                .collect::<Vec<_>>(),
+        )
+        .expect("failed to verify proof")
+    }
+}
+
+impl Default for Groth16Bls12381Prover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
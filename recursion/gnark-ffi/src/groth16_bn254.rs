@@ -0,0 +1,121 @@
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    ffi::{build_groth16_bn254, prove_groth16_bn254, verify_groth16_bn254},
+    witness::{GnarkWitness, NUM_PUBLIC_INPUTS},
+};
+
+use num_bigint::BigUint;
+use serde::{Deserialize, Serialize};
+use sp1_recursion_compiler::{
+    constraints::Constraint,
+    ir::{Config, Witness},
+};
+
+/// The name of the verifying key of the Solana verifier, written to the build directory by
+/// [Groth16Bn254Prover::build].
+pub const GROTH16_SOLANA_VK_FILE: &str = "groth16_vk_solana.bin";
+
+/// A prover that can generate proofs with the Groth16 protocol using bindings to Gnark.
+#[derive(Debug, Clone)]
+pub struct Groth16Bn254Prover;
+
+/// A zero-knowledge proof generated by the Groth16 protocol with a hex encoded gnark Groth16
+/// proof.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Groth16Bn254Proof {
+    pub public_inputs: [String; NUM_PUBLIC_INPUTS],
+    pub encoded_proof: String,
+    pub raw_proof: String,
+}
+
+impl Groth16Bn254Prover {
+    /// Creates a new [Groth16Bn254Prover].
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Builds the Groth16 circuit locally.
+    ///
+    /// Unlike the setup of PLONK, the setup of Groth16 is specific to the circuit, so the keys
+    /// written to `build_dir` change every time the circuit is built.
+    pub fn build<C: Config>(constraints: Vec<Constraint>, witness: Witness<C>, build_dir: PathBuf) {
+        let serialized = serde_json::to_string(&constraints).unwrap();
+
+        // Write constraints.
+        let constraints_path = build_dir.join("constraints.json");
+        let mut file = File::create(constraints_path).unwrap();
+        file.write_all(serialized.as_bytes()).unwrap();
+
+        // Write witness.
+        let witness_path = build_dir.join("witness.json");
+        let gnark_witness = GnarkWitness::new(witness);
+        let mut file = File::create(witness_path).unwrap();
+        let serialized = serde_json::to_string(&gnark_witness).unwrap();
+        file.write_all(serialized.as_bytes()).unwrap();
+
+        build_groth16_bn254(build_dir.to_str().unwrap());
+
+        // Write the corresponding asset files to the build dir.
+        let sp1_verifier_path = build_dir.join("SP1Verifier.sol");
+        let sp1_verifier_str = include_str!("../assets/SP1Groth16Verifier.txt");
+        let mut sp1_verifier_file = File::create(sp1_verifier_path).unwrap();
+        sp1_verifier_file
+            .write_all(sp1_verifier_str.as_bytes())
+            .unwrap();
+
+        let interface_sp1_verifier_path = build_dir.join("ISP1Verifier.sol");
+        let interface_sp1_verifier_str = include_str!("../assets/ISP1Verifier.txt");
+        let mut interface_sp1_verifier_file = File::create(interface_sp1_verifier_path).unwrap();
+        interface_sp1_verifier_file
+            .write_all(interface_sp1_verifier_str.as_bytes())
+            .unwrap();
+    }
+
+    /// Generates a Groth16 proof with the artifacts in `build_dir`.
+    pub fn prove<C: Config>(&self, witness: Witness<C>, build_dir: PathBuf) -> Groth16Bn254Proof {
+        // Write witness.
+        let mut witness_file = tempfile::NamedTempFile::new().unwrap();
+        let gnark_witness = GnarkWitness::new(witness);
+        let serialized = serde_json::to_string(&gnark_witness).unwrap();
+        witness_file.write_all(serialized.as_bytes()).unwrap();
+
+        prove_groth16_bn254(
+            build_dir.to_str().unwrap(),
+            witness_file.path().to_str().unwrap(),
+        )
+    }
+
+    /// Verify a Groth16 proof and verify that the supplied vkey_hash, committed_values_digest and
+    /// user_values match.
+    pub fn verify(
+        &self,
+        proof: &Groth16Bn254Proof,
+        vkey_hash: &BigUint,
+        committed_values_digest: &BigUint,
+        user_values: &[BigUint],
+        build_dir: &Path,
+    ) {
+        verify_groth16_bn254(
+            build_dir.to_str().unwrap(),
+            &proof.raw_proof,
+            &vkey_hash.to_string(),
+            &committed_values_digest.to_string(),
+            &user_values
+                .iter()
+                .map(BigUint::to_string)
+                .collect::<Vec<_>>(),
+        )
+        .expect("failed to verify proof")
+    }
+}
+
+impl Default for Groth16Bn254Prover {
+    fn default() -> Self {
+        Self::new()
+    }
+}
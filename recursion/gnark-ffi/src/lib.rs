@@ -1,7 +1,12 @@
 mod babybear;
 pub mod ffi;
+pub mod groth16_bls12381;
+pub mod groth16_bn254;
 pub mod plonk_bn254;
+pub mod solidity;
 pub mod witness;
 
+pub use groth16_bls12381::*;
+pub use groth16_bn254::*;
 pub use plonk_bn254::*;
 pub use witness::*;
@@ -6,7 +6,7 @@ use std::{
 
 use crate::{
     ffi::{build_plonk_bn254, prove_plonk_bn254, test_plonk_bn254, verify_plonk_bn254},
-    witness::GnarkWitness,
+    witness::{GnarkWitness, NUM_PUBLIC_INPUTS},
 };
 
 use num_bigint::BigUint;
@@ -23,7 +23,7 @@ pub struct PlonkBn254Prover;
 /// A zero-knowledge proof generated by the PLONK protocol with a Base64 encoded gnark PLONK proof.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PlonkBn254Proof {
-    pub public_inputs: [String; 2],
+    pub public_inputs: [String; NUM_PUBLIC_INPUTS],
     pub encoded_proof: String,
     pub raw_proof: String,
 }
@@ -109,12 +109,14 @@ impl PlonkBn254Prover {
         )
     }
 
-    /// Verify a PLONK proof and verify that the supplied vkey_hash and committed_values_digest match.
+    /// Verify a PLONK proof and verify that the supplied vkey_hash, committed_values_digest and
+    /// user_values match.
     pub fn verify(
         &self,
         proof: &PlonkBn254Proof,
         vkey_hash: &BigUint,
         committed_values_digest: &BigUint,
+        user_values: &[BigUint],
         build_dir: &Path,
     ) {
         verify_plonk_bn254(
@@ -122,6 +124,10 @@ impl PlonkBn254Prover {
             &proof.raw_proof,
             &vkey_hash.to_string(),
             &committed_values_digest.to_string(),
+            &user_values
+                .iter()
+                .map(BigUint::to_string)
+                .collect::<Vec<_>>(),
         )
         .expect("failed to verify proof")
     }
@@ -0,0 +1,147 @@
+//! Solidity verifiers of the Groth16 proofs of a single program.
+//!
+//! A program verifier is a Foundry project with the verifier of the Groth16 circuit, exported by
+//! Gnark when the circuit is built, and a contract which embeds the verification key of the
+//! program. The project pins the compiler and its settings, so that its bytecode is the same on
+//! every machine, and can be checked against a deployed contract.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Error, ErrorKind},
+    path::Path,
+};
+
+/// The version of solc the program verifiers are built with.
+pub const SOLC_VERSION: &str = "0.8.25";
+
+/// The name of the verifier exported by Gnark in the directory of the Groth16 artifacts.
+pub const GROTH16_VERIFIER_CONTRACT: &str = "Groth16Verifier.sol";
+
+/// The calldata format of `verifyProgramProof` in a program verifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VerifierCalldata {
+    /// `verifyProgramProof(bytes publicValues, bytes proofBytes)`, where the proof bytes are the
+    /// encoded proof, as for `ISP1Verifier.verifyProof`.
+    #[default]
+    Standard,
+    /// `verifyProgramProof(bytes publicValues, uint256[8] proof, uint256[2] commitments,
+    /// uint256[2] commitmentPok)`, which are the twelve words of the encoded proof, without the
+    /// offset and the length of a dynamic array.
+    Minimal,
+}
+
+/// The sources of the verifier of a program, by their path in the project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramVerifier {
+    pub sources: BTreeMap<String, String>,
+}
+
+impl ProgramVerifier {
+    /// Generates the verifier of the program with the verification key `program_vkey`, a `0x`
+    /// prefixed hex string of 32 bytes, from the Groth16 artifacts in `build_dir`.
+    pub fn new(
+        build_dir: &Path,
+        program_vkey: &str,
+        calldata: VerifierCalldata,
+    ) -> io::Result<Self> {
+        let digits = program_vkey.strip_prefix("0x").unwrap_or_default();
+        if digits.len() != 64 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "invalid program vkey {}, expected 0x and 64 hex digits",
+                    program_vkey
+                ),
+            ));
+        }
+        let groth16_verifier = fs::read_to_string(build_dir.join(GROTH16_VERIFIER_CONTRACT))?;
+
+        let mut sources = BTreeMap::new();
+        sources.insert(
+            format!("src/{}", GROTH16_VERIFIER_CONTRACT),
+            groth16_verifier,
+        );
+        let program_verifier = match calldata {
+            VerifierCalldata::Standard => {
+                sources.insert(
+                    "src/SP1Verifier.sol".to_string(),
+                    include_str!("../assets/SP1Groth16Verifier.txt").to_string(),
+                );
+                sources.insert(
+                    "src/ISP1Verifier.sol".to_string(),
+                    include_str!("../assets/ISP1Verifier.txt").to_string(),
+                );
+                include_str!("../assets/SP1ProgramVerifier.txt")
+            }
+            VerifierCalldata::Minimal => include_str!("../assets/SP1ProgramVerifierMinimal.txt"),
+        };
+        sources.insert(
+            "src/SP1ProgramVerifier.sol".to_string(),
+            program_verifier.replace("{{PROGRAM_VKEY}}", &program_vkey.to_ascii_lowercase()),
+        );
+        sources.insert(
+            "foundry.toml".to_string(),
+            include_str!("../assets/foundry.txt").replace("{{SOLC_VERSION}}", SOLC_VERSION),
+        );
+        Ok(Self { sources })
+    }
+
+    /// Writes the project to `out_dir`.
+    pub fn write(&self, out_dir: &Path) -> io::Result<()> {
+        for (path, source) in self.sources.iter() {
+            let path = out_dir.join(path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, source)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VKEY: &str = "0x00a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f";
+
+    #[test]
+    fn test_program_verifier() {
+        let build_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            build_dir.path().join(GROTH16_VERIFIER_CONTRACT),
+            "contract Groth16Verifier {}",
+        )
+        .unwrap();
+
+        let standard =
+            ProgramVerifier::new(build_dir.path(), VKEY, VerifierCalldata::Standard).unwrap();
+        let program_verifier = &standard.sources["src/SP1ProgramVerifier.sol"];
+        assert!(program_verifier.contains(&format!("PROGRAM_VKEY = {};", VKEY)));
+        assert!(program_verifier.contains("bytes calldata proofBytes"));
+        assert!(standard.sources.contains_key("src/SP1Verifier.sol"));
+        assert!(standard.sources["foundry.toml"].contains(SOLC_VERSION));
+
+        let minimal =
+            ProgramVerifier::new(build_dir.path(), VKEY, VerifierCalldata::Minimal).unwrap();
+        assert!(minimal.sources["src/SP1ProgramVerifier.sol"].contains("uint256[8] calldata proof"));
+        assert!(!minimal.sources.contains_key("src/SP1Verifier.sol"));
+
+        // The sources only depend on the artifacts and the vkey.
+        let again =
+            ProgramVerifier::new(build_dir.path(), VKEY, VerifierCalldata::Standard).unwrap();
+        assert_eq!(again, standard);
+
+        let out_dir = tempfile::tempdir().unwrap();
+        standard.write(out_dir.path()).unwrap();
+        assert_eq!(
+            fs::read_to_string(out_dir.path().join("src/Groth16Verifier.sol")).unwrap(),
+            "contract Groth16Verifier {}"
+        );
+
+        assert!(
+            ProgramVerifier::new(build_dir.path(), "0x1234", VerifierCalldata::Standard).is_err()
+        );
+    }
+}
@@ -9,6 +9,13 @@ use serde::Serialize;
 use sp1_recursion_compiler::ir::Config;
 use sp1_recursion_compiler::ir::Witness;
 
+/// The number of user values among the public inputs of the circuits, after the vkey hash and the
+/// committed values digest.
+pub const NUM_USER_VALUES: usize = 8;
+
+/// The number of public inputs of the circuits.
+pub const NUM_PUBLIC_INPUTS: usize = 2 + NUM_USER_VALUES;
+
 /// A witness that can be used to initialize values for witness generation inside Gnark.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GnarkWitness {
@@ -17,6 +24,7 @@ pub struct GnarkWitness {
     pub exts: Vec<Vec<String>>,
     pub vkey_hash: String,
     pub commited_values_digest: String,
+    pub user_values: Vec<String>,
 }
 
 impl GnarkWitness {
@@ -51,6 +59,11 @@ impl GnarkWitness {
                 .commited_values_digest
                 .as_canonical_biguint()
                 .to_string(),
+            user_values: witness
+                .user_values
+                .into_iter()
+                .map(|w| w.as_canonical_biguint().to_string())
+                .collect(),
         }
     }
 
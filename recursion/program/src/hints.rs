@@ -625,6 +625,7 @@ impl<'a, A: MachineAir<BabyBear>> Hintable<C>
 
         let sp1_vk = VerifyingKeyHint::<'a, BabyBearPoseidon2, RiscvAir<_>>::read(builder);
         let committed_value_digest = Vec::<Vec<InnerVal>>::read(builder);
+        let user_values = Vec::<Vec<InnerVal>>::read(builder);
         let deferred_proofs_digest = Vec::<InnerVal>::read(builder);
         let leaf_challenger = DuplexChallenger::<InnerVal, InnerPerm, 16, 8>::read(builder);
         let end_pc = InnerVal::read(builder);
@@ -637,6 +638,7 @@ impl<'a, A: MachineAir<BabyBear>> Hintable<C>
             is_complete,
             sp1_vk,
             committed_value_digest,
+            user_values,
             deferred_proofs_digest,
             leaf_challenger,
             end_pc,
@@ -665,6 +667,12 @@ impl<'a, A: MachineAir<BabyBear>> Hintable<C>
             .map(|w| w.0.to_vec())
             .collect::<Vec<_>>();
 
+        let user_values = self
+            .user_values
+            .iter()
+            .map(|w| w.0.to_vec())
+            .collect::<Vec<_>>();
+
         stream.extend(compress_vk_hint.write());
         stream.extend(proof_hints.write());
         stream.extend(self.start_reconstruct_deferred_digest.write());
@@ -672,6 +680,7 @@ impl<'a, A: MachineAir<BabyBear>> Hintable<C>
 
         stream.extend(sp1_vk_hint.write());
         stream.extend(committed_value_digest.write());
+        stream.extend(user_values.write());
         stream.extend(self.deferred_proofs_digest.write());
         stream.extend(self.leaf_challenger.write());
         stream.extend(self.end_pc.write());
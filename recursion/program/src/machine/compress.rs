@@ -10,7 +10,7 @@ use p3_commit::TwoAdicMultiplicativeCoset;
 use p3_field::{AbstractField, PrimeField32, TwoAdicField};
 use serde::{Deserialize, Serialize};
 use sp1_core::air::MachineAir;
-use sp1_core::air::{Word, POSEIDON_NUM_WORDS, PV_DIGEST_NUM_WORDS};
+use sp1_core::air::{Word, POSEIDON_NUM_WORDS, PV_DIGEST_NUM_WORDS, USER_PV_NUM_WORDS};
 use sp1_core::stark::StarkMachine;
 use sp1_core::stark::{Com, ShardProof, StarkGenericConfig, StarkVerifyingKey};
 use sp1_core::utils::BabyBearPoseidon2;
@@ -169,6 +169,8 @@ where
         let mut leaf_challenger = DuplexChallengerVariable::new(builder);
         let committed_value_digest: [Word<Felt<_>>; PV_DIGEST_NUM_WORDS] =
             array::from_fn(|_| Word(array::from_fn(|_| builder.uninit())));
+        let user_values: [Word<Felt<_>>; USER_PV_NUM_WORDS] =
+            array::from_fn(|_| Word(array::from_fn(|_| builder.uninit())));
         let deferred_proofs_digest: [Felt<_>; POSEIDON_NUM_WORDS] =
             array::from_fn(|_| builder.uninit());
         let reconstruct_deferred_digest: [Felt<_>; POSEIDON_NUM_WORDS] =
@@ -314,7 +316,7 @@ where
                     current_public_values.start_reconstruct_challenger,
                 );
 
-                // Assign the commited values and deferred proof digests.
+                // Assign the commited values and deferred proof digests, and the user values.
                 for (word, current_word) in committed_value_digest
                     .iter()
                     .zip_eq(current_public_values.committed_value_digest.iter())
@@ -324,6 +326,15 @@ where
                     }
                 }
 
+                for (word, current_word) in user_values
+                    .iter()
+                    .zip_eq(current_public_values.user_values.iter())
+                {
+                    for (byte, current_byte) in word.0.iter().zip_eq(current_word.0.iter()) {
+                        builder.assign(*byte, *current_byte);
+                    }
+                }
+
                 for (digest, current_digest) in deferred_proofs_digest
                     .iter()
                     .zip_eq(current_public_values.deferred_proofs_digest.iter())
@@ -395,6 +406,16 @@ where
                 }
             }
 
+            // Assert that the user values are the same.
+            for (word, current_word) in user_values
+                .iter()
+                .zip_eq(current_public_values.user_values.iter())
+            {
+                for (byte, current_byte) in word.0.iter().zip_eq(current_word.0.iter()) {
+                    builder.assert_felt_eq(*byte, *current_byte);
+                }
+            }
+
             // Assert that the deferred proof digests are the same.
             for (digest, current_digest) in deferred_proofs_digest
                 .iter()
@@ -457,6 +478,8 @@ where
         reduce_public_values.deferred_proofs_digest = deferred_proofs_digest;
         // Assign the committed value digests.
         reduce_public_values.committed_value_digest = committed_value_digest;
+        // Assign the user values.
+        reduce_public_values.user_values = user_values;
         // Assign the cumulative sum.
         reduce_public_values.cumulative_sum = cumulative_sum;
 
@@ -6,8 +6,8 @@ use itertools::Itertools;
 use p3_baby_bear::BabyBear;
 use p3_commit::TwoAdicMultiplicativeCoset;
 use p3_field::{AbstractField, PrimeField32, TwoAdicField};
-use sp1_core::air::{MachineAir, PublicValues};
-use sp1_core::air::{Word, POSEIDON_NUM_WORDS, PV_DIGEST_NUM_WORDS};
+use sp1_core::air::{MachineAir, PublicValues, PV_VERSION};
+use sp1_core::air::{Word, POSEIDON_NUM_WORDS, PV_DIGEST_NUM_WORDS, USER_PV_NUM_WORDS};
 use sp1_core::stark::StarkMachine;
 use sp1_core::stark::{Com, RiscvAir, ShardProof, StarkGenericConfig, StarkVerifyingKey};
 use sp1_core::utils::BabyBearPoseidon2;
@@ -145,6 +145,8 @@ where
         // same for all proofs.
         let committed_value_digest: [Word<Felt<_>>; PV_DIGEST_NUM_WORDS] =
             array::from_fn(|_| Word(array::from_fn(|_| builder.uninit())));
+        let user_values: [Word<Felt<_>>; USER_PV_NUM_WORDS] =
+            array::from_fn(|_| Word(array::from_fn(|_| builder.uninit())));
         let deferred_proofs_digest: [Felt<_>; POSEIDON_NUM_WORDS] =
             array::from_fn(|_| builder.uninit());
 
@@ -160,6 +162,11 @@ where
         let cumulative_sum: Ext<_, _> = builder.eval(C::EF::zero().cons());
         let current_pc: Felt<_> = builder.uninit();
         let exit_code: Felt<_> = builder.uninit();
+
+        // Compute vk digest.
+        let vk_digest = hash_vkey(builder, &vk);
+        let vk_digest: [Felt<_>; DIGEST_SIZE] = array::from_fn(|i| builder.get(&vk_digest, i));
+
         // Verify proofs, validate transitions, and update accumulation variables.
         builder.range(0, shard_proofs.len()).for_each(|i, builder| {
             // Load the proof.
@@ -184,6 +191,14 @@ where
             }
             let public_values = PublicValues::<Word<Felt<_>>, Felt<_>>::from_vec(pv_elements);
 
+            // Reject public values of another layout.
+            builder.assert_felt_eq(public_values.version, C::F::from_canonical_u32(PV_VERSION));
+
+            // Bind the shard to the program of the verifying key.
+            for (digest, pv_digest) in vk_digest.iter().zip(public_values.vk_digest.iter()) {
+                builder.assert_felt_eq(*digest, *pv_digest);
+            }
+
             // If this is the first proof in the batch, verify the initial conditions.
             builder.if_eq(i, C::N::zero()).then(|builder| {
                 // Initialize the values of accumulated variables.
@@ -206,6 +221,15 @@ where
                     }
                 }
 
+                // User values.
+                for (word, first_word) in
+                    user_values.iter().zip_eq(public_values.user_values.iter())
+                {
+                    for (byte, first_byte) in word.0.iter().zip_eq(first_word.0.iter()) {
+                        builder.assign(*byte, *first_byte);
+                    }
+                }
+
                 // Deferred proofs digests.
                 for (digest, first_digest) in deferred_proofs_digest
                     .iter()
@@ -228,11 +252,15 @@ where
                 builder.assert_felt_eq(public_values.start_pc, vk.pc_start);
 
                 // Assert that the initial challenger is equal to a fresh challenger observing the
-                // verifier key and the initial pc.
+                // verifier key, the initial pc and the domain separator of the machine.
                 let mut first_initial_challenger = DuplexChallengerVariable::new(builder);
 
                 first_initial_challenger.observe(builder, vk.commitment.clone());
                 first_initial_challenger.observe(builder, vk.pc_start);
+                for element in machine.domain_separator().elements::<C::F>() {
+                    let element: Felt<_> = builder.eval(element);
+                    first_initial_challenger.observe(builder, element);
+                }
 
                 // Make sure the start reconstruct challenger is correct, since we will
                 // commit to it in public values.
@@ -251,6 +279,14 @@ where
                 }
             }
 
+            // Assert that the user values are all the same.
+            for (word, current_word) in user_values.iter().zip_eq(public_values.user_values.iter())
+            {
+                for (byte, current_byte) in word.0.iter().zip_eq(current_word.0.iter()) {
+                    builder.assert_felt_eq(*byte, *current_byte);
+                }
+            }
+
             // Assert that the start_pc of the proof is equal to the current pc.
             builder.assert_felt_eq(current_pc, public_values.start_pc);
             // Assert that the start_pc is not zero (this means program has halted in a non-last
@@ -300,10 +336,6 @@ where
 
         // Write all values to the public values struct and commit to them.
 
-        // Compute vk digest.
-        let vk_digest = hash_vkey(builder, &vk);
-        let vk_digest: [Felt<_>; DIGEST_SIZE] = array::from_fn(|i| builder.get(&vk_digest, i));
-
         // Collect values for challenges.
         let initial_challenger_public_values =
             get_challenger_public_values(builder, &initial_reconstruct_challenger);
@@ -327,6 +359,7 @@ where
         let is_complete_felt = var2felt(builder, is_complete);
 
         recursion_public_values.committed_value_digest = committed_value_digest;
+        recursion_public_values.user_values = user_values;
         recursion_public_values.deferred_proofs_digest = deferred_proofs_digest;
         recursion_public_values.start_pc = start_pc;
         recursion_public_values.next_pc = current_pc;
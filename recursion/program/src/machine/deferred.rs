@@ -50,6 +50,7 @@ where
     pub sp1_vk: &'a StarkVerifyingKey<SC>,
     pub sp1_machine: &'a StarkMachine<SC, RiscvAir<SC::Val>>,
     pub committed_value_digest: Vec<Word<SC::Val>>,
+    pub user_values: Vec<Word<SC::Val>>,
     pub deferred_proofs_digest: Vec<SC::Val>,
     pub leaf_challenger: SC::Challenger,
     pub end_pc: SC::Val,
@@ -69,6 +70,7 @@ pub struct SP1DeferredMemoryLayoutVariable<C: Config> {
 
     pub sp1_vk: VerifyingKeyVariable<C>,
     pub committed_value_digest: Array<C, Array<C, Felt<C::F>>>,
+    pub user_values: Array<C, Array<C, Felt<C::F>>>,
     pub deferred_proofs_digest: Array<C, Felt<C::F>>,
     pub leaf_challenger: DuplexChallengerVariable<C>,
     pub end_pc: Felt<C::F>,
@@ -132,6 +134,7 @@ where
 
             sp1_vk,
             committed_value_digest,
+            user_values,
             deferred_proofs_digest,
             leaf_challenger,
             end_pc,
@@ -273,6 +276,12 @@ where
             public_word.0 = array::from_fn(|j| builder.get(&hinted_word, j));
         }
 
+        // Set the user values to be the hinted values.
+        for (i, public_word) in deferred_public_values.user_values.iter_mut().enumerate() {
+            let hinted_word = builder.get(&user_values, i);
+            public_word.0 = array::from_fn(|j| builder.get(&hinted_word, j));
+        }
+
         // Set the deferred proof digest to be the hitned value.
         deferred_public_values.deferred_proofs_digest =
             core::array::from_fn(|i| builder.get(&deferred_proofs_digest, i));
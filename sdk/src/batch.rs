@@ -0,0 +1,147 @@
+//! Proving many programs and inputs concurrently.
+
+use std::collections::{HashMap, VecDeque};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+
+use anyhow::{anyhow, Result};
+use sp1_prover::{SP1ProvingKey, SP1Stdin, SP1VerifyingKey};
+
+use crate::provers::{Prover, ProverType};
+use crate::{CachedProofKind, ProofCache, SP1Proof, VkeyRegistry};
+
+/// The number of proofs of a batch that the network prover proves at once by default.
+pub const NETWORK_BATCH_CONCURRENCY: usize = 32;
+
+/// The number of proofs of a batch that the local prover proves at once by default.
+///
+/// A proof already uses all the cores, but a second one keeps them busy during the sequential
+/// phases of the other, such as the execution, for the cost of the memory of two proofs.
+pub const LOCAL_BATCH_CONCURRENCY: usize = 2;
+
+/// The number of proofs of a batch proven at once by default by `prover`.
+pub fn default_batch_concurrency(prover: ProverType) -> usize {
+    match prover {
+        ProverType::Local => LOCAL_BATCH_CONCURRENCY,
+        ProverType::Mock => thread::available_parallelism().map_or(1, |n| n.get()),
+        ProverType::Network | ProverType::Custom => NETWORK_BATCH_CONCURRENCY,
+    }
+}
+
+/// A proof of a batch, or the error of proving it.
+pub struct BatchProof {
+    /// The index of the program and the input of the proof in the batch.
+    pub index: usize,
+    /// The verifying key of the program, or `None` if its setup failed.
+    pub vk: Option<SP1VerifyingKey>,
+    /// The proof.
+    pub result: Result<SP1Proof>,
+}
+
+/// The proofs of a batch, in the order in which they complete.
+///
+/// Dropping the iterator before the batch completes does not cancel the proofs which already
+/// started, but no other proof of the batch starts.
+pub struct BatchProofs {
+    receiver: Receiver<BatchProof>,
+    queue: Arc<Mutex<VecDeque<BatchJob>>>,
+}
+
+impl Iterator for BatchProofs {
+    type Item = BatchProof;
+
+    fn next(&mut self) -> Option<BatchProof> {
+        self.receiver.recv().ok()
+    }
+}
+
+impl Drop for BatchProofs {
+    fn drop(&mut self) {
+        self.queue.lock().unwrap().clear();
+    }
+}
+
+/// The keys of a program of a batch, which are set up once by the first proof of the program.
+type BatchKeys = Arc<OnceLock<(SP1ProvingKey, SP1VerifyingKey)>>;
+
+struct BatchJob {
+    index: usize,
+    elf: Arc<Vec<u8>>,
+    keys: BatchKeys,
+    stdin: SP1Stdin,
+}
+
+/// Proves the programs and inputs of `jobs` with `concurrency` proofs at once. See
+/// [crate::ProverClient::prove_batch].
+pub(crate) fn prove_batch(
+    prover: Arc<dyn Prover>,
+    proof_cache: Option<ProofCache>,
+    vkey_registry: Option<VkeyRegistry>,
+    jobs: Vec<(Vec<u8>, SP1Stdin)>,
+    concurrency: usize,
+) -> BatchProofs {
+    // The jobs of the same program share its keys.
+    let mut programs: HashMap<Vec<u8>, (Arc<Vec<u8>>, BatchKeys)> = HashMap::new();
+    let nb_jobs = jobs.len();
+    let queue = jobs
+        .into_iter()
+        .enumerate()
+        .map(|(index, (elf, stdin))| {
+            let (elf, keys) = programs
+                .entry(elf)
+                .or_insert_with_key(|elf| (Arc::new(elf.clone()), Arc::default()))
+                .clone();
+            BatchJob {
+                index,
+                elf,
+                keys,
+                stdin,
+            }
+        })
+        .collect::<VecDeque<_>>();
+    let queue = Arc::new(Mutex::new(queue));
+
+    let (sender, receiver) = channel();
+    for _ in 0..concurrency.clamp(1, nb_jobs.max(1)) {
+        let prover = prover.clone();
+        let proof_cache = proof_cache.clone();
+        let vkey_registry = vkey_registry.clone();
+        let queue = queue.clone();
+        let sender = sender.clone();
+        thread::spawn(move || loop {
+            let Some(job) = queue.lock().unwrap().pop_front() else {
+                return;
+            };
+            // A panic of a proof is its error, so that it does not end the batch.
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                let (pk, _) = job.keys.get_or_init(|| prover.setup(&job.elf));
+                if let Some(registry) = &vkey_registry {
+                    registry.check_elf(&pk.elf, &pk.vk)?;
+                }
+                match &proof_cache {
+                    Some(cache) => cache.get_or_prove(
+                        prover.id(),
+                        CachedProofKind::Core,
+                        &pk.elf,
+                        job.stdin,
+                        |stdin| prover.prove(pk, stdin),
+                    ),
+                    None => prover.prove(pk, job.stdin),
+                }
+            }))
+            .unwrap_or_else(|_| Err(anyhow!("the proof of job {} panicked", job.index)));
+            let proof = BatchProof {
+                index: job.index,
+                vk: job.keys.get().map(|(_, vk)| vk.clone()),
+                result,
+            };
+            if sender.send(proof).is_err() {
+                return;
+            }
+        });
+    }
+
+    BatchProofs { receiver, queue }
+}
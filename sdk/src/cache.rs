@@ -0,0 +1,135 @@
+use std::{
+    env,
+    fmt::Debug,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+use sp1_core::air::PV_VERSION;
+use sp1_prover::install::PLONK_BN254_ARTIFACTS_COMMIT;
+use sp1_prover::SP1Stdin;
+
+use crate::{provers::ProverType, SP1ProofWithPublicValues};
+
+/// The environment variable which sets the directory of the proof cache of [crate::ProverClient].
+pub const PROOF_CACHE_DIR_ENV: &str = "SP1_PROOF_CACHE_DIR";
+
+/// The kind of a cached proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachedProofKind {
+    Core,
+    Compressed,
+    Plonk,
+    Groth16,
+    Groth16Bls12381,
+}
+
+impl CachedProofKind {
+    fn name(self) -> &'static str {
+        match self {
+            CachedProofKind::Core => "core",
+            CachedProofKind::Compressed => "compressed",
+            CachedProofKind::Plonk => "plonk",
+            CachedProofKind::Groth16 => "groth16",
+            CachedProofKind::Groth16Bls12381 => "groth16_bls12381",
+        }
+    }
+}
+
+/// A cache of proofs in a directory, keyed by the hashes of the ELF and the inputs, the kind of
+/// proof and the prover which generated it.
+///
+/// Identical requests, such as those of CI runs and retried pipelines, are then served from the
+/// cache instead of being proven again. Entries also depend on the version of the proof format, so
+/// upgrading SP1 does not return stale proofs.
+#[derive(Debug, Clone)]
+pub struct ProofCache {
+    dir: PathBuf,
+}
+
+impl ProofCache {
+    /// Creates a cache in `dir`, which is created when the first proof is stored.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Creates a cache in the directory set by the `SP1_PROOF_CACHE_DIR` environment variable, if
+    /// it is set.
+    pub fn from_env() -> Option<Self> {
+        env::var(PROOF_CACHE_DIR_ENV).ok().map(Self::new)
+    }
+
+    /// The directory of the cache.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the proof of `elf` on `stdin` from the cache, or generates it with `prove` and
+    /// stores it.
+    ///
+    /// Streamed input is buffered first, since it is part of the key. An entry which cannot be
+    /// read is proven again and overwritten.
+    pub fn get_or_prove<P, F>(
+        &self,
+        prover: ProverType,
+        kind: CachedProofKind,
+        elf: &[u8],
+        mut stdin: SP1Stdin,
+        prove: F,
+    ) -> Result<SP1ProofWithPublicValues<P>>
+    where
+        P: Debug + Clone + Serialize + DeserializeOwned,
+        F: FnOnce(SP1Stdin) -> Result<SP1ProofWithPublicValues<P>>,
+    {
+        stdin.buffer_stream()?;
+        let path = self.path(prover, kind, elf, &stdin)?;
+        if path.exists() {
+            match SP1ProofWithPublicValues::<P>::load(&path) {
+                Ok(proof) => {
+                    log::info!("Loaded the {} proof from {}", kind.name(), path.display());
+                    return Ok(proof);
+                }
+                Err(e) => log::warn!("Ignoring the cached proof {}: {}", path.display(), e),
+            }
+        }
+
+        let proof = prove(stdin)?;
+        self.store(&path, &proof)?;
+        Ok(proof)
+    }
+
+    /// The path of the entry of a proof.
+    fn path(
+        &self,
+        prover: ProverType,
+        kind: CachedProofKind,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+    ) -> Result<PathBuf> {
+        let stdin_bytes = bincode::serialize(stdin).context("failed to serialize the stdin")?;
+        let mut hasher = Sha256::new();
+        hasher.update(PLONK_BN254_ARTIFACTS_COMMIT.as_bytes());
+        hasher.update(PV_VERSION.to_le_bytes());
+        hasher.update(format!("{:?}", prover).as_bytes());
+        hasher.update(kind.name().as_bytes());
+        hasher.update(Sha256::digest(elf));
+        hasher.update(Sha256::digest(stdin_bytes));
+        let key = hex::encode(hasher.finalize());
+        Ok(self.dir.join(format!("{}-{}.bin", kind.name(), key)))
+    }
+
+    /// Stores a proof in the format of [SP1ProofWithPublicValues::save], writing it to a temporary
+    /// file first so that a reader never sees a partially written entry.
+    fn store<P>(&self, path: &Path, proof: &SP1ProofWithPublicValues<P>) -> Result<()>
+    where
+        P: Debug + Clone + Serialize + DeserializeOwned,
+    {
+        std::fs::create_dir_all(&self.dir).context("failed to create the proof cache directory")?;
+        let file = tempfile::NamedTempFile::new_in(&self.dir)?;
+        proof.save(file.path())?;
+        file.persist(path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+//! # Program Chains
+//!
+//! A chain is a pipeline of guest programs where the public values committed by each program are
+//! the first input of the next one, e.g. "parse block → execute txs → compute state root".
+//!
+//! Every program after the first reads its predecessor's outputs with
+//! `sp1_zkvm::io::read_chained`, which verifies the compressed proof of the predecessor inside the
+//! zkVM and commits the SHA-256 digest of its outputs followed by its verifying key digest as the
+//! first 64 bytes of its own public values. The verification of the predecessor's proof is deferred
+//! to the recursive verifier, so every compressed proof of a chain also proves the links before
+//! it, and [ProverClient::verify_chain] checks that the committed links name the given proofs.
+
+use anyhow::{anyhow, ensure, Result};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    HashableKey, ProverClient, SP1CompressedProof, SP1ProvingKey, SP1PublicValues, SP1Stdin,
+    SP1VerifyingKey,
+};
+
+/// The length of the digest of the previous program's outputs committed by a chained program.
+pub const CHAIN_DIGEST_LEN: usize = 32;
+
+/// The length of the link a chained program commits before its own outputs: the digest of the
+/// previous program's outputs, followed by the digest of the previous program's verifying key.
+pub const CHAIN_LINK_LEN: usize = CHAIN_DIGEST_LEN + 32;
+
+/// Build the stdin of a chained program from the compressed proof of the previous program,
+/// followed by the program's own inputs.
+pub fn chained_stdin(
+    previous: &SP1CompressedProof,
+    previous_vk: &SP1VerifyingKey,
+    stdin: SP1Stdin,
+) -> SP1Stdin {
+    let mut chained = SP1Stdin::new();
+    chained.write(&previous_vk.hash_u32());
+    chained.write_slice(previous.public_values.as_slice());
+    chained.write_proof(previous.proof.clone(), previous_vk.vk.clone());
+    chained.buffer.extend(stdin.buffer);
+    chained.proofs.extend(stdin.proofs);
+    chained.stream = stdin.stream;
+    chained.random_seed = stdin.random_seed;
+    chained
+}
+
+/// The link `next` must commit to for its program to have read `previous`, the outputs of the
+/// program of `previous_vk`, with `read_chained`.
+pub fn chain_link(previous: &SP1PublicValues, previous_vk: &SP1VerifyingKey) -> Vec<u8> {
+    let mut link = Sha256::digest(previous.as_slice()).to_vec();
+    for word in previous_vk.hash_u32() {
+        link.extend_from_slice(&word.to_le_bytes());
+    }
+    link
+}
+
+/// Whether `next` commits to the link to `previous`, i.e. the program that produced `next` read
+/// `previous` with `read_chained` and verified its proof against `previous_vk`.
+pub fn is_chained(
+    previous: &SP1PublicValues,
+    previous_vk: &SP1VerifyingKey,
+    next: &SP1PublicValues,
+) -> bool {
+    next.as_slice().get(..CHAIN_LINK_LEN) == Some(chain_link(previous, previous_vk).as_slice())
+}
+
+impl ProverClient {
+    /// Proves a chain of programs, feeding the compressed proof and public values of each program
+    /// into the next. Returns one compressed proof per program, which can be checked with
+    /// [ProverClient::verify_chain].
+    pub fn prove_chain(
+        &self,
+        stages: Vec<(&SP1ProvingKey, SP1Stdin)>,
+    ) -> Result<Vec<SP1CompressedProof>> {
+        let mut proofs: Vec<SP1CompressedProof> = Vec::new();
+        let mut previous_vk: Option<&SP1VerifyingKey> = None;
+        for (pk, stdin) in stages {
+            let stdin = match (proofs.last(), previous_vk) {
+                (Some(previous), Some(vk)) => chained_stdin(previous, vk, stdin),
+                _ => stdin,
+            };
+            proofs.push(self.prove_compressed(pk, stdin)?);
+            previous_vk = Some(&pk.vk);
+        }
+        Ok(proofs)
+    }
+
+    /// Verifies a chain of compressed proofs generated by [ProverClient::prove_chain] against the
+    /// verifying keys of the programs, in order, and checks that every program committed to the
+    /// outputs and verifying key of its predecessor, whose proof it verified in the zkVM.
+    pub fn verify_chain(
+        &self,
+        proofs: &[SP1CompressedProof],
+        vkeys: &[&SP1VerifyingKey],
+    ) -> Result<()> {
+        ensure!(!proofs.is_empty(), "chain is empty");
+        ensure!(
+            proofs.len() == vkeys.len(),
+            "chain has {} proofs but {} verifying keys",
+            proofs.len(),
+            vkeys.len()
+        );
+        for (i, (proof, vkey)) in proofs.iter().zip(vkeys.iter()).enumerate() {
+            self.verify_compressed(proof, vkey)
+                .map_err(|e| anyhow!("proof {} of the chain is invalid: {}", i, e))?;
+            if i > 0 {
+                ensure!(
+                    is_chained(
+                        &proofs[i - 1].public_values,
+                        vkeys[i - 1],
+                        &proof.public_values
+                    ),
+                    "proof {} of the chain does not commit to the outputs of proof {}",
+                    i,
+                    i - 1
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chain_link, is_chained, CHAIN_LINK_LEN};
+    use crate::{ProverClient, SP1PublicValues};
+
+    #[test]
+    fn test_chain_link() {
+        let client = ProverClient::mock();
+        let (_, vk) = client.setup(include_bytes!(
+            "../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf"
+        ));
+        let (_, other_vk) = client.setup(include_bytes!(
+            "../../tests/panic/elf/riscv32im-succinct-zkvm-elf"
+        ));
+        let previous = SP1PublicValues::from(&[1, 2, 3]);
+
+        let mut next = chain_link(&previous, &vk);
+        assert_eq!(next.len(), CHAIN_LINK_LEN);
+        next.extend_from_slice(&[4, 5]);
+        let next = SP1PublicValues::from(&next);
+        assert!(is_chained(&previous, &vk, &next));
+
+        // The link names both the outputs and the program of the previous proof.
+        assert!(!is_chained(&SP1PublicValues::from(&[1, 2]), &vk, &next));
+        assert!(!is_chained(&previous, &other_vk, &next));
+        assert!(!is_chained(&previous, &vk, &SP1PublicValues::from(&[4, 5])));
+    }
+}
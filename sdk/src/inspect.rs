@@ -0,0 +1,143 @@
+//! The inspection of the structure of a proof, to triage proofs which fail to verify.
+
+use std::fmt::{self, Display, Formatter};
+
+use p3_field::PrimeField32;
+use serde::{Deserialize, Serialize};
+use sp1_core::{
+    air::{PublicValues, Word, POSEIDON_NUM_WORDS, PV_DIGEST_NUM_WORDS},
+    stark::{ProofSummary as _, ShardProofSummary, Val},
+};
+use sp1_prover::{CoreSC, SP1ReduceProof};
+
+use crate::{SP1CompressedProof, SP1Proof};
+
+/// The public values of a shard proof which describe the execution it proves.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionPublicValues {
+    /// The digest of the public values the program committed.
+    pub committed_value_digest: [u32; PV_DIGEST_NUM_WORDS],
+    /// The digest of the proofs the program verified.
+    pub deferred_proofs_digest: [u32; POSEIDON_NUM_WORDS],
+    /// The program counter at the start of the execution of the proof.
+    pub start_pc: u32,
+    /// The program counter after the execution of the proof, which is `0` once the program halts.
+    pub next_pc: u32,
+    /// The first shard of the execution of the proof.
+    pub start_shard: u32,
+    /// The shard after the last shard of the execution of the proof.
+    pub next_shard: u32,
+    /// The exit code of the program.
+    pub exit_code: u32,
+}
+
+/// The structure and the public values of a shard proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardInspection {
+    /// The structure of the proof.
+    pub summary: ShardProofSummary,
+    /// The decoded public values of the proof.
+    pub public_values: ExecutionPublicValues,
+}
+
+/// The structure and the public values of the shard proofs of a proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofInspection {
+    /// The shard proofs of the proof, which a compressed proof has one of.
+    pub shards: Vec<ShardInspection>,
+}
+
+impl SP1Proof {
+    /// Returns the structure and the decoded public values of each shard proof.
+    pub fn inspect(&self) -> ProofInspection {
+        let shards = self
+            .proof
+            .iter()
+            .map(|shard| {
+                let public_values = PublicValues::<Word<Val<CoreSC>>, Val<CoreSC>>::from_vec(
+                    shard.public_values.clone(),
+                )
+                .to_u32s();
+                ShardInspection {
+                    summary: shard.summary(),
+                    public_values: ExecutionPublicValues {
+                        committed_value_digest: public_values.committed_value_digest,
+                        deferred_proofs_digest: public_values.deferred_proofs_digest,
+                        start_pc: public_values.start_pc,
+                        next_pc: public_values.next_pc,
+                        start_shard: public_values.shard,
+                        next_shard: public_values.shard + 1,
+                        exit_code: public_values.exit_code,
+                    },
+                }
+            })
+            .collect();
+        ProofInspection { shards }
+    }
+}
+
+impl SP1CompressedProof {
+    /// Returns the structure and the decoded public values of the compressed proof, which cover
+    /// all the shards of the execution.
+    pub fn inspect(&self) -> ProofInspection {
+        let public_values = SP1ReduceProof {
+            proof: self.proof.clone(),
+        }
+        .recursion_public_values();
+        let shard = ShardInspection {
+            summary: self.proof.summary(),
+            public_values: ExecutionPublicValues {
+                committed_value_digest: public_values
+                    .committed_value_digest
+                    .map(|word| word.to_u32()),
+                deferred_proofs_digest: public_values
+                    .deferred_proofs_digest
+                    .map(|f| f.as_canonical_u32()),
+                start_pc: public_values.start_pc.as_canonical_u32(),
+                next_pc: public_values.next_pc.as_canonical_u32(),
+                start_shard: public_values.start_shard.as_canonical_u32(),
+                next_shard: public_values.next_shard.as_canonical_u32(),
+                exit_code: public_values.exit_code.as_canonical_u32(),
+            },
+        };
+        ProofInspection {
+            shards: vec![shard],
+        }
+    }
+}
+
+impl Display for ExecutionPublicValues {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let words = |words: &[u32]| {
+            words
+                .iter()
+                .map(|word| format!("{:08x}", word))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        writeln!(f, "  shards: {}..{}", self.start_shard, self.next_shard)?;
+        writeln!(f, "  pc: 0x{:08x} -> 0x{:08x}", self.start_pc, self.next_pc)?;
+        writeln!(f, "  exit code: {}", self.exit_code)?;
+        writeln!(
+            f,
+            "  committed value digest: {}",
+            words(&self.committed_value_digest)
+        )?;
+        writeln!(
+            f,
+            "  deferred proofs digest: {}",
+            words(&self.deferred_proofs_digest)
+        )
+    }
+}
+
+impl Display for ProofInspection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, shard) in self.shards.iter().enumerate() {
+            writeln!(f, "shard {}", i)?;
+            write!(f, "{}", shard.public_values)?;
+            write!(f, "{}", shard.summary)?;
+        }
+        Ok(())
+    }
+}
@@ -9,38 +9,87 @@
 pub mod proto {
     pub mod network;
 }
+pub mod abi {
+    pub use sp1_precompiles::abi::*;
+}
 pub mod artifacts;
+pub mod batch;
+pub mod cache;
+pub mod chain;
+pub mod inspect;
+pub mod metadata;
 #[cfg(feature = "network")]
 pub mod network;
 #[cfg(feature = "network")]
 pub use crate::network::prover::NetworkProver;
+#[cfg(feature = "network")]
+pub use crate::network::retry::RetryPolicy;
+#[cfg(feature = "network")]
+pub use crate::network::status::{NetworkError, ProofRequestId, ProofRequestStatus};
 
+pub mod proof_file;
 pub mod provers;
+pub mod registry;
 pub mod utils {
     pub use sp1_core::utils::setup_logger;
 }
 
 use cfg_if::cfg_if;
-use std::{env, fmt::Debug, fs::File, path::Path};
+use p3_field::PrimeField32;
+use std::{collections::BTreeMap, env, fmt::Debug, path::Path, sync::Arc};
 
 use anyhow::{Ok, Result};
 
-pub use provers::{LocalProver, MockProver, Prover};
+pub use batch::{BatchProof, BatchProofs};
+pub use cache::{CachedProofKind, ProofCache};
+pub use inspect::{ExecutionPublicValues, ProofInspection, ShardInspection};
+pub use metadata::{ProofMetadata, ProofStageTimes};
+pub use proof_file::{ProofFileError, ProofHeader};
+pub use provers::{LocalProver, LocalProverOpts, MockProver, Prover};
+pub use registry::{VkeyRegistry, VkeyRegistryError};
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+pub use sp1_core::io::{
+    PublicOutputProof, PublicOutputsTree, UserPublicValue, UserPublicValueKind,
+    UserPublicValuesSchema,
+};
+pub use sp1_core::stark::{
+    ChipProofSize, ChipSummary, FriProofSize, FriProofSummary, ShardProofSize, ShardProofSummary,
+};
+pub use sp1_core::utils::{
+    set_prover_backend, ProgressReporter, ProverBackend, ProvingEvent, ProvingStage, TraceStorage,
+};
 use sp1_core::{
+    air::{PublicValues, Word, USER_PV_NUM_WORDS},
     runtime::ExecutionReport,
-    stark::{MachineVerificationError, ShardProof},
+    stark::{MachineVerificationError, ProofSizeBreakdown, ShardProof, StarkGenericConfig, Val},
+    utils::SP1CoreOpts,
 };
+use sp1_prover::aggregate::AggregationInput;
+pub use sp1_prover::aggregate::{decode_aggregated_proofs, AggregatedProof, AggregationError};
+use sp1_prover::encoding::{self, COMPRESS_VKEY_MAGIC, PROOF_MAGIC, SP1_CIRCUIT_VERSION};
+use sp1_prover::SP1ReduceProof;
 pub use sp1_prover::{
-    CoreSC, HashableKey, InnerSC, OuterSC, PlonkBn254Proof, SP1Prover, SP1ProvingKey,
-    SP1PublicValues, SP1Stdin, SP1VerifyingKey,
+    CoreSC, Groth16Bls12381Proof, Groth16Bn254Proof, HashableKey, InnerSC, OuterSC,
+    PlonkBn254Proof, ProgramVerifier, RecursionShape, SP1Prover, SP1ProvingKey, SP1PublicValues,
+    SP1Stdin, SP1VerifyingKey, SecurityPreset, VerifierCalldata,
 };
 
 /// A client for interacting with SP1.
 pub struct ProverClient {
     /// The underlying prover implementation.
-    pub prover: Box<dyn Prover>,
+    pub prover: Arc<dyn Prover>,
+    /// Whether [ProverClient::verify] and [ProverClient::verify_compressed] accept proofs of
+    /// programs which halted with a nonzero exit code.
+    pub allow_nonzero_exit_code: bool,
+    /// The cache which [ProverClient::prove], [ProverClient::prove_compressed],
+    /// [ProverClient::prove_plonk] and [ProverClient::prove_groth16] consult before proving. It is the directory set by the
+    /// `SP1_PROOF_CACHE_DIR` environment variable by default, and no cache if it is not set.
+    pub proof_cache: Option<ProofCache>,
+    /// The registry of verifying keys which [ProverClient] checks the programs it proves and the
+    /// keys of the proofs it verifies against. It is the registry at the path set by the
+    /// `SP1_VKEY_REGISTRY` environment variable by default, and no registry if it is not set.
+    pub vkey_registry: Option<VkeyRegistry>,
 }
 
 /// A proof generated with SP1.
@@ -51,6 +100,12 @@ pub struct SP1ProofWithPublicValues<P> {
     pub proof: P,
     pub stdin: SP1Stdin,
     pub public_values: SP1PublicValues,
+    /// The [SP1_CIRCUIT_VERSION] of the prover which generated the proof, which the verifiers
+    /// check before the proof itself.
+    pub circuit_version: String,
+    /// The metadata of the generation of the proof, which is not serialized with the proof.
+    #[serde(skip)]
+    pub metadata: ProofMetadata,
 }
 
 /// A [SP1ProofWithPublicValues] generated with [ProverClient::prove].
@@ -64,6 +119,12 @@ pub type SP1CompressedProofVerificationError = MachineVerificationError<InnerSC>
 /// A [SP1ProofWithPublicValues] generated with [ProverClient::prove_plonk].
 pub type SP1PlonkBn254Proof = SP1ProofWithPublicValues<PlonkBn254Proof>;
 
+/// A [SP1ProofWithPublicValues] generated with [ProverClient::prove_groth16].
+pub type SP1Groth16Bn254Proof = SP1ProofWithPublicValues<Groth16Bn254Proof>;
+
+/// A [SP1ProofWithPublicValues] generated with [ProverClient::prove_groth16_bls12381].
+pub type SP1Groth16Bls12381Proof = SP1ProofWithPublicValues<Groth16Bls12381Proof>;
+
 impl ProverClient {
     /// Creates a new [ProverClient].
     ///
@@ -87,16 +148,25 @@ impl ProverClient {
             .as_str()
         {
             "mock" => Self {
-                prover: Box::new(MockProver::new()),
+                prover: Arc::new(MockProver::new()),
+                allow_nonzero_exit_code: false,
+                proof_cache: ProofCache::from_env(),
+                vkey_registry: VkeyRegistry::from_env(),
             },
             "local" => Self {
-                prover: Box::new(LocalProver::new()),
+                prover: Arc::new(LocalProver::new()),
+                allow_nonzero_exit_code: false,
+                proof_cache: ProofCache::from_env(),
+                vkey_registry: VkeyRegistry::from_env(),
             },
             "network" => {
                 cfg_if! {
                     if #[cfg(feature = "network")] {
                         Self {
-                            prover: Box::new(NetworkProver::new()),
+                            prover: Arc::new(NetworkProver::new()),
+                            allow_nonzero_exit_code: false,
+                            proof_cache: ProofCache::from_env(),
+                            vkey_registry: VkeyRegistry::from_env(),
                         }
                     } else {
                         panic!("network feature is not enabled")
@@ -123,7 +193,10 @@ impl ProverClient {
     /// ```
     pub fn mock() -> Self {
         Self {
-            prover: Box::new(MockProver::new()),
+            prover: Arc::new(MockProver::new()),
+            allow_nonzero_exit_code: false,
+            proof_cache: ProofCache::from_env(),
+            vkey_registry: VkeyRegistry::from_env(),
         }
     }
 
@@ -141,8 +214,93 @@ impl ProverClient {
     /// ```
     pub fn local() -> Self {
         Self {
-            prover: Box::new(LocalProver::new()),
+            prover: Arc::new(LocalProver::new()),
+            allow_nonzero_exit_code: false,
+            proof_cache: ProofCache::from_env(),
+            vkey_registry: VkeyRegistry::from_env(),
+        }
+    }
+
+    /// Creates a new [ProverClient] with the given prover.
+    ///
+    /// Useful for proving with an implementation of [Prover] outside of the SDK, such as a queue
+    /// in front of a farm of provers.
+    ///
+    /// ### Examples
+    ///
+    /// ```no_run
+    /// use sp1_sdk::{MockProver, ProverClient};
+    ///
+    /// let client = ProverClient::with_prover(MockProver::new());
+    /// ```
+    pub fn with_prover(prover: impl Prover + 'static) -> Self {
+        Self {
+            prover: Arc::new(prover),
+            allow_nonzero_exit_code: false,
+            proof_cache: ProofCache::from_env(),
+            vkey_registry: VkeyRegistry::from_env(),
+        }
+    }
+
+    /// Creates a new [ProverClient] with the local prover and the given options.
+    ///
+    /// ### Examples
+    ///
+    /// ```no_run
+    /// use sp1_sdk::{LocalProverOpts, ProverClient, RecursionShape, SecurityPreset};
+    ///
+    /// let client = ProverClient::local_with_opts(LocalProverOpts {
+    ///     recursion_shape: RecursionShape::Arity(4),
+    ///     security_preset: SecurityPreset::Bits128,
+    ///     ..Default::default()
+    /// });
+    /// ```
+    pub fn local_with_opts(opts: LocalProverOpts) -> Self {
+        Self {
+            prover: Arc::new(LocalProver::with_opts(opts)),
+            allow_nonzero_exit_code: false,
+            proof_cache: ProofCache::from_env(),
+            vkey_registry: VkeyRegistry::from_env(),
+        }
+    }
+
+    /// Builds the recursion programs and keys of the local prover and saves them to the cache on
+    /// disk, unless they are cached already, so that the next local prover of the same version of
+    /// SP1 skips their setup. Returns the directory of the cache, or `None` if it is disabled by
+    /// the `SP1_RECURSION_CACHE` environment variable.
+    ///
+    /// ### Examples
+    ///
+    /// ```no_run
+    /// use sp1_sdk::ProverClient;
+    ///
+    /// ProverClient::warm_recursion_cache().unwrap();
+    /// ```
+    pub fn warm_recursion_cache() -> Result<Option<std::path::PathBuf>> {
+        Ok(SP1Prover::warm_recursion_cache()?)
+    }
+
+    /// Creates a new [ProverClient] with the local prover, which computes the low-degree
+    /// extensions of the traces on a GPU: with Metal on macOS, and on a CUDA device otherwise.
+    ///
+    /// Requires the `metal` or `cuda` feature, and falls back to the CPU with a warning if there is
+    /// no device. The backend is shared by all the local provers of the process, and can also be
+    /// set with the `SP1_PROVER_BACKEND` environment variable.
+    ///
+    /// ### Examples
+    ///
+    /// ```no_run
+    /// use sp1_sdk::ProverClient;
+    ///
+    /// let client = ProverClient::gpu();
+    /// ```
+    pub fn gpu() -> Self {
+        if cfg!(target_os = "macos") {
+            set_prover_backend(ProverBackend::Metal);
+        } else {
+            set_prover_backend(ProverBackend::Cuda);
         }
+        Self::local()
     }
 
     /// Creates a new [ProverClient] with the network prover.
@@ -161,7 +319,10 @@ impl ProverClient {
         cfg_if! {
             if #[cfg(feature = "network")] {
                 Self {
-                    prover: Box::new(NetworkProver::new()),
+                    prover: Arc::new(NetworkProver::new()),
+                    allow_nonzero_exit_code: false,
+                    proof_cache: ProofCache::from_env(),
+                    vkey_registry: VkeyRegistry::from_env(),
                 }
             } else {
                 panic!("network feature is not enabled")
@@ -169,6 +330,53 @@ impl ProverClient {
         }
     }
 
+    /// Creates a new [ProverClient] with the network prover, which proves locally if a request is
+    /// not picked up by a prover of the network within `pickup_deadline`, or once the prover
+    /// network is unavailable.
+    ///
+    /// Recommended for services which need a bound on the latency of their proofs, on machines
+    /// which can generate the proofs of the program.
+    ///
+    /// ### Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use sp1_sdk::ProverClient;
+    ///
+    /// let client = ProverClient::network_with_fallback(Duration::from_secs(60));
+    /// ```
+    #[cfg(feature = "network")]
+    pub fn network_with_fallback(pickup_deadline: std::time::Duration) -> Self {
+        Self {
+            prover: Arc::new(
+                NetworkProver::with_policy(RetryPolicy::default(), true)
+                    .with_pickup_deadline(pickup_deadline),
+            ),
+            allow_nonzero_exit_code: false,
+            proof_cache: ProofCache::from_env(),
+            vkey_registry: VkeyRegistry::from_env(),
+        }
+    }
+
+    /// Creates a builder of a [ProverClient] with the network prover, which sets how it retries
+    /// failed requests and whether it falls back to proving locally when the prover network is
+    /// unavailable.
+    ///
+    /// ### Examples
+    ///
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, RetryPolicy};
+    ///
+    /// let client = ProverClient::network_builder()
+    ///     .retry_policy(RetryPolicy::default().with_max_attempts(5))
+    ///     .fallback_to_local(true)
+    ///     .build();
+    /// ```
+    #[cfg(feature = "network")]
+    pub fn network_builder() -> NetworkProverBuilder {
+        NetworkProverBuilder::default()
+    }
+
     /// Executes the given program on the given input (without generating a proof).
     ///
     /// Returns the public values and execution report of the program after it has been executed.
@@ -196,7 +404,103 @@ impl ProverClient {
         elf: &[u8],
         stdin: SP1Stdin,
     ) -> Result<(SP1PublicValues, ExecutionReport)> {
-        Ok(SP1Prover::execute(elf, &stdin)?)
+        self.prover.execute(elf, &stdin)
+    }
+
+    /// Executes the given program on the given input and checks the constraints of every chip on
+    /// the traces of its shards, without generating a proof.
+    ///
+    /// Fails with the chip, shard, row and index of the first constraint which does not hold, which
+    /// is much faster than proving to find out whether a program, or a new precompile, would prove.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    /// let public_values = client.check_constraints(elf, stdin).unwrap();
+    /// ```
+    pub fn check_constraints(&self, elf: &[u8], stdin: SP1Stdin) -> Result<SP1PublicValues> {
+        Ok(SP1Prover::check_constraints(elf, &stdin, SP1CoreOpts::default())?.0)
+    }
+
+    /// Executes the given program on the given input (without generating a proof), without
+    /// blocking the async runtime.
+    ///
+    /// The program runs on the current worker thread, whose other tasks are moved to another
+    /// thread first, so this must be called from a multi-threaded tokio runtime.
+    pub async fn execute_async(
+        &self,
+        elf: &[u8],
+        stdin: SP1Stdin,
+    ) -> Result<(SP1PublicValues, ExecutionReport)> {
+        tokio::task::block_in_place(|| self.execute(elf, stdin))
+    }
+
+    /// Executes the given program on the given input with a hard cycle budget (without generating
+    /// a proof).
+    ///
+    /// If the program does not halt within `max_cycles` cycles, this returns an
+    /// [sp1_core::runtime::ExecutionError::OutOfGas] error recording the cycles consumed and the
+    /// last program counter.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    /// let (public_values, report) = client
+    ///     .execute_with_max_cycles(elf, stdin, 1_000_000)
+    ///     .unwrap();
+    /// ```
+    pub fn execute_with_max_cycles(
+        &self,
+        elf: &[u8],
+        stdin: SP1Stdin,
+        max_cycles: u64,
+    ) -> Result<(SP1PublicValues, ExecutionReport)> {
+        let opts = SP1CoreOpts {
+            max_cycles: Some(max_cycles),
+            ..Default::default()
+        };
+        Ok(SP1Prover::execute_with_opts(elf, &stdin, opts)?)
+    }
+
+    /// Executes the given program on the given input, writing the public values to `sink` as they
+    /// are committed instead of buffering them in memory.
+    ///
+    /// Returns the SHA-256 digest of the public values, which is the committed value digest of a
+    /// proof of the execution, and the report of the execution.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    /// let file = std::fs::File::create("public_values.bin").unwrap();
+    /// let (digest, report) = client.execute_streaming(elf, stdin, file).unwrap();
+    /// ```
+    pub fn execute_streaming<W: std::io::Write + Send + 'static>(
+        &self,
+        elf: &[u8],
+        stdin: SP1Stdin,
+        sink: W,
+    ) -> Result<([u8; 32], ExecutionReport)> {
+        Ok(SP1Prover::execute_streaming(
+            elf,
+            &stdin,
+            SP1CoreOpts::default(),
+            sink,
+        )?)
     }
 
     /// Setup a program to be proven and verified by the SP1 RISC-V zkVM by computing the proving
@@ -246,7 +550,61 @@ impl ProverClient {
     /// let proof = client.prove(&pk, stdin).unwrap();
     /// ```
     pub fn prove(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Proof> {
-        self.prover.prove(pk, stdin)
+        self.check_registered_elf(pk)?;
+        match &self.proof_cache {
+            Some(cache) => cache.get_or_prove(
+                self.prover.id(),
+                CachedProofKind::Core,
+                &pk.elf,
+                stdin,
+                |stdin| self.prover.prove(pk, stdin),
+            ),
+            None => self.prover.prove(pk, stdin),
+        }
+    }
+
+    /// Proves the execution of many programs with their inputs, as [Self::prove] does, and returns
+    /// the proofs as they complete.
+    ///
+    /// The proofs are scheduled on background threads, as many at once as the prover can take
+    /// without leaving the cores or the network idle: see [batch::default_batch_concurrency]. The
+    /// setup of each distinct program runs once, and the proof cache is consulted for each proof.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    ///
+    /// let jobs = (0..10usize).map(|n| {
+    ///     let mut stdin = SP1Stdin::new();
+    ///     stdin.write(&n);
+    ///     (elf.to_vec(), stdin)
+    /// });
+    /// for proof in client.prove_batch(jobs.collect()) {
+    ///     println!("proof {} done: {}", proof.index, proof.result.is_ok());
+    /// }
+    /// ```
+    pub fn prove_batch(&self, jobs: Vec<(Vec<u8>, SP1Stdin)>) -> BatchProofs {
+        let concurrency = batch::default_batch_concurrency(self.prover.id());
+        self.prove_batch_with_concurrency(jobs, concurrency)
+    }
+
+    /// Proves the execution of many programs with their inputs, with at most `concurrency` proofs
+    /// at once. See [Self::prove_batch].
+    pub fn prove_batch_with_concurrency(
+        &self,
+        jobs: Vec<(Vec<u8>, SP1Stdin)>,
+        concurrency: usize,
+    ) -> BatchProofs {
+        batch::prove_batch(
+            self.prover.clone(),
+            self.proof_cache.clone(),
+            self.vkey_registry.clone(),
+            jobs,
+            concurrency,
+        )
     }
 
     /// Proves the execution of the given program with the given input in the compressed mode.
@@ -279,7 +637,17 @@ impl ProverClient {
         pk: &SP1ProvingKey,
         stdin: SP1Stdin,
     ) -> Result<SP1CompressedProof> {
-        self.prover.prove_compressed(pk, stdin)
+        self.check_registered_elf(pk)?;
+        match &self.proof_cache {
+            Some(cache) => cache.get_or_prove(
+                self.prover.id(),
+                CachedProofKind::Compressed,
+                &pk.elf,
+                stdin,
+                |stdin| self.prover.prove_compressed(pk, stdin),
+            ),
+            None => self.prover.prove_compressed(pk, stdin),
+        }
     }
 
     /// Proves the execution of the given program with the given input in the plonk bn254 mode.
@@ -309,11 +677,24 @@ impl ProverClient {
     /// ```
     /// Generates a plonk bn254 proof, verifiable onchain, of the given elf and stdin.
     pub fn prove_plonk(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1PlonkBn254Proof> {
-        self.prover.prove_plonk(pk, stdin)
+        self.check_registered_elf(pk)?;
+        match &self.proof_cache {
+            Some(cache) => cache.get_or_prove(
+                self.prover.id(),
+                CachedProofKind::Plonk,
+                &pk.elf,
+                stdin,
+                |stdin| self.prover.prove_plonk(pk, stdin),
+            ),
+            None => self.prover.prove_plonk(pk, stdin),
+        }
     }
 
-    /// Verifies that the given proof is valid and matches the given verification key produced by
-    /// [Self::setup].
+    /// Generates a groth16 bn254 proof, verifiable onchain, of the given elf and stdin.
+    ///
+    /// Groth16 proofs are smaller and cheaper to verify onchain than plonk proofs, but the setup
+    /// of their circuit is not universal. Verify them onchain with the contract of
+    /// [Self::export_groth16_verifier].
     ///
     /// ### Examples
     /// ```no_run
@@ -324,110 +705,697 @@ impl ProverClient {
     /// let (pk, vk) = client.setup(elf);
     /// let mut stdin = SP1Stdin::new();
     /// stdin.write(&10usize);
-    /// let proof = client.prove(&pk, stdin).unwrap();
-    /// client.verify(&proof, &vk).unwrap();
+    /// let proof = client.prove_groth16(&pk, stdin).unwrap();
+    /// client.verify_groth16(&proof, &vk).unwrap();
     /// ```
-    pub fn verify(
+    pub fn prove_groth16(
         &self,
-        proof: &SP1Proof,
-        vkey: &SP1VerifyingKey,
-    ) -> Result<(), SP1ProofVerificationError> {
-        self.prover.verify(proof, vkey)
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1Groth16Bn254Proof> {
+        self.check_registered_elf(pk)?;
+        match &self.proof_cache {
+            Some(cache) => cache.get_or_prove(
+                self.prover.id(),
+                CachedProofKind::Groth16,
+                &pk.elf,
+                stdin,
+                |stdin| self.prover.prove_groth16(pk, stdin),
+            ),
+            None => self.prover.prove_groth16(pk, stdin),
+        }
     }
 
-    /// Verifies that the given compressed proof is valid and matches the given verification key
-    /// produced by [Self::setup].
+    /// Generates a groth16 proof over BLS12-381 of the given elf and stdin, for the chains which
+    /// can only verify pairings over BLS12-381, such as with the precompiles of EIP-2537.
+    ///
+    /// There are no released artifacts of the circuit over BLS12-381, so its setup is built on
+    /// the first proof, and the verifying key is written to [sp1_prover::GROTH16_EIP2537_VK_FILE]
+    /// in the artifacts, in the encoding of the precompiles of EIP-2537.
     ///
     /// ### Examples
     /// ```no_run
     /// use sp1_sdk::{ProverClient, SP1Stdin};
     ///
-    /// // Load the program.
     /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
-    ///
-    /// // Initialize the prover client.
     /// let client = ProverClient::new();
-    ///
-    /// // Setup the program.
     /// let (pk, vk) = client.setup(elf);
-    ///
-    /// // Setup the inputs.
     /// let mut stdin = SP1Stdin::new();
     /// stdin.write(&10usize);
+    /// let proof = client.prove_groth16_bls12381(&pk, stdin).unwrap();
+    /// client.verify_groth16_bls12381(&proof, &vk).unwrap();
+    /// ```
+    pub fn prove_groth16_bls12381(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1Groth16Bls12381Proof> {
+        self.check_registered_elf(pk)?;
+        match &self.proof_cache {
+            Some(cache) => cache.get_or_prove(
+                self.prover.id(),
+                CachedProofKind::Groth16Bls12381,
+                &pk.elf,
+                stdin,
+                |stdin| self.prover.prove_groth16_bls12381(pk, stdin),
+            ),
+            None => self.prover.prove_groth16_bls12381(pk, stdin),
+        }
+    }
+
+    /// Aggregates the compressed proofs of possibly different programs into a single compressed
+    /// proof, whose public values enumerate the hash of the verifying key and the digest of the
+    /// public values of each proof.
     ///
-    /// // Generate the proof.
+    /// The aggregated proof is a proof of the aggregation program of SP1, which is verified with
+    /// [ProverClient::verify_compressed] against [ProverClient::aggregation_vk]. Its public values
+    /// are decoded by [decode_aggregated_proofs].
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{decode_aggregated_proofs, ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
     /// let proof = client.prove_compressed(&pk, stdin).unwrap();
-    /// client.verify_compressed(&proof, &vk).unwrap();
+    ///
+    /// let aggregated = client.aggregate(&[(&proof, &vk), (&proof, &vk)]).unwrap();
+    /// client.verify_compressed(&aggregated, client.aggregation_vk()).unwrap();
+    /// let proofs = decode_aggregated_proofs(aggregated.public_values.as_slice()).unwrap();
+    /// assert!(proofs[0].is_proof_of(&vk, &proof.public_values));
     /// ```
-    pub fn verify_compressed(
+    pub fn aggregate(
         &self,
-        proof: &SP1CompressedProof,
-        vkey: &SP1VerifyingKey,
-    ) -> Result<()> {
-        self.prover.verify_compressed(proof, vkey)
+        proofs: &[(&SP1CompressedProof, &SP1VerifyingKey)],
+    ) -> Result<SP1CompressedProof> {
+        for (_, vk) in proofs {
+            self.check_registered_vkey(vk)?;
+        }
+        let start = std::time::Instant::now();
+        let inputs = proofs
+            .iter()
+            .map(|(proof, vk)| AggregationInput {
+                proof: &proof.proof,
+                vk,
+                public_values: &proof.public_values,
+            })
+            .collect::<Vec<_>>();
+        let prover = self.prover.sp1_prover();
+        let (proof, public_values) = prover.aggregate(&inputs)?;
+        Ok(SP1CompressedProof {
+            proof: proof.proof,
+            stdin: SP1Prover::aggregation_stdin(&inputs),
+            public_values,
+            circuit_version: SP1_CIRCUIT_VERSION.to_string(),
+            metadata: ProofMetadata::from_events(std::iter::empty(), start.elapsed()),
+        })
     }
 
-    /// Verifies that the given plonk bn254 proof is valid and matches the given verification key
-    /// produced by [Self::setup].
+    /// The verifying key of the aggregation program of [ProverClient::aggregate].
+    pub fn aggregation_vk(&self) -> &SP1VerifyingKey {
+        &self.prover.sp1_prover().aggregation_keys().1
+    }
+
+    /// Proves the execution of the given program with the given input in the default mode, and
+    /// reports the stages of the proof to `progress`.
+    ///
+    /// The local prover reports the execution, the commitment and proof of each shard, each layer
+    /// of the recursion and the wrapping steps. Other provers report nothing.
     ///
     /// ### Examples
     /// ```no_run
-    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    /// use sp1_sdk::{ProgressReporter, ProverClient, SP1Stdin};
     ///
-    /// // Load the program.
     /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
-    ///
-    /// // Initialize the prover client.
     /// let client = ProverClient::new();
-    ///
-    /// // Setup the program.
     /// let (pk, vk) = client.setup(elf);
-    ///
-    /// // Setup the inputs.
     /// let mut stdin = SP1Stdin::new();
     /// stdin.write(&10usize);
     ///
-    /// // Generate the proof.
-    /// let proof = client.prove_plonk(&pk, stdin).unwrap();
-    ///
-    /// // Verify the proof.
-    /// client.verify_plonk(&proof, &vk).unwrap();
+    /// let (progress, events) = ProgressReporter::channel();
+    /// std::thread::spawn(move || {
+    ///     for event in events {
+    ///         println!("{:?} after {:?}", event.stage, event.elapsed);
+    ///     }
+    /// });
+    /// let proof = client.prove_with_progress(&pk, stdin, &progress).unwrap();
     /// ```
-    pub fn verify_plonk(&self, proof: &SP1PlonkBn254Proof, vkey: &SP1VerifyingKey) -> Result<()> {
-        self.prover.verify_plonk(proof, vkey)
+    pub fn prove_with_progress(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+        progress: &ProgressReporter,
+    ) -> Result<SP1Proof> {
+        self.check_registered_elf(pk)?;
+        self.prover.prove_with_progress(pk, stdin, progress)
     }
-}
 
-impl Default for ProverClient {
-    fn default() -> Self {
-        Self::new()
+    /// Proves the execution of the given program with the given input in the compressed mode, and
+    /// reports the stages of the proof to `progress`. See [Self::prove_with_progress].
+    pub fn prove_compressed_with_progress(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+        progress: &ProgressReporter,
+    ) -> Result<SP1CompressedProof> {
+        self.check_registered_elf(pk)?;
+        self.prover
+            .prove_compressed_with_progress(pk, stdin, progress)
     }
-}
 
-impl<P: Debug + Clone + Serialize + DeserializeOwned> SP1ProofWithPublicValues<P> {
-    /// Saves the proof to a path.
-    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
-        bincode::serialize_into(File::create(path).expect("failed to open file"), self)
-            .map_err(Into::into)
+    /// Proves the execution of the given program with the given input in the plonk bn254 mode,
+    /// and reports the stages of the proof to `progress`. See [Self::prove_with_progress].
+    pub fn prove_plonk_with_progress(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+        progress: &ProgressReporter,
+    ) -> Result<SP1PlonkBn254Proof> {
+        self.check_registered_elf(pk)?;
+        self.prover.prove_plonk_with_progress(pk, stdin, progress)
     }
 
-    /// Loads a proof from a path.
-    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
-        bincode::deserialize_from(File::open(path).expect("failed to open file"))
-            .map_err(Into::into)
+    /// Proves the execution of the given program with the given input in the default mode,
+    /// without blocking the async runtime.
+    ///
+    /// The network prover waits for the proof asynchronously. The local prover generates it on
+    /// the current worker thread, whose other tasks are moved to another thread first, so this
+    /// must be called from a multi-threaded tokio runtime.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    /// let proof = client.prove_async(&pk, stdin).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn prove_async(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Proof> {
+        self.check_registered_elf(pk)?;
+        self.prover.prove_async(pk, stdin).await
     }
-}
 
-impl SP1PlonkBn254Proof {
-    pub fn bytes(&self) -> String {
-        format!("0x{}", self.proof.encoded_proof.clone())
+    /// Proves the execution of the given program with the given input in the compressed mode,
+    /// without blocking the async runtime. See [Self::prove_async].
+    pub async fn prove_compressed_async(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1CompressedProof> {
+        self.check_registered_elf(pk)?;
+        self.prover.prove_compressed_async(pk, stdin).await
     }
-}
-
-#[cfg(test)]
-mod tests {
 
-    use crate::{utils, ProverClient, SP1Stdin};
+    /// Proves the execution of the given program with the given input in the plonk bn254 mode,
+    /// without blocking the async runtime. See [Self::prove_async].
+    pub async fn prove_plonk_async(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1PlonkBn254Proof> {
+        self.check_registered_elf(pk)?;
+        self.prover.prove_plonk_async(pk, stdin).await
+    }
+
+    /// Verifies that the given proof is valid and matches the given verification key produced by
+    /// [Self::setup].
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (pk, vk) = client.setup(elf);
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    /// let proof = client.prove(&pk, stdin).unwrap();
+    /// client.verify(&proof, &vk).unwrap();
+    /// ```
+    ///
+    /// Unless [ProverClient::allow_nonzero_exit_code] is set, this fails with
+    /// [MachineVerificationError::NonZeroExitCode] if the program halted with a nonzero exit code.
+    pub fn verify(
+        &self,
+        proof: &SP1Proof,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<(), SP1ProofVerificationError> {
+        // The errors of core proofs are machine verification errors, and a key which is not in the
+        // registry is a key which the public values of the proof are not meant to commit to.
+        if self.check_registered_vkey(vkey).is_err() {
+            return Err(MachineVerificationError::InvalidPublicValues(
+                "the verifying key is not in the vkey registry",
+            ));
+        }
+        proof.check_circuit_version(vkey)?;
+        // Mock proofs have no shards, and are only generated for programs which exit with code 0.
+        if !proof.proof.is_empty() && !self.allow_nonzero_exit_code {
+            let exit_code = proof.exit_code()?;
+            if exit_code != 0 {
+                return Err(MachineVerificationError::NonZeroExitCode(exit_code));
+            }
+        }
+        self.prover.verify(proof, vkey)
+    }
+
+    /// Verifies that the given compressed proof is valid and matches the given verification key
+    /// produced by [Self::setup].
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// // Load the program.
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    ///
+    /// // Initialize the prover client.
+    /// let client = ProverClient::new();
+    ///
+    /// // Setup the program.
+    /// let (pk, vk) = client.setup(elf);
+    ///
+    /// // Setup the inputs.
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    ///
+    /// // Generate the proof.
+    /// let proof = client.prove_compressed(&pk, stdin).unwrap();
+    /// client.verify_compressed(&proof, &vk).unwrap();
+    /// ```
+    ///
+    /// Unless [ProverClient::allow_nonzero_exit_code] is set, this fails if the program halted
+    /// with a nonzero exit code.
+    pub fn verify_compressed(
+        &self,
+        proof: &SP1CompressedProof,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<()> {
+        self.check_registered_vkey(vkey)?;
+        proof.check_circuit_version::<InnerSC>(vkey)?;
+        self.prover.verify_compressed(proof, vkey)?;
+        let exit_code = proof.exit_code();
+        if exit_code != 0 && !self.allow_nonzero_exit_code {
+            return Err(MachineVerificationError::<InnerSC>::NonZeroExitCode(exit_code).into());
+        }
+        Ok(())
+    }
+
+    /// Verifies that the given plonk bn254 proof is valid and matches the given verification key
+    /// produced by [Self::setup].
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, SP1Stdin};
+    ///
+    /// // Load the program.
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    ///
+    /// // Initialize the prover client.
+    /// let client = ProverClient::new();
+    ///
+    /// // Setup the program.
+    /// let (pk, vk) = client.setup(elf);
+    ///
+    /// // Setup the inputs.
+    /// let mut stdin = SP1Stdin::new();
+    /// stdin.write(&10usize);
+    ///
+    /// // Generate the proof.
+    /// let proof = client.prove_plonk(&pk, stdin).unwrap();
+    ///
+    /// // Verify the proof.
+    /// client.verify_plonk(&proof, &vk).unwrap();
+    /// ```
+    pub fn verify_plonk(&self, proof: &SP1PlonkBn254Proof, vkey: &SP1VerifyingKey) -> Result<()> {
+        self.check_registered_vkey(vkey)?;
+        proof.check_circuit_version::<CoreSC>(vkey)?;
+        self.prover.verify_plonk(proof, vkey)
+    }
+
+    /// Verifies that the given groth16 bn254 proof is valid and matches the given verification
+    /// key produced by [Self::setup].
+    pub fn verify_groth16(
+        &self,
+        proof: &SP1Groth16Bn254Proof,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<()> {
+        self.check_registered_vkey(vkey)?;
+        proof.check_circuit_version::<CoreSC>(vkey)?;
+        self.prover.verify_groth16(proof, vkey)
+    }
+
+    /// Verifies that the given groth16 proof over BLS12-381 is valid and matches the given
+    /// verification key produced by [Self::setup].
+    pub fn verify_groth16_bls12381(
+        &self,
+        proof: &SP1Groth16Bls12381Proof,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<()> {
+        self.check_registered_vkey(vkey)?;
+        proof.check_circuit_version::<CoreSC>(vkey)?;
+        self.prover.verify_groth16_bls12381(proof, vkey)
+    }
+
+    /// Generates the Solidity verifier of the groth16 bn254 proofs of the program with the given
+    /// verification key, with the given calldata format.
+    pub fn groth16_verifier(
+        &self,
+        vkey: &SP1VerifyingKey,
+        calldata: VerifierCalldata,
+    ) -> Result<ProgramVerifier> {
+        let artifacts_dir = if sp1_prover::build::sp1_dev_mode() {
+            sp1_prover::build::groth16_bn254_artifacts_dev_dir()
+        } else {
+            sp1_prover::build::try_install_groth16_bn254_artifacts()
+        };
+        Ok(ProgramVerifier::new(
+            &artifacts_dir,
+            &vkey.bytes32(),
+            calldata,
+        )?)
+    }
+
+    /// Writes the Foundry project of the Solidity verifier of [Self::groth16_verifier] to
+    /// `out_dir`.
+    ///
+    /// The project pins the compiler and its settings, so `forge build` gives the same bytecode
+    /// on every machine, and the deployed verifier can be checked against the sources.
+    ///
+    /// ### Examples
+    /// ```no_run
+    /// use sp1_sdk::{ProverClient, VerifierCalldata};
+    ///
+    /// let elf = include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+    /// let client = ProverClient::new();
+    /// let (_, vk) = client.setup(elf);
+    /// client
+    ///     .export_groth16_verifier(&vk, VerifierCalldata::Minimal, "contracts")
+    ///     .unwrap();
+    /// ```
+    pub fn export_groth16_verifier(
+        &self,
+        vkey: &SP1VerifyingKey,
+        calldata: VerifierCalldata,
+        out_dir: impl AsRef<Path>,
+    ) -> Result<()> {
+        self.groth16_verifier(vkey, calldata)?
+            .write(out_dir.as_ref())?;
+        Ok(())
+    }
+
+    /// Returns the verifying key of the groth16 bn254 circuit for the Solana verifier of
+    /// `sp1-solana-verifier`, which a Solana program embeds to verify the proofs of
+    /// [Self::prove_groth16] encoded with [SP1Groth16Bn254Proof::to_solana_bytes].
+    ///
+    /// The key of the circuit is the same for every program: the program is identified by the
+    /// bytes of `vkey.bytes32()`, which the Solana program checks the proofs against.
+    pub fn groth16_solana_vk(&self) -> Result<Vec<u8>> {
+        let artifacts_dir = if sp1_prover::build::sp1_dev_mode() {
+            sp1_prover::build::groth16_bn254_artifacts_dev_dir()
+        } else {
+            sp1_prover::build::try_install_groth16_bn254_artifacts()
+        };
+        Ok(std::fs::read(
+            artifacts_dir.join(sp1_prover::GROTH16_SOLANA_VK_FILE),
+        )?)
+    }
+
+    /// Returns the verifying key of the compress program, in the versioned encoding, for the
+    /// verifiers of [Self::prove_compressed] proofs outside of the prover, such as the browser
+    /// verifier of `sp1-wasm-verifier`.
+    ///
+    /// The key is the same for every program, and only changes with the version of SP1.
+    pub fn compress_vk_bytes(&self) -> Result<Vec<u8>> {
+        encoding::encode(COMPRESS_VKEY_MAGIC, &self.prover.sp1_prover().compress_vk)
+            .map_err(Into::into)
+    }
+
+    /// Checks that the ELF of `pk` is in the vkey registry of the client, with the verifying key
+    /// of `pk`, if the client has a registry.
+    fn check_registered_elf(&self, pk: &SP1ProvingKey) -> Result<()> {
+        if let Some(registry) = &self.vkey_registry {
+            registry.check_elf(&pk.elf, &pk.vk)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that `vk` is in the vkey registry of the client, if the client has a registry.
+    fn check_registered_vkey(&self, vk: &SP1VerifyingKey) -> Result<()> {
+        if let Some(registry) = &self.vkey_registry {
+            registry.check_vkey(vk)?;
+        }
+        Ok(())
+    }
+}
+
+/// A builder of a [ProverClient] with the network prover, created by
+/// [ProverClient::network_builder].
+#[cfg(feature = "network")]
+#[derive(Debug, Clone, Default)]
+pub struct NetworkProverBuilder {
+    retry_policy: RetryPolicy,
+    fallback_to_local: bool,
+    pickup_deadline: Option<std::time::Duration>,
+}
+
+#[cfg(feature = "network")]
+impl NetworkProverBuilder {
+    /// Sets how the prover retries the requests which fail because of the network or of their
+    /// prover. Defaults to [RetryPolicy::default].
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sets whether the prover proves locally once the prover network is unavailable, after the
+    /// retries of a request failed or if the request was not fulfilled in time. Defaults to
+    /// `false`.
+    pub fn fallback_to_local(mut self, fallback_to_local: bool) -> Self {
+        self.fallback_to_local = fallback_to_local;
+        self
+    }
+
+    /// Sets the time a request may wait for a prover of the network to pick it up, after which it
+    /// fails, or is proven locally if the prover falls back to proving locally. Defaults to no
+    /// deadline.
+    pub fn pickup_deadline(mut self, pickup_deadline: std::time::Duration) -> Self {
+        self.pickup_deadline = Some(pickup_deadline);
+        self
+    }
+
+    /// Builds the [ProverClient].
+    pub fn build(self) -> ProverClient {
+        let mut prover = NetworkProver::with_policy(self.retry_policy, self.fallback_to_local);
+        if let Some(pickup_deadline) = self.pickup_deadline {
+            prover = prover.with_pickup_deadline(pickup_deadline);
+        }
+        ProverClient {
+            prover: Arc::new(prover),
+            allow_nonzero_exit_code: false,
+            proof_cache: ProofCache::from_env(),
+            vkey_registry: VkeyRegistry::from_env(),
+        }
+    }
+}
+
+impl Default for ProverClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Debug + Clone + Serialize + DeserializeOwned> SP1ProofWithPublicValues<P> {
+    /// Checks that the proof and `vkey` were generated by a prover of the circuits of this
+    /// verifier, which is the [SP1_CIRCUIT_VERSION] of the SDK.
+    pub fn check_circuit_version<SC: StarkGenericConfig>(
+        &self,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<(), MachineVerificationError<SC>> {
+        sp1_prover::check_circuit_version(&self.circuit_version)?;
+        vkey.check_circuit_version()
+    }
+
+    /// Encodes the proof into bytes, in the versioned encoding of [sp1_prover::encoding], without
+    /// the header and the checksum of the files of [SP1ProofWithPublicValues::save].
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        encoding::encode(PROOF_MAGIC, self).map_err(Into::into)
+    }
+
+    /// Decodes a proof from bytes returned by [SP1ProofWithPublicValues::to_bytes].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        encoding::decode(PROOF_MAGIC, bytes).map_err(Into::into)
+    }
+}
+
+impl SP1PlonkBn254Proof {
+    pub fn bytes(&self) -> String {
+        format!("0x{}", self.proof.encoded_proof.clone())
+    }
+
+    /// Returns the words that the program set with `sp1_zkvm::io::set_user_public_value`, which
+    /// are public inputs of the proof.
+    pub fn user_public_values(&self) -> Result<[u32; USER_PV_NUM_WORDS]> {
+        wrapped_user_public_values(&self.proof.public_inputs)
+    }
+
+    /// Decodes the user public values into named fields.
+    pub fn user_public_fields(
+        &self,
+        schema: &UserPublicValuesSchema,
+    ) -> Result<BTreeMap<String, UserPublicValue>> {
+        Ok(schema.decode(&self.user_public_values()?)?)
+    }
+}
+
+impl SP1Groth16Bn254Proof {
+    pub fn bytes(&self) -> String {
+        format!("0x{}", self.proof.encoded_proof.clone())
+    }
+
+    /// Returns the words that the program set with `sp1_zkvm::io::set_user_public_value`, which
+    /// are public inputs of the proof.
+    pub fn user_public_values(&self) -> Result<[u32; USER_PV_NUM_WORDS]> {
+        wrapped_user_public_values(&self.proof.public_inputs)
+    }
+
+    /// Decodes the user public values into named fields.
+    pub fn user_public_fields(
+        &self,
+        schema: &UserPublicValuesSchema,
+    ) -> Result<BTreeMap<String, UserPublicValue>> {
+        Ok(schema.decode(&self.user_public_values()?)?)
+    }
+
+    /// Encodes the proof for the Solana verifier of `sp1-solana-verifier`: the length of the
+    /// encoded Groth16 proof as a little-endian `u32`, the encoded proof and the public values.
+    pub fn to_solana_bytes(&self) -> Result<Vec<u8>> {
+        let proof = hex::decode(&self.proof.encoded_proof)?;
+        let mut bytes = Vec::with_capacity(4 + proof.len() + self.public_values.as_slice().len());
+        bytes.extend_from_slice(&(proof.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&proof);
+        bytes.extend_from_slice(self.public_values.as_slice());
+        Ok(bytes)
+    }
+}
+
+impl SP1Groth16Bls12381Proof {
+    /// Returns the words that the program set with `sp1_zkvm::io::set_user_public_value`, which
+    /// are public inputs of the proof.
+    pub fn user_public_values(&self) -> Result<[u32; USER_PV_NUM_WORDS]> {
+        wrapped_user_public_values(&self.proof.public_inputs)
+    }
+
+    /// Decodes the user public values into named fields.
+    pub fn user_public_fields(
+        &self,
+        schema: &UserPublicValuesSchema,
+    ) -> Result<BTreeMap<String, UserPublicValue>> {
+        Ok(schema.decode(&self.user_public_values()?)?)
+    }
+}
+
+impl SP1Proof {
+    /// Returns the public values of the last shard.
+    fn last_shard_public_values(
+        &self,
+    ) -> Result<PublicValues<Word<Val<CoreSC>>, Val<CoreSC>>, SP1ProofVerificationError> {
+        let shard_proof = self
+            .proof
+            .last()
+            .ok_or(MachineVerificationError::EmptyProof)?;
+        PublicValues::<Word<Val<CoreSC>>, Val<CoreSC>>::try_from(shard_proof.public_values.clone())
+            .map_err(MachineVerificationError::MalformedPublicValues)
+    }
+
+    /// Returns the exit code the program halted with, which is `0` unless it panicked or called
+    /// `sp1_zkvm::process::exit`.
+    pub fn exit_code(&self) -> Result<u32, SP1ProofVerificationError> {
+        self.last_shard_public_values()
+            .map(|public_values| public_values.exit_code.as_canonical_u32())
+    }
+
+    /// Returns the words that the program set with `sp1_zkvm::io::set_user_public_value`.
+    pub fn user_public_values(&self) -> Result<[u32; USER_PV_NUM_WORDS]> {
+        Ok(self.last_shard_public_values()?.user_values_u32())
+    }
+
+    /// Decodes the user public values into named fields.
+    pub fn user_public_fields(
+        &self,
+        schema: &UserPublicValuesSchema,
+    ) -> Result<BTreeMap<String, UserPublicValue>> {
+        Ok(schema.decode(&self.user_public_values()?)?)
+    }
+
+    /// Returns the sizes of the components of each shard proof, in the encoding of
+    /// [SP1ProofWithPublicValues::save].
+    pub fn size_breakdown(&self) -> Vec<ShardProofSize> {
+        self.proof
+            .iter()
+            .map(|shard| shard.size_breakdown())
+            .collect()
+    }
+}
+
+impl SP1CompressedProof {
+    /// Returns the exit code the program halted with.
+    pub fn exit_code(&self) -> u32 {
+        SP1ReduceProof {
+            proof: self.proof.clone(),
+        }
+        .exit_code()
+    }
+
+    /// Returns the words that the program set with `sp1_zkvm::io::set_user_public_value`.
+    pub fn user_public_values(&self) -> [u32; USER_PV_NUM_WORDS] {
+        SP1ReduceProof {
+            proof: self.proof.clone(),
+        }
+        .user_values()
+    }
+
+    /// Decodes the user public values into named fields.
+    pub fn user_public_fields(
+        &self,
+        schema: &UserPublicValuesSchema,
+    ) -> Result<BTreeMap<String, UserPublicValue>> {
+        Ok(schema.decode(&self.user_public_values())?)
+    }
+
+    /// Returns the sizes of the components of the compressed proof.
+    pub fn size_breakdown(&self) -> ShardProofSize {
+        self.proof.size_breakdown()
+    }
+}
+
+/// Parses the user public values of a proof wrapped in a SNARK, which are the public inputs after
+/// the vkey hash and the committed values digest.
+fn wrapped_user_public_values(public_inputs: &[String]) -> Result<[u32; USER_PV_NUM_WORDS]> {
+    let mut user_values = [0; USER_PV_NUM_WORDS];
+    for (user_value, public_input) in user_values.iter_mut().zip(&public_inputs[2..]) {
+        *user_value = public_input.parse()?;
+    }
+    Ok(user_values)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        provers::ProverType, registry, utils, CachedProofKind, HashableKey, ProofCache,
+        ProofFileError, ProverClient, SP1PlonkBn254Proof, SP1Stdin, SP1VerifyingKey, VkeyRegistry,
+        VkeyRegistryError,
+    };
+    use sp1_core::runtime::ExecutionError;
 
     #[test]
     fn test_execute() {
@@ -440,6 +1408,108 @@ mod tests {
         client.execute(elf, stdin).unwrap();
     }
 
+    #[test]
+    fn test_execute_max_cycles() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let err = client.execute_with_max_cycles(elf, stdin, 100).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::OutOfGas(100, _))
+        ));
+    }
+
+    #[test]
+    fn test_execute_stream_twice() {
+        use sp1_core::io::{InputStream, InputStreamConsumed};
+
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let streamed = || {
+            let mut stdin = SP1Stdin::new();
+            stdin.stream = Some(InputStream::new(
+                vec![bincode::serialize(&10usize).unwrap()].into_iter(),
+            ));
+            stdin
+        };
+
+        // A clone shares the stream, so the second execution must fail instead of running on the
+        // rest of it.
+        let stdin = streamed();
+        client.execute(elf, stdin.clone()).unwrap();
+        let err = client.execute(elf, stdin).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ExecutionError>(),
+            Some(ExecutionError::InputStreamConsumed(InputStreamConsumed))
+        ));
+
+        // A buffered stream can be executed any number of times.
+        let mut stdin = streamed();
+        stdin.buffer_stream().unwrap();
+        let (first, _) = client.execute(elf, stdin.clone()).unwrap();
+        let (second, _) = client.execute(elf, stdin).unwrap();
+        assert_eq!(first.as_slice(), second.as_slice());
+    }
+
+    #[test]
+    fn test_check_constraints() {
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let (public_values, _) = client.execute(elf, stdin.clone()).unwrap();
+        let checked = client.check_constraints(elf, stdin).unwrap();
+        assert_eq!(checked.as_slice(), public_values.as_slice());
+    }
+
+    #[test]
+    fn test_inspect() {
+        use p3_field::PrimeField32;
+
+        utils::setup_logger();
+        let client = ProverClient::local();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, _) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof = client.prove(&pk, stdin).unwrap();
+
+        let inspection = proof.inspect();
+        assert_eq!(inspection.shards.len(), proof.proof.len());
+        let first = &inspection.shards[0];
+        assert_eq!(
+            first.public_values.start_pc,
+            pk.pk.pc_start.as_canonical_u32()
+        );
+        assert!(first
+            .summary
+            .chips
+            .iter()
+            .any(|chip| chip.name == "CPU" && chip.height() > 0));
+        let last = &inspection.shards[inspection.shards.len() - 1];
+        assert_eq!(last.public_values.next_pc, 0);
+        assert_eq!(last.public_values.exit_code, 0);
+        for shards in inspection.shards.windows(2) {
+            assert_eq!(
+                shards[0].public_values.next_shard,
+                shards[1].public_values.start_shard
+            );
+            assert_eq!(
+                shards[0].public_values.next_pc,
+                shards[1].public_values.start_pc
+            );
+        }
+    }
+
     #[test]
     #[should_panic]
     fn test_execute_panic() {
@@ -464,6 +1534,565 @@ mod tests {
         client.verify_plonk(&proof, &vk).unwrap();
     }
 
+    #[test]
+    fn test_proof_cache() {
+        utils::setup_logger();
+        let dir = tempfile::tempdir().unwrap();
+        let mut client = ProverClient::mock();
+        client.proof_cache = Some(ProofCache::new(dir.path()));
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, _) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+
+        let proof = client.prove(&pk, stdin.clone()).unwrap();
+        let entries = || std::fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(entries(), 1);
+
+        let cached = client.prove(&pk, stdin.clone()).unwrap();
+        assert_eq!(
+            cached.public_values.as_slice(),
+            proof.public_values.as_slice()
+        );
+        assert_eq!(entries(), 1);
+
+        client.prove_plonk(&pk, stdin.clone()).unwrap();
+        let mut other_stdin = SP1Stdin::new();
+        other_stdin.write(&11usize);
+        client.prove(&pk, other_stdin).unwrap();
+        assert_eq!(entries(), 3);
+
+        // Two identical requests are proven once.
+        let fresh_dir = tempfile::tempdir().unwrap();
+        let cache = ProofCache::new(fresh_dir.path());
+        let calls = std::cell::Cell::new(0);
+        for _ in 0..2 {
+            let cached = cache
+                .get_or_prove(
+                    ProverType::Mock,
+                    CachedProofKind::Core,
+                    elf,
+                    stdin.clone(),
+                    |_| {
+                        calls.set(calls.get() + 1);
+                        Ok(proof.clone())
+                    },
+                )
+                .unwrap();
+            assert_eq!(
+                cached.public_values.as_slice(),
+                proof.public_values.as_slice()
+            );
+        }
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_prove_batch() {
+        utils::setup_logger();
+        let client = ProverClient::mock();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let jobs = (0..4usize)
+            .map(|n| {
+                let mut stdin = SP1Stdin::new();
+                stdin.write(&n);
+                (elf.to_vec(), stdin)
+            })
+            .collect::<Vec<_>>();
+
+        let mut indices = client
+            .prove_batch_with_concurrency(jobs, 2)
+            .map(|proof| {
+                let mut result = proof.result.unwrap();
+                assert_eq!(result.public_values.read::<u32>() as usize, proof.index);
+                client.verify(&result, &proof.vk.unwrap()).unwrap();
+                proof.index
+            })
+            .collect::<Vec<_>>();
+        indices.sort();
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+
+        let mut failures = client.prove_batch(vec![(b"not an elf".to_vec(), SP1Stdin::new())]);
+        let failure = failures.next().unwrap();
+        assert!(failure.result.is_err());
+        assert!(failure.vk.is_none());
+        assert!(failures.next().is_none());
+    }
+
+    #[test]
+    fn test_vkey_registry() {
+        utils::setup_logger();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("vkeys.json");
+        let mut client = ProverClient::mock();
+        client.vkey_registry = Some(VkeyRegistry::open(&path).unwrap());
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+
+        let err = client.prove(&pk, stdin.clone()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<VkeyRegistryError>(),
+            Some(VkeyRegistryError::UnregisteredElf { .. })
+        ));
+
+        let mut registry = VkeyRegistry::open(&path).unwrap();
+        assert!(registry.register("fibonacci", elf, &vk).unwrap().is_none());
+        client.vkey_registry = Some(VkeyRegistry::open(&path).unwrap());
+        let proof = client.prove(&pk, stdin).unwrap();
+        client.verify(&proof, &vk).unwrap();
+
+        let registered = client
+            .vkey_registry
+            .as_ref()
+            .unwrap()
+            .get("fibonacci")
+            .unwrap();
+        assert_eq!(registered.vkey_hash, vk.bytes32());
+        assert_eq!(registered.elf_hash, registry::elf_hash(elf));
+        assert!(matches!(
+            registry.check("fibonacci", b"rebuilt", &vk),
+            Err(VkeyRegistryError::ElfMismatch { .. })
+        ));
+        assert!(matches!(
+            registry.check("other", elf, &vk),
+            Err(VkeyRegistryError::UnknownProgram { .. })
+        ));
+    }
+
+    #[test]
+    fn test_save_load() {
+        utils::setup_logger();
+        let dir = tempfile::tempdir().unwrap();
+        let client = ProverClient::mock();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof = client.prove_plonk(&pk, stdin).unwrap();
+
+        let proof_path = dir.path().join("proof.bin");
+        let vk_path = dir.path().join("vk.bin");
+        proof.save(&proof_path).unwrap();
+        vk.save(&vk_path).unwrap();
+        let loaded = SP1PlonkBn254Proof::load(&proof_path).unwrap();
+        let loaded_vk = SP1VerifyingKey::load(&vk_path).unwrap();
+        assert_eq!(
+            loaded.public_values.as_slice(),
+            proof.public_values.as_slice()
+        );
+        client.verify_plonk(&loaded, &loaded_vk).unwrap();
+
+        // A verifying key is not a proof, and raw bincode has no header.
+        assert!(SP1PlonkBn254Proof::load(&vk_path).is_err());
+        let mut bytes = proof.to_bytes().unwrap();
+        assert!(SP1PlonkBn254Proof::from_bytes(&bytes[12..]).is_err());
+
+        // A proof of another encoding version is rejected.
+        bytes[4] ^= 0xff;
+        assert!(SP1PlonkBn254Proof::from_bytes(&bytes).is_err());
+
+        // A corrupted proof file is rejected.
+        let mut file = std::fs::read(&proof_path).unwrap();
+        let middle = file.len() / 2;
+        file[middle] ^= 0xff;
+        std::fs::write(&proof_path, &file).unwrap();
+        let err = SP1PlonkBn254Proof::load(&proof_path).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ProofFileError>(),
+            Some(ProofFileError::ChecksumMismatch)
+        ));
+
+        // A proof saved for a program is only loaded for the verifying key of the program.
+        proof
+            .save_for_program(&proof_path, &vk, "fibonacci")
+            .unwrap();
+        let (_, header) = SP1PlonkBn254Proof::load_with_header(&proof_path).unwrap();
+        assert_eq!(header.program_name.as_deref(), Some("fibonacci"));
+        assert_eq!(header.vkey_hash, Some(vk.bytes32()));
+        assert_eq!(header.circuit_version, proof.circuit_version);
+        SP1PlonkBn254Proof::load_for_program(&proof_path, &vk).unwrap();
+        let (_, other_vk) = client.setup(include_bytes!(
+            "../../examples/io/program/elf/riscv32im-succinct-zkvm-elf"
+        ));
+        let err = SP1PlonkBn254Proof::load_for_program(&proof_path, &other_vk).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ProofFileError>(),
+            Some(ProofFileError::VkeyMismatch { .. })
+        ));
+        proof.save(&proof_path).unwrap();
+        let err = SP1PlonkBn254Proof::load_for_program(&proof_path, &vk).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ProofFileError>(),
+            Some(ProofFileError::MissingVkey)
+        ));
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn test_proof_request_status() {
+        use crate::proto::network::{GetProofStatusResponse, ProofStatus, UnclaimReason};
+        use crate::{NetworkError, ProofRequestId, ProofRequestStatus};
+
+        let id = ProofRequestId::new("proofrequest_01");
+        let status = |res: GetProofStatusResponse| ProofRequestStatus::from_response(&id, &res);
+        let response = |status: ProofStatus| GetProofStatusResponse {
+            status: status.into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            status(response(ProofStatus::ProofRequested)).unwrap(),
+            ProofRequestStatus::Queued
+        );
+        assert_eq!(
+            status(response(ProofStatus::ProofClaimed)).unwrap(),
+            ProofRequestStatus::Assigned
+        );
+        assert_eq!(
+            status(GetProofStatusResponse {
+                proven_shards: Some(3),
+                total_shards: Some(8),
+                ..response(ProofStatus::ProofClaimed)
+            })
+            .unwrap(),
+            ProofRequestStatus::Proving {
+                proven_shards: 3,
+                total_shards: 8
+            }
+        );
+        assert_eq!(
+            status(GetProofStatusResponse {
+                unclaim_reason: Some(UnclaimReason::Invalid.into()),
+                unclaim_description: Some("invalid program".to_string()),
+                ..response(ProofStatus::ProofUnclaimed)
+            })
+            .unwrap(),
+            ProofRequestStatus::Failed {
+                reason: UnclaimReason::Invalid,
+                description: "invalid program".to_string()
+            }
+        );
+        assert!(matches!(
+            status(GetProofStatusResponse {
+                status: 42,
+                ..Default::default()
+            }),
+            Err(NetworkError::UnknownStatus { status: 42, .. })
+        ));
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn test_retry_policy() {
+        use crate::network::retry::{is_transient, is_unavailable};
+        use crate::proto::network::UnclaimReason;
+        use crate::{NetworkError, ProofRequestId, RetryPolicy};
+        use std::time::Duration;
+
+        let policy = RetryPolicy::default()
+            .with_initial_backoff(Duration::from_secs(1))
+            .with_max_backoff(Duration::from_secs(5));
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(4));
+        assert_eq!(policy.backoff(4), Duration::from_secs(5));
+        for multiplier in [-2.0, f64::NAN, f64::INFINITY] {
+            let policy = policy.with_multiplier(multiplier);
+            assert_eq!(policy.backoff(2), Duration::from_secs(5));
+        }
+        assert_eq!(RetryPolicy::none().with_max_attempts(0).max_attempts, 1);
+
+        let id = ProofRequestId::new("proofrequest_01");
+        let failed = |reason| -> anyhow::Error {
+            NetworkError::RequestFailed {
+                id: id.clone(),
+                reason,
+                description: String::new(),
+            }
+            .into()
+        };
+        assert!(is_transient(&failed(UnclaimReason::Abandoned)));
+        assert!(!is_transient(&failed(UnclaimReason::Invalid)));
+        assert!(!is_unavailable(&failed(UnclaimReason::Invalid)));
+
+        let timeout: anyhow::Error = NetworkError::Timeout {
+            id: id.clone(),
+            timeout: Duration::from_secs(1),
+        }
+        .into();
+        assert!(!is_transient(&timeout));
+        assert!(is_unavailable(&timeout));
+
+        let not_picked_up: anyhow::Error = NetworkError::NotPickedUp {
+            id: id.clone(),
+            deadline: Duration::from_secs(1),
+        }
+        .into();
+        assert!(!is_transient(&not_picked_up));
+        assert!(is_unavailable(&not_picked_up));
+        let unresponsive: anyhow::Error = NetworkError::Unresponsive {
+            deadline: Duration::from_secs(1),
+        }
+        .into();
+        assert!(!is_transient(&unresponsive));
+        assert!(is_unavailable(&unresponsive));
+        assert!(!is_unavailable(&anyhow::anyhow!("the program panicked")));
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn test_network_fallback() {
+        use crate::proto::network::ProofMode;
+        use crate::{NetworkError, NetworkProver, RetryPolicy, SP1Proof};
+        use sp1_core::io::InputStream;
+        use sp1_prover::utils::block_on;
+        use std::net::TcpListener;
+        use std::time::{Duration, Instant};
+
+        utils::setup_logger();
+        // A prover network which accepts connections and never answers them.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        std::env::set_var(
+            "PROVER_NETWORK_RPC",
+            format!("http://{}/", listener.local_addr().unwrap()),
+        );
+        std::env::set_var("SP1_PRIVATE_KEY", format!("0x{}", "11".repeat(32)));
+        std::thread::spawn(move || {
+            let mut connections = Vec::new();
+            for stream in listener.incoming() {
+                connections.push(stream);
+            }
+        });
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+
+        // Without the fallback, the request fails once the pickup deadline is over.
+        let deadline = Duration::from_secs(1);
+        let prover =
+            NetworkProver::with_policy(RetryPolicy::none(), false).with_pickup_deadline(deadline);
+        let start = Instant::now();
+        let err =
+            block_on(prover.prove_remote::<SP1Proof>(elf, stdin, ProofMode::Core)).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<NetworkError>(),
+            Some(NetworkError::Unresponsive { .. })
+        ));
+        assert!(start.elapsed() < Duration::from_secs(30));
+
+        // With the fallback, the proof is generated locally, even from a streamed input.
+        let mut stdin = SP1Stdin::new();
+        stdin.stream = Some(InputStream::new(
+            vec![bincode::serialize(&10usize).unwrap()].into_iter(),
+        ));
+        let client = ProverClient::network_with_fallback(deadline);
+        let (pk, vk) = client.setup(elf);
+        let mut proof = client.prove(&pk, stdin).unwrap();
+        client.verify(&proof, &vk).unwrap();
+        assert_eq!(proof.public_values.read::<u32>(), 10);
+    }
+
+    #[test]
+    fn test_proof_metadata() {
+        use crate::metadata::SP1_VERSION;
+        use crate::{ProofMetadata, ProvingEvent, ProvingStage};
+        use std::time::Duration;
+
+        let event = |stage, secs| ProvingEvent {
+            stage,
+            elapsed: Duration::from_secs(secs),
+        };
+        let events = [
+            event(
+                ProvingStage::Executed {
+                    cycles: 1000,
+                    num_checkpoints: 1,
+                },
+                1,
+            ),
+            event(
+                ProvingStage::ShardProved {
+                    shard: 0,
+                    num_shards: 2,
+                },
+                3,
+            ),
+            event(ProvingStage::CoreProved { num_shards: 2 }, 4),
+            event(
+                ProvingStage::RecursionLayer {
+                    layer: 0,
+                    num_proofs: 1,
+                },
+                8,
+            ),
+            event(ProvingStage::Shrunk, 9),
+            event(ProvingStage::Wrapped, 11),
+            event(ProvingStage::PlonkWrapped, 16),
+        ];
+        let metadata = ProofMetadata::from_events(events, Duration::from_secs(17));
+        assert_eq!(metadata.cycles, Some(1000));
+        assert_eq!(metadata.num_shards, Some(2));
+        assert_eq!(metadata.total_time, Duration::from_secs(17));
+        assert_eq!(metadata.prover_version, SP1_VERSION);
+        let times = metadata.stage_times;
+        assert_eq!(times.execution, Some(Duration::from_secs(1)));
+        assert_eq!(times.core, Some(Duration::from_secs(3)));
+        assert_eq!(times.compress, Some(Duration::from_secs(4)));
+        assert_eq!(times.shrink, Some(Duration::from_secs(1)));
+        assert_eq!(times.wrap, Some(Duration::from_secs(2)));
+        assert_eq!(times.snark, Some(Duration::from_secs(5)));
+
+        let client = ProverClient::mock();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, _) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof = client.prove(&pk, stdin).unwrap();
+        assert_eq!(proof.metadata.prover_version, SP1_VERSION);
+        assert_eq!(proof.metadata.stage_times.core, None);
+    }
+
+    #[test]
+    fn test_custom_prover() {
+        use crate::provers::ProverType;
+        use crate::{
+            MockProver, Prover, SP1CompressedProof, SP1Groth16Bls12381Proof, SP1Groth16Bn254Proof,
+            SP1Proof, SP1ProofVerificationError, SP1ProvingKey,
+        };
+        use sp1_prover::SP1Prover;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        /// A prover which counts the proofs it generates with a mock prover.
+        struct CountingProver {
+            inner: MockProver,
+            proofs: Arc<AtomicUsize>,
+        }
+
+        impl Prover for CountingProver {
+            fn id(&self) -> ProverType {
+                ProverType::Custom
+            }
+
+            fn sp1_prover(&self) -> &SP1Prover {
+                &self.inner.prover
+            }
+
+            fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
+                self.inner.setup(elf)
+            }
+
+            fn prove(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> anyhow::Result<SP1Proof> {
+                self.proofs.fetch_add(1, Ordering::SeqCst);
+                self.inner.prove(pk, stdin)
+            }
+
+            fn prove_compressed(
+                &self,
+                pk: &SP1ProvingKey,
+                stdin: SP1Stdin,
+            ) -> anyhow::Result<SP1CompressedProof> {
+                self.proofs.fetch_add(1, Ordering::SeqCst);
+                self.inner.prove_compressed(pk, stdin)
+            }
+
+            fn prove_plonk(
+                &self,
+                pk: &SP1ProvingKey,
+                stdin: SP1Stdin,
+            ) -> anyhow::Result<SP1PlonkBn254Proof> {
+                self.proofs.fetch_add(1, Ordering::SeqCst);
+                self.inner.prove_plonk(pk, stdin)
+            }
+
+            fn prove_groth16(
+                &self,
+                pk: &SP1ProvingKey,
+                stdin: SP1Stdin,
+            ) -> anyhow::Result<SP1Groth16Bn254Proof> {
+                self.proofs.fetch_add(1, Ordering::SeqCst);
+                self.inner.prove_groth16(pk, stdin)
+            }
+
+            fn prove_groth16_bls12381(
+                &self,
+                pk: &SP1ProvingKey,
+                stdin: SP1Stdin,
+            ) -> anyhow::Result<SP1Groth16Bls12381Proof> {
+                self.proofs.fetch_add(1, Ordering::SeqCst);
+                self.inner.prove_groth16_bls12381(pk, stdin)
+            }
+
+            fn verify(
+                &self,
+                proof: &SP1Proof,
+                vkey: &SP1VerifyingKey,
+            ) -> Result<(), SP1ProofVerificationError> {
+                self.inner.verify(proof, vkey)
+            }
+        }
+
+        utils::setup_logger();
+        let proofs = Arc::new(AtomicUsize::new(0));
+        let client = ProverClient::with_prover(CountingProver {
+            inner: MockProver::new(),
+            proofs: proofs.clone(),
+        });
+        assert_eq!(client.prover.id(), ProverType::Custom);
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let (public_values, _) = client.execute(elf, stdin.clone()).unwrap();
+        let proof = client.prove(&pk, stdin).unwrap();
+        assert_eq!(proof.public_values.as_slice(), public_values.as_slice());
+        client.verify(&proof, &vk).unwrap();
+        assert_eq!(proofs.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_circuit_version() {
+        use sp1_core::stark::MachineVerificationError;
+        use sp1_prover::encoding::SP1_CIRCUIT_VERSION;
+
+        utils::setup_logger();
+        let client = ProverClient::mock();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof = client.prove_plonk(&pk, stdin.clone()).unwrap();
+        assert_eq!(proof.circuit_version, SP1_CIRCUIT_VERSION);
+        assert_eq!(vk.circuit_version, SP1_CIRCUIT_VERSION);
+        client.verify_plonk(&proof, &vk).unwrap();
+
+        let mut old_proof = proof.clone();
+        old_proof.circuit_version = "v0.0.1".to_string();
+        let err = client.verify_plonk(&old_proof, &vk).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Proof produced by incompatible prover version v0.0.1"));
+
+        let mut old_vk = vk.clone();
+        old_vk.circuit_version = "v0.0.1".to_string();
+        assert!(client.verify_plonk(&proof, &old_vk).is_err());
+        let core_proof = client.prove(&pk, stdin).unwrap();
+        assert!(matches!(
+            client.verify(&core_proof, &old_vk),
+            Err(MachineVerificationError::CircuitVersionMismatch { .. })
+        ));
+    }
+
     #[test]
     fn test_e2e_prove_plonk_mock() {
         utils::setup_logger();
@@ -476,4 +2105,35 @@ mod tests {
         let proof = client.prove_plonk(&pk, stdin).unwrap();
         client.verify_plonk(&proof, &vk).unwrap();
     }
+
+    #[test]
+    fn test_e2e_prove_groth16_mock() {
+        utils::setup_logger();
+        let client = ProverClient::mock();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof = client.prove_groth16(&pk, stdin).unwrap();
+        client.verify_groth16(&proof, &vk).unwrap();
+
+        // The mock proof is empty, so the solana encoding is its length and the public values.
+        let bytes = proof.to_solana_bytes().unwrap();
+        assert_eq!(bytes[..4], 0u32.to_le_bytes());
+        assert_eq!(&bytes[4..], proof.public_values.as_slice());
+    }
+
+    #[test]
+    fn test_e2e_prove_groth16_bls12381_mock() {
+        utils::setup_logger();
+        let client = ProverClient::mock();
+        let elf =
+            include_bytes!("../../examples/fibonacci/program/elf/riscv32im-succinct-zkvm-elf");
+        let (pk, vk) = client.setup(elf);
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&10usize);
+        let proof = client.prove_groth16_bls12381(&pk, stdin).unwrap();
+        client.verify_groth16_bls12381(&proof, &vk).unwrap();
+    }
 }
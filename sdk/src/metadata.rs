@@ -0,0 +1,123 @@
+//! The metadata of the generation of a proof.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sp1_core::utils::{ProvingEvent, ProvingStage};
+use sp1_prover::install::PLONK_BN254_ARTIFACTS_COMMIT;
+
+/// The version of the SDK, which is the version of SP1 which generates the proofs.
+pub const SP1_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The wall-clock time of each stage of the generation of a proof, or `None` for the stages which
+/// the proof did not go through.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofStageTimes {
+    /// The execution of the program.
+    pub execution: Option<Duration>,
+    /// The proof of the shards of the execution.
+    pub core: Option<Duration>,
+    /// The recursive compression of the shard proofs into a single proof.
+    pub compress: Option<Duration>,
+    /// The shrinking of the compressed proof.
+    pub shrink: Option<Duration>,
+    /// The wrapping of the shrunk proof into a proof over BN254 or BLS12-381.
+    pub wrap: Option<Duration>,
+    /// The wrapping of the BN254 proof into a PLONK or Groth16 proof.
+    pub snark: Option<Duration>,
+}
+
+/// The metadata of the generation of a proof, which is attached to the proofs returned by
+/// [crate::ProverClient].
+///
+/// The metadata is not saved with the proof, so the proofs loaded from a file or a cache have the
+/// default metadata.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofMetadata {
+    /// The number of cycles of the execution of the program.
+    pub cycles: Option<u64>,
+    /// The number of shards of the core proof.
+    pub num_shards: Option<usize>,
+    /// The wall-clock time of each stage.
+    pub stage_times: ProofStageTimes,
+    /// The wall-clock time of the whole generation of the proof.
+    pub total_time: Duration,
+    /// The peak resident memory of the process in bytes when the proof was generated, if the
+    /// platform reports it. It includes the memory used before the proof.
+    pub peak_memory: Option<u64>,
+    /// The version of SP1 which generated the proof.
+    pub prover_version: String,
+    /// The version of the circuits of SP1, which the verifying keys of the PLONK and Groth16
+    /// proofs depend on.
+    pub circuit_version: String,
+}
+
+impl ProofMetadata {
+    /// Creates the metadata of a proof generated in `total_time` from the events that its
+    /// generation reported.
+    pub fn from_events(
+        events: impl IntoIterator<Item = ProvingEvent>,
+        total_time: Duration,
+    ) -> Self {
+        let mut metadata = Self {
+            total_time,
+            peak_memory: peak_memory(),
+            prover_version: SP1_VERSION.to_string(),
+            circuit_version: PLONK_BN254_ARTIFACTS_COMMIT.to_string(),
+            ..Default::default()
+        };
+
+        // The events are timed from the start of the proof, so each stage lasts from the end of
+        // the previous stage to its own end. The compression ends with its last layer.
+        let times = &mut metadata.stage_times;
+        let mut last = Duration::ZERO;
+        let mut compressed = None;
+        let mut since = |at: Duration| {
+            let time = at.saturating_sub(last);
+            last = at;
+            Some(time)
+        };
+        for event in events {
+            match event.stage {
+                ProvingStage::Executed { cycles, .. } => {
+                    metadata.cycles = Some(cycles);
+                    times.execution = since(event.elapsed);
+                }
+                ProvingStage::CoreProved { num_shards } => {
+                    metadata.num_shards = Some(num_shards);
+                    times.core = since(event.elapsed);
+                }
+                ProvingStage::RecursionLayer { .. } => compressed = Some(event.elapsed),
+                ProvingStage::Shrunk => {
+                    if let Some(at) = compressed.take() {
+                        times.compress = since(at);
+                    }
+                    times.shrink = since(event.elapsed);
+                }
+                ProvingStage::Wrapped => times.wrap = since(event.elapsed),
+                ProvingStage::PlonkWrapped | ProvingStage::Groth16Wrapped => {
+                    times.snark = since(event.elapsed)
+                }
+                ProvingStage::ShardsCommitted { .. } | ProvingStage::ShardProved { .. } => {}
+            }
+        }
+        if let Some(at) = compressed {
+            times.compress = since(at);
+        }
+        metadata
+    }
+}
+
+/// The peak resident memory of the process in bytes, on Linux.
+fn peak_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kb = line
+        .trim_start_matches("VmHWM:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kb * 1024)
+}
@@ -2,6 +2,7 @@ use std::{env, time::Duration};
 
 use crate::{
     network::auth::NetworkAuth,
+    network::status::{NetworkError, ProofRequestId, ProofRequestStatus},
     proto::network::{UnclaimProofRequest, UnclaimReason},
 };
 use anyhow::{Context, Ok, Result};
@@ -28,7 +29,7 @@ pub const DEFAULT_PROVER_NETWORK_RPC: &str = "https://rpc.succinct.xyz/";
 const DEFAULT_SP1_VERIFIER_ADDRESS: &str = "0xed2107448519345059eab9cddab42ddc78fbebe9";
 
 /// The timeout for a proof request to be fulfilled.
-const TIMEOUT: Duration = Duration::from_secs(60 * 60);
+pub const TIMEOUT: Duration = Duration::from_secs(60 * 60);
 
 pub struct NetworkClient {
     pub rpc: TwirpClient,
@@ -87,7 +88,8 @@ impl NetworkClient {
             .get_nonce(GetNonceRequest {
                 address: self.auth.get_address().to_vec(),
             })
-            .await?;
+            .await
+            .map_err(NetworkError::from)?;
         Ok(res.nonce)
     }
 
@@ -97,25 +99,30 @@ impl NetworkClient {
         Ok(())
     }
 
-    // Get the status of a given proof. If the status is ProofFulfilled, the proof is also returned.
+    /// Gets the status of a proof request, without downloading the proof.
+    pub async fn get_request_status(&self, id: &ProofRequestId) -> Result<ProofRequestStatus> {
+        let res = self.status_response(id).await?;
+        Ok(ProofRequestStatus::from_response(id, &res)?)
+    }
+
+    /// Gets the status of a proof request. If the request is complete, the proof is also returned.
     pub async fn get_proof_status<P: DeserializeOwned>(
         &self,
-        proof_id: &str,
-    ) -> Result<(GetProofStatusResponse, Option<P>)> {
-        let res = self
-            .rpc
-            .get_proof_status(GetProofStatusRequest {
-                proof_id: proof_id.to_string(),
-            })
-            .await
-            .context("Failed to get proof status")?;
+        id: &ProofRequestId,
+    ) -> Result<(ProofRequestStatus, Option<P>)> {
+        let res = self.status_response(id).await?;
+        let status = ProofRequestStatus::from_response(id, &res)?;
 
-        let proof = match res.status() {
-            ProofStatus::ProofFulfilled => {
+        let proof = match status {
+            ProofRequestStatus::Complete => {
                 log::info!("Proof request fulfilled");
+                let proof_url = res
+                    .proof_url
+                    .as_ref()
+                    .ok_or_else(|| NetworkError::MissingProofUrl { id: id.clone() })?;
                 let proof_bytes = self
                     .http
-                    .get(res.proof_url.as_ref().expect("no proof url"))
+                    .get(proof_url)
                     .send()
                     .await
                     .context("Failed to send HTTP request for proof")?
@@ -128,7 +135,17 @@ impl NetworkClient {
             _ => None,
         };
 
-        Ok((res, proof))
+        Ok((status, proof))
+    }
+
+    async fn status_response(&self, id: &ProofRequestId) -> Result<GetProofStatusResponse> {
+        self.rpc
+            .get_proof_status(GetProofStatusRequest {
+                proof_id: id.to_string(),
+            })
+            .await
+            .map_err(NetworkError::from)
+            .context("Failed to get proof status")
     }
 
     // Get all the proof requests for a given status.
@@ -141,7 +158,8 @@ impl NetworkClient {
             .get_proof_requests(GetProofRequestsRequest {
                 status: status.into(),
             })
-            .await?;
+            .await
+            .map_err(NetworkError::from)?;
 
         Ok(res)
     }
@@ -156,7 +174,8 @@ impl NetworkClient {
             .get_relay_status(GetRelayStatusRequest {
                 tx_id: tx_id.to_string(),
             })
-            .await?;
+            .await
+            .map_err(NetworkError::from)?;
 
         let tx_hash = match res.status() {
             TransactionStatus::TransactionScheduled => None,
@@ -171,14 +190,14 @@ impl NetworkClient {
         Ok((res, tx_hash, simulation_url))
     }
 
-    /// Creates a proof request for the given ELF and stdin.
+    /// Creates a proof request for the given ELF and stdin, and submits it to the network.
     pub async fn create_proof(
         &self,
         elf: &[u8],
         stdin: &SP1Stdin,
         mode: ProofMode,
         version: &str,
-    ) -> Result<String> {
+    ) -> Result<ProofRequestId> {
         let start = SystemTime::now();
         let since_the_epoch = start
             .duration_since(UNIX_EPOCH)
@@ -199,7 +218,8 @@ impl NetworkClient {
                 mode: mode.into(),
                 version: version.to_string(),
             })
-            .await?;
+            .await
+            .map_err(NetworkError::from)?;
 
         let program_bytes = bincode::serialize(elf)?;
         let stdin_bytes = bincode::serialize(&stdin)?;
@@ -221,9 +241,10 @@ impl NetworkClient {
                 nonce,
                 proof_id: res.proof_id.clone(),
             })
-            .await?;
+            .await
+            .map_err(NetworkError::from)?;
 
-        Ok(res.proof_id)
+        Ok(ProofRequestId::new(res.proof_id))
     }
 
     // Claim a proof that was requested. This commits to generating a proof and fulfilling it.
@@ -238,7 +259,8 @@ impl NetworkClient {
                 nonce,
                 proof_id: proof_id.to_string(),
             })
-            .await?;
+            .await
+            .map_err(NetworkError::from)?;
 
         Ok(res)
     }
@@ -265,7 +287,8 @@ impl NetworkClient {
                 reason: reason.into(),
                 description,
             })
-            .await?;
+            .await
+            .map_err(NetworkError::from)?;
 
         Ok(())
     }
@@ -285,7 +308,8 @@ impl NetworkClient {
                 nonce,
                 proof_id: proof_id.to_string(),
             })
-            .await?;
+            .await
+            .map_err(NetworkError::from)?;
 
         Ok(res)
     }
@@ -313,7 +337,11 @@ impl NetworkClient {
             callback: callback.to_vec(),
             callback_data: callback_data.to_vec(),
         };
-        let result = self.rpc.relay_proof(req).await?;
+        let result = self
+            .rpc
+            .relay_proof(req)
+            .await
+            .map_err(NetworkError::from)?;
         Ok(result.tx_id)
     }
 }
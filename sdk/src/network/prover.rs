@@ -1,53 +1,190 @@
-use std::{env, time::Duration};
+use std::{
+    env,
+    fmt::Debug,
+    iter,
+    time::{Duration, Instant},
+};
 
 use crate::proto::network::ProofMode;
 use crate::{
-    network::client::{NetworkClient, DEFAULT_PROVER_NETWORK_RPC},
-    proto::network::{ProofStatus, TransactionStatus},
+    network::client::{NetworkClient, DEFAULT_PROVER_NETWORK_RPC, TIMEOUT},
+    network::retry::{is_transient, is_unavailable, RetryPolicy},
+    network::status::{NetworkError, ProofRequestId, ProofRequestStatus},
+    proto::network::TransactionStatus,
     Prover,
 };
-use crate::{SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof, SP1ProvingKey, SP1VerifyingKey};
+use crate::{
+    ProofMetadata, SP1CompressedProof, SP1Groth16Bls12381Proof, SP1Groth16Bn254Proof,
+    SP1PlonkBn254Proof, SP1Proof, SP1ProofWithPublicValues, SP1ProvingKey, SP1VerifyingKey,
+};
 use anyhow::{Context, Result};
-use serde::de::DeserializeOwned;
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
 use sp1_prover::install::PLONK_BN254_ARTIFACTS_COMMIT;
 use sp1_prover::utils::block_on;
 use sp1_prover::{SP1Prover, SP1Stdin};
-use tokio::{runtime, time::sleep};
+use tokio::{
+    runtime,
+    task::block_in_place,
+    time::{sleep, timeout},
+};
 
 use crate::provers::{LocalProver, ProverType};
 
 /// An implementation of [crate::ProverClient] that can generate proofs on a remote RPC server.
+///
+/// A prover which falls back to proving locally and has a pickup deadline proves locally if a
+/// request is not picked up by a prover of the network within the deadline, so that the latency of
+/// a proof is bounded by the deadline and the time of a local proof. The requests it gives up on
+/// are left on the network.
 pub struct NetworkProver {
     client: NetworkClient,
     local_prover: LocalProver,
+    retry_policy: RetryPolicy,
+    fallback_to_local: bool,
+    pickup_deadline: Option<Duration>,
 }
 
 impl NetworkProver {
-    /// Creates a new [NetworkProver].
+    /// Creates a new [NetworkProver], which retries failed requests with the default
+    /// [RetryPolicy] and does not fall back to proving locally.
     pub fn new() -> Self {
+        Self::with_policy(RetryPolicy::default(), false)
+    }
+
+    /// Creates a new [NetworkProver] which retries failed requests with `retry_policy`, and proves
+    /// locally once the prover network is unavailable if `fallback_to_local` is set.
+    pub fn with_policy(retry_policy: RetryPolicy, fallback_to_local: bool) -> Self {
         let private_key = env::var("SP1_PRIVATE_KEY")
             .unwrap_or_else(|_| panic!("SP1_PRIVATE_KEY must be set for remote proving"));
         let local_prover = LocalProver::new();
         Self {
             client: NetworkClient::new(&private_key),
             local_prover,
+            retry_policy,
+            fallback_to_local,
+            pickup_deadline: None,
         }
     }
 
-    pub async fn prove_async<P: DeserializeOwned>(
+    /// Sets the time a request may wait for a prover of the network to pick it up, after which
+    /// [Self::wait_proof] fails with a [NetworkError::NotPickedUp], and the prover proves locally
+    /// if it falls back to proving locally. A network which does not answer the submission of the
+    /// request within the deadline fails it with a [NetworkError::Unresponsive].
+    pub fn with_pickup_deadline(mut self, pickup_deadline: Duration) -> Self {
+        self.pickup_deadline = Some(pickup_deadline);
+        self
+    }
+
+    /// The policy with which the prover retries failed requests.
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// The time a request may wait for a prover of the network to pick it up, if it is bounded.
+    pub fn pickup_deadline(&self) -> Option<Duration> {
+        self.pickup_deadline
+    }
+
+    /// Requests a proof of the given mode from the network, and polls its status until it is
+    /// fulfilled. Waiting for the proof does not block the async runtime.
+    ///
+    /// The request is sent again if it fails because of the network or of its prover, as set by
+    /// the [RetryPolicy] of the prover.
+    pub async fn prove_remote<P: DeserializeOwned>(
         &self,
         elf: &[u8],
-        stdin: SP1Stdin,
+        mut stdin: SP1Stdin,
         mode: ProofMode,
     ) -> Result<P> {
-        let client = &self.client;
+        // Streams are not serialized, so buffer any streamed input before sending it.
+        stdin.buffer_stream()?;
+        self.simulate(elf, &stdin)?;
 
+        let mut attempt = 1;
+        loop {
+            let result = match self.submit_proof_within_deadline(elf, &stdin, mode).await {
+                Ok(id) => self.wait_proof(&id).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Err(e) if attempt < self.retry_policy.max_attempts && is_transient(&e) => {
+                    let backoff = self.retry_policy.backoff(attempt);
+                    log::warn!(
+                        "Proof request attempt {} failed, retrying in {:?}: {:#}",
+                        attempt,
+                        backoff,
+                        e
+                    );
+                    sleep(backoff).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Proves with [Self::prove_remote], or with `local` if the prover network is unavailable and
+    /// the prover falls back to proving locally.
+    async fn prove_with_fallback<P: DeserializeOwned + Serialize + Debug + Clone>(
+        &self,
+        pk: &SP1ProvingKey,
+        mut stdin: SP1Stdin,
+        mode: ProofMode,
+        local: impl FnOnce(
+            &LocalProver,
+            &SP1ProvingKey,
+            SP1Stdin,
+        ) -> Result<SP1ProofWithPublicValues<P>>,
+    ) -> Result<SP1ProofWithPublicValues<P>> {
+        let start = Instant::now();
+        // Clones share a stream, so buffer it first for the local prover to read it as well.
+        stdin.buffer_stream()?;
+        let fallback_stdin = self.fallback_to_local.then(|| stdin.clone());
+        let result = self
+            .prove_remote::<SP1ProofWithPublicValues<P>>(&pk.elf, stdin, mode)
+            .await;
+        match (result, fallback_stdin) {
+            (Ok(mut proof), _) => {
+                // The network does not report the metadata of the proofs it generates.
+                proof.metadata = ProofMetadata::from_events(iter::empty(), start.elapsed());
+                proof.metadata.peak_memory = None;
+                Ok(proof)
+            }
+            (Err(e), Some(stdin)) if is_unavailable(&e) => {
+                log::warn!(
+                    "The prover network is unavailable, proving locally: {:#}",
+                    e
+                );
+                block_in_place(|| local(&self.local_prover, pk, stdin))
+            }
+            (Err(e), _) => Err(e),
+        }
+    }
+
+    /// Requests a proof of the given mode from the network, and returns the id of the request
+    /// without waiting for the proof.
+    pub async fn request_proof(
+        &self,
+        elf: &[u8],
+        mut stdin: SP1Stdin,
+        mode: ProofMode,
+    ) -> Result<ProofRequestId> {
+        // Streams are not serialized, so buffer any streamed input before sending it.
+        stdin.buffer_stream()?;
+        self.simulate(elf, &stdin)?;
+        self.submit_proof(elf, &stdin, mode).await
+    }
+
+    /// Executes the program before requesting its proof, so that a failing execution does not
+    /// cost a request, unless the `SKIP_SIMULATION` environment variable is set.
+    fn simulate(&self, elf: &[u8], stdin: &SP1Stdin) -> Result<()> {
         let skip_simulation = env::var("SKIP_SIMULATION")
             .map(|val| val == "true")
             .unwrap_or(false);
 
         if !skip_simulation {
-            let (_, report) = SP1Prover::execute(elf, &stdin)?;
+            let (_, report) = block_in_place(|| SP1Prover::execute(elf, stdin))?;
             log::info!(
                 "Simulation complete, cycles: {}",
                 report.total_instruction_count()
@@ -55,42 +192,140 @@ impl NetworkProver {
         } else {
             log::info!("Skipping simulation");
         }
+        Ok(())
+    }
+
+    /// Submits a proof request, failing with a [NetworkError::Unresponsive] if the network does
+    /// not answer within the pickup deadline of the prover.
+    async fn submit_proof_within_deadline(
+        &self,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        mode: ProofMode,
+    ) -> Result<ProofRequestId> {
+        let submission = self.submit_proof(elf, stdin, mode);
+        match self.pickup_deadline {
+            Some(deadline) => timeout(deadline, submission)
+                .await
+                .unwrap_or_else(|_| Err(NetworkError::Unresponsive { deadline }.into())),
+            None => submission.await,
+        }
+    }
 
+    async fn submit_proof(
+        &self,
+        elf: &[u8],
+        stdin: &SP1Stdin,
+        mode: ProofMode,
+    ) -> Result<ProofRequestId> {
         let version = PLONK_BN254_ARTIFACTS_COMMIT;
         log::info!("Client version {}", version);
 
-        let proof_id = client.create_proof(elf, &stdin, mode, version).await?;
-        log::info!("Created {}", proof_id);
+        let id = self.client.create_proof(elf, stdin, mode, version).await?;
+        log::info!("Created {}", id);
 
         if NetworkClient::rpc_url() == DEFAULT_PROVER_NETWORK_RPC {
-            log::info!(
-                "View in explorer: https://explorer.succinct.xyz/{}",
-                proof_id.split('_').last().unwrap_or(&proof_id)
-            );
+            log::info!("View in explorer: {}", id.explorer_url());
         }
+        Ok(id)
+    }
 
-        let mut is_claimed = false;
+    /// Gets the status of a proof request.
+    pub async fn proof_status(&self, id: &ProofRequestId) -> Result<ProofRequestStatus> {
+        self.client.get_request_status(id).await
+    }
+
+    /// Polls the status of a proof request until it is fulfilled, and returns the proof.
+    ///
+    /// Returns a [NetworkError::RequestFailed] if the prover gives up on the request, a
+    /// [NetworkError::NotPickedUp] if no prover claims the request before the pickup deadline of
+    /// the prover, and a [NetworkError::Timeout] if the request is not fulfilled before its
+    /// deadline. Polls which fail because of the network are retried as set by the [RetryPolicy]
+    /// of the prover.
+    pub async fn wait_proof<P: DeserializeOwned>(&self, id: &ProofRequestId) -> Result<P> {
+        let start = Instant::now();
+        let mut last_status = None;
+        let mut failed_polls = 0;
         loop {
-            let (status, maybe_proof) = client.get_proof_status::<P>(&proof_id).await?;
+            // Until the request is picked up, a poll which is not answered is bounded by the
+            // pickup deadline.
+            let poll = self.client.get_proof_status::<P>(id);
+            let awaiting_pickup = matches!(
+                last_status,
+                None | Some(ProofRequestStatus::Preparing | ProofRequestStatus::Queued)
+            );
+            let poll = match self.pickup_deadline.filter(|_| awaiting_pickup) {
+                Some(deadline) => timeout(deadline.saturating_sub(start.elapsed()), poll)
+                    .await
+                    .unwrap_or_else(|_| {
+                        Err(NetworkError::NotPickedUp {
+                            id: id.clone(),
+                            deadline,
+                        }
+                        .into())
+                    }),
+                None => poll.await,
+            };
+            let (status, maybe_proof) = match poll {
+                Ok(res) => {
+                    failed_polls = 0;
+                    res
+                }
+                Err(e) if failed_polls + 1 < self.retry_policy.max_attempts && is_transient(&e) => {
+                    failed_polls += 1;
+                    let backoff = self.retry_policy.backoff(failed_polls);
+                    log::warn!(
+                        "Failed to get the status of {}, retrying in {:?}: {:#}",
+                        id,
+                        backoff,
+                        e
+                    );
+                    sleep(backoff).await;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            if last_status.as_ref() != Some(&status) {
+                log::info!("Proof request {}", status);
+            }
 
-            match status.status() {
-                ProofStatus::ProofFulfilled => {
-                    return Ok(maybe_proof.unwrap());
+            match status {
+                ProofRequestStatus::Complete => {
+                    return maybe_proof
+                        .ok_or_else(|| NetworkError::MissingProofUrl { id: id.clone() }.into());
                 }
-                ProofStatus::ProofClaimed => {
-                    if !is_claimed {
-                        log::info!("Proof request claimed, proving...");
-                        is_claimed = true;
+                ProofRequestStatus::Failed {
+                    reason,
+                    description,
+                } => {
+                    return Err(NetworkError::RequestFailed {
+                        id: id.clone(),
+                        reason,
+                        description,
                     }
+                    .into());
                 }
-                ProofStatus::ProofUnclaimed => {
-                    return Err(anyhow::anyhow!(
-                        "Proof generation failed: {}",
-                        status.unclaim_description()
-                    ));
+                ProofRequestStatus::Preparing | ProofRequestStatus::Queued => {
+                    if let Some(deadline) = self.pickup_deadline {
+                        if start.elapsed() > deadline {
+                            return Err(NetworkError::NotPickedUp {
+                                id: id.clone(),
+                                deadline,
+                            }
+                            .into());
+                        }
+                    }
                 }
                 _ => {}
             }
+            if start.elapsed() > TIMEOUT {
+                return Err(NetworkError::Timeout {
+                    id: id.clone(),
+                    timeout: TIMEOUT,
+                }
+                .into());
+            }
+            last_status = Some(status);
             sleep(Duration::from_secs(2)).await;
         }
     }
@@ -160,6 +395,7 @@ impl NetworkProver {
     }
 }
 
+#[async_trait]
 impl Prover for NetworkProver {
     fn id(&self) -> ProverType {
         ProverType::Network
@@ -174,15 +410,59 @@ impl Prover for NetworkProver {
     }
 
     fn prove(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Proof> {
-        block_on(self.prove_async(&pk.elf, stdin, ProofMode::Core))
+        block_on(self.prove_async(pk, stdin))
     }
 
     fn prove_compressed(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1CompressedProof> {
-        block_on(self.prove_async(&pk.elf, stdin, ProofMode::Compressed))
+        block_on(self.prove_compressed_async(pk, stdin))
     }
 
     fn prove_plonk(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1PlonkBn254Proof> {
-        block_on(self.prove_async(&pk.elf, stdin, ProofMode::Plonk))
+        block_on(self.prove_plonk_async(pk, stdin))
+    }
+
+    fn prove_groth16(&self, _pk: &SP1ProvingKey, _stdin: SP1Stdin) -> Result<SP1Groth16Bn254Proof> {
+        Err(anyhow::anyhow!(
+            "the prover network does not generate groth16 proofs yet"
+        ))
+    }
+
+    fn prove_groth16_bls12381(
+        &self,
+        _pk: &SP1ProvingKey,
+        _stdin: SP1Stdin,
+    ) -> Result<SP1Groth16Bls12381Proof> {
+        Err(anyhow::anyhow!(
+            "the prover network does not generate groth16 proofs over bls12-381"
+        ))
+    }
+
+    async fn prove_async(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Proof> {
+        self.prove_with_fallback(pk, stdin, ProofMode::Core, LocalProver::prove)
+            .await
+    }
+
+    async fn prove_compressed_async(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1CompressedProof> {
+        self.prove_with_fallback(
+            pk,
+            stdin,
+            ProofMode::Compressed,
+            LocalProver::prove_compressed,
+        )
+        .await
+    }
+
+    async fn prove_plonk_async(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1PlonkBn254Proof> {
+        self.prove_with_fallback(pk, stdin, ProofMode::Plonk, LocalProver::prove_plonk)
+            .await
     }
 }
 
@@ -0,0 +1,106 @@
+//! The retry policy of the network prover.
+//!
+//! [crate::NetworkProver] retries the proof requests which fail because of the network or of the
+//! prover which claimed them, with an exponential backoff, and can fall back to proving locally
+//! once the prover network is unavailable. It does not retry the requests which fail because of
+//! the program or its input, since they fail again on any prover.
+
+use std::time::Duration;
+
+use crate::network::status::NetworkError;
+use crate::proto::network::UnclaimReason;
+
+/// How many times and how often [crate::NetworkProver] retries a failed proof request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// The number of attempts of a request, including the first one.
+    pub max_attempts: u32,
+    /// The delay before the first retry.
+    pub initial_backoff: Duration,
+    /// The maximum delay between two attempts.
+    pub max_backoff: Duration,
+    /// The factor of the delay between two attempts with each retry.
+    pub multiplier: f64,
+}
+
+impl RetryPolicy {
+    /// A policy which never retries.
+    pub const fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            multiplier: 1.0,
+        }
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// The delay before the next attempt after `attempt` failed attempts.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let backoff = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        // A negative or NaN backoff, from a negative or NaN multiplier, waits for the maximum.
+        Duration::try_from_secs_f64(backoff)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+/// Retries a request up to 3 times, 2 seconds after the first failure and then twice as long
+/// after each failure, up to a minute.
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_secs(2),
+            max_backoff: Duration::from_secs(60),
+            multiplier: 2.0,
+        }
+    }
+}
+
+/// Whether `err` is a failure of the network or of a prover of the network, which may not happen
+/// again if the request is retried.
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<NetworkError>() {
+        Some(NetworkError::RpcError(_)) => true,
+        Some(NetworkError::RequestFailed { reason, .. }) => *reason != UnclaimReason::Invalid,
+        Some(_) => false,
+        None => err
+            .chain()
+            .any(|cause| cause.is::<reqwest::Error>() || cause.is::<reqwest_middleware::Error>()),
+    }
+}
+
+/// Whether `err` means that the prover network is unavailable, so that the proof may be generated
+/// locally instead: a transient failure which outlived the retries, a network which did not
+/// respond, or a request which was not picked up or fulfilled in time.
+pub fn is_unavailable(err: &anyhow::Error) -> bool {
+    is_transient(err)
+        || matches!(
+            err.downcast_ref::<NetworkError>(),
+            Some(
+                NetworkError::Timeout { .. }
+                    | NetworkError::NotPickedUp { .. }
+                    | NetworkError::Unresponsive { .. }
+            )
+        )
+}
@@ -0,0 +1,153 @@
+use std::{fmt, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::proto::network::{GetProofStatusResponse, ProofStatus, UnclaimReason};
+
+/// The identifier of a proof request on the prover network.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ProofRequestId(String);
+
+impl ProofRequestId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The URL of the request in the explorer of the default prover network.
+    pub fn explorer_url(&self) -> String {
+        format!(
+            "https://explorer.succinct.xyz/{}",
+            self.0.split('_').last().unwrap_or(&self.0)
+        )
+    }
+}
+
+impl fmt::Display for ProofRequestId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The state of a proof request in its lifecycle on the prover network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofRequestStatus {
+    /// The request has been created, but its program and stdin have not been submitted yet.
+    Preparing,
+    /// The request has been submitted and is waiting for a prover to claim it.
+    Queued,
+    /// A prover has claimed the request and has not reported any progress yet.
+    Assigned,
+    /// A prover is proving the shards of the execution.
+    Proving {
+        proven_shards: u32,
+        total_shards: u32,
+    },
+    /// The proof has been generated and can be downloaded.
+    Complete,
+    /// The prover gave up on the request.
+    Failed {
+        reason: UnclaimReason,
+        description: String,
+    },
+}
+
+impl ProofRequestStatus {
+    /// Reads the status of the request `id` from a status response.
+    pub fn from_response(
+        id: &ProofRequestId,
+        res: &GetProofStatusResponse,
+    ) -> Result<Self, NetworkError> {
+        let status =
+            ProofStatus::try_from(res.status).map_err(|_| NetworkError::UnknownStatus {
+                id: id.clone(),
+                status: res.status,
+            })?;
+        Ok(match status {
+            ProofStatus::ProofPreparing => Self::Preparing,
+            ProofStatus::ProofRequested => Self::Queued,
+            ProofStatus::ProofClaimed => match (res.proven_shards, res.total_shards) {
+                (Some(proven_shards), Some(total_shards)) => Self::Proving {
+                    proven_shards,
+                    total_shards,
+                },
+                _ => Self::Assigned,
+            },
+            ProofStatus::ProofUnclaimed => Self::Failed {
+                reason: res.unclaim_reason(),
+                description: res.unclaim_description().to_string(),
+            },
+            ProofStatus::ProofFulfilled => Self::Complete,
+            ProofStatus::ProofUnspecifiedStatus => {
+                return Err(NetworkError::UnknownStatus {
+                    id: id.clone(),
+                    status: res.status,
+                })
+            }
+        })
+    }
+
+    /// Whether the request will not change state anymore.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Complete | Self::Failed { .. })
+    }
+}
+
+impl fmt::Display for ProofRequestStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Preparing => write!(f, "preparing"),
+            Self::Queued => write!(f, "queued"),
+            Self::Assigned => write!(f, "assigned to a prover"),
+            Self::Proving {
+                proven_shards,
+                total_shards,
+            } => write!(
+                f,
+                "proving, {}/{} shards proven",
+                proven_shards, total_shards
+            ),
+            Self::Complete => write!(f, "complete"),
+            Self::Failed {
+                reason,
+                description,
+            } => write!(f, "failed ({}): {}", reason.as_str_name(), description),
+        }
+    }
+}
+
+/// An error of a proof request on the prover network.
+///
+/// The methods of [crate::network::client::NetworkClient] and [crate::NetworkProver] return
+/// [anyhow::Error]s, which can be downcast to this type to tell these errors apart.
+#[derive(Error, Debug)]
+pub enum NetworkError {
+    #[error("rpc error: {0}")]
+    RpcError(#[from] twirp::ClientError),
+    #[error("proof request {id} failed ({}): {description}", .reason.as_str_name())]
+    RequestFailed {
+        id: ProofRequestId,
+        reason: UnclaimReason,
+        description: String,
+    },
+    #[error("proof request {id} has an unknown status {status}")]
+    UnknownStatus { id: ProofRequestId, status: i32 },
+    #[error("proof request {id} is fulfilled without a proof url")]
+    MissingProofUrl { id: ProofRequestId },
+    #[error("proof request {id} was not fulfilled within {timeout:?}")]
+    Timeout {
+        id: ProofRequestId,
+        timeout: Duration,
+    },
+    #[error("proof request {id} was not picked up by a prover within {deadline:?}")]
+    NotPickedUp {
+        id: ProofRequestId,
+        deadline: Duration,
+    },
+    #[error("the prover network did not respond within {deadline:?}")]
+    Unresponsive { deadline: Duration },
+}
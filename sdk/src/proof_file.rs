@@ -0,0 +1,147 @@
+//! The files of saved proofs.
+//!
+//! A proof file is a proof in the versioned encoding of [sp1_prover::encoding], with the magic
+//! bytes [PROOF_FILE_MAGIC], preceded by a [ProofHeader] which describes the proof and followed by
+//! the SHA-256 checksum of everything before it. A file whose checksum does not match is rejected
+//! as corrupted, and [SP1ProofWithPublicValues::load_for_program] also rejects the proofs of other
+//! programs than the one of the given verifying key.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sp1_prover::encoding;
+use thiserror::Error;
+
+use crate::metadata::SP1_VERSION;
+use crate::registry::vkey_hash;
+use crate::{SP1ProofWithPublicValues, SP1VerifyingKey};
+
+/// The magic bytes of proof files.
+pub const PROOF_FILE_MAGIC: [u8; 4] = *b"SP1F";
+
+/// The length of the checksum at the end of a proof file.
+pub const CHECKSUM_LEN: usize = 32;
+
+#[derive(Error, Debug)]
+pub enum ProofFileError {
+    #[error("the proof file is shorter than its checksum")]
+    Truncated,
+    #[error("the checksum of the proof file does not match its contents: the file is corrupted")]
+    ChecksumMismatch,
+    #[error("the proof file does not record the verifying key of its program")]
+    MissingVkey,
+    #[error("the proof is of the program with the vkey hash {found}, expected {expected}")]
+    VkeyMismatch { expected: String, found: String },
+}
+
+/// The description of a saved proof, which is written before the proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofHeader {
+    /// The version of SP1 which saved the proof.
+    pub sp1_version: String,
+    /// The circuit version of the prover which generated the proof.
+    pub circuit_version: String,
+    /// The hash of the verifying key of the program, as computed by [vkey_hash], if the proof was
+    /// saved with [SP1ProofWithPublicValues::save_for_program].
+    pub vkey_hash: Option<String>,
+    /// The name of the program, if the proof was saved with
+    /// [SP1ProofWithPublicValues::save_for_program].
+    pub program_name: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ProofFileRef<'a, P: Serialize> {
+    header: &'a ProofHeader,
+    proof: &'a P,
+}
+
+#[derive(Deserialize)]
+struct ProofFile<P> {
+    header: ProofHeader,
+    proof: P,
+}
+
+impl<P: std::fmt::Debug + Clone + Serialize + DeserializeOwned> SP1ProofWithPublicValues<P> {
+    /// Saves the proof to a path, with a header of the versions of SP1 and of its circuits and a
+    /// checksum, so that [SP1ProofWithPublicValues::load] rejects proofs saved by an incompatible
+    /// version of SP1 and corrupted files.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.save_with_header(path, self.header(None, None))
+    }
+
+    /// Saves the proof to a path like [SP1ProofWithPublicValues::save], and records the hash of
+    /// `vk` and the name of the program in the header.
+    pub fn save_for_program(
+        &self,
+        path: impl AsRef<Path>,
+        vk: &SP1VerifyingKey,
+        program_name: &str,
+    ) -> Result<()> {
+        self.save_with_header(
+            path,
+            self.header(Some(vkey_hash(vk)), Some(program_name.to_string())),
+        )
+    }
+
+    /// Loads a proof saved by [SP1ProofWithPublicValues::save] from a path.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::load_with_header(path)?.0)
+    }
+
+    /// Loads a proof saved by [SP1ProofWithPublicValues::save] from a path, with its header.
+    pub fn load_with_header(path: impl AsRef<Path>) -> Result<(Self, ProofHeader)> {
+        let bytes = fs::read(path)?;
+        if bytes.len() < CHECKSUM_LEN {
+            return Err(ProofFileError::Truncated.into());
+        }
+        // The checksum is only checked for files with the magic bytes of proof files, so that a
+        // file of another kind is reported as such instead of as corrupted.
+        let (contents, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        if contents.starts_with(&PROOF_FILE_MAGIC)
+            && Sha256::digest(contents).as_slice() != checksum
+        {
+            return Err(ProofFileError::ChecksumMismatch.into());
+        }
+        let file: ProofFile<Self> = encoding::decode(PROOF_FILE_MAGIC, contents)?;
+        Ok((file.proof, file.header))
+    }
+
+    /// Loads a proof saved by [SP1ProofWithPublicValues::save_for_program] from a path, and
+    /// checks that it is a proof of the program of `vk`.
+    pub fn load_for_program(path: impl AsRef<Path>, vk: &SP1VerifyingKey) -> Result<Self> {
+        let (proof, header) = Self::load_with_header(path)?;
+        let expected = vkey_hash(vk);
+        match header.vkey_hash {
+            Some(found) if found == expected => Ok(proof),
+            Some(found) => Err(ProofFileError::VkeyMismatch { expected, found }.into()),
+            None => Err(ProofFileError::MissingVkey.into()),
+        }
+    }
+
+    /// The header of the proof, with the hash of the verifying key and the name of its program.
+    fn header(&self, vkey_hash: Option<String>, program_name: Option<String>) -> ProofHeader {
+        ProofHeader {
+            sp1_version: SP1_VERSION.to_string(),
+            circuit_version: self.circuit_version.clone(),
+            vkey_hash,
+            program_name,
+        }
+    }
+
+    fn save_with_header(&self, path: impl AsRef<Path>, header: ProofHeader) -> Result<()> {
+        let mut bytes = encoding::encode(
+            PROOF_FILE_MAGIC,
+            &ProofFileRef {
+                header: &header,
+                proof: self,
+            },
+        )?;
+        let checksum = Sha256::digest(&bytes);
+        bytes.extend_from_slice(&checksum);
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+}
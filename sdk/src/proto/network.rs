@@ -223,6 +223,12 @@ pub struct GetProofStatusResponse {
     /// If the proof was unclaimed, the description detailing why.
     #[prost(string, optional, tag = "4")]
     pub unclaim_description: ::core::option::Option<::prost::alloc::string::String>,
+    /// If the proof is being proven, the number of shards which have been proven.
+    #[prost(uint32, optional, tag = "5")]
+    pub proven_shards: ::core::option::Option<u32>,
+    /// If the proof is being proven, the total number of shards of the execution.
+    #[prost(uint32, optional, tag = "6")]
+    pub total_shards: ::core::option::Option<u32>,
 }
 /// The request to get proof requests by a given status.
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -1,24 +1,64 @@
+use std::time::Instant;
+
 use anyhow::Result;
 use cfg_if::cfg_if;
-use sp1_prover::{SP1Prover, SP1Stdin};
+use sp1_core::utils::{ProgressReporter, SP1CoreOpts, TraceStorage};
+use sp1_prover::encoding::SP1_CIRCUIT_VERSION;
+#[cfg(feature = "plonk")]
+use sp1_prover::{InnerSC, OuterSC, SP1PublicValues, SP1ReduceProof};
+use sp1_prover::{RecursionShape, SP1Prover, SP1Stdin, SecurityPreset};
 
 use crate::{
-    Prover, SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof, SP1ProofWithPublicValues,
-    SP1ProvingKey, SP1VerifyingKey,
+    ProofMetadata, Prover, SP1CompressedProof, SP1Groth16Bls12381Proof, SP1Groth16Bn254Proof,
+    SP1PlonkBn254Proof, SP1Proof, SP1ProofWithPublicValues, SP1ProvingKey, SP1VerifyingKey,
 };
 
 use super::ProverType;
 
+/// The options of a [LocalProver].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalProverOpts {
+    /// The shape of the recursion tree which compresses the shard proofs, which trades the latency
+    /// of compressed proofs against their memory.
+    pub recursion_shape: RecursionShape,
+    /// The conjectured security of the core, compress and shrink proofs. The proofs are only
+    /// verified by a prover of the same preset.
+    pub security_preset: SecurityPreset,
+    /// Where the traces of the shards of the core proofs are kept while they are proven, which
+    /// trades the throughput of the prover against its memory.
+    pub trace_storage: TraceStorage,
+}
+
 /// An implementation of [crate::ProverClient] that can generate end-to-end proofs locally.
 pub struct LocalProver {
     prover: SP1Prover,
+    core_opts: SP1CoreOpts,
 }
 
 impl LocalProver {
     /// Creates a new [LocalProver].
     pub fn new() -> Self {
-        let prover = SP1Prover::new();
-        Self { prover }
+        Self::with_opts(LocalProverOpts::default())
+    }
+
+    /// Creates a new [LocalProver] with the given options.
+    pub fn with_opts(opts: LocalProverOpts) -> Self {
+        let prover = SP1Prover::with_security_preset(opts.security_preset)
+            .with_recursion_shape(opts.recursion_shape);
+        let core_opts = SP1CoreOpts {
+            trace_storage: opts.trace_storage,
+            ..Default::default()
+        };
+        Self { prover, core_opts }
+    }
+
+    /// The options for core and compressed proofs, whose public values include the exit code, so
+    /// that programs which halt with a nonzero exit code are proven and rejected when verifying.
+    fn nonzero_exit_code_opts(&self) -> SP1CoreOpts {
+        SP1CoreOpts {
+            allow_nonzero_exit_code: true,
+            ..self.core_opts
+        }
     }
 }
 
@@ -36,57 +76,239 @@ impl Prover for LocalProver {
     }
 
     fn prove(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Proof> {
-        let proof = self.prover.prove_core(pk, &stdin)?;
+        self.prove_with_progress(pk, stdin, &ProgressReporter::default())
+    }
+
+    fn prove_compressed(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1CompressedProof> {
+        self.prove_compressed_with_progress(pk, stdin, &ProgressReporter::default())
+    }
+
+    fn prove_plonk(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1PlonkBn254Proof> {
+        self.prove_plonk_with_progress(pk, stdin, &ProgressReporter::default())
+    }
+
+    fn prove_with_progress(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+        progress: &ProgressReporter,
+    ) -> Result<SP1Proof> {
+        let start = Instant::now();
+        let (progress, events) = progress.tee();
+        let proof = self.prover.prove_core_with_progress(
+            pk,
+            &stdin,
+            self.nonzero_exit_code_opts(),
+            &progress,
+        )?;
         Ok(SP1ProofWithPublicValues {
             proof: proof.proof.0,
             stdin: proof.stdin,
             public_values: proof.public_values,
+            circuit_version: SP1_CIRCUIT_VERSION.to_string(),
+            metadata: ProofMetadata::from_events(events.try_iter(), start.elapsed()),
         })
     }
 
-    fn prove_compressed(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1CompressedProof> {
-        let proof = self.prover.prove_core(pk, &stdin)?;
+    fn prove_compressed_with_progress(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+        progress: &ProgressReporter,
+    ) -> Result<SP1CompressedProof> {
+        let start = Instant::now();
+        let (progress, events) = progress.tee();
+        let proof = self.prover.prove_core_with_progress(
+            pk,
+            &stdin,
+            self.nonzero_exit_code_opts(),
+            &progress,
+        )?;
         let deferred_proofs = stdin.proofs.iter().map(|p| p.0.clone()).collect();
         let public_values = proof.public_values.clone();
-        let reduce_proof = self.prover.compress(&pk.vk, proof, deferred_proofs)?;
+        let reduce_proof =
+            self.prover
+                .compress_with_progress(&pk.vk, proof, deferred_proofs, &progress)?;
         Ok(SP1CompressedProof {
             proof: reduce_proof.proof,
             stdin,
             public_values,
+            circuit_version: SP1_CIRCUIT_VERSION.to_string(),
+            metadata: ProofMetadata::from_events(events.try_iter(), start.elapsed()),
         })
     }
 
     #[allow(unused)]
-    fn prove_plonk(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1PlonkBn254Proof> {
+    fn prove_plonk_with_progress(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+        progress: &ProgressReporter,
+    ) -> Result<SP1PlonkBn254Proof> {
         cfg_if! {
             if #[cfg(feature = "plonk")] {
+                use sp1_core::utils::ProvingStage;
 
-                let proof = self.prover.prove_core(pk, &stdin)?;
-                let deferred_proofs = stdin.proofs.iter().map(|p| p.0.clone()).collect();
-                let public_values = proof.public_values.clone();
-                let reduce_proof = self.prover.compress(&pk.vk, proof, deferred_proofs)?;
-                let compress_proof = self.prover.shrink(reduce_proof)?;
-                let outer_proof = self.prover.wrap_bn254(compress_proof)?;
-
-                let plonk_bn254_aritfacts = if sp1_prover::build::sp1_dev_mode() {
+                let start = Instant::now();
+                let (progress, events) = progress.tee();
+                let (outer_proof, public_values) = self.prove_outer(pk, &stdin, &progress)?;
+                let plonk_bn254_aritfacts = if let Some(srs) = sp1_prover::srs::PlonkSrs::from_env()? {
+                    sp1_prover::build::try_build_plonk_bn254_artifacts_with_srs(
+                        &self.prover.wrap_vk,
+                        &outer_proof.proof,
+                        &srs,
+                    )?
+                } else if sp1_prover::build::sp1_dev_mode() {
                     sp1_prover::build::try_build_plonk_bn254_artifacts_dev(
                         &self.prover.wrap_vk,
                         &outer_proof.proof,
                     )
                 } else {
-                    sp1_prover::build::try_install_plonk_bn254_artifacts()
+                    sp1_prover::build::try_install_or_build_plonk_bn254_artifacts(
+                        &self.prover.wrap_vk,
+                        &outer_proof.proof,
+                    )
                 };
                 let proof = self.prover.wrap_plonk_bn254(outer_proof, &plonk_bn254_aritfacts);
+                progress.report(ProvingStage::PlonkWrapped);
+                Ok(SP1ProofWithPublicValues {
+                    proof,
+                    stdin,
+                    public_values,
+                    circuit_version: SP1_CIRCUIT_VERSION.to_string(),
+                    metadata: ProofMetadata::from_events(events.try_iter(), start.elapsed()),
+                })
+            } else {
+                panic!("plonk feature not enabled")
+            }
+        }
+    }
+
+    #[allow(unused)]
+    fn prove_groth16(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Groth16Bn254Proof> {
+        cfg_if! {
+            if #[cfg(feature = "plonk")] {
+                use sp1_core::utils::ProvingStage;
+
+                let start = Instant::now();
+                let (progress, events) = ProgressReporter::channel();
+                let (outer_proof, public_values) = self.prove_outer(pk, &stdin, &progress)?;
+                let groth16_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
+                    sp1_prover::build::try_build_groth16_bn254_artifacts_dev(
+                        &self.prover.wrap_vk,
+                        &outer_proof.proof,
+                    )
+                } else {
+                    sp1_prover::build::try_install_or_build_groth16_bn254_artifacts(
+                        &self.prover.wrap_vk,
+                        &outer_proof.proof,
+                    )
+                };
+                let proof = self
+                    .prover
+                    .wrap_groth16_bn254(outer_proof, &groth16_bn254_artifacts);
+                progress.report(ProvingStage::Groth16Wrapped);
                 Ok(SP1ProofWithPublicValues {
                     proof,
                     stdin,
                     public_values,
+                    circuit_version: SP1_CIRCUIT_VERSION.to_string(),
+                    metadata: ProofMetadata::from_events(events.try_iter(), start.elapsed()),
                 })
             } else {
                 panic!("plonk feature not enabled")
             }
         }
     }
+
+    #[allow(unused)]
+    fn prove_groth16_bls12381(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1Groth16Bls12381Proof> {
+        cfg_if! {
+            if #[cfg(feature = "plonk")] {
+                use sp1_core::utils::ProvingStage;
+
+                let start = Instant::now();
+                let (progress, events) = ProgressReporter::channel();
+                let (shrunk_proof, public_values) = self.prove_shrunk(pk, &stdin, &progress)?;
+                let outer_proof = self.prover.wrap_bls12381(shrunk_proof)?;
+                progress.report(ProvingStage::Wrapped);
+                let wrap_vk = &self.prover.wrap_bls12381_keys().vk;
+                let groth16_bls12381_artifacts = if sp1_prover::build::sp1_dev_mode() {
+                    sp1_prover::build::try_build_groth16_bls12381_artifacts_dev(
+                        wrap_vk,
+                        &outer_proof.proof,
+                    )
+                } else {
+                    sp1_prover::build::try_build_groth16_bls12381_artifacts(
+                        wrap_vk,
+                        &outer_proof.proof,
+                    )
+                };
+                let proof = self
+                    .prover
+                    .wrap_groth16_bls12381(outer_proof, &groth16_bls12381_artifacts);
+                progress.report(ProvingStage::Groth16Wrapped);
+                Ok(SP1ProofWithPublicValues {
+                    proof,
+                    stdin,
+                    public_values,
+                    circuit_version: SP1_CIRCUIT_VERSION.to_string(),
+                    metadata: ProofMetadata::from_events(events.try_iter(), start.elapsed()),
+                })
+            } else {
+                panic!("plonk feature not enabled")
+            }
+        }
+    }
+}
+
+impl LocalProver {
+    /// Proves, compresses, shrinks and wraps the execution of a program into the proof over the
+    /// SNARK-friendly field which the plonk and groth16 circuits verify.
+    #[cfg(feature = "plonk")]
+    fn prove_outer(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        progress: &ProgressReporter,
+    ) -> Result<(SP1ReduceProof<OuterSC>, SP1PublicValues)> {
+        use sp1_core::utils::ProvingStage;
+
+        let (compress_proof, public_values) = self.prove_shrunk(pk, stdin, progress)?;
+        let outer_proof = self.prover.wrap_bn254(compress_proof)?;
+        progress.report(ProvingStage::Wrapped);
+        Ok((outer_proof, public_values))
+    }
+
+    /// Proves, compresses and shrinks the execution of a program into the proof which the wraps
+    /// over BN254 and BLS12-381 verify.
+    #[cfg(feature = "plonk")]
+    fn prove_shrunk(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: &SP1Stdin,
+        progress: &ProgressReporter,
+    ) -> Result<(SP1ReduceProof<InnerSC>, SP1PublicValues)> {
+        use sp1_core::utils::ProvingStage;
+
+        // The exit code is not part of the public inputs of a SNARK proof, so programs which
+        // halt with a nonzero exit code are not proven.
+        let proof = self
+            .prover
+            .prove_core_with_progress(pk, stdin, self.core_opts, progress)?;
+        let deferred_proofs = stdin.proofs.iter().map(|p| p.0.clone()).collect();
+        let public_values = proof.public_values.clone();
+        let reduce_proof =
+            self.prover
+                .compress_with_progress(&pk.vk, proof, deferred_proofs, progress)?;
+        let compress_proof = self.prover.shrink(reduce_proof)?;
+        progress.report(ProvingStage::Shrunk);
+        Ok((compress_proof, public_values))
+    }
 }
 
 impl Default for LocalProver {
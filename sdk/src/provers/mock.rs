@@ -1,12 +1,21 @@
 #![allow(unused_variables)]
+use std::array;
+use std::iter;
+use std::time::Instant;
+
 use crate::{
-    Prover, SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof, SP1ProofVerificationError,
-    SP1ProofWithPublicValues, SP1ProvingKey, SP1VerifyingKey,
+    ProofMetadata, Prover, SP1CompressedProof, SP1Groth16Bls12381Proof, SP1Groth16Bn254Proof,
+    SP1PlonkBn254Proof, SP1Proof, SP1ProofVerificationError, SP1ProofWithPublicValues,
+    SP1ProvingKey, SP1VerifyingKey,
 };
 use anyhow::Result;
 use p3_field::PrimeField;
+use sp1_core::runtime::ExecutionReport;
+use sp1_core::utils::SP1CoreOpts;
+use sp1_prover::encoding::SP1_CIRCUIT_VERSION;
 use sp1_prover::{
-    verify::verify_plonk_bn254_public_inputs, HashableKey, PlonkBn254Proof, SP1Prover, SP1Stdin,
+    verify::verify_plonk_bn254_public_inputs, Groth16Bls12381Proof, Groth16Bn254Proof, HashableKey,
+    PlonkBn254Proof, SP1Prover, SP1PublicValues, SP1Stdin, NUM_PUBLIC_INPUTS,
 };
 
 use super::ProverType;
@@ -14,13 +23,42 @@ use super::ProverType;
 /// An implementation of [crate::ProverClient] that can generate mock proofs.
 pub struct MockProver {
     pub(crate) prover: SP1Prover,
+    /// Whether mock proofs are only generated for executions whose traces satisfy the constraints
+    /// of every chip, which is slower than executing the program but tells whether it would prove.
+    pub check_constraints: bool,
 }
 
 impl MockProver {
     /// Creates a new [MockProver].
     pub fn new() -> Self {
         let prover = SP1Prover::new();
-        Self { prover }
+        Self {
+            prover,
+            check_constraints: false,
+        }
+    }
+
+    /// Creates a new [MockProver] which checks the constraints of every chip on the traces of the
+    /// programs it proves.
+    pub fn with_constraint_checks() -> Self {
+        Self {
+            check_constraints: true,
+            ..Self::new()
+        }
+    }
+
+    /// Executes a program, checking the constraints if `check_constraints` is set, and returns its
+    /// public values with the report of the execution.
+    fn execute(&self, elf: &[u8], stdin: &SP1Stdin) -> Result<(SP1PublicValues, ExecutionReport)> {
+        if self.check_constraints {
+            Ok(SP1Prover::check_constraints(
+                elf,
+                stdin,
+                SP1CoreOpts::default(),
+            )?)
+        } else {
+            Ok(SP1Prover::execute(elf, stdin)?)
+        }
     }
 }
 
@@ -38,11 +76,14 @@ impl Prover for MockProver {
     }
 
     fn prove(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Proof> {
-        let (public_values, _) = SP1Prover::execute(&pk.elf, &stdin)?;
+        let start = Instant::now();
+        let (public_values, _) = self.execute(&pk.elf, &stdin)?;
         Ok(SP1ProofWithPublicValues {
             proof: vec![],
             stdin,
             public_values,
+            circuit_version: SP1_CIRCUIT_VERSION.to_string(),
+            metadata: ProofMetadata::from_events(iter::empty(), start.elapsed()),
         })
     }
 
@@ -55,18 +96,56 @@ impl Prover for MockProver {
     }
 
     fn prove_plonk(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1PlonkBn254Proof> {
-        let (public_values, _) = SP1Prover::execute(&pk.elf, &stdin)?;
+        let start = Instant::now();
+        let (public_values, report) = self.execute(&pk.elf, &stdin)?;
         Ok(SP1PlonkBn254Proof {
             proof: PlonkBn254Proof {
-                public_inputs: [
-                    pk.vk.hash_bn254().as_canonical_biguint().to_string(),
-                    public_values.hash().to_string(),
-                ],
+                public_inputs: mock_public_inputs(&pk.vk, &public_values, &report),
+                encoded_proof: "".to_string(),
+                raw_proof: "".to_string(),
+            },
+            stdin,
+            public_values,
+            circuit_version: SP1_CIRCUIT_VERSION.to_string(),
+            metadata: ProofMetadata::from_events(iter::empty(), start.elapsed()),
+        })
+    }
+
+    fn prove_groth16(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Groth16Bn254Proof> {
+        let start = Instant::now();
+        let (public_values, report) = self.execute(&pk.elf, &stdin)?;
+        Ok(SP1Groth16Bn254Proof {
+            proof: Groth16Bn254Proof {
+                public_inputs: mock_public_inputs(&pk.vk, &public_values, &report),
+                encoded_proof: "".to_string(),
+                raw_proof: "".to_string(),
+            },
+            stdin,
+            public_values,
+            circuit_version: SP1_CIRCUIT_VERSION.to_string(),
+            metadata: ProofMetadata::from_events(iter::empty(), start.elapsed()),
+        })
+    }
+
+    fn prove_groth16_bls12381(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1Groth16Bls12381Proof> {
+        let start = Instant::now();
+        let (public_values, report) = self.execute(&pk.elf, &stdin)?;
+        // The public inputs are smaller than both scalar fields, so they are the same as the
+        // public inputs of the proofs over BN254.
+        Ok(SP1Groth16Bls12381Proof {
+            proof: Groth16Bls12381Proof {
+                public_inputs: mock_public_inputs(&pk.vk, &public_values, &report),
                 encoded_proof: "".to_string(),
                 raw_proof: "".to_string(),
             },
             stdin,
             public_values,
+            circuit_version: SP1_CIRCUIT_VERSION.to_string(),
+            metadata: ProofMetadata::from_events(iter::empty(), start.elapsed()),
         })
     }
 
@@ -90,6 +169,37 @@ impl Prover for MockProver {
         verify_plonk_bn254_public_inputs(vkey, &proof.public_values, &proof.proof.public_inputs)?;
         Ok(())
     }
+
+    fn verify_groth16(&self, proof: &SP1Groth16Bn254Proof, vkey: &SP1VerifyingKey) -> Result<()> {
+        verify_plonk_bn254_public_inputs(vkey, &proof.public_values, &proof.proof.public_inputs)?;
+        Ok(())
+    }
+
+    fn verify_groth16_bls12381(
+        &self,
+        proof: &SP1Groth16Bls12381Proof,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<()> {
+        verify_plonk_bn254_public_inputs(vkey, &proof.public_values, &proof.proof.public_inputs)?;
+        Ok(())
+    }
+}
+
+/// The public inputs of a wrapped proof of an execution, which commit to the verifying key, the
+/// public values and the user values.
+fn mock_public_inputs(
+    vk: &SP1VerifyingKey,
+    public_values: &SP1PublicValues,
+    report: &ExecutionReport,
+) -> [String; NUM_PUBLIC_INPUTS] {
+    let user_values = report.user_values.iter().map(u32::to_string);
+    let mut public_inputs = [
+        vk.hash_bn254().as_canonical_biguint().to_string(),
+        public_values.hash().to_string(),
+    ]
+    .into_iter()
+    .chain(user_values);
+    array::from_fn(|_| public_inputs.next().unwrap())
 }
 
 impl Default for MockProver {
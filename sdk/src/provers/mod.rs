@@ -1,17 +1,24 @@
 mod local;
 mod mock;
 
-use crate::{SP1CompressedProof, SP1PlonkBn254Proof, SP1Proof};
+use crate::{
+    SP1CompressedProof, SP1Groth16Bls12381Proof, SP1Groth16Bn254Proof, SP1PlonkBn254Proof, SP1Proof,
+};
 use anyhow::Result;
-pub use local::LocalProver;
+use async_trait::async_trait;
+pub use local::{LocalProver, LocalProverOpts};
 pub use mock::MockProver;
+use sp1_core::runtime::ExecutionReport;
 use sp1_core::stark::MachineVerificationError;
+use sp1_core::utils::ProgressReporter;
 use sp1_prover::CoreSC;
 use sp1_prover::SP1CoreProofData;
 use sp1_prover::SP1Prover;
+use sp1_prover::SP1PublicValues;
 use sp1_prover::SP1ReduceProof;
 use sp1_prover::{SP1ProvingKey, SP1Stdin, SP1VerifyingKey};
 use strum_macros::EnumString;
+use tokio::task::block_in_place;
 
 /// The type of prover.
 #[derive(Debug, PartialEq, EnumString)]
@@ -19,14 +26,33 @@ pub enum ProverType {
     Local,
     Mock,
     Network,
+    /// A prover implemented outside of the SDK, such as a queue in front of a farm of provers,
+    /// which is given to [crate::ProverClient::with_prover].
+    #[strum(disabled)]
+    Custom,
 }
 
 /// An implementation of [crate::ProverClient].
+///
+/// The local, mock and network provers implement it, and so can other crates: a
+/// [crate::ProverClient] created with [crate::ProverClient::with_prover] runs every execution,
+/// setup, proof and verification of its programs through the given prover. Only the methods
+/// which generate proofs need to be implemented, since the others default to the local
+/// [SP1Prover] returned by [Prover::sp1_prover].
+#[async_trait]
 pub trait Prover: Send + Sync {
+    /// The type of the prover, which is [ProverType::Custom] for the provers implemented outside
+    /// of the SDK.
     fn id(&self) -> ProverType;
 
+    /// The local prover which executes programs and verifies proofs by default.
     fn sp1_prover(&self) -> &SP1Prover;
 
+    /// Execute a RISCV ELF with the given inputs, without generating a proof.
+    fn execute(&self, elf: &[u8], stdin: &SP1Stdin) -> Result<(SP1PublicValues, ExecutionReport)> {
+        Ok(SP1Prover::execute(elf, stdin)?)
+    }
+
     fn setup(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey);
 
     /// Prove the execution of a RISCV ELF with the given inputs.
@@ -38,6 +64,80 @@ pub trait Prover: Send + Sync {
     /// Given an SP1 program and input, generate a PLONK proof that can be verified on-chain.
     fn prove_plonk(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1PlonkBn254Proof>;
 
+    /// Given an SP1 program and input, generate a Groth16 proof that can be verified on-chain.
+    fn prove_groth16(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Groth16Bn254Proof>;
+
+    /// Given an SP1 program and input, generate a Groth16 proof over BLS12-381 that can be
+    /// verified on the chains which only have precompiles of BLS12-381.
+    fn prove_groth16_bls12381(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1Groth16Bls12381Proof>;
+
+    /// Prove the execution of a RISCV ELF with the given inputs, and report the stages of the
+    /// proof to `progress`.
+    ///
+    /// Provers which do not report progress ignore `progress` by default.
+    fn prove_with_progress(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+        _progress: &ProgressReporter,
+    ) -> Result<SP1Proof> {
+        self.prove(pk, stdin)
+    }
+
+    /// Generate a compressed proof, and report the stages of the proof to `progress`.
+    fn prove_compressed_with_progress(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+        _progress: &ProgressReporter,
+    ) -> Result<SP1CompressedProof> {
+        self.prove_compressed(pk, stdin)
+    }
+
+    /// Generate a PLONK proof, and report the stages of the proof to `progress`.
+    fn prove_plonk_with_progress(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+        _progress: &ProgressReporter,
+    ) -> Result<SP1PlonkBn254Proof> {
+        self.prove_plonk(pk, stdin)
+    }
+
+    /// Prove the execution of a RISCV ELF with the given inputs, without blocking the async
+    /// runtime.
+    ///
+    /// By default the proof is generated with [Prover::prove] on the current worker thread, whose
+    /// other tasks are moved to another thread first, so this must be called from a
+    /// multi-threaded tokio runtime.
+    async fn prove_async(&self, pk: &SP1ProvingKey, stdin: SP1Stdin) -> Result<SP1Proof> {
+        block_in_place(|| self.prove(pk, stdin))
+    }
+
+    /// Generate a compressed proof of the execution of a RISCV ELF with the given inputs, without
+    /// blocking the async runtime. See [Prover::prove_async].
+    async fn prove_compressed_async(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1CompressedProof> {
+        block_in_place(|| self.prove_compressed(pk, stdin))
+    }
+
+    /// Generate a PLONK proof of the execution of a RISCV ELF with the given inputs, without
+    /// blocking the async runtime. See [Prover::prove_async].
+    async fn prove_plonk_async(
+        &self,
+        pk: &SP1ProvingKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1PlonkBn254Proof> {
+        block_in_place(|| self.prove_plonk(pk, stdin))
+    }
+
     /// Verify that an SP1 proof is valid given its vkey and metadata.
     fn verify(
         &self,
@@ -65,7 +165,9 @@ pub trait Prover: Send + Sync {
     fn verify_plonk(&self, proof: &SP1PlonkBn254Proof, vkey: &SP1VerifyingKey) -> Result<()> {
         let sp1_prover = self.sp1_prover();
 
-        let plonk_bn254_aritfacts = if sp1_prover::build::sp1_dev_mode() {
+        let plonk_bn254_aritfacts = if let Some(srs) = sp1_prover::srs::PlonkSrs::from_env()? {
+            srs.artifacts_dir()
+        } else if sp1_prover::build::sp1_dev_mode() {
             sp1_prover::build::plonk_bn254_artifacts_dev_dir()
         } else {
             sp1_prover::build::try_install_plonk_bn254_artifacts()
@@ -79,4 +181,49 @@ pub trait Prover: Send + Sync {
 
         Ok(())
     }
+
+    /// Verify that a SP1 Groth16 proof is valid. Verify that the public inputs of the
+    /// Groth16Bn254 proof match the hash of the VK and the committed public values of the
+    /// SP1ProofWithPublicValues.
+    fn verify_groth16(&self, proof: &SP1Groth16Bn254Proof, vkey: &SP1VerifyingKey) -> Result<()> {
+        let sp1_prover = self.sp1_prover();
+
+        let groth16_bn254_artifacts = if sp1_prover::build::sp1_dev_mode() {
+            sp1_prover::build::groth16_bn254_artifacts_dev_dir()
+        } else {
+            sp1_prover::build::try_install_groth16_bn254_artifacts()
+        };
+        sp1_prover.verify_groth16_bn254(
+            &proof.proof,
+            vkey,
+            &proof.public_values,
+            &groth16_bn254_artifacts,
+        )?;
+
+        Ok(())
+    }
+
+    /// Verify that a SP1 Groth16 proof over BLS12-381 is valid, and that its public inputs match
+    /// the hash of the VK and the committed public values of the SP1ProofWithPublicValues.
+    fn verify_groth16_bls12381(
+        &self,
+        proof: &SP1Groth16Bls12381Proof,
+        vkey: &SP1VerifyingKey,
+    ) -> Result<()> {
+        let sp1_prover = self.sp1_prover();
+
+        let groth16_bls12381_artifacts = if sp1_prover::build::sp1_dev_mode() {
+            sp1_prover::build::groth16_bls12381_artifacts_dev_dir()
+        } else {
+            sp1_prover::build::groth16_bls12381_artifacts_dir()
+        };
+        sp1_prover.verify_groth16_bls12381(
+            &proof.proof,
+            vkey,
+            &proof.public_values,
+            &groth16_bls12381_artifacts,
+        )?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,242 @@
+//! A registry of the verifying keys of programs.
+//!
+//! The registry maps the name of each program to the hash of its verifying key and the hash of its
+//! ELF, in a JSON file which is meant to be committed next to the contracts and services which
+//! check the proofs of the programs. Once a [crate::ProverClient] has a registry, it only proves
+//! registered ELFs and only verifies proofs of registered verifying keys, so a rebuild which
+//! changes a program, or an upgrade of SP1 which changes its verifying key, fails loudly instead of
+//! producing proofs that the deployed verifiers reject.
+//!
+//! The hash of a verifying key is [vkey_hash]: the Poseidon2 digest of the key packed into a BN254
+//! scalar, as a `0x`-prefixed 32-byte hex string. It is the `programVKey` which the Solidity and
+//! Solana verifiers check proofs against, and it only depends on the preprocessed traces of the
+//! program and its start pc, so it is stable across machines and runs. The hash of an ELF is its
+//! SHA-256 hash, as a `0x`-prefixed hex string.
+
+use std::{
+    collections::BTreeMap,
+    env,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sp1_prover::{HashableKey, SP1VerifyingKey};
+use thiserror::Error;
+
+/// The environment variable which sets the path of the registry of [crate::ProverClient].
+pub const VKEY_REGISTRY_ENV: &str = "SP1_VKEY_REGISTRY";
+
+/// The version of the layout of the registry file.
+pub const VKEY_REGISTRY_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum VkeyRegistryError {
+    #[error("io error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("invalid registry file: {0}")]
+    InvalidFile(#[from] serde_json::Error),
+    #[error("unsupported registry version {found}, expected {expected}")]
+    UnsupportedVersion { found: u32, expected: u32 },
+    #[error("the ELF {elf_hash} is not registered: register the program again if it was rebuilt")]
+    UnregisteredElf { elf_hash: String },
+    #[error("the verifying key {vkey_hash} is not registered")]
+    UnregisteredVkey { vkey_hash: String },
+    #[error("program {name} is not registered")]
+    UnknownProgram { name: String },
+    #[error("the ELF of program {name} is {found}, but {registered} is registered")]
+    ElfMismatch {
+        name: String,
+        registered: String,
+        found: String,
+    },
+    #[error("the verifying key of program {name} is {found}, but {registered} is registered: the version of SP1 may have changed")]
+    VkeyMismatch {
+        name: String,
+        registered: String,
+        found: String,
+    },
+}
+
+/// The stable hash of a verifying key: see the [module documentation](self).
+pub fn vkey_hash(vk: &SP1VerifyingKey) -> String {
+    vk.bytes32()
+}
+
+/// The SHA-256 hash of an ELF, as a `0x`-prefixed hex string.
+pub fn elf_hash(elf: &[u8]) -> String {
+    format!("0x{}", hex::encode(Sha256::digest(elf)))
+}
+
+/// A registered program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisteredProgram {
+    /// The hash of the verifying key of the program, as computed by [vkey_hash].
+    pub vkey_hash: String,
+    /// The hash of the ELF of the program, as computed by [elf_hash].
+    pub elf_hash: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RegistryFile {
+    version: u32,
+    programs: BTreeMap<String, RegisteredProgram>,
+}
+
+/// A registry of verifying keys, persisted to a JSON file: see the [module documentation](self).
+#[derive(Debug, Clone, Default)]
+pub struct VkeyRegistry {
+    path: Option<PathBuf>,
+    programs: BTreeMap<String, RegisteredProgram>,
+}
+
+impl VkeyRegistry {
+    /// Creates an empty registry which is not persisted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens the registry at `path`, which is empty if the file does not exist yet. The registry
+    /// is written back to `path` by [Self::register].
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, VkeyRegistryError> {
+        let path = path.into();
+        let programs = match std::fs::read(&path) {
+            Ok(bytes) => {
+                let file: RegistryFile = serde_json::from_slice(&bytes)?;
+                if file.version != VKEY_REGISTRY_VERSION {
+                    return Err(VkeyRegistryError::UnsupportedVersion {
+                        found: file.version,
+                        expected: VKEY_REGISTRY_VERSION,
+                    });
+                }
+                file.programs
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path: Some(path),
+            programs,
+        })
+    }
+
+    /// Opens the registry at the path set by the `SP1_VKEY_REGISTRY` environment variable, if it
+    /// is set.
+    ///
+    /// Panics if the registry cannot be read, since proving without the registry the user asked
+    /// for would skip its checks.
+    pub fn from_env() -> Option<Self> {
+        env::var(VKEY_REGISTRY_ENV).ok().map(|path| {
+            Self::open(&path)
+                .unwrap_or_else(|e| panic!("failed to open the vkey registry {}: {}", path, e))
+        })
+    }
+
+    /// The path of the file of the registry, if it is persisted.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// The registered programs, by name.
+    pub fn programs(&self) -> &BTreeMap<String, RegisteredProgram> {
+        &self.programs
+    }
+
+    /// The registered program named `name`.
+    pub fn get(&self, name: &str) -> Option<&RegisteredProgram> {
+        self.programs.get(name)
+    }
+
+    /// Registers the program `name` with its ELF and verifying key, replacing the previous entry
+    /// of `name`, and saves the registry. Returns the previous entry.
+    pub fn register(
+        &mut self,
+        name: &str,
+        elf: &[u8],
+        vk: &SP1VerifyingKey,
+    ) -> Result<Option<RegisteredProgram>, VkeyRegistryError> {
+        let program = RegisteredProgram {
+            vkey_hash: vkey_hash(vk),
+            elf_hash: elf_hash(elf),
+        };
+        let previous = self.programs.insert(name.to_string(), program);
+        self.save()?;
+        Ok(previous)
+    }
+
+    /// Saves the registry to its file, if it is persisted.
+    pub fn save(&self) -> Result<(), VkeyRegistryError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let file = RegistryFile {
+            version: VKEY_REGISTRY_VERSION,
+            programs: self.programs.clone(),
+        };
+        let mut json = serde_json::to_vec_pretty(&file)?;
+        json.push(b'\n');
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Checks that `elf` and `vk` are the registered ELF and verifying key of the program `name`.
+    pub fn check(
+        &self,
+        name: &str,
+        elf: &[u8],
+        vk: &SP1VerifyingKey,
+    ) -> Result<(), VkeyRegistryError> {
+        let program = self
+            .get(name)
+            .ok_or_else(|| VkeyRegistryError::UnknownProgram {
+                name: name.to_string(),
+            })?;
+        let found = elf_hash(elf);
+        if program.elf_hash != found {
+            return Err(VkeyRegistryError::ElfMismatch {
+                name: name.to_string(),
+                registered: program.elf_hash.clone(),
+                found,
+            });
+        }
+        let found = vkey_hash(vk);
+        if program.vkey_hash != found {
+            return Err(VkeyRegistryError::VkeyMismatch {
+                name: name.to_string(),
+                registered: program.vkey_hash.clone(),
+                found,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that `elf` is registered, with the verifying key `vk`. This is the check of
+    /// [crate::ProverClient] before it proves.
+    pub fn check_elf(&self, elf: &[u8], vk: &SP1VerifyingKey) -> Result<(), VkeyRegistryError> {
+        let elf_hash = elf_hash(elf);
+        let (name, _) = self
+            .programs
+            .iter()
+            .find(|(_, program)| program.elf_hash == elf_hash)
+            .ok_or(VkeyRegistryError::UnregisteredElf { elf_hash })?;
+        self.check(name, elf, vk)
+    }
+
+    /// Checks that `vk` is registered. This is the check of [crate::ProverClient] before it
+    /// verifies.
+    pub fn check_vkey(&self, vk: &SP1VerifyingKey) -> Result<(), VkeyRegistryError> {
+        let vkey_hash = vkey_hash(vk);
+        if self
+            .programs
+            .values()
+            .any(|program| program.vkey_hash == vkey_hash)
+        {
+            Ok(())
+        } else {
+            Err(VkeyRegistryError::UnregisteredVkey { vkey_hash })
+        }
+    }
+}
@@ -0,0 +1,9 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+pub fn main() {
+    let start = sp1_zkvm::cycle_count();
+    let end = sp1_zkvm::cycle_count();
+    assert!(end > start);
+    sp1_zkvm::io::commit(&(end - start));
+}
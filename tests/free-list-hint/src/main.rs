@@ -0,0 +1,17 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use std::hint::black_box;
+
+pub fn main() {
+    // Free a written buffer of the size of each hint, so that the allocator has a used block to
+    // give to the hint.
+    drop(black_box(vec![0xffu8; 1021]));
+    let a = sp1_zkvm::io::read_vec();
+    sp1_zkvm::io::commit_slice(&a);
+
+    // The encoding of a vec of 1021 bytes is 1029 bytes long.
+    drop(black_box(vec![0xffu8; 1029]));
+    let b = sp1_zkvm::io::read::<Vec<u8>>();
+    sp1_zkvm::io::commit_slice(&b);
+}
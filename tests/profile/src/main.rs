@@ -0,0 +1,23 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use std::hint::black_box;
+
+/// Always inlined, so only the debug info of the ELF knows that its code belongs to it.
+#[inline(always)]
+fn inlined_square(x: u32) -> u32 {
+    let mut result = 0u32;
+    for _ in 0..black_box(x) {
+        result = black_box(result.wrapping_add(x));
+    }
+    result
+}
+
+#[inline(never)]
+fn sum_of_squares(n: u32) -> u32 {
+    (0..n).fold(0u32, |acc, i| acc.wrapping_add(inlined_square(i)))
+}
+
+pub fn main() {
+    black_box(sum_of_squares(black_box(100)));
+}
@@ -0,0 +1,100 @@
+//! Arithmetic on BN254 with the alt_bn128 syscalls of the Solana runtime.
+//!
+//! Points are uncompressed and big-endian, as the syscalls read them: a point of G1 is `x || y`,
+//! and a point of G2 is `x.c1 || x.c0 || y.c1 || y.c0`, as in EIP-197. Off-chain, the syscalls
+//! fall back to an implementation in Rust, so the verifier can be tested like any other crate.
+
+use solana_program::alt_bn128::prelude::{
+    alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing,
+};
+
+use crate::Groth16Error;
+
+/// The length of an uncompressed point of G1.
+pub const G1_LEN: usize = 64;
+
+/// The length of an uncompressed point of G2.
+pub const G2_LEN: usize = 128;
+
+/// The length of a scalar.
+pub const SCALAR_LEN: usize = 32;
+
+pub type G1 = [u8; G1_LEN];
+pub type G2 = [u8; G2_LEN];
+pub type Scalar = [u8; SCALAR_LEN];
+
+/// The order of the groups, which is the modulus of the scalar field, big-endian.
+pub const SCALAR_MODULUS: Scalar = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// Whether `x` is a canonical scalar, smaller than the modulus.
+pub fn is_scalar(x: &Scalar) -> bool {
+    x < &SCALAR_MODULUS
+}
+
+/// Reduces a 256-bit big-endian integer modulo the order of the groups.
+pub fn reduce_scalar(mut x: Scalar) -> Scalar {
+    // The modulus is larger than 2^253, so this subtracts it at most five times.
+    while !is_scalar(&x) {
+        let mut borrow = 0u16;
+        for i in (0..SCALAR_LEN).rev() {
+            let diff = 0x100 + x[i] as u16 - SCALAR_MODULUS[i] as u16 - borrow;
+            x[i] = diff as u8;
+            borrow = 1 - (diff >> 8);
+        }
+    }
+    x
+}
+
+/// Adds two points of G1.
+pub fn g1_add(p: &G1, q: &G1) -> Result<G1, Groth16Error> {
+    let mut input = [0u8; 2 * G1_LEN];
+    input[..G1_LEN].copy_from_slice(p);
+    input[G1_LEN..].copy_from_slice(q);
+    let output = alt_bn128_addition(&input).map_err(|_| Groth16Error::InvalidPoint)?;
+    output.try_into().map_err(|_| Groth16Error::InvalidPoint)
+}
+
+/// Multiplies a point of G1 by a scalar.
+pub fn g1_mul(p: &G1, s: &Scalar) -> Result<G1, Groth16Error> {
+    let mut input = [0u8; G1_LEN + SCALAR_LEN];
+    input[..G1_LEN].copy_from_slice(p);
+    input[G1_LEN..].copy_from_slice(s);
+    let output = alt_bn128_multiplication(&input).map_err(|_| Groth16Error::InvalidPoint)?;
+    output.try_into().map_err(|_| Groth16Error::InvalidPoint)
+}
+
+/// Checks that the product of the pairings of the pairs is one, with a single syscall.
+pub fn pairing_check(pairs: &[(G1, G2)]) -> Result<bool, Groth16Error> {
+    let mut input = Vec::with_capacity(pairs.len() * (G1_LEN + G2_LEN));
+    for (p, q) in pairs {
+        input.extend_from_slice(p);
+        input.extend_from_slice(q);
+    }
+    let output = alt_bn128_pairing(&input).map_err(|_| Groth16Error::InvalidPoint)?;
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    Ok(output == one)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reduce_scalar() {
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let mut modulus_plus_one = SCALAR_MODULUS;
+        modulus_plus_one[31] += 1;
+        assert_eq!(reduce_scalar(modulus_plus_one), one);
+        assert_eq!(reduce_scalar(SCALAR_MODULUS), [0u8; 32]);
+        assert_eq!(reduce_scalar(one), one);
+
+        let reduced = reduce_scalar([0xff; 32]);
+        assert!(is_scalar(&reduced));
+        assert!(!is_scalar(&SCALAR_MODULUS));
+    }
+}
@@ -0,0 +1,214 @@
+//! Verification of gnark Groth16 proofs over BN254.
+
+use solana_program::hash::hashv;
+use thiserror::Error;
+
+use crate::bn254::{
+    g1_add, g1_mul, is_scalar, pairing_check, reduce_scalar, Scalar, G1, G1_LEN, G2, G2_LEN,
+};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Groth16Error {
+    #[error("invalid verifying key encoding")]
+    InvalidVerifyingKey,
+    #[error("invalid proof encoding")]
+    InvalidProof,
+    #[error("invalid point")]
+    InvalidPoint,
+    #[error("expected {expected} public inputs, found {found}")]
+    InvalidPublicInputsLength { expected: usize, found: usize },
+    #[error("public input {0} is not a canonical scalar")]
+    InvalidPublicInput(usize),
+    #[error("the proof has {found} commitments, expected {expected}")]
+    InvalidCommitmentsLength { expected: usize, found: usize },
+    #[error("the pairing check failed")]
+    VerificationFailed,
+}
+
+/// The verifying key of a Pedersen commitment of the proofs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PedersenVerifyingKey {
+    pub g: G2,
+    pub g_root_sigma_neg: G2,
+}
+
+/// A Groth16 verifying key, in the layout written by `ExportSolanaVerifyingKey` in the gnark FFI,
+/// with the points of G2 the pairing check needs negated already negated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Groth16VerifyingKey {
+    pub alpha_g1: G1,
+    pub beta_g2_neg: G2,
+    pub gamma_g2_neg: G2,
+    pub delta_g2_neg: G2,
+    pub k: Vec<G1>,
+    pub commitment_key: Option<PedersenVerifyingKey>,
+}
+
+/// A Groth16 proof, in the layout of `MarshalSolidity` in gnark.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Groth16Proof {
+    pub a: G1,
+    pub b: G2,
+    pub c: G1,
+    /// The commitment of the proof and its proof of knowledge.
+    pub commitment: Option<(G1, G1)>,
+}
+
+/// A reader of the fields of an encoded key or proof.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        if self.bytes.len() < N {
+            return None;
+        }
+        let (head, tail) = self.bytes.split_at(N);
+        self.bytes = tail;
+        head.try_into().ok()
+    }
+
+    fn take_u32(&mut self) -> Option<u32> {
+        self.take::<4>().map(u32::from_be_bytes)
+    }
+}
+
+impl Groth16VerifyingKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        Self::read(&mut Reader { bytes }).ok_or(Groth16Error::InvalidVerifyingKey)
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        let alpha_g1 = reader.take::<G1_LEN>()?;
+        let beta_g2_neg = reader.take::<G2_LEN>()?;
+        let gamma_g2_neg = reader.take::<G2_LEN>()?;
+        let delta_g2_neg = reader.take::<G2_LEN>()?;
+        let nb_k = reader.take_u32()?;
+        let k = (0..nb_k)
+            .map(|_| reader.take::<G1_LEN>())
+            .collect::<Option<Vec<_>>>()?;
+        let commitment_key = match reader.take_u32()? {
+            0 => None,
+            1 => Some(PedersenVerifyingKey {
+                g: reader.take::<G2_LEN>()?,
+                g_root_sigma_neg: reader.take::<G2_LEN>()?,
+            }),
+            _ => return None,
+        };
+        let nb_commitments = commitment_key.is_some() as usize;
+        if !reader.bytes.is_empty() || k.len() < 1 + nb_commitments {
+            return None;
+        }
+        Some(Self {
+            alpha_g1,
+            beta_g2_neg,
+            gamma_g2_neg,
+            delta_g2_neg,
+            k,
+            commitment_key,
+        })
+    }
+
+    /// The number of public inputs of the circuit, without the hash of the commitment.
+    pub fn nb_public_inputs(&self) -> usize {
+        self.k.len() - 1 - self.commitment_key.is_some() as usize
+    }
+}
+
+impl Groth16Proof {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Groth16Error> {
+        Self::read(&mut Reader { bytes }).ok_or(Groth16Error::InvalidProof)
+    }
+
+    fn read(reader: &mut Reader) -> Option<Self> {
+        let a = reader.take::<G1_LEN>()?;
+        let b = reader.take::<G2_LEN>()?;
+        let c = reader.take::<G1_LEN>()?;
+        // The proofs without commitments end here, and the others follow with the commitments, as
+        // a slice prefixed by its length, and the proof of knowledge.
+        let commitment = if reader.bytes.is_empty() {
+            None
+        } else {
+            if reader.take_u32()? != 1 {
+                return None;
+            }
+            Some((reader.take::<G1_LEN>()?, reader.take::<G1_LEN>()?))
+        };
+        if !reader.bytes.is_empty() {
+            return None;
+        }
+        Some(Self {
+            a,
+            b,
+            c,
+            commitment,
+        })
+    }
+}
+
+/// Verifies a Groth16 proof of the circuit of `vk` with the given public inputs, as big-endian
+/// scalars.
+///
+/// The check of the proof and the check of the proof of knowledge of its commitment are folded
+/// into a single pairing check with a random challenge, which saves the base cost of a second
+/// pairing syscall for the cost of two scalar multiplications.
+pub fn verify_groth16(
+    vk: &Groth16VerifyingKey,
+    proof: &Groth16Proof,
+    public_inputs: &[Scalar],
+) -> Result<(), Groth16Error> {
+    if public_inputs.len() != vk.nb_public_inputs() {
+        return Err(Groth16Error::InvalidPublicInputsLength {
+            expected: vk.nb_public_inputs(),
+            found: public_inputs.len(),
+        });
+    }
+    if let Some(i) = public_inputs.iter().position(|x| !is_scalar(x)) {
+        return Err(Groth16Error::InvalidPublicInput(i));
+    }
+    if vk.commitment_key.is_some() != proof.commitment.is_some() {
+        return Err(Groth16Error::InvalidCommitmentsLength {
+            expected: vk.commitment_key.is_some() as usize,
+            found: proof.commitment.is_some() as usize,
+        });
+    }
+
+    // Compute the linear combination of the public inputs and the commitment, whose hash is the
+    // last public input.
+    let mut l = vk.k[0];
+    for (input, k) in public_inputs.iter().zip(vk.k[1..].iter()) {
+        l = g1_add(&l, &g1_mul(k, input)?)?;
+    }
+    if let Some((commitment, _)) = &proof.commitment {
+        let hash = reduce_scalar(hashv(&[&commitment[..]]).to_bytes());
+        l = g1_add(&l, &g1_mul(&vk.k[vk.k.len() - 1], &hash)?)?;
+        l = g1_add(&l, commitment)?;
+    }
+
+    let mut pairs = vec![
+        (proof.a, proof.b),
+        (proof.c, vk.delta_g2_neg),
+        (vk.alpha_g1, vk.beta_g2_neg),
+        (l, vk.gamma_g2_neg),
+    ];
+    if let (Some(key), Some((commitment, pok))) = (&vk.commitment_key, &proof.commitment) {
+        let mut transcript = Vec::with_capacity(5 * G1_LEN + G2_LEN);
+        let points: [&[u8]; 5] = [&proof.a, &proof.b, &proof.c, commitment, pok];
+        for point in points {
+            transcript.extend_from_slice(point);
+        }
+        for input in public_inputs {
+            transcript.extend_from_slice(input);
+        }
+        let challenge = reduce_scalar(hashv(&[&transcript[..]]).to_bytes());
+        pairs.push((g1_mul(commitment, &challenge)?, key.g_root_sigma_neg));
+        pairs.push((g1_mul(pok, &challenge)?, key.g));
+    }
+
+    if pairing_check(&pairs)? {
+        Ok(())
+    } else {
+        Err(Groth16Error::VerificationFailed)
+    }
+}
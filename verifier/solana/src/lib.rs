@@ -0,0 +1,290 @@
+//! A verifier of the Groth16 proofs of SP1 for Solana programs.
+//!
+//! The verifier checks the proofs with the alt_bn128 syscalls of the Solana runtime, which are
+//! orders of magnitude cheaper than pairings computed in the program, and folds all the pairings
+//! of a proof into a single pairing syscall. A proof costs about 120k compute units to verify.
+//!
+//! The verifying key of the Groth16 circuit is written to `groth16_vk_solana.bin` in the directory
+//! of the Groth16 artifacts, and proofs are encoded for this crate by
+//! `SP1Groth16Bn254Proof::to_solana_bytes` in the SDK.
+//!
+//! ```ignore
+//! use sp1_solana_verifier::{verify_solana_proof, Groth16VerifyingKey};
+//!
+//! const GROTH16_VK: &[u8] = include_bytes!("groth16_vk_solana.bin");
+//! // The bytes of `vk.bytes32()` of the program.
+//! const PROGRAM_VKEY_HASH: [u8; 32] = [0; 32];
+//!
+//! fn process_instruction(data: &[u8]) -> ProgramResult {
+//!     let vk = Groth16VerifyingKey::from_bytes(GROTH16_VK).unwrap();
+//!     let public_values = verify_solana_proof(data, &PROGRAM_VKEY_HASH, &vk)
+//!         .map_err(|_| ProgramError::InvalidInstructionData)?;
+//!     // Use the public values.
+//!     Ok(())
+//! }
+//! ```
+
+pub mod bn254;
+mod groth16;
+
+pub use groth16::*;
+
+use bn254::Scalar;
+use solana_program::hash::hashv;
+
+/// The name of the file of the verifying key in the directory of the Groth16 artifacts.
+pub const GROTH16_VK_FILE: &str = "groth16_vk_solana.bin";
+
+/// The number of user public values, the public inputs of the Groth16 circuit after the vkey hash
+/// and the committed values digest.
+pub const NUM_USER_VALUES: usize = 8;
+
+/// Hashes the public values of a program into the committed values digest, the second public
+/// input of the Groth16 circuit: their SHA-256 hash, with the top three bits cleared so that the
+/// hash is a scalar.
+pub fn hash_public_values(public_values: &[u8]) -> Scalar {
+    let mut hash = hashv(&[public_values]).to_bytes();
+    hash[0] &= 0b00011111;
+    hash
+}
+
+/// Verifies an encoded Groth16 proof of the program whose verifying key hashes to `vkey_hash`,
+/// with the given public values and with user public values which are all zero.
+pub fn verify_proof(
+    proof: &[u8],
+    public_values: &[u8],
+    vkey_hash: &Scalar,
+    vk: &Groth16VerifyingKey,
+) -> Result<(), Groth16Error> {
+    verify_proof_with_user_values(proof, public_values, &[0; NUM_USER_VALUES], vkey_hash, vk)
+}
+
+/// Verifies an encoded Groth16 proof of the program whose verifying key hashes to `vkey_hash`,
+/// with the given public values and user public values.
+pub fn verify_proof_with_user_values(
+    proof: &[u8],
+    public_values: &[u8],
+    user_values: &[u32; NUM_USER_VALUES],
+    vkey_hash: &Scalar,
+    vk: &Groth16VerifyingKey,
+) -> Result<(), Groth16Error> {
+    let proof = Groth16Proof::from_bytes(proof)?;
+    let mut public_inputs = [[0; 32]; 2 + NUM_USER_VALUES];
+    public_inputs[0] = *vkey_hash;
+    public_inputs[1] = hash_public_values(public_values);
+    for (input, value) in public_inputs[2..].iter_mut().zip(user_values) {
+        input[28..].copy_from_slice(&value.to_be_bytes());
+    }
+    verify_groth16(vk, &proof, &public_inputs)
+}
+
+/// Verifies a proof encoded by `SP1Groth16Bn254Proof::to_solana_bytes`, the length of the encoded
+/// Groth16 proof as a little-endian `u32`, the proof and the public values, and returns the
+/// public values.
+pub fn verify_solana_proof<'a>(
+    bytes: &'a [u8],
+    vkey_hash: &Scalar,
+    vk: &Groth16VerifyingKey,
+) -> Result<&'a [u8], Groth16Error> {
+    if bytes.len() < 4 {
+        return Err(Groth16Error::InvalidProof);
+    }
+    let (len, rest) = bytes.split_at(4);
+    let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+    if rest.len() < len {
+        return Err(Groth16Error::InvalidProof);
+    }
+    let (proof, public_values) = rest.split_at(len);
+    verify_proof(proof, public_values, vkey_hash, vk)?;
+    Ok(public_values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bn254::{g1_add, g1_mul, G1, G2};
+    use super::*;
+
+    fn from_hex<const N: usize>(hex: &str) -> [u8; N] {
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect::<Vec<_>>();
+        bytes.try_into().unwrap()
+    }
+
+    fn scalar(x: u64) -> Scalar {
+        let mut s = [0u8; 32];
+        s[24..].copy_from_slice(&x.to_be_bytes());
+        s
+    }
+
+    fn g1() -> G1 {
+        let mut p = [0u8; 64];
+        p[31] = 1;
+        p[63] = 2;
+        p
+    }
+
+    fn g2(negate: bool) -> G2 {
+        let y = if negate {
+            "275dc4a288d1afb3cbb1ac09187524c7db36395df7be3b99e673b13a075a65ec\
+             1d9befcd05a5323e6da4d435f3b617cdb3af83285c2df711ef39c01571827f9d"
+        } else {
+            "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+             12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"
+        };
+        from_hex(&format!(
+            "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+             1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed{}",
+            y
+        ))
+    }
+
+    /// A key and a proof which pass the pairing check, with every point of G2 the generator or
+    /// its negation, so that the check is `a = c + alpha + l` in G1.
+    fn fixture(public_inputs: &[Scalar]) -> (Groth16VerifyingKey, Groth16Proof) {
+        let k = (0..public_inputs.len() as u64 + 2)
+            .map(|i| g1_mul(&g1(), &scalar(5 + 2 * i)).unwrap())
+            .collect();
+        let vk = Groth16VerifyingKey {
+            alpha_g1: g1(),
+            beta_g2_neg: g2(true),
+            gamma_g2_neg: g2(true),
+            delta_g2_neg: g2(true),
+            k,
+            commitment_key: Some(PedersenVerifyingKey {
+                g: g2(false),
+                g_root_sigma_neg: g2(true),
+            }),
+        };
+
+        let commitment = g1_mul(&g1(), &scalar(3)).unwrap();
+        let hash = bn254::reduce_scalar(hashv(&[&commitment[..]]).to_bytes());
+        let mut l = vk.k[0];
+        for (input, k) in public_inputs.iter().chain([&hash]).zip(vk.k[1..].iter()) {
+            l = g1_add(&l, &g1_mul(k, input).unwrap()).unwrap();
+        }
+        l = g1_add(&l, &commitment).unwrap();
+        let c = g1_mul(&g1(), &scalar(17)).unwrap();
+        let a = g1_add(&g1_add(&c, &vk.alpha_g1).unwrap(), &l).unwrap();
+        let proof = Groth16Proof {
+            a,
+            b: g2(false),
+            c,
+            commitment: Some((commitment, commitment)),
+        };
+        (vk, proof)
+    }
+
+    #[test]
+    fn test_verify_groth16() {
+        let public_inputs = [scalar(42), hash_public_values(b"public values")];
+        let (vk, proof) = fixture(&public_inputs);
+        verify_groth16(&vk, &proof, &public_inputs).unwrap();
+
+        let wrong_inputs = [scalar(43), public_inputs[1]];
+        assert_eq!(
+            verify_groth16(&vk, &proof, &wrong_inputs),
+            Err(Groth16Error::VerificationFailed)
+        );
+
+        let mut wrong_pok = proof.clone();
+        wrong_pok.commitment = Some((
+            proof.commitment.unwrap().0,
+            g1_mul(&g1(), &scalar(4)).unwrap(),
+        ));
+        assert_eq!(
+            verify_groth16(&vk, &wrong_pok, &public_inputs),
+            Err(Groth16Error::VerificationFailed)
+        );
+
+        assert!(matches!(
+            verify_groth16(&vk, &proof, &public_inputs[..1]),
+            Err(Groth16Error::InvalidPublicInputsLength { .. })
+        ));
+        assert_eq!(
+            verify_groth16(&vk, &proof, &[bn254::SCALAR_MODULUS, public_inputs[1]]),
+            Err(Groth16Error::InvalidPublicInput(0))
+        );
+    }
+
+    #[test]
+    fn test_verify_solana_proof() {
+        let vkey_hash = scalar(42);
+        let public_values = b"public values";
+        let mut public_inputs = [scalar(0); 2 + NUM_USER_VALUES];
+        public_inputs[0] = vkey_hash;
+        public_inputs[1] = hash_public_values(public_values);
+        let (vk, proof) = fixture(&public_inputs);
+        let (commitment, pok) = proof.commitment.unwrap();
+
+        let mut encoded = proof.a.to_vec();
+        encoded.extend_from_slice(&proof.b);
+        encoded.extend_from_slice(&proof.c);
+        encoded.extend_from_slice(&1u32.to_be_bytes());
+        encoded.extend_from_slice(&commitment);
+        encoded.extend_from_slice(&pok);
+        assert_eq!(Groth16Proof::from_bytes(&encoded).unwrap(), proof);
+
+        let mut bytes = (encoded.len() as u32).to_le_bytes().to_vec();
+        bytes.extend_from_slice(&encoded);
+        bytes.extend_from_slice(public_values);
+        assert_eq!(
+            verify_solana_proof(&bytes, &vkey_hash, &vk).unwrap(),
+            public_values
+        );
+
+        bytes.push(0);
+        assert_eq!(
+            verify_solana_proof(&bytes, &vkey_hash, &vk),
+            Err(Groth16Error::VerificationFailed)
+        );
+        assert_eq!(
+            verify_solana_proof(&bytes[..100], &vkey_hash, &vk),
+            Err(Groth16Error::InvalidProof)
+        );
+        let mut user_values = [0; NUM_USER_VALUES];
+        user_values[3] = 0xdead_beef;
+        public_inputs[5] = scalar(0xdead_beef);
+        let (vk, proof) = fixture(&public_inputs);
+        let mut encoded = proof.a.to_vec();
+        encoded.extend_from_slice(&proof.b);
+        encoded.extend_from_slice(&proof.c);
+        encoded.extend_from_slice(&1u32.to_be_bytes());
+        encoded.extend_from_slice(&commitment);
+        encoded.extend_from_slice(&pok);
+        assert_eq!(
+            verify_proof_with_user_values(&encoded, public_values, &user_values, &vkey_hash, &vk),
+            Ok(())
+        );
+        assert_eq!(
+            verify_proof(&encoded, public_values, &vkey_hash, &vk),
+            Err(Groth16Error::VerificationFailed)
+        );
+    }
+
+    #[test]
+    fn test_verifying_key_encoding() {
+        let (vk, _) = fixture(&[scalar(1), scalar(2)]);
+        let key = vk.commitment_key.as_ref().unwrap();
+        let mut bytes = vk.alpha_g1.to_vec();
+        bytes.extend_from_slice(&vk.beta_g2_neg);
+        bytes.extend_from_slice(&vk.gamma_g2_neg);
+        bytes.extend_from_slice(&vk.delta_g2_neg);
+        bytes.extend_from_slice(&(vk.k.len() as u32).to_be_bytes());
+        for k in vk.k.iter() {
+            bytes.extend_from_slice(k);
+        }
+        bytes.extend_from_slice(&1u32.to_be_bytes());
+        bytes.extend_from_slice(&key.g);
+        bytes.extend_from_slice(&key.g_root_sigma_neg);
+
+        let decoded = Groth16VerifyingKey::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, vk);
+        assert_eq!(decoded.nb_public_inputs(), 2);
+        assert_eq!(
+            Groth16VerifyingKey::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(Groth16Error::InvalidVerifyingKey)
+        );
+    }
+}
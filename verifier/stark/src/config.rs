@@ -0,0 +1,184 @@
+use alloc::vec::Vec;
+use p3_challenger::{CanObserve, CanSample, FieldChallenger};
+use p3_commit::{Pcs, PolynomialSpace};
+
+use p3_field::{AbstractField, ExtensionField, Field, PrimeField};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+pub type Domain<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+    <SC as StarkGenericConfig>::Challenge,
+    <SC as StarkGenericConfig>::Challenger,
+>>::Domain;
+
+pub type Val<SC> = <<<SC as StarkGenericConfig>::Pcs as Pcs<
+    <SC as StarkGenericConfig>::Challenge,
+    <SC as StarkGenericConfig>::Challenger,
+>>::Domain as PolynomialSpace>::Val;
+
+pub type Dom<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+    <SC as StarkGenericConfig>::Challenge,
+    <SC as StarkGenericConfig>::Challenger,
+>>::Domain;
+
+pub type PackedVal<SC> = <Val<SC> as Field>::Packing;
+
+pub type PackedChallenge<SC> =
+    <<SC as StarkGenericConfig>::Challenge as ExtensionField<Val<SC>>>::ExtensionPacking;
+
+pub type Com<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+    <SC as StarkGenericConfig>::Challenge,
+    <SC as StarkGenericConfig>::Challenger,
+>>::Commitment;
+
+pub type OpeningProof<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+    <SC as StarkGenericConfig>::Challenge,
+    <SC as StarkGenericConfig>::Challenger,
+>>::Proof;
+
+pub type OpeningError<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+    <SC as StarkGenericConfig>::Challenge,
+    <SC as StarkGenericConfig>::Challenger,
+>>::Error;
+
+pub type PcsProverData<SC> = <<SC as StarkGenericConfig>::Pcs as Pcs<
+    <SC as StarkGenericConfig>::Challenge,
+    <SC as StarkGenericConfig>::Challenger,
+>>::ProverData;
+
+pub type Challenge<SC> = <SC as StarkGenericConfig>::Challenge;
+pub type Challenger<SC> = <SC as StarkGenericConfig>::Challenger;
+
+/// The hash of the Merkle trees which commit to the traces and the FRI layers of a STARK.
+///
+/// Poseidon2 is the cheapest to verify in the recursion circuits, while Blake3 and Keccak are
+/// faster to prove natively and cheaper to verify outside of a circuit. The hash is recorded in
+/// the verifying keys, so that a key is only accepted by a verifier of the same hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MerkleHash {
+    Poseidon2,
+    Blake3,
+    Keccak,
+}
+
+/// The layout of the logarithmic-derivative (LogUp) argument which balances the interactions of
+/// the chips, such as the byte and range lookups.
+///
+/// Each column of a permutation trace holds the sum of the fractions `m_i / (alpha + rlc_i)` of a
+/// batch of interactions, and a running sum column accumulates the sums of the batches over the
+/// rows. The layout is recorded in the verifying keys, so that a key is only accepted by a machine
+/// with the same layout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LookupArgument {
+    /// One column per batch of interactions and a separate running sum column.
+    #[default]
+    Batched,
+    /// The running sum column holds the sum of the rows before each row, and the sum of the last
+    /// batch of interactions is derived from two consecutive rows of it, which saves a column of
+    /// the permutation trace of every chip.
+    Compact,
+}
+
+/// The length in bytes of the tag of a [DomainSeparator].
+pub const DOMAIN_SEPARATOR_LEN: usize = 32;
+
+/// A tag of the proofs of a machine, which the challenger absorbs right after the preprocessed
+/// commitment and the start pc of the verifying key.
+///
+/// The tag is `<version>/<purpose>`, such as the version of SP1 and the machine which proves, and
+/// the key which precedes it binds the proofs to their program. The tag is recorded in the verifying
+/// keys, so the transcript of a proof of a machine with another tag, or without a tag, differs from
+/// the one of the key, and the proof is rejected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DomainSeparator(Option<[u8; DOMAIN_SEPARATOR_LEN]>);
+
+impl DomainSeparator {
+    /// No tag, which keeps the transcripts of the machines without one.
+    pub const NONE: Self = Self(None);
+
+    /// The tag `<version>/<purpose>`, padded with zeros.
+    ///
+    /// Panics if the tag is longer than [DOMAIN_SEPARATOR_LEN] bytes.
+    pub fn new(version: &str, purpose: &str) -> Self {
+        let len = version.len() + 1 + purpose.len();
+        assert!(
+            len <= DOMAIN_SEPARATOR_LEN,
+            "the domain separator {}/{} is longer than {} bytes",
+            version,
+            purpose,
+            DOMAIN_SEPARATOR_LEN
+        );
+        let mut tag = [0u8; DOMAIN_SEPARATOR_LEN];
+        tag[..version.len()].copy_from_slice(version.as_bytes());
+        tag[version.len()] = b'/';
+        tag[version.len() + 1..len].copy_from_slice(purpose.as_bytes());
+        Self(Some(tag))
+    }
+
+    /// The bytes of the tag, without the padding, or `None` if there is no tag.
+    pub fn tag(&self) -> Option<&[u8]> {
+        self.0.as_ref().map(|tag| {
+            let len = tag.iter().rposition(|&byte| byte != 0).map_or(0, |i| i + 1);
+            &tag[..len]
+        })
+    }
+
+    /// The field elements absorbed by the challenger, one per byte of the padded tag.
+    pub fn elements<F: AbstractField>(&self) -> Vec<F> {
+        self.0
+            .iter()
+            .flatten()
+            .map(|&byte| F::from_canonical_u8(byte))
+            .collect()
+    }
+}
+
+/// The parameters of FRI, which determine the conjectured security of a STARK.
+///
+/// The parameters are recorded in the verifying keys, so that a key is only accepted by a verifier
+/// with the same parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FriParameters {
+    /// The logarithm of the blowup factor of the low-degree extensions.
+    pub log_blowup: usize,
+    /// The number of queries of FRI.
+    pub num_queries: usize,
+    /// The number of bits of the proof-of-work of the queries.
+    pub proof_of_work_bits: usize,
+}
+
+impl FriParameters {
+    /// The conjectured security of the parameters in bits, `log_blowup * num_queries +
+    /// proof_of_work_bits`.
+    pub const fn conjectured_security_bits(&self) -> usize {
+        self.log_blowup * self.num_queries + self.proof_of_work_bits
+    }
+}
+
+pub trait StarkGenericConfig: Send + Sync + Serialize + DeserializeOwned + Clone {
+    type Val: PrimeField;
+
+    type Domain: PolynomialSpace<Val = Self::Val> + Sync;
+
+    /// The PCS used to commit to trace polynomials.
+    type Pcs: Pcs<Self::Challenge, Self::Challenger, Domain = Self::Domain> + Sync;
+
+    /// The field from which most random challenges are drawn.
+    type Challenge: ExtensionField<Self::Val>;
+
+    /// The challenger (Fiat-Shamir) implementation used.
+    type Challenger: FieldChallenger<Val<Self>>
+        + CanObserve<<Self::Pcs as Pcs<Self::Challenge, Self::Challenger>>::Commitment>
+        + CanSample<Self::Challenge>;
+
+    /// Get the PCS used by this configuration.
+    fn pcs(&self) -> &Self::Pcs;
+
+    /// Initialize a new challenger.
+    fn challenger(&self) -> Self::Challenger;
+
+    /// The hash of the Merkle trees of the PCS.
+    fn merkle_hash(&self) -> MerkleHash;
+
+    /// The parameters of FRI of the PCS.
+    fn fri_parameters(&self) -> FriParameters;
+}
@@ -0,0 +1,23 @@
+//! The verifier of SP1 STARK proofs, without the standard library.
+//!
+//! This crate only depends on `alloc` and the Plonky3 crates of the verifier, so that SP1 proofs
+//! can be verified on embedded devices or in the guest of another zkVM. It has the configuration
+//! trait of the STARKs, the proofs and verifying keys, and the verification of shard and machine
+//! proofs. The chips of a machine are given by the caller as [VerifierChip]s, which `sp1-core`
+//! implements for its chips.
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod config;
+mod folder;
+mod types;
+mod verifier;
+
+pub use config::*;
+pub use folder::*;
+pub use types::*;
+pub use verifier::*;
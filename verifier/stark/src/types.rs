@@ -0,0 +1,134 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+use p3_challenger::CanObserve;
+use p3_matrix::dense::RowMajorMatrixView;
+use p3_matrix::stack::VerticalPair;
+use p3_matrix::Dimensions;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use super::{
+    Challenge, Com, Dom, DomainSeparator, FriParameters, LookupArgument, MerkleHash, OpeningProof,
+    StarkGenericConfig, Val,
+};
+
+pub type QuotientOpenedValues<T> = Vec<T>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardCommitment<C> {
+    pub main_commit: C,
+    pub permutation_commit: C,
+    pub quotient_commit: C,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AirOpenedValues<T> {
+    pub local: Vec<T>,
+    pub next: Vec<T>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChipOpenedValues<T: Serialize> {
+    pub preprocessed: AirOpenedValues<T>,
+    pub main: AirOpenedValues<T>,
+    pub permutation: AirOpenedValues<T>,
+    pub quotient: Vec<Vec<T>>,
+    pub cumulative_sum: T,
+    pub log_degree: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardOpenedValues<T: Serialize> {
+    pub chips: Vec<ChipOpenedValues<T>>,
+}
+
+/// The maximum number of elements that can be stored in the public values vec.  Both SP1 and recursive
+/// proofs need to pad their public_values vec to this length.  This is required since the recursion
+/// verification program expects the public values vec to be fixed length.
+pub const PROOF_MAX_NUM_PVS: usize = 272;
+
+/// The proof of a shard.
+///
+/// The chips of the shard are ordered by name in a [BTreeMap] rather than a `HashMap`, whose
+/// hasher is seeded with randomness from the operating system.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(bound = "")]
+pub struct ShardProof<SC: StarkGenericConfig> {
+    pub commitment: ShardCommitment<Com<SC>>,
+    pub opened_values: ShardOpenedValues<Challenge<SC>>,
+    pub opening_proof: OpeningProof<SC>,
+    pub chip_ordering: BTreeMap<String, usize>,
+    pub public_values: Vec<Val<SC>>,
+}
+
+impl<SC: StarkGenericConfig> Debug for ShardProof<SC> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ShardProof").finish()
+    }
+}
+
+impl<T: Send + Sync + Clone> AirOpenedValues<T> {
+    pub fn view(&self) -> VerticalPair<RowMajorMatrixView<'_, T>, RowMajorMatrixView<'_, T>> {
+        let a = RowMajorMatrixView::new_row(&self.local);
+        let b = RowMajorMatrixView::new_row(&self.next);
+        VerticalPair::new(a, b)
+    }
+}
+
+impl<SC: StarkGenericConfig> ShardProof<SC> {
+    pub fn cumulative_sum(&self) -> Challenge<SC> {
+        self.opened_values
+            .chips
+            .iter()
+            .map(|c| c.cumulative_sum)
+            .sum()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(bound = "")]
+pub struct MachineProof<SC: StarkGenericConfig> {
+    pub shard_proofs: Vec<ShardProof<SC>>,
+}
+
+impl<SC: StarkGenericConfig> Debug for MachineProof<SC> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Proof")
+            .field("shard_proofs", &self.shard_proofs.len())
+            .finish()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(serialize = "Dom<SC>: Serialize"))]
+#[serde(bound(deserialize = "Dom<SC>: DeserializeOwned"))]
+pub struct StarkVerifyingKey<SC: StarkGenericConfig> {
+    pub commit: Com<SC>,
+    pub pc_start: Val<SC>,
+    pub chip_information: Vec<(String, Dom<SC>, Dimensions)>,
+    pub chip_ordering: BTreeMap<String, usize>,
+    /// The hash of the Merkle trees of the config which set up the key.
+    pub merkle_hash: MerkleHash,
+    /// The parameters of FRI of the config which set up the key.
+    pub fri_parameters: FriParameters,
+    /// The layout of the lookup argument of the machine which set up the key.
+    pub lookup_argument: LookupArgument,
+    /// The tag of the proofs of the machine which set up the key.
+    pub domain_separator: DomainSeparator,
+}
+
+impl<SC: StarkGenericConfig> StarkVerifyingKey<SC> {
+    pub fn observe_into(&self, challenger: &mut SC::Challenger) {
+        challenger.observe(self.commit.clone());
+        challenger.observe(self.pc_start);
+        challenger.observe_slice(&self.domain_separator.elements::<Val<SC>>());
+    }
+}
+
+impl<SC: StarkGenericConfig> Debug for StarkVerifyingKey<SC> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VerifyingKey").finish()
+    }
+}
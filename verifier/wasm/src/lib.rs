@@ -0,0 +1,180 @@
+//! A verifier of the compressed proofs of SP1 for browsers.
+//!
+//! The crate compiles to `wasm32-unknown-unknown` and exports a [CompressedVerifier] and a
+//! [PublicValues] reader to JavaScript with `wasm-bindgen`, so that dapps verify proofs client-side
+//! without a server round trip:
+//!
+//! ```sh
+//! wasm-pack build verifier/wasm --target web --release
+//! ```
+//!
+//! ```js
+//! import init, { CompressedVerifier } from "./pkg/sp1_wasm_verifier.js";
+//!
+//! await init();
+//! const verifier = new CompressedVerifier(compressVk);
+//! const publicValues = verifier.verify(proof, vkey);
+//! const n = publicValues.read_u32();
+//! ```
+//!
+//! The verifying key of the compress program is exported by `ProverClient::compress_vk_bytes`,
+//! the proofs by `SP1CompressedProof::to_bytes` and the verifying keys of the programs by
+//! `SP1VerifyingKey::save`.
+
+mod public_values;
+
+use std::borrow::Borrow;
+
+use p3_baby_bear::BabyBear;
+use p3_field::{AbstractField, PrimeField32};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sp1_core::encoding::{
+    self, EncodingError, COMPRESS_VKEY_MAGIC, PROOF_MAGIC, SP1_CIRCUIT_VERSION, VKEY_MAGIC,
+};
+use sp1_core::io::{SP1PublicValues, SP1Stdin};
+use sp1_core::stark::{
+    MachineProof, MachineVerificationError, ShardProof, StarkGenericConfig, StarkMachine,
+    StarkVerifyingKey, VerifyingKeyDigest, PROOF_MAX_NUM_PVS,
+};
+use sp1_core::utils::BabyBearPoseidon2;
+use sp1_recursion_core::air::RecursionPublicValues;
+use sp1_recursion_core::stark::RecursionAirWideDeg3;
+use thiserror::Error;
+use wasm_bindgen::prelude::*;
+
+pub use public_values::PublicValues;
+
+/// The configuration of the compress machine.
+type InnerSC = BabyBearPoseidon2;
+
+#[derive(Error, Debug)]
+pub enum VerifierError {
+    #[error("encoding error: {0}")]
+    EncodingError(#[from] EncodingError),
+    #[error("the proof has {0} public values, expected {PROOF_MAX_NUM_PVS}")]
+    MalformedPublicValues(usize),
+    #[error("invalid proof: {0}")]
+    InvalidProof(#[from] MachineVerificationError<InnerSC>),
+    #[error("the proof is not complete")]
+    IncompleteProof,
+    #[error("the proof is of another program")]
+    ProgramMismatch,
+    #[error("the proof was compressed with another compress program")]
+    CompressProgramMismatch,
+    #[error("the public values do not match the digest committed by the proof")]
+    PublicValuesMismatch,
+    #[error("the program exited with code {0}")]
+    NonZeroExitCode(u32),
+    #[error(
+        "proof produced by incompatible prover version {0}, verifier expects {SP1_CIRCUIT_VERSION}"
+    )]
+    CircuitVersionMismatch(String),
+}
+
+/// A compressed proof, in the layout of `SP1CompressedProof` in the SDK.
+#[derive(Deserialize)]
+struct CompressedProof {
+    proof: ShardProof<InnerSC>,
+    #[allow(dead_code)]
+    stdin: SP1Stdin,
+    public_values: SP1PublicValues,
+    circuit_version: String,
+}
+
+/// A verifying key of a program, in the layout of `SP1VerifyingKey` in the prover.
+#[derive(Deserialize)]
+struct ProgramVerifyingKey {
+    vk: StarkVerifyingKey<BabyBearPoseidon2>,
+    circuit_version: String,
+}
+
+/// A verifier of compressed proofs, with the machine and the verifying key of the compress
+/// program, which take a while to build and are shared by all the proofs.
+#[wasm_bindgen]
+pub struct CompressedVerifier {
+    machine: StarkMachine<InnerSC, RecursionAirWideDeg3<BabyBear>>,
+    compress_vk: StarkVerifyingKey<InnerSC>,
+}
+
+impl CompressedVerifier {
+    /// Creates a verifier from the verifying key of the compress program, as encoded by
+    /// `ProverClient::compress_vk_bytes`.
+    pub fn from_bytes(compress_vk: &[u8]) -> Result<Self, VerifierError> {
+        Ok(Self {
+            machine: RecursionAirWideDeg3::machine(InnerSC::default()),
+            compress_vk: encoding::decode(COMPRESS_VKEY_MAGIC, compress_vk)?,
+        })
+    }
+
+    /// Verifies an encoded compressed proof of the program of the encoded verifying key, and
+    /// returns its public values.
+    ///
+    /// This checks the same as `ProverClient::verify_compressed`, and that the public values of
+    /// the proof are the ones its program committed to.
+    pub fn verify_bytes(&self, proof: &[u8], vkey: &[u8]) -> Result<Vec<u8>, VerifierError> {
+        let proof: CompressedProof = encoding::decode(PROOF_MAGIC, proof)?;
+        let vkey: ProgramVerifyingKey = encoding::decode(VKEY_MAGIC, vkey)?;
+        for circuit_version in [proof.circuit_version, vkey.circuit_version] {
+            if circuit_version != SP1_CIRCUIT_VERSION {
+                return Err(VerifierError::CircuitVersionMismatch(circuit_version));
+            }
+        }
+
+        // Check the length before the verifier and the borrow below index the public values.
+        let shard_proof = proof.proof;
+        if shard_proof.public_values.len() != PROOF_MAX_NUM_PVS {
+            return Err(VerifierError::MalformedPublicValues(
+                shard_proof.public_values.len(),
+            ));
+        }
+        let public_values: &RecursionPublicValues<BabyBear> =
+            shard_proof.public_values.as_slice().borrow();
+        if public_values.is_complete != BabyBear::one() {
+            return Err(VerifierError::IncompleteProof);
+        }
+        if public_values.sp1_vk_digest != vkey.vk.digest_babybear() {
+            return Err(VerifierError::ProgramMismatch);
+        }
+        if public_values.compress_vk_digest != self.compress_vk.digest_babybear() {
+            return Err(VerifierError::CompressProgramMismatch);
+        }
+        let digest = Sha256::digest(proof.public_values.as_slice());
+        let committed_digest = public_values
+            .committed_value_digest
+            .iter()
+            .flat_map(|word| word.0);
+        if !committed_digest
+            .zip(digest)
+            .all(|(committed, byte)| committed == BabyBear::from_canonical_u8(byte))
+        {
+            return Err(VerifierError::PublicValuesMismatch);
+        }
+        let exit_code = public_values.exit_code.as_canonical_u32();
+        if exit_code != 0 {
+            return Err(VerifierError::NonZeroExitCode(exit_code));
+        }
+
+        let mut challenger = self.machine.config().challenger();
+        let machine_proof = MachineProof {
+            shard_proofs: vec![shard_proof],
+        };
+        self.machine
+            .verify(&self.compress_vk, &machine_proof, &mut challenger)?;
+
+        Ok(proof.public_values.to_vec())
+    }
+}
+
+#[wasm_bindgen]
+impl CompressedVerifier {
+    #[wasm_bindgen(constructor)]
+    pub fn new(compress_vk: &[u8]) -> Result<CompressedVerifier, JsError> {
+        Ok(Self::from_bytes(compress_vk)?)
+    }
+
+    /// Verifies a compressed proof of the program of `vkey`, and returns its public values.
+    pub fn verify(&self, proof: &[u8], vkey: &[u8]) -> Result<PublicValues, JsError> {
+        Ok(PublicValues::new(self.verify_bytes(proof, vkey)?))
+    }
+}
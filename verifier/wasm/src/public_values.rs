@@ -0,0 +1,124 @@
+use serde::de::DeserializeOwned;
+use wasm_bindgen::prelude::*;
+
+/// The public values of a verified proof, with a cursor which reads the values that the program
+/// committed in order, as `sp1_zkvm::io::commit` and `sp1_zkvm::io::commit_slice` wrote them.
+#[wasm_bindgen]
+pub struct PublicValues {
+    bytes: Vec<u8>,
+    ptr: usize,
+}
+
+impl PublicValues {
+    pub const fn new(bytes: Vec<u8>) -> Self {
+        Self { bytes, ptr: 0 }
+    }
+
+    /// Reads a value committed with `sp1_zkvm::io::commit`.
+    pub fn read<T: DeserializeOwned>(&mut self) -> Result<T, bincode::Error> {
+        let mut reader = &self.bytes[self.ptr..];
+        let value = bincode::deserialize_from(&mut reader)?;
+        self.ptr = self.bytes.len() - reader.len();
+        Ok(value)
+    }
+
+    /// Reads `len` bytes committed with `sp1_zkvm::io::commit_slice`.
+    pub fn read_raw(&mut self, len: usize) -> Option<&[u8]> {
+        let end = self
+            .ptr
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())?;
+        let slice = &self.bytes[self.ptr..end];
+        self.ptr = end;
+        Some(slice)
+    }
+}
+
+#[wasm_bindgen]
+impl PublicValues {
+    /// All the bytes of the public values.
+    pub fn bytes(&self) -> Vec<u8> {
+        self.bytes.clone()
+    }
+
+    /// The public values as a hex string prefixed with `0x`.
+    pub fn hex(&self) -> String {
+        format!("0x{}", hex::encode(&self.bytes))
+    }
+
+    /// The number of bytes left to read.
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.ptr
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, JsError> {
+        Ok(self.read()?)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, JsError> {
+        Ok(self.read()?)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, JsError> {
+        Ok(self.read()?)
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, JsError> {
+        Ok(self.read()?)
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, JsError> {
+        Ok(self.read()?)
+    }
+
+    /// Reads a `u64`, as a `BigInt`.
+    pub fn read_u64(&mut self) -> Result<u64, JsError> {
+        Ok(self.read()?)
+    }
+
+    /// Reads an `i64`, as a `BigInt`.
+    pub fn read_i64(&mut self) -> Result<i64, JsError> {
+        Ok(self.read()?)
+    }
+
+    /// Reads a `Vec<u8>`, which is prefixed by its length. Byte arrays of a fixed size are read
+    /// with [Self::read_slice].
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>, JsError> {
+        Ok(self.read()?)
+    }
+
+    pub fn read_string(&mut self) -> Result<String, JsError> {
+        Ok(self.read()?)
+    }
+
+    /// Reads `len` bytes committed with `sp1_zkvm::io::commit_slice`.
+    pub fn read_slice(&mut self, len: usize) -> Result<Vec<u8>, JsError> {
+        self.read_raw(len)
+            .map(<[u8]>::to_vec)
+            .ok_or_else(|| JsError::new("not enough public values left to read"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_public_values() {
+        let mut bytes = bincode::serialize(&42u32).unwrap();
+        bytes.extend(bincode::serialize(&(1u64 << 40)).unwrap());
+        bytes.extend(bincode::serialize(&vec![1u8, 2, 3]).unwrap());
+        bytes.extend(bincode::serialize(&"sp1".to_string()).unwrap());
+        bytes.extend([7u8, 8]);
+
+        let mut public_values = PublicValues::new(bytes);
+        assert_eq!(public_values.read::<u32>().unwrap(), 42);
+        assert_eq!(public_values.read::<u64>().unwrap(), 1 << 40);
+        assert_eq!(public_values.read::<Vec<u8>>().unwrap(), vec![1, 2, 3]);
+        assert_eq!(public_values.read::<String>().unwrap(), "sp1");
+        assert_eq!(public_values.remaining(), 2);
+        assert!(public_values.read_raw(3).is_none());
+        assert_eq!(public_values.read_raw(2).unwrap(), &[7, 8]);
+        assert!(public_values.read::<u8>().is_err());
+    }
+}
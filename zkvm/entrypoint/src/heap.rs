@@ -1,12 +1,42 @@
 use core::alloc::{GlobalAlloc, Layout};
+use core::cell::UnsafeCell;
+use core::ptr;
+
+use cfg_if::cfg_if;
 
 use crate::syscalls::sys_alloc_aligned;
 
+cfg_if! {
+    if #[cfg(feature = "free-list-allocator")] {
+        /// The allocator of the programs declared with [crate::entrypoint].
+        pub type GuestAlloc = FreeListAlloc;
+    } else {
+        /// The allocator of the programs declared with [crate::entrypoint].
+        pub type GuestAlloc = SimpleAlloc;
+    }
+}
+
 /// A simple heap allocator.
 ///
 /// Allocates memory from left to right, without any deallocation.
+#[derive(Default)]
 pub struct SimpleAlloc;
 
+impl SimpleAlloc {
+    pub const fn new() -> Self {
+        Self
+    }
+
+    /// Allocates memory for `layout` which was never written.
+    ///
+    /// # Safety
+    ///
+    /// The same as [GlobalAlloc::alloc]: `layout` must have a non-zero size.
+    pub unsafe fn alloc_fresh(layout: Layout) -> *mut u8 {
+        sys_alloc_aligned(layout.size(), layout.align())
+    }
+}
+
 unsafe impl GlobalAlloc for SimpleAlloc {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         sys_alloc_aligned(layout.size(), layout.align())
@@ -14,3 +44,127 @@ unsafe impl GlobalAlloc for SimpleAlloc {
 
     unsafe fn dealloc(&self, _: *mut u8, _: Layout) {}
 }
+
+/// The size of the smallest block of [FreeListAlloc], which holds the pointer to the next free
+/// block of its size.
+const MIN_BLOCK_SIZE: usize = 8;
+
+/// The largest alignment of the blocks of [FreeListAlloc]. Allocations with a larger alignment are
+/// taken from the heap as with [SimpleAlloc], and never freed.
+const MAX_BLOCK_ALIGN: usize = 4096;
+
+/// The number of sizes of the blocks of [FreeListAlloc], the powers of two from [MIN_BLOCK_SIZE]
+/// to 2^31 bytes.
+const NUM_SIZE_CLASSES: usize = 29;
+
+/// A free block of [FreeListAlloc].
+struct FreeBlock {
+    next: *mut FreeBlock,
+}
+
+/// A heap allocator which reuses the freed memory.
+///
+/// Each allocation is rounded up to a block whose size is a power of two, and a freed block is
+/// kept in the free list of its size to serve the next allocation of that size. New blocks are
+/// taken from the heap as with [SimpleAlloc], aligned to their size up to [MAX_BLOCK_ALIGN] bytes.
+///
+/// The memory of the blocks which is never written is not part of the trace of the program, so the
+/// rounding of the blocks only costs address space, while programs which allocate and free a lot
+/// touch much less memory than with [SimpleAlloc].
+pub struct FreeListAlloc {
+    free_lists: UnsafeCell<[*mut FreeBlock; NUM_SIZE_CLASSES]>,
+}
+
+// SAFETY: The programs of the zkVM are single threaded.
+unsafe impl Sync for FreeListAlloc {}
+
+impl FreeListAlloc {
+    pub const fn new() -> Self {
+        Self {
+            free_lists: UnsafeCell::new([ptr::null_mut(); NUM_SIZE_CLASSES]),
+        }
+    }
+
+    /// The index of the size of the blocks of `layout`, or `None` if it is not served by blocks.
+    fn size_class(layout: &Layout) -> Option<usize> {
+        if layout.align() > MAX_BLOCK_ALIGN {
+            return None;
+        }
+        let size = layout
+            .size()
+            .max(layout.align())
+            .max(MIN_BLOCK_SIZE)
+            .checked_next_power_of_two()?;
+        let class = (size.trailing_zeros() - MIN_BLOCK_SIZE.trailing_zeros()) as usize;
+        (class < NUM_SIZE_CLASSES).then_some(class)
+    }
+
+    /// Allocates a new block for `layout` from the heap, without looking at the free lists, so
+    /// that its memory was never written. The block may be freed with `layout` like the others.
+    ///
+    /// # Safety
+    ///
+    /// The same as [GlobalAlloc::alloc]: `layout` must have a non-zero size.
+    pub unsafe fn alloc_fresh(layout: Layout) -> *mut u8 {
+        let Some(class) = Self::size_class(&layout) else {
+            return sys_alloc_aligned(layout.size(), layout.align());
+        };
+        let size = MIN_BLOCK_SIZE << class;
+        sys_alloc_aligned(size, size.min(MAX_BLOCK_ALIGN))
+    }
+}
+
+impl Default for FreeListAlloc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl GlobalAlloc for FreeListAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let Some(class) = Self::size_class(&layout) else {
+            return Self::alloc_fresh(layout);
+        };
+        let free_lists = &mut *self.free_lists.get();
+        let block = free_lists[class];
+        if !block.is_null() {
+            free_lists[class] = (*block).next;
+            return block as *mut u8;
+        }
+        Self::alloc_fresh(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let Some(class) = Self::size_class(&layout) else {
+            return;
+        };
+        let free_lists = &mut *self.free_lists.get();
+        let block = ptr as *mut FreeBlock;
+        (*block).next = free_lists[class];
+        free_lists[class] = block;
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        let class = Self::size_class(&layout);
+        if class.is_some() && class == Self::size_class(&new_layout) {
+            // The block of the allocation also fits the new size.
+            return ptr;
+        }
+        let new_ptr = self.alloc(new_layout);
+        if !new_ptr.is_null() {
+            ptr::copy_nonoverlapping(ptr, new_ptr, layout.size().min(new_size));
+            self.dealloc(ptr, layout);
+        }
+        new_ptr
+    }
+}
+
+/// Allocates memory which was never written with the allocator of the programs, for the `HINT_READ`
+/// syscall which assumes that the memory it writes is uninitialized. The memory may be freed with
+/// the layout of `bytes` and `align`.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn sys_alloc_fresh(bytes: usize, align: usize) -> *mut u8 {
+    GuestAlloc::alloc_fresh(Layout::from_size_align_unchecked(bytes, align))
+}
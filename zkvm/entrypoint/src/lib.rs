@@ -1,11 +1,39 @@
 pub mod heap;
 pub mod syscalls;
+pub mod codec {
+    pub use sp1_precompiles::codec::*;
+}
+pub mod abi {
+    pub use sp1_precompiles::abi::*;
+}
 pub mod io {
     pub use sp1_precompiles::io::*;
 }
 pub mod precompiles {
     pub use sp1_precompiles::*;
 }
+pub mod process {
+    pub use sp1_precompiles::process::*;
+}
+
+/// Verifies the next proof written to the stdin with `SP1Stdin::write_proof`, given the digest of
+/// its verifying key and the SHA-256 digest of its public values.
+///
+/// The proof is verified when the proof of the program is compressed, so a program which verifies
+/// proofs only has a valid compressed proof. Requires the `verify` feature.
+#[cfg(feature = "verify")]
+pub use sp1_precompiles::verify::verify_sp1_proof;
+
+/// Executes a block of code without proving it. See [sp1_precompiles::unconstrained].
+pub use sp1_precompiles::unconstrained;
+
+/// The number of cycles the program has executed so far, modulo 2^32.
+///
+/// The count is given by the prover and is not constrained, so the program must be correct
+/// whatever the count is, for example by only using it to bound a search whose result it checks.
+pub fn cycle_count() -> u32 {
+    syscalls::syscall_cycle_count()
+}
 
 extern crate alloc;
 
@@ -14,10 +42,8 @@ macro_rules! entrypoint {
     ($path:path) => {
         const ZKVM_ENTRY: fn() = $path;
 
-        use $crate::heap::SimpleAlloc;
-
         #[global_allocator]
-        static HEAP: SimpleAlloc = SimpleAlloc;
+        static HEAP: $crate::heap::GuestAlloc = $crate::heap::GuestAlloc::new();
 
         mod zkvm_generated_main {
 
@@ -36,6 +62,9 @@ mod libm;
 pub const PV_DIGEST_NUM_WORDS: usize = 8;
 pub const POSEIDON_NUM_WORDS: usize = 8;
 
+/// The number of 32 bit words that the program can set as public values of its own.
+pub const USER_PV_NUM_WORDS: usize = 8;
+
 #[cfg(target_os = "zkvm")]
 mod zkvm {
     use crate::syscalls::syscall_halt;
@@ -55,6 +84,9 @@ mod zkvm {
 
     pub static mut PUBLIC_VALUES_HASHER: Option<Sha256> = None;
 
+    pub static mut USER_PUBLIC_VALUES: [u32; crate::USER_PV_NUM_WORDS] =
+        [0; crate::USER_PV_NUM_WORDS];
+
     #[cfg(not(feature = "interface"))]
     #[no_mangle]
     unsafe extern "C" fn __start() {
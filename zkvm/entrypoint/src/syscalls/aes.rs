@@ -0,0 +1,63 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Executes one AES encryption round on a 16-byte state.
+///
+/// The round applies SubBytes, ShiftRows, MixColumns and AddRoundKey with the 16-byte round key.
+/// The result is written over the state.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_aes_encrypt_round(state: *mut u32, round_key: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::AES_ENCRYPT_ROUND,
+            in("a0") state,
+            in("a1") round_key,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Executes the last AES encryption round on a 16-byte state.
+///
+/// The last round skips MixColumns. The result is written over the state.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_aes_encrypt_last_round(state: *mut u32, round_key: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::AES_ENCRYPT_LAST_ROUND,
+            in("a0") state,
+            in("a1") round_key,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Multiplies two 16-byte blocks in the GHASH field of AES-GCM.
+///
+/// The result is written over the first input.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_ghash_mul(x: *mut u32, h: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::GHASH_MUL,
+            in("a0") x,
+            in("a1") h,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -0,0 +1,90 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Uint2048 modular multiplication operation.
+///
+/// The second input points to the multiplier followed by the modulus. The result is written over
+/// the first input. Execution fails unless the modulus is nonzero and one of the inputs is reduced
+/// modulo it.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint2048_mulmod(x: *mut u32, y: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT2048_MULMOD,
+            in("a0") x,
+            in("a1") y,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Uint4096 modular multiplication operation.
+///
+/// The second input points to the multiplier followed by the modulus. The result is written over
+/// the first input. Execution fails unless the modulus is nonzero and one of the inputs is reduced
+/// modulo it.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint4096_mulmod(x: *mut u32, y: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT4096_MULMOD,
+            in("a0") x,
+            in("a1") y,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Uint384 modular multiplication operation.
+///
+/// The second input points to the multiplier followed by the modulus. The result is written over
+/// the first input. Execution fails unless the modulus is nonzero and one of the inputs is reduced
+/// modulo it.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint384_mulmod(x: *mut u32, y: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT384_MULMOD,
+            in("a0") x,
+            in("a1") y,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Uint512 modular multiplication operation.
+///
+/// The second input points to the multiplier followed by the modulus. The result is written over
+/// the first input. Execution fails unless the modulus is nonzero and one of the inputs is reduced
+/// modulo it.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_uint512_mulmod(x: *mut u32, y: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::UINT512_MULMOD,
+            in("a0") x,
+            in("a1") y,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -0,0 +1,24 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Adds the product of a coefficient by 64 bytes of data to a 64-byte accumulator, elementwise in
+/// GF(2^8) modulo `x^8 + x^4 + x^3 + x^2 + 1`.
+///
+/// The input is the coefficient as a word smaller than 256, followed by the 16 words of the data.
+/// The result is written over the accumulator.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_gf256_mac(acc: *mut u32, input: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::GF256_MAC,
+            in("a0") acc,
+            in("a1") input,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -5,7 +5,7 @@ cfg_if! {
         use core::arch::asm;
         use sha2::Digest;
         use crate::zkvm;
-        use crate::{PV_DIGEST_NUM_WORDS, POSEIDON_NUM_WORDS};
+        use crate::{PV_DIGEST_NUM_WORDS, POSEIDON_NUM_WORDS, USER_PV_NUM_WORDS};
     }
 }
 
@@ -61,6 +61,12 @@ pub extern "C" fn syscall_halt(exit_code: u8) -> ! {
             }
         }
 
+        // Commit all the user public values, so that the ones the program did not set are bound
+        // to zero.
+        for i in 0..USER_PV_NUM_WORDS {
+            asm!("ecall", in("t0") crate::syscalls::COMMIT_USER_VALUE, in("a0") i, in("a1") zkvm::USER_PUBLIC_VALUES[i]);
+        }
+
         asm!(
             "ecall",
             in("t0") crate::syscalls::HALT,
@@ -72,3 +78,39 @@ pub extern "C" fn syscall_halt(exit_code: u8) -> ! {
     #[cfg(not(target_os = "zkvm"))]
     unreachable!()
 }
+
+/// Reports the panic message of the program to the host, before the panic handler halts.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_panic(msg_ptr: *const u8, len: usize) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::PANIC,
+            in("a0") msg_ptr,
+            in("a1") len,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
+
+/// Returns the lower word of the global clock.
+#[no_mangle]
+pub extern "C" fn syscall_cycle_count() -> u32 {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        let value;
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::CYCLE_COUNT,
+            lateout("t0") value,
+        );
+        value
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
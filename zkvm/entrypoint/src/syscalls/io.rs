@@ -7,6 +7,24 @@ cfg_if::cfg_if! {
     }
 }
 
+/// Sets a word of the user public values, which are committed when the program halts.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_set_user_public_value(index: u32, word: u32) {
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "zkvm")] {
+            assert!(
+                (index as usize) < crate::USER_PV_NUM_WORDS,
+                "user public value index {} is out of bounds",
+                index
+            );
+            unsafe { zkvm::USER_PUBLIC_VALUES[index as usize] = word };
+        } else {
+            unreachable!()
+        }
+    }
+}
+
 /// Write data to the prover.
 #[allow(unused_variables)]
 #[no_mangle]
@@ -70,3 +88,19 @@ pub extern "C" fn syscall_hint_read(ptr: *mut u8, len: usize) {
     #[cfg(not(target_os = "zkvm"))]
     unreachable!()
 }
+
+/// Hints the next random bytes drawn from the seed of the host, to be read with `read_vec`.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_random() {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::RANDOM,
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -18,3 +18,24 @@ pub extern "C" fn syscall_keccak_permute(state: *mut u64) {
     #[cfg(not(target_os = "zkvm"))]
     unreachable!()
 }
+
+/// Absorbs a block into the Keccak sponge state and permutes it.
+///
+/// The first word of `block` is the rate of the sponge in 64-bit lanes, followed by the lanes of
+/// the block that are XORed into the state.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_keccak_absorb(state: *mut u64, block: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::KECCAK_ABSORB,
+            in("a0") state,
+            in("a1") block
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
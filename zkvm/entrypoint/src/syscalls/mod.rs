@@ -1,12 +1,19 @@
+mod aes;
+mod bigint_mulmod;
 mod blake3_compress;
 mod bls12381;
 mod bn254;
+mod chacha20;
 mod ed25519;
+mod gf256;
 mod halt;
 mod io;
 mod keccak_permute;
 mod memory;
+mod poseidon2;
+mod ripemd160;
 mod secp256k1;
+mod secp256r1;
 mod sha_compress;
 mod sha_extend;
 mod sys;
@@ -15,14 +22,21 @@ mod unconstrained;
 #[cfg(feature = "verify")]
 mod verify;
 
+pub use aes::*;
+pub use bigint_mulmod::*;
 pub use bls12381::*;
 pub use bn254::*;
+pub use chacha20::*;
 pub use ed25519::*;
+pub use gf256::*;
 pub use halt::*;
 pub use io::*;
 pub use keccak_permute::*;
 pub use memory::*;
+pub use poseidon2::*;
+pub use ripemd160::*;
 pub use secp256k1::*;
+pub use secp256r1::*;
 pub use sha_compress::*;
 pub use sha_extend::*;
 pub use sys::*;
@@ -85,6 +99,9 @@ pub const COMMIT: u32 = 0x00_00_00_10;
 /// Executes the `COMMIT_DEFERRED_PROOFS` precompile.
 pub const COMMIT_DEFERRED_PROOFS: u32 = 0x00_00_00_1A;
 
+/// Executes the `COMMIT_USER_VALUE` precompile.
+pub const COMMIT_USER_VALUE: u32 = 0x00_00_00_35;
+
 /// Executes the `VERIFY_SP1_PROOF` precompile.
 pub const VERIFY_SP1_PROOF: u32 = 0x00_00_00_1B;
 
@@ -94,6 +111,15 @@ pub const HINT_LEN: u32 = 0x00_00_00_F0;
 /// Executes `HINT_READ`.
 pub const HINT_READ: u32 = 0x00_00_00_F1;
 
+/// Reports the panic message of the program.
+pub const PANIC: u32 = 0x00_00_00_F2;
+
+/// Returns a word of the global clock.
+pub const CYCLE_COUNT: u32 = 0x00_00_00_F3;
+
+/// Hints the next random bytes drawn from the seed of the host.
+pub const RANDOM: u32 = 0x00_00_00_F4;
+
 /// Executes `BLS12381_DECOMPRESS`.
 pub const BLS12381_DECOMPRESS: u32 = 0x00_00_01_1C;
 
@@ -105,3 +131,58 @@ pub const BLS12381_ADD: u32 = 0x00_01_01_1E;
 
 /// Executes the `BLS12381_DOUBLE` precompile.
 pub const BLS12381_DOUBLE: u32 = 0x00_00_01_1F;
+
+/// Executes the `BLS12381_FP_ADD` precompile.
+pub const BLS12381_FP_ADD: u32 = 0x00_01_01_20;
+
+/// Executes the `BLS12381_FP_SUB` precompile.
+pub const BLS12381_FP_SUB: u32 = 0x00_01_01_21;
+
+/// Executes the `BLS12381_FP_MUL` precompile.
+pub const BLS12381_FP_MUL: u32 = 0x00_01_01_22;
+
+/// Executes the `SECP256R1_ADD` precompile.
+pub const SECP256R1_ADD: u32 = 0x00_01_01_26;
+
+/// Executes the `SECP256R1_DOUBLE` precompile.
+pub const SECP256R1_DOUBLE: u32 = 0x00_00_01_27;
+
+/// Executes the `UINT2048_MULMOD` precompile.
+pub const UINT2048_MULMOD: u32 = 0x00_01_01_28;
+
+/// Executes the `UINT4096_MULMOD` precompile.
+pub const UINT4096_MULMOD: u32 = 0x00_01_01_29;
+
+/// Executes the `KECCAK_ABSORB` precompile.
+pub const KECCAK_ABSORB: u32 = 0x00_02_01_2A;
+
+/// Executes the `POSEIDON2_PERMUTE` precompile.
+pub const POSEIDON2_PERMUTE: u32 = 0x00_00_01_2B;
+
+/// Executes the `UINT384_MULMOD` precompile.
+pub const UINT384_MULMOD: u32 = 0x00_01_01_2C;
+
+/// Executes the `UINT512_MULMOD` precompile.
+pub const UINT512_MULMOD: u32 = 0x00_01_01_2D;
+
+/// Executes the `AES_ENCRYPT_ROUND` precompile.
+pub const AES_ENCRYPT_ROUND: u32 = 0x00_01_01_2E;
+
+/// Executes the `AES_ENCRYPT_LAST_ROUND` precompile.
+pub const AES_ENCRYPT_LAST_ROUND: u32 = 0x00_01_01_2F;
+
+/// Executes the `GHASH_MUL` precompile.
+pub const GHASH_MUL: u32 = 0x00_01_01_30;
+
+/// Executes the `CHACHA20_PERMUTE` precompile.
+pub const CHACHA20_PERMUTE: u32 = 0x00_50_01_31;
+
+/// Executes the `RIPEMD160_COMPRESS` precompile.
+#[allow(clippy::mistyped_literal_suffixes)]
+pub const RIPEMD160_COMPRESS: u32 = 0x00_50_01_32;
+
+/// Executes the `SECP256K1_ECRECOVER` precompile.
+pub const SECP256K1_ECRECOVER: u32 = 0x00_01_01_33;
+
+/// Executes the `GF256_MAC` precompile.
+pub const GF256_MAC: u32 = 0x00_01_01_34;
@@ -0,0 +1,22 @@
+#[cfg(target_os = "zkvm")]
+use core::arch::asm;
+
+/// Executes the RIPEMD-160 compression function on a 5-word state and a 16-word message block.
+///
+/// The result is written over the state.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_ripemd160_compress(state: *mut u32, block: *const u32) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::RIPEMD160_COMPRESS,
+            in("a0") state,
+            in("a1") block
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
@@ -67,3 +67,25 @@ pub extern "C" fn syscall_secp256k1_decompress(point: &mut [u8; 64], is_odd: boo
     #[cfg(not(target_os = "zkvm"))]
     unreachable!()
 }
+
+/// Recovers the Secp256k1 public key of an ECDSA signature.
+///
+/// The input is 24 words long and holds the message hash, `r` and `s` as little-endian integers,
+/// and `is_odd` is the parity of the `y` coordinate of the nonce point. The first 16 words of the
+/// input will be overwritten with the `x` and `y` coordinates of the public key.
+#[allow(unused_variables)]
+#[no_mangle]
+pub extern "C" fn syscall_secp256k1_ecrecover(input: *mut u32, is_odd: bool) {
+    #[cfg(target_os = "zkvm")]
+    unsafe {
+        asm!(
+            "ecall",
+            in("t0") crate::syscalls::SECP256K1_ECRECOVER,
+            in("a0") input,
+            in("a1") is_odd as u32
+        );
+    }
+
+    #[cfg(not(target_os = "zkvm"))]
+    unreachable!()
+}
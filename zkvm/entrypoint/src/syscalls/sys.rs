@@ -1,27 +1,102 @@
-use crate::syscalls::{syscall_halt, syscall_write};
+//! The platform functions which the standard library of the zkVM target calls, so that programs
+//! and their dependencies can use `std`.
+
+use crate::syscalls::{
+    sys_alloc_aligned, syscall_cycle_count, syscall_halt, syscall_panic, syscall_write,
+};
+
+/// The file descriptor of the stdin of the program.
+const FD_STDIN: u32 = 0;
+
+/// The value [sys_getenv] and [sys_argv] return for a variable or argument which is not set.
+const NOT_SET: usize = usize::MAX;
 
 #[allow(clippy::missing_safety_doc)]
 #[no_mangle]
 pub unsafe extern "C" fn sys_panic(msg_ptr: *const u8, len: usize) -> ! {
     sys_write(2, msg_ptr, len);
+    syscall_panic(msg_ptr, len);
     syscall_halt(1);
 }
 
+/// Reads an environment variable. Programs have no environment, so no variable is set.
 #[allow(unused_variables)]
 #[no_mangle]
-pub const fn sys_getenv(
+pub const extern "C" fn sys_getenv(
     recv_buf: *mut u32,
     words: usize,
     varname: *const u8,
     varname_len: usize,
 ) -> usize {
+    NOT_SET
+}
+
+/// The number of arguments of the program, which has none.
+#[no_mangle]
+pub const extern "C" fn sys_argc() -> usize {
     0
 }
 
+/// Reads an argument of the program, which has none.
 #[allow(unused_variables)]
 #[no_mangle]
-pub const fn sys_alloc_words(nwords: usize) -> *mut u32 {
-    core::ptr::null_mut()
+pub const extern "C" fn sys_argv(
+    out_words: *mut u32,
+    out_nwords: usize,
+    arg_index: usize,
+) -> usize {
+    NOT_SET
+}
+
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn sys_alloc_words(nwords: usize) -> *mut u32 {
+    sys_alloc_aligned(nwords * 4, 4) as *mut u32
+}
+
+/// Fills `recv_buf` with random words, from the same generator as `getrandom`. The words are not
+/// secret, since the prover chooses them.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn sys_rand(recv_buf: *mut u32, words: usize) {
+    let bytes = core::slice::from_raw_parts_mut(recv_buf as *mut u8, words * 4);
+    getrandom::getrandom(bytes).expect("failed to generate random words");
+}
+
+/// Reads up to `nrequested` bytes of `fd` into `recv_buf`, and returns the number of bytes read.
+///
+/// The stdin of the program is the next input the host wrote, such as with
+/// `SP1Stdin::write_slice`, which the program reads as a stream of bytes until its end. The other
+/// file descriptors are empty.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn sys_read(fd: u32, recv_buf: *mut u8, nrequested: usize) -> usize {
+    /// The bytes of the stdin, and the number of them which were read.
+    static mut STDIN: Option<(Vec<u8>, usize)> = None;
+
+    if fd != FD_STDIN {
+        return 0;
+    }
+    let (stdin, pos) = (*core::ptr::addr_of_mut!(STDIN))
+        .get_or_insert_with(|| (sp1_precompiles::io::read_vec(), 0));
+    let nread = nrequested.min(stdin.len() - *pos);
+    core::ptr::copy_nonoverlapping(stdin[*pos..].as_ptr(), recv_buf, nread);
+    *pos += nread;
+    nread
+}
+
+/// The lower word of the number of cycles the program has executed.
+#[no_mangle]
+pub extern "C" fn sys_cycle_count() -> usize {
+    syscall_cycle_count() as usize
+}
+
+/// Writes a message of the standard library to the stderr of the program.
+#[allow(clippy::missing_safety_doc)]
+#[no_mangle]
+pub unsafe extern "C" fn sys_log(msg_ptr: *const u8, len: usize) {
+    sys_write(2, msg_ptr, len);
+    sys_write(2, b"\n".as_ptr(), 1);
 }
 
 #[allow(unused_unsafe)]
@@ -0,0 +1,426 @@
+//! Encoding of values as with Solidity's `abi.encode`, so that a contract can read the public
+//! values of a proof with `abi.decode` instead of parsing them at hand-picked byte offsets.
+//!
+//! A value is encoded as a tuple of one element: a static value is its inline encoding, and a
+//! dynamic value is an offset followed by its encoding, as for `abi.encode(value)`. Structs are
+//! encoded as tuples of their fields with `#[derive(SolidityAbi)]`:
+//!
+//! ```ignore
+//! use sp1_zkvm::abi::{Bytes32, SolidityAbi};
+//!
+//! #[derive(SolidityAbi)]
+//! struct Output {
+//!     root: Bytes32,
+//!     count: u64,
+//!     balances: Vec<u128>,
+//! }
+//! ```
+//!
+//! The generated code refers to the module as `sp1_zkvm::abi`. Crates which depend on the SDK
+//! instead set the path with `#[sol_abi_path = "sp1_sdk::abi"]`.
+
+use anyhow::anyhow;
+pub use anyhow::Result;
+
+pub use sp1_derive::SolidityAbi;
+
+/// The number of bytes of a word of the ABI.
+pub const WORD_LEN: usize = 32;
+
+/// A type which can be encoded in the Solidity ABI.
+pub trait SolidityAbi: Sized {
+    /// Whether the type is dynamic, in which case its encoding is in the tail of the enclosing
+    /// tuple and its head is an offset.
+    const DYNAMIC: bool;
+
+    /// The number of bytes of the head of the type in an enclosing tuple.
+    const HEAD_LEN: usize;
+
+    /// The Solidity type, such as `uint64` or `(bytes32,uint128[])`.
+    fn sol_type() -> String;
+
+    /// Appends the encoding of the value, which for a dynamic value excludes its offset.
+    fn encode_body(&self, out: &mut Vec<u8>);
+
+    /// Decodes a value whose encoding, excluding the offset of a dynamic value, starts at
+    /// `offset` of `data`.
+    fn decode_body(data: &[u8], offset: usize) -> Result<Self>;
+}
+
+/// Encodes a value as with `abi.encode(value)`.
+pub fn abi_encode<T: SolidityAbi>(value: &T) -> Vec<u8> {
+    let mut encoder = TupleEncoder::new(T::HEAD_LEN);
+    encoder.push(value);
+    let mut out = Vec::new();
+    encoder.finish(&mut out);
+    out
+}
+
+/// Decodes a value encoded as with `abi.encode(value)`, as `abi.decode(data, (T))` does.
+pub fn abi_decode<T: SolidityAbi>(data: &[u8]) -> Result<T> {
+    TupleDecoder::new(data, 0).read()
+}
+
+/// The encoder of the elements of a tuple, which writes the heads of the elements and then the
+/// encodings of the dynamic ones.
+pub struct TupleEncoder {
+    head_len: usize,
+    head: Vec<u8>,
+    tail: Vec<u8>,
+}
+
+impl TupleEncoder {
+    /// Creates an encoder for a tuple whose elements have heads of `head_len` bytes in total.
+    pub fn new(head_len: usize) -> Self {
+        Self {
+            head_len,
+            head: Vec::with_capacity(head_len),
+            tail: Vec::new(),
+        }
+    }
+
+    /// Appends an element to the tuple.
+    pub fn push<T: SolidityAbi>(&mut self, value: &T) {
+        if T::DYNAMIC {
+            let offset = self.head_len + self.tail.len();
+            self.head.extend_from_slice(&encode_uint(offset as u128));
+            value.encode_body(&mut self.tail);
+        } else {
+            value.encode_body(&mut self.head);
+        }
+    }
+
+    /// Appends the encoding of the tuple to `out`.
+    pub fn finish(self, out: &mut Vec<u8>) {
+        debug_assert_eq!(self.head.len(), self.head_len, "wrong head length");
+        out.extend_from_slice(&self.head);
+        out.extend_from_slice(&self.tail);
+    }
+}
+
+/// The decoder of the elements of a tuple.
+pub struct TupleDecoder<'a> {
+    data: &'a [u8],
+    start: usize,
+    position: usize,
+}
+
+impl<'a> TupleDecoder<'a> {
+    /// Creates a decoder for a tuple whose encoding starts at `start` of `data`.
+    pub fn new(data: &'a [u8], start: usize) -> Self {
+        Self {
+            data,
+            start,
+            position: start,
+        }
+    }
+
+    /// Decodes the next element of the tuple.
+    pub fn read<T: SolidityAbi>(&mut self) -> Result<T> {
+        let value = if T::DYNAMIC {
+            let offset = decode_offset(self.data, self.position)?;
+            let offset = self
+                .start
+                .checked_add(offset)
+                .ok_or_else(|| anyhow!("the offset {} is out of range", offset))?;
+            T::decode_body(self.data, offset)?
+        } else {
+            T::decode_body(self.data, self.position)?
+        };
+        self.position += T::HEAD_LEN;
+        Ok(value)
+    }
+}
+
+/// A `bytes32` value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Bytes32(pub [u8; 32]);
+
+/// A dynamic `bytes` value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Bytes(pub Vec<u8>);
+
+/// An `address` value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Address(pub [u8; 20]);
+
+impl From<[u8; 32]> for Bytes32 {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<[u8; 20]> for Address {
+    fn from(bytes: [u8; 20]) -> Self {
+        Self(bytes)
+    }
+}
+
+/// Encodes an unsigned integer as a word.
+fn encode_uint(value: u128) -> [u8; WORD_LEN] {
+    let mut word = [0u8; WORD_LEN];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// Returns the word at `offset` of `data`.
+fn word_at(data: &[u8], offset: usize) -> Result<&[u8; WORD_LEN]> {
+    offset
+        .checked_add(WORD_LEN)
+        .and_then(|end| data.get(offset..end))
+        .map(|word| word.try_into().unwrap())
+        .ok_or_else(|| anyhow!("the data is too short to read a word at {}", offset))
+}
+
+/// Decodes the unsigned integer of `bits` bits at `offset` of `data`.
+fn decode_uint(data: &[u8], offset: usize, bits: usize) -> Result<u128> {
+    let word = word_at(data, offset)?;
+    let padding = WORD_LEN - bits / 8;
+    if word[..padding].iter().any(|byte| *byte != 0) {
+        return Err(anyhow!("the value at {} is not a uint{}", offset, bits));
+    }
+    Ok(u128::from_be_bytes(word[16..].try_into().unwrap()))
+}
+
+/// Decodes an offset or a length at `offset` of `data`.
+fn decode_offset(data: &[u8], offset: usize) -> Result<usize> {
+    let value = decode_uint(data, offset, 64)?;
+    usize::try_from(value).map_err(|_| anyhow!("the offset {} is out of range", value))
+}
+
+macro_rules! impl_uint {
+    ($($ty:ty),*) => {
+        $(
+            impl SolidityAbi for $ty {
+                const DYNAMIC: bool = false;
+                const HEAD_LEN: usize = WORD_LEN;
+
+                fn sol_type() -> String {
+                    format!("uint{}", <$ty>::BITS)
+                }
+
+                fn encode_body(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&encode_uint(*self as u128));
+                }
+
+                fn decode_body(data: &[u8], offset: usize) -> Result<Self> {
+                    Ok(decode_uint(data, offset, <$ty>::BITS as usize)? as $ty)
+                }
+            }
+        )*
+    };
+}
+
+impl_uint!(u8, u16, u32, u64, u128);
+
+macro_rules! impl_int {
+    ($($ty:ty),*) => {
+        $(
+            impl SolidityAbi for $ty {
+                const DYNAMIC: bool = false;
+                const HEAD_LEN: usize = WORD_LEN;
+
+                fn sol_type() -> String {
+                    format!("int{}", <$ty>::BITS)
+                }
+
+                fn encode_body(&self, out: &mut Vec<u8>) {
+                    let fill = if *self < 0 { 0xff } else { 0 };
+                    let mut word = [fill; WORD_LEN];
+                    word[16..].copy_from_slice(&(*self as i128).to_be_bytes());
+                    out.extend_from_slice(&word);
+                }
+
+                fn decode_body(data: &[u8], offset: usize) -> Result<Self> {
+                    let word = word_at(data, offset)?;
+                    let value = i128::from_be_bytes(word[16..].try_into().unwrap());
+                    let fill = if value < 0 { 0xff } else { 0 };
+                    let padding = WORD_LEN - <$ty>::BITS as usize / 8;
+                    let sign_bytes = &word[..padding];
+                    <$ty>::try_from(value)
+                        .ok()
+                        .filter(|_| sign_bytes.iter().all(|byte| *byte == fill))
+                        .ok_or_else(|| {
+                            anyhow!("the value at {} is not an int{}", offset, <$ty>::BITS)
+                        })
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(i8, i16, i32, i64, i128);
+
+impl SolidityAbi for bool {
+    const DYNAMIC: bool = false;
+    const HEAD_LEN: usize = WORD_LEN;
+
+    fn sol_type() -> String {
+        "bool".to_string()
+    }
+
+    fn encode_body(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&encode_uint(*self as u128));
+    }
+
+    fn decode_body(data: &[u8], offset: usize) -> Result<Self> {
+        match decode_uint(data, offset, 8)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(anyhow!("the value at {} is not a bool", offset)),
+        }
+    }
+}
+
+impl SolidityAbi for Bytes32 {
+    const DYNAMIC: bool = false;
+    const HEAD_LEN: usize = WORD_LEN;
+
+    fn sol_type() -> String {
+        "bytes32".to_string()
+    }
+
+    fn encode_body(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.0);
+    }
+
+    fn decode_body(data: &[u8], offset: usize) -> Result<Self> {
+        Ok(Self(*word_at(data, offset)?))
+    }
+}
+
+impl SolidityAbi for Address {
+    const DYNAMIC: bool = false;
+    const HEAD_LEN: usize = WORD_LEN;
+
+    fn sol_type() -> String {
+        "address".to_string()
+    }
+
+    fn encode_body(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&[0u8; 12]);
+        out.extend_from_slice(&self.0);
+    }
+
+    fn decode_body(data: &[u8], offset: usize) -> Result<Self> {
+        let word = word_at(data, offset)?;
+        if word[..12].iter().any(|byte| *byte != 0) {
+            return Err(anyhow!("the value at {} is not an address", offset));
+        }
+        Ok(Self(word[12..].try_into().unwrap()))
+    }
+}
+
+/// Appends a length followed by the bytes, padded with zeros to a multiple of a word.
+fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    out.extend_from_slice(&encode_uint(bytes.len() as u128));
+    out.extend_from_slice(bytes);
+    let padded_len = bytes.len().div_ceil(WORD_LEN) * WORD_LEN;
+    out.resize(out.len() + padded_len - bytes.len(), 0);
+}
+
+/// Decodes a length followed by as many bytes at `offset` of `data`.
+fn decode_bytes(data: &[u8], offset: usize) -> Result<&[u8]> {
+    let len = decode_offset(data, offset)?;
+    let start = offset + WORD_LEN;
+    start
+        .checked_add(len)
+        .and_then(|end| data.get(start..end))
+        .ok_or_else(|| anyhow!("the data is too short to read {} bytes at {}", len, start))
+}
+
+impl SolidityAbi for Bytes {
+    const DYNAMIC: bool = true;
+    const HEAD_LEN: usize = WORD_LEN;
+
+    fn sol_type() -> String {
+        "bytes".to_string()
+    }
+
+    fn encode_body(&self, out: &mut Vec<u8>) {
+        encode_bytes(&self.0, out);
+    }
+
+    fn decode_body(data: &[u8], offset: usize) -> Result<Self> {
+        Ok(Self(decode_bytes(data, offset)?.to_vec()))
+    }
+}
+
+impl SolidityAbi for String {
+    const DYNAMIC: bool = true;
+    const HEAD_LEN: usize = WORD_LEN;
+
+    fn sol_type() -> String {
+        "string".to_string()
+    }
+
+    fn encode_body(&self, out: &mut Vec<u8>) {
+        encode_bytes(self.as_bytes(), out);
+    }
+
+    fn decode_body(data: &[u8], offset: usize) -> Result<Self> {
+        String::from_utf8(decode_bytes(data, offset)?.to_vec())
+            .map_err(|_| anyhow!("the string at {} is not valid UTF-8", offset))
+    }
+}
+
+impl<T: SolidityAbi, const N: usize> SolidityAbi for [T; N] {
+    const DYNAMIC: bool = T::DYNAMIC;
+    const HEAD_LEN: usize = if T::DYNAMIC {
+        WORD_LEN
+    } else {
+        N * T::HEAD_LEN
+    };
+
+    fn sol_type() -> String {
+        format!("{}[{}]", T::sol_type(), N)
+    }
+
+    fn encode_body(&self, out: &mut Vec<u8>) {
+        let mut encoder = TupleEncoder::new(N * T::HEAD_LEN);
+        for value in self.iter() {
+            encoder.push(value);
+        }
+        encoder.finish(out);
+    }
+
+    fn decode_body(data: &[u8], offset: usize) -> Result<Self> {
+        let mut decoder = TupleDecoder::new(data, offset);
+        let values = (0..N).map(|_| decoder.read()).collect::<Result<Vec<T>>>()?;
+        Ok(values.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+}
+
+impl<T: SolidityAbi> SolidityAbi for Vec<T> {
+    const DYNAMIC: bool = true;
+    const HEAD_LEN: usize = WORD_LEN;
+
+    fn sol_type() -> String {
+        format!("{}[]", T::sol_type())
+    }
+
+    fn encode_body(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&encode_uint(self.len() as u128));
+        let mut encoder = TupleEncoder::new(self.len() * T::HEAD_LEN);
+        for value in self.iter() {
+            encoder.push(value);
+        }
+        encoder.finish(out);
+    }
+
+    fn decode_body(data: &[u8], offset: usize) -> Result<Self> {
+        let len = decode_offset(data, offset)?;
+        // Each element has a head of at least a word, which bounds the length by the data.
+        if len > data.len() / WORD_LEN {
+            return Err(anyhow!("the array at {} is too long", offset));
+        }
+        let mut decoder = TupleDecoder::new(data, offset + WORD_LEN);
+        (0..len).map(|_| decoder.read()).collect()
+    }
+}
@@ -0,0 +1,274 @@
+//! The AES-128 and AES-256 block ciphers and the AES-GCM authenticated encryption mode, with the
+//! cipher rounds accelerated by the `AES_ENCRYPT_ROUND` and `AES_ENCRYPT_LAST_ROUND` precompiles
+//! and the GHASH multiplications by the `GHASH_MUL` precompile inside the zkVM.
+//!
+//! The key schedule and the counter mode are handled here. Only encryption of blocks is provided,
+//! which is all that AES-GCM needs.
+
+#![allow(unused_imports)]
+use crate::{syscall_aes_encrypt_last_round, syscall_aes_encrypt_round, syscall_ghash_mul};
+
+/// The length of an AES block in bytes.
+pub const BLOCK_LEN: usize = 16;
+
+/// The length of an AES-GCM nonce in bytes.
+pub const NONCE_LEN: usize = 12;
+
+/// The length of an AES-GCM authentication tag in bytes.
+pub const TAG_LEN: usize = 16;
+
+#[allow(dead_code)]
+const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+/// The AES block cipher with an expanded key.
+#[derive(Debug, Clone)]
+pub struct Aes {
+    /// The round keys, one more than the number of rounds.
+    round_keys: Vec<[u8; BLOCK_LEN]>,
+}
+
+impl Aes {
+    /// Creates an AES-128 cipher from a 16-byte key.
+    pub fn new_128(key: &[u8; 16]) -> Self {
+        Self::new(key)
+    }
+
+    /// Creates an AES-256 cipher from a 32-byte key.
+    pub fn new_256(key: &[u8; 32]) -> Self {
+        Self::new(key)
+    }
+
+    /// Expands a key of 4 or 8 words into the round keys of FIPS 197, section 5.2.
+    fn new(key: &[u8]) -> Self {
+        let key_words = key.len() / 4;
+        let num_words = 4 * (key_words + 7);
+        let mut words = key
+            .chunks_exact(4)
+            .map(|chunk| <[u8; 4]>::try_from(chunk).unwrap())
+            .collect::<Vec<_>>();
+        let mut rcon = 1u8;
+        for i in key_words..num_words {
+            let mut temp = words[i - 1];
+            if i % key_words == 0 {
+                temp.rotate_left(1);
+                temp = temp.map(|b| SBOX[b as usize]);
+                temp[0] ^= rcon;
+                rcon = xtime(rcon);
+            } else if key_words > 6 && i % key_words == 4 {
+                temp = temp.map(|b| SBOX[b as usize]);
+            }
+            words.push(core::array::from_fn(|j| words[i - key_words][j] ^ temp[j]));
+        }
+        let round_keys = words
+            .chunks_exact(4)
+            .map(|chunk| chunk.concat().try_into().unwrap())
+            .collect();
+        Self { round_keys }
+    }
+
+    /// Encrypts a block in place.
+    pub fn encrypt_block(&self, block: &mut [u8; BLOCK_LEN]) {
+        for (b, k) in block.iter_mut().zip(self.round_keys[0].iter()) {
+            *b ^= k;
+        }
+        let num_rounds = self.round_keys.len() - 1;
+        for (round, round_key) in self.round_keys[1..].iter().enumerate() {
+            encrypt_round(block, round_key, round + 1 == num_rounds);
+        }
+    }
+}
+
+/// The AES-GCM authenticated encryption mode of NIST SP 800-38D, with 96-bit nonces and 128-bit
+/// tags.
+#[derive(Debug, Clone)]
+pub struct AesGcm {
+    cipher: Aes,
+    /// The hash subkey, the encryption of the zero block.
+    h: [u8; BLOCK_LEN],
+}
+
+impl AesGcm {
+    /// Creates an AES-GCM instance over a block cipher.
+    pub fn new(cipher: Aes) -> Self {
+        let mut h = [0u8; BLOCK_LEN];
+        cipher.encrypt_block(&mut h);
+        Self { cipher, h }
+    }
+
+    /// Encrypts `plaintext` and authenticates it together with `aad`, returning the ciphertext and
+    /// the tag.
+    pub fn encrypt(
+        &self,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> (Vec<u8>, [u8; TAG_LEN]) {
+        let ciphertext = self.apply_keystream(nonce, plaintext);
+        let tag = self.tag(nonce, aad, &ciphertext);
+        (ciphertext, tag)
+    }
+
+    /// Checks the tag of `ciphertext` and `aad`, and decrypts `ciphertext`. Returns `None` if the
+    /// tag does not match.
+    pub fn decrypt(
+        &self,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        ciphertext: &[u8],
+        tag: &[u8; TAG_LEN],
+    ) -> Option<Vec<u8>> {
+        let expected = self.tag(nonce, aad, ciphertext);
+        let diff = expected
+            .iter()
+            .zip(tag.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        if diff != 0 {
+            return None;
+        }
+        Some(self.apply_keystream(nonce, ciphertext))
+    }
+
+    /// The counter block `nonce || counter`.
+    fn counter_block(nonce: &[u8; NONCE_LEN], counter: u32) -> [u8; BLOCK_LEN] {
+        let mut block = [0u8; BLOCK_LEN];
+        block[..NONCE_LEN].copy_from_slice(nonce);
+        block[NONCE_LEN..].copy_from_slice(&counter.to_be_bytes());
+        block
+    }
+
+    /// XORs `input` with the keystream, which starts at counter 2.
+    fn apply_keystream(&self, nonce: &[u8; NONCE_LEN], input: &[u8]) -> Vec<u8> {
+        let mut output = Vec::with_capacity(input.len());
+        for (i, chunk) in input.chunks(BLOCK_LEN).enumerate() {
+            let mut keystream = Self::counter_block(nonce, 2 + i as u32);
+            self.cipher.encrypt_block(&mut keystream);
+            output.extend(chunk.iter().zip(keystream.iter()).map(|(a, b)| a ^ b));
+        }
+        output
+    }
+
+    /// Computes the tag, GHASH of the padded `aad`, the padded `ciphertext` and their bit lengths,
+    /// masked with the encryption of the first counter block.
+    fn tag(&self, nonce: &[u8; NONCE_LEN], aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_LEN] {
+        let mut y = [0u8; BLOCK_LEN];
+        for chunk in aad.chunks(BLOCK_LEN).chain(ciphertext.chunks(BLOCK_LEN)) {
+            for (a, b) in y.iter_mut().zip(chunk.iter()) {
+                *a ^= b;
+            }
+            ghash_mul(&mut y, &self.h);
+        }
+        let mut lengths = [0u8; BLOCK_LEN];
+        lengths[..8].copy_from_slice(&(8 * aad.len() as u64).to_be_bytes());
+        lengths[8..].copy_from_slice(&(8 * ciphertext.len() as u64).to_be_bytes());
+        for (a, b) in y.iter_mut().zip(lengths.iter()) {
+            *a ^= b;
+        }
+        ghash_mul(&mut y, &self.h);
+
+        let mut mask = Self::counter_block(nonce, 1);
+        self.cipher.encrypt_block(&mut mask);
+        core::array::from_fn(|i| y[i] ^ mask[i])
+    }
+}
+
+/// Applies one AES round with `round_key` to `block`, skipping MixColumns in the last round.
+fn encrypt_round(block: &mut [u8; BLOCK_LEN], round_key: &[u8; BLOCK_LEN], is_last_round: bool) {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            let mut state = bytes_to_words(block);
+            let round_key = bytes_to_words(round_key);
+            unsafe {
+                if is_last_round {
+                    syscall_aes_encrypt_last_round(state.as_mut_ptr(), round_key.as_ptr());
+                } else {
+                    syscall_aes_encrypt_round(state.as_mut_ptr(), round_key.as_ptr());
+                }
+            }
+            *block = words_to_bytes(&state);
+        } else {
+            // SubBytes and ShiftRows.
+            let shifted: [u8; BLOCK_LEN] = core::array::from_fn(|i| {
+                let (column, row) = (i / 4, i % 4);
+                SBOX[block[4 * ((column + row) % 4) + row] as usize]
+            });
+            let mut state = shifted;
+            if !is_last_round {
+                for column in 0..4 {
+                    let a: [u8; 4] = core::array::from_fn(|row| shifted[4 * column + row]);
+                    for row in 0..4 {
+                        state[4 * column + row] = xtime(a[row])
+                            ^ xtime(a[(row + 1) % 4])
+                            ^ a[(row + 1) % 4]
+                            ^ a[(row + 2) % 4]
+                            ^ a[(row + 3) % 4];
+                    }
+                }
+            }
+            for (i, b) in block.iter_mut().enumerate() {
+                *b = state[i] ^ round_key[i];
+            }
+        }
+    }
+}
+
+/// Multiplies `x` by `h` in place in the GHASH field.
+fn ghash_mul(x: &mut [u8; BLOCK_LEN], h: &[u8; BLOCK_LEN]) {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            let mut state = bytes_to_words(x);
+            let h = bytes_to_words(h);
+            unsafe {
+                syscall_ghash_mul(state.as_mut_ptr(), h.as_ptr());
+            }
+            *x = words_to_bytes(&state);
+        } else {
+            // The bits of a block are the coefficients of a polynomial, from x^0 at the most
+            // significant bit of the first byte.
+            let (a, mut v) = (u128::from_be_bytes(*x), u128::from_be_bytes(*h));
+            let mut z = 0u128;
+            for i in 0..128 {
+                if (a >> (127 - i)) & 1 == 1 {
+                    z ^= v;
+                }
+                v = if v & 1 == 1 {
+                    (v >> 1) ^ (0xe1 << 120)
+                } else {
+                    v >> 1
+                };
+            }
+            *x = z.to_be_bytes();
+        }
+    }
+}
+
+/// Multiplies by `x` in the AES field.
+const fn xtime(b: u8) -> u8 {
+    (b << 1) ^ if b & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+#[allow(dead_code)]
+fn bytes_to_words(block: &[u8; BLOCK_LEN]) -> [u32; 4] {
+    core::array::from_fn(|i| u32::from_le_bytes(block[4 * i..4 * i + 4].try_into().unwrap()))
+}
+
+#[allow(dead_code)]
+fn words_to_bytes(words: &[u32; 4]) -> [u8; BLOCK_LEN] {
+    core::array::from_fn(|i| words[i / 4].to_le_bytes()[i % 4])
+}
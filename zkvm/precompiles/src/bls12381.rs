@@ -1,6 +1,9 @@
 #![allow(unused_imports)]
 use crate::utils::CurveOperations;
-use crate::{syscall_bls12381_add, syscall_bls12381_decompress, syscall_bls12381_double};
+use crate::{
+    syscall_bls12381_add, syscall_bls12381_decompress, syscall_bls12381_double,
+    syscall_bls12381_fp_add, syscall_bls12381_fp_mul, syscall_bls12381_fp_sub,
+};
 
 use amcl::bls381::bls381::utils::deserialize_g1;
 use anyhow::Result;
@@ -10,6 +13,9 @@ pub struct Bls12381;
 
 const NUM_WORDS: usize = 24;
 
+/// The number of words of an element of the base field, in little-endian order.
+pub const FP_NUM_WORDS: usize = 12;
+
 impl CurveOperations<NUM_WORDS> for Bls12381 {
     // The generator has been taken from py_ecc python library by Ethereum Foundation.
     // https://github.com/ethereum/py_ecc/blob/7b9e1b3/py_ecc/bls12_381/bls12_381_curve.py#L38-L45
@@ -59,3 +65,27 @@ pub fn decompress_pubkey(compressed_key: &[u8; 48]) -> Result<[u8; 96]> {
         }
     }
 }
+
+/// Computes `x = x + y` in the base field. Both inputs must be reduced modulo the field modulus.
+///
+/// Together with [fp_sub] and [fp_mul], this is the only arithmetic of BLS12-381 besides G1
+/// addition and doubling which has a precompile. There are no precompiles for G2 or the pairing.
+pub fn fp_add(x: &mut [u32; FP_NUM_WORDS], y: &[u32; FP_NUM_WORDS]) {
+    unsafe {
+        syscall_bls12381_fp_add(x.as_mut_ptr(), y.as_ptr());
+    }
+}
+
+/// Computes `x = x - y` in the base field. Both inputs must be reduced modulo the field modulus.
+pub fn fp_sub(x: &mut [u32; FP_NUM_WORDS], y: &[u32; FP_NUM_WORDS]) {
+    unsafe {
+        syscall_bls12381_fp_sub(x.as_mut_ptr(), y.as_ptr());
+    }
+}
+
+/// Computes `x = x * y` in the base field. Both inputs must be reduced modulo the field modulus.
+pub fn fp_mul(x: &mut [u32; FP_NUM_WORDS], y: &[u32; FP_NUM_WORDS]) {
+    unsafe {
+        syscall_bls12381_fp_mul(x.as_mut_ptr(), y.as_ptr());
+    }
+}
@@ -0,0 +1,586 @@
+//! The optimal ate pairing of BLS12-381, for checking that a product of pairings is one, as in the
+//! verification of KZG proofs and BLS signatures.
+//!
+//! Inside the zkVM, every operation of the base field is a `BLS12381_FP_ADD`, `BLS12381_FP_SUB` or
+//! `BLS12381_FP_MUL` precompile, and the extension fields, the Miller loop and the final
+//! exponentiation are built on top of them. Outside of the zkVM, the base field is computed in
+//! software.
+//!
+//! The tower is `Fp2 = Fp[u] / (u^2 + 1)`, `Fp6 = Fp2[v] / (v^3 - (u + 1))` and
+//! `Fp12 = Fp6[w] / (w^2 - v)`, and G2 is on the twist `y^2 = x^3 + 4(u + 1)`.
+
+#![allow(unused_imports)]
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::bls12381::{fp_add, fp_mul, fp_sub, FP_NUM_WORDS};
+
+/// The modulus of the base field, in little-endian words.
+const MODULUS: [u32; FP_NUM_WORDS] = [
+    4294945451, 3120496639, 2975072255, 514588670, 4138792484, 1731252896, 4085584575, 1685539716,
+    1129032919, 1260103606, 964683418, 436277738,
+];
+
+/// The absolute value of the parameter `x = -0xd201000000010000` of the curve, which is the length
+/// of the Miller loop.
+const X: u64 = 0xd201000000010000;
+
+/// The constant `(u + 1)^((p - 1) / 6)` of the Frobenius map, in little-endian words.
+const FROBENIUS_COEFF: [[u32; FP_NUM_WORDS]; 2] = [
+    [
+        2451791800, 2366076397, 1676116285, 4135494973, 2226829764, 2065974231, 1019043663,
+        265683965, 539757855, 3258039988, 45811303, 419746751,
+    ],
+    [
+        1843153651, 754420242, 1298955970, 674060993, 1911962719, 3960245961, 3066540911,
+        1419855751, 589275064, 2297030914, 918872114, 16530987,
+    ],
+];
+
+/// An element of the base field, in little-endian words, reduced modulo the field modulus.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Fp([u32; FP_NUM_WORDS]);
+
+impl Fp {
+    pub const ZERO: Self = Self([0; FP_NUM_WORDS]);
+
+    pub const ONE: Self = {
+        let mut words = [0; FP_NUM_WORDS];
+        words[0] = 1;
+        Self(words)
+    };
+
+    /// Decodes an element from its 48-byte big-endian encoding. Returns `None` if it is not
+    /// reduced modulo the field modulus.
+    pub fn from_be_bytes(bytes: &[u8; 48]) -> Option<Self> {
+        let mut words = [0u32; FP_NUM_WORDS];
+        for (word, chunk) in words.iter_mut().zip(bytes.rchunks_exact(4)) {
+            *word = u32::from_be_bytes(chunk.try_into().unwrap());
+        }
+        words
+            .iter()
+            .rev()
+            .lt(MODULUS.iter().rev())
+            .then_some(Self(words))
+    }
+
+    fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// Computes the inverse as `self^(p - 2)`, which is zero for zero.
+    fn invert(&self) -> Self {
+        let mut exponent = MODULUS;
+        exponent[0] -= 2;
+        let mut result = Self::ONE;
+        for word in exponent.iter().rev() {
+            for i in (0..32).rev() {
+                result = result.square();
+                if (word >> i) & 1 == 1 {
+                    result = result * *self;
+                }
+            }
+        }
+        result
+    }
+}
+
+impl Add for Fp {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        cfg_if::cfg_if! {
+            if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+                let mut result = self;
+                fp_add(&mut result.0, &other.0);
+                result
+            } else {
+                software::add(&self, &other)
+            }
+        }
+    }
+}
+
+impl Sub for Fp {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        cfg_if::cfg_if! {
+            if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+                let mut result = self;
+                fp_sub(&mut result.0, &other.0);
+                result
+            } else {
+                software::sub(&self, &other)
+            }
+        }
+    }
+}
+
+impl Mul for Fp {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        cfg_if::cfg_if! {
+            if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+                let mut result = self;
+                fp_mul(&mut result.0, &other.0);
+                result
+            } else {
+                software::mul(&self, &other)
+            }
+        }
+    }
+}
+
+impl Neg for Fp {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+/// An element `c0 + c1 * u` of the quadratic extension.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Fp2 {
+    pub c0: Fp,
+    pub c1: Fp,
+}
+
+impl Fp2 {
+    pub const ZERO: Self = Self {
+        c0: Fp::ZERO,
+        c1: Fp::ZERO,
+    };
+
+    pub const ONE: Self = Self {
+        c0: Fp::ONE,
+        c1: Fp::ZERO,
+    };
+
+    pub const fn new(c0: Fp, c1: Fp) -> Self {
+        Self { c0, c1 }
+    }
+
+    fn from_fp(c0: Fp) -> Self {
+        Self::new(c0, Fp::ZERO)
+    }
+
+    /// Multiplies by the small integer `n`.
+    fn scale(&self, n: u32) -> Self {
+        let mut words = [0; FP_NUM_WORDS];
+        words[0] = n;
+        let n = Fp(words);
+        Self::new(self.c0 * n, self.c1 * n)
+    }
+
+    fn square(&self) -> Self {
+        let sum = self.c0 + self.c1;
+        let diff = self.c0 - self.c1;
+        let product = self.c0 * self.c1;
+        Self::new(sum * diff, product + product)
+    }
+
+    /// Multiplies by the non-residue `u + 1`.
+    fn mul_by_nonresidue(&self) -> Self {
+        Self::new(self.c0 - self.c1, self.c0 + self.c1)
+    }
+
+    fn conjugate(&self) -> Self {
+        Self::new(self.c0, -self.c1)
+    }
+
+    fn invert(&self) -> Self {
+        let norm_inverse = (self.c0.square() + self.c1.square()).invert();
+        Self::new(self.c0 * norm_inverse, -(self.c1 * norm_inverse))
+    }
+}
+
+impl Add for Fp2 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.c0 + other.c0, self.c1 + other.c1)
+    }
+}
+
+impl Sub for Fp2 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.c0 - other.c0, self.c1 - other.c1)
+    }
+}
+
+impl Mul for Fp2 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let t0 = self.c0 * other.c0;
+        let t1 = self.c1 * other.c1;
+        let cross = (self.c0 + self.c1) * (other.c0 + other.c1);
+        Self::new(t0 - t1, cross - t0 - t1)
+    }
+}
+
+impl Neg for Fp2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.c0, -self.c1)
+    }
+}
+
+/// An element `c0 + c1 * v + c2 * v^2` of the sextic extension.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Fp6 {
+    c0: Fp2,
+    c1: Fp2,
+    c2: Fp2,
+}
+
+impl Fp6 {
+    const ZERO: Self = Self::new(Fp2::ZERO, Fp2::ZERO, Fp2::ZERO);
+
+    const ONE: Self = Self::new(Fp2::ONE, Fp2::ZERO, Fp2::ZERO);
+
+    const fn new(c0: Fp2, c1: Fp2, c2: Fp2) -> Self {
+        Self { c0, c1, c2 }
+    }
+
+    /// Multiplies by `v`.
+    fn mul_by_v(&self) -> Self {
+        Self::new(self.c2.mul_by_nonresidue(), self.c0, self.c1)
+    }
+
+    fn invert(&self) -> Self {
+        let t0 = self.c0.square() - (self.c1 * self.c2).mul_by_nonresidue();
+        let t1 = self.c2.square().mul_by_nonresidue() - self.c0 * self.c1;
+        let t2 = self.c1.square() - self.c0 * self.c2;
+        let norm = self.c0 * t0 + (self.c2 * t1 + self.c1 * t2).mul_by_nonresidue();
+        let norm_inverse = norm.invert();
+        Self::new(t0 * norm_inverse, t1 * norm_inverse, t2 * norm_inverse)
+    }
+}
+
+impl Add for Fp6 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.c0 + other.c0, self.c1 + other.c1, self.c2 + other.c2)
+    }
+}
+
+impl Sub for Fp6 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.c0 - other.c0, self.c1 - other.c1, self.c2 - other.c2)
+    }
+}
+
+impl Mul for Fp6 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let t0 = self.c0 * other.c0;
+        let t1 = self.c1 * other.c1;
+        let t2 = self.c2 * other.c2;
+        let c0 = t0 + ((self.c1 + self.c2) * (other.c1 + other.c2) - t1 - t2).mul_by_nonresidue();
+        let c1 = (self.c0 + self.c1) * (other.c0 + other.c1) - t0 - t1 + t2.mul_by_nonresidue();
+        let c2 = (self.c0 + self.c2) * (other.c0 + other.c2) - t0 - t2 + t1;
+        Self::new(c0, c1, c2)
+    }
+}
+
+impl Neg for Fp6 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.c0, -self.c1, -self.c2)
+    }
+}
+
+/// An element `c0 + c1 * w` of the degree 12 extension, which is the target group of the pairing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Fp12 {
+    c0: Fp6,
+    c1: Fp6,
+}
+
+impl Fp12 {
+    const ONE: Self = Self::new(Fp6::ONE, Fp6::ZERO);
+
+    const fn new(c0: Fp6, c1: Fp6) -> Self {
+        Self { c0, c1 }
+    }
+
+    fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// The conjugate, which is the inverse of an element of the cyclotomic subgroup.
+    fn conjugate(&self) -> Self {
+        Self::new(self.c0, -self.c1)
+    }
+
+    fn invert(&self) -> Self {
+        let norm_inverse = (self.c0 * self.c0 - (self.c1 * self.c1).mul_by_v()).invert();
+        Self::new(self.c0 * norm_inverse, -(self.c1 * norm_inverse))
+    }
+
+    /// Raises to the power `p`. The coefficient of `w^i` is conjugated and multiplied by
+    /// `(u + 1)^(i * (p - 1) / 6)`.
+    fn frobenius_map(&self) -> Self {
+        let gamma1 = Fp2::new(Fp(FROBENIUS_COEFF[0]), Fp(FROBENIUS_COEFF[1]));
+        let gamma2 = gamma1.square();
+        let gamma3 = gamma2 * gamma1;
+        let gamma4 = gamma2.square();
+        let gamma5 = gamma4 * gamma1;
+        Self::new(
+            Fp6::new(
+                self.c0.c0.conjugate(),
+                self.c0.c1.conjugate() * gamma2,
+                self.c0.c2.conjugate() * gamma4,
+            ),
+            Fp6::new(
+                self.c1.c0.conjugate() * gamma1,
+                self.c1.c1.conjugate() * gamma3,
+                self.c1.c2.conjugate() * gamma5,
+            ),
+        )
+    }
+
+    /// Raises an element of the cyclotomic subgroup to the power `x`, which is negative.
+    fn pow_by_x(&self) -> Self {
+        let mut result = *self;
+        for i in (0..X.ilog2()).rev() {
+            result = result.square();
+            if (X >> i) & 1 == 1 {
+                result = result * *self;
+            }
+        }
+        result.conjugate()
+    }
+}
+
+impl Mul for Fp12 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let t0 = self.c0 * other.c0;
+        let t1 = self.c1 * other.c1;
+        let c1 = (self.c0 + self.c1) * (other.c0 + other.c1) - t0 - t1;
+        Self::new(t0 + t1.mul_by_v(), c1)
+    }
+}
+
+/// A point of G1 which is not the point at infinity.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct G1Affine {
+    pub x: Fp,
+    pub y: Fp,
+}
+
+impl Neg for G1Affine {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            x: self.x,
+            y: -self.y,
+        }
+    }
+}
+
+/// A point of G2 on the twist which is not the point at infinity.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct G2Affine {
+    pub x: Fp2,
+    pub y: Fp2,
+}
+
+/// A point of G2 in homogeneous projective coordinates.
+#[derive(Copy, Clone, Debug)]
+struct G2Projective {
+    x: Fp2,
+    y: Fp2,
+    z: Fp2,
+}
+
+impl G2Projective {
+    /// The constant `3 * b` of the twist, which is `12 * (u + 1)`.
+    fn b3() -> Fp2 {
+        Fp2::new(Fp::ONE, Fp::ONE).scale(12)
+    }
+
+    /// Doubles the point, with the complete formulas of Renes, Costello and Batina for `a = 0`.
+    fn double(&self) -> Self {
+        let b3 = Self::b3();
+        let t0 = self.y.square();
+        let z3 = t0.scale(8);
+        let t1 = self.y * self.z;
+        let t2 = b3 * self.z.square();
+        let x3 = t2 * z3;
+        let y3 = t0 + t2;
+        let z3 = t1 * z3;
+        let t0 = t0 - t2.scale(3);
+        let y3 = x3 + t0 * y3;
+        let x3 = (t0 * (self.x * self.y)).scale(2);
+        Self {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    /// Adds an affine point, with the complete formulas of Renes, Costello and Batina for `a = 0`.
+    fn add_affine(&self, other: &G2Affine) -> Self {
+        let b3 = Self::b3();
+        let t0 = self.x * other.x;
+        let t1 = self.y * other.y;
+        let t2 = self.z;
+        let t3 = (self.x + self.y) * (other.x + other.y) - (t0 + t1);
+        let t4 = (self.y + self.z) * (other.y + Fp2::ONE) - (t1 + t2);
+        let y3 = (self.x + self.z) * (other.x + Fp2::ONE) - (t0 + t2);
+        let t0 = t0.scale(3);
+        let t2 = b3 * t2;
+        let z3 = t1 + t2;
+        let t1 = t1 - t2;
+        let y3 = b3 * y3;
+        let x3 = t3 * t1 - t4 * y3;
+        let y3 = t1 * z3 + y3 * t0;
+        let z3 = z3 * t4 + t0 * t3;
+        Self {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    /// The tangent line at the point, evaluated at `p` and scaled by `2 * y * z^2`.
+    fn tangent_line(&self, p: &G1Affine) -> Fp12 {
+        let xx = self.x.square();
+        let c0 = (xx * self.x).scale(3) - (self.y.square() * self.z).scale(2);
+        let c1 = -((xx * self.z).scale(3) * Fp2::from_fp(p.x));
+        let c2 = (self.y * self.z.square()).scale(2) * Fp2::from_fp(p.y);
+        line(c0, c1, c2)
+    }
+
+    /// The line through the point and `q`, evaluated at `p` and scaled by `x_q * z - x`.
+    fn chord_line(&self, q: &G2Affine, p: &G1Affine) -> Fp12 {
+        let numerator = q.y * self.z - self.y;
+        let denominator = q.x * self.z - self.x;
+        let c0 = numerator * q.x - denominator * q.y;
+        let c1 = -(numerator * Fp2::from_fp(p.x));
+        let c2 = denominator * Fp2::from_fp(p.y);
+        line(c0, c1, c2)
+    }
+}
+
+impl From<&G2Affine> for G2Projective {
+    fn from(point: &G2Affine) -> Self {
+        Self {
+            x: point.x,
+            y: point.y,
+            z: Fp2::ONE,
+        }
+    }
+}
+
+/// The line `c0 + c1 * w^2 + c2 * w^3`. A line through points of the twist, evaluated at a point
+/// of G1, is mapped to this form by the untwisting and a multiplication by `w^3`, which is in a
+/// subfield and removed by the final exponentiation.
+fn line(c0: Fp2, c1: Fp2, c2: Fp2) -> Fp12 {
+    Fp12::new(
+        Fp6::new(c0, c1, Fp2::ZERO),
+        Fp6::new(Fp2::ZERO, c2, Fp2::ZERO),
+    )
+}
+
+/// The product of the Miller loops of the pairs, sharing the squarings of the accumulator.
+fn multi_miller_loop(pairs: &[(G1Affine, G2Affine)]) -> Fp12 {
+    let mut acc = Fp12::ONE;
+    let mut points = pairs
+        .iter()
+        .map(|(_, q)| G2Projective::from(q))
+        .collect::<Vec<_>>();
+    for i in (0..X.ilog2()).rev() {
+        acc = acc.square();
+        for ((p, _), t) in pairs.iter().zip(points.iter_mut()) {
+            acc = acc * t.tangent_line(p);
+            *t = t.double();
+        }
+        if (X >> i) & 1 == 1 {
+            for ((p, q), t) in pairs.iter().zip(points.iter_mut()) {
+                acc = acc * t.chord_line(q, p);
+                *t = t.add_affine(q);
+            }
+        }
+    }
+    // The parameter `x` is negative.
+    acc.conjugate()
+}
+
+/// Raises the output of the Miller loop to the power `3 * (p^12 - 1) / r`.
+///
+/// The hard part uses the decomposition `3 * (p^4 - p^2 + 1) / r = l0 + l1 * p + l2 * p^2 +
+/// l3 * p^3`, with `l3 = (x - 1)^2`, `l2 = l3 * x`, `l1 = l2 * x - l3` and `l0 = l1 * x + 3`. The
+/// result is the cube of the pairing, which is one if and only if the pairing is.
+fn final_exponentiation(f: &Fp12) -> Fp12 {
+    // The easy part, `(p^6 - 1) * (p^2 + 1)`.
+    let f = f.conjugate() * f.invert();
+    let f = f.frobenius_map().frobenius_map() * f;
+
+    let a = f.pow_by_x() * f.conjugate();
+    let t3 = a.pow_by_x() * a.conjugate();
+    let t2 = t3.pow_by_x();
+    let t1 = t2.pow_by_x() * t3.conjugate();
+    let t0 = t1.pow_by_x() * f.square() * f;
+    t0 * t1.frobenius_map()
+        * t2.frobenius_map().frobenius_map()
+        * t3.frobenius_map().frobenius_map().frobenius_map()
+}
+
+/// Whether the product of the pairings `e(p_i, q_i)` is one.
+///
+/// The points must be in the prime order subgroups of G1 and G2. Pairs with the point at infinity,
+/// whose pairing is one, are left out by the caller.
+pub fn pairing_product_is_one(pairs: &[(G1Affine, G2Affine)]) -> bool {
+    final_exponentiation(&multi_miller_loop(pairs)) == Fp12::ONE
+}
+
+/// The arithmetic of the base field outside of the zkVM.
+#[allow(dead_code)]
+mod software {
+    use num::BigUint;
+
+    use super::{Fp, FP_NUM_WORDS, MODULUS};
+
+    fn to_biguint(x: &Fp) -> BigUint {
+        BigUint::from_slice(&x.0)
+    }
+
+    fn from_biguint(x: &BigUint) -> Fp {
+        let mut words = [0; FP_NUM_WORDS];
+        for (word, digit) in words.iter_mut().zip(x.to_u32_digits()) {
+            *word = digit;
+        }
+        Fp(words)
+    }
+
+    pub(super) fn add(x: &Fp, y: &Fp) -> Fp {
+        let p = BigUint::from_slice(&MODULUS);
+        from_biguint(&((to_biguint(x) + to_biguint(y)) % p))
+    }
+
+    pub(super) fn sub(x: &Fp, y: &Fp) -> Fp {
+        let p = BigUint::from_slice(&MODULUS);
+        from_biguint(&((to_biguint(x) + &p - to_biguint(y)) % p))
+    }
+
+    pub(super) fn mul(x: &Fp, y: &Fp) -> Fp {
+        let p = BigUint::from_slice(&MODULUS);
+        from_biguint(&((to_biguint(x) * to_biguint(y)) % p))
+    }
+}
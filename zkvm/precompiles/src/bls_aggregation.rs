@@ -0,0 +1,200 @@
+//! Aggregation of BLS12-381 public keys and signatures, as used by Ethereum sync committees.
+//!
+//! Public keys are G1 points and signatures are G2 points. Inside the zkVM, the public keys are
+//! decompressed with the `BLS12381_DECOMPRESS` precompile and summed with the `BLS12381_ADD` and
+//! `BLS12381_DOUBLE` precompiles, which is the bulk of the work of verifying a sync committee
+//! signature. The pairing check of a signature runs on the base field precompiles, see
+//! [crate::bls12381_pairing]. Signatures are decoded, aggregated and hashed to G2 in software.
+
+#![allow(unused_imports)]
+use amcl::bls381::big::Big;
+use amcl::bls381::bls381::proof_of_possession::DST_G2;
+use amcl::bls381::bls381::utils::{
+    deserialize_g1, deserialize_g2, hash_to_curve_g2, serialize_g2, subgroup_check_g2,
+};
+use amcl::bls381::ecp::ECP;
+use amcl::bls381::ecp2::ECP2;
+use anyhow::{anyhow, Result};
+
+use crate::bls12381::{decompress_pubkey, Bls12381};
+use crate::bls12381_pairing::pairing_product_is_one;
+use crate::kzg::{pairing_g1, pairing_g2};
+use crate::utils::AffinePoint;
+
+/// The length of a compressed public key.
+pub const PUBKEY_LEN: usize = 48;
+
+/// The length of a decompressed public key, which is `x || y` in big-endian order.
+pub const DECOMPRESSED_PUBKEY_LEN: usize = 96;
+
+/// The length of a compressed signature.
+pub const SIGNATURE_LEN: usize = 96;
+
+/// The number of members of an Ethereum sync committee.
+pub const SYNC_COMMITTEE_SIZE: usize = 512;
+
+/// A sum of G1 points, which may be the point at infinity.
+#[derive(Debug, Clone, Default)]
+pub struct G1Aggregate {
+    sum: Option<[u8; DECOMPRESSED_PUBKEY_LEN]>,
+    count: usize,
+}
+
+impl G1Aggregate {
+    /// Creates an empty aggregate.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of points added to the aggregate.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Adds a decompressed point to the aggregate. The same point may be added several times.
+    pub fn add(&mut self, point: &[u8; DECOMPRESSED_PUBKEY_LEN]) {
+        self.count += 1;
+        self.sum = match self.sum.take() {
+            None => Some(*point),
+            Some(sum) => add_g1(&sum, point),
+        };
+    }
+
+    /// Adds a compressed point to the aggregate.
+    pub fn add_compressed(&mut self, point: &[u8; PUBKEY_LEN]) -> Result<()> {
+        self.add(&decompress_pubkey(point)?);
+        Ok(())
+    }
+
+    /// Returns the decompressed sum, or `None` if it is the point at infinity.
+    pub fn finalize(self) -> Option<[u8; DECOMPRESSED_PUBKEY_LEN]> {
+        self.sum
+    }
+}
+
+/// Aggregates the public keys of the participants of a sync committee, where bit `i` of
+/// `participation_bits` is bit `i % 8` of byte `i / 8`, as in an SSZ bitvector.
+///
+/// Returns the decompressed aggregate public key and the number of participants.
+pub fn aggregate_sync_committee_pubkeys(
+    pubkeys: &[[u8; PUBKEY_LEN]],
+    participation_bits: &[u8],
+) -> Result<([u8; DECOMPRESSED_PUBKEY_LEN], usize)> {
+    if participation_bits.len() * 8 < pubkeys.len() {
+        return Err(anyhow!("the participation bits are too short"));
+    }
+    let mut aggregate = G1Aggregate::new();
+    for (i, pubkey) in pubkeys.iter().enumerate() {
+        if (participation_bits[i / 8] >> (i % 8)) & 1 == 1 {
+            aggregate.add_compressed(pubkey)?;
+        }
+    }
+    let count = aggregate.count();
+    let pubkey = aggregate
+        .finalize()
+        .ok_or_else(|| anyhow!("the aggregate public key is the point at infinity"))?;
+    Ok((pubkey, count))
+}
+
+/// Aggregates compressed G2 signatures into a compressed signature.
+pub fn aggregate_signatures(signatures: &[[u8; SIGNATURE_LEN]]) -> Result<[u8; SIGNATURE_LEN]> {
+    let mut sum = ECP2::new();
+    for signature in signatures {
+        let point = deserialize_g2(signature).map_err(|_| anyhow!("invalid signature"))?;
+        sum.add(&point);
+    }
+    Ok(serialize_g2(&sum))
+}
+
+/// Verifies a signature of `message` under a decompressed public key, such as the aggregate
+/// public key of a sync committee, with the proof of possession scheme used by Ethereum.
+///
+/// The signature is valid if `e(pubkey, H(message)) * e(-[1]_1, signature) = 1`.
+pub fn verify_signature(
+    pubkey: &[u8; DECOMPRESSED_PUBKEY_LEN],
+    message: &[u8],
+    signature: &[u8; SIGNATURE_LEN],
+) -> Result<bool> {
+    let pubkey = ecp_point(pubkey);
+    if pubkey.is_infinity() {
+        return Err(anyhow!("invalid public key"));
+    }
+    let signature = deserialize_g2(signature).map_err(|_| anyhow!("invalid signature"))?;
+    if signature.is_infinity() || !subgroup_check_g2(&signature) {
+        return Ok(false);
+    }
+    let hash = hash_to_curve_g2(message, DST_G2);
+    let mut generator = ECP::generator();
+    generator.neg();
+
+    let pairs = [
+        (pairing_g1(&pubkey), pairing_g2(&hash)),
+        (pairing_g1(&generator), pairing_g2(&signature)),
+    ];
+    Ok(pairing_product_is_one(&pairs))
+}
+
+/// Verifies the signature of a sync committee over `signing_root`, given the public keys of the
+/// committee and the participation bits of the signers.
+pub fn verify_sync_committee_signature(
+    pubkeys: &[[u8; PUBKEY_LEN]],
+    participation_bits: &[u8],
+    signing_root: &[u8; 32],
+    signature: &[u8; SIGNATURE_LEN],
+) -> Result<bool> {
+    let (pubkey, _) = aggregate_sync_committee_pubkeys(pubkeys, participation_bits)?;
+    verify_signature(&pubkey, signing_root, signature)
+}
+
+/// Adds two decompressed G1 points. Returns `None` if the sum is the point at infinity.
+fn add_g1(
+    p: &[u8; DECOMPRESSED_PUBKEY_LEN],
+    q: &[u8; DECOMPRESSED_PUBKEY_LEN],
+) -> Option<[u8; DECOMPRESSED_PUBKEY_LEN]> {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            // The add precompile needs points with distinct `x` coordinates.
+            let mut sum = affine_point(p);
+            if p[..48] != q[..48] {
+                sum.add_assign(&affine_point(q));
+            } else if p[48..] == q[48..] {
+                sum.double();
+            } else {
+                return None;
+            }
+            let mut bytes = sum.to_le_bytes();
+            bytes[..48].reverse();
+            bytes[48..].reverse();
+            Some(bytes.try_into().unwrap())
+        } else {
+            let mut sum = ecp_point(p);
+            sum.add(&ecp_point(q));
+            if sum.is_infinity() {
+                return None;
+            }
+            let mut bytes = [0u8; DECOMPRESSED_PUBKEY_LEN];
+            sum.getx().to_bytes(&mut bytes[..48]);
+            sum.gety().to_bytes(&mut bytes[48..]);
+            Some(bytes)
+        }
+    }
+}
+
+/// Converts a decompressed point to the little-endian words of its coordinates.
+#[allow(dead_code)]
+fn affine_point(point: &[u8; DECOMPRESSED_PUBKEY_LEN]) -> AffinePoint<Bls12381, 24> {
+    let mut x = point[..48].to_vec();
+    let mut y = point[48..].to_vec();
+    x.reverse();
+    y.reverse();
+    AffinePoint::from(&x, &y)
+}
+
+/// Converts a decompressed point to a point of the software implementation.
+#[allow(dead_code)]
+fn ecp_point(point: &[u8; DECOMPRESSED_PUBKEY_LEN]) -> ECP {
+    ECP::new_bigs(
+        &Big::from_bytes(&point[..48]),
+        &Big::from_bytes(&point[48..]),
+    )
+}
@@ -0,0 +1,237 @@
+//! The ChaCha20 stream cipher, the Poly1305 authenticator and the ChaCha20-Poly1305 AEAD of
+//! RFC 8439, as used by Noise, WireGuard and TLS 1.3.
+//!
+//! Inside the zkVM, the rounds of the block function are accelerated by the `CHACHA20_PERMUTE`
+//! precompile, and the multiplications modulo `2^130 - 5` of Poly1305 by the `UINT256_MUL`
+//! precompile.
+
+#![allow(unused_imports)]
+use num::{BigUint, One};
+
+use crate::utils::{bytes_to_words_le, words_to_bytes_le};
+use crate::{syscall_chacha20_permute, syscall_uint256_mulmod};
+
+/// The length of a ChaCha20 key in bytes.
+pub const KEY_LEN: usize = 32;
+
+/// The length of a ChaCha20 nonce in bytes.
+pub const NONCE_LEN: usize = 12;
+
+/// The length of a Poly1305 tag in bytes.
+pub const TAG_LEN: usize = 16;
+
+/// The length of a ChaCha20 block in bytes.
+pub const BLOCK_LEN: usize = 64;
+
+/// The number of words of the ChaCha20 state.
+const STATE_WORDS: usize = 16;
+
+/// The number of words of the operands of the `UINT256_MUL` precompile.
+const UINT256_WORDS: usize = 8;
+
+/// The constant words "expand 32-byte k".
+const CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+/// The Poly1305 prime `2^130 - 5`, in little-endian words.
+const POLY1305_PRIME: [u32; UINT256_WORDS] =
+    [0xfffffffb, 0xffffffff, 0xffffffff, 0xffffffff, 0x3, 0, 0, 0];
+
+/// Computes the ChaCha20 block function for a key, a block counter and a nonce.
+pub fn chacha20_block(
+    key: &[u8; KEY_LEN],
+    counter: u32,
+    nonce: &[u8; NONCE_LEN],
+) -> [u8; BLOCK_LEN] {
+    let mut input = [0u32; STATE_WORDS];
+    input[..4].copy_from_slice(&CONSTANTS);
+    input[4..12].copy_from_slice(&bytes_to_words_le(key));
+    input[12] = counter;
+    input[13..].copy_from_slice(&bytes_to_words_le(nonce));
+
+    let mut state = input;
+    permute(&mut state);
+    for (word, input) in state.iter_mut().zip(input.iter()) {
+        *word = word.wrapping_add(*input);
+    }
+    words_to_bytes_le(&state).try_into().unwrap()
+}
+
+/// Encrypts or decrypts `data` in place with the ChaCha20 keystream, starting at block `counter`.
+pub fn chacha20_xor(key: &[u8; KEY_LEN], counter: u32, nonce: &[u8; NONCE_LEN], data: &mut [u8]) {
+    for (i, chunk) in data.chunks_mut(BLOCK_LEN).enumerate() {
+        let keystream = chacha20_block(key, counter.wrapping_add(i as u32), nonce);
+        for (b, k) in chunk.iter_mut().zip(keystream.iter()) {
+            *b ^= k;
+        }
+    }
+}
+
+/// Computes the Poly1305 tag of `message` under a one-time key.
+pub fn poly1305(key: &[u8; KEY_LEN], message: &[u8]) -> [u8; TAG_LEN] {
+    // Clamp r.
+    let mut r = [0u8; TAG_LEN];
+    r.copy_from_slice(&key[..TAG_LEN]);
+    for i in [3, 7, 11, 15] {
+        r[i] &= 0x0f;
+    }
+    for i in [4, 8, 12] {
+        r[i] &= 0xfc;
+    }
+    let r = to_uint256(&r);
+
+    let mut acc = [0u32; UINT256_WORDS];
+    for chunk in message.chunks(TAG_LEN) {
+        // The block with a one byte appended, which is less than 2^129.
+        let mut block = [0u8; TAG_LEN + 1];
+        block[..chunk.len()].copy_from_slice(chunk);
+        block[chunk.len()] = 1;
+        // The accumulator is reduced, so the sum is less than 2^131 and does not overflow.
+        add_assign(&mut acc, &to_uint256(&block));
+        acc = mulmod_poly1305(&acc, &r);
+    }
+
+    // The tag is (acc + s) mod 2^128.
+    add_assign(&mut acc, &to_uint256(&key[TAG_LEN..]));
+    words_to_bytes_le(&acc)[..TAG_LEN].try_into().unwrap()
+}
+
+/// The ChaCha20-Poly1305 AEAD of RFC 8439, section 2.8.
+#[derive(Debug, Clone)]
+pub struct ChaCha20Poly1305 {
+    key: [u8; KEY_LEN],
+}
+
+impl ChaCha20Poly1305 {
+    /// Creates an AEAD instance from a 32-byte key.
+    pub fn new(key: &[u8; KEY_LEN]) -> Self {
+        Self { key: *key }
+    }
+
+    /// Encrypts `plaintext` and authenticates it together with `aad`, returning the ciphertext and
+    /// the tag.
+    pub fn encrypt(
+        &self,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> (Vec<u8>, [u8; TAG_LEN]) {
+        let mut ciphertext = plaintext.to_vec();
+        chacha20_xor(&self.key, 1, nonce, &mut ciphertext);
+        let tag = self.tag(nonce, aad, &ciphertext);
+        (ciphertext, tag)
+    }
+
+    /// Checks the tag of `ciphertext` and `aad`, and decrypts `ciphertext`. Returns `None` if the
+    /// tag does not match.
+    pub fn decrypt(
+        &self,
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        ciphertext: &[u8],
+        tag: &[u8; TAG_LEN],
+    ) -> Option<Vec<u8>> {
+        let expected = self.tag(nonce, aad, ciphertext);
+        let diff = expected
+            .iter()
+            .zip(tag.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        if diff != 0 {
+            return None;
+        }
+        let mut plaintext = ciphertext.to_vec();
+        chacha20_xor(&self.key, 1, nonce, &mut plaintext);
+        Some(plaintext)
+    }
+
+    /// Computes the Poly1305 tag of the padded `aad`, the padded `ciphertext` and their lengths,
+    /// with the one-time key of the first keystream block.
+    fn tag(&self, nonce: &[u8; NONCE_LEN], aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_LEN] {
+        let block = chacha20_block(&self.key, 0, nonce);
+        let one_time_key: [u8; KEY_LEN] = block[..KEY_LEN].try_into().unwrap();
+
+        let padding = |len: usize| (TAG_LEN - len % TAG_LEN) % TAG_LEN;
+        let mut mac_data = Vec::with_capacity(aad.len() + ciphertext.len() + 3 * TAG_LEN);
+        mac_data.extend_from_slice(aad);
+        mac_data.resize(mac_data.len() + padding(aad.len()), 0);
+        mac_data.extend_from_slice(ciphertext);
+        mac_data.resize(mac_data.len() + padding(ciphertext.len()), 0);
+        mac_data.extend_from_slice(&(aad.len() as u64).to_le_bytes());
+        mac_data.extend_from_slice(&(ciphertext.len() as u64).to_le_bytes());
+        poly1305(&one_time_key, &mac_data)
+    }
+}
+
+/// Applies the 20 rounds of the block function to `state`.
+fn permute(state: &mut [u32; STATE_WORDS]) {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            unsafe {
+                syscall_chacha20_permute(state.as_mut_ptr());
+            }
+        } else {
+            for _ in 0..10 {
+                quarter_round(state, 0, 4, 8, 12);
+                quarter_round(state, 1, 5, 9, 13);
+                quarter_round(state, 2, 6, 10, 14);
+                quarter_round(state, 3, 7, 11, 15);
+                quarter_round(state, 0, 5, 10, 15);
+                quarter_round(state, 1, 6, 11, 12);
+                quarter_round(state, 2, 7, 8, 13);
+                quarter_round(state, 3, 4, 9, 14);
+            }
+        }
+    }
+}
+
+#[allow(dead_code)]
+fn quarter_round(state: &mut [u32; STATE_WORDS], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+/// Computes `(x * y) % (2^130 - 5)`.
+fn mulmod_poly1305(x: &[u32; UINT256_WORDS], y: &[u32; UINT256_WORDS]) -> [u32; UINT256_WORDS] {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            // The syscall reads the multiplier followed by the modulus from its second argument.
+            let mut y_and_modulus = [0u32; 2 * UINT256_WORDS];
+            y_and_modulus[..UINT256_WORDS].copy_from_slice(y);
+            y_and_modulus[UINT256_WORDS..].copy_from_slice(&POLY1305_PRIME);
+            let mut result = *x;
+            unsafe {
+                syscall_uint256_mulmod(result.as_mut_ptr(), y_and_modulus.as_ptr());
+            }
+            result
+        } else {
+            let to_biguint = |x: &[u32; UINT256_WORDS]| {
+                BigUint::from_bytes_le(&words_to_bytes_le(x))
+            };
+            let result = (to_biguint(x) * to_biguint(y)) % to_biguint(&POLY1305_PRIME);
+            to_uint256(&result.to_bytes_le())
+        }
+    }
+}
+
+/// Adds `y` to `x` modulo `2^256`.
+fn add_assign(x: &mut [u32; UINT256_WORDS], y: &[u32; UINT256_WORDS]) {
+    let mut carry = false;
+    for i in 0..UINT256_WORDS {
+        let (s, c1) = x[i].overflowing_add(y[i]);
+        let (s, c2) = s.overflowing_add(carry as u32);
+        x[i] = s;
+        carry = c1 || c2;
+    }
+}
+
+/// Converts up to 32 little-endian bytes to words.
+fn to_uint256(bytes: &[u8]) -> [u32; UINT256_WORDS] {
+    let mut padded = [0u8; 32];
+    padded[..bytes.len()].copy_from_slice(bytes);
+    bytes_to_words_le(&padded).try_into().unwrap()
+}
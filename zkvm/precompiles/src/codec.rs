@@ -0,0 +1,83 @@
+//! The encodings of the values read and committed by programs.
+//!
+//! [crate::io::read] and [crate::io::commit] encode values with bincode. The `_with` variants of
+//! the IO functions, and of `SP1Stdin` and `SP1PublicValues` on the host, take the [IoCodec] as a
+//! type parameter instead, such as [Postcard] whose values are smaller and cheaper to decode in the
+//! program. The host and the program must use the same codec for each value.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// An encoding of serializable values as bytes.
+pub trait IoCodec {
+    /// Encodes `value`.
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Vec<u8>;
+
+    /// Decodes a value from the start of `bytes`, and returns it with the number of bytes it was
+    /// encoded in.
+    fn decode_prefix<T: DeserializeOwned>(bytes: &[u8]) -> (T, usize);
+
+    /// Decodes a value encoded in all of `bytes`.
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> T {
+        Self::decode_prefix(bytes).0
+    }
+}
+
+/// The bincode encoding, which is the encoding of [crate::io::read] and [crate::io::commit].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bincode;
+
+impl IoCodec for Bincode {
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Vec<u8> {
+        bincode::serialize(value).expect("serialization failed")
+    }
+
+    fn decode_prefix<T: DeserializeOwned>(bytes: &[u8]) -> (T, usize) {
+        let mut rest = bytes;
+        let value: T = bincode::deserialize_from(&mut rest).expect("deserialization failed");
+        (value, bytes.len() - rest.len())
+    }
+
+    fn decode<T: DeserializeOwned>(bytes: &[u8]) -> T {
+        bincode::deserialize(bytes).expect("deserialization failed")
+    }
+}
+
+/// The postcard encoding, whose integers are varints, so that the values are smaller and take
+/// fewer cycles to decode than with bincode.
+#[cfg(feature = "postcard")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Postcard;
+
+#[cfg(feature = "postcard")]
+impl IoCodec for Postcard {
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Vec<u8> {
+        postcard::to_allocvec(value).expect("serialization failed")
+    }
+
+    fn decode_prefix<T: DeserializeOwned>(bytes: &[u8]) -> (T, usize) {
+        let (value, rest) = postcard::take_from_bytes(bytes).expect("deserialization failed");
+        (value, bytes.len() - rest.len())
+    }
+}
+
+/// The JSON encoding, for values exchanged with tools which do not read bincode.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl IoCodec for Json {
+    fn encode<T: Serialize + ?Sized>(value: &T) -> Vec<u8> {
+        serde_json::to_vec(value).expect("serialization failed")
+    }
+
+    fn decode_prefix<T: DeserializeOwned>(bytes: &[u8]) -> (T, usize) {
+        let mut values = serde_json::Deserializer::from_slice(bytes).into_iter();
+        let value = values
+            .next()
+            .expect("deserialization failed")
+            .expect("deserialization failed");
+        (value, values.byte_offset())
+    }
+}
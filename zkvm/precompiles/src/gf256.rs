@@ -0,0 +1,117 @@
+//! Arithmetic over GF(2^8) for Reed-Solomon erasure codes.
+//!
+//! The field is GF(2^8) modulo `x^8 + x^4 + x^3 + x^2 + 1`. Inside the zkVM, multiply-accumulates
+//! of byte vectors are computed 64 bytes at a time by the `GF256_MAC` precompile, so that encoding
+//! or recovering a shard is a linear combination of the other shards at a few cycles per byte.
+//! Multiplications in GF(2^16), used by codes with more than 256 shards, run in software.
+
+#![allow(unused_imports)]
+use crate::syscall_gf256_mac;
+
+/// The reduction polynomial of GF(2^8).
+pub const GF256_POLYNOMIAL: u16 = 0x11d;
+
+/// The reduction polynomial `x^16 + x^12 + x^3 + x + 1` of GF(2^16).
+pub const GF65536_POLYNOMIAL: u32 = 0x1100b;
+
+/// The number of bytes of a multiply-accumulate of the precompile.
+pub const MAC_BLOCK_LEN: usize = 64;
+
+/// Multiplies two elements of GF(2^8).
+pub const fn gf256_mul(a: u8, b: u8) -> u8 {
+    let (mut a, mut b) = (a as u16, b);
+    let mut product = 0u16;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a <<= 1;
+        if a & 0x100 != 0 {
+            a ^= GF256_POLYNOMIAL;
+        }
+        b >>= 1;
+    }
+    product as u8
+}
+
+/// Multiplies two elements of GF(2^16).
+pub const fn gf65536_mul(a: u16, b: u16) -> u16 {
+    let (mut a, mut b) = (a as u32, b);
+    let mut product = 0u32;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a <<= 1;
+        if a & 0x10000 != 0 {
+            a ^= GF65536_POLYNOMIAL;
+        }
+        b >>= 1;
+    }
+    product as u16
+}
+
+/// Adds `coefficient * data` to `acc`, elementwise in GF(2^8).
+///
+/// Panics if `acc` and `data` have different lengths.
+pub fn gf256_mac(acc: &mut [u8], coefficient: u8, data: &[u8]) {
+    assert_eq!(acc.len(), data.len(), "the lengths do not match");
+    if coefficient == 0 {
+        return;
+    }
+
+    let mut acc_blocks = acc.chunks_exact_mut(MAC_BLOCK_LEN);
+    let mut data_blocks = data.chunks_exact(MAC_BLOCK_LEN);
+    for (acc_block, data_block) in (&mut acc_blocks).zip(&mut data_blocks) {
+        mac_block(acc_block.try_into().unwrap(), coefficient, data_block);
+    }
+    for (a, d) in acc_blocks
+        .into_remainder()
+        .iter_mut()
+        .zip(data_blocks.remainder())
+    {
+        *a ^= gf256_mul(coefficient, *d);
+    }
+}
+
+/// Computes `sum_i coefficients[i] * shards[i]` into `out`, elementwise in GF(2^8), as when
+/// encoding a parity shard or recovering a missing shard with a row of the coding matrix.
+///
+/// Panics if the numbers of coefficients and shards differ, or if a shard has a different length
+/// than `out`.
+pub fn gf256_linear_combination(coefficients: &[u8], shards: &[&[u8]], out: &mut [u8]) {
+    assert_eq!(
+        coefficients.len(),
+        shards.len(),
+        "the numbers of coefficients and shards do not match"
+    );
+    out.fill(0);
+    for (coefficient, shard) in coefficients.iter().zip(shards.iter()) {
+        gf256_mac(out, *coefficient, shard);
+    }
+}
+
+/// Adds `coefficient * data` to a block of the accumulator.
+fn mac_block(acc: &mut [u8; MAC_BLOCK_LEN], coefficient: u8, data: &[u8]) {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            let mut state = [0u32; MAC_BLOCK_LEN / 4];
+            let mut input = [0u32; MAC_BLOCK_LEN / 4 + 1];
+            input[0] = coefficient as u32;
+            for i in 0..MAC_BLOCK_LEN / 4 {
+                state[i] = u32::from_le_bytes(acc[4 * i..4 * i + 4].try_into().unwrap());
+                input[i + 1] = u32::from_le_bytes(data[4 * i..4 * i + 4].try_into().unwrap());
+            }
+            unsafe {
+                syscall_gf256_mac(state.as_mut_ptr(), input.as_ptr());
+            }
+            for (chunk, word) in acc.chunks_exact_mut(4).zip(state.iter()) {
+                chunk.copy_from_slice(&word.to_le_bytes());
+            }
+        } else {
+            for (a, d) in acc.iter_mut().zip(data.iter()) {
+                *a ^= gf256_mul(coefficient, *d);
+            }
+        }
+    }
+}
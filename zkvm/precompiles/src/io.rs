@@ -1,13 +1,22 @@
 #![allow(unused_unsafe)]
+use crate::abi::{abi_encode, SolidityAbi};
+use crate::codec::IoCodec;
+use crate::syscall_set_user_public_value;
 use crate::syscall_write;
-use crate::{syscall_hint_len, syscall_hint_read};
+use crate::{sys_alloc_fresh, syscall_hint_len, syscall_hint_read, syscall_random};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use std::alloc::Layout;
+use sha2::{Digest, Sha256};
 use std::io::Write;
 
+pub const FD_STDOUT: u32 = 1;
+pub const FD_STDERR: u32 = 2;
 const FD_HINT: u32 = 4;
 pub const FD_PUBLIC_VALUES: u32 = 3;
+const FD_PUBLIC_LEAVES: u32 = 5;
+
+/// The hashes of the leaves appended with [commit_leaf] and [commit_leaf_slice].
+static mut PUBLIC_LEAF_HASHES: Vec<[u8; 32]> = Vec::new();
 
 pub struct SyscallWriter {
     fd: u32,
@@ -33,18 +42,18 @@ pub fn read_vec() -> Vec<u8> {
     // Round up to the nearest multiple of 4 so that the memory allocated is in whole words
     let capacity = (len + 3) / 4 * 4;
 
-    // Allocate a buffer of the required length that is 4 byte aligned
-    let layout = Layout::from_size_align(capacity, 4).expect("vec is too large");
-    let ptr = unsafe { std::alloc::alloc(layout) };
+    // Allocate a buffer of the required length that is 4 byte aligned. The syscall assumes the
+    // memory is uninitialized, so the buffer is taken from memory which was never written, even
+    // if the allocator reuses freed memory.
+    let ptr = unsafe { sys_alloc_fresh(capacity, 4) };
     // SAFETY:
-    // 1. `ptr` was allocated using alloc
-    // 2. We assuume that the VM global allocator doesn't dealloc
+    // 1. `ptr` was allocated with the global allocator by `sys_alloc_fresh`
+    // 2. The allocator accepts the layout of the vec when it is freed
     // 3/6. Size is correct from above
     // 4/5. Length is 0
-    // 7. Layout::from_size_align already checks this
+    // 7. The size is a multiple of the alignment
     let mut vec = unsafe { Vec::from_raw_parts(ptr, 0, capacity) };
-    // Read the vec into uninitialized memory. The syscall assumes the memory is uninitialized,
-    // which should be true because the allocator does not dealloc, so a new alloc should be fresh.
+    // Read the vec into uninitialized memory.
     unsafe {
         syscall_hint_read(ptr, len);
         vec.set_len(len);
@@ -57,6 +66,30 @@ pub fn read<T: DeserializeOwned>() -> T {
     bincode::deserialize(&vec).expect("deserialization failed")
 }
 
+/// Read a value which the host wrote with `SP1Stdin::write_with::<C, _>`.
+pub fn read_with<C: IoCodec, T: DeserializeOwned>() -> T {
+    C::decode(&read_vec())
+}
+
+/// Read the public values committed by the previous program of a chain and verify its proof, then
+/// commit the SHA-256 digest of the values and the verifying key digest of the previous program, so
+/// that the two proofs are linked by the recursive verifier.
+///
+/// Must be called before anything else is read or committed, since the link has to be the first 64
+/// bytes of the public values and the proof of the previous program is the first of the stdin.
+#[cfg(feature = "verify")]
+pub fn read_chained() -> Vec<u8> {
+    let vkey = read::<[u32; 8]>();
+    let previous = read_vec();
+    let digest: [u8; 32] = Sha256::digest(&previous).into();
+    crate::verify::verify_sp1_proof(&vkey, &digest);
+    commit_slice(&digest);
+    for word in vkey {
+        commit_slice(&word.to_le_bytes());
+    }
+    previous
+}
+
 pub fn commit<T: Serialize>(value: &T) {
     let writer = SyscallWriter {
         fd: FD_PUBLIC_VALUES,
@@ -64,6 +97,11 @@ pub fn commit<T: Serialize>(value: &T) {
     bincode::serialize_into(writer, value).expect("serialization failed");
 }
 
+/// Commit a value encoded with `C`, which the host reads with `SP1PublicValues::read_with::<C, _>`.
+pub fn commit_with<C: IoCodec, T: Serialize>(value: &T) {
+    commit_slice(&C::encode(value));
+}
+
 pub fn commit_slice(buf: &[u8]) {
     let mut my_writer = SyscallWriter {
         fd: FD_PUBLIC_VALUES,
@@ -71,12 +109,201 @@ pub fn commit_slice(buf: &[u8]) {
     my_writer.write_all(buf).unwrap();
 }
 
+/// Commit a value encoded as with Solidity's `abi.encode`, so that a contract can read it from the
+/// public values with `abi.decode(publicValues, (T))`.
+pub fn commit_abi<T: SolidityAbi>(value: &T) {
+    commit_slice(&abi_encode(value));
+}
+
+/// Append a value as a leaf of the Merkle tree of public outputs. Returns the index of the leaf.
+///
+/// The leaf is sent to the host, but it is not committed: only the root of the tree is, with
+/// [commit_merkle_root].
+pub fn commit_leaf<T: Serialize>(value: &T) -> usize {
+    let leaf = bincode::serialize(value).expect("serialization failed");
+    commit_leaf_slice(&leaf)
+}
+
+/// Append a slice of bytes as a leaf of the Merkle tree of public outputs. Returns the index of the
+/// leaf.
+pub fn commit_leaf_slice(leaf: &[u8]) -> usize {
+    unsafe {
+        syscall_write(FD_PUBLIC_LEAVES, leaf.as_ptr(), leaf.len());
+    }
+    let mut hasher = Sha256::new();
+    hasher.update([0u8]);
+    hasher.update(leaf);
+    let leaf_hashes = unsafe { &mut *core::ptr::addr_of_mut!(PUBLIC_LEAF_HASHES) };
+    leaf_hashes.push(hasher.finalize().into());
+    leaf_hashes.len() - 1
+}
+
+/// Commit the root of the Merkle tree of the leaves appended so far, and return it.
+///
+/// A verifier can then open each output with a proof from the host's `PublicOutputsTree`, instead
+/// of being given all of them. This must be called once, after the last leaf is appended.
+pub fn commit_merkle_root() -> [u8; 32] {
+    let mut layer = unsafe { core::mem::take(&mut *core::ptr::addr_of_mut!(PUBLIC_LEAF_HASHES)) };
+    let root = if layer.is_empty() {
+        [0u8; 32]
+    } else {
+        layer.resize(layer.len().next_power_of_two(), [0u8; 32]);
+        while layer.len() > 1 {
+            layer = layer
+                .chunks_exact(2)
+                .map(|pair| {
+                    let mut hasher = Sha256::new();
+                    hasher.update([1u8]);
+                    hasher.update(pair[0]);
+                    hasher.update(pair[1]);
+                    hasher.finalize().into()
+                })
+                .collect();
+        }
+        layer[0]
+    };
+    commit_slice(&root);
+    root
+}
+
 pub fn hint<T: Serialize>(value: &T) {
     let writer = SyscallWriter { fd: FD_HINT };
     bincode::serialize_into(writer, value).expect("serialization failed");
 }
 
+/// Hint a value encoded with `C`, which is read back with [read_with].
+pub fn hint_with<C: IoCodec, T: Serialize>(value: &T) {
+    hint_slice(&C::encode(value));
+}
+
+/// Compute a value with `f` in an unconstrained block, and return it to the program as a hint.
+///
+/// The cycles of `f` are not proven, so `f` can compute a value natively which the program then
+/// checks cheaply, such as a square root or a sorting permutation. The value is not trusted: the
+/// program must check it.
+pub fn hint_unconstrained<T: Serialize + DeserializeOwned>(f: impl FnOnce() -> T) -> T {
+    crate::unconstrained! {
+        hint(&f());
+    }
+    read()
+}
+
+/// Draw 32 random bytes from the seed the host set with `SP1Stdin::set_random_seed`, and commit
+/// them to the public values.
+///
+/// The bytes are chosen by the prover, not by the verifier, so they can seed hash tables or
+/// sampling but must not be relied on to be unpredictable. Since they are committed, the verifier
+/// sees the randomness of the execution and can check it against a seed it expects.
+pub fn random_bytes() -> [u8; 32] {
+    unsafe { syscall_random() };
+    let bytes: [u8; 32] = read_vec()
+        .try_into()
+        .expect("the host hinted random bytes of the wrong length");
+    commit_slice(&bytes);
+    bytes
+}
+
+/// A random number generator seeded with [random_bytes], whose seed is committed to the public
+/// values.
+pub fn committed_rng() -> rand::rngs::StdRng {
+    rand::SeedableRng::from_seed(random_bytes())
+}
+
 pub fn hint_slice(buf: &[u8]) {
     let mut my_reader = SyscallWriter { fd: FD_HINT };
     my_reader.write_all(buf).unwrap();
 }
+
+/// A value which fits in one word of the user public values.
+pub trait UserPublicValue {
+    /// Encodes the value as a word.
+    fn to_word(self) -> u32;
+}
+
+impl UserPublicValue for u32 {
+    fn to_word(self) -> u32 {
+        self
+    }
+}
+
+impl UserPublicValue for i32 {
+    fn to_word(self) -> u32 {
+        self as u32
+    }
+}
+
+impl UserPublicValue for u16 {
+    fn to_word(self) -> u32 {
+        self as u32
+    }
+}
+
+impl UserPublicValue for u8 {
+    fn to_word(self) -> u32 {
+        self as u32
+    }
+}
+
+impl UserPublicValue for bool {
+    fn to_word(self) -> u32 {
+        self as u32
+    }
+}
+
+/// Set one of the 8 words of the user public values, which are part of the public values of the
+/// proof next to the digest of the committed bytes. The words which are not set are zero.
+pub fn set_user_public_value<T: UserPublicValue>(index: usize, value: T) {
+    unsafe {
+        syscall_set_user_public_value(index as u32, value.to_word());
+    }
+}
+
+/// Write formatted text to the file descriptor `fd` in one syscall, so that the host never sees
+/// half of a write. Used by [print], [println], [eprint] and [eprintln].
+#[doc(hidden)]
+pub fn write_fmt(fd: u32, args: core::fmt::Arguments) {
+    let text = std::fmt::format(args);
+    unsafe {
+        syscall_write(fd, text.as_ptr(), text.len());
+    }
+}
+
+/// Print to the stdout of the program, which the host captures in the execution report.
+#[macro_export]
+macro_rules! print {
+    ($($arg:tt)*) => {
+        $crate::io::write_fmt($crate::io::FD_STDOUT, format_args!($($arg)*))
+    };
+}
+
+/// Print a line to the stdout of the program, which the host captures in the execution report.
+#[macro_export]
+macro_rules! println {
+    () => {
+        $crate::print!("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::io::write_fmt($crate::io::FD_STDOUT, format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+/// Print to the stderr of the program, which the host captures in the execution report.
+#[macro_export]
+macro_rules! eprint {
+    ($($arg:tt)*) => {
+        $crate::io::write_fmt($crate::io::FD_STDERR, format_args!($($arg)*))
+    };
+}
+
+/// Print a line to the stderr of the program, which the host captures in the execution report.
+#[macro_export]
+macro_rules! eprintln {
+    () => {
+        $crate::eprint!("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::io::write_fmt($crate::io::FD_STDERR, format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+pub use crate::{eprint, eprintln, print, println};
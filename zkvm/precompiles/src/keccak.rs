@@ -0,0 +1,302 @@
+//! Keccak sponge hashers (SHA3-256, SHA3-512, Keccak-256 and SHAKE), with the absorb and the
+//! permutation accelerated by the `KECCAK_ABSORB` and `KECCAK_PERMUTE` precompiles inside the zkVM.
+//!
+//! The padding and the state of the sponge are handled here, so that guest programs only need to
+//! feed their input to a hasher.
+
+#![allow(unused_imports)]
+use crate::{syscall_keccak_absorb, syscall_keccak_permute};
+
+/// The number of 64-bit lanes of the Keccak state.
+const STATE_LANES: usize = 25;
+
+/// The largest rate supported by the `KECCAK_ABSORB` precompile, in 64-bit lanes.
+pub const MAX_RATE_LANES: usize = 21;
+
+/// The domain separation byte of the SHA-3 hash functions.
+pub const SHA3_DOMAIN: u8 = 0x06;
+
+/// The domain separation byte of the original Keccak hash functions, as used by Ethereum.
+pub const KECCAK_DOMAIN: u8 = 0x01;
+
+/// The domain separation byte of the SHAKE extendable-output functions.
+pub const SHAKE_DOMAIN: u8 = 0x1F;
+
+#[allow(dead_code)]
+const RC: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+#[allow(dead_code)]
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+#[allow(dead_code)]
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// Computes the SHA3-256 hash of `input`.
+pub fn sha3_256(input: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(input);
+    hasher.finalize()
+}
+
+/// Computes the SHA3-512 hash of `input`.
+pub fn sha3_512(input: &[u8]) -> [u8; 64] {
+    let mut hasher = Sha3_512::new();
+    hasher.update(input);
+    hasher.finalize()
+}
+
+/// Computes the Keccak-256 hash of `input`, as used by Ethereum.
+pub fn keccak256(input: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(input);
+    hasher.finalize()
+}
+
+/// The Keccak-f[1600] permutation.
+pub fn keccak_f(state: &mut [u64; STATE_LANES]) {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            unsafe {
+                syscall_keccak_permute(state.as_mut_ptr());
+            }
+        } else {
+            for rc in RC {
+                // Theta
+                let mut array = [0u64; 5];
+                for x in 0..5 {
+                    for y in 0..5 {
+                        array[x] ^= state[x + 5 * y];
+                    }
+                }
+                for x in 0..5 {
+                    for y in 0..5 {
+                        state[x + 5 * y] ^= array[(x + 4) % 5] ^ array[(x + 1) % 5].rotate_left(1);
+                    }
+                }
+
+                // Rho and pi
+                let mut last = state[1];
+                for x in 0..24 {
+                    let current = state[PI[x]];
+                    state[PI[x]] = last.rotate_left(RHO[x]);
+                    last = current;
+                }
+
+                // Chi
+                for y in 0..5 {
+                    let row: [u64; 5] = state[5 * y..5 * y + 5].try_into().unwrap();
+                    for x in 0..5 {
+                        state[x + 5 * y] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+                    }
+                }
+
+                // Iota
+                state[0] ^= rc;
+            }
+        }
+    }
+}
+
+/// A Keccak sponge with a rate of up to [MAX_RATE_LANES] lanes and a padding domain byte.
+#[derive(Clone)]
+pub struct KeccakSponge {
+    state: [u64; STATE_LANES],
+    rate_lanes: usize,
+    domain: u8,
+    // The rate of the sponge followed by the buffered block, in the layout of `KECCAK_ABSORB`.
+    block: [u32; 1 + 2 * MAX_RATE_LANES],
+    block_len: usize,
+    // The number of bytes of the current squeezed block that have been output.
+    squeezed: Option<usize>,
+}
+
+impl KeccakSponge {
+    /// Creates a sponge with a rate of `rate_bytes` bytes, which must be a nonzero multiple of 8 of
+    /// at most `8 * MAX_RATE_LANES`, and the given domain separation byte.
+    pub fn new(rate_bytes: usize, domain: u8) -> Self {
+        assert!(
+            rate_bytes % 8 == 0 && (8..=8 * MAX_RATE_LANES).contains(&rate_bytes),
+            "invalid keccak sponge rate: {}",
+            rate_bytes
+        );
+        let rate_lanes = rate_bytes / 8;
+        let mut block = [0u32; 1 + 2 * MAX_RATE_LANES];
+        block[0] = rate_lanes as u32;
+        Self {
+            state: [0; STATE_LANES],
+            rate_lanes,
+            domain,
+            block,
+            block_len: 0,
+            squeezed: None,
+        }
+    }
+
+    /// The rate of the sponge in bytes.
+    pub fn rate(&self) -> usize {
+        8 * self.rate_lanes
+    }
+
+    /// Absorbs `input` into the sponge. This can be called any number of times before squeezing.
+    pub fn absorb(&mut self, input: &[u8]) {
+        assert!(
+            self.squeezed.is_none(),
+            "cannot absorb into a keccak sponge after squeezing"
+        );
+        for &byte in input {
+            self.xor_block_byte(self.block_len, byte);
+            self.block_len += 1;
+            if self.block_len == self.rate() {
+                self.absorb_block();
+            }
+        }
+    }
+
+    /// Squeezes the next `output.len()` bytes out of the sponge, padding the input on the first
+    /// call.
+    pub fn squeeze(&mut self, output: &mut [u8]) {
+        let mut offset = match self.squeezed {
+            Some(offset) => offset,
+            None => {
+                // Pad with the domain byte and a final bit, which may be in the same byte.
+                self.xor_block_byte(self.block_len, self.domain);
+                self.xor_block_byte(self.rate() - 1, 0x80);
+                self.absorb_block();
+                0
+            }
+        };
+        for byte in output.iter_mut() {
+            if offset == self.rate() {
+                keccak_f(&mut self.state);
+                offset = 0;
+            }
+            *byte = self.state[offset / 8].to_le_bytes()[offset % 8];
+            offset += 1;
+        }
+        self.squeezed = Some(offset);
+    }
+
+    fn xor_block_byte(&mut self, index: usize, byte: u8) {
+        self.block[1 + index / 4] ^= (byte as u32) << (8 * (index % 4));
+    }
+
+    /// XORs the buffered block into the state and permutes it.
+    fn absorb_block(&mut self) {
+        cfg_if::cfg_if! {
+            if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+                unsafe {
+                    syscall_keccak_absorb(self.state.as_mut_ptr(), self.block.as_ptr());
+                }
+            } else {
+                for i in 0..self.rate_lanes {
+                    let lane = self.block[1 + 2 * i] as u64 | (self.block[2 + 2 * i] as u64) << 32;
+                    self.state[i] ^= lane;
+                }
+                keccak_f(&mut self.state);
+            }
+        }
+        self.block[1..].fill(0);
+        self.block_len = 0;
+    }
+}
+
+macro_rules! fixed_output_hasher {
+    ($name:ident, $rate:expr, $domain:expr, $output_len:expr, $doc:expr) => {
+        #[doc = $doc]
+        #[derive(Clone)]
+        pub struct $name {
+            sponge: KeccakSponge,
+        }
+
+        impl $name {
+            /// The length of the output in bytes.
+            pub const OUTPUT_LEN: usize = $output_len;
+
+            /// Creates a new hasher.
+            pub fn new() -> Self {
+                Self {
+                    sponge: KeccakSponge::new($rate, $domain),
+                }
+            }
+
+            /// Adds input to the hash state. This can be called any number of times.
+            pub fn update(&mut self, input: &[u8]) {
+                self.sponge.absorb(input);
+            }
+
+            /// Finalizes the hash.
+            pub fn finalize(mut self) -> [u8; $output_len] {
+                let mut output = [0u8; $output_len];
+                self.sponge.squeeze(&mut output);
+                output
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    };
+}
+
+fixed_output_hasher!(
+    Sha3_256,
+    136,
+    SHA3_DOMAIN,
+    32,
+    "An incremental SHA3-256 hasher."
+);
+fixed_output_hasher!(
+    Sha3_512,
+    72,
+    SHA3_DOMAIN,
+    64,
+    "An incremental SHA3-512 hasher."
+);
+fixed_output_hasher!(
+    Keccak256,
+    136,
+    KECCAK_DOMAIN,
+    32,
+    "An incremental Keccak-256 hasher."
+);
+
+/// The SHAKE128 extendable-output function.
+pub fn shake128() -> KeccakSponge {
+    KeccakSponge::new(168, SHAKE_DOMAIN)
+}
+
+/// The SHAKE256 extendable-output function.
+pub fn shake256() -> KeccakSponge {
+    KeccakSponge::new(136, SHAKE_DOMAIN)
+}
@@ -0,0 +1,238 @@
+//! The KZG point evaluation check of EIP-4844, which verifies that a blob committed to by a KZG
+//! commitment evaluates to `y` at `z`.
+//!
+//! Inside the zkVM, the G1 scalar multiplications of the check use the `BLS12381_ADD` and
+//! `BLS12381_DOUBLE` precompiles, and the pairing check runs on the base field precompiles with
+//! [crate::bls12381_pairing]. Only the decoding of the points runs in software.
+
+#![allow(unused_imports)]
+use amcl::bls381::big::Big;
+use amcl::bls381::bls381::utils::{deserialize_g1, deserialize_g2};
+use amcl::bls381::ecp::ECP;
+use amcl::bls381::ecp2::ECP2;
+use anyhow::{anyhow, Result};
+use sha2::{Digest, Sha256};
+
+use crate::bls12381::Bls12381;
+use crate::bls12381_pairing::{pairing_product_is_one, Fp, Fp2, G1Affine, G2Affine};
+use crate::utils::{double_and_add, AffinePoint};
+
+/// The length of a compressed G1 point, which is the encoding of commitments and proofs.
+pub const G1_POINT_LEN: usize = 48;
+
+/// The length of the input of the point evaluation precompile of EIP-4844.
+pub const POINT_EVALUATION_INPUT_LEN: usize = 192;
+
+/// The version byte of versioned hashes of KZG commitments.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// The number of field elements of a blob.
+pub const FIELD_ELEMENTS_PER_BLOB: u64 = 4096;
+
+/// The order of the scalar field of BLS12-381, in big-endian order.
+pub const BLS_MODULUS: [u8; 32] = [
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+/// The G2 point `[tau]_2` of the KZG trusted setup of the Ethereum ceremony, compressed.
+const TAU_G2: [u8; 96] = [
+    0xb5, 0xbf, 0xd7, 0xdd, 0x8c, 0xde, 0xb1, 0x28, 0x84, 0x3b, 0xc2, 0x87, 0x23, 0x0a, 0xf3, 0x89,
+    0x26, 0x18, 0x70, 0x75, 0xcb, 0xfb, 0xef, 0xa8, 0x10, 0x09, 0xa2, 0xce, 0x61, 0x5a, 0xc5, 0x3d,
+    0x29, 0x14, 0xe5, 0x87, 0x0c, 0xb4, 0x52, 0xd2, 0xaf, 0xaa, 0xab, 0x24, 0xf3, 0x49, 0x9f, 0x72,
+    0x18, 0x5c, 0xbf, 0xee, 0x53, 0x49, 0x27, 0x14, 0x73, 0x44, 0x29, 0xb7, 0xb3, 0x86, 0x08, 0xe2,
+    0x39, 0x26, 0xc9, 0x11, 0xcc, 0xec, 0xea, 0xc9, 0xa3, 0x68, 0x51, 0x47, 0x7b, 0xa4, 0xc6, 0x0b,
+    0x08, 0x70, 0x41, 0xde, 0x62, 0x10, 0x00, 0xed, 0xc9, 0x8e, 0xda, 0xda, 0x20, 0xc1, 0xde, 0xf2,
+];
+
+/// Computes the versioned hash of a KZG commitment, which is its SHA-256 digest with the first
+/// byte replaced by the version.
+pub fn kzg_to_versioned_hash(commitment: &[u8; G1_POINT_LEN]) -> [u8; 32] {
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+/// Verifies the KZG proof that the polynomial committed to by `commitment` evaluates to `y` at
+/// `z`, where `z` and `y` are big-endian scalars.
+///
+/// The check `e(C - [y]_1, [1]_2) = e(proof, [tau]_2 - [z]_2)` is computed as
+/// `e(C - [y]_1 + z * proof, [1]_2) * e(-proof, [tau]_2) = 1`, so that all the scalar
+/// multiplications are in G1.
+pub fn verify_kzg_proof(
+    commitment: &[u8; G1_POINT_LEN],
+    z: &[u8; 32],
+    y: &[u8; 32],
+    proof: &[u8; G1_POINT_LEN],
+) -> Result<bool> {
+    if !is_canonical_scalar(z) || !is_canonical_scalar(y) {
+        return Err(anyhow!("the evaluation point is not a canonical scalar"));
+    }
+    let commitment_point = deserialize_g1(commitment).map_err(|_| anyhow!("invalid commitment"))?;
+    let mut proof_point = deserialize_g1(proof).map_err(|_| anyhow!("invalid proof"))?;
+    let tau_g2 = deserialize_g2(&TAU_G2).map_err(|_| anyhow!("invalid trusted setup"))?;
+
+    let lhs = g1_combination(&commitment_point, z, y, &proof_point);
+    proof_point.neg();
+
+    // A pairing with the point at infinity is one, and would not change the product.
+    let pairs = [(lhs, ECP2::generator()), (proof_point, tau_g2)]
+        .iter()
+        .filter(|(p, q)| !p.is_infinity() && !q.is_infinity())
+        .map(|(p, q)| (pairing_g1(p), pairing_g2(q)))
+        .collect::<Vec<_>>();
+    Ok(pairing_product_is_one(&pairs))
+}
+
+/// Runs the point evaluation precompile of EIP-4844 on its input, which is
+/// `versioned_hash || z || y || commitment || proof`. Returns `FIELD_ELEMENTS_PER_BLOB` and
+/// `BLS_MODULUS` as two big-endian 32-byte integers if the proof is valid.
+pub fn point_evaluation(input: &[u8; POINT_EVALUATION_INPUT_LEN]) -> Result<[u8; 64]> {
+    let versioned_hash: &[u8; 32] = input[..32].try_into().unwrap();
+    let z: &[u8; 32] = input[32..64].try_into().unwrap();
+    let y: &[u8; 32] = input[64..96].try_into().unwrap();
+    let commitment: &[u8; G1_POINT_LEN] = input[96..144].try_into().unwrap();
+    let proof: &[u8; G1_POINT_LEN] = input[144..].try_into().unwrap();
+
+    if kzg_to_versioned_hash(commitment) != *versioned_hash {
+        return Err(anyhow!("the versioned hash does not match the commitment"));
+    }
+    if !verify_kzg_proof(commitment, z, y, proof)? {
+        return Err(anyhow!("invalid KZG proof"));
+    }
+
+    let mut output = [0u8; 64];
+    output[24..32].copy_from_slice(&FIELD_ELEMENTS_PER_BLOB.to_be_bytes());
+    output[32..].copy_from_slice(&BLS_MODULUS);
+    Ok(output)
+}
+
+/// Converts a point of G1 which is not the point at infinity to the input of the pairing.
+pub(crate) fn pairing_g1(point: &ECP) -> G1Affine {
+    G1Affine {
+        x: fp_from_big(&point.getx()),
+        y: fp_from_big(&point.gety()),
+    }
+}
+
+/// Converts a point of G2 which is not the point at infinity to the input of the pairing.
+pub(crate) fn pairing_g2(point: &ECP2) -> G2Affine {
+    let (x, y) = (point.getx(), point.gety());
+    G2Affine {
+        x: Fp2::new(fp_from_big(&x.geta()), fp_from_big(&x.getb())),
+        y: Fp2::new(fp_from_big(&y.geta()), fp_from_big(&y.getb())),
+    }
+}
+
+/// Converts a coordinate of a decoded point, which is reduced, to an element of the base field.
+fn fp_from_big(coordinate: &Big) -> Fp {
+    let mut bytes = [0u8; 48];
+    coordinate.to_bytes(&mut bytes);
+    Fp::from_be_bytes(&bytes).expect("the coordinate is reduced")
+}
+
+/// Whether a big-endian scalar is smaller than the order of the scalar field.
+fn is_canonical_scalar(scalar: &[u8; 32]) -> bool {
+    *scalar < BLS_MODULUS
+}
+
+/// Converts a big-endian scalar to the 48-byte big-endian encoding of a `Big`.
+fn scalar_to_big(scalar: &[u8; 32]) -> Big {
+    let mut bytes = [0u8; 48];
+    bytes[16..].copy_from_slice(scalar);
+    Big::from_bytes(&bytes)
+}
+
+/// Computes `C - [y]_1 + z * proof`.
+fn g1_combination(commitment: &ECP, z: &[u8; 32], y: &[u8; 32], proof: &ECP) -> ECP {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            // The precompiles do not handle the point at infinity, which is left to software.
+            if !commitment.is_infinity() && !proof.is_infinity() {
+                if let Some(result) = g1_combination_precompile(commitment, z, y, proof) {
+                    return result;
+                }
+            }
+        }
+    }
+
+    let mut result = ECP::generator().mul(&scalar_to_big(y));
+    result.neg();
+    result.add(&proof.mul(&scalar_to_big(z)));
+    result.add(commitment);
+    result
+}
+
+/// Computes `C + (r - y) * G + z * proof` with the BLS12-381 precompiles. Returns `None` if an
+/// intermediate point is the point at infinity or if the final addition is a doubling.
+#[allow(dead_code)]
+fn g1_combination_precompile(
+    commitment: &ECP,
+    z: &[u8; 32],
+    y: &[u8; 32],
+    proof: &ECP,
+) -> Option<ECP> {
+    let commitment = g1_affine_point(commitment);
+    let proof = g1_affine_point(proof);
+
+    let neg_y = sub_from_modulus(y);
+    let mut result = double_and_add(
+        le_bits(&neg_y),
+        &AffinePoint::<Bls12381, 24>::generator_in_affine(),
+        le_bits(z),
+        &proof,
+    )?;
+    if result.limbs[..12] == commitment.limbs[..12] {
+        return None;
+    }
+    result.add_assign(&commitment);
+
+    // Convert the little-endian words back to big-endian coordinates.
+    let mut bytes = result.to_le_bytes();
+    bytes[..48].reverse();
+    bytes[48..].reverse();
+    Some(ECP::new_bigs(
+        &Big::from_bytes(&bytes[..48]),
+        &Big::from_bytes(&bytes[48..]),
+    ))
+}
+
+/// Converts a G1 point which is not the point at infinity to the little-endian words of its
+/// coordinates.
+#[allow(dead_code)]
+fn g1_affine_point(point: &ECP) -> AffinePoint<Bls12381, 24> {
+    let (mut x, mut y) = ([0u8; 48], [0u8; 48]);
+    point.getx().to_bytes(&mut x);
+    point.gety().to_bytes(&mut y);
+    x.reverse();
+    y.reverse();
+    AffinePoint::from(&x, &y)
+}
+
+/// Computes `BLS_MODULUS - scalar` for a nonzero big-endian scalar, and zero for zero.
+#[allow(dead_code)]
+fn sub_from_modulus(scalar: &[u8; 32]) -> [u8; 32] {
+    if scalar.iter().all(|byte| *byte == 0) {
+        return [0u8; 32];
+    }
+    let mut result = [0u8; 32];
+    let mut borrow = 0i16;
+    for i in (0..32).rev() {
+        let mut diff = BLS_MODULUS[i] as i16 - scalar[i] as i16 - borrow;
+        borrow = (diff < 0) as i16;
+        if diff < 0 {
+            diff += 256;
+        }
+        result[i] = diff as u8;
+    }
+    result
+}
+
+/// The little-endian bits of a big-endian scalar.
+#[allow(dead_code)]
+fn le_bits(scalar: &[u8; 32]) -> impl Iterator<Item = bool> + '_ {
+    scalar
+        .iter()
+        .rev()
+        .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+}
@@ -1,17 +1,39 @@
+pub mod abi;
+pub mod aes;
 pub mod bigint_mulmod;
+pub mod blake3;
 pub mod bls12381;
+pub mod bls12381_pairing;
+pub mod bls_aggregation;
 pub mod bn254;
+pub mod chacha20;
+pub mod codec;
+pub mod ed25519;
+pub mod gf256;
 pub mod io;
+pub mod keccak;
+pub mod kzg;
+pub mod modexp;
+pub mod pedersen;
+pub mod poseidon2;
+pub mod process;
+pub mod ripemd160;
+pub mod rsa;
+pub mod schnorr;
 pub mod secp256k1;
+pub mod secp256r1;
+pub mod sha256;
 pub mod uint256_div;
 pub mod unconstrained;
 pub mod utils;
 #[cfg(feature = "verify")]
 pub mod verify;
+pub mod wide_uint;
 
 extern "C" {
     pub fn syscall_halt(exit_code: u8) -> !;
     pub fn syscall_write(fd: u32, write_buf: *const u8, nbytes: usize);
+    pub fn syscall_set_user_public_value(index: u32, word: u32);
     pub fn syscall_read(fd: u32, read_buf: *mut u8, nbytes: usize);
     pub fn syscall_sha256_extend(w: *mut u32);
     pub fn syscall_sha256_compress(w: *mut u32, state: *mut u32);
@@ -20,18 +42,38 @@ extern "C" {
     pub fn syscall_secp256k1_add(p: *mut u32, q: *const u32);
     pub fn syscall_secp256k1_double(p: *mut u32);
     pub fn syscall_secp256k1_decompress(point: &mut [u8; 64], is_odd: bool);
+    pub fn syscall_secp256k1_ecrecover(input: *mut u32, is_odd: bool);
+    pub fn syscall_secp256r1_add(p: *mut u32, q: *const u32);
+    pub fn syscall_secp256r1_double(p: *mut u32);
     pub fn syscall_bn254_add(p: *mut u32, q: *const u32);
     pub fn syscall_bn254_double(p: *mut u32);
     pub fn syscall_bls12381_add(p: *mut u32, q: *const u32);
     pub fn syscall_bls12381_double(p: *mut u32);
+    pub fn syscall_bls12381_fp_add(x: *mut u32, y: *const u32);
+    pub fn syscall_bls12381_fp_sub(x: *mut u32, y: *const u32);
+    pub fn syscall_bls12381_fp_mul(x: *mut u32, y: *const u32);
     pub fn syscall_keccak_permute(state: *mut u64);
+    pub fn syscall_keccak_absorb(state: *mut u64, block: *const u32);
+    pub fn syscall_poseidon2_permute(state: *mut u32);
     pub fn syscall_uint256_mulmod(x: *mut u32, y: *const u32);
+    pub fn syscall_uint2048_mulmod(x: *mut u32, y: *const u32);
+    pub fn syscall_uint4096_mulmod(x: *mut u32, y: *const u32);
+    pub fn syscall_uint384_mulmod(x: *mut u32, y: *const u32);
+    pub fn syscall_uint512_mulmod(x: *mut u32, y: *const u32);
+    pub fn syscall_aes_encrypt_round(state: *mut u32, round_key: *const u32);
+    pub fn syscall_aes_encrypt_last_round(state: *mut u32, round_key: *const u32);
+    pub fn syscall_ghash_mul(x: *mut u32, h: *const u32);
+    pub fn syscall_gf256_mac(acc: *mut u32, input: *const u32);
+    pub fn syscall_chacha20_permute(state: *mut u32);
+    pub fn syscall_ripemd160_compress(state: *mut u32, block: *const u32);
     pub fn syscall_blake3_compress_inner(p: *mut u32, q: *const u32);
     pub fn syscall_enter_unconstrained() -> bool;
     pub fn syscall_exit_unconstrained();
     pub fn syscall_verify_sp1_proof(vkey: &[u32; 8], pv_digest: &[u8; 32]);
     pub fn syscall_hint_len() -> usize;
     pub fn syscall_hint_read(ptr: *mut u8, len: usize);
+    pub fn syscall_random();
     pub fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u8;
+    pub fn sys_alloc_fresh(bytes: usize, align: usize) -> *mut u8;
     pub fn syscall_bls12381_decompress(point: &mut [u8; 96], is_odd: bool);
 }
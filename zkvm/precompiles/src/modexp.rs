@@ -0,0 +1,111 @@
+#![allow(unused_imports)]
+use num::{BigUint, One, Zero};
+
+use crate::{syscall_uint2048_mulmod, syscall_uint4096_mulmod};
+
+/// The number of words of the operands of the `UINT2048_MULMOD` precompile.
+pub const UINT2048_NUM_WORDS: usize = 64;
+
+/// The number of words of the operands of the `UINT4096_MULMOD` precompile.
+pub const UINT4096_NUM_WORDS: usize = 128;
+
+/// The number of exponent bits processed per multiplication by a precomputed power of the base.
+const WINDOW_BITS: usize = 4;
+
+/// Computes `base^exponent % modulus`, where all values are big-endian byte strings, following the
+/// semantics of the EVM's `MODEXP` precompile: the result has the same length as the modulus, and
+/// is zero if the modulus is zero or one.
+///
+/// Inside the zkVM, moduli of up to 4096 bits use fixed-window exponentiation on top of the
+/// `UINT2048_MULMOD` and `UINT4096_MULMOD` precompiles, so that RSA signature verification costs
+/// one syscall per multiplication.
+pub fn modexp(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Vec<u8> {
+    let m = BigUint::from_bytes_be(modulus);
+    let result = if m <= BigUint::one() {
+        BigUint::zero()
+    } else {
+        cfg_if::cfg_if! {
+            if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+                let b = BigUint::from_bytes_be(base);
+                match m.bits() {
+                    0..=2048 => {
+                        windowed_modexp(&b, exponent, &m, UINT2048_NUM_WORDS, syscall_uint2048_mulmod)
+                    }
+                    2049..=4096 => {
+                        windowed_modexp(&b, exponent, &m, UINT4096_NUM_WORDS, syscall_uint4096_mulmod)
+                    }
+                    _ => b.modpow(&BigUint::from_bytes_be(exponent), &m),
+                }
+            } else {
+                BigUint::from_bytes_be(base).modpow(&BigUint::from_bytes_be(exponent), &m)
+            }
+        }
+    };
+
+    let bytes = result.to_bytes_be();
+    let mut output = vec![0u8; modulus.len()];
+    output[modulus.len() - bytes.len()..].copy_from_slice(&bytes);
+    output
+}
+
+/// Modular exponentiation with a mulmod syscall over `num_words`-word operands.
+#[allow(dead_code)]
+fn windowed_modexp(
+    base: &BigUint,
+    exponent: &[u8],
+    modulus: &BigUint,
+    num_words: usize,
+    syscall: unsafe extern "C" fn(*mut u32, *const u32),
+) -> BigUint {
+    // The syscall reads the multiplier followed by the modulus from its second argument.
+    let mut y_modulus = vec![0u32; 2 * num_words];
+    y_modulus[num_words..].copy_from_slice(&to_words(modulus, num_words));
+    let mut mulmod = |x: &mut [u32], y: &[u32]| {
+        y_modulus[..num_words].copy_from_slice(y);
+        unsafe { syscall(x.as_mut_ptr(), y_modulus.as_ptr()) }
+    };
+
+    // Reduce the base and one modulo the modulus, multiplying them by one.
+    let one = to_words(&BigUint::one(), num_words);
+    let reduced_base = if base.bits() <= 32 * num_words as u64 {
+        to_words(base, num_words)
+    } else {
+        to_words(&(base % modulus), num_words)
+    };
+    let mut powers = vec![one.clone(), reduced_base];
+    mulmod(&mut powers[0], &one);
+    mulmod(&mut powers[1], &one);
+
+    // Precompute base^i for every window value i.
+    for i in 2..1 << WINDOW_BITS {
+        let mut power = powers[i - 1].clone();
+        mulmod(&mut power, &powers[1]);
+        powers.push(power);
+    }
+
+    let mut acc = powers[0].clone();
+    let mut started = false;
+    for window in exponent.iter().flat_map(|byte| [byte >> 4, byte & 0xf]) {
+        if started {
+            for _ in 0..WINDOW_BITS {
+                let square = acc.clone();
+                mulmod(&mut acc, &square);
+            }
+        }
+        if window != 0 {
+            mulmod(&mut acc, &powers[window as usize]);
+            started = true;
+        }
+    }
+
+    let result = BigUint::from_slice(&acc);
+    assert!(&result < modulus, "modexp result is not reduced");
+    result
+}
+
+#[allow(dead_code)]
+fn to_words(x: &BigUint, num_words: usize) -> Vec<u32> {
+    let mut words = x.to_u32_digits();
+    words.resize(num_words, 0);
+    words
+}
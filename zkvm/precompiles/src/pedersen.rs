@@ -0,0 +1,82 @@
+//! A windowed Pedersen hash over any curve with point operation precompiles.
+//!
+//! The message is split into segments of [SEGMENT_LEN] bytes, and each segment is hashed with its
+//! own generator. The nibbles `w_k` of a segment are encoded as the scalar
+//! `sum_k (w_k + 1) * 2^(5 * k)`, which is nonzero and injective, and the digest is the sum of the
+//! scalar multiples of the generators. Inside the zkVM, the point operations use the add and
+//! double precompiles of the curve.
+//!
+//! The generators must be independent points of the curve, with no known discrete logarithm
+//! relation between them, such as the outputs of a hash-to-curve function.
+//!
+//! The hash is not a precompile of its own: the scalar multiplications run in the guest, and only
+//! their point additions and doublings are proven by the chips of the curve. Sinsemilla is not
+//! supported.
+
+use crate::utils::{AffinePoint, CurveOperations};
+
+/// The number of bytes of a segment, so that its scalar is smaller than 2^250 and below the group
+/// order of all the supported curves.
+pub const SEGMENT_LEN: usize = 25;
+
+/// The number of bits of each nibble of the encoding, including a separating bit.
+const WINDOW_BITS: usize = 5;
+
+/// A Pedersen hasher with a fixed set of generators, one per segment of the message.
+#[derive(Debug, Clone)]
+pub struct PedersenHasher<C: CurveOperations<NUM_WORDS> + Copy, const NUM_WORDS: usize> {
+    generators: Vec<AffinePoint<C, NUM_WORDS>>,
+}
+
+impl<C: CurveOperations<NUM_WORDS> + Copy, const NUM_WORDS: usize> PedersenHasher<C, NUM_WORDS> {
+    /// Creates a hasher for messages of up to `generators.len() * SEGMENT_LEN` bytes.
+    pub fn new(generators: Vec<AffinePoint<C, NUM_WORDS>>) -> Self {
+        assert!(!generators.is_empty(), "a generator is needed");
+        Self { generators }
+    }
+
+    /// The maximum length of a message in bytes.
+    pub fn max_message_len(&self) -> usize {
+        self.generators.len() * SEGMENT_LEN
+    }
+
+    /// Computes the Pedersen hash of a nonempty message.
+    ///
+    /// Panics if the message is empty or longer than [PedersenHasher::max_message_len].
+    pub fn hash(&self, message: &[u8]) -> AffinePoint<C, NUM_WORDS> {
+        assert!(!message.is_empty(), "the message is empty");
+        assert!(
+            message.len() <= self.max_message_len(),
+            "the message is longer than {} bytes",
+            self.max_message_len()
+        );
+
+        let mut digest: Option<AffinePoint<C, NUM_WORDS>> = None;
+        for (segment, generator) in message.chunks(SEGMENT_LEN).zip(self.generators.iter()) {
+            let mut point = *generator;
+            point.mul_assign(&segment_scalar::<NUM_WORDS>(segment));
+            match digest.as_mut() {
+                Some(digest) => digest.add_assign(&point),
+                None => digest = Some(point),
+            }
+        }
+        digest.unwrap()
+    }
+}
+
+/// Encodes the nibbles of a segment as the little-endian words of `sum_k (w_k + 1) * 2^(5 * k)`.
+fn segment_scalar<const NUM_WORDS: usize>(segment: &[u8]) -> Vec<u32> {
+    let mut scalar = vec![0u32; NUM_WORDS / 2];
+    let nibbles = segment.iter().flat_map(|byte| [byte & 0xf, byte >> 4]);
+    for (k, nibble) in nibbles.enumerate() {
+        // The windows do not overlap, so the value can be written bit by bit.
+        let value = nibble as u32 + 1;
+        for bit in 0..WINDOW_BITS {
+            if (value >> bit) & 1 == 1 {
+                let position = k * WINDOW_BITS + bit;
+                scalar[position / 32] |= 1 << (position % 32);
+            }
+        }
+    }
+    scalar
+}
@@ -0,0 +1,85 @@
+//! The Poseidon2 hash over BabyBear used by the recursion verifier, with the permutation
+//! accelerated by the `POSEIDON2_PERMUTE` precompile inside the zkVM.
+//!
+//! Elements are canonical BabyBear values stored as `u32`s. The sponge and the two-to-one
+//! compression match the Merkle tree hashing of SP1 proofs, so that roots and commitments computed
+//! in a guest are cheap to open inside the recursion circuits.
+
+#![allow(unused_imports)]
+use crate::syscall_poseidon2_permute;
+
+/// The width of the permutation.
+pub const WIDTH: usize = 16;
+
+/// The number of elements absorbed per permutation by [hash].
+pub const RATE: usize = 8;
+
+/// The number of elements of a digest.
+pub const DIGEST_LEN: usize = 8;
+
+/// The order of the BabyBear field.
+pub const BABYBEAR_ORDER: u32 = 0x7800_0001;
+
+/// Reduces a `u32` to a canonical BabyBear element.
+pub fn reduce(x: u32) -> u32 {
+    x % BABYBEAR_ORDER
+}
+
+/// The Poseidon2 permutation. Every element of the state must be a canonical BabyBear element.
+pub fn permute(state: &mut [u32; WIDTH]) {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            unsafe {
+                syscall_poseidon2_permute(state.as_mut_ptr());
+            }
+        } else {
+            use p3_baby_bear::BabyBear;
+            use p3_field::{AbstractField, PrimeField32};
+            use p3_symmetric::Permutation;
+
+            let input = state.map(|x| {
+                assert!(x < BABYBEAR_ORDER, "{} is not a canonical BabyBear element", x);
+                BabyBear::from_canonical_u32(x)
+            });
+            let output = sp1_primitives::poseidon2_init().permute(input);
+            *state = output.map(|x| x.as_canonical_u32());
+        }
+    }
+}
+
+/// Hashes `input` with the padding-free Poseidon2 sponge, overwriting the first [RATE] elements of
+/// the state with each chunk of the input.
+pub fn hash(input: &[u32]) -> [u32; DIGEST_LEN] {
+    let mut state = [0u32; WIDTH];
+    for chunk in input.chunks(RATE) {
+        state[..chunk.len()].copy_from_slice(chunk);
+        permute(&mut state);
+    }
+    state[..DIGEST_LEN].try_into().unwrap()
+}
+
+/// Compresses two digests into one, as in the inner nodes of a Merkle tree.
+pub fn compress(left: &[u32; DIGEST_LEN], right: &[u32; DIGEST_LEN]) -> [u32; DIGEST_LEN] {
+    let mut state = [0u32; WIDTH];
+    state[..DIGEST_LEN].copy_from_slice(left);
+    state[DIGEST_LEN..].copy_from_slice(right);
+    permute(&mut state);
+    state[..DIGEST_LEN].try_into().unwrap()
+}
+
+/// Computes the root of the Merkle tree whose leaves are the given digests, duplicating the last
+/// node of each odd layer. The number of leaves must be nonzero.
+pub fn merkle_root(leaves: &[[u32; DIGEST_LEN]]) -> [u32; DIGEST_LEN] {
+    assert!(
+        !leaves.is_empty(),
+        "a merkle tree must have at least one leaf"
+    );
+    let mut layer = leaves.to_vec();
+    while layer.len() > 1 {
+        layer = layer
+            .chunks(2)
+            .map(|pair| compress(&pair[0], pair.last().unwrap()))
+            .collect();
+    }
+    layer[0]
+}
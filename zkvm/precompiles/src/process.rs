@@ -0,0 +1,12 @@
+//! Halting the program early.
+
+use crate::syscall_halt;
+
+/// Halt the program with the given exit code.
+///
+/// The public values committed so far are finalized as when `main` returns, and the exit code is
+/// part of the public values of the proof. Proofs of programs which exit with a nonzero code are
+/// rejected by `ProverClient::verify`, unless it is configured to accept them.
+pub fn exit(code: u8) -> ! {
+    unsafe { syscall_halt(code) }
+}
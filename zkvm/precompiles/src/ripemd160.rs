@@ -0,0 +1,203 @@
+//! The RIPEMD-160 hash function, and Bitcoin's `hash160`, which is `RIPEMD160(SHA256(data))`.
+//!
+//! Inside the zkVM, the compression function is accelerated by the `RIPEMD160_COMPRESS`
+//! precompile.
+
+#![allow(unused_imports)]
+use sha2::{Digest, Sha256};
+
+use crate::syscall_ripemd160_compress;
+use crate::utils::{bytes_to_words_le, words_to_bytes_le};
+
+/// The length of a RIPEMD-160 digest in bytes.
+pub const DIGEST_LEN: usize = 20;
+
+/// The length of a RIPEMD-160 message block in bytes.
+pub const BLOCK_LEN: usize = 64;
+
+/// The number of words of the RIPEMD-160 state.
+const STATE_WORDS: usize = 5;
+
+/// The number of words of a message block.
+const BLOCK_WORDS: usize = 16;
+
+/// The initial state of RIPEMD-160.
+const IV: [u32; STATE_WORDS] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+/// An incremental RIPEMD-160 hasher.
+#[derive(Debug, Clone)]
+pub struct Ripemd160 {
+    state: [u32; STATE_WORDS],
+    buffer: [u8; BLOCK_LEN],
+    buffer_len: usize,
+    len: u64,
+}
+
+impl Default for Ripemd160 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Ripemd160 {
+    /// Creates a hasher for an empty message.
+    pub const fn new() -> Self {
+        Self {
+            state: IV,
+            buffer: [0u8; BLOCK_LEN],
+            buffer_len: 0,
+            len: 0,
+        }
+    }
+
+    /// Absorbs `data` into the hasher.
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.len = self.len.wrapping_add(data.len() as u64);
+        if self.buffer_len > 0 {
+            let take = core::cmp::min(BLOCK_LEN - self.buffer_len, data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+            if self.buffer_len < BLOCK_LEN {
+                return;
+            }
+            let block = self.buffer;
+            compress(&mut self.state, &block);
+            self.buffer_len = 0;
+        }
+        let mut blocks = data.chunks_exact(BLOCK_LEN);
+        for block in &mut blocks {
+            compress(&mut self.state, block.try_into().unwrap());
+        }
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_len = remainder.len();
+    }
+
+    /// Pads the message and returns its digest.
+    pub fn finalize(mut self) -> [u8; DIGEST_LEN] {
+        // The message is followed by a one bit, padded with zeros to 8 bytes short of a block,
+        // and followed by its length in bits as a little-endian integer.
+        let bit_len = self.len.wrapping_mul(8);
+        let pad_len = if self.buffer_len < BLOCK_LEN - 8 {
+            BLOCK_LEN - 8 - self.buffer_len
+        } else {
+            2 * BLOCK_LEN - 8 - self.buffer_len
+        };
+        let mut padding = [0u8; BLOCK_LEN + 8];
+        padding[0] = 0x80;
+        padding[pad_len..pad_len + 8].copy_from_slice(&bit_len.to_le_bytes());
+        self.update(&padding[..pad_len + 8]);
+        words_to_bytes_le(&self.state).try_into().unwrap()
+    }
+}
+
+/// Computes the RIPEMD-160 digest of `data`.
+pub fn ripemd160(data: &[u8]) -> [u8; DIGEST_LEN] {
+    let mut hasher = Ripemd160::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// Computes Bitcoin's `hash160` of `data`, which is `RIPEMD160(SHA256(data))`.
+pub fn hash160(data: &[u8]) -> [u8; DIGEST_LEN] {
+    ripemd160(&Sha256::digest(data))
+}
+
+/// Applies the RIPEMD-160 compression function to the state and a message block.
+fn compress(state: &mut [u32; STATE_WORDS], block: &[u8; BLOCK_LEN]) {
+    let block: [u32; BLOCK_WORDS] = bytes_to_words_le(block).try_into().unwrap();
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            unsafe {
+                syscall_ripemd160_compress(state.as_mut_ptr(), block.as_ptr());
+            }
+        } else {
+            let (mut left, mut right) = (*state, *state);
+            for step in 0..80 {
+                let round = step / 16;
+                left = step_func(
+                    left,
+                    round,
+                    block[LEFT_MSG_SCHEDULE[step]],
+                    LEFT_K[round],
+                    LEFT_ROTATIONS[step],
+                );
+                right = step_func(
+                    right,
+                    4 - round,
+                    block[RIGHT_MSG_SCHEDULE[step]],
+                    RIGHT_K[round],
+                    RIGHT_ROTATIONS[step],
+                );
+            }
+            let h = *state;
+            for (i, word) in state.iter_mut().enumerate() {
+                *word = h[(i + 1) % STATE_WORDS]
+                    .wrapping_add(left[(i + 2) % STATE_WORDS])
+                    .wrapping_add(right[(i + 3) % STATE_WORDS]);
+            }
+        }
+    }
+}
+
+/// Computes one step of a line on the working variables `[a, b, c, d, e]`, with the boolean
+/// function of round `f_round`.
+#[allow(dead_code)]
+fn step_func(
+    vars: [u32; STATE_WORDS],
+    f_round: usize,
+    x: u32,
+    k: u32,
+    rotation: u32,
+) -> [u32; STATE_WORDS] {
+    let [a, b, c, d, e] = vars;
+    let f = match f_round {
+        0 => b ^ c ^ d,
+        1 => (b & c) | (!b & d),
+        2 => (b | !c) ^ d,
+        3 => (b & d) | (c & !d),
+        _ => b ^ (c | !d),
+    };
+    let t = a
+        .wrapping_add(f)
+        .wrapping_add(x)
+        .wrapping_add(k)
+        .rotate_left(rotation)
+        .wrapping_add(e);
+    [e, t, b, c.rotate_left(10), d]
+}
+
+#[allow(dead_code)]
+const LEFT_MSG_SCHEDULE: [usize; 80] = [
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 7, 4, 13, 1, 10, 6, 15, 3, 12, 0, 9, 5,
+    2, 14, 11, 8, 3, 10, 14, 4, 9, 15, 8, 1, 2, 7, 0, 6, 13, 11, 5, 12, 1, 9, 11, 10, 0, 8, 12, 4,
+    13, 3, 7, 15, 14, 5, 6, 2, 4, 0, 5, 9, 7, 12, 2, 10, 14, 1, 3, 8, 11, 6, 15, 13,
+];
+
+#[allow(dead_code)]
+const RIGHT_MSG_SCHEDULE: [usize; 80] = [
+    5, 14, 7, 0, 9, 2, 11, 4, 13, 6, 15, 8, 1, 10, 3, 12, 6, 11, 3, 7, 0, 13, 5, 10, 14, 15, 8, 12,
+    4, 9, 1, 2, 15, 5, 1, 3, 7, 14, 6, 9, 11, 8, 12, 2, 10, 0, 4, 13, 8, 6, 4, 1, 3, 11, 15, 0, 5,
+    12, 2, 13, 9, 7, 10, 14, 12, 15, 10, 4, 1, 5, 8, 7, 6, 2, 13, 14, 0, 3, 9, 11,
+];
+
+#[allow(dead_code)]
+const LEFT_ROTATIONS: [u32; 80] = [
+    11, 14, 15, 12, 5, 8, 7, 9, 11, 13, 14, 15, 6, 7, 9, 8, 7, 6, 8, 13, 11, 9, 7, 15, 7, 12, 15,
+    9, 11, 7, 13, 12, 11, 13, 6, 7, 14, 9, 13, 15, 14, 8, 13, 6, 5, 12, 7, 5, 11, 12, 14, 15, 14,
+    15, 9, 8, 9, 14, 5, 6, 8, 6, 5, 12, 9, 15, 5, 11, 6, 8, 13, 12, 5, 12, 13, 14, 11, 8, 5, 6,
+];
+
+#[allow(dead_code)]
+const RIGHT_ROTATIONS: [u32; 80] = [
+    8, 9, 9, 11, 13, 15, 15, 5, 7, 7, 8, 11, 14, 14, 12, 6, 9, 13, 15, 7, 12, 8, 9, 11, 7, 7, 12,
+    7, 6, 15, 13, 11, 9, 7, 15, 11, 8, 6, 6, 14, 12, 13, 5, 14, 13, 13, 7, 5, 15, 5, 8, 11, 14, 14,
+    6, 14, 6, 9, 12, 9, 12, 5, 15, 8, 8, 5, 12, 9, 12, 5, 14, 6, 8, 13, 6, 5, 15, 13, 11, 11,
+];
+
+#[allow(dead_code)]
+const LEFT_K: [u32; 5] = [0x00000000, 0x5a827999, 0x6ed9eba1, 0x8f1bbcdc, 0xa953fd4e];
+
+#[allow(dead_code)]
+const RIGHT_K: [u32; 5] = [0x50a28be6, 0x5c4dd124, 0x6d703ef3, 0x7a6d76e9, 0x00000000];
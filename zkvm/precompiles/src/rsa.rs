@@ -0,0 +1,32 @@
+use crate::modexp::modexp;
+
+/// The DER encoding of the `DigestInfo` prefix of a SHA-256 digest, as defined in RFC 8017.
+const SHA256_DIGEST_INFO_PREFIX: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+/// Verifies an RSASSA-PKCS1-v1_5 signature of a SHA-256 digest, given the big-endian public
+/// modulus `n` and exponent `e` of the key. Keys of up to 4096 bits are accelerated by the
+/// modular multiplication precompiles.
+pub fn verify_pkcs1v15_sha256(n: &[u8], e: &[u8], msg_hash: &[u8; 32], signature: &[u8]) -> bool {
+    let k = n.len();
+    if signature.len() != k || k < SHA256_DIGEST_INFO_PREFIX.len() + msg_hash.len() + 11 {
+        return false;
+    }
+    // The signature must be reduced modulo n.
+    if signature >= n {
+        return false;
+    }
+
+    // EM = 0x00 || 0x01 || PS || 0x00 || DigestInfo, where PS is a padding of 0xff bytes.
+    let mut expected = vec![0xffu8; k];
+    expected[0] = 0x00;
+    expected[1] = 0x01;
+    let t_len = SHA256_DIGEST_INFO_PREFIX.len() + msg_hash.len();
+    expected[k - t_len - 1] = 0x00;
+    expected[k - t_len..k - msg_hash.len()].copy_from_slice(&SHA256_DIGEST_INFO_PREFIX);
+    expected[k - msg_hash.len()..].copy_from_slice(msg_hash);
+
+    modexp(signature, e, n) == expected
+}
@@ -0,0 +1,98 @@
+#![allow(unused)]
+
+use crate::secp256k1::Secp256k1Operations;
+use crate::syscall_secp256k1_decompress;
+use crate::utils::{double_and_add, AffinePoint};
+use k256::elliptic_curve::ff::PrimeFieldBits;
+use k256::elliptic_curve::ops::Reduce;
+use k256::elliptic_curve::PrimeField;
+use k256::{Scalar, U256};
+use sha2::{Digest, Sha256};
+
+const NUM_WORDS: usize = 16;
+
+/// The order of the base field of secp256k1, in big-endian bytes.
+const FIELD_ORDER: [u8; 32] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0xff, 0xff, 0xfc, 0x2f,
+];
+
+/// Computes the BIP-340 tagged hash `SHA256(SHA256(tag) || SHA256(tag) || data)`.
+pub fn tagged_hash(tag: &[u8], data: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag);
+    let mut hasher = Sha256::new();
+    hasher.update(tag_hash);
+    hasher.update(tag_hash);
+    for chunk in data {
+        hasher.update(chunk);
+    }
+    hasher.finalize().into()
+}
+
+/// Verifies a BIP-340 Schnorr signature, as used by Bitcoin Taproot, of `message` under the
+/// x-only public key `pubkey`.
+///
+/// Inside the zkVM, the public key is lifted to the point with an even y coordinate with the
+/// `SECP256K1_DECOMPRESS` precompile, and `s * G - e * P` is computed with the `SECP256K1_ADD` and
+/// `SECP256K1_DOUBLE` precompiles.
+///
+/// Warning: inside the zkVM, the decompress precompile panics if `pubkey` is not the x coordinate
+/// of a point on the curve.
+pub fn verify_signature(pubkey: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            let (r, s) = signature.split_at(32);
+            if pubkey[..] >= FIELD_ORDER[..] || r >= &FIELD_ORDER[..] {
+                return false;
+            }
+            let s: Option<Scalar> = Scalar::from_repr(*k256::FieldBytes::from_slice(s)).into();
+            let s = match s {
+                Some(s) => s,
+                None => return false,
+            };
+
+            // Lift the x coordinate to the point with an even y coordinate.
+            let mut decompressed = [0u8; 64];
+            decompressed[..32].copy_from_slice(pubkey);
+            unsafe {
+                syscall_secp256k1_decompress(&mut decompressed, false);
+            }
+            let mut pubkey_x_le_bytes = decompressed[..32].to_vec();
+            pubkey_x_le_bytes.reverse();
+            let mut pubkey_y_le_bytes = decompressed[32..].to_vec();
+            pubkey_y_le_bytes.reverse();
+            let affine = AffinePoint::<Secp256k1Operations, NUM_WORDS>::from(&pubkey_x_le_bytes, &pubkey_y_le_bytes);
+
+            const GENERATOR: AffinePoint<Secp256k1Operations, NUM_WORDS> = AffinePoint::<Secp256k1Operations, NUM_WORDS>::generator_in_affine();
+
+            let challenge = tagged_hash(b"BIP0340/challenge", &[r, pubkey, message]);
+            let e = <Scalar as Reduce<U256>>::reduce_bytes(&challenge.into());
+
+            // R = s * G - e * P must not be the point at infinity.
+            let res = match double_and_add(s.to_le_bits(), &GENERATOR, (-e).to_le_bits(), &affine) {
+                Some(res) => res,
+                None => return false,
+            };
+
+            // R must have an even y coordinate and r as its x coordinate.
+            if res.limbs[NUM_WORDS / 2] & 1 == 1 {
+                return false;
+            }
+            let mut x_bytes_be = [0u8; 32];
+            for i in 0..8 {
+                x_bytes_be[i * 4..(i * 4) + 4].copy_from_slice(&res.limbs[i].to_le_bytes());
+            }
+            x_bytes_be.reverse();
+            x_bytes_be[..] == r[..]
+        } else {
+            let verify_key = k256::schnorr::VerifyingKey::from_bytes(pubkey);
+            let signature = k256::schnorr::Signature::try_from(&signature[..]);
+            match (verify_key, signature) {
+                (Ok(verify_key), Ok(signature)) => {
+                    verify_key.verify_raw(message, &signature).is_ok()
+                }
+                _ => false,
+            }
+        }
+    }
+}
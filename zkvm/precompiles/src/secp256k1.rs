@@ -1,7 +1,12 @@
 #![allow(unused)]
 
-use crate::utils::{AffinePoint, CurveOperations};
-use crate::{syscall_secp256k1_add, syscall_secp256k1_decompress, syscall_secp256k1_double};
+use crate::utils::{
+    bytes_to_words_le, double_and_add, words_to_bytes_le, AffinePoint, CurveOperations,
+};
+use crate::{
+    syscall_secp256k1_add, syscall_secp256k1_decompress, syscall_secp256k1_double,
+    syscall_secp256k1_ecrecover,
+};
 use anyhow::Context;
 use anyhow::{anyhow, Result};
 use core::convert::TryInto;
@@ -155,32 +160,7 @@ fn double_and_add_base(
     b: &Scalar,
     B: &AffinePoint<Secp256k1Operations, NUM_WORDS>,
 ) -> Option<AffinePoint<Secp256k1Operations, NUM_WORDS>> {
-    let mut res: Option<AffinePoint<Secp256k1Operations, NUM_WORDS>> = None;
-    let mut temp_A = *A;
-    let mut temp_B = *B;
-
-    let a_bits = a.to_le_bits();
-    let b_bits = b.to_le_bits();
-    for (a_bit, b_bit) in a_bits.iter().zip(b_bits) {
-        if *a_bit {
-            match res.as_mut() {
-                Some(res) => res.add_assign(&temp_A),
-                None => res = Some(temp_A),
-            };
-        }
-
-        if b_bit {
-            match res.as_mut() {
-                Some(res) => res.add_assign(&temp_B),
-                None => res = Some(temp_B),
-            };
-        }
-
-        temp_A.double();
-        temp_B.double();
-    }
-
-    res
+    double_and_add(a.to_le_bits(), A, b.to_le_bits(), B)
 }
 
 /// Outside of the VM, computes the pubkey and s_inverse value from a signature and a message hash.
@@ -217,18 +197,51 @@ pub fn unconstrained_ecrecover(sig: &[u8; 65], msg_hash: &[u8; 32]) -> ([u8; 33]
 }
 
 /// Given a signature and a message hash, returns the public key that signed the message.
+///
+/// The signature is `r || s || v` with `r` and `s` in big-endian order, where the recovery id `v`
+/// is the parity of the `y` coordinate of the nonce point. Inside the zkVM, the key is recovered by
+/// the `SECP256K1_ECRECOVER` precompile in a single syscall, which panics if `r` is not the `x`
+/// coordinate of a point.
 pub fn ecrecover(sig: &[u8; 65], msg_hash: &[u8; 32]) -> Result<[u8; 65]> {
-    let (pubkey, s_inv) = unconstrained_ecrecover(sig, msg_hash);
-    let pubkey = decompress_pubkey(&pubkey).context("decompress pubkey failed")?;
-    let verified = verify_signature(
-        &pubkey,
-        msg_hash,
-        &Signature::from_slice(&sig[..64]).unwrap(),
-        Some(&s_inv),
-    );
-    if verified {
-        Ok(pubkey)
-    } else {
-        Err(anyhow!("failed to verify signature"))
+    let signature = Signature::from_slice(&sig[..64]).context("invalid signature")?;
+    if sig[64] > 1 {
+        return Err(anyhow!("unsupported recovery id"));
+    }
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            // The message hash, r and s are passed as little-endian words.
+            let mut input = [0u32; 24];
+            for (words, bytes) in input.chunks_exact_mut(8).zip([&msg_hash[..], &sig[..32], &sig[32..64]]) {
+                let mut le_bytes = [0u8; 32];
+                le_bytes.copy_from_slice(bytes);
+                le_bytes.reverse();
+                words.copy_from_slice(&bytes_to_words_le(&le_bytes));
+            }
+            unsafe {
+                syscall_secp256k1_ecrecover(input.as_mut_ptr(), sig[64] == 1);
+            }
+
+            let mut result: [u8; 65] = [0; 65];
+            result[0] = 4;
+            for (bytes, words) in result[1..].chunks_exact_mut(32).zip(input[..16].chunks_exact(8)) {
+                bytes.copy_from_slice(&words_to_bytes_le(words));
+                bytes.reverse();
+            }
+            Ok(result)
+        } else {
+            let mut recovery_id = sig[64];
+            let mut signature = signature;
+            if let Some(signature_normalized) = signature.normalize_s() {
+                signature = signature_normalized;
+                recovery_id ^= 1;
+            }
+            let recid = RecoveryId::from_byte(recovery_id).context("invalid recovery id")?;
+            let recovered_key = VerifyingKey::recover_from_prehash(&msg_hash[..], &signature, recid)
+                .context("failed to recover the public key")?;
+            let bytes = recovered_key.to_encoded_point(false).to_bytes();
+            let mut result: [u8; 65] = [0; 65];
+            result.copy_from_slice(&bytes);
+            Ok(result)
+        }
     }
 }
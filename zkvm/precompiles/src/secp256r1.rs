@@ -0,0 +1,89 @@
+#![allow(unused)]
+
+use crate::utils::{double_and_add, AffinePoint, CurveOperations};
+use crate::{syscall_secp256r1_add, syscall_secp256r1_double};
+use anyhow::Context;
+use p256::ecdsa::signature::hazmat::PrehashVerifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use p256::elliptic_curve::ff::PrimeFieldBits;
+use p256::elliptic_curve::ops::{Invert, Reduce};
+use p256::elliptic_curve::PrimeField;
+use p256::{FieldBytes, NistP256, Scalar, U256};
+
+const NUM_WORDS: usize = 16;
+
+#[derive(Copy, Clone)]
+pub struct Secp256r1Operations;
+
+impl CurveOperations<NUM_WORDS> for Secp256r1Operations {
+    // The values are taken from https://neuromancer.sk/std/secg/secp256r1.
+    const GENERATOR: [u32; NUM_WORDS] = [
+        3633889942, 4104206661, 770388896, 1996717441, 1671708914, 4173129445, 3777774151,
+        1796723186, 935285237, 3417718888, 1798397646, 734933847, 2081398294, 2397563722,
+        4263149467, 1340293858,
+    ];
+
+    fn add_assign(limbs: &mut [u32; NUM_WORDS], other: &[u32; NUM_WORDS]) {
+        unsafe {
+            syscall_secp256r1_add(limbs.as_mut_ptr(), other.as_ptr());
+        }
+    }
+
+    fn double(limbs: &mut [u32; NUM_WORDS]) {
+        unsafe {
+            syscall_secp256r1_double(limbs.as_mut_ptr());
+        }
+    }
+}
+
+/// Verifies a secp256r1 (P-256) ECDSA signature using the uncompressed SEC1 public key and the
+/// message hash, as produced by WebAuthn authenticators and the Apple Secure Enclave.
+///
+/// Warning: this function does not check if the key is actually on the curve.
+pub fn verify_signature(pubkey: &[u8; 65], msg_hash: &[u8; 32], signature: &Signature) -> bool {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            let mut pubkey_x_le_bytes = pubkey[1..33].to_vec();
+            pubkey_x_le_bytes.reverse();
+            let mut pubkey_y_le_bytes = pubkey[33..].to_vec();
+            pubkey_y_le_bytes.reverse();
+
+            // Convert the public key to an affine point
+            let affine = AffinePoint::<Secp256r1Operations, NUM_WORDS>::from(&pubkey_x_le_bytes, &pubkey_y_le_bytes);
+
+            const GENERATOR: AffinePoint<Secp256r1Operations, NUM_WORDS> = AffinePoint::<Secp256r1Operations, NUM_WORDS>::generator_in_affine();
+
+            // The hash is exactly as wide as the field, so it is reduced without truncation.
+            let z = <Scalar as Reduce<U256>>::reduce_bytes(FieldBytes::from_slice(msg_hash));
+            let (r, s) = signature.split_scalars();
+            let s_inv = *s.invert();
+
+            let u1 = z * s_inv;
+            let u2 = *r * s_inv;
+
+            let res = match double_and_add(u1.to_le_bits(), &GENERATOR, u2.to_le_bits(), &affine) {
+                Some(res) => res,
+                None => return false,
+            };
+            let mut x_bytes_be = [0u8; 32];
+            for i in 0..8 {
+                x_bytes_be[i * 4..(i * 4) + 4].copy_from_slice(&res.limbs[i].to_le_bytes());
+            }
+            x_bytes_be.reverse();
+
+            // The x coordinate is reduced modulo the group order before comparing it with r.
+            *r == <Scalar as Reduce<U256>>::reduce_bytes(&x_bytes_be.into())
+        } else {
+            let verify_key = VerifyingKey::from_sec1_bytes(pubkey);
+            if verify_key.is_err() {
+                return false;
+            }
+            let res = verify_key
+                .unwrap()
+                .verify_prehash(msg_hash, signature)
+                .context("invalid signature");
+
+            res.is_ok()
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! An incremental SHA-256 hasher with `init`/`update`/`finalize` steps.
+//!
+//! Inside the zkVM, each block is hashed with the `SHA_EXTEND` and `SHA_COMPRESS` precompiles as
+//! soon as it is complete, so the hasher only keeps the state and one partial block in memory, and
+//! data which is produced incrementally is hashed without buffering the whole message. The hasher
+//! implements [sha2::Digest], so it can be used where a `sha2::Sha256` is expected.
+
+#![allow(unused_imports)]
+use sha2::digest::consts::U32;
+use sha2::digest::generic_array::GenericArray;
+use sha2::digest::{FixedOutput, HashMarker, Output, OutputSizeUser, Reset, Update};
+
+use crate::{syscall_sha256_compress, syscall_sha256_extend};
+
+/// The length of a SHA-256 digest in bytes.
+pub const DIGEST_LEN: usize = 32;
+
+/// The length of a SHA-256 message block in bytes.
+pub const BLOCK_LEN: usize = 64;
+
+/// The number of words of the SHA-256 state.
+const STATE_WORDS: usize = 8;
+
+/// The number of words of the message schedule of a block.
+#[allow(dead_code)]
+const SCHEDULE_WORDS: usize = 64;
+
+/// The initial state of SHA-256.
+const IV: [u32; STATE_WORDS] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// An incremental SHA-256 hasher.
+#[derive(Debug, Clone)]
+pub struct Sha256 {
+    state: [u32; STATE_WORDS],
+    buffer: [u8; BLOCK_LEN],
+    buffer_len: usize,
+    len: u64,
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha256 {
+    /// Creates a hasher for an empty message.
+    pub const fn new() -> Self {
+        Self {
+            state: IV,
+            buffer: [0u8; BLOCK_LEN],
+            buffer_len: 0,
+            len: 0,
+        }
+    }
+
+    /// Absorbs `data` into the hasher.
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.len = self.len.wrapping_add(data.len() as u64);
+        if self.buffer_len > 0 {
+            let take = core::cmp::min(BLOCK_LEN - self.buffer_len, data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+            if self.buffer_len < BLOCK_LEN {
+                return;
+            }
+            let block = self.buffer;
+            compress(&mut self.state, &block);
+            self.buffer_len = 0;
+        }
+        let mut blocks = data.chunks_exact(BLOCK_LEN);
+        for block in &mut blocks {
+            compress(&mut self.state, block.try_into().unwrap());
+        }
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_len = remainder.len();
+    }
+
+    /// Pads the message and returns its digest.
+    pub fn finalize(mut self) -> [u8; DIGEST_LEN] {
+        // The message is followed by a one bit, padded with zeros to 8 bytes short of a block,
+        // and followed by its length in bits as a big-endian integer.
+        let bit_len = self.len.wrapping_mul(8);
+        let pad_len = if self.buffer_len < BLOCK_LEN - 8 {
+            BLOCK_LEN - 8 - self.buffer_len
+        } else {
+            2 * BLOCK_LEN - 8 - self.buffer_len
+        };
+        let mut padding = [0u8; BLOCK_LEN + 8];
+        padding[0] = 0x80;
+        padding[pad_len..pad_len + 8].copy_from_slice(&bit_len.to_be_bytes());
+        self.update(&padding[..pad_len + 8]);
+
+        let mut digest = [0u8; DIGEST_LEN];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state.iter()) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+}
+
+impl HashMarker for Sha256 {}
+
+impl OutputSizeUser for Sha256 {
+    type OutputSize = U32;
+}
+
+impl Update for Sha256 {
+    fn update(&mut self, data: &[u8]) {
+        Sha256::update(self, data);
+    }
+}
+
+impl FixedOutput for Sha256 {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        out.copy_from_slice(&Sha256::finalize(self));
+    }
+}
+
+impl Reset for Sha256 {
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+/// Computes the SHA-256 digest of `data`.
+pub fn sha256(data: &[u8]) -> [u8; DIGEST_LEN] {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+/// Applies the SHA-256 compression function to the state and a message block.
+fn compress(state: &mut [u32; STATE_WORDS], block: &[u8; BLOCK_LEN]) {
+    cfg_if::cfg_if! {
+        if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+            // The precompiles extend the first 16 words of the schedule to all of its words, and
+            // compress the schedule into the state.
+            let mut w = [0u32; SCHEDULE_WORDS];
+            for (word, bytes) in w.iter_mut().zip(block.chunks_exact(4)) {
+                *word = u32::from_be_bytes(bytes.try_into().unwrap());
+            }
+            unsafe {
+                syscall_sha256_extend(w.as_mut_ptr());
+                syscall_sha256_compress(w.as_mut_ptr(), state.as_mut_ptr());
+            }
+        } else {
+            sha2::compress256(state, &[GenericArray::clone_from_slice(block)]);
+        }
+    }
+}
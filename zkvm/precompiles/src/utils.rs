@@ -92,6 +92,41 @@ impl<C: CurveOperations<NUM_WORDS> + Copy, const NUM_WORDS: usize> AffinePoint<C
     }
 }
 
+/// Computes `a * A + b * B` with a double-and-add over the little-endian bits of the scalars.
+/// Returns `None` if the result is the point at infinity.
+#[allow(non_snake_case)]
+pub fn double_and_add<C: CurveOperations<NUM_WORDS> + Copy, const NUM_WORDS: usize>(
+    a_bits: impl IntoIterator<Item = bool>,
+    A: &AffinePoint<C, NUM_WORDS>,
+    b_bits: impl IntoIterator<Item = bool>,
+    B: &AffinePoint<C, NUM_WORDS>,
+) -> Option<AffinePoint<C, NUM_WORDS>> {
+    let mut res: Option<AffinePoint<C, NUM_WORDS>> = None;
+    let mut temp_A = *A;
+    let mut temp_B = *B;
+
+    for (a_bit, b_bit) in a_bits.into_iter().zip(b_bits) {
+        if a_bit {
+            match res.as_mut() {
+                Some(res) => res.add_assign(&temp_A),
+                None => res = Some(temp_A),
+            };
+        }
+
+        if b_bit {
+            match res.as_mut() {
+                Some(res) => res.add_assign(&temp_B),
+                None => res = Some(temp_B),
+            };
+        }
+
+        temp_A.double();
+        temp_B.double();
+    }
+
+    res
+}
+
 /// Converts a slice of words to a byte array in little endian.
 pub fn words_to_bytes_le(words: &[u32]) -> Vec<u8> {
     words
@@ -0,0 +1,156 @@
+//! Modular arithmetic on 384-bit and 512-bit unsigned integers, accelerated by the
+//! `UINT384_MULMOD` and `UINT512_MULMOD` precompiles inside the zkVM.
+//!
+//! Values are little-endian words. The 384-bit operations fit the BLS12-381 base field, and the
+//! 512-bit reduction maps 64-byte hash outputs into a field, as in hash-to-curve.
+
+#![allow(unused_imports)]
+use num::{BigUint, One, Zero};
+
+use crate::utils::{bytes_to_words_le, words_to_bytes_le};
+use crate::{syscall_uint384_mulmod, syscall_uint512_mulmod};
+
+macro_rules! wide_uint {
+    ($bits:literal, $num_words:ident, $n:literal, $mulmod:ident, $addmod:ident, $reduce:ident, $syscall:ident) => {
+        #[doc = concat!("The number of words of a ", $bits, "-bit unsigned integer.")]
+        pub const $num_words: usize = $n;
+
+        #[doc = concat!("Computes `(x * y) % modulus` for ", $bits, "-bit unsigned integers.")]
+        /// The modulus must be nonzero.
+        pub fn $mulmod(x: &[u32; $n], y: &[u32; $n], modulus: &[u32; $n]) -> [u32; $n] {
+            cfg_if::cfg_if! {
+                if #[cfg(all(target_os = "zkvm", target_vendor = "succinct"))] {
+                    mulmod_with_syscall(x, y, modulus, $syscall)
+                } else {
+                    let m = nonzero_modulus(modulus);
+                    from_biguint((to_biguint(x) * to_biguint(y)) % m)
+                }
+            }
+        }
+
+        #[doc = concat!("Computes `(x + y) % modulus` for ", $bits, "-bit unsigned integers.")]
+        /// The modulus must be nonzero.
+        pub fn $addmod(x: &[u32; $n], y: &[u32; $n], modulus: &[u32; $n]) -> [u32; $n] {
+            let x = if less_than(x, modulus) {
+                *x
+            } else {
+                $reduce(x, modulus)
+            };
+            let y = if less_than(y, modulus) {
+                *y
+            } else {
+                $reduce(y, modulus)
+            };
+            add_reduced(&x, &y, modulus)
+        }
+
+        #[doc = concat!("Computes `x % modulus` for ", $bits, "-bit unsigned integers.")]
+        /// The modulus must be nonzero.
+        pub fn $reduce(x: &[u32; $n], modulus: &[u32; $n]) -> [u32; $n] {
+            let mut one = [0u32; $n];
+            one[0] = 1;
+            $mulmod(x, &one, modulus)
+        }
+    };
+}
+
+wide_uint!(
+    "384",
+    UINT384_NUM_WORDS,
+    12,
+    uint384_mulmod,
+    uint384_addmod,
+    uint384_mod,
+    syscall_uint384_mulmod
+);
+
+wide_uint!(
+    "512",
+    UINT512_NUM_WORDS,
+    16,
+    uint512_mulmod,
+    uint512_addmod,
+    uint512_mod,
+    syscall_uint512_mulmod
+);
+
+/// Multiplies with a mulmod syscall, first reducing `x` if neither operand is reduced, as the
+/// precompile requires.
+#[allow(dead_code)]
+fn mulmod_with_syscall<const N: usize>(
+    x: &[u32; N],
+    y: &[u32; N],
+    modulus: &[u32; N],
+    syscall: unsafe extern "C" fn(*mut u32, *const u32),
+) -> [u32; N] {
+    assert!(
+        modulus.iter().any(|&w| w != 0),
+        "the modulus must be nonzero"
+    );
+    let mut one = [0u32; N];
+    one[0] = 1;
+    if !less_than(&one, modulus) {
+        // Everything is zero modulo one.
+        return [0u32; N];
+    }
+
+    let mut result = *x;
+    if !less_than(x, modulus) && !less_than(y, modulus) {
+        result = mulmod_with_syscall(x, &one, modulus, syscall);
+    }
+
+    // The syscall reads the multiplier followed by the modulus from its second argument.
+    let mut y_and_modulus = Vec::with_capacity(2 * N);
+    y_and_modulus.extend_from_slice(y);
+    y_and_modulus.extend_from_slice(modulus);
+    unsafe {
+        syscall(result.as_mut_ptr(), y_and_modulus.as_ptr());
+    }
+    result
+}
+
+/// Adds two values that are reduced modulo `modulus`.
+fn add_reduced<const N: usize>(x: &[u32; N], y: &[u32; N], modulus: &[u32; N]) -> [u32; N] {
+    let mut sum = [0u32; N];
+    let mut carry = false;
+    for i in 0..N {
+        let (s, c1) = x[i].overflowing_add(y[i]);
+        let (s, c2) = s.overflowing_add(carry as u32);
+        sum[i] = s;
+        carry = c1 || c2;
+    }
+    if carry || !less_than(&sum, modulus) {
+        let mut borrow = false;
+        for i in 0..N {
+            let (d, b1) = sum[i].overflowing_sub(modulus[i]);
+            let (d, b2) = d.overflowing_sub(borrow as u32);
+            sum[i] = d;
+            borrow = b1 || b2;
+        }
+    }
+    sum
+}
+
+/// Whether `x < y`, comparing the most significant words first.
+fn less_than<const N: usize>(x: &[u32; N], y: &[u32; N]) -> bool {
+    x.iter().rev().lt(y.iter().rev())
+}
+
+#[allow(dead_code)]
+fn nonzero_modulus<const N: usize>(modulus: &[u32; N]) -> BigUint {
+    let m = to_biguint(modulus);
+    assert!(!m.is_zero(), "the modulus must be nonzero");
+    m
+}
+
+#[allow(dead_code)]
+fn to_biguint<const N: usize>(x: &[u32; N]) -> BigUint {
+    BigUint::from_bytes_le(&words_to_bytes_le(x))
+}
+
+#[allow(dead_code)]
+fn from_biguint<const N: usize>(x: BigUint) -> [u32; N] {
+    let mut bytes = x.to_bytes_le();
+    bytes.resize(4 * N, 0u8);
+    bytes_to_words_le(&bytes).try_into().unwrap()
+}